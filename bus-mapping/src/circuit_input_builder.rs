@@ -7,6 +7,8 @@ mod call;
 mod execution;
 mod input_state_ref;
 #[cfg(test)]
+mod prestate_tests;
+#[cfg(test)]
 mod tracer_tests;
 mod transaction;
 mod withdrawal;
@@ -15,26 +17,32 @@ use self::access::gen_state_access_trace;
 use crate::{
     error::Error,
     evm::opcodes::{gen_associated_ops, gen_associated_steps},
-    operation::{CallContextField, Operation, RWCounter, StartOp, RW},
+    operation::{AccountField, CallContextField, Operation, RWCounter, StartOp, RW},
+    precompile::PrecompileEcParams,
     rpc::GethClient,
     state_db::{self, CodeDB, StateDB},
+    witness_bundle::{WitnessBundle, WITNESS_BUNDLE_VERSION},
 };
 pub use access::{Access, AccessSet, AccessValue, CodeSource};
-pub use block::{Block, BlockContext};
+pub use block::{Block, BlockContext, BlockHead};
 pub use call::{Call, CallContext, CallKind};
 use core::fmt::Debug;
 use eth_types::{
     self, geth_types,
     sign_types::{pk_bytes_le, pk_bytes_swap_endianness, SignData},
-    Address, GethExecStep, GethExecTrace, ToWord, Word,
+    Address, GethExecStep, GethExecTrace, GethPrestateAccount, ToWord, Word,
 };
 use ethers_providers::JsonRpcClient;
 pub use execution::{
-    CopyDataType, CopyEvent, CopyStep, ExecState, ExecStep, ExpEvent, ExpStep, NumberOrHash,
+    Blake2fEvent, CopyDataType, CopyEvent, CopyStep, EcAddOp, EcMulOp, EcPairingOp, ExecState,
+    ExecStep, ExpEvent, ExpStep, ModExpEvent, NumberOrHash, PointEvaluationEvent, PrecompileEvent,
+    PrecompileEvents, Ripemd160Event, Sha256Event,
 };
+use external_tracer::{LoggerConfig, TraceConfig};
 pub use input_state_ref::CircuitInputStateRef;
 use itertools::Itertools;
 use log::warn;
+use serde::{Deserialize, Serialize};
 use std::{
     collections::{HashMap, HashSet},
     ops::Deref,
@@ -43,12 +51,15 @@ pub use transaction::{Transaction, TransactionContext};
 pub use withdrawal::{Withdrawal, WithdrawalContext};
 
 /// Circuit Setup Parameters
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FixedCParams {
     /// Maximum number of rw operations in the state circuit (RwTable length /
     /// nummber of rows). This must be at least the number of rw operations
     /// + 1, in order to allocate at least a Start row.
     pub max_rws: usize,
+    /// Maximum number of consecutive blocks in a chunk
+    #[serde(default = "default_max_blocks")]
+    pub max_blocks: usize,
     // TODO: evm_rows: Maximum number of rows in the EVM Circuit
     /// Maximum number of txs in the Tx Circuit
     pub max_txs: usize,
@@ -75,6 +86,38 @@ pub struct FixedCParams {
     /// calculated, so the same circuit will not be able to prove different
     /// witnesses.
     pub max_keccak_rows: usize,
+    /// Maximum number of ECRECOVER precompile calls verified by the SignVerify
+    /// chip, on top of the `max_txs` transaction signatures.
+    pub max_ecrecover: usize,
+    /// Pad the SHA256 circuit with this number of rows to a static capacity.
+    /// When 0, the SHA256 circuit number of rows will be dynamically
+    /// calculated, so the same circuit will not be able to prove different
+    /// witnesses.
+    pub max_sha256_rows: usize,
+    /// Pad the RIPEMD160 circuit with this number of rows to a static
+    /// capacity.  When 0, the RIPEMD160 circuit number of rows will be
+    /// dynamically calculated.
+    pub max_ripemd160_rows: usize,
+    /// Maximum number of MODEXP precompile calls verified by the ModExp
+    /// circuit.
+    pub max_modexp: usize,
+    /// Maximum number of ECADD, ECMUL and ECPAIRING precompile calls verified
+    /// by the ECC circuit.
+    pub max_ec_ops: PrecompileEcParams,
+    /// Pad the BLAKE2F circuit with this number of rows to a static capacity.
+    /// When 0, the BLAKE2F circuit number of rows will be dynamically
+    /// calculated.
+    pub max_blake2f_rows: usize,
+    /// Maximum number of KZG point evaluation precompile calls verified by the
+    /// KZG circuit.
+    pub max_point_evaluations: usize,
+    /// Maximum number of bytes of the RLP encoded logs of all the receipts in
+    /// the Receipt circuit
+    pub max_log_bytes: usize,
+    /// Pad the MPT circuit with this number of rows to a static capacity.
+    /// When 0, the MPT circuit number of rows will be dynamically calculated,
+    /// so the same circuit will not be able to prove different witnesses.
+    pub max_mpt_rows: usize,
 }
 
 /// Unset Circuits Parameters
@@ -101,11 +144,17 @@ impl CircuitsParams for DynamicCParams {
     }
 }
 
+/// Parameters serialized before the chunks of blocks have a single block.
+fn default_max_blocks() -> usize {
+    1
+}
+
 impl Default for FixedCParams {
     /// Default values for most of the unit tests of the Circuit Parameters
     fn default() -> Self {
         FixedCParams {
             max_rws: 1000,
+            max_blocks: 1,
             max_txs: 1,
             max_withdrawals: 1,
             max_calldata: 256,
@@ -116,6 +165,15 @@ impl Default for FixedCParams {
             max_bytecode: 512,
             max_evm_rows: 0,
             max_keccak_rows: 0,
+            max_ecrecover: 0,
+            max_sha256_rows: 0,
+            max_ripemd160_rows: 0,
+            max_modexp: 0,
+            max_ec_ops: PrecompileEcParams::default(),
+            max_blake2f_rows: 0,
+            max_point_evaluations: 0,
+            max_log_bytes: 512,
+            max_mpt_rows: 0,
         }
     }
 }
@@ -193,19 +251,13 @@ impl<'a, C: CircuitsParams> CircuitInputBuilder<C> {
     ) -> Result<Transaction, Error> {
         let call_id = self.block_ctx.rwc.0;
 
-        self.block_ctx.call_map.insert(
-            call_id,
-            (
-                eth_tx
-                    .transaction_index
-                    .ok_or(Error::EthTypeError(eth_types::Error::IncompleteBlock))?
-                    .as_u64() as usize,
-                0,
-            ),
-        );
+        self.block_ctx
+            .call_map
+            .insert(call_id, (self.block.txs.len(), 0));
 
         Transaction::new(
             id,
+            self.block.number.low_u64(),
             call_id,
             &self.sdb,
             &mut self.code_db,
@@ -248,7 +300,8 @@ impl<'a, C: CircuitsParams> CircuitInputBuilder<C> {
         tx_index: u64,
     ) -> Result<(), Error> {
         let mut tx = self.new_tx(tx_index, eth_tx, !geth_trace.failed)?;
-        let mut tx_ctx = TransactionContext::new(eth_tx, geth_trace, is_last_tx)?;
+        let mut tx_ctx =
+            TransactionContext::new(tx_index as usize, eth_tx, geth_trace, is_last_tx)?;
 
         if !geth_trace.invalid {
             // Generate BeginTx step
@@ -287,6 +340,78 @@ impl<'a, C: CircuitsParams> CircuitInputBuilder<C> {
 
         Ok(())
     }
+
+    /// Handle the withdrawals of the current block, which are processed after
+    /// its last transaction.  Each withdrawal generates a Withdrawal step that
+    /// credits the withdrawn amount (in Wei) to the balance of its address,
+    /// creating the account if it doesn't exist.  The 1-based index of the
+    /// withdrawal in the chunk is kept in the program counter of its step.
+    fn handle_withdrawals(&mut self) -> Result<(), Error> {
+        let mut dummy_tx = Transaction::default();
+        let mut dummy_tx_ctx = TransactionContext::default();
+        let mut state = self.state_ref(&mut dummy_tx, &mut dummy_tx_ctx);
+
+        for withdrawal in state.block.head().withdrawals().iter() {
+            let mut exec_step = ExecStep {
+                exec_state: ExecState::Withdrawal,
+                pc: state.block.block_steps.withdrawals.len() as u64 + 1,
+                rwc: state.block_ctx.rwc,
+                ..ExecStep::default()
+            };
+            let (_, account) = state.sdb.get_account(&withdrawal.address);
+            let account_exists = !account.is_empty();
+            let account_code_hash = if account_exists {
+                account.code_hash.to_word()
+            } else {
+                Word::zero()
+            };
+            state.account_read(
+                &mut exec_step,
+                withdrawal.address,
+                AccountField::CodeHash,
+                account_code_hash,
+            )?;
+            state.transfer_to(
+                &mut exec_step,
+                withdrawal.address,
+                account_exists,
+                false,
+                withdrawal.amount_in_wei(),
+                false,
+            )?;
+            state.block.block_steps.withdrawals.push(exec_step);
+        }
+
+        Ok(())
+    }
+
+    /// Generate the EndBlock step that ends the current block when it's
+    /// followed by another block of the chunk.  Like the last EndBlock, it
+    /// holds the number of processed withdrawals in its program counter and
+    /// reads the id of the last processed tx.
+    fn handle_end_block(&mut self) -> Result<(), Error> {
+        let mut dummy_tx = Transaction::default();
+        let mut dummy_tx_ctx = TransactionContext::default();
+        let mut state = self.state_ref(&mut dummy_tx, &mut dummy_tx_ctx);
+
+        let mut end_block = ExecStep {
+            exec_state: ExecState::EndBlock,
+            pc: state.block.block_steps.withdrawals.len() as u64,
+            rwc: state.block_ctx.rwc,
+            ..ExecStep::default()
+        };
+        if let Some(call_id) = state.block.txs.last().map(|tx| tx.calls[0].call_id) {
+            state.call_context_read(
+                &mut end_block,
+                call_id,
+                CallContextField::TxId,
+                Word::from(state.block.txs.len() as u64),
+            )?;
+        }
+        state.block.block_steps.end_blocks.push(end_block);
+
+        Ok(())
+    }
 }
 
 impl CircuitInputBuilder<FixedCParams> {
@@ -303,11 +428,27 @@ impl CircuitInputBuilder<FixedCParams> {
         Ok(self)
     }
 
+    /// Handle a chunk of consecutive blocks, the first of which is the block
+    /// of the builder, by handling each of their transactions and withdrawals
+    /// to generate all the associated operations in a single RW table.
+    pub fn handle_blocks(
+        &mut self,
+        blocks: &[(&EthBlock, &[eth_types::GethExecTrace])],
+    ) -> Result<&CircuitInputBuilder<FixedCParams>, Error> {
+        self.begin_handle_blocks(blocks)?;
+        self.set_end_block(self.circuits_params.max_rws)?;
+        Ok(self)
+    }
+
     fn set_end_block(&mut self, max_rws: usize) -> Result<(), Error> {
         let mut end_block_not_last = self.block.block_steps.end_block_not_last.clone();
         let mut end_block_last = self.block.block_steps.end_block_last.clone();
         end_block_not_last.rwc = self.block_ctx.rwc;
         end_block_last.rwc = self.block_ctx.rwc;
+        // The program counter of EndBlock holds the number of processed withdrawals
+        let total_withdrawals = self.block.block_steps.withdrawals.len() as u64;
+        end_block_not_last.pc = total_withdrawals;
+        end_block_last.pc = total_withdrawals;
 
         let mut dummy_tx = Transaction::default();
         let mut dummy_tx_ctx = TransactionContext::default();
@@ -367,22 +508,57 @@ impl<C: CircuitsParams> CircuitInputBuilder<C> {
         &mut self,
         eth_block: &EthBlock,
         geth_traces: &[eth_types::GethExecTrace],
+    ) -> Result<(), Error> {
+        self.handle_block_txs(eth_block, geth_traces)?;
+        self.handle_withdrawals()?;
+        // set eth_block
+        self.block.eth_block = eth_block.clone();
+        if let Some(head) = self.block.headers.last_mut() {
+            head.eth_block = eth_block.clone();
+        }
+        self.set_value_ops_call_context_rwc_eor();
+        Ok(())
+    }
+
+    /// First part of handle_blocks, common for dynamic and static circuit
+    /// parameters.  Each block but the last one is followed by an EndBlock
+    /// step, and the gas used is accumulated per block.
+    pub fn begin_handle_blocks(
+        &mut self,
+        blocks: &[(&EthBlock, &[eth_types::GethExecTrace])],
+    ) -> Result<(), Error> {
+        for (index, (eth_block, geth_traces)) in blocks.iter().enumerate() {
+            if index > 0 {
+                self.handle_end_block()?;
+                let head = self.block.head().next(eth_block)?;
+                self.block.begin_block(head);
+                self.block_ctx.cumulative_gas_used = 0;
+            }
+            self.handle_block_txs(eth_block, geth_traces)?;
+            self.handle_withdrawals()?;
+        }
+        self.set_value_ops_call_context_rwc_eor();
+        Ok(())
+    }
+
+    fn handle_block_txs(
+        &mut self,
+        eth_block: &EthBlock,
+        geth_traces: &[eth_types::GethExecTrace],
     ) -> Result<(), Error> {
         // accumulates gas across all txs in the block
         for (idx, tx) in eth_block.transactions.iter().enumerate() {
             let geth_trace = &geth_traces[idx];
-            // Transaction index starts from 1
-            let tx_id = idx + 1;
+            // Transaction index starts from 1, and follows the txs of the
+            // previous blocks of the chunk
+            let tx_id = self.block.txs.len() + 1;
             self.handle_tx(
                 tx,
                 geth_trace,
-                tx_id == eth_block.transactions.len(),
+                idx + 1 == eth_block.transactions.len(),
                 tx_id as u64,
             )?;
         }
-        // set eth_block
-        self.block.eth_block = eth_block.clone();
-        self.set_value_ops_call_context_rwc_eor();
         Ok(())
     }
 }
@@ -396,17 +572,36 @@ impl CircuitInputBuilder<DynamicCParams> {
         geth_traces: &[eth_types::GethExecTrace],
     ) -> Result<CircuitInputBuilder<FixedCParams>, Error> {
         self.begin_handle_block(eth_block, geth_traces)?;
+        self.into_fixed()
+    }
+
+    /// Handle a chunk of consecutive blocks, the first of which is the block
+    /// of the builder, by handling each of their transactions and withdrawals
+    /// to generate all the associated operations in a single RW table.  From
+    /// these operations, the optimal circuit parameters are derived and set.
+    pub fn handle_blocks(
+        mut self,
+        blocks: &[(&EthBlock, &[eth_types::GethExecTrace])],
+    ) -> Result<CircuitInputBuilder<FixedCParams>, Error> {
+        self.begin_handle_blocks(blocks)?;
+        self.into_fixed()
+    }
 
+    /// Derive the circuit parameters from the handled blocks and end the
+    /// chunk.
+    fn into_fixed(self) -> Result<CircuitInputBuilder<FixedCParams>, Error> {
         // Compute subcircuits parameters
         let c_params = {
-            let max_txs = eth_block.transactions.len();
-            let max_withdrawals = eth_block.withdrawals.as_ref().unwrap().len();
+            let max_blocks = self.block.headers.len();
+            let max_txs = self.block.txs.len();
+            let max_withdrawals = self.block.block_steps.withdrawals.len();
             let max_bytecode = self.code_db.num_rows_required_for_bytecode_table();
 
-            let max_calldata = eth_block
-                .transactions
+            let max_calldata = self
+                .block
+                .txs
                 .iter()
-                .fold(0, |acc, tx| acc + tx.input.len());
+                .fold(0, |acc, tx| acc + tx.call_data.len());
             let max_exp_steps = self
                 .block
                 .exp_events
@@ -428,7 +623,7 @@ impl CircuitInputBuilder<DynamicCParams> {
                 <RWCounter as Into<usize>>::into(self.block_ctx.rwc) - 1; // -1 since rwc start from index `1`
             let max_rws_after_padding = total_rws_before_padding
                 + 1 // fill 1 to have exactly one StartOp padding in below `set_end_block`
-                + if total_rws_before_padding > 0 { 1 /*end_block -> CallContextFieldTag::TxId lookup*/ } else { 0 };
+                + if self.block.txs.is_empty() { 0 } else { 1 /*end_block -> CallContextFieldTag::TxId lookup*/ };
             // Computing the number of rows for the EVM circuit requires the size of ExecStep,
             // which is determined in the code of zkevm-circuits and cannot be imported here.
             // When the evm circuit receives a 0 value it dynamically computes the minimum
@@ -439,8 +634,35 @@ impl CircuitInputBuilder<DynamicCParams> {
             // With a 0 value the keccak circuit computes dynamically the minimum number of rows
             // needed.
             let max_keccak_rows = 0;
+            let max_ecrecover = self.block.precompile_events.get_ecrecover_events().len();
+            // Same as the keccak circuit, the SHA256, RIPEMD160 and BLAKE2F circuits
+            // compute their number of rows.
+            let max_sha256_rows = 0;
+            let max_ripemd160_rows = 0;
+            let max_blake2f_rows = 0;
+            // The MPT circuit witness is generated outside of the builder from the
+            // state trie, so its number of rows is computed by the MPT circuit.
+            let max_mpt_rows = 0;
+            let max_point_evaluations = self
+                .block
+                .precompile_events
+                .get_point_evaluation_events()
+                .len();
+            let max_modexp = self.block.precompile_events.get_modexp_events().len();
+            let max_log_bytes = self
+                .block
+                .receipts()
+                .iter()
+                .flat_map(|receipt| receipt.logs.iter())
+                .fold(0, |acc, log| acc + log.rlp().len());
+            let max_ec_ops = PrecompileEcParams {
+                ec_add: self.block.precompile_events.get_ec_add_events().len(),
+                ec_mul: self.block.precompile_events.get_ec_mul_events().len(),
+                ec_pairing: self.block.precompile_events.get_ec_pairing_events().len(),
+            };
             FixedCParams {
                 max_rws: max_rws_after_padding,
+                max_blocks,
                 max_txs,
                 max_withdrawals,
                 max_calldata,
@@ -449,6 +671,15 @@ impl CircuitInputBuilder<DynamicCParams> {
                 max_bytecode,
                 max_evm_rows,
                 max_keccak_rows,
+                max_ecrecover,
+                max_sha256_rows,
+                max_ripemd160_rows,
+                max_modexp,
+                max_ec_ops,
+                max_blake2f_rows,
+                max_point_evaluations,
+                max_log_bytes,
+                max_mpt_rows,
             }
         };
         let mut cib = CircuitInputBuilder::<FixedCParams> {
@@ -481,6 +712,11 @@ pub fn keccak_inputs(block: &Block, code_db: &CodeDB) -> Result<Vec<Vec<u8>>, Er
     for input in &block.sha3_inputs {
         keccak_inputs.insert(input.clone());
     }
+    // Ecrecover precompile calls verified by the SignVerify Chip
+    let ecrecover_events = block.precompile_events.get_ecrecover_events();
+    for input in keccak_inputs_sign_verify(&ecrecover_events) {
+        keccak_inputs.insert(input);
+    }
     // MPT Circuit
     // TODO https://github.com/privacy-scaling-explorations/zkevm-circuits/issues/696
     Ok(keccak_inputs.into_iter().collect_vec())
@@ -490,7 +726,8 @@ pub fn keccak_inputs(block: &Block, code_db: &CodeDB) -> Result<Vec<Vec<u8>>, Er
 /// signature datas.
 pub fn keccak_inputs_sign_verify(sigs: &[SignData]) -> Vec<Vec<u8>> {
     let mut inputs = Vec::new();
-    for sig in sigs {
+    // The signatures that recover no public key don't hash it.
+    for sig in sigs.iter().filter(|sig| sig.is_recovered()) {
         let pk_le = pk_bytes_le(&sig.pk);
         let pk_be = pk_bytes_swap_endianness(&pk_le);
         inputs.push(pk_be.to_vec());
@@ -508,7 +745,7 @@ pub fn keccak_inputs_tx_circuit(
     chain_id: u64,
 ) -> Result<Vec<Vec<u8>>, Error> {
     let mut inputs = Vec::new();
-    let sign_datas: Vec<SignData> = txs
+    let signed_txs = txs
         .iter()
         .enumerate()
         .filter(|(i, tx)| {
@@ -519,11 +756,19 @@ pub fn keccak_inputs_tx_circuit(
                 true
             }
         })
-        .map(|(_, tx)| tx.sign_data(chain_id))
+        .map(|(_, tx)| tx)
+        .collect_vec();
+    let sign_datas: Vec<SignData> = signed_txs
+        .iter()
+        .map(|tx| tx.sign_data(chain_id))
         .try_collect()?;
     // Keccak inputs from SignVerify Chip
     let sign_verify_inputs = keccak_inputs_sign_verify(&sign_datas);
     inputs.extend_from_slice(&sign_verify_inputs);
+    // Keccak inputs of the Tx Sign Hash
+    for tx in signed_txs {
+        inputs.push(tx.rlp_unsigned(chain_id)?.to_vec());
+    }
     // NOTE: We don't verify the Tx Hash in the circuit yet, so we don't have more
     // hash inputs.
     Ok(inputs)
@@ -588,6 +833,16 @@ pub fn get_state_accesses(
         let tx_access_trace = gen_state_access_trace(eth_block, tx, geth_trace)?;
         block_access_trace.extend(tx_access_trace);
     }
+    // Withdrawals credit their addresses after the last transaction
+    for withdrawal in eth_block.withdrawals.iter().flatten() {
+        block_access_trace.push(Access::new(
+            None,
+            RW::WRITE,
+            AccessValue::Account {
+                address: withdrawal.address,
+            },
+        ));
+    }
 
     Ok(AccessSet::from(block_access_trace))
 }
@@ -621,6 +876,84 @@ pub fn build_state_code_db(
     (sdb, code_db)
 }
 
+/// Merge the prestates of the transactions of a block into the state before
+/// the block.  The first transaction accessing an account or a storage slot
+/// sees its value before the block.
+pub fn merge_prestates(
+    prestates: Vec<HashMap<Address, GethPrestateAccount>>,
+) -> Vec<geth_types::Account> {
+    let mut accounts: HashMap<Address, geth_types::Account> = HashMap::new();
+    for prestate in prestates {
+        for (address, prestate_account) in prestate {
+            let account = accounts
+                .entry(address)
+                .or_insert_with(|| geth_types::Account {
+                    address,
+                    nonce: prestate_account.nonce.into(),
+                    balance: prestate_account.balance,
+                    code: prestate_account.code,
+                    storage: HashMap::new(),
+                });
+            for (key, value) in prestate_account.storage {
+                account.storage.entry(key).or_insert(value);
+            }
+        }
+    }
+    accounts.into_values().collect()
+}
+
+/// Re-execute the transactions of a block from its prestate to regenerate
+/// their TxExecTraces
+pub fn gen_geth_traces_from_prestate(
+    chain_id: Word,
+    eth_block: &EthBlock,
+    accounts: &[geth_types::Account],
+    history_hashes: Vec<Word>,
+) -> Result<Vec<GethExecTrace>, Error> {
+    let trace_config = TraceConfig {
+        chain_id,
+        history_hashes,
+        block_constants: geth_types::BlockConstants::try_from(eth_block)?,
+        accounts: accounts
+            .iter()
+            .map(|account| (account.address, account.clone()))
+            .collect(),
+        transactions: eth_block
+            .transactions
+            .iter()
+            .map(geth_types::Transaction::from)
+            .collect(),
+        // Withdrawals are processed after the transactions, so they don't
+        // change the traces.
+        withdrawals: vec![],
+        logger_config: LoggerConfig::default(),
+    };
+    Ok(external_tracer::trace(&trace_config)?)
+}
+
+/// Build a partial StateDB from the prestate of a block
+pub fn build_state_code_db_from_prestate(
+    eth_block: &EthBlock,
+    geth_traces: &[GethExecTrace],
+    accounts: Vec<geth_types::Account>,
+) -> Result<(StateDB, CodeDB), Error> {
+    let mut sdb = StateDB::new();
+    let mut code_db = CodeDB::default();
+
+    // Initialize all accesses accounts to zero, as the prestate doesn't include
+    // the withdrawal addresses.
+    let access_set = get_state_accesses(eth_block, geth_traces)?;
+    for addr in access_set.state.keys() {
+        sdb.set_account(addr, state_db::Account::zero());
+    }
+
+    for account in accounts {
+        code_db.insert(account.code.to_vec());
+        sdb.set_account(&account.address, state_db::Account::from(account));
+    }
+    Ok((sdb, code_db))
+}
+
 impl<P: JsonRpcClient> BuilderClient<P> {
     /// Create a new BuilderClient
     pub async fn new(client: GethClient<P>, circuits_params: FixedCParams) -> Result<Self, Error> {
@@ -641,7 +974,60 @@ impl<P: JsonRpcClient> BuilderClient<P> {
     ) -> Result<(EthBlock, Vec<eth_types::GethExecTrace>, Vec<Word>, Word), Error> {
         let eth_block = self.cli.get_block_by_number(block_num.into()).await?;
         let geth_traces = self.cli.trace_block_by_number(block_num.into()).await?;
+        let (history_hashes, prev_state_root) = self.get_history(&eth_block, block_num).await?;
 
+        Ok((eth_block, geth_traces, history_hashes, prev_state_root))
+    }
+
+    /// Step 1 from compact data. Query geth for Block, Txs, the prestate of the
+    /// accounts accessed by the block, history block hashes and previous state
+    /// root.  The TxExecTraces are then regenerated by re-executing the Txs
+    /// locally, instead of fetching the struct logs which are huge for real
+    /// blocks.
+    pub async fn get_block_from_prestate(
+        &self,
+        block_num: u64,
+    ) -> Result<
+        (
+            EthBlock,
+            Vec<eth_types::GethExecTrace>,
+            Vec<geth_types::Account>,
+            Vec<Word>,
+            Word,
+        ),
+        Error,
+    > {
+        let eth_block = self.cli.get_block_by_number(block_num.into()).await?;
+        let prestates = self
+            .cli
+            .trace_block_prestate_by_number(block_num.into())
+            .await?;
+        let (history_hashes, prev_state_root) = self.get_history(&eth_block, block_num).await?;
+
+        let accounts = merge_prestates(prestates);
+        let geth_traces = gen_geth_traces_from_prestate(
+            self.chain_id,
+            &eth_block,
+            &accounts,
+            history_hashes.clone(),
+        )?;
+
+        Ok((
+            eth_block,
+            geth_traces,
+            accounts,
+            history_hashes,
+            prev_state_root,
+        ))
+    }
+
+    // Query geth for up to 256 history block hashes and the previous state
+    // root.
+    async fn get_history(
+        &self,
+        eth_block: &EthBlock,
+        block_num: u64,
+    ) -> Result<(Vec<Word>, Word), Error> {
         // fetch up to 256 blocks
         let mut n_blocks = std::cmp::min(256, block_num as usize);
         let mut next_hash = eth_block.parent_hash;
@@ -669,12 +1055,7 @@ impl<P: JsonRpcClient> BuilderClient<P> {
             next_hash = header.parent_hash;
         }
 
-        Ok((
-            eth_block,
-            geth_traces,
-            history_hashes,
-            prev_state_root.unwrap_or_default(),
-        ))
+        Ok((history_hashes, prev_state_root.unwrap_or_default()))
     }
 
     /// Step 2. Get State Accesses from TxExecTraces
@@ -740,7 +1121,13 @@ impl<P: JsonRpcClient> BuilderClient<P> {
         history_hashes: Vec<Word>,
         prev_state_root: Word,
     ) -> Result<CircuitInputBuilder<FixedCParams>, Error> {
-        let block = Block::new(self.chain_id, history_hashes, prev_state_root, eth_block)?;
+        let block = Block::new(
+            self.chain_id,
+            geth_types::block_hardfork(eth_block),
+            history_hashes,
+            prev_state_root,
+            eth_block,
+        )?;
         let mut builder = CircuitInputBuilder::new(sdb, code_db, block, self.circuits_params);
         builder.handle_block(eth_block, geth_traces)?;
         Ok(builder)
@@ -772,4 +1159,55 @@ impl<P: JsonRpcClient> BuilderClient<P> {
         )?;
         Ok((builder, eth_block))
     }
+
+    /// Perform the steps 1 to 3 and collect their results in a
+    /// [`WitnessBundle`], from which the circuit inputs can be generated
+    /// without querying geth.
+    pub async fn gen_witness_bundle(&self, block_num: u64) -> Result<WitnessBundle, Error> {
+        let (eth_block, geth_traces, history_hashes, prev_state_root) =
+            self.get_block(block_num).await?;
+        let access_set = Self::get_state_accesses(&eth_block, &geth_traces)?;
+        let (proofs, codes) = self.get_state(block_num, access_set).await?;
+        Ok(WitnessBundle {
+            version: WITNESS_BUNDLE_VERSION,
+            chain_id: self.chain_id,
+            eth_block,
+            geth_traces,
+            proofs,
+            codes: codes
+                .into_iter()
+                .map(|(address, code)| (address, code.into()))
+                .collect(),
+            history_hashes,
+            prev_state_root,
+            circuits_params: self.circuits_params,
+        })
+    }
+
+    /// Perform all the steps to generate the circuit inputs from the prestate
+    /// of the block, which doesn't need the struct logs nor the state proofs.
+    pub async fn gen_inputs_from_prestate(
+        &self,
+        block_num: u64,
+    ) -> Result<
+        (
+            CircuitInputBuilder<FixedCParams>,
+            eth_types::Block<eth_types::Transaction>,
+        ),
+        Error,
+    > {
+        let (eth_block, geth_traces, accounts, history_hashes, prev_state_root) =
+            self.get_block_from_prestate(block_num).await?;
+        let (state_db, code_db) =
+            build_state_code_db_from_prestate(&eth_block, &geth_traces, accounts)?;
+        let builder = self.gen_inputs_from_state(
+            state_db,
+            code_db,
+            &eth_block,
+            &geth_traces,
+            history_hashes,
+            prev_state_root,
+        )?;
+        Ok((builder, eth_block))
+    }
 }
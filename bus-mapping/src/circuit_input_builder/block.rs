@@ -1,13 +1,19 @@
 //! Block-related utility module
 
 use super::{
-    execution::ExecState, transaction::Transaction, CopyEvent, ExecStep, ExpEvent, Withdrawal,
+    execution::ExecState, transaction::Transaction, CopyEvent, ExecStep, ExpEvent, PrecompileEvent,
+    PrecompileEvents, Withdrawal,
 };
 use crate::{
-    operation::{OperationContainer, RWCounter},
+    operation::{OperationContainer, RWCounter, TxLogField, TxReceiptField},
     Error,
 };
-use eth_types::{evm_unimplemented, Address, Word, H256};
+use eth_types::{
+    evm_types::{blob_base_fee, Hardfork},
+    evm_unimplemented,
+    geth_types::{self, block_excess_blob_gas, Receipt, ReceiptLog},
+    Address, ToBigEndian, ToWord, Word, H256,
+};
 use itertools::Itertools;
 use std::collections::HashMap;
 
@@ -45,6 +51,12 @@ impl BlockContext {
 /// Block-wise execution steps that don't belong to any Transaction.
 #[derive(Debug)]
 pub struct BlockSteps {
+    /// Withdrawal steps, one per withdrawal of the chunk, that credit the
+    /// withdrawn amounts after the last transaction of their block.
+    pub withdrawals: Vec<ExecStep>,
+    /// EndBlock steps between the blocks of a chunk, one after each block but
+    /// the last one.
+    pub end_blocks: Vec<ExecStep>,
     /// EndBlock step that is repeated after the last transaction and before
     /// reaching the last EVM row.
     pub end_block_not_last: ExecStep,
@@ -52,10 +64,9 @@ pub struct BlockSteps {
     pub end_block_last: ExecStep,
 }
 
-// TODO: Remove fields that are duplicated in`eth_block`
-/// Circuit Input related to a block.
-#[derive(Debug)]
-pub struct Block {
+/// Header values of a block, as seen by the transactions of the block.
+#[derive(Debug, Clone)]
+pub struct BlockHead {
     /// chain id
     pub chain_id: Word,
     /// history hashes contains most recent 256 block hashes in history, where
@@ -73,28 +84,23 @@ pub struct Block {
     pub difficulty: Word,
     /// base fee
     pub base_fee: Word,
+    /// excess blob gas (EIP-4844)
+    pub excess_blob_gas: u64,
+    /// base fee per unit of blob gas (EIP-4844)
+    pub blob_base_fee: Word,
     /// State root of the previous block
     pub prev_state_root: Word,
-    /// Container of operations done in this block.
-    pub container: OperationContainer,
-    /// Transactions contained in the block
-    pub txs: Vec<Transaction>,
-    /// Block-wise steps
-    pub block_steps: BlockSteps,
-    /// Copy events in this block.
-    pub copy_events: Vec<CopyEvent>,
-    /// Inputs to the SHA3 opcode
-    pub sha3_inputs: Vec<Vec<u8>>,
-    /// Exponentiation events in the block.
-    pub exp_events: Vec<ExpEvent>,
+    /// Hardfork of the block
+    pub hardfork: Hardfork,
     /// Original block from geth
     pub eth_block: eth_types::Block<eth_types::Transaction>,
 }
 
-impl Block {
-    /// Create a new block.
+impl BlockHead {
+    /// Create a new block head.
     pub fn new(
         chain_id: Word,
+        hardfork: Hardfork,
         history_hashes: Vec<Word>,
         prev_state_root: Word,
         eth_block: &eth_types::Block<eth_types::Transaction>,
@@ -106,6 +112,8 @@ impl Block {
             );
         }
 
+        let excess_blob_gas = block_excess_blob_gas(eth_block).as_u64();
+
         Ok(Self {
             chain_id,
             history_hashes,
@@ -129,10 +137,168 @@ impl Block {
                 eth_block.difficulty
             },
             base_fee: eth_block.base_fee_per_gas.unwrap_or_default(),
+            excess_blob_gas,
+            blob_base_fee: blob_base_fee(excess_blob_gas),
             prev_state_root,
+            hardfork,
+            eth_block: eth_block.clone(),
+        })
+    }
+
+    /// Create the head of the block that follows this one in a chunk.  The
+    /// history hashes are shifted to include the hash of this block, whose
+    /// state root becomes the previous state root.  All the blocks of a chunk
+    /// share the same chain id and hardfork.
+    pub fn next(
+        &self,
+        eth_block: &eth_types::Block<eth_types::Transaction>,
+    ) -> Result<Self, Error> {
+        let number = eth_block
+            .number
+            .ok_or(Error::EthTypeError(eth_types::Error::IncompleteBlock))?
+            .low_u64();
+        let hash = self
+            .eth_block
+            .hash
+            .ok_or(Error::EthTypeError(eth_types::Error::IncompleteBlock))?;
+        if number != self.number.low_u64() + 1 || eth_block.parent_hash != hash {
+            return Err(Error::NonConsecutiveBlock(number));
+        }
+
+        let mut history_hashes = self.history_hashes.clone();
+        if history_hashes.len() == 256 {
+            history_hashes.remove(0);
+        }
+        history_hashes.push(hash.to_word());
+
+        Self::new(
+            self.chain_id,
+            self.hardfork,
+            history_hashes,
+            self.eth_block.state_root.to_word(),
+            eth_block,
+        )
+    }
+
+    /// Return the list of withdrawals of this block.
+    pub fn withdrawals(&self) -> Vec<Withdrawal> {
+        let eth_withdrawals = self.eth_block.withdrawals.clone().unwrap_or_default();
+        eth_withdrawals
+            .iter()
+            .map({
+                |w| {
+                    Withdrawal::new(
+                        w.index.as_u64(),
+                        w.validator_index.as_u64(),
+                        w.address,
+                        w.amount.as_u64(),
+                    )
+                    .unwrap()
+                }
+            })
+            .collect_vec()
+    }
+
+    /// Return root of withdrawals of this block, computed from its withdrawals
+    pub fn withdrawals_root(&self) -> H256 {
+        let withdrawals = self
+            .withdrawals()
+            .into_iter()
+            .map(geth_types::Withdrawal::from)
+            .collect_vec();
+        geth_types::withdrawals_root(&withdrawals)
+    }
+}
+
+// TODO: Remove fields that are duplicated in`eth_block`
+/// Circuit Input related to a chunk of consecutive blocks.  The header fields
+/// are those of the current block, which is the last one once the chunk has
+/// been handled.
+#[derive(Debug)]
+pub struct Block {
+    /// chain id
+    pub chain_id: Word,
+    /// history hashes contains most recent 256 block hashes in history, where
+    /// the lastest one is at history_hashes[history_hashes.len() - 1].
+    pub history_hashes: Vec<Word>,
+    /// coinbase
+    pub coinbase: Address,
+    /// gas limit
+    pub gas_limit: u64,
+    /// number
+    pub number: Word,
+    /// time
+    pub timestamp: Word,
+    /// difficulty
+    pub difficulty: Word,
+    /// base fee
+    pub base_fee: Word,
+    /// excess blob gas (EIP-4844)
+    pub excess_blob_gas: u64,
+    /// base fee per unit of blob gas (EIP-4844)
+    pub blob_base_fee: Word,
+    /// State root of the previous block
+    pub prev_state_root: Word,
+    /// Hardfork of the block, which selects the set of valid opcodes, the gas
+    /// schedule and the set of precompiled contracts.
+    pub hardfork: Hardfork,
+    /// Heads of the blocks of the chunk, in order.
+    pub headers: Vec<BlockHead>,
+    /// Container of operations done in this block.
+    pub container: OperationContainer,
+    /// Transactions contained in the block
+    pub txs: Vec<Transaction>,
+    /// Block-wise steps
+    pub block_steps: BlockSteps,
+    /// Copy events in this block.
+    pub copy_events: Vec<CopyEvent>,
+    /// Inputs to the SHA3 opcode
+    pub sha3_inputs: Vec<Vec<u8>>,
+    /// Exponentiation events in the block.
+    pub exp_events: Vec<ExpEvent>,
+    /// Events of precompile calls verified by other circuits.
+    pub precompile_events: PrecompileEvents,
+    /// Original block from geth
+    pub eth_block: eth_types::Block<eth_types::Transaction>,
+}
+
+impl Block {
+    /// Create a new block.
+    pub fn new(
+        chain_id: Word,
+        hardfork: Hardfork,
+        history_hashes: Vec<Word>,
+        prev_state_root: Word,
+        eth_block: &eth_types::Block<eth_types::Transaction>,
+    ) -> Result<Self, Error> {
+        let head = BlockHead::new(
+            chain_id,
+            hardfork,
+            history_hashes,
+            prev_state_root,
+            eth_block,
+        )?;
+
+        Ok(Self {
+            chain_id: head.chain_id,
+            history_hashes: head.history_hashes.clone(),
+            coinbase: head.coinbase,
+            gas_limit: head.gas_limit,
+            number: head.number,
+            timestamp: head.timestamp,
+            difficulty: head.difficulty,
+            base_fee: head.base_fee,
+            excess_blob_gas: head.excess_blob_gas,
+            blob_base_fee: head.blob_base_fee,
+            prev_state_root: head.prev_state_root,
+            hardfork: head.hardfork,
+            eth_block: head.eth_block.clone(),
+            headers: vec![head],
             container: OperationContainer::new(),
             txs: Vec::new(),
             block_steps: BlockSteps {
+                withdrawals: Vec::new(),
+                end_blocks: Vec::new(),
                 end_block_not_last: ExecStep {
                     exec_state: ExecState::EndBlock,
                     ..ExecStep::default()
@@ -144,11 +310,34 @@ impl Block {
             },
             copy_events: Vec::new(),
             exp_events: Vec::new(),
+            precompile_events: PrecompileEvents::default(),
             sha3_inputs: Vec::new(),
-            eth_block: eth_block.clone(),
         })
     }
 
+    /// Make the block of `head` the current block of the chunk.
+    pub fn begin_block(&mut self, head: BlockHead) {
+        self.chain_id = head.chain_id;
+        self.history_hashes = head.history_hashes.clone();
+        self.coinbase = head.coinbase;
+        self.gas_limit = head.gas_limit;
+        self.number = head.number;
+        self.timestamp = head.timestamp;
+        self.difficulty = head.difficulty;
+        self.base_fee = head.base_fee;
+        self.excess_blob_gas = head.excess_blob_gas;
+        self.blob_base_fee = head.blob_base_fee;
+        self.prev_state_root = head.prev_state_root;
+        self.hardfork = head.hardfork;
+        self.eth_block = head.eth_block.clone();
+        self.headers.push(head);
+    }
+
+    /// Return the head of the current block of the chunk.
+    pub fn head(&self) -> &BlockHead {
+        self.headers.last().expect("a chunk has at least one block")
+    }
+
     /// Return the list of transactions of this block.
     pub fn txs(&self) -> &[Transaction] {
         &self.txs
@@ -159,28 +348,78 @@ impl Block {
         &mut self.txs
     }
 
-    /// Return the list of withdrawals of this block.
+    /// Return the list of withdrawals of all the blocks of the chunk, in
+    /// order.
     pub fn withdrawals(&self) -> Vec<Withdrawal> {
-        let eth_withdrawals = self.eth_block.withdrawals.clone().unwrap();
-        eth_withdrawals
+        self.headers
             .iter()
-            .map({
-                |w| {
-                    Withdrawal::new(
-                        w.index.as_u64(),
-                        w.validator_index.as_u64(),
-                        w.address,
-                        w.amount.as_u64(),
-                    )
-                    .unwrap()
-                }
-            })
+            .flat_map(BlockHead::withdrawals)
             .collect_vec()
     }
 
-    /// Return root of withdrawals of this block
+    /// Return root of withdrawals of the current block, computed from its
+    /// withdrawals
     pub fn withdrawals_root(&self) -> H256 {
-        self.eth_block.withdrawals_root.unwrap_or_default()
+        self.head().withdrawals_root()
+    }
+
+    /// Return the receipts of the transactions of this block, as written by
+    /// the tx receipt and tx log operations.
+    pub fn receipts(&self) -> Vec<Receipt> {
+        let mut receipts = self
+            .txs
+            .iter()
+            .map(|tx| Receipt {
+                tx_type: tx.transaction_type.as_u64(),
+                ..Default::default()
+            })
+            .collect_vec();
+        for op in self.container.tx_receipt.iter().map(|op| op.op()) {
+            let receipt = &mut receipts[op.tx_id - 1];
+            match op.field {
+                TxReceiptField::PostStateOrStatus => receipt.status = op.value != 0,
+                TxReceiptField::CumulativeGasUsed => receipt.cumulative_gas_used = op.value,
+                TxReceiptField::LogLength => (),
+            }
+        }
+
+        // The data of the logs by (tx_id, log_id), as it is written byte by byte
+        let mut data: HashMap<(usize, usize), Vec<u8>> = HashMap::new();
+        for op in self.container.tx_log.iter().map(|op| op.op()) {
+            let logs = &mut receipts[op.tx_id - 1].logs;
+            if logs.len() < op.log_id {
+                logs.resize(op.log_id, ReceiptLog::default());
+            }
+            let log = &mut logs[op.log_id - 1];
+            match op.field {
+                TxLogField::Address => {
+                    log.address = Address::from_slice(&op.value.to_be_bytes()[12..])
+                }
+                TxLogField::Topic => {
+                    if log.topics.len() <= op.index {
+                        log.topics.resize(op.index + 1, H256::zero());
+                    }
+                    log.topics[op.index] = H256::from(op.value.to_be_bytes());
+                }
+                TxLogField::Data => {
+                    let bytes = data.entry((op.tx_id, op.log_id)).or_default();
+                    if bytes.len() <= op.index {
+                        bytes.resize(op.index + 1, 0);
+                    }
+                    bytes[op.index] = op.value.as_u32() as u8;
+                }
+                TxLogField::TopicLength => log.topics.resize(op.value.as_usize(), H256::zero()),
+                TxLogField::DataLength => {
+                    data.entry((op.tx_id, op.log_id))
+                        .or_default()
+                        .resize(op.value.as_usize(), 0);
+                }
+            }
+        }
+        for ((tx_id, log_id), bytes) in data {
+            receipts[tx_id - 1].logs[log_id - 1].data = bytes.into();
+        }
+        receipts
     }
 
     /// Push a copy event to the block.
@@ -191,4 +430,8 @@ impl Block {
     pub fn add_exp_event(&mut self, event: ExpEvent) {
         self.exp_events.push(event);
     }
+    /// Push a precompile event to the block.
+    pub fn add_precompile_event(&mut self, event: PrecompileEvent) {
+        self.precompile_events.events.push(event);
+    }
 }
@@ -5,9 +5,9 @@ use crate::{
     error::{ExecError, OogError},
     exec_trace::OperationRef,
     operation::RWCounter,
-    precompile::PrecompileCalls,
+    precompile::{PrecompileAuxData, PrecompileCalls, N_BYTES_BLAKE2F_INPUT},
 };
-use eth_types::{evm_types::OpcodeId, GethExecStep, Word, H256};
+use eth_types::{evm_types::OpcodeId, sign_types::SignData, GethExecStep, Word, H256};
 use gadgets::impl_expr;
 use halo2_proofs::plonk::Expression;
 use strum_macros::EnumIter;
@@ -49,6 +49,9 @@ pub struct ExecStep {
     pub copy_rw_counter_delta: u64,
     /// Error generated by this step
     pub error: Option<ExecError>,
+    /// Optional auxiliary data that is attached to precompile call internal
+    /// states.
+    pub aux_data: Option<PrecompileAuxData>,
 }
 
 impl ExecStep {
@@ -77,6 +80,7 @@ impl ExecStep {
             bus_mapping_instance: Vec::new(),
             copy_rw_counter_delta: 0,
             error: None,
+            aux_data: None,
         }
     }
 
@@ -148,6 +152,8 @@ pub enum ExecState {
     EndBlock,
     /// Invalid Tx
     InvalidTx,
+    /// Virtual step crediting a withdrawal to its address
+    Withdrawal,
 }
 
 impl Default for ExecState {
@@ -292,8 +298,31 @@ impl CopyEvent {
         self.rw_counter_increase(self.bytes.len() * 2)
     }
 
+    /// Whether the copy happens within the memory of a single call, as for
+    /// MCOPY. All source bytes of such an event are read before any
+    /// destination byte is written, so overlapping ranges behave as if the
+    /// data went through an intermediate buffer.
+    pub fn is_memory_copy(&self) -> bool {
+        self.src_type == CopyDataType::Memory
+            && self.dst_type == CopyDataType::Memory
+            && self.src_id == self.dst_id
+    }
+
     // increase in rw counter from the start of the copy event to step index
     fn rw_counter_increase(&self, step_index: usize) -> u64 {
+        if self.is_memory_copy() {
+            // reads take the first half of the rw counters and writes the
+            // second half.
+            let bytes_len = u64::try_from(self.bytes.len()).unwrap();
+            let step_index = u64::try_from(step_index).unwrap();
+            return if step_index == bytes_len * 2 {
+                step_index
+            } else if step_index % 2 == 0 {
+                step_index / 2
+            } else {
+                bytes_len + step_index / 2
+            };
+        }
         let source_rw_increase = match self.src_type {
             CopyDataType::Bytecode | CopyDataType::TxCalldata | CopyDataType::RlcAcc => 0,
             CopyDataType::Memory => std::cmp::min(
@@ -364,3 +393,242 @@ impl Default for ExpEvent {
         }
     }
 }
+
+/// Event representing the precompile calls which are verified outside of
+/// the EVM circuit.
+#[derive(Clone, Debug)]
+pub enum PrecompileEvent {
+    /// Represents the signature to be verified for an ECRECOVER call.
+    Ecrecover(SignData),
+    /// Represents the input of a SHA256 call.
+    Sha256(Sha256Event),
+    /// Represents the input of a RIPEMD160 call.
+    Ripemd160(Ripemd160Event),
+    /// Represents the operands and result of a MODEXP call.
+    ModExp(ModExpEvent),
+    /// Represents the operands and result of an ECADD call.
+    EcAdd(EcAddOp),
+    /// Represents the operands and result of an ECMUL call.
+    EcMul(EcMulOp),
+    /// Represents the input and result of an ECPAIRING call.
+    EcPairing(EcPairingOp),
+    /// Represents the input and result of a BLAKE2F call.
+    Blake2f(Blake2fEvent),
+    /// Represents the input and result of a KZG point evaluation call.
+    PointEvaluation(PointEvaluationEvent),
+}
+
+/// The input bytes and digest of a SHA256 call.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Sha256Event {
+    /// Input bytes of the call.
+    pub input: Vec<u8>,
+    /// Output digest.
+    pub digest: [u8; 32],
+}
+
+/// The input bytes and digest of a RIPEMD160 call.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Ripemd160Event {
+    /// Input bytes of the call.
+    pub input: Vec<u8>,
+    /// Output digest, which is returned left padded with zeroes to 32 bytes.
+    pub digest: [u8; 20],
+}
+
+/// The operands and result of a MODEXP call, i.e. `base ^ exponent % modulus`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ModExpEvent {
+    /// Base of the exponentiation.
+    pub base: Word,
+    /// Exponent of the exponentiation.
+    pub exponent: Word,
+    /// Modulus of the exponentiation, which is not zero.
+    pub modulus: Word,
+    /// Result of the call.
+    pub result: Word,
+}
+
+/// The operands and result of an ECADD call, i.e. `P + Q = R`, whose
+/// coordinates are lower than the modulus of the base field.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EcAddOp {
+    /// First point, as `(x, y)`.
+    pub p: (Word, Word),
+    /// Second point, as `(x, y)`.
+    pub q: (Word, Word),
+    /// Sum of the points, or `(0, 0)` if the call fails.
+    pub r: (Word, Word),
+    /// Whether both points are on the curve, i.e. the call succeeds.
+    pub is_valid: bool,
+}
+
+/// The operands and result of an ECMUL call, i.e. `s * P = R`, whose
+/// coordinates are lower than the modulus of the base field.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EcMulOp {
+    /// Point, as `(x, y)`.
+    pub p: (Word, Word),
+    /// Scalar, reduced by the order of the curve.
+    pub s: Word,
+    /// Product, or `(0, 0)` if the call fails.
+    pub r: (Word, Word),
+    /// Whether the point is on the curve, i.e. the call succeeds.
+    pub is_valid: bool,
+}
+
+/// The input and result of an ECPAIRING call, whose length is a multiple of
+/// the length of a pair.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EcPairingOp {
+    /// Input bytes of the call, i.e. the pairs of G1 and G2 points.
+    pub input: Vec<u8>,
+    /// Whether the product of the pairings is the identity.
+    pub output: bool,
+    /// Whether all the points are valid, i.e. the call succeeds.
+    pub is_valid: bool,
+}
+
+/// The input and result of a BLAKE2F call, whose input is
+/// [`N_BYTES_BLAKE2F_INPUT`] bytes long.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Blake2fEvent {
+    /// Input bytes of the call, i.e. the rounds, `h`, `m`, `t` and `f`.
+    pub input: Vec<u8>,
+    /// The compressed state, even if the call runs out of gas, or empty if
+    /// the final block flag is neither 0 nor 1.
+    pub output: Vec<u8>,
+}
+
+impl Blake2fEvent {
+    /// Number of rounds, the first 4 bytes of the input in big-endian.
+    pub fn rounds(&self) -> u32 {
+        u32::from_be_bytes(self.input[..4].try_into().unwrap())
+    }
+
+    /// Whether the final block flag, the last byte of the input, is 0 or 1.
+    pub fn is_valid(&self) -> bool {
+        self.input[N_BYTES_BLAKE2F_INPUT - 1] <= 1
+    }
+}
+
+/// The input and result of a KZG point evaluation call, whose input is
+/// [`N_BYTES_POINT_EVALUATION_INPUT`](crate::precompile::N_BYTES_POINT_EVALUATION_INPUT)
+/// bytes long.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PointEvaluationEvent {
+    /// Input bytes of the call, i.e. the versioned hash, `z`, `y`, the
+    /// commitment and the proof.
+    pub input: Vec<u8>,
+    /// Whether the versioned hash matches the commitment and the proof opens
+    /// the commitment at `z` to `y`, i.e. the call succeeds with enough gas.
+    pub is_valid: bool,
+}
+
+/// The precompile events in a block.
+#[derive(Clone, Debug, Default)]
+pub struct PrecompileEvents {
+    /// All the precompile events, in the order they were executed.
+    pub events: Vec<PrecompileEvent>,
+}
+
+impl PrecompileEvents {
+    /// Get all the ecrecover events.
+    pub fn get_ecrecover_events(&self) -> Vec<SignData> {
+        self.events
+            .iter()
+            .filter_map(|e| match e {
+                PrecompileEvent::Ecrecover(sign_data) => Some(sign_data.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Get all the SHA256 events.
+    pub fn get_sha256_events(&self) -> Vec<Sha256Event> {
+        self.events
+            .iter()
+            .filter_map(|e| match e {
+                PrecompileEvent::Sha256(event) => Some(event.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Get all the RIPEMD160 events.
+    pub fn get_ripemd160_events(&self) -> Vec<Ripemd160Event> {
+        self.events
+            .iter()
+            .filter_map(|e| match e {
+                PrecompileEvent::Ripemd160(event) => Some(event.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Get all the MODEXP events.
+    pub fn get_modexp_events(&self) -> Vec<ModExpEvent> {
+        self.events
+            .iter()
+            .filter_map(|e| match e {
+                PrecompileEvent::ModExp(event) => Some(event.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Get all the ECADD events.
+    pub fn get_ec_add_events(&self) -> Vec<EcAddOp> {
+        self.events
+            .iter()
+            .filter_map(|e| match e {
+                PrecompileEvent::EcAdd(op) => Some(op.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Get all the ECMUL events.
+    pub fn get_ec_mul_events(&self) -> Vec<EcMulOp> {
+        self.events
+            .iter()
+            .filter_map(|e| match e {
+                PrecompileEvent::EcMul(op) => Some(op.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Get all the ECPAIRING events.
+    pub fn get_ec_pairing_events(&self) -> Vec<EcPairingOp> {
+        self.events
+            .iter()
+            .filter_map(|e| match e {
+                PrecompileEvent::EcPairing(op) => Some(op.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Get all the BLAKE2F events.
+    pub fn get_blake2f_events(&self) -> Vec<Blake2fEvent> {
+        self.events
+            .iter()
+            .filter_map(|e| match e {
+                PrecompileEvent::Blake2f(event) => Some(event.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Get all the KZG point evaluation events.
+    pub fn get_point_evaluation_events(&self) -> Vec<PointEvaluationEvent> {
+        self.events
+            .iter()
+            .filter_map(|e| match e {
+                PrecompileEvent::PointEvaluation(event) => Some(event.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+}
@@ -2,7 +2,7 @@
 
 use super::{
     get_call_memory_offset_length, get_create_init_code, Block, BlockContext, Call, CallContext,
-    CallKind, CodeSource, CopyEvent, ExecState, ExecStep, ExpEvent, Transaction,
+    CallKind, CodeSource, CopyEvent, ExecState, ExecStep, ExpEvent, PrecompileEvent, Transaction,
     TransactionContext,
 };
 use crate::{
@@ -10,8 +10,8 @@ use crate::{
     exec_trace::OperationRef,
     operation::{
         AccountField, AccountOp, CallContextField, CallContextOp, MemoryOp, Op, OpEnum, Operation,
-        StackOp, Target, TxAccessListAccountOp, TxLogField, TxLogOp, TxReceiptField, TxReceiptOp,
-        RW,
+        StackOp, Target, TxAccessListAccountOp, TxCreatedAccountOp, TxLogField, TxLogOp,
+        TxReceiptField, TxReceiptOp, RW,
     },
     precompile::{is_precompiled, PrecompileCalls},
     state_db::{CodeDB, StateDB},
@@ -556,6 +556,44 @@ impl<'a> CircuitInputStateRef<'a> {
         )
     }
 
+    /// Mark address as created in the current transaction.
+    pub fn tx_created_account_write(
+        &mut self,
+        step: &mut ExecStep,
+        address: Address,
+    ) -> Result<(), Error> {
+        let is_created = self.sdb.check_account_created(&address);
+        self.push_op_reversible(
+            step,
+            TxCreatedAccountOp {
+                tx_id: self.tx_ctx.id(),
+                address,
+                is_created: true,
+                is_created_prev: is_created,
+            },
+        )
+    }
+
+    /// Read whether address was created in the current transaction.
+    pub fn tx_created_account_read(
+        &mut self,
+        step: &mut ExecStep,
+        address: Address,
+    ) -> Result<bool, Error> {
+        let is_created = self.sdb.check_account_created(&address);
+        self.push_op(
+            step,
+            RW::READ,
+            TxCreatedAccountOp {
+                tx_id: self.tx_ctx.id(),
+                address,
+                is_created,
+                is_created_prev: is_created,
+            },
+        )?;
+        Ok(is_created)
+    }
+
     /// Push 2 reversible [`AccountOp`] to update `sender` and `receiver`'s
     /// balance by `value`. If `fee` is existing (not None), also need to push 1
     /// non-reversible [`AccountOp`] to update `sender` balance by `fee`.
@@ -860,7 +898,7 @@ impl<'a> CircuitInputStateRef<'a> {
 
     /// Check if address is a precompiled or not.
     pub fn is_precompiled(&self, address: &Address) -> bool {
-        address.0[0..19] == [0u8; 19] && (1..=9).contains(&address.0[19])
+        is_precompiled(address, self.block.hardfork)
     }
 
     /// Parse [`Call`] from a *CALL*/CREATE* step.
@@ -1003,6 +1041,22 @@ impl<'a> CircuitInputStateRef<'a> {
                     None
                 }
             }
+            OperationRef(Target::TxCreatedAccount, idx) => {
+                let operation = &self.block.container.tx_created_account[*idx];
+                if operation.rw().is_write() && operation.reversible() {
+                    Some(OpEnum::TxCreatedAccount(operation.op().reverse()))
+                } else {
+                    None
+                }
+            }
+            OperationRef(Target::TransientStorage, idx) => {
+                let operation = &self.block.container.transient_storage[*idx];
+                if operation.rw().is_write() && operation.reversible() {
+                    Some(OpEnum::TransientStorage(operation.op().reverse()))
+                } else {
+                    None
+                }
+            }
             _ => None,
         }
     }
@@ -1035,6 +1089,18 @@ impl<'a> CircuitInputStateRef<'a> {
             OpEnum::TxRefund(op) => {
                 self.sdb.set_refund(op.value);
             }
+            OpEnum::TxCreatedAccount(op) => {
+                if !op.is_created_prev && op.is_created {
+                    self.sdb.add_account_to_created(op.address);
+                }
+                if op.is_created_prev && !op.is_created {
+                    self.sdb.remove_account_from_created(&op.address);
+                }
+            }
+            OpEnum::TransientStorage(op) => {
+                self.sdb
+                    .set_transient_storage(&op.address, &op.key, &op.value);
+            }
             _ => unreachable!(),
         };
     }
@@ -1404,6 +1470,12 @@ impl<'a> CircuitInputStateRef<'a> {
         self.block.add_exp_event(event)
     }
 
+    /// Push an event representing auxiliary data for a precompile call to the
+    /// state.
+    pub fn push_precompile_event(&mut self, event: PrecompileEvent) {
+        self.block.add_precompile_event(event)
+    }
+
     pub(crate) fn get_step_err(
         &self,
         step: &GethExecStep,
@@ -1413,7 +1485,8 @@ impl<'a> CircuitInputStateRef<'a> {
             return Ok(Some(error));
         }
 
-        if matches!(step.op, OpcodeId::INVALID(_)) {
+        // Opcodes introduced by a later hardfork are undefined in the block.
+        if !self.block.hardfork.is_opcode_enabled(&step.op) {
             return Ok(Some(ExecError::InvalidOpcode));
         }
 
@@ -1462,6 +1535,7 @@ impl<'a> CircuitInputStateRef<'a> {
                     OpcodeId::RETURNDATACOPY => Some(ExecError::ReturnDataOutOfBounds),
                     // Break write protection (CALL with value will be handled below)
                     OpcodeId::SSTORE
+                    | OpcodeId::TSTORE
                     | OpcodeId::CREATE
                     | OpcodeId::CREATE2
                     | OpcodeId::SELFDESTRUCT
@@ -1608,36 +1682,14 @@ impl<'a> CircuitInputStateRef<'a> {
                 //   because the callGasTemp might probably be smaller than the gas
                 //   on top of the stack (step.stack.last())
                 // Therefore we postpone the oog handling to the implementor of callop.
-                if is_precompiled(&code_address) {
+                if self.is_precompiled(&code_address) {
                     let precompile_call: PrecompileCalls = code_address[19].into();
-                    match precompile_call {
-                        PrecompileCalls::Sha256
-                        | PrecompileCalls::Ripemd160
-                        | PrecompileCalls::Blake2F
-                        | PrecompileCalls::ECRecover
-                        | PrecompileCalls::Bn128Add
-                        | PrecompileCalls::Bn128Mul
-                        | PrecompileCalls::Bn128Pairing
-                        | PrecompileCalls::Modexp => {
-                            // Log the precompile address and gas left.
-                            // Failure due to precompile being unsupported.
-                            // Failure cases are routed to `PrecompileFailed` dummy gadget.
-                            log::trace!(
-                                "Precompile failed: code_address = {}, step.gas = {}",
-                                code_address,
-                                step.gas,
-                            );
-                            return Ok(Some(ExecError::UnimplementedPrecompiles));
-                        }
-                        pre_call => {
-                            log::trace!(
-                                "Precompile call failed: addr={:?}, step.gas={:?}",
-                                pre_call,
-                                step.gas
-                            );
-                            return Ok(None);
-                        }
-                    }
+                    log::trace!(
+                        "Precompile call failed: addr={:?}, step.gas={:?}",
+                        precompile_call,
+                        step.gas
+                    );
+                    return Ok(None);
                 }
             }
 
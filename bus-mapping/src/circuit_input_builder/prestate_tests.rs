@@ -0,0 +1,114 @@
+use super::*;
+use eth_types::{bytecode, geth_types::GethData, ResultGethPrestateTraces};
+use mock::test_ctx::{helpers::*, TestContext};
+use pretty_assertions::assert_eq;
+
+// Recorded `debug_traceBlockByNumber` response with the `prestateTracer` for
+// the block built in `gen_inputs_from_prestate`.
+const PRESTATE_FIXTURE: &str = r#"[
+  {
+    "result": {
+      "0x000000000000000000000000000000000cafe111": {
+        "balance": "0x8ac7230489e80000",
+        "nonce": 0,
+        "code": "0x602a60005560005400",
+        "storage": {
+          "0x0000000000000000000000000000000000000000000000000000000000000000": "0x0000000000000000000000000000000000000000000000000000000000000000"
+        }
+      },
+      "0x000000000000000000000000000000000cafe222": {
+        "balance": "0x8ac7230489e80000",
+        "nonce": 0
+      },
+      "0x00000000000000000000000000000000c014ba5e": {
+        "balance": "0x0"
+      }
+    }
+  }
+]"#;
+
+#[test]
+fn gen_inputs_from_prestate() {
+    let code = bytecode! {
+        PUSH1(0x2a)
+        PUSH1(0x00)
+        SSTORE
+        PUSH1(0x00)
+        SLOAD
+        STOP
+    };
+    let block: GethData = TestContext::<2, 1>::new(
+        None,
+        account_0_code_account_1_no_code(code),
+        tx_from_1_to_0,
+        |block, _tx| block,
+    )
+    .unwrap()
+    .into();
+
+    let prestates = serde_json::from_str::<ResultGethPrestateTraces>(PRESTATE_FIXTURE)
+        .unwrap()
+        .0
+        .into_iter()
+        .map(|prestate| prestate.result)
+        .collect();
+    let accounts = merge_prestates(prestates);
+
+    // The re-executed traces match the ones of the full struct logs.
+    let geth_traces = gen_geth_traces_from_prestate(
+        block.chain_id,
+        &block.eth_block,
+        &accounts,
+        block.history_hashes.clone(),
+    )
+    .unwrap();
+    assert_eq!(geth_traces, block.geth_traces);
+
+    let (sdb, code_db) =
+        build_state_code_db_from_prestate(&block.eth_block, &geth_traces, accounts).unwrap();
+    let builder = CircuitInputBuilder::new(
+        sdb,
+        code_db,
+        Block::new(
+            block.chain_id,
+            block.hardfork,
+            block.history_hashes,
+            Word::default(),
+            &block.eth_block,
+        )
+        .unwrap(),
+        DynamicCParams {},
+    );
+    builder
+        .handle_block(&block.eth_block, &geth_traces)
+        .unwrap();
+}
+
+#[test]
+fn merge_prestates_keeps_first_access() {
+    let address = Address::repeat_byte(0xff);
+    let prestate = |balance: u64, slots: &[(u64, u64)]| {
+        HashMap::from([(
+            address,
+            GethPrestateAccount {
+                balance: balance.into(),
+                storage: slots
+                    .iter()
+                    .map(|(key, value)| (Word::from(*key), Word::from(*value)))
+                    .collect(),
+                ..Default::default()
+            },
+        )])
+    };
+
+    let accounts = merge_prestates(vec![prestate(1, &[(0, 1)]), prestate(2, &[(0, 2), (1, 3)])]);
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(accounts[0].balance, Word::from(1));
+    assert_eq!(
+        accounts[0].storage,
+        HashMap::from([
+            (Word::from(0), Word::from(1)),
+            (Word::from(1), Word::from(3))
+        ])
+    );
+}
@@ -41,6 +41,7 @@ impl CircuitInputBuilderTx {
             .new_tx(0, &block.eth_block.transactions[0], true)
             .unwrap();
         let tx_ctx = TransactionContext::new(
+            1,
             &block.eth_block.transactions[0],
             &GethExecTrace {
                 gas: 0,
@@ -15,7 +15,7 @@ use super::{call::ReversionGroup, Call, CallContext, CallKind, CodeSource, ExecS
 #[derive(Debug, Default)]
 /// Context of a [`Transaction`] which can mutate in an [`ExecStep`].
 pub struct TransactionContext {
-    /// Unique identifier of transaction of the block. The value is `index + 1`.
+    /// Unique identifier of transaction of the chunk. The value is `index + 1`.
     id: usize,
     /// The index of logs made in the transaction.
     pub(crate) log_id: usize,
@@ -36,6 +36,7 @@ pub struct TransactionContext {
 impl TransactionContext {
     /// Create a new Self.
     pub fn new(
+        id: usize,
         eth_tx: &eth_types::Transaction,
         geth_trace: &GethExecTrace,
         is_last_tx: bool,
@@ -68,11 +69,7 @@ impl TransactionContext {
         };
 
         let mut tx_ctx = Self {
-            id: eth_tx
-                .transaction_index
-                .ok_or(Error::EthTypeError(eth_types::Error::IncompleteBlock))?
-                .as_u64() as usize
-                + 1,
+            id,
             log_id: 0,
             is_last_tx,
             call_is_success,
@@ -181,6 +178,8 @@ impl TransactionContext {
 pub struct Transaction {
     /// The transaction id
     pub id: u64,
+    /// Number of the block of the transaction
+    pub block_num: u64,
     /// The raw transaction fields
     tx: geth_types::Transaction,
     /// Calls made in the transaction
@@ -193,6 +192,7 @@ impl Transaction {
     /// Create a new Self.
     pub fn new(
         id: u64,
+        block_num: u64,
         call_id: usize,
         sdb: &StateDB,
         code_db: &mut CodeDB,
@@ -248,6 +248,7 @@ impl Transaction {
 
         Ok(Self {
             id,
+            block_num,
             tx: eth_tx.into(),
             calls: vec![call],
             steps: Vec::new(),
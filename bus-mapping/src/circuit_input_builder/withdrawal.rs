@@ -1,6 +1,6 @@
 //! Withdrawal & WithdrawalContext utility module.
 
-use eth_types::Address;
+use eth_types::{geth_types, Address, Word};
 
 use crate::Error;
 
@@ -42,9 +42,15 @@ impl Withdrawal {
             amount,
         })
     }
-    /// Return the amount in this withdrawal
-    pub fn amount_in_wei(&self) -> u64 {
-        self.amount * (10 ^ 9)
+    /// Return the amount in this withdrawal in Wei
+    pub fn amount_in_wei(&self) -> Word {
+        Word::from(self.amount) * Word::from(10u64.pow(9))
+    }
+
+    /// Return the RLP encoding of this withdrawal, as committed to by the
+    /// withdrawals root
+    pub fn rlp(&self) -> Vec<u8> {
+        geth_types::Withdrawal::from(*self).rlp()
     }
 
     /// Constructor for padding withdrawal in withdrawal circuit
@@ -55,3 +61,14 @@ impl Withdrawal {
         }
     }
 }
+
+impl From<Withdrawal> for geth_types::Withdrawal {
+    fn from(wd: Withdrawal) -> Self {
+        Self {
+            id: wd.id,
+            validator_id: wd.validator_id,
+            address: wd.address,
+            amount: wd.amount,
+        }
+    }
+}
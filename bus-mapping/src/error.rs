@@ -5,9 +5,12 @@ use eth_types::{evm_types::OpcodeId, Address, GethExecStep, Word, H256};
 use ethers_providers::ProviderError;
 use std::error::Error as StdError;
 
-use crate::geth_errors::{
-    GETH_ERR_GAS_UINT_OVERFLOW, GETH_ERR_OUT_OF_GAS, GETH_ERR_STACK_OVERFLOW,
-    GETH_ERR_STACK_UNDERFLOW,
+use crate::{
+    geth_errors::{
+        GETH_ERR_GAS_UINT_OVERFLOW, GETH_ERR_OUT_OF_GAS, GETH_ERR_STACK_OVERFLOW,
+        GETH_ERR_STACK_UNDERFLOW,
+    },
+    precompile::PrecompileCalls,
 };
 
 /// Error type for any BusMapping related failure.
@@ -42,6 +45,13 @@ pub enum Error {
     InternalError(&'static str),
     /// Rw number overflow
     RwsNotEnough(usize, usize),
+    /// Precompile call with an input larger than supported by the circuits.
+    PrecompileInputTooLarge(PrecompileCalls),
+    /// Witness bundle written with an unsupported format version.
+    WitnessBundleVersion(u64),
+    /// Block of a chunk, by number, that doesn't follow the previous block of
+    /// the chunk.
+    NonConsecutiveBlock(u64),
 }
 
 impl From<eth_types::Error> for Error {
@@ -75,9 +85,12 @@ pub enum OogError {
     /// Out of Gas for CREATE, RETURN, REVERT, which have dynamic memory
     /// expansion gas cost
     DynamicMemoryExpansion,
-    /// Out of Gas for CALLDATACOPY, CODECOPY, EXTCODECOPY, RETURNDATACOPY,
-    /// which copy a specified chunk of memory
+    /// Out of Gas for CALLDATACOPY, CODECOPY, RETURNDATACOPY, MCOPY, which
+    /// copy a specified chunk of memory
     MemoryCopy,
+    /// Out of Gas for EXTCODECOPY, which touches an extra account and copies
+    /// its code to memory
+    ExtCodeCopy,
     /// Out of Gas for BALANCE, EXTCODESIZE, EXTCODEHASH, which possibly touch
     /// an extra account
     AccountAccess,
@@ -115,8 +128,9 @@ impl From<&OpcodeId> for OogError {
             OpcodeId::RETURN | OpcodeId::REVERT => OogError::DynamicMemoryExpansion,
             OpcodeId::CALLDATACOPY
             | OpcodeId::CODECOPY
-            | OpcodeId::EXTCODECOPY
-            | OpcodeId::RETURNDATACOPY => OogError::MemoryCopy,
+            | OpcodeId::RETURNDATACOPY
+            | OpcodeId::MCOPY => OogError::MemoryCopy,
+            OpcodeId::EXTCODECOPY => OogError::ExtCodeCopy,
             OpcodeId::BALANCE | OpcodeId::EXTCODESIZE | OpcodeId::EXTCODEHASH => {
                 OogError::AccountAccess
             }
@@ -178,7 +192,7 @@ pub enum ExecError {
     StackUnderflow,
     /// Out of Gas
     OutOfGas(OogError),
-    /// For SSTORE, LOG0, LOG1, LOG2, LOG3, LOG4, CREATE, CALL, CREATE2,
+    /// For SSTORE, TSTORE, LOG0, LOG1, LOG2, LOG3, LOG4, CREATE, CALL, CREATE2,
     /// SELFDESTRUCT
     WriteProtection,
     /// For CALL, CALLCODE, DELEGATECALL, STATICCALL, CREATE, CREATE2
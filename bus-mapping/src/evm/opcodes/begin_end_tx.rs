@@ -5,10 +5,7 @@ use crate::{
     state_db::CodeDB,
     Error,
 };
-use eth_types::{
-    evm_types::{GasCost, MAX_REFUND_QUOTIENT_OF_GAS_USED, PRECOMPILE_COUNT},
-    ToWord, Word,
-};
+use eth_types::{evm_types::GasCost, ToWord, Word};
 use ethers_core::utils::get_contract_address;
 
 #[derive(Clone, Copy, Debug)]
@@ -62,7 +59,7 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
     )?;
 
     // Add precompile contract address to access list
-    for address in 1..=PRECOMPILE_COUNT {
+    for address in 1..=state.block.hardfork.precompile_count() {
         let address = eth_types::Address::from_low_u64_be(address);
         let is_warm_prev = !state.sdb.add_account_to_access_list(address);
         state.tx_accesslist_account_write(
@@ -88,7 +85,7 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
 
     let init_code_gas_cost = if state.tx.is_create() {
         // Calculate gas cost of init code for EIP-3860.
-        (state.tx.call_data.len() as u64 + 31) / 32 * eth_types::evm_types::INIT_CODE_WORD_GAS
+        (state.tx.call_data.len() as u64 + 31) / 32 * state.block.hardfork.init_code_word_gas()
     } else {
         0
     };
@@ -121,7 +118,8 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
         )?;
     }
 
-    // Transfer with fee
+    // Transfer with fee. The blob gas fee (EIP-4844) is paid upfront together
+    // with the gas fee, and is burned rather than refunded at the end of the tx.
     state.transfer_with_fee(
         &mut exec_step,
         call.caller_address,
@@ -129,7 +127,9 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
         callee_exists,
         call.is_create(),
         call.value,
-        Some(state.tx.gas_price * state.tx.gas()),
+        Some(
+            state.tx.gas_price * state.tx.gas() + state.tx.blob_gas_fee(state.block.blob_base_fee),
+        ),
     )?;
 
     // In case of contract creation we wish to verify the correctness of the
@@ -167,6 +167,8 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
                     value_prev: 0.into(),
                 },
             )?;
+            // EIP 6780, mark callee as created in this tx
+            state.tx_created_account_write(&mut exec_step, call.address)?;
             for (field, value) in [
                 (CallContextField::Depth, call.depth.into()),
                 (
@@ -271,8 +273,10 @@ fn gen_end_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Error>
         },
     )?;
 
-    let effective_refund =
-        refund.min((state.tx.gas() - exec_step.gas_left) / MAX_REFUND_QUOTIENT_OF_GAS_USED as u64);
+    let effective_refund = refund.min(
+        (state.tx.gas() - exec_step.gas_left)
+            / state.block.hardfork.max_refund_quotient_of_gas_used(),
+    );
     let (found, caller_account) = state.sdb.get_account(&call.caller_address);
     if !found {
         return Err(Error::AccountNotFound(call.caller_address));
@@ -367,7 +371,14 @@ fn write_tx_receipt(
         log_id as u64,
     )?;
 
-    if state.tx_ctx.id() > 1 {
+    // The cumulative gas used restarts at the first tx of each block of the
+    // chunk
+    let is_first_tx_in_block = state
+        .block
+        .txs
+        .last()
+        .map_or(true, |prev_tx| prev_tx.block_num != state.tx.block_num);
+    if !is_first_tx_in_block {
         // query pre tx cumulative gas
         state.tx_receipt_read(
             exec_step,
@@ -0,0 +1,208 @@
+use super::Opcode;
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    operation::CallContextField,
+    Error,
+};
+use eth_types::GethExecStep;
+
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the opcodes that push a field of the block context:
+/// COINBASE, TIMESTAMP, NUMBER, DIFFICULTY, GASLIMIT, BASEFEE, BLOBBASEFEE
+/// and BLOCKHASH, which pops the number of the hashed block.  In a chunk of
+/// several blocks, the field is taken from the block of the tx, so the TxId
+/// is read first.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct BlockCtx<const N_POP: usize>;
+
+impl<const N_POP: usize> Opcode for BlockCtx<N_POP> {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        let mut exec_step = state.new_step(geth_step)?;
+
+        // CallContext read of the TxId
+        state.call_context_read(
+            &mut exec_step,
+            state.call()?.call_id,
+            CallContextField::TxId,
+            state.tx_ctx.id().into(),
+        )?;
+
+        // N_POP stack reads
+        for i in 0..N_POP {
+            state.stack_read(
+                &mut exec_step,
+                geth_step.stack.nth_last_filled(i),
+                geth_step.stack.nth_last(i)?,
+            )?;
+        }
+
+        // Stack write of the block context value
+        state.stack_write(
+            &mut exec_step,
+            geth_steps[1].stack.last_filled(),
+            geth_steps[1].stack.last()?,
+        )?;
+
+        Ok(vec![exec_step])
+    }
+}
+
+#[cfg(test)]
+mod block_ctx_tests {
+    use crate::{
+        circuit_input_builder::ExecState,
+        mock::BlockData,
+        operation::{CallContextField, CallContextOp, StackOp, RW},
+    };
+    use eth_types::{
+        bytecode,
+        evm_types::{blob_base_fee, OpcodeId, StackAddress},
+        geth_types::GethData,
+        Bytecode, Hash, ToWord, Word,
+    };
+    use mock::{
+        test_ctx::{helpers::*, TestContext},
+        MOCK_BASEFEE, MOCK_COINBASE, MOCK_DIFFICULTY, MOCK_GASLIMIT, MOCK_MIX_HASH,
+    };
+    use pretty_assertions::assert_eq;
+
+    fn block_ctx_opcode_impl(opcode: OpcodeId, code: Bytecode, push: StackOp) {
+        block_ctx_opcode_impl_ext(opcode, code, push, None);
+    }
+
+    fn block_ctx_opcode_impl_ext(
+        opcode: OpcodeId,
+        code: Bytecode,
+        push: StackOp,
+        mix_hash: Option<Hash>,
+    ) {
+        // Get the execution steps from the external tracer
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| {
+                if let Some(mix_hash) = mix_hash {
+                    block.difficulty(Word::zero());
+                    block.mix_hash(mix_hash);
+                }
+                block.number(0xcafeu64)
+            },
+        )
+        .unwrap()
+        .into();
+
+        let builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        let builder = builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let step = builder.block.txs()[0]
+            .steps()
+            .iter()
+            .find(|step| step.exec_state == ExecState::Op(opcode))
+            .unwrap();
+
+        let call_id = builder.block.txs()[0].calls()[0].call_id;
+        assert_eq!(
+            {
+                let operation =
+                    &builder.block.container.call_context[step.bus_mapping_instance[0].as_usize()];
+                (operation.rw(), operation.op())
+            },
+            (
+                RW::READ,
+                &CallContextOp {
+                    call_id,
+                    field: CallContextField::TxId,
+                    value: Word::one(),
+                }
+            )
+        );
+        assert_eq!(
+            {
+                let operation =
+                    &builder.block.container.stack[step.bus_mapping_instance[1].as_usize()];
+                (operation.rw(), operation.op())
+            },
+            (RW::WRITE, &push)
+        );
+    }
+
+    #[test]
+    fn coinbase_opcode_impl() {
+        block_ctx_opcode_impl(
+            OpcodeId::COINBASE,
+            bytecode! {
+                COINBASE
+                STOP
+            },
+            StackOp::new(1, StackAddress(1023), MOCK_COINBASE.to_word()),
+        );
+    }
+
+    #[test]
+    fn difficulty_opcode_impl() {
+        block_ctx_opcode_impl(
+            OpcodeId::DIFFICULTY,
+            bytecode! {
+                DIFFICULTY
+                STOP
+            },
+            StackOp::new(1, StackAddress(1023), *MOCK_DIFFICULTY),
+        );
+    }
+
+    #[test]
+    fn prevrandao_opcode_impl() {
+        block_ctx_opcode_impl_ext(
+            OpcodeId::DIFFICULTY,
+            bytecode! {
+                DIFFICULTY
+                STOP
+            },
+            StackOp::new(1, StackAddress(1023), MOCK_MIX_HASH.to_word()),
+            Some(*MOCK_MIX_HASH),
+        );
+    }
+
+    #[test]
+    fn gas_limit_opcode_impl() {
+        block_ctx_opcode_impl(
+            OpcodeId::GASLIMIT,
+            bytecode! {
+                GASLIMIT
+                STOP
+            },
+            StackOp::new(1, StackAddress(1023), *MOCK_GASLIMIT),
+        );
+    }
+
+    #[test]
+    fn basefee_opcode_impl() {
+        block_ctx_opcode_impl(
+            OpcodeId::BASEFEE,
+            bytecode! {
+                BASEFEE
+                STOP
+            },
+            StackOp::new(1, StackAddress(1023), *MOCK_BASEFEE),
+        );
+    }
+
+    #[test]
+    fn blobbasefee_opcode_impl() {
+        block_ctx_opcode_impl(
+            OpcodeId::BLOBBASEFEE,
+            bytecode! {
+                BLOBBASEFEE
+                STOP
+            },
+            StackOp::new(1, StackAddress(1023), blob_base_fee(0)),
+        );
+    }
+}
@@ -3,6 +3,7 @@ use crate::{
     circuit_input_builder::{
         CallKind, CircuitInputStateRef, CodeSource, CopyDataType, CopyEvent, ExecStep, NumberOrHash,
     },
+    error::{DepthError, ExecError, InsufficientBalanceError},
     evm::opcodes::{
         error_oog_precompile::ErrorOOGPrecompile,
         precompiles::gen_associated_ops as precompile_associated_ops,
@@ -180,7 +181,7 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
 
         let code_address = call.code_address();
         let is_precompile = code_address
-            .map(|ref addr| is_precompiled(addr))
+            .map(|ref addr| is_precompiled(addr, state.block.hardfork))
             .unwrap_or(false);
         // CALLCODE does not need to do real transfer
         // Transfer value only when all these conditions met:
@@ -274,7 +275,7 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
 
                 // get the result of the precompile call.
                 // For failed call, it will cost all gas provided
-                let (result, precompile_call_gas_cost, has_oog_err) = execute_precompiled(
+                let (mut result, mut precompile_call_gas_cost, has_oog_err) = execute_precompiled(
                     &code_address,
                     if args_length != 0 {
                         let caller_memory = &state.caller_ctx()?.memory;
@@ -285,6 +286,16 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
                     callee_gas_left_with_stipend,
                 );
 
+                // The KZG opening of a point evaluation isn't checked natively, so its
+                // failure is taken from the trace.
+                if precompile_call == PrecompileCalls::PointEvaluation
+                    && !call.is_success
+                    && !has_oog_err
+                {
+                    result = vec![];
+                    precompile_call_gas_cost = callee_gas_left_with_stipend;
+                }
+
                 // mutate the callee memory by at least the precompile call's result that will be
                 // written from memory addr 0 to memory addr result.len()
                 state.call_ctx_mut()?.memory.extend_at_least(result.len());
@@ -362,12 +373,11 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
 
                 // insert a copy event (input) for this step and generate memory op
                 let rw_counter_start = state.block_ctx.rwc;
-                if call.call_data_length > 0 {
-                    let n_input_bytes = if let Some(input_len) = precompile_call.input_len() {
-                        min(input_len, call.call_data_length as usize)
-                    } else {
-                        call.call_data_length as usize
-                    };
+                let input_bytes = if call.call_data_length > 0 {
+                    let n_input_bytes = precompile_call.input_len_of(
+                        call.call_data_length as usize,
+                        &state.caller_ctx()?.memory.0[args_offset..args_offset + args_length],
+                    );
 
                     let input_bytes = state.gen_copy_steps_for_precompile_calldata(
                         &mut exec_step,
@@ -390,7 +400,10 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
                             bytes: input_bytes.iter().map(|s| (*s, false)).collect(),
                         },
                     );
-                }
+                    input_bytes
+                } else {
+                    vec![]
+                };
 
                 // write the result in the callee's memory
                 let rw_counter_start = state.block_ctx.rwc;
@@ -441,7 +454,14 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
                     );
                 }
 
-                if has_oog_err {
+                // MODEXP and BLAKE2F run out of gas in their own gadgets, as their gas
+                // costs depend on the input.
+                if has_oog_err
+                    && !matches!(
+                        precompile_call,
+                        PrecompileCalls::Modexp | PrecompileCalls::Blake2F
+                    )
+                {
                     let mut oog_step = ErrorOOGPrecompile::gen_associated_ops(
                         state,
                         &geth_steps[1],
@@ -462,6 +482,8 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
                         geth_steps[1].clone(),
                         call.clone(),
                         precompile_call,
+                        &input_bytes,
+                        &result,
                     )?;
 
                     // Set gas left and gas cost for precompile step.
@@ -561,6 +583,11 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
             }
             // 4. insufficient balance or error depth cases.
             (false, _, _) => {
+                exec_step.error = Some(if is_valid_depth {
+                    ExecError::InsufficientBalance(InsufficientBalanceError::Call)
+                } else {
+                    ExecError::Depth(DepthError::Call)
+                });
                 for (field, value) in [
                     (CallContextField::LastCalleeId, call.call_id.into()),
                     (CallContextField::LastCalleeReturnDataOffset, 0.into()),
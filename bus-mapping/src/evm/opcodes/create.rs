@@ -2,7 +2,7 @@ use crate::{
     circuit_input_builder::{
         CircuitInputStateRef, CopyDataType, CopyEvent, ExecStep, NumberOrHash,
     },
-    error::ExecError,
+    error::{DepthError, ExecError, InsufficientBalanceError},
     evm::Opcode,
     operation::{AccountField, AccountOp, CallContextField, MemoryOp, RW},
     state_db::CodeDB,
@@ -43,6 +43,13 @@ impl<const IS_CREATE2: bool> Opcode for Create<IS_CREATE2> {
 
         state.reversion_info_read(&mut exec_step, &caller)?;
 
+        state.call_context_read(
+            &mut exec_step,
+            caller.call_id,
+            CallContextField::CalleeAddress,
+            caller.address.to_word(),
+        )?;
+
         // stack operation
         // Get low Uint64 of offset to generate copy steps. Since offset could
         // be Uint64 overflow if length is zero.
@@ -126,7 +133,9 @@ impl<const IS_CREATE2: bool> Opcode for Create<IS_CREATE2> {
             // operation happens in evm create() method before checking
             // ErrContractAddressCollision
             let code_hash_previous = if callee_exists {
-                if is_precheck_ok && is_address_collision {
+                // A collision on a CREATE address is only reachable from a crafted
+                // state, so it stays within the CREATE step.
+                if IS_CREATE2 && is_address_collision {
                     exec_step.error = Some(ExecError::ContractAddressCollision);
                 }
                 callee_account.code_hash
@@ -232,6 +241,9 @@ impl<const IS_CREATE2: bool> Opcode for Create<IS_CREATE2> {
                 },
             )?;
 
+            // EIP 6780, mark callee as created in this tx
+            state.tx_created_account_write(&mut exec_step, callee.address)?;
+
             if length > 0 {
                 for (field, value) in [
                     (CallContextField::CallerId, caller.call_id.into()),
@@ -274,6 +286,19 @@ impl<const IS_CREATE2: bool> Opcode for Create<IS_CREATE2> {
         }
         // failed case: is_precheck_ok is false or is_address_collision is true
         else {
+            if depth >= 1025 {
+                exec_step.error = Some(ExecError::Depth(if IS_CREATE2 {
+                    DepthError::Create2
+                } else {
+                    DepthError::Create
+                }));
+            } else if caller_balance < callee.value {
+                exec_step.error = Some(ExecError::InsufficientBalance(if IS_CREATE2 {
+                    InsufficientBalanceError::Create2
+                } else {
+                    InsufficientBalanceError::Create
+                }));
+            }
             for (field, value) in [
                 (CallContextField::LastCalleeId, callee.call_id.into()),
                 (CallContextField::LastCalleeReturnDataOffset, 0.into()),
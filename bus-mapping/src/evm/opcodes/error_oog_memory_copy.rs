@@ -9,7 +9,8 @@ use eth_types::{evm_types::OpcodeId, GethExecStep, ToAddress};
 
 /// Placeholder structure used to implement [`Opcode`] trait over it
 /// corresponding to the
-/// [`OogError::MemoryCopy`](crate::error::OogError::MemoryCopy).
+/// [`OogError::MemoryCopy`](crate::error::OogError::MemoryCopy) and
+/// [`OogError::ExtCodeCopy`](crate::error::OogError::ExtCodeCopy).
 #[derive(Clone, Copy, Debug)]
 pub(crate) struct OOGMemoryCopy;
 
@@ -23,12 +24,13 @@ impl Opcode for OOGMemoryCopy {
             OpcodeId::CALLDATACOPY,
             OpcodeId::CODECOPY,
             OpcodeId::EXTCODECOPY,
-            OpcodeId::RETURNDATACOPY
+            OpcodeId::RETURNDATACOPY,
+            OpcodeId::MCOPY
         ]
         .contains(&geth_step.op));
 
         let mut exec_step = state.new_step(geth_step)?;
-        exec_step.error = Some(ExecError::OutOfGas(OogError::MemoryCopy));
+        exec_step.error = Some(ExecError::OutOfGas(OogError::from(&geth_step.op)));
 
         let is_extcodecopy = geth_step.op == OpcodeId::EXTCODECOPY;
 
@@ -56,7 +58,7 @@ impl Opcode for OOGMemoryCopy {
             )?;
         }
 
-        // Each of CALLDATACOPY, CODECOPY and RETURNDATACOPY has 3 stack read values.
+        // Each of CALLDATACOPY, CODECOPY, RETURNDATACOPY and MCOPY has 3 stack read values.
         // But EXTCODECOPY has 4. It has an extra stack pop for external address.
         let stack_read_num = if is_extcodecopy { 4 } else { 3 };
         for i in 0..stack_read_num {
@@ -0,0 +1,153 @@
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    error::{ExecError, OogError},
+    evm::{Opcode, OpcodeId},
+    operation::{AccountField, CallContextField, TxAccessListAccountOp, RW},
+    Error,
+};
+use eth_types::{GethExecStep, ToAddress, ToWord, H256, U256};
+
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the
+/// [`OogError::SelfDestruct`](crate::error::OogError::SelfDestruct).
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct ErrorOOGSelfDestruct;
+
+impl Opcode for ErrorOOGSelfDestruct {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        debug_assert_eq!(geth_step.op, OpcodeId::SELFDESTRUCT);
+
+        let mut exec_step = state.new_step(geth_step)?;
+        exec_step.error = Some(ExecError::OutOfGas(OogError::SelfDestruct));
+
+        // Read beneficiary address from stack.
+        let beneficiary_word = geth_step.stack.last()?;
+        let beneficiary = beneficiary_word.to_address();
+        state.stack_read(
+            &mut exec_step,
+            geth_step.stack.last_filled(),
+            beneficiary_word,
+        )?;
+
+        let call = state.call()?.clone();
+        for (field, value) in [
+            (CallContextField::TxId, U256::from(state.tx_ctx.id())),
+            (CallContextField::CalleeAddress, call.address.to_word()),
+        ] {
+            state.call_context_read(&mut exec_step, call.call_id, field, value)?;
+        }
+
+        // Read `is_warm` of the beneficiary, which decides the cold access cost.
+        let is_warm = state.sdb.check_account_in_access_list(&beneficiary);
+        state.push_op(
+            &mut exec_step,
+            RW::READ,
+            TxAccessListAccountOp {
+                tx_id: state.tx_ctx.id(),
+                address: beneficiary,
+                is_warm,
+                is_warm_prev: is_warm,
+            },
+        )?;
+
+        // Read beneficiary code hash, which is 0 for non-existing accounts, and
+        // the balance to be moved, which together decide the new account cost.
+        let beneficiary_account = state.sdb.get_account(&beneficiary).1;
+        let beneficiary_code_hash = if beneficiary_account.is_empty() {
+            H256::zero()
+        } else {
+            beneficiary_account.code_hash
+        };
+        state.account_read(
+            &mut exec_step,
+            beneficiary,
+            AccountField::CodeHash,
+            beneficiary_code_hash.to_word(),
+        )?;
+        let balance = state.sdb.get_account(&call.address).1.balance;
+        state.account_read(&mut exec_step, call.address, AccountField::Balance, balance)?;
+
+        // common error handling
+        state.handle_return(&mut [&mut exec_step], geth_steps, true)?;
+        Ok(vec![exec_step])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        circuit_input_builder::ExecState,
+        error::{ExecError, OogError},
+        mock::BlockData,
+        operation::{StackOp, RW},
+    };
+    use eth_types::{address, bytecode, evm_types::OpcodeId, geth_types::GethData, ToWord, Word};
+    use mock::TestContext;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_oog_self_destruct() {
+        let beneficiary = address!("0xaabbccddee000000000000000000000000000000");
+        let code = bytecode! {
+            PUSH20(beneficiary.to_word())
+            SELFDESTRUCT
+        };
+
+        // Get the execution steps from the external tracer.
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x0000000000000000000000000000000000000010"))
+                    .balance(Word::from(1u64 << 20))
+                    .code(code.clone());
+                accs[1]
+                    .address(address!("0x0000000000000000000000000000000000cafe01"))
+                    .balance(Word::from(1u64 << 20));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .to(accs[0].address)
+                    .from(accs[1].address)
+                    .gas(25000.into());
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+        .into();
+
+        let builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        let builder = builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let transaction = &builder.block.txs()[0];
+        let call_id = transaction.calls()[0].call_id;
+        let step = transaction
+            .steps()
+            .iter()
+            .find(|step| step.exec_state == ExecState::Op(OpcodeId::SELFDESTRUCT))
+            .unwrap();
+
+        assert_eq!(
+            step.error,
+            Some(ExecError::OutOfGas(OogError::SelfDestruct))
+        );
+
+        let container = builder.block.container.clone();
+        let operation = &container.stack[step.bus_mapping_instance[0].as_usize()];
+        assert_eq!(operation.rw(), RW::READ);
+        assert_eq!(
+            operation.op(),
+            &StackOp {
+                call_id,
+                address: 1023.into(),
+                value: beneficiary.to_word(),
+            }
+        );
+    }
+}
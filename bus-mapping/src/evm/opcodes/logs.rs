@@ -126,6 +126,25 @@ fn gen_log_step(
         }
     }
 
+    if state.call()?.is_persistent {
+        state.tx_log_write(
+            &mut exec_step,
+            state.tx_ctx.id(),
+            state.tx_ctx.log_id + 1,
+            TxLogField::TopicLength,
+            0,
+            Word::from(topic_count),
+        )?;
+        state.tx_log_write(
+            &mut exec_step,
+            state.tx_ctx.id(),
+            state.tx_ctx.log_id + 1,
+            TxLogField::DataLength,
+            0,
+            msize,
+        )?;
+    }
+
     Ok(exec_step)
 }
 
@@ -425,8 +444,36 @@ mod log_tests {
                 memory_ops
             },
         );
+        // topic and data length writes
+        assert_eq!(
+            [1 + topic_count, 2 + topic_count]
+                .map(|idx| &builder.block.container.tx_log[idx])
+                .map(|op| (op.rw(), op.op().clone())),
+            [
+                (
+                    RW::WRITE,
+                    TxLogOp::new(
+                        1,
+                        step.log_id + 1,
+                        TxLogField::TopicLength,
+                        0,
+                        Word::from(topic_count)
+                    )
+                ),
+                (
+                    RW::WRITE,
+                    TxLogOp::new(
+                        1,
+                        step.log_id + 1,
+                        TxLogField::DataLength,
+                        0,
+                        Word::from(msize)
+                    )
+                ),
+            ],
+        );
         assert_eq!(
-            ((1 + topic_count)..msize + 1 + topic_count)
+            ((3 + topic_count)..msize + 3 + topic_count)
                 .map(|idx| &builder.block.container.tx_log[idx])
                 .map(|op| (op.rw(), op.op().clone()))
                 .collect::<Vec<(RW, TxLogOp)>>(),
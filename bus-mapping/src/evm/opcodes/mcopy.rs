@@ -0,0 +1,217 @@
+use super::Opcode;
+use crate::{
+    circuit_input_builder::{
+        CircuitInputStateRef, CopyDataType, CopyEvent, ExecStep, NumberOrHash,
+    },
+    Error,
+};
+use eth_types::GethExecStep;
+
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the [`OpcodeId::MCOPY`](crate::evm::OpcodeId::MCOPY)
+/// `OpcodeId`.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Mcopy;
+
+impl Opcode for Mcopy {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        let mut exec_step = state.new_step(geth_step)?;
+
+        let dst_offset = geth_step.stack.nth_last(0)?;
+        let src_offset = geth_step.stack.nth_last(1)?;
+        let length = geth_step.stack.nth_last(2)?;
+
+        state.stack_read(
+            &mut exec_step,
+            geth_step.stack.nth_last_filled(0),
+            dst_offset,
+        )?;
+        state.stack_read(
+            &mut exec_step,
+            geth_step.stack.nth_last_filled(1),
+            src_offset,
+        )?;
+        state.stack_read(&mut exec_step, geth_step.stack.nth_last_filled(2), length)?;
+
+        // reconstruction
+        // Both the source and destination ranges count towards memory
+        // expansion.
+        let memory = &mut state.call_ctx_mut()?.memory;
+        memory.extend_for_range(src_offset, length);
+        memory.extend_for_range(dst_offset, length);
+
+        let copy_event = gen_copy_event(state, geth_step, &mut exec_step)?;
+        state.push_copy(&mut exec_step, copy_event);
+        Ok(vec![exec_step])
+    }
+}
+
+fn gen_copy_steps(
+    state: &mut CircuitInputStateRef,
+    exec_step: &mut ExecStep,
+    src_addr: u64,
+    dst_addr: u64,
+    bytes_left: u64,
+) -> Result<Vec<(u8, bool)>, Error> {
+    // Read the whole source range before writing anything, so that an
+    // overlapping destination doesn't clobber bytes which are yet to be read.
+    let mut copy_steps = Vec::with_capacity(bytes_left as usize);
+    for idx in 0..bytes_left {
+        let value = state.memory_read(exec_step, (src_addr + idx).into())?;
+        copy_steps.push((value, false));
+    }
+    for (idx, (value, _)) in copy_steps.iter().enumerate() {
+        state.memory_write(exec_step, (dst_addr + idx as u64).into(), *value)?;
+    }
+    Ok(copy_steps)
+}
+
+fn gen_copy_event(
+    state: &mut CircuitInputStateRef,
+    geth_step: &GethExecStep,
+    exec_step: &mut ExecStep,
+) -> Result<CopyEvent, Error> {
+    let rw_counter_start = state.block_ctx.rwc;
+
+    // Get low Uint64 of the offsets to generate copy steps. Since they could
+    // be Uint64 overflow if length is zero.
+    let dst_addr = geth_step.stack.nth_last(0)?.low_u64();
+    let src_addr = geth_step.stack.nth_last(1)?.low_u64();
+    let length = geth_step.stack.nth_last(2)?.as_u64();
+
+    let copy_steps = gen_copy_steps(state, exec_step, src_addr, dst_addr, length)?;
+
+    let call_id = state.call()?.call_id;
+    Ok(CopyEvent {
+        src_type: CopyDataType::Memory,
+        src_id: NumberOrHash::Number(call_id),
+        src_addr,
+        src_addr_end: src_addr + length,
+        dst_type: CopyDataType::Memory,
+        dst_id: NumberOrHash::Number(call_id),
+        dst_addr,
+        log_id: None,
+        rw_counter_start,
+        bytes: copy_steps,
+    })
+}
+
+#[cfg(test)]
+mod mcopy_tests {
+    use crate::{
+        circuit_input_builder::{ExecState, NumberOrHash},
+        mock::BlockData,
+        operation::{MemoryOp, StackOp, RW},
+    };
+    use eth_types::{
+        bytecode,
+        evm_types::{OpcodeId, StackAddress},
+        geth_types::GethData,
+        Word,
+    };
+    use mock::test_ctx::{helpers::*, TestContext};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn mcopy_opcode_overlapping() {
+        let dst_offset = 0x10usize;
+        let src_offset = 0x00usize;
+        let copy_size = 0x20usize;
+        // Memory [0x00, 0x20) holds the bytes 0x01..=0x20.
+        let stored = (1..=copy_size as u8).collect::<Vec<u8>>();
+        let code = bytecode! {
+            PUSH32(Word::from_big_endian(&stored))
+            PUSH1(0x00)
+            MSTORE
+            .op_mcopy(dst_offset, src_offset, copy_size)
+            STOP
+        };
+
+        // Get the execution steps from the external tracer
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+        .into();
+
+        let builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        let builder = builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let step = builder.block.txs()[0]
+            .steps()
+            .iter()
+            .find(|step| step.exec_state == ExecState::Op(OpcodeId::MCOPY))
+            .unwrap();
+        let call_id = builder.block.txs()[0].calls()[step.call_index].call_id;
+
+        // 3 stack reads + `copy_size` memory reads + `copy_size` memory writes
+        assert_eq!(step.bus_mapping_instance.len(), 3 + copy_size * 2);
+
+        assert_eq!(
+            [0, 1, 2]
+                .map(|idx| &builder.block.container.stack[step.bus_mapping_instance[idx].as_usize()])
+                .map(|operation| (operation.rw(), operation.op())),
+            [
+                (
+                    RW::READ,
+                    &StackOp::new(call_id, StackAddress::from(1021), Word::from(dst_offset))
+                ),
+                (
+                    RW::READ,
+                    &StackOp::new(call_id, StackAddress::from(1022), Word::from(src_offset))
+                ),
+                (
+                    RW::READ,
+                    &StackOp::new(call_id, StackAddress::from(1023), Word::from(copy_size))
+                ),
+            ]
+        );
+
+        // All the reads come first and see the memory as it was before the
+        // copy, even where the destination overlaps the source.
+        assert_eq!(
+            (3..3 + copy_size * 2)
+                .map(|idx| &builder.block.container.memory
+                    [step.bus_mapping_instance[idx].as_usize()])
+                .map(|operation| (operation.rw(), operation.op().clone()))
+                .collect::<Vec<(RW, MemoryOp)>>(),
+            (0..copy_size)
+                .map(|idx| (
+                    RW::READ,
+                    MemoryOp::new(call_id, (src_offset + idx).into(), stored[idx])
+                ))
+                .chain((0..copy_size).map(|idx| (
+                    RW::WRITE,
+                    MemoryOp::new(call_id, (dst_offset + idx).into(), stored[idx])
+                )))
+                .collect::<Vec<(RW, MemoryOp)>>(),
+        );
+
+        let copy_events = builder.block.copy_events.clone();
+        assert_eq!(copy_events.len(), 1);
+        assert!(copy_events[0].is_memory_copy());
+        assert_eq!(copy_events[0].src_id, NumberOrHash::Number(call_id));
+        assert_eq!(copy_events[0].dst_id, NumberOrHash::Number(call_id));
+        assert_eq!(copy_events[0].src_addr as usize, src_offset);
+        assert_eq!(copy_events[0].src_addr_end as usize, src_offset + copy_size);
+        assert_eq!(copy_events[0].dst_addr as usize, dst_offset);
+        assert_eq!(copy_events[0].rw_counter_delta() as usize, copy_size * 2);
+        assert_eq!(
+            copy_events[0]
+                .bytes
+                .iter()
+                .map(|(value, _)| *value)
+                .collect::<Vec<u8>>(),
+            stored
+        );
+    }
+}
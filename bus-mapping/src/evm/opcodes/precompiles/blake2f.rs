@@ -0,0 +1,47 @@
+use crate::{
+    circuit_input_builder::{Blake2fEvent, PrecompileEvent},
+    precompile::{
+        execute_precompiled, Blake2fAuxData, PrecompileAuxData, PrecompileCalls,
+        N_BYTES_BLAKE2F_INPUT,
+    },
+};
+
+pub(crate) fn opt_data(
+    input_bytes: &[u8],
+    output_bytes: &[u8],
+    is_success: bool,
+) -> (Option<PrecompileEvent>, Option<PrecompileAuxData>) {
+    // An input of the wrong length is rejected by the EVM circuit, otherwise the
+    // BLAKE2F circuit also verifies the final block flag.  The compression is
+    // verified even when the call runs out of gas, since the gas cost is given by
+    // the rounds looked up from its table.
+    let event = (input_bytes.len() == N_BYTES_BLAKE2F_INPUT).then(|| {
+        let output = if is_success {
+            output_bytes.to_vec()
+        } else {
+            let (output, _, _) = execute_precompiled(
+                &PrecompileCalls::Blake2F.into(),
+                input_bytes,
+                u64::from(u32::from_be_bytes(input_bytes[..4].try_into().unwrap())),
+            );
+            output
+        };
+        Blake2fEvent {
+            input: input_bytes.to_vec(),
+            output,
+        }
+    });
+    let aux_data = Blake2fAuxData {
+        input_bytes: input_bytes.to_vec(),
+        output_bytes: output_bytes.to_vec(),
+        compressed: event
+            .as_ref()
+            .map(|event| event.output.clone())
+            .unwrap_or_default(),
+    };
+
+    (
+        event.map(PrecompileEvent::Blake2f),
+        Some(PrecompileAuxData::Blake2F(aux_data)),
+    )
+}
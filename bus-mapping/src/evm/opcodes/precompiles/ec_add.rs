@@ -0,0 +1,29 @@
+use crate::{
+    circuit_input_builder::{EcAddOp, PrecompileEvent},
+    precompile::{EcAddAuxData, PrecompileAuxData, BN254_FQ_MODULUS},
+};
+
+pub(crate) fn opt_data(
+    input_bytes: &[u8],
+    output_bytes: &[u8],
+    is_success: bool,
+) -> (Option<PrecompileEvent>, Option<PrecompileAuxData>) {
+    let aux_data = EcAddAuxData::new(input_bytes, output_bytes);
+
+    // The coordinates out of the field are rejected by the EVM circuit, the ECC
+    // circuit verifies whether the points are on the curve otherwise.
+    let coordinates = [aux_data.p_x, aux_data.p_y, aux_data.q_x, aux_data.q_y];
+    let event = coordinates
+        .iter()
+        .all(|coordinate| *coordinate < *BN254_FQ_MODULUS)
+        .then(|| {
+            PrecompileEvent::EcAdd(EcAddOp {
+                p: (aux_data.p_x, aux_data.p_y),
+                q: (aux_data.q_x, aux_data.q_y),
+                r: (aux_data.r_x, aux_data.r_y),
+                is_valid: is_success,
+            })
+        });
+
+    (event, Some(PrecompileAuxData::EcAdd(aux_data)))
+}
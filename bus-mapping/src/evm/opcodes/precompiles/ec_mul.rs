@@ -0,0 +1,25 @@
+use crate::{
+    circuit_input_builder::{EcMulOp, PrecompileEvent},
+    precompile::{EcMulAuxData, PrecompileAuxData, BN254_FQ_MODULUS},
+};
+
+pub(crate) fn opt_data(
+    input_bytes: &[u8],
+    output_bytes: &[u8],
+    is_success: bool,
+) -> (Option<PrecompileEvent>, Option<PrecompileAuxData>) {
+    let aux_data = EcMulAuxData::new(input_bytes, output_bytes);
+
+    // The coordinates out of the field are rejected by the EVM circuit, the ECC
+    // circuit verifies whether the point is on the curve otherwise.
+    let event = (aux_data.p_x < *BN254_FQ_MODULUS && aux_data.p_y < *BN254_FQ_MODULUS).then(|| {
+        PrecompileEvent::EcMul(EcMulOp {
+            p: (aux_data.p_x, aux_data.p_y),
+            s: aux_data.s(),
+            r: (aux_data.r_x, aux_data.r_y),
+            is_valid: is_success,
+        })
+    });
+
+    (event, Some(PrecompileAuxData::EcMul(aux_data)))
+}
@@ -0,0 +1,34 @@
+use eth_types::Word;
+
+use crate::{
+    circuit_input_builder::{EcPairingOp, PrecompileEvent},
+    precompile::{EcPairingAuxData, PrecompileAuxData, N_BYTES_EC_PAIR},
+};
+
+pub(crate) fn opt_data(
+    input_bytes: &[u8],
+    output_bytes: &[u8],
+    is_success: bool,
+) -> (Option<PrecompileEvent>, Option<PrecompileAuxData>) {
+    let output = if output_bytes.is_empty() {
+        Word::zero()
+    } else {
+        Word::from_big_endian(output_bytes)
+    };
+
+    // An input length which is not a multiple of the length of a pair is
+    // rejected by the EVM circuit.
+    let event = (input_bytes.len() % N_BYTES_EC_PAIR == 0).then(|| {
+        PrecompileEvent::EcPairing(EcPairingOp {
+            input: input_bytes.to_vec(),
+            output: !output.is_zero(),
+            is_valid: is_success,
+        })
+    });
+    let aux_data = EcPairingAuxData {
+        input_bytes: input_bytes.to_vec(),
+        output,
+    };
+
+    (event, Some(PrecompileAuxData::EcPairing(aux_data)))
+}
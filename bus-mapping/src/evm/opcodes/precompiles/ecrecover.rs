@@ -0,0 +1,48 @@
+use eth_types::{
+    sign_types::{msg_hash_to_scalar, recover_pk, SignData},
+    ToBigEndian, ToLittleEndian,
+};
+use halo2_proofs::halo2curves::{
+    ff::Field,
+    group::prime::PrimeCurveAffine,
+    secp256k1::{Fq, Secp256k1Affine},
+};
+
+use crate::{
+    circuit_input_builder::PrecompileEvent,
+    precompile::{EcrecoverAuxData, PrecompileAuxData},
+};
+
+pub(crate) fn opt_data(
+    input_bytes: &[u8],
+    output_bytes: &[u8],
+) -> (Option<PrecompileEvent>, Option<PrecompileAuxData>) {
+    let aux_data = EcrecoverAuxData::new(input_bytes, output_bytes);
+
+    // The calls with a valid v and 0 < r, s < n are verified by the SignVerify
+    // chip, which proves that the signature recovers the address or else that
+    // it recovers no public key.  The other calls simply return no output.
+    let sig_r: Option<Fq> = Fq::from_bytes(&aux_data.sig_r.to_le_bytes()).into();
+    let sig_s: Option<Fq> = Fq::from_bytes(&aux_data.sig_s.to_le_bytes()).into();
+    let event = sig_r
+        .zip(sig_s)
+        .filter(|(sig_r, sig_s)| !bool::from(sig_r.is_zero() | sig_s.is_zero()))
+        .zip(aux_data.recovery_id())
+        .map(|((sig_r, sig_s), v)| {
+            let msg_hash = aux_data.msg_hash.to_be_bytes();
+            let pk = if output_bytes.is_empty() {
+                Secp256k1Affine::identity()
+            } else {
+                recover_pk(v, &aux_data.sig_r, &aux_data.sig_s, &msg_hash)
+                    .expect("a recovered address implies a valid public key")
+            };
+            PrecompileEvent::Ecrecover(SignData {
+                signature: (sig_r, sig_s),
+                pk,
+                msg_hash: msg_hash_to_scalar(&msg_hash),
+                v,
+            })
+        });
+
+    (event, Some(PrecompileAuxData::Ecrecover(aux_data)))
+}
@@ -7,11 +7,33 @@ use crate::{
     Error,
 };
 
+mod blake2f;
+mod ec_add;
+mod ec_mul;
+mod ec_pairing;
+mod ecrecover;
+mod modexp;
+mod point_evaluation;
+mod ripemd160;
+mod sha256;
+
+use blake2f::opt_data as opt_data_blake2f;
+use ec_add::opt_data as opt_data_ec_add;
+use ec_mul::opt_data as opt_data_ec_mul;
+use ec_pairing::opt_data as opt_data_ec_pairing;
+use ecrecover::opt_data as opt_data_ecrecover;
+use modexp::opt_data as opt_data_modexp;
+use point_evaluation::opt_data as opt_data_point_evaluation;
+use ripemd160::opt_data as opt_data_ripemd160;
+use sha256::opt_data as opt_data_sha256;
+
 pub fn gen_associated_ops(
     state: &mut CircuitInputStateRef,
     geth_step: GethExecStep,
     call: Call,
     precompile: PrecompileCalls,
+    input_bytes: &[u8],
+    output_bytes: &[u8],
 ) -> Result<ExecStep, Error> {
     assert_eq!(call.code_address(), Some(precompile.into()));
     let mut exec_step = state.new_step(&geth_step)?;
@@ -19,6 +41,27 @@ pub fn gen_associated_ops(
 
     common_call_ctx_reads(state, &mut exec_step, &call)?;
 
+    let (opt_event, aux_data) = match precompile {
+        PrecompileCalls::ECRecover => opt_data_ecrecover(input_bytes, output_bytes),
+        PrecompileCalls::Sha256 => opt_data_sha256(input_bytes, output_bytes),
+        PrecompileCalls::Ripemd160 => opt_data_ripemd160(input_bytes, output_bytes),
+        PrecompileCalls::Modexp => opt_data_modexp(input_bytes, output_bytes, call.is_success)?,
+        PrecompileCalls::Bn128Add => opt_data_ec_add(input_bytes, output_bytes, call.is_success),
+        PrecompileCalls::Bn128Mul => opt_data_ec_mul(input_bytes, output_bytes, call.is_success),
+        PrecompileCalls::Bn128Pairing => {
+            opt_data_ec_pairing(input_bytes, output_bytes, call.is_success)
+        }
+        PrecompileCalls::Blake2F => opt_data_blake2f(input_bytes, output_bytes, call.is_success),
+        PrecompileCalls::PointEvaluation => {
+            opt_data_point_evaluation(input_bytes, output_bytes, call.is_success)
+        }
+        _ => (None, None),
+    };
+    if let Some(event) = opt_event {
+        state.push_precompile_event(event);
+    }
+    exec_step.aux_data = aux_data;
+
     Ok(exec_step)
 }
 
@@ -0,0 +1,28 @@
+use crate::{
+    circuit_input_builder::{ModExpEvent, PrecompileEvent},
+    precompile::{ModExpAuxData, PrecompileAuxData, PrecompileCalls},
+    Error,
+};
+
+pub(crate) fn opt_data(
+    input_bytes: &[u8],
+    output_bytes: &[u8],
+    is_success: bool,
+) -> Result<(Option<PrecompileEvent>, Option<PrecompileAuxData>), Error> {
+    let aux_data = ModExpAuxData::new(input_bytes, output_bytes)
+        .ok_or(Error::PrecompileInputTooLarge(PrecompileCalls::Modexp))?;
+
+    // Only the successful calls with a non-zero modulus are verified by the
+    // ModExp circuit, the result is zero otherwise.
+    let [base, exponent, modulus] = aux_data.inputs;
+    let event = (is_success && !modulus.is_zero()).then(|| {
+        PrecompileEvent::ModExp(ModExpEvent {
+            base,
+            exponent,
+            modulus,
+            result: aux_data.output,
+        })
+    });
+
+    Ok((event, Some(PrecompileAuxData::Modexp(aux_data))))
+}
@@ -0,0 +1,25 @@
+use crate::{
+    circuit_input_builder::{PointEvaluationEvent, PrecompileEvent},
+    precompile::{PointEvaluationAuxData, PrecompileAuxData, N_BYTES_POINT_EVALUATION_INPUT},
+};
+
+pub(crate) fn opt_data(
+    input_bytes: &[u8],
+    output_bytes: &[u8],
+    is_success: bool,
+) -> (Option<PrecompileEvent>, Option<PrecompileAuxData>) {
+    // An input of the wrong length is rejected by the EVM circuit, otherwise the
+    // whole point evaluation is verified against the KZG table.
+    let event = (input_bytes.len() == N_BYTES_POINT_EVALUATION_INPUT).then(|| {
+        PrecompileEvent::PointEvaluation(PointEvaluationEvent {
+            input: input_bytes.to_vec(),
+            is_valid: is_success,
+        })
+    });
+    let aux_data = PointEvaluationAuxData {
+        input_bytes: input_bytes.to_vec(),
+        output_bytes: output_bytes.to_vec(),
+    };
+
+    (event, Some(PrecompileAuxData::PointEvaluation(aux_data)))
+}
@@ -0,0 +1,26 @@
+use crate::{
+    circuit_input_builder::{PrecompileEvent, Ripemd160Event},
+    precompile::{PrecompileAuxData, Ripemd160AuxData},
+};
+
+pub(crate) fn opt_data(
+    input_bytes: &[u8],
+    output_bytes: &[u8],
+) -> (Option<PrecompileEvent>, Option<PrecompileAuxData>) {
+    // The digest is returned as a 32 bytes word.
+    let event = Ripemd160Event {
+        input: input_bytes.to_vec(),
+        digest: output_bytes[12..]
+            .try_into()
+            .expect("ripemd160 precompile returns 32 bytes"),
+    };
+    let aux_data = Ripemd160AuxData {
+        input_bytes: input_bytes.to_vec(),
+        output_bytes: output_bytes.to_vec(),
+    };
+
+    (
+        Some(PrecompileEvent::Ripemd160(event)),
+        Some(PrecompileAuxData::Ripemd160(aux_data)),
+    )
+}
@@ -0,0 +1,25 @@
+use crate::{
+    circuit_input_builder::{PrecompileEvent, Sha256Event},
+    precompile::{PrecompileAuxData, Sha256AuxData},
+};
+
+pub(crate) fn opt_data(
+    input_bytes: &[u8],
+    output_bytes: &[u8],
+) -> (Option<PrecompileEvent>, Option<PrecompileAuxData>) {
+    let event = Sha256Event {
+        input: input_bytes.to_vec(),
+        digest: output_bytes
+            .try_into()
+            .expect("sha256 precompile returns 32 bytes"),
+    };
+    let aux_data = Sha256AuxData {
+        input_bytes: input_bytes.to_vec(),
+        output_bytes: output_bytes.to_vec(),
+    };
+
+    (
+        Some(PrecompileEvent::Sha256(event)),
+        Some(PrecompileAuxData::Sha256(aux_data)),
+    )
+}
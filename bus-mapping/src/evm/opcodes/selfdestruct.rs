@@ -0,0 +1,287 @@
+use super::Opcode;
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    operation::{AccountField, AccountOp, CallContextField, TxAccessListAccountOp},
+    Error,
+};
+use eth_types::{GethExecStep, ToAddress, ToWord, Word, H256, U256};
+
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the [`OpcodeId::SELFDESTRUCT`](crate::evm::OpcodeId::SELFDESTRUCT)
+/// `OpcodeId`.
+///
+/// SELFDESTRUCT follows EIP-6780: the whole balance of the executing account
+/// is moved to the beneficiary, and the account is only deleted when it was
+/// created in the same transaction (in which case a balance sent to itself is
+/// burnt).  Since EIP-3529 there's no refund for SELFDESTRUCT.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct SelfDestruct;
+
+impl Opcode for SelfDestruct {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        let mut exec_step = state.new_step(geth_step)?;
+
+        // Read beneficiary address from stack.
+        let beneficiary_word = geth_step.stack.last()?;
+        let beneficiary = beneficiary_word.to_address();
+        state.stack_read(
+            &mut exec_step,
+            geth_step.stack.last_filled(),
+            beneficiary_word,
+        )?;
+
+        let call = state.call()?.clone();
+        let sender = call.address;
+        for (field, value) in [
+            (CallContextField::TxId, U256::from(state.tx_ctx.id())),
+            (CallContextField::IsSuccess, 1.into()),
+            (CallContextField::CalleeAddress, sender.to_word()),
+            (
+                CallContextField::RwCounterEndOfReversion,
+                U256::from(call.rw_counter_end_of_reversion as u64),
+            ),
+            (
+                CallContextField::IsPersistent,
+                U256::from(call.is_persistent as u64),
+            ),
+        ] {
+            state.call_context_read(&mut exec_step, call.call_id, field, value)?;
+        }
+
+        // Update transaction access list for beneficiary address.
+        let is_warm = state.sdb.check_account_in_access_list(&beneficiary);
+        state.push_op_reversible(
+            &mut exec_step,
+            TxAccessListAccountOp {
+                tx_id: state.tx_ctx.id(),
+                address: beneficiary,
+                is_warm: true,
+                is_warm_prev: is_warm,
+            },
+        )?;
+
+        // Read beneficiary code hash, which is 0 for non-existing accounts.
+        let beneficiary_account = state.sdb.get_account(&beneficiary).1;
+        let beneficiary_exists = !beneficiary_account.is_empty();
+        let beneficiary_code_hash = if beneficiary_exists {
+            beneficiary_account.code_hash
+        } else {
+            H256::zero()
+        };
+        state.account_read(
+            &mut exec_step,
+            beneficiary,
+            AccountField::CodeHash,
+            beneficiary_code_hash.to_word(),
+        )?;
+
+        let (found, sender_account) = state.sdb.get_account(&sender);
+        if !found {
+            return Err(Error::AccountNotFound(sender));
+        }
+        let sender_account = sender_account.clone();
+        let value = sender_account.balance;
+        state.account_read(&mut exec_step, sender, AccountField::Balance, value)?;
+
+        let is_created = state.tx_created_account_read(&mut exec_step, sender)?;
+
+        // Move the whole balance to the beneficiary, which is a noop when the
+        // beneficiary is the executing account itself.
+        if beneficiary != sender {
+            state.transfer(
+                &mut exec_step,
+                sender,
+                beneficiary,
+                beneficiary_exists,
+                false,
+                value,
+            )?;
+        }
+
+        // EIP-6780, delete the account only if it was created in this tx.
+        if is_created {
+            let balance_prev = if beneficiary == sender {
+                value
+            } else {
+                Word::zero()
+            };
+            for (field, value, value_prev) in [
+                (AccountField::Balance, Word::zero(), balance_prev),
+                (
+                    AccountField::Nonce,
+                    Word::zero(),
+                    sender_account.nonce.into(),
+                ),
+                (
+                    AccountField::CodeHash,
+                    Word::zero(),
+                    sender_account.code_hash.to_word(),
+                ),
+            ] {
+                state.push_op_reversible(
+                    &mut exec_step,
+                    AccountOp {
+                        address: sender,
+                        field,
+                        value,
+                        value_prev,
+                    },
+                )?;
+            }
+
+            if call.is_persistent {
+                state.sdb.destruct_account(sender);
+            }
+        }
+
+        state.handle_return(&mut [&mut exec_step], geth_steps, !call.is_root)?;
+        Ok(vec![exec_step])
+    }
+}
+
+#[cfg(test)]
+mod selfdestruct_tests {
+    use super::*;
+    use crate::{
+        circuit_input_builder::ExecState,
+        mock::BlockData,
+        operation::{StackOp, TxCreatedAccountOp, RW},
+        state_db::CodeDB,
+    };
+    use eth_types::{
+        address, bytecode,
+        evm_types::{OpcodeId, StackAddress},
+        geth_types::GethData,
+    };
+    use mock::TestContext;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_selfdestruct_of_pre_existing_account() {
+        let sender = address!("0x0000000000000000000000000000000000000010");
+        let beneficiary = address!("0xaabbccddee000000000000000000000000000000");
+        let balance = Word::from(1u64 << 20);
+
+        let code = bytecode! {
+            PUSH20(beneficiary.to_word())
+            SELFDESTRUCT
+        };
+
+        // Get the execution steps from the external tracer.
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(sender).balance(balance).code(code);
+                accs[1]
+                    .address(address!("0x0000000000000000000000000000000000cafe01"))
+                    .balance(Word::from(1u64 << 30));
+            },
+            |mut txs, accs| {
+                txs[0].to(accs[0].address).from(accs[1].address);
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+        .into();
+
+        let builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        let builder = builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let tx_id = 1;
+        let transaction = &builder.block.txs()[tx_id - 1];
+        let call_id = transaction.calls()[0].call_id;
+
+        let indices = transaction
+            .steps()
+            .iter()
+            .find(|step| step.exec_state == ExecState::Op(OpcodeId::SELFDESTRUCT))
+            .unwrap()
+            .bus_mapping_instance
+            .clone();
+
+        let container = &builder.block.container;
+
+        let operation = &container.stack[indices[0].as_usize()];
+        assert_eq!(operation.rw(), RW::READ);
+        assert_eq!(
+            operation.op(),
+            &StackOp {
+                call_id,
+                address: StackAddress::from(1023u32),
+                value: beneficiary.to_word()
+            }
+        );
+
+        let operation = &container.tx_access_list_account[indices[6].as_usize()];
+        assert_eq!(operation.rw(), RW::WRITE);
+        assert_eq!(
+            operation.op(),
+            &TxAccessListAccountOp {
+                tx_id,
+                address: beneficiary,
+                is_warm: true,
+                is_warm_prev: false
+            }
+        );
+
+        let operation = &container.tx_created_account[indices[9].as_usize()];
+        assert_eq!(operation.rw(), RW::READ);
+        assert_eq!(
+            operation.op(),
+            &TxCreatedAccountOp {
+                tx_id,
+                address: sender,
+                is_created: false,
+                is_created_prev: false
+            }
+        );
+
+        // The beneficiary is created and receives the whole balance.
+        for (index, expected) in [
+            (
+                10,
+                AccountOp {
+                    address: beneficiary,
+                    field: AccountField::CodeHash,
+                    value: CodeDB::empty_code_hash().to_word(),
+                    value_prev: Word::zero(),
+                },
+            ),
+            (
+                11,
+                AccountOp {
+                    address: sender,
+                    field: AccountField::Balance,
+                    value: Word::zero(),
+                    value_prev: balance,
+                },
+            ),
+            (
+                12,
+                AccountOp {
+                    address: beneficiary,
+                    field: AccountField::Balance,
+                    value: balance,
+                    value_prev: Word::zero(),
+                },
+            ),
+        ] {
+            let operation = &container.account[indices[index].as_usize()];
+            assert_eq!(operation.rw(), RW::WRITE);
+            assert_eq!(operation.op(), &expected);
+        }
+
+        // The account wasn't created in this tx, so it's not deleted.
+        assert_eq!(indices.len(), 13);
+        let (_, account) = builder.sdb.get_account(&sender);
+        assert_ne!(account.code_hash, CodeDB::empty_code_hash());
+        assert_eq!(account.nonce, 0);
+        assert_eq!(account.balance, Word::zero());
+    }
+}
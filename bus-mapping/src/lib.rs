@@ -236,4 +236,5 @@ pub mod operation;
 pub mod precompile;
 pub mod rpc;
 pub mod state_db;
+pub mod witness_bundle;
 pub use error::Error;
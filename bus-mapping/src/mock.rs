@@ -7,7 +7,7 @@ use crate::{
     },
     state_db::{self, CodeDB, StateDB},
 };
-use eth_types::{geth_types::GethData, Word};
+use eth_types::{evm_types::Hardfork, geth_types::GethData, Word};
 
 /// BlockData is a type that contains all the information from a block required
 /// to build the circuit inputs.
@@ -19,6 +19,8 @@ pub struct BlockData<C: CircuitsParams> {
     pub code_db: CodeDB,
     /// chain id
     pub chain_id: Word,
+    /// hardfork
+    pub hardfork: Hardfork,
     /// history hashes contains most recent 256 block hashes in history, where
     /// the lastest one is at history_hashes[history_hashes.len() - 1].
     pub history_hashes: Vec<Word>,
@@ -39,6 +41,7 @@ impl<C: CircuitsParams> BlockData<C> {
             self.code_db.clone(),
             Block::new(
                 self.chain_id,
+                self.hardfork,
                 self.history_hashes.clone(),
                 Word::default(),
                 &self.eth_block,
@@ -78,6 +81,7 @@ impl BlockData<FixedCParams> {
             sdb,
             code_db,
             chain_id: geth_data.chain_id,
+            hardfork: geth_data.hardfork,
             history_hashes: geth_data.history_hashes,
             eth_block: geth_data.eth_block,
             geth_traces: geth_data.geth_traces,
@@ -95,6 +99,7 @@ impl BlockData<DynamicCParams> {
             sdb,
             code_db,
             chain_id: geth_data.chain_id,
+            hardfork: geth_data.hardfork,
             history_hashes: geth_data.history_hashes,
             eth_block: geth_data.eth_block,
             geth_traces: geth_data.geth_traces,
@@ -1,16 +1,59 @@
 //! precompile helpers
 
 use eth_types::{
-    evm_types::{GasCost, OpcodeId},
-    Address, Bytecode, Word,
+    evm_types::{GasCost, Hardfork, OpcodeId},
+    word, Address, Bytecode, ToBigEndian, Word,
 };
+use lazy_static::lazy_static;
 use revm_precompile::{Precompile, PrecompileError, Precompiles};
+use serde::{Deserialize, Serialize};
+use std::cmp::{max, min};
 
-/// Check if address is a precompiled or not.
-pub fn is_precompiled(address: &Address) -> bool {
-    Precompiles::berlin()
-        .get(address.as_fixed_bytes())
-        .is_some()
+/// Length of the header of a MODEXP input, which declares the lengths of the
+/// base, exponent and modulus as 32-bytes big-endian words.
+pub const MODEXP_HEADER_LEN: usize = 96;
+
+/// Maximum length in bytes of the base, exponent and modulus of a MODEXP call
+/// supported by the circuits.
+pub const MODEXP_SIZE_LIMIT: usize = 32;
+
+/// Length of the input of ECPAIRING per pair of G1 and G2 points.
+pub const N_BYTES_EC_PAIR: usize = 192;
+
+/// Length of the input of BLAKE2F, i.e. the rounds, `h`, `m`, `t` and `f`.
+/// Calls with any other input length fail.
+pub const N_BYTES_BLAKE2F_INPUT: usize = 213;
+
+/// Length of the input of the KZG point evaluation, i.e. the versioned hash,
+/// `z`, `y`, the commitment and the proof.  Calls with any other input length
+/// fail.
+pub const N_BYTES_POINT_EVALUATION_INPUT: usize = 192;
+
+/// Number of field elements in a blob, as defined in EIP-4844.
+pub const FIELD_ELEMENTS_PER_BLOB: u64 = 4096;
+
+/// Version byte of the versioned hash of a KZG commitment.
+pub const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+lazy_static! {
+    /// Modulus of the base field of the BN254 curve used by ECADD, ECMUL and
+    /// ECPAIRING.  Coordinates greater or equal to it are rejected.
+    pub static ref BN254_FQ_MODULUS: Word =
+        word!("0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47");
+    /// Order of the BN254 curve, i.e. the modulus of its scalar field.
+    pub static ref BN254_FR_MODULUS: Word =
+        word!("0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001");
+    /// Modulus of the scalar field of the BLS12-381 curve used by the KZG point
+    /// evaluation.  Evaluation points and values greater or equal to it are
+    /// rejected.
+    pub static ref BLS_MODULUS: Word =
+        word!("0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001");
+}
+
+/// Check if address is a precompiled or not in the given hardfork.
+pub fn is_precompiled(address: &Address, hardfork: Hardfork) -> bool {
+    address.0[..19] == [0u8; 19]
+        && (1..=hardfork.precompile_count()).contains(&(address.0[19] as u64))
 }
 
 pub(crate) fn execute_precompiled(
@@ -18,6 +61,9 @@ pub(crate) fn execute_precompiled(
     input: &[u8],
     gas: u64,
 ) -> (Vec<u8>, u64, bool) {
+    if *address == PrecompileCalls::PointEvaluation.into() {
+        return execute_point_evaluation(input, gas);
+    }
     let Some(Precompile::Standard(precompile_fn)) = Precompiles::berlin()
         .get(address.as_fixed_bytes())  else {
         panic!("calling non-exist precompiled contract address")
@@ -39,6 +85,39 @@ pub(crate) fn execute_precompiled(
     (return_data, gas_cost, is_oog)
 }
 
+/// Executes the KZG point evaluation, which isn't supported by revm.  The
+/// input is checked except for the KZG opening itself, whose result is taken
+/// from the trace by the caller.
+fn execute_point_evaluation(input: &[u8], gas: u64) -> (Vec<u8>, u64, bool) {
+    let gas_cost = GasCost::PRECOMPILE_POINT_EVALUATION;
+    if gas < gas_cost {
+        return (vec![], gas, true);
+    }
+    if input.len() != N_BYTES_POINT_EVALUATION_INPUT {
+        return (vec![], gas, false);
+    }
+    let (commitment_hash, _, _) =
+        execute_precompiled(&PrecompileCalls::Sha256.into(), &input[96..144], u64::MAX);
+    let is_valid_hash =
+        input[0] == VERSIONED_HASH_VERSION_KZG && input[1..32] == commitment_hash[1..32];
+    let is_valid_point = Word::from_big_endian(&input[32..64]) < *BLS_MODULUS
+        && Word::from_big_endian(&input[64..96]) < *BLS_MODULUS;
+    if !is_valid_hash || !is_valid_point {
+        return (vec![], gas, false);
+    }
+    (point_evaluation_output(), gas_cost, false)
+}
+
+/// Output of a successful KZG point evaluation, i.e. `FIELD_ELEMENTS_PER_BLOB`
+/// and `BLS_MODULUS` as 32-bytes big-endian words.
+pub fn point_evaluation_output() -> Vec<u8> {
+    Word::from(FIELD_ELEMENTS_PER_BLOB)
+        .to_be_bytes()
+        .into_iter()
+        .chain(BLS_MODULUS.to_be_bytes())
+        .collect()
+}
+
 /// Addresses of the precompiled contracts.
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum PrecompileCalls {
@@ -60,6 +139,8 @@ pub enum PrecompileCalls {
     Bn128Pairing = 0x08,
     /// Compression function
     Blake2F = 0x09,
+    /// KZG point evaluation
+    PointEvaluation = 0x0a,
 }
 
 impl From<PrecompileCalls> for Address {
@@ -94,7 +175,8 @@ impl From<u8> for PrecompileCalls {
             0x07 => Self::Bn128Mul,
             0x08 => Self::Bn128Pairing,
             0x09 => Self::Blake2F,
-            _ => unreachable!("precompile contracts only from 0x01 to 0x09"),
+            0x0a => Self::PointEvaluation,
+            _ => unreachable!("precompile contracts only from 0x01 to 0x0a"),
         }
     }
 }
@@ -112,6 +194,7 @@ impl PrecompileCalls {
             Self::Bn128Mul => GasCost::PRECOMPILE_BN256MUL,
             Self::Bn128Pairing => GasCost::PRECOMPILE_BN256PAIRING,
             Self::Blake2F => GasCost::PRECOMPILE_BLAKE2F,
+            Self::PointEvaluation => GasCost::PRECOMPILE_POINT_EVALUATION,
         }
     }
 
@@ -128,6 +211,340 @@ impl PrecompileCalls {
             _ => None,
         }
     }
+
+    /// Number of call data bytes taken by the precompile call.  MODEXP reads
+    /// the length of its input from the header at the start of `call_data`,
+    /// which doesn't need to hold more than the header.
+    pub fn input_len_of(&self, call_data_length: usize, call_data: &[u8]) -> usize {
+        let input_len = match self {
+            Self::Modexp => modexp_input_lens(call_data)
+                .iter()
+                .fold(MODEXP_HEADER_LEN, |acc, len| {
+                    acc.saturating_add(saturating_usize(*len))
+                }),
+            _ => self.input_len().unwrap_or(call_data_length),
+        };
+        min(input_len, call_data_length)
+    }
+}
+
+fn saturating_usize(value: Word) -> usize {
+    if value > Word::from(usize::MAX) {
+        usize::MAX
+    } else {
+        value.as_usize()
+    }
+}
+
+/// Returns the lengths of the base, exponent and modulus declared in the
+/// header of a MODEXP input, which is right padded with zeroes.
+pub fn modexp_input_lens(input: &[u8]) -> [Word; 3] {
+    let mut header = input[..min(input.len(), MODEXP_HEADER_LEN)].to_vec();
+    header.resize(MODEXP_HEADER_LEN, 0);
+    [0, 1, 2].map(|i| Word::from_big_endian(&header[i * 32..(i + 1) * 32]))
+}
+
+/// Gas cost of a MODEXP call, as defined in EIP-2565.
+pub fn modexp_gas_cost(input: &[u8]) -> u64 {
+    let [base_len, exp_len, mod_len] = modexp_input_lens(input);
+
+    let words = max(base_len, mod_len).saturating_add(Word::from(7)) / 8;
+    let mult_complexity = words.saturating_mul(words);
+
+    // Only the first 32 bytes of the exponent are taken into account.
+    let exp_head = {
+        let offset = MODEXP_HEADER_LEN.saturating_add(saturating_usize(base_len));
+        let len = min(exp_len, Word::from(32)).as_usize();
+        let mut bytes = input[min(offset, input.len())..].to_vec();
+        bytes.resize(len, 0);
+        Word::from_big_endian(&bytes[..len])
+    };
+    let exp_head_bits = Word::from(exp_head.bits().saturating_sub(1));
+    let iteration_count = if exp_len <= Word::from(32) {
+        exp_head_bits
+    } else {
+        (exp_len - Word::from(32))
+            .saturating_mul(Word::from(8))
+            .saturating_add(exp_head_bits)
+    };
+    let iteration_count = max(iteration_count, Word::one());
+
+    let gas_cost = mult_complexity.saturating_mul(iteration_count) / 3;
+    if gas_cost > Word::from(u64::MAX) {
+        u64::MAX
+    } else {
+        max(gas_cost.as_u64(), GasCost::PRECOMPILE_MODEXP_MIN)
+    }
+}
+
+/// Maximum number of ECADD, ECMUL and ECPAIRING calls verified by the ECC
+/// circuit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrecompileEcParams {
+    /// Maximum number of ECADD calls.
+    pub ec_add: usize,
+    /// Maximum number of ECMUL calls.
+    pub ec_mul: usize,
+    /// Maximum number of ECPAIRING calls.
+    pub ec_pairing: usize,
+}
+
+/// Auxiliary data for Ecrecover
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EcrecoverAuxData {
+    /// Keccak hash of the message being signed.
+    pub msg_hash: Word,
+    /// v-component of signature.
+    pub sig_v: Word,
+    /// r-component of signature.
+    pub sig_r: Word,
+    /// s-component of signature.
+    pub sig_s: Word,
+    /// Address that was recovered, or zero if the signature is invalid.
+    pub recovered_addr: Address,
+}
+
+impl EcrecoverAuxData {
+    /// Create a new instance of ecrecover auxiliary data.  The input is right
+    /// padded with zeroes to 128 bytes, as done by the precompile.
+    pub fn new(input: &[u8], output: &[u8]) -> Self {
+        let mut resized_input = input.to_vec();
+        resized_input.resize(128, 0u8);
+        let recovered_addr = if output.is_empty() {
+            Address::zero()
+        } else {
+            assert_eq!(output.len(), 32);
+            Address::from_slice(&output[12..])
+        };
+
+        Self {
+            msg_hash: Word::from_big_endian(&resized_input[0x00..0x20]),
+            sig_v: Word::from_big_endian(&resized_input[0x20..0x40]),
+            sig_r: Word::from_big_endian(&resized_input[0x40..0x60]),
+            sig_s: Word::from_big_endian(&resized_input[0x60..0x80]),
+            recovered_addr,
+        }
+    }
+
+    /// Recovery id of the signature if `v` is valid, i.e. 27 or 28.
+    pub fn recovery_id(&self) -> Option<u8> {
+        let sig_v = self.sig_v.to_be_bytes();
+        let is_valid = sig_v[0x00..0x1f].iter().all(|&b| b == 0) && matches!(sig_v[0x1f], 27 | 28);
+        is_valid.then(|| sig_v[0x1f] - 27)
+    }
+}
+
+/// Auxiliary data for Sha256
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Sha256AuxData {
+    /// Input bytes to the precompile call.
+    pub input_bytes: Vec<u8>,
+    /// Output bytes of the precompile call, i.e. the 32-bytes digest.
+    pub output_bytes: Vec<u8>,
+}
+
+/// Auxiliary data for Ripemd160
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Ripemd160AuxData {
+    /// Input bytes to the precompile call.
+    pub input_bytes: Vec<u8>,
+    /// Output bytes of the precompile call, i.e. the 20-bytes digest left
+    /// padded with zeroes to 32 bytes.
+    pub output_bytes: Vec<u8>,
+}
+
+/// Auxiliary data for Modexp
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ModExpAuxData {
+    /// Lengths of the base, exponent and modulus declared in the input header.
+    pub input_lens: [usize; 3],
+    /// The base, exponent and modulus.
+    pub inputs: [Word; 3],
+    /// Result of the call, i.e. `base ^ exponent % modulus`, or zero when the
+    /// call runs out of gas.
+    pub output: Word,
+    /// Input bytes taken by the precompile call.
+    pub input_bytes: Vec<u8>,
+}
+
+impl ModExpAuxData {
+    /// Create a new instance of modexp auxiliary data, or `None` when a length
+    /// exceeds [`MODEXP_SIZE_LIMIT`].  The input is right padded with zeroes
+    /// up to the declared lengths, as done by the precompile.
+    pub fn new(input: &[u8], output: &[u8]) -> Option<Self> {
+        let input_lens = modexp_input_lens(input);
+        if input_lens
+            .iter()
+            .any(|len| *len > Word::from(MODEXP_SIZE_LIMIT))
+        {
+            return None;
+        }
+        let input_lens = input_lens.map(|len| len.as_usize());
+
+        let mut padded_input = input.to_vec();
+        padded_input.resize(MODEXP_HEADER_LEN + input_lens.iter().sum::<usize>(), 0);
+        let mut offset = MODEXP_HEADER_LEN;
+        let inputs = input_lens.map(|len| {
+            offset += len;
+            Word::from_big_endian(&padded_input[offset - len..offset])
+        });
+
+        Some(Self {
+            input_lens,
+            inputs,
+            output: Word::from_big_endian(output),
+            input_bytes: input.to_vec(),
+        })
+    }
+}
+
+/// Auxiliary data for EcAdd, i.e. `P + Q = R`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EcAddAuxData {
+    /// x-coordinate of the first point.
+    pub p_x: Word,
+    /// y-coordinate of the first point.
+    pub p_y: Word,
+    /// x-coordinate of the second point.
+    pub q_x: Word,
+    /// y-coordinate of the second point.
+    pub q_y: Word,
+    /// x-coordinate of the sum, or zero if the call fails.
+    pub r_x: Word,
+    /// y-coordinate of the sum, or zero if the call fails.
+    pub r_y: Word,
+}
+
+impl EcAddAuxData {
+    /// Create a new instance of ecAdd auxiliary data.  The input is right
+    /// padded with zeroes to 128 bytes, as done by the precompile.
+    pub fn new(input: &[u8], output: &[u8]) -> Self {
+        let mut resized_input = input.to_vec();
+        resized_input.resize(128, 0u8);
+        let mut resized_output = output.to_vec();
+        resized_output.resize(64, 0u8);
+
+        Self {
+            p_x: Word::from_big_endian(&resized_input[0x00..0x20]),
+            p_y: Word::from_big_endian(&resized_input[0x20..0x40]),
+            q_x: Word::from_big_endian(&resized_input[0x40..0x60]),
+            q_y: Word::from_big_endian(&resized_input[0x60..0x80]),
+            r_x: Word::from_big_endian(&resized_output[0x00..0x20]),
+            r_y: Word::from_big_endian(&resized_output[0x20..0x40]),
+        }
+    }
+}
+
+/// Auxiliary data for EcMul, i.e. `s * P = R`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EcMulAuxData {
+    /// x-coordinate of the point.
+    pub p_x: Word,
+    /// y-coordinate of the point.
+    pub p_y: Word,
+    /// Scalar as given in the input, which is not reduced by the curve order.
+    pub s_raw: Word,
+    /// x-coordinate of the product, or zero if the call fails.
+    pub r_x: Word,
+    /// y-coordinate of the product, or zero if the call fails.
+    pub r_y: Word,
+}
+
+impl EcMulAuxData {
+    /// Create a new instance of ecMul auxiliary data.  The input is right
+    /// padded with zeroes to 96 bytes, as done by the precompile.
+    pub fn new(input: &[u8], output: &[u8]) -> Self {
+        let mut resized_input = input.to_vec();
+        resized_input.resize(96, 0u8);
+        let mut resized_output = output.to_vec();
+        resized_output.resize(64, 0u8);
+
+        Self {
+            p_x: Word::from_big_endian(&resized_input[0x00..0x20]),
+            p_y: Word::from_big_endian(&resized_input[0x20..0x40]),
+            s_raw: Word::from_big_endian(&resized_input[0x40..0x60]),
+            r_x: Word::from_big_endian(&resized_output[0x00..0x20]),
+            r_y: Word::from_big_endian(&resized_output[0x20..0x40]),
+        }
+    }
+
+    /// The scalar reduced by the order of the curve.
+    pub fn s(&self) -> Word {
+        self.s_raw % *BN254_FR_MODULUS
+    }
+}
+
+/// Auxiliary data for EcPairing
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EcPairingAuxData {
+    /// Input bytes to the precompile call, i.e. the pairs of G1 and G2 points.
+    pub input_bytes: Vec<u8>,
+    /// Result of the pairing check, 1 if the product of the pairings is the
+    /// identity, 0 otherwise or if the call fails.
+    pub output: Word,
+}
+
+/// Auxiliary data for Blake2F
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Blake2fAuxData {
+    /// Input bytes to the precompile call.
+    pub input_bytes: Vec<u8>,
+    /// Output bytes of the precompile call, empty if the call fails.
+    pub output_bytes: Vec<u8>,
+    /// The compressed state of a [`N_BYTES_BLAKE2F_INPUT`] bytes input with a
+    /// valid final block flag, even if the call runs out of gas, or empty
+    /// otherwise.
+    pub compressed: Vec<u8>,
+}
+
+impl Blake2fAuxData {
+    /// Number of rounds of the compression, or zero for an input of the wrong
+    /// length.
+    pub fn rounds(&self) -> u32 {
+        if self.input_bytes.len() == N_BYTES_BLAKE2F_INPUT {
+            u32::from_be_bytes(self.input_bytes[..4].try_into().unwrap())
+        } else {
+            0
+        }
+    }
+}
+
+/// Auxiliary data for the KZG point evaluation
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PointEvaluationAuxData {
+    /// Input bytes to the precompile call.
+    pub input_bytes: Vec<u8>,
+    /// Output bytes of the precompile call, empty if the call fails.
+    pub output_bytes: Vec<u8>,
+}
+
+/// Auxiliary data attached to an internal state for precompile verification.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PrecompileAuxData {
+    /// Ecrecover.
+    Ecrecover(EcrecoverAuxData),
+    /// Sha256.
+    Sha256(Sha256AuxData),
+    /// Ripemd160.
+    Ripemd160(Ripemd160AuxData),
+    /// Modexp.
+    Modexp(ModExpAuxData),
+    /// EcAdd.
+    EcAdd(EcAddAuxData),
+    /// EcMul.
+    EcMul(EcMulAuxData),
+    /// EcPairing.
+    EcPairing(EcPairingAuxData),
+    /// Blake2F.
+    Blake2F(Blake2fAuxData),
+    /// KZG point evaluation.
+    PointEvaluation(PointEvaluationAuxData),
+}
+
+impl Default for PrecompileAuxData {
+    fn default() -> Self {
+        Self::Ecrecover(EcrecoverAuxData::default())
+    }
 }
 
 /// Precompile call args
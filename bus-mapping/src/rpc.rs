@@ -3,12 +3,13 @@
 
 use crate::Error;
 use eth_types::{
-    Address, Block, Bytes, EIP1186ProofResponse, GethExecTrace, Hash, ResultGethExecTraces,
-    Transaction, Word, U64,
+    Address, Block, Bytes, EIP1186ProofResponse, GethExecTrace, GethPrestateAccount, Hash,
+    ResultGethExecTraces, ResultGethPrestateTraces, Transaction, Word, U64,
 };
 pub use ethers_core::types::BlockNumber;
 use ethers_providers::JsonRpcClient;
 use serde::Serialize;
+use std::collections::HashMap;
 
 /// Serialize a type.
 ///
@@ -47,6 +48,21 @@ impl Default for GethLoggerConfig {
     }
 }
 
+#[derive(Serialize)]
+#[doc(hidden)]
+pub(crate) struct GethPrestateTracerConfig {
+    /// name of the tracer
+    tracer: &'static str,
+}
+
+impl Default for GethPrestateTracerConfig {
+    fn default() -> Self {
+        Self {
+            tracer: "prestateTracer",
+        }
+    }
+}
+
 /// Placeholder structure designed to contain the methods that the BusMapping
 /// needs in order to enable Geth queries.
 pub struct GethClient<P: JsonRpcClient>(pub P);
@@ -132,6 +148,23 @@ impl<P: JsonRpcClient> GethClient<P> {
         Ok(resp.0.into_iter().map(|step| step.result).collect())
     }
 
+    /// Calls `debug_traceBlockByNumber` with the `prestateTracer` via JSON-RPC
+    /// returning, for each transaction of the block, the state of the accounts
+    /// it accesses before it is executed.
+    pub async fn trace_block_prestate_by_number(
+        &self,
+        block_num: BlockNumber,
+    ) -> Result<Vec<HashMap<Address, GethPrestateAccount>>, Error> {
+        let num = serialize(&block_num);
+        let cfg = serialize(&GethPrestateTracerConfig::default());
+        let resp: ResultGethPrestateTraces = self
+            .0
+            .request("debug_traceBlockByNumber", [num, cfg])
+            .await
+            .map_err(|e| Error::JSONRpcError(e.into()))?;
+        Ok(resp.0.into_iter().map(|prestate| prestate.result).collect())
+    }
+
     /// Calls `eth_getCode` via JSON-RPC returning a contract code
     pub async fn get_code(
         &self,
@@ -134,6 +134,11 @@ pub struct StateDB {
     // Fields with transaction lifespan, will be clear in `clear_access_list_and_refund`.
     access_list_account: HashSet<Address>,
     access_list_account_storage: HashSet<(Address, U256)>,
+    // Accounts created in the current transaction. Under EIP-6780, `SELFDESTRUCT` only deletes
+    // the account when it's in this set.
+    created_account: HashSet<Address>,
+    // Transient storage (EIP-1153), which is discarded at the end of every transaction.
+    transient_storage: HashMap<(Address, Word), Word>,
     // `dirty_storage` contains writes during current transaction.
     // When current transaction finishes, `dirty_storage` will be committed into `state`.
     // The reason why we need this is that EVM needs committed state, namely
@@ -227,6 +232,20 @@ impl StateDB {
         self.dirty_storage.insert((*addr, *key), *value);
     }
 
+    /// Get the transient storage value at `addr` and `key`. Returns false and a
+    /// zero [`Word`] when the `key` wasn't written in the current transaction.
+    pub fn get_transient_storage(&self, addr: &Address, key: &Word) -> (bool, &Word) {
+        match self.transient_storage.get(&(*addr, *key)) {
+            Some(v) => (true, v),
+            None => (false, &VALUE_ZERO),
+        }
+    }
+
+    /// Set transient storage value at `addr` and `key`.
+    pub fn set_transient_storage(&mut self, addr: &Address, key: &Word, value: &Word) {
+        self.transient_storage.insert((*addr, *key), *value);
+    }
+
     /// Get nonce of account with `addr`.
     pub fn get_nonce(&self, addr: &Address) -> u64 {
         let (_, account) = self.get_account(addr);
@@ -281,6 +300,23 @@ impl StateDB {
         debug_assert!(exist);
     }
 
+    /// Check whether `addr` was created in the current transaction.
+    pub fn check_account_created(&self, addr: &Address) -> bool {
+        self.created_account.contains(addr)
+    }
+
+    /// Mark `addr` as created in the current transaction. Returns `true` if it
+    /// was not marked before.
+    pub fn add_account_to_created(&mut self, addr: Address) -> bool {
+        self.created_account.insert(addr)
+    }
+
+    /// Unmark `addr` as created in the current transaction.
+    pub fn remove_account_from_created(&mut self, addr: &Address) {
+        let exist = self.created_account.remove(addr);
+        debug_assert!(exist);
+    }
+
     /// Set account as self destructed.
     pub fn destruct_account(&mut self, addr: Address) {
         self.destructed_account.insert(addr);
@@ -302,6 +338,8 @@ impl StateDB {
     pub fn commit_tx(&mut self) {
         self.access_list_account = HashSet::new();
         self.access_list_account_storage = HashSet::new();
+        self.created_account = HashSet::new();
+        self.transient_storage = HashMap::new();
         for ((addr, key), value) in self.dirty_storage.clone() {
             let (_, ptr) = self.get_storage_mut(&addr, &key);
             *ptr = value;
@@ -311,6 +349,7 @@ impl StateDB {
             let (_, account) = self.get_account_mut(&addr);
             *account = ACCOUNT_ZERO.clone();
         }
+        self.destructed_account = HashSet::new();
         self.refund = 0;
     }
 }
@@ -0,0 +1,203 @@
+//! A witness bundle holds all the data queried from a node to generate the
+//! circuit inputs of a block, so that it can be written once and replayed into
+//! the [`CircuitInputBuilder`] without network access.
+
+use crate::{
+    circuit_input_builder::{build_state_code_db, Block, CircuitInputBuilder, FixedCParams},
+    error::Error,
+};
+use eth_types::{geth_types, Address, Bytes, EIP1186ProofResponse, GethExecTrace, Word};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+};
+
+/// Version of the witness bundle format.  It must be increased on every
+/// change of the format, so that outdated bundles are rejected.
+pub const WITNESS_BUNDLE_VERSION: u64 = 1;
+
+/// Data needed to generate the circuit inputs of a block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WitnessBundle {
+    /// Version of the format, [`WITNESS_BUNDLE_VERSION`]
+    pub version: u64,
+    /// chain id
+    pub chain_id: Word,
+    /// Block from geth
+    pub eth_block: eth_types::Block<eth_types::Transaction>,
+    /// Execution Trace from geth
+    pub geth_traces: Vec<GethExecTrace>,
+    /// Proofs of the accounts and storage slots accessed by the block, in the
+    /// state before the block
+    pub proofs: Vec<EIP1186ProofResponse>,
+    /// Codes of the accounts accessed by the block
+    pub codes: HashMap<Address, Bytes>,
+    /// history hashes contains most recent 256 block hashes in history, where
+    /// the latest one is at history_hashes[history_hashes.len() - 1].
+    pub history_hashes: Vec<Word>,
+    /// State root before the block
+    pub prev_state_root: Word,
+    /// Circuits setup parameters
+    pub circuits_params: FixedCParams,
+}
+
+impl WitnessBundle {
+    /// Write the bundle as JSON.
+    pub fn write<W: Write>(&self, writer: W) -> Result<(), Error> {
+        serde_json::to_writer(writer, self).map_err(Error::SerdeError)
+    }
+
+    /// Read a bundle written by [`WitnessBundle::write`].  Fails if the bundle
+    /// was written with another version of the format.
+    pub fn read<R: Read>(reader: R) -> Result<Self, Error> {
+        let bundle: serde_json::Value =
+            serde_json::from_reader(reader).map_err(Error::SerdeError)?;
+        // Check the version before the format, which may have changed.
+        let version = bundle["version"].as_u64().unwrap_or_default();
+        if version != WITNESS_BUNDLE_VERSION {
+            return Err(Error::WitnessBundleVersion(version));
+        }
+        serde_json::from_value(bundle).map_err(Error::SerdeError)
+    }
+
+    /// Build the partial StateDB and generate the circuit inputs of the block,
+    /// like the steps 4 and 5 of the `BuilderClient`.
+    pub fn gen_inputs(&self) -> Result<CircuitInputBuilder<FixedCParams>, Error> {
+        let codes = self
+            .codes
+            .iter()
+            .map(|(address, code)| (*address, code.to_vec()))
+            .collect();
+        let (sdb, code_db) = build_state_code_db(self.proofs.clone(), codes);
+        let block = Block::new(
+            self.chain_id,
+            geth_types::block_hardfork(&self.eth_block),
+            self.history_hashes.clone(),
+            self.prev_state_root,
+            &self.eth_block,
+        )?;
+        let mut builder = CircuitInputBuilder::new(sdb, code_db, block, self.circuits_params);
+        builder.handle_block(&self.eth_block, &self.geth_traces)?;
+        Ok(builder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{circuit_input_builder::get_state_accesses, state_db::CodeDB};
+    use eth_types::{bytecode, geth_types::GethData, StorageProof, U64};
+    use mock::test_ctx::{helpers::*, TestContext};
+    use pretty_assertions::assert_eq;
+
+    fn witness_bundle() -> WitnessBundle {
+        let code = bytecode! {
+            PUSH1(0x2a)
+            PUSH1(0x00)
+            SSTORE
+            STOP
+        };
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block,
+        )
+        .unwrap()
+        .into();
+
+        // Mock the `eth_getProof` responses of the accessed accounts.
+        let access_set = get_state_accesses(&block.eth_block, &block.geth_traces).unwrap();
+        let proofs = access_set
+            .state
+            .iter()
+            .map(|(address, keys)| {
+                let account = block
+                    .accounts
+                    .iter()
+                    .find(|account| account.address == *address)
+                    .cloned()
+                    .unwrap_or_default();
+                EIP1186ProofResponse {
+                    address: *address,
+                    balance: account.balance,
+                    code_hash: CodeDB::hash(&account.code),
+                    nonce: U64::from(account.nonce.as_u64()),
+                    storage_proof: keys
+                        .iter()
+                        .map(|key| StorageProof {
+                            key: *key,
+                            value: account.storage.get(key).cloned().unwrap_or_default(),
+                            proof: vec![],
+                        })
+                        .collect(),
+                    ..Default::default()
+                }
+            })
+            .collect();
+        let codes = block
+            .accounts
+            .iter()
+            .map(|account| (account.address, account.code.clone()))
+            .collect();
+
+        WitnessBundle {
+            version: WITNESS_BUNDLE_VERSION,
+            chain_id: block.chain_id,
+            eth_block: block.eth_block,
+            geth_traces: block.geth_traces,
+            proofs,
+            codes,
+            history_hashes: block.history_hashes,
+            prev_state_root: Word::zero(),
+            circuits_params: FixedCParams {
+                max_rws: 256,
+                max_blocks: 1,
+                max_txs: 1,
+                max_withdrawals: 1,
+                max_calldata: 256,
+                max_bytecode: 256,
+                max_copy_rows: 256,
+                max_evm_rows: 0,
+                max_exp_steps: 256,
+                max_keccak_rows: 0,
+                max_ecrecover: 0,
+                max_sha256_rows: 0,
+                max_ripemd160_rows: 0,
+                max_modexp: 0,
+                max_ec_ops: Default::default(),
+                max_blake2f_rows: 0,
+                max_point_evaluations: 0,
+                max_log_bytes: 256,
+                max_mpt_rows: 0,
+            },
+        }
+    }
+
+    #[test]
+    fn witness_bundle_replay() {
+        let bundle = witness_bundle();
+        let mut buffer = Vec::new();
+        bundle.write(&mut buffer).unwrap();
+
+        let read_bundle = WitnessBundle::read(buffer.as_slice()).unwrap();
+        assert_eq!(read_bundle, bundle);
+        read_bundle.gen_inputs().unwrap();
+    }
+
+    #[test]
+    fn witness_bundle_unsupported_version() {
+        let bundle = WitnessBundle {
+            version: WITNESS_BUNDLE_VERSION + 1,
+            ..witness_bundle()
+        };
+        let mut buffer = Vec::new();
+        bundle.write(&mut buffer).unwrap();
+
+        assert!(matches!(
+            WitnessBundle::read(buffer.as_slice()),
+            Err(Error::WitnessBundleVersion(version)) if version == WITNESS_BUNDLE_VERSION + 1
+        ));
+    }
+}
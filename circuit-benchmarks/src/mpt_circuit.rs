@@ -53,6 +53,7 @@ mod tests {
             keccak_data,
             degree: degree as usize,
             disable_preimage_check: false,
+            max_rows: 0,
             _marker: PhantomData,
         };
 
@@ -2,7 +2,10 @@
 #[cfg(test)]
 mod tests {
     use ark_std::{end_timer, start_timer};
-    use eth_types::Word;
+    use eth_types::{
+        geth_types::{Receipt, LEGACY_TX_TYPE},
+        Word,
+    };
     use halo2_proofs::{
         halo2curves::bn256::{Bn256, Fr, G1Affine},
         plonk::{create_proof, keygen_pk, keygen_vk, verify_proof},
@@ -36,6 +39,7 @@ mod tests {
         const MAX_TXS: usize = 10;
         const MAX_WITHDRAWALS: usize = 10;
         const MAX_CALLDATA: usize = 128;
+        const MAX_LOG_BYTES: usize = 128;
 
         let degree: u32 = var("DEGREE")
             .unwrap_or("17".to_string())
@@ -43,7 +47,13 @@ mod tests {
             .expect("Cannot parse DEGREE env var as u32");
 
         let public_data = generate_publicdata(MAX_TXS);
-        let circuit = PiCircuit::<Fr>::new(MAX_TXS, MAX_WITHDRAWALS, MAX_CALLDATA, public_data);
+        let circuit = PiCircuit::<Fr>::new(
+            MAX_TXS,
+            MAX_WITHDRAWALS,
+            MAX_CALLDATA,
+            MAX_LOG_BYTES,
+            public_data,
+        );
         let public_inputs = circuit.instance();
         let instance: Vec<&[Fr]> = public_inputs.iter().map(|input| &input[..]).collect();
         let instances = &[&instance[..]];
@@ -119,10 +129,19 @@ mod tests {
         ))
         .take(max_txs)
         .collect_vec();
+        let receipts = (1..=max_txs as u64)
+            .map(|i| Receipt {
+                tx_type: LEGACY_TX_TYPE,
+                status: true,
+                cumulative_gas_used: 21000 * i,
+                logs: vec![],
+            })
+            .collect_vec();
 
         PublicData {
             chain_id: Word::from(1337),
             transactions,
+            receipts,
             ..Default::default()
         }
     }
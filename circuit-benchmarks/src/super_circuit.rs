@@ -3,7 +3,7 @@
 #[cfg(test)]
 mod tests {
     use ark_std::{end_timer, start_timer};
-    use bus_mapping::circuit_input_builder::FixedCParams;
+    use bus_mapping::{circuit_input_builder::FixedCParams, precompile::PrecompileEcParams};
     use eth_types::{address, bytecode, geth_types::GethData, Word};
     use ethers_signers::{LocalWallet, Signer};
     use halo2_proofs::{
@@ -72,7 +72,7 @@ mod tests {
                     .to(accs[0].address)
                     .gas(Word::from(1_000_000u64));
             },
-            |block, _tx| block.number(0xcafeu64),
+            |block, _tx| block.number(0xcafeu64).author(addr_b),
         )
         .unwrap()
         .into();
@@ -80,6 +80,7 @@ mod tests {
         block.sign(&wallets);
 
         let circuits_params = FixedCParams {
+            max_blocks: 1,
             max_txs: 1,
             max_withdrawals: 1,
             max_calldata: 32,
@@ -89,6 +90,15 @@ mod tests {
             max_bytecode: 512,
             max_evm_rows: 0,
             max_keccak_rows: 0,
+            max_ecrecover: 0,
+            max_sha256_rows: 0,
+            max_ripemd160_rows: 0,
+            max_modexp: 0,
+            max_ec_ops: PrecompileEcParams::default(),
+            max_blake2f_rows: 0,
+            max_point_evaluations: 0,
+            max_log_bytes: 512,
+            max_mpt_rows: 0,
         };
         let (_, circuit, instance, _) =
             SuperCircuit::build(block, circuits_params, Fr::from(0x100)).unwrap();
@@ -500,6 +500,9 @@ impl_other_opcodes! {
     (op_mstore8, MSTORE8, offset: O, value: V),
     (op_sload, SLOAD, offset: O),
     (op_sstore, SSTORE, offset: O, value: V),
+    (op_tload, TLOAD, offset: O),
+    (op_tstore, TSTORE, offset: O, value: V),
+    (op_mcopy, MCOPY, dest_offset: D, offset: B, size: C),
     (op_jump, JUMP, counter: C),
     (op_jumpi, JUMPI, counter: C), // branch not included
     (op_pc, PC),
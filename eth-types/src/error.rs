@@ -33,6 +33,8 @@ pub enum Error {
     WordToMemAddr,
     /// Signature parsing error.
     Signature(libsecp256k1::Error),
+    /// Transaction type (EIP-2718) which can't be encoded for signing.
+    UnsupportedTxType(u64),
 }
 
 impl From<libsecp256k1::Error> for Error {
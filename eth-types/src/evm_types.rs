@@ -1,65 +1,44 @@
 //! Evm types needed for parsing instruction sets as well
 
-// use serde::{Deserialize, Serialize};
-// use std::fmt;
-
 pub mod gas_utils;
 pub mod memory;
 pub mod opcode_ids;
 pub mod stack;
 pub mod storage;
 
+use crate::Word;
 pub use memory::{Memory, MemoryAddress};
 pub use opcode_ids::OpcodeId;
+use serde::{Deserialize, Serialize};
 pub use stack::{Stack, StackAddress};
 pub use storage::Storage;
+use strum_macros::EnumIter;
 
 /// According to EIP-3541, disallow new code starting with 0xEF to be deployed.
 pub const INVALID_INIT_CODE_FIRST_BYTE: u8 = 0xef;
-/// Once per word of the init code when creating a contract.
+/// Once per word of the init code when creating a contract, since EIP-3860
+/// (Shanghai).
 pub const INIT_CODE_WORD_GAS: u64 = 2;
-/// Quotient for max refund of gas used
+/// Quotient for max refund of gas used, since EIP-3529 (London).
 pub const MAX_REFUND_QUOTIENT_OF_GAS_USED: usize = 5;
 /// Gas stipend when CALL or CALLCODE is attached with value.
 pub const GAS_STIPEND_CALL_WITH_VALUE: u64 = 2300;
+/// Blob gas consumed by each blob attached to a transaction (EIP-4844).
+pub const GAS_PER_BLOB: u64 = 1 << 17;
+/// Maximum number of blobs attached to the transactions of a block (EIP-4844).
+pub const MAX_BLOBS_PER_BLOCK: usize = 6;
+/// Minimum base fee per unit of blob gas (EIP-4844).
+pub const MIN_BLOB_BASE_FEE: u64 = 1;
+/// Controls the maximum rate of change of the blob base fee (EIP-4844).
+pub const BLOB_BASE_FEE_UPDATE_FRACTION: u64 = 3338477;
 
 /// This constant ((2^32 - 1) * 32) is the highest number that can be used without overflowing the
 /// square operation of gas calculation.
 /// <https://github.com/ethereum/go-ethereum/blob/e6b6a8b738069ad0579f6798ee59fde93ed13b43/core/vm/gas_table.go#L38>
 pub const MAX_EXPANDED_MEMORY_ADDRESS: u64 = 0x1FFFFFFFE0;
 
-#[cfg(feature = "shanghai")]
-mod gas_create {
-    // For EIP-3860, there are 2 special gas cost constraints in geth
-    // [gasCreate2Eip3860](https://github.com/ethereum/go-ethereum/blob/eb83e7c54021573eaceb14236af3a7a8c64f6027/core/vm/gas_table.go#L321)
-    // (similar for CREATE).
-    // 1. size <= 49152 (MaxInitCodeSize)
-    // 2. gasCost = memoryGasCost + (2 + 6) * ((size + 31) / 32) should not
-    //    overflow for Uint64.
-    // No need to constrain the second condition, since the maximum gas cost
-    // cannot overflow for Uint64 (36028809887100925 calculated by
-    // `memorySize = 0x1FFFFFFFE0` and `size = 49152`) if the first condition is
-    // satisfied.
-
-    /// Maximum init code size to permit in a creation transaction and create instructions.
-    pub const MAX_INIT_CODE_SIZE: u64 = 2 * super::MAX_CODE_SIZE;
-    /// Once per word of the init code when creating a contract.
-    pub const INIT_CODE_WORD_GAS: u64 = 2;
-    /// Gas per code word for CREATE.
-    pub const CREATE_GAS_PER_CODE_WORD: u64 = INIT_CODE_WORD_GAS;
-    /// Gas per code word for CREATE2.
-    pub const CREATE2_GAS_PER_CODE_WORD: u64 = INIT_CODE_WORD_GAS + super::GasCost::COPY_SHA3.0;
-}
-#[cfg(not(feature = "shanghai"))]
-mod gas_create {
-    /// Maximum init code size (0x1FFFFFFFE0) if not EIP-3860.
-    pub use super::MAX_EXPANDED_MEMORY_ADDRESS as MAX_INIT_CODE_SIZE;
-    /// Gas per code word for CREATE if not EIP-3860.
-    pub const CREATE_GAS_PER_CODE_WORD: u64 = 0;
-    /// Gas per code word for CREATE2 if not EIP-3860.
-    pub const CREATE2_GAS_PER_CODE_WORD: u64 = super::GasCost::COPY_SHA3;
-}
-pub use gas_create::*;
+/// Maximum size of the code of a contract, as defined in EIP-170.
+pub const MAX_CODE_SIZE: u64 = 0x6000;
 
 /// Defines the gas consumption.
 pub struct GasCost;
@@ -154,7 +133,185 @@ impl GasCost {
     pub const PRECOMPILE_MODEXP_MIN: u64 = 200;
     /// Base gas cost for precompile call: BLAKE2F
     pub const PRECOMPILE_BLAKE2F: u64 = 0;
+    /// Gas cost per round for precompile call: BLAKE2F
+    pub const PRECOMPILE_BLAKE2F_PER_ROUND: u64 = 1;
+    /// Gas cost for precompile call: KZG point evaluation (EIP-4844)
+    pub const PRECOMPILE_POINT_EVALUATION: u64 = 50000;
+}
+
+/// This constant is used to iterate through precompile contract addresses 0x01 to 0x0a, the
+/// precompiles of the latest supported hardfork.
+pub const PRECOMPILE_COUNT: u64 = 10;
+
+/// Hardforks supported by the circuits, ordered by activation. The hardfork of
+/// a block selects its set of valid opcodes, its gas schedule and its set of
+/// precompiled contracts.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    EnumIter,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Hardfork {
+    /// Berlin
+    Berlin,
+    /// London, which adds BASEFEE (EIP-3198) and reduces the gas refunds
+    /// (EIP-3529).
+    London,
+    /// Shanghai, which adds PUSH0 (EIP-3855) and limits and meters the init
+    /// code (EIP-3860).
+    Shanghai,
+    /// Cancun, which adds TLOAD/TSTORE (EIP-1153), MCOPY (EIP-5656),
+    /// BLOBHASH/BLOBBASEFEE (EIP-4844, EIP-7516) and the KZG point evaluation
+    /// precompile (EIP-4844).
+    #[default]
+    Cancun,
 }
 
-/// This constant is used to iterate through precompile contract addresses 0x01 to 0x09
-pub const PRECOMPILE_COUNT: u64 = 9;
+impl Hardfork {
+    /// Identifier of the hardfork, as exposed in the block table and the
+    /// public inputs.
+    pub fn as_u64(&self) -> u64 {
+        *self as u64
+    }
+
+    /// Returns `true` if the opcode is defined in the hardfork.
+    pub fn is_opcode_enabled(&self, opcode: &OpcodeId) -> bool {
+        opcode
+            .hardfork()
+            .map_or(false, |activation| activation <= *self)
+    }
+
+    /// Number of precompiled contracts, stored from address 0x01.
+    pub fn precompile_count(&self) -> u64 {
+        if *self >= Self::Cancun {
+            PRECOMPILE_COUNT
+        } else {
+            PRECOMPILE_COUNT - 1
+        }
+    }
+
+    /// Refund of a storage clear. EIP-3529 changed it to 4800 from 15000.
+    pub fn sstore_clears_schedule(&self) -> u64 {
+        if *self >= Self::London {
+            GasCost::SSTORE_CLEARS_SCHEDULE
+        } else {
+            15000
+        }
+    }
+
+    /// Quotient for max refund of gas used. EIP-3529 changed it to 5 from 2.
+    pub fn max_refund_quotient_of_gas_used(&self) -> u64 {
+        if *self >= Self::London {
+            MAX_REFUND_QUOTIENT_OF_GAS_USED as u64
+        } else {
+            2
+        }
+    }
+
+    /// Gas charged per word of the init code of a creation transaction, CREATE
+    /// and CREATE2, which is only charged since EIP-3860.
+    pub fn init_code_word_gas(&self) -> u64 {
+        if *self >= Self::Shanghai {
+            INIT_CODE_WORD_GAS
+        } else {
+            0
+        }
+    }
+
+    /// Maximum init code size of a creation transaction, CREATE and CREATE2.
+    ///
+    /// EIP-3860 limits it to 49152 (2 * MAX_CODE_SIZE). Before that the size is
+    /// only bounded by the maximum value of offset + size (0x1FFFFFFFE0). There
+    /// is a second condition in geth
+    /// [gasCreate2Eip3860](https://github.com/ethereum/go-ethereum/blob/eb83e7c54021573eaceb14236af3a7a8c64f6027/core/vm/gas_table.go#L321):
+    /// `memoryGasCost + (2 + 6) * ((size + 31) / 32)` should not overflow for
+    /// Uint64. No need to constrain it, since the maximum gas cost cannot
+    /// overflow for Uint64 (36028809887100925 calculated by
+    /// `memorySize = 0x1FFFFFFFE0` and `size = 49152`) if the size is within
+    /// the limit.
+    pub fn max_init_code_size(&self) -> u64 {
+        if *self >= Self::Shanghai {
+            2 * MAX_CODE_SIZE
+        } else {
+            MAX_EXPANDED_MEMORY_ADDRESS
+        }
+    }
+}
+
+/// Compute the base fee per unit of blob gas from the excess blob gas of a
+/// block header, as defined in EIP-4844:
+/// `fake_exponential(MIN_BLOB_BASE_FEE, excess_blob_gas, BLOB_BASE_FEE_UPDATE_FRACTION)`
+pub fn blob_base_fee(excess_blob_gas: u64) -> Word {
+    let denominator = Word::from(BLOB_BASE_FEE_UPDATE_FRACTION);
+    let numerator = Word::from(excess_blob_gas);
+    let mut output = Word::zero();
+    let mut numerator_accum = Word::from(MIN_BLOB_BASE_FEE) * denominator;
+    let mut i = 1u64;
+    while !numerator_accum.is_zero() {
+        output += numerator_accum;
+        numerator_accum = numerator_accum * numerator / (denominator * i);
+        i += 1;
+    }
+    output / denominator
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn blob_base_fee_from_excess_blob_gas() {
+        // Test vectors from the fake_exponential tests of go-ethereum's
+        // consensus/misc/eip4844 package.
+        for (excess_blob_gas, fee) in [(0, 1), (2314057, 1), (2314058, 2), (10 * 1024 * 1024, 23)] {
+            assert_eq!(blob_base_fee(excess_blob_gas), Word::from(fee));
+        }
+    }
+
+    #[test]
+    fn precompile_count_by_hardfork() {
+        assert_eq!(Hardfork::Shanghai.precompile_count(), 9);
+        assert_eq!(Hardfork::Cancun.precompile_count(), 10);
+        assert_eq!(Hardfork::default(), Hardfork::Cancun);
+    }
+
+    #[test]
+    fn opcodes_by_hardfork() {
+        for (opcode, activation) in [
+            (OpcodeId::ADD, Hardfork::Berlin),
+            (OpcodeId::BASEFEE, Hardfork::London),
+            (OpcodeId::PUSH0, Hardfork::Shanghai),
+            (OpcodeId::TLOAD, Hardfork::Cancun),
+            (OpcodeId::MCOPY, Hardfork::Cancun),
+            (OpcodeId::BLOBBASEFEE, Hardfork::Cancun),
+        ] {
+            for hardfork in Hardfork::iter() {
+                assert_eq!(hardfork.is_opcode_enabled(&opcode), hardfork >= activation);
+            }
+        }
+        assert!(!Hardfork::Cancun.is_opcode_enabled(&OpcodeId::INVALID(0xfe)));
+    }
+
+    #[test]
+    fn gas_schedule_by_hardfork() {
+        assert_eq!(Hardfork::Berlin.sstore_clears_schedule(), 15000);
+        assert_eq!(Hardfork::London.sstore_clears_schedule(), 4800);
+        assert_eq!(Hardfork::Berlin.max_refund_quotient_of_gas_used(), 2);
+        assert_eq!(Hardfork::London.max_refund_quotient_of_gas_used(), 5);
+        assert_eq!(Hardfork::London.init_code_word_gas(), 0);
+        assert_eq!(Hardfork::Shanghai.init_code_word_gas(), 2);
+        assert_eq!(Hardfork::London.max_init_code_size(), 0x1FFFFFFFE0);
+        assert_eq!(Hardfork::Shanghai.max_init_code_size(), 49152);
+    }
+}
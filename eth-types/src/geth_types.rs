@@ -1,23 +1,22 @@
 //! Types needed for generating Ethereum traces
 
 use crate::{
-    evm_types::{self, GasCost},
+    evm_types::{self, GasCost, Hardfork},
     keccak256,
-    sign_types::{biguint_to_32bytes_le, ct_option_ok_or, recover_pk, SignData, SECP256K1_Q},
+    sign_types::{ct_option_ok_or, msg_hash_to_scalar, recover_pk, SignData},
+    trie::ordered_trie_root,
     AccessList, Address, Block, Bytecode, Bytes, Error, GethExecTrace, Hash, ToBigEndian,
-    ToLittleEndian, ToWord, Word, U64,
+    ToLittleEndian, ToWord, Word, H256, H64, U64,
 };
 use ethers_core::{
-    types::{transaction::response, NameOrAddress, TransactionRequest},
-    utils::get_contract_address,
+    types::{transaction::response, Bloom, NameOrAddress, OtherFields, TransactionRequest},
+    utils::{get_contract_address, rlp::RlpStream},
 };
 use ethers_signers::{LocalWallet, Signer};
 use halo2_proofs::halo2curves::{group::ff::PrimeField, secp256k1};
-use num::Integer;
-use num_bigint::BigUint;
 use serde::{Serialize, Serializer};
 use serde_with::serde_as;
-use std::collections::HashMap;
+use std::{collections::HashMap, iter};
 
 /// Definition of all of the data related to an account.
 #[serde_as]
@@ -69,6 +68,80 @@ impl Account {
     }
 }
 
+/// Transaction type of legacy transactions.
+pub const LEGACY_TX_TYPE: u64 = 0;
+/// Transaction type of access list transactions (EIP-2930).
+pub const ACCESS_LIST_TX_TYPE: u64 = 1;
+/// Transaction type of dynamic fee transactions (EIP-1559).
+pub const DYNAMIC_FEE_TX_TYPE: u64 = 2;
+/// Transaction type of blob-carrying transactions (EIP-4844).
+pub const BLOB_TX_TYPE: u64 = 3;
+
+// Keys of the EIP-4844 fields, which ethers doesn't know about and keeps in the
+// `other` fields of a transaction or block.
+const MAX_FEE_PER_BLOB_GAS_KEY: &str = "maxFeePerBlobGas";
+const BLOB_VERSIONED_HASHES_KEY: &str = "blobVersionedHashes";
+const EXCESS_BLOB_GAS_KEY: &str = "excessBlobGas";
+const BLOB_GAS_USED_KEY: &str = "blobGasUsed";
+const PARENT_BEACON_BLOCK_ROOT_KEY: &str = "parentBeaconBlockRoot";
+
+fn get_other_field<T: serde::de::DeserializeOwned + Default>(other: &OtherFields, key: &str) -> T {
+    other
+        .get_deserialized(key)
+        .and_then(Result::ok)
+        .unwrap_or_default()
+}
+
+/// Generate the `other` fields of a transaction carrying the EIP-4844 blob
+/// fields.
+pub fn blob_tx_other_fields(
+    max_fee_per_blob_gas: Word,
+    blob_versioned_hashes: &[H256],
+) -> OtherFields {
+    let mut other = OtherFields::default();
+    other.insert(
+        MAX_FEE_PER_BLOB_GAS_KEY.to_string(),
+        serde_json::to_value(max_fee_per_blob_gas).expect("serialize max_fee_per_blob_gas"),
+    );
+    other.insert(
+        BLOB_VERSIONED_HASHES_KEY.to_string(),
+        serde_json::to_value(blob_versioned_hashes).expect("serialize blob_versioned_hashes"),
+    );
+    other
+}
+
+/// Return the EIP-4844 `excess_blob_gas` of a block header, which is zero
+/// for blocks before the Cancun upgrade.
+pub fn block_excess_blob_gas<TX>(block: &Block<TX>) -> U64 {
+    get_other_field(&block.other, EXCESS_BLOB_GAS_KEY)
+}
+
+/// Infer the hardfork of a block from the header fields introduced by each
+/// hardfork: the base fee (London), the withdrawals root (Shanghai) and the
+/// excess blob gas (Cancun).
+pub fn block_hardfork<TX>(block: &Block<TX>) -> Hardfork {
+    if block.base_fee_per_gas.is_none() {
+        Hardfork::Berlin
+    } else if block.withdrawals_root.is_none() {
+        Hardfork::London
+    } else if !block.other.contains_key(EXCESS_BLOB_GAS_KEY) {
+        Hardfork::Shanghai
+    } else {
+        Hardfork::Cancun
+    }
+}
+
+/// Generate the `other` fields of a block header carrying the EIP-4844
+/// `excess_blob_gas` field.
+pub fn blob_block_other_fields(excess_blob_gas: u64) -> OtherFields {
+    let mut other = OtherFields::default();
+    other.insert(
+        EXCESS_BLOB_GAS_KEY.to_string(),
+        serde_json::to_value(U64::from(excess_blob_gas)).expect("serialize excess_blob_gas"),
+    );
+    other
+}
+
 fn serde_account_storage<S: Serializer>(
     to_serialize: &HashMap<Word, Word>,
     serializer: S,
@@ -97,12 +170,20 @@ pub struct BlockConstants {
     pub gas_limit: Word,
     /// base fee
     pub base_fee: Word,
+    /// excess blob gas (EIP-4844)
+    /// U64 type is required to serialize into proper hex with 0x prefix
+    pub excess_blob_gas: U64,
+    /// base fee per unit of blob gas (EIP-4844), derived from the excess blob gas
+    pub blob_base_fee: Word,
+    /// hardfork, which selects the rules the block is executed with
+    pub hardfork: Hardfork,
 }
 
 impl<TX> TryFrom<&Block<TX>> for BlockConstants {
     type Error = Error;
 
     fn try_from(block: &Block<TX>) -> Result<Self, Self::Error> {
+        let excess_blob_gas = block_excess_blob_gas(block);
         Ok(Self {
             coinbase: block.author.ok_or(Error::IncompleteBlock)?,
             timestamp: block.timestamp,
@@ -114,6 +195,9 @@ impl<TX> TryFrom<&Block<TX>> for BlockConstants {
             },
             gas_limit: block.gas_limit,
             base_fee: block.base_fee_per_gas.ok_or(Error::IncompleteBlock)?,
+            excess_blob_gas,
+            blob_base_fee: evm_types::blob_base_fee(excess_blob_gas.as_u64()),
+            hardfork: block_hardfork(block),
         })
     }
 }
@@ -127,6 +211,8 @@ impl BlockConstants {
         difficulty: Word,
         gas_limit: Word,
         base_fee: Word,
+        excess_blob_gas: U64,
+        hardfork: Hardfork,
     ) -> BlockConstants {
         BlockConstants {
             coinbase,
@@ -135,10 +221,232 @@ impl BlockConstants {
             difficulty,
             gas_limit,
             base_fee,
+            excess_blob_gas,
+            blob_base_fee: evm_types::blob_base_fee(excess_blob_gas.as_u64()),
+            hardfork,
+        }
+    }
+}
+
+/// Header of an Ethereum block, holding all the fields which are hashed into
+/// the block hash.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BlockHeader {
+    /// Hash of the parent block
+    pub parent_hash: H256,
+    /// Hash of the uncles list
+    pub uncles_hash: H256,
+    /// Beneficiary of the block rewards (coinbase)
+    pub beneficiary: Address,
+    /// State root after the execution of the block
+    pub state_root: H256,
+    /// Root of the transactions trie
+    pub transactions_root: H256,
+    /// Root of the receipts trie
+    pub receipts_root: H256,
+    /// Bloom filter of the logs of the block
+    pub logs_bloom: Bloom,
+    /// Difficulty, which is zero after the merge
+    pub difficulty: Word,
+    /// Block number
+    pub number: U64,
+    /// Gas limit
+    pub gas_limit: Word,
+    /// Gas used by the transactions of the block
+    pub gas_used: Word,
+    /// Timestamp
+    pub timestamp: Word,
+    /// Extra data
+    pub extra_data: Bytes,
+    /// Mix hash, which holds the PREVRANDAO value after the merge
+    pub mix_hash: H256,
+    /// Nonce
+    pub nonce: H64,
+    /// Base fee (EIP-1559, London)
+    pub base_fee: Word,
+    /// Root of the withdrawals trie (EIP-4895, Shanghai)
+    pub withdrawals_root: H256,
+    /// Blob gas used by the transactions of the block (EIP-4844, Cancun)
+    pub blob_gas_used: U64,
+    /// Excess blob gas (EIP-4844, Cancun)
+    pub excess_blob_gas: U64,
+    /// Root of the parent beacon block (EIP-4788, Cancun)
+    pub parent_beacon_block_root: H256,
+}
+
+impl<TX> From<&Block<TX>> for BlockHeader {
+    fn from(block: &Block<TX>) -> Self {
+        Self {
+            parent_hash: block.parent_hash,
+            uncles_hash: block.uncles_hash,
+            beneficiary: block.author.unwrap_or_default(),
+            state_root: block.state_root,
+            transactions_root: block.transactions_root,
+            receipts_root: block.receipts_root,
+            logs_bloom: block.logs_bloom.unwrap_or_default(),
+            difficulty: block.difficulty,
+            number: block.number.unwrap_or_default(),
+            gas_limit: block.gas_limit,
+            gas_used: block.gas_used,
+            timestamp: block.timestamp,
+            extra_data: block.extra_data.clone(),
+            mix_hash: block.mix_hash.unwrap_or_default(),
+            nonce: block.nonce.unwrap_or_default(),
+            base_fee: block.base_fee_per_gas.unwrap_or_default(),
+            withdrawals_root: block.withdrawals_root.unwrap_or_default(),
+            blob_gas_used: get_other_field(&block.other, BLOB_GAS_USED_KEY),
+            excess_blob_gas: block_excess_blob_gas(block),
+            parent_beacon_block_root: get_other_field(&block.other, PARENT_BEACON_BLOCK_ROOT_KEY),
+        }
+    }
+}
+
+impl BlockHeader {
+    /// Return the RLP encoding of the header, which contains the fields
+    /// introduced up to the given hardfork.
+    pub fn rlp(&self, hardfork: Hardfork) -> Bytes {
+        let mut stream = RlpStream::new();
+        stream.begin_unbounded_list();
+        stream.append(&self.parent_hash);
+        stream.append(&self.uncles_hash);
+        stream.append(&self.beneficiary);
+        stream.append(&self.state_root);
+        stream.append(&self.transactions_root);
+        stream.append(&self.receipts_root);
+        stream.append(&self.logs_bloom);
+        stream.append(&self.difficulty);
+        stream.append(&self.number);
+        stream.append(&self.gas_limit);
+        stream.append(&self.gas_used);
+        stream.append(&self.timestamp);
+        stream.append(&self.extra_data.to_vec());
+        stream.append(&self.mix_hash);
+        stream.append(&self.nonce);
+        if hardfork >= Hardfork::London {
+            stream.append(&self.base_fee);
+        }
+        if hardfork >= Hardfork::Shanghai {
+            stream.append(&self.withdrawals_root);
+        }
+        if hardfork >= Hardfork::Cancun {
+            stream.append(&self.blob_gas_used);
+            stream.append(&self.excess_blob_gas);
+            stream.append(&self.parent_beacon_block_root);
+        }
+        stream.finalize_unbounded_list();
+        stream.out().to_vec().into()
+    }
+
+    /// Return the hash of the header in the given hardfork, which is the block
+    /// hash.
+    pub fn hash(&self, hardfork: Hardfork) -> H256 {
+        H256(keccak256(&self.rlp(hardfork)))
+    }
+}
+
+/// Return the root of the transactions trie of a block, which holds the
+/// EIP-2718 envelopes of its signed transactions.
+pub fn transactions_root(chain_id: u64, txs: &[crate::Transaction]) -> Result<H256, Error> {
+    let envelopes = txs
+        .iter()
+        .map(|tx| Transaction::from(tx).rlp_signed(chain_id))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(ordered_trie_root(&envelopes))
+}
+
+/// Log of a transaction as committed to by its receipt.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ReceiptLog {
+    /// Address of the contract which emitted the log
+    pub address: Address,
+    /// Topics of the log
+    pub topics: Vec<H256>,
+    /// Data of the log
+    pub data: Bytes,
+}
+
+impl ReceiptLog {
+    /// Return the RLP encoding of the log: `rlp([address, topics, data])`.
+    pub fn rlp(&self) -> Vec<u8> {
+        let mut stream = RlpStream::new_list(3);
+        stream.append(&self.address);
+        stream.append_list(&self.topics);
+        stream.append(&self.data.to_vec());
+        stream.out().to_vec()
+    }
+}
+
+/// Receipt of a transaction, as committed to by the receipts root of a block.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Receipt {
+    /// EIP-2718 type of the transaction
+    pub tx_type: u64,
+    /// Whether the transaction succeeded
+    pub status: bool,
+    /// Gas used by the block up to and including the transaction
+    pub cumulative_gas_used: u64,
+    /// Logs emitted by the transaction
+    pub logs: Vec<ReceiptLog>,
+}
+
+impl Receipt {
+    /// Return the bloom filter of the logs of the receipt.
+    pub fn logs_bloom(&self) -> Bloom {
+        let mut bloom = [0u8; 256];
+        for log in self.logs.iter() {
+            accrue_bloom(&mut bloom, log.address.as_bytes());
+            for topic in log.topics.iter() {
+                accrue_bloom(&mut bloom, topic.as_bytes());
+            }
         }
+        Bloom::from(bloom)
+    }
+
+    /// Return the EIP-2718 envelope of the receipt:
+    /// `[tx_type ||] rlp([status, cumulative_gas_used, logs_bloom, logs])`.
+    pub fn rlp(&self) -> Vec<u8> {
+        let mut stream = RlpStream::new_list(4);
+        stream.append(&(self.status as u64));
+        stream.append(&self.cumulative_gas_used);
+        stream.append(&self.logs_bloom());
+        stream.begin_list(self.logs.len());
+        for log in self.logs.iter() {
+            stream.append_raw(&log.rlp(), 1);
+        }
+        if self.tx_type == 0 {
+            stream.out().to_vec()
+        } else {
+            iter::once(self.tx_type as u8)
+                .chain(stream.out().iter().copied())
+                .collect()
+        }
+    }
+}
+
+/// Set the 3 bits selected by the hash of `input` in the 2048-bit `bloom`.
+pub fn accrue_bloom(bloom: &mut [u8; 256], input: &[u8]) {
+    let hash = keccak256(input);
+    for pair in hash[..6].chunks(2) {
+        let bit = ((pair[0] as usize & 7) << 8) | pair[1] as usize;
+        bloom[255 - bit / 8] |= 1 << (bit % 8);
     }
 }
 
+/// Return the bloom filter of a block, which is the union of the blooms of its
+/// receipts.
+pub fn logs_bloom(receipts: &[Receipt]) -> Bloom {
+    receipts
+        .iter()
+        .fold(Bloom::zero(), |bloom, receipt| bloom | receipt.logs_bloom())
+}
+
+/// Return the root of the receipts trie of a block, which holds the EIP-2718
+/// envelopes of its receipts.
+pub fn receipts_root(receipts: &[Receipt]) -> H256 {
+    let envelopes = receipts.iter().map(|r| r.rlp()).collect::<Vec<_>>();
+    ordered_trie_root(&envelopes)
+}
+
 /// Definition of all of the constants related to an Ethereum withdrawal.
 #[derive(Debug, Default, Clone, Serialize)]
 pub struct Withdrawal {
@@ -153,9 +461,32 @@ pub struct Withdrawal {
     pub amount: u64,
 }
 
+impl Withdrawal {
+    /// Return the RLP encoding of the withdrawal:
+    /// `rlp([id, validator_id, address, amount])`.
+    pub fn rlp(&self) -> Vec<u8> {
+        let mut stream = RlpStream::new_list(4);
+        stream.append(&self.id);
+        stream.append(&self.validator_id);
+        stream.append(&self.address);
+        stream.append(&self.amount);
+        stream.out().to_vec()
+    }
+}
+
+/// Return the root of the withdrawals trie of a block, which holds the RLP
+/// encodings of its withdrawals.
+pub fn withdrawals_root(withdrawals: &[Withdrawal]) -> H256 {
+    let encodings = withdrawals.iter().map(|wd| wd.rlp()).collect::<Vec<_>>();
+    ordered_trie_root(&encodings)
+}
+
 /// Definition of all of the constants related to an Ethereum transaction.
 #[derive(Debug, Default, Clone, Serialize)]
 pub struct Transaction {
+    /// Transaction type (EIP-2718), 0 for legacy transactions
+    /// U64 type is required to serialize into proper hex with 0x prefix
+    pub transaction_type: U64,
     /// Sender address
     pub from: Address,
     /// Recipient address (None for contract creation)
@@ -181,6 +512,10 @@ pub struct Transaction {
     pub call_data: Bytes,
     /// Access list
     pub access_list: Option<AccessList>,
+    /// Max fee per unit of blob gas (EIP-4844)
+    pub max_fee_per_blob_gas: Word,
+    /// Versioned hashes of the blobs carried by the transaction (EIP-4844)
+    pub blob_versioned_hashes: Vec<H256>,
 
     /// "v" value of the transaction signature
     pub v: u64,
@@ -193,6 +528,7 @@ pub struct Transaction {
 impl From<&Transaction> for crate::Transaction {
     fn from(tx: &Transaction) -> crate::Transaction {
         crate::Transaction {
+            transaction_type: Some(tx.transaction_type),
             from: tx.from,
             to: tx.to,
             nonce: tx.nonce.to_word(),
@@ -206,6 +542,11 @@ impl From<&Transaction> for crate::Transaction {
             v: tx.v.into(),
             r: tx.r,
             s: tx.s,
+            other: if tx.is_blob_tx() {
+                blob_tx_other_fields(tx.max_fee_per_blob_gas, &tx.blob_versioned_hashes)
+            } else {
+                OtherFields::default()
+            },
             ..Default::default()
         }
     }
@@ -214,6 +555,7 @@ impl From<&Transaction> for crate::Transaction {
 impl From<&crate::Transaction> for Transaction {
     fn from(tx: &crate::Transaction) -> Transaction {
         Transaction {
+            transaction_type: tx.transaction_type.unwrap_or_default(),
             from: tx.from,
             to: tx.to,
             nonce: tx.nonce.as_u64().into(),
@@ -224,6 +566,8 @@ impl From<&crate::Transaction> for Transaction {
             gas_fee_cap: tx.max_fee_per_gas.unwrap_or_default(),
             call_data: tx.input.clone(),
             access_list: tx.access_list.clone(),
+            max_fee_per_blob_gas: get_other_field(&tx.other, MAX_FEE_PER_BLOB_GAS_KEY),
+            blob_versioned_hashes: get_other_field(&tx.other, BLOB_VERSIONED_HASHES_KEY),
             v: tx.v.as_u64(),
             r: tx.r,
             s: tx.s,
@@ -247,6 +591,125 @@ impl From<&Transaction> for TransactionRequest {
 }
 
 impl Transaction {
+    /// Return the message signed by the sender of this Transaction: the RLP
+    /// encoding of the unsigned transaction fields, prefixed by the
+    /// transaction type for typed transactions (EIP-2718).
+    pub fn rlp_unsigned(&self, chain_id: u64) -> Result<Bytes, Error> {
+        let tx_type = self.transaction_type.as_u64();
+        if tx_type == LEGACY_TX_TYPE {
+            let req: TransactionRequest = self.into();
+            return Ok(if self.is_replay_protected() {
+                // msg = rlp([nonce, gasPrice, gas, to, value, data, chain_id, 0, 0])
+                req.chain_id(chain_id).rlp()
+            } else {
+                // msg = rlp([nonce, gasPrice, gas, to, value, data])
+                req.rlp()
+            });
+        }
+
+        // msg = tx_type || rlp([chain_id, nonce, <fee fields>, gas, to, value, data,
+        //                       access_list, <blob fields>])
+        let mut stream = RlpStream::new();
+        stream.begin_unbounded_list();
+        self.rlp_append_typed_fields(&mut stream, chain_id)?;
+        stream.finalize_unbounded_list();
+
+        Ok(iter::once(tx_type as u8)
+            .chain(stream.out())
+            .collect::<Vec<u8>>()
+            .into())
+    }
+
+    /// Return the EIP-2718 envelope of this signed Transaction, which is the
+    /// value stored in the transactions trie of a block: the RLP encoding of
+    /// the signed fields for legacy transactions, prefixed by the transaction
+    /// type for typed transactions.
+    pub fn rlp_signed(&self, chain_id: u64) -> Result<Bytes, Error> {
+        let tx_type = self.transaction_type.as_u64();
+        let mut stream = RlpStream::new();
+        stream.begin_unbounded_list();
+        if tx_type == LEGACY_TX_TYPE {
+            // rlp([nonce, gasPrice, gas, to, value, data, v, r, s])
+            stream.append(&self.nonce);
+            stream.append(&self.gas_price);
+            stream.append(&self.gas_limit);
+            self.rlp_append_call_fields(&mut stream);
+        } else {
+            // tx_type || rlp([<unsigned fields>, y_parity, r, s])
+            self.rlp_append_typed_fields(&mut stream, chain_id)?;
+        }
+        stream.append(&self.v);
+        stream.append(&self.r);
+        stream.append(&self.s);
+        stream.finalize_unbounded_list();
+
+        let envelope = if tx_type == LEGACY_TX_TYPE {
+            stream.out().to_vec()
+        } else {
+            iter::once(tx_type as u8).chain(stream.out()).collect()
+        };
+        Ok(envelope.into())
+    }
+
+    /// Append the unsigned fields of a typed transaction to an RLP list.
+    fn rlp_append_typed_fields(&self, stream: &mut RlpStream, chain_id: u64) -> Result<(), Error> {
+        let tx_type = self.transaction_type.as_u64();
+        stream.append(&chain_id);
+        stream.append(&self.nonce);
+        match tx_type {
+            ACCESS_LIST_TX_TYPE => {
+                stream.append(&self.gas_price);
+            }
+            DYNAMIC_FEE_TX_TYPE | BLOB_TX_TYPE => {
+                stream.append(&self.gas_tip_cap);
+                stream.append(&self.gas_fee_cap);
+            }
+            _ => return Err(Error::UnsupportedTxType(tx_type)),
+        }
+        stream.append(&self.gas_limit);
+        self.rlp_append_call_fields(stream);
+        stream.append(&self.access_list.clone().unwrap_or_default());
+        if tx_type == BLOB_TX_TYPE {
+            stream.append(&self.max_fee_per_blob_gas);
+            stream.append_list::<H256, _>(&self.blob_versioned_hashes);
+        }
+        Ok(())
+    }
+
+    /// Append the `to`, `value` and `data` fields shared by all transaction
+    /// types to an RLP list.
+    fn rlp_append_call_fields(&self, stream: &mut RlpStream) {
+        match self.to {
+            Some(to) => stream.append(&to),
+            None => stream.append_empty_data(),
+        };
+        stream.append(&self.value);
+        stream.append(&self.call_data);
+    }
+
+    /// Determine if the signature of this transaction commits to the chain id,
+    /// which is the case for typed transactions and for legacy transactions
+    /// signed following EIP-155.
+    pub fn is_replay_protected(&self) -> bool {
+        self.transaction_type.as_u64() != LEGACY_TX_TYPE || !matches!(self.v, 27 | 28)
+    }
+
+    /// Return the recovery id of the signature, which is encoded in `v` as
+    /// 27 + y parity for pre-EIP-155 legacy transactions, following EIP-155
+    /// for the other legacy transactions and as the y parity for typed
+    /// transactions.
+    pub fn recovery_id(&self, chain_id: u64) -> Option<u8> {
+        let v = match (self.transaction_type.as_u64(), self.v) {
+            (LEGACY_TX_TYPE, v @ (27 | 28)) => Some(v - 27),
+            (LEGACY_TX_TYPE, v) => v.checked_sub(35 + chain_id * 2),
+            (_, v @ (0 | 1)) => Some(v),
+            (_, v @ (27 | 28)) => Some(v - 27),
+            // Signers that always encode `v` following EIP-155
+            (_, v) => v.checked_sub(35 + chain_id * 2),
+        };
+        v.filter(|v| *v <= 1).map(|v| v as u8)
+    }
+
     /// Return the SignData associated with this Transaction.
     pub fn sign_data(&self, chain_id: u64) -> Result<SignData, Error> {
         let sig_r_le = self.r.to_le_bytes();
@@ -259,27 +722,19 @@ impl Transaction {
             secp256k1::Fq::from_repr(sig_s_le),
             Error::Signature(libsecp256k1::Error::InvalidSignature),
         )?;
-        // msg = rlp([nonce, gasPrice, gas, to, value, data, sig_v, r, s])
-        let req: TransactionRequest = self.into();
-        let msg = req.chain_id(chain_id).rlp();
+        let msg = self.rlp_unsigned(chain_id)?;
         let msg_hash: [u8; 32] = keccak256(&msg);
         let v = self
-            .v
-            .checked_sub(35 + chain_id * 2)
-            .ok_or(Error::Signature(libsecp256k1::Error::InvalidSignature))? as u8;
+            .recovery_id(chain_id)
+            .ok_or(Error::Signature(libsecp256k1::Error::InvalidSignature))?;
         let pk = recover_pk(v, &self.r, &self.s, &msg_hash)?;
         // msg_hash = msg_hash % q
-        let msg_hash = BigUint::from_bytes_be(msg_hash.as_slice());
-        let msg_hash = msg_hash.mod_floor(&*SECP256K1_Q);
-        let msg_hash_le = biguint_to_32bytes_le(msg_hash);
-        let msg_hash = ct_option_ok_or(
-            secp256k1::Fq::from_repr(msg_hash_le),
-            libsecp256k1::Error::InvalidMessage,
-        )?;
+        let msg_hash = msg_hash_to_scalar(&msg_hash);
         Ok(SignData {
             signature: (sig_r, sig_s),
             pk,
             msg_hash,
+            v,
         })
     }
 
@@ -290,17 +745,49 @@ impl Transaction {
             .fold(0, |acc, byte| acc + if *byte == 0 { 4 } else { 16 })
     }
 
-    /// Compute the intrinsic gas cost
-    pub fn intrinsic_gas_cost(&self) -> u64 {
+    /// Compute the intrinsic gas cost under the rules of the hardfork
+    pub fn intrinsic_gas_cost(&self, hardfork: Hardfork) -> u64 {
         let is_create = self.is_create() as u64;
         // Calculate gas cost of init code for EIP-3860.
         let init_code_gas_cost =
-            ((self.call_data.len() as u64 + 31) / 32) * evm_types::INIT_CODE_WORD_GAS;
+            ((self.call_data.len() as u64 + 31) / 32) * hardfork.init_code_word_gas();
         is_create * (GasCost::CREATION_TX + init_code_gas_cost)
             + (1 - is_create) * GasCost::TX
             + self.call_data_gas_cost()
     }
 
+    /// Return the max fee per gas the sender is willing to pay, which is the
+    /// gas price for transactions without dynamic fees (EIP-1559)
+    pub fn max_fee_per_gas(&self) -> Word {
+        match self.transaction_type.as_u64() {
+            LEGACY_TX_TYPE | ACCESS_LIST_TX_TYPE => self.gas_price,
+            _ => self.gas_fee_cap,
+        }
+    }
+
+    /// Return the number of addresses in the access list (EIP-2930)
+    pub fn access_list_addresses_len(&self) -> u64 {
+        self.access_list
+            .as_ref()
+            .map_or(0, |access_list| access_list.0.len() as u64)
+    }
+
+    /// Determine if this transaction is a blob-carrying transaction (EIP-4844)
+    pub fn is_blob_tx(&self) -> bool {
+        self.transaction_type.as_u64() == BLOB_TX_TYPE
+    }
+
+    /// Compute the blob gas used by the blobs carried by this transaction
+    pub fn blob_gas(&self) -> u64 {
+        self.blob_versioned_hashes.len() as u64 * evm_types::GAS_PER_BLOB
+    }
+
+    /// Compute the blob gas fee paid for the blobs carried by this
+    /// transaction, which is burned and never refunded.
+    pub fn blob_gas_fee(&self, blob_base_fee: Word) -> Word {
+        blob_base_fee * self.blob_gas()
+    }
+
     /// Get the "to" address. If `to` is None then zero address
     pub fn to_or_zero(&self) -> Address {
         self.to.unwrap_or_default()
@@ -337,6 +824,12 @@ impl Transaction {
             v: U64::from(self.v),
             block_number: Some(block_number),
             chain_id: Some(chain_id),
+            transaction_type: Some(self.transaction_type),
+            other: if self.is_blob_tx() {
+                blob_tx_other_fields(self.max_fee_per_blob_gas, &self.blob_versioned_hashes)
+            } else {
+                OtherFields::default()
+            },
             ..response::Transaction::default()
         }
     }
@@ -360,22 +853,287 @@ pub struct GethData {
     pub geth_traces: Vec<GethExecTrace>,
     /// Accounts
     pub accounts: Vec<Account>,
+    /// Hardfork the block is executed with
+    pub hardfork: Hardfork,
 }
 
 impl GethData {
     /// Signs transactions with selected wallets
     pub fn sign(&mut self, wallets: &HashMap<Address, LocalWallet>) {
+        // The transactions root and the block hash are kept in sync with the new
+        // signatures, unless they were set to arbitrary values.
+        let chain_id = self.chain_id.as_u64();
+        let derived_root = transactions_root(chain_id, &self.eth_block.transactions).ok()
+            == Some(self.eth_block.transactions_root);
+        let derived_hash =
+            self.eth_block.hash == Some(BlockHeader::from(&self.eth_block).hash(self.hardfork));
+
         for tx in self.eth_block.transactions.iter_mut() {
             let wallet = wallets.get(&tx.from).unwrap();
             assert_eq!(Word::from(wallet.chain_id()), self.chain_id);
             let geth_tx: Transaction = (&*tx).into();
-            let req: TransactionRequest = (&geth_tx).into();
-            let sig = wallet
-                .sign_transaction_sync(&req.chain_id(self.chain_id.as_u64()).into())
-                .unwrap();
+            let sig = if geth_tx.transaction_type.as_u64() == LEGACY_TX_TYPE {
+                let req: TransactionRequest = (&geth_tx).into();
+                wallet
+                    .sign_transaction_sync(&req.chain_id(self.chain_id.as_u64()).into())
+                    .unwrap()
+            } else {
+                // Typed txs are signed over their EIP-2718 envelope and carry the y
+                // parity of the signature as `v`.
+                let msg = geth_tx.rlp_unsigned(self.chain_id.as_u64()).unwrap();
+                let mut sig = wallet.sign_hash(H256(keccak256(&msg))).unwrap();
+                sig.v -= 27;
+                sig
+            };
             tx.v = U64::from(sig.v);
             tx.r = sig.r;
             tx.s = sig.s;
         }
+
+        if derived_root {
+            self.eth_block.transactions_root =
+                transactions_root(chain_id, &self.eth_block.transactions)
+                    .expect("transactions of the block are supported");
+            if derived_hash {
+                self.eth_block.hash = Some(BlockHeader::from(&self.eth_block).hash(self.hardfork));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        sign_types::{pk_bytes_le, pk_bytes_swap_endianness},
+        Signature,
+    };
+    use ethers_core::types::{
+        transaction::{eip2718::TypedTransaction, eip2930::AccessListItem},
+        Eip1559TransactionRequest, Eip2930TransactionRequest,
+    };
+
+    const CHAIN_ID: u64 = 1337;
+
+    fn wallet() -> LocalWallet {
+        "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
+            .parse::<LocalWallet>()
+            .unwrap()
+            .with_chain_id(CHAIN_ID)
+    }
+
+    fn tx(transaction_type: u64) -> Transaction {
+        Transaction {
+            transaction_type: transaction_type.into(),
+            from: wallet().address(),
+            to: Some(Address::repeat_byte(0x11)),
+            nonce: 3u64.into(),
+            gas_limit: 50_000u64.into(),
+            value: 1000u64.into(),
+            gas_price: 20u64.into(),
+            gas_fee_cap: 30u64.into(),
+            gas_tip_cap: 2u64.into(),
+            call_data: Bytes::from(b"hello"),
+            access_list: Some(AccessList(vec![AccessListItem {
+                address: Address::repeat_byte(0x22),
+                storage_keys: vec![H256::repeat_byte(0x33)],
+            }])),
+            max_fee_per_blob_gas: if transaction_type == BLOB_TX_TYPE {
+                10u64.into()
+            } else {
+                Word::zero()
+            },
+            blob_versioned_hashes: if transaction_type == BLOB_TX_TYPE {
+                vec![H256::repeat_byte(0x01)]
+            } else {
+                vec![]
+            },
+            ..Default::default()
+        }
+    }
+
+    fn signed_tx(transaction_type: u64) -> Transaction {
+        let tx = tx(transaction_type);
+        let mut geth_data = GethData {
+            chain_id: CHAIN_ID.into(),
+            history_hashes: vec![],
+            eth_block: Block {
+                transactions: vec![crate::Transaction::from(&tx)],
+                ..Default::default()
+            },
+            geth_traces: vec![],
+            accounts: vec![],
+            hardfork: Hardfork::default(),
+        };
+        geth_data.sign(&HashMap::from([(tx.from, wallet())]));
+        Transaction::from(&geth_data.eth_block.transactions[0])
+    }
+
+    fn assert_sign_data_recovers_sender(tx: &Transaction) {
+        let sign_data = tx.sign_data(CHAIN_ID).unwrap();
+        let pk_be = pk_bytes_swap_endianness(&pk_bytes_le(&sign_data.pk));
+        let address = Address::from_slice(&keccak256(&pk_be)[12..]);
+        assert_eq!(address, tx.from);
+    }
+
+    #[test]
+    fn rlp_unsigned_typed_txs() {
+        let tx_1 = tx(ACCESS_LIST_TX_TYPE);
+        let req = Eip2930TransactionRequest::new(
+            TransactionRequest::from(&tx_1).chain_id(CHAIN_ID),
+            tx_1.access_list.clone().unwrap(),
+        );
+        assert_eq!(
+            tx_1.rlp_unsigned(CHAIN_ID).unwrap(),
+            TypedTransaction::Eip2930(req).rlp()
+        );
+
+        let tx_2 = tx(DYNAMIC_FEE_TX_TYPE);
+        let req = Eip1559TransactionRequest::new()
+            .from(tx_2.from)
+            .to(tx_2.to.unwrap())
+            .nonce(tx_2.nonce.as_u64())
+            .gas(tx_2.gas_limit.as_u64())
+            .value(tx_2.value)
+            .data(tx_2.call_data.clone())
+            .max_priority_fee_per_gas(tx_2.gas_tip_cap)
+            .max_fee_per_gas(tx_2.gas_fee_cap)
+            .access_list(tx_2.access_list.clone().unwrap())
+            .chain_id(CHAIN_ID);
+        assert_eq!(
+            tx_2.rlp_unsigned(CHAIN_ID).unwrap(),
+            TypedTransaction::Eip1559(req).rlp()
+        );
+
+        assert_eq!(tx(BLOB_TX_TYPE).rlp_unsigned(CHAIN_ID).unwrap()[0], 0x03);
+        assert!(matches!(
+            tx(4).rlp_unsigned(CHAIN_ID),
+            Err(Error::UnsupportedTxType(4))
+        ));
+    }
+
+    #[test]
+    fn rlp_receipt() {
+        let receipt = Receipt {
+            tx_type: 0,
+            status: true,
+            cumulative_gas_used: 42_000,
+            logs: vec![ReceiptLog {
+                address: Address::repeat_byte(0x11),
+                topics: vec![H256::repeat_byte(0x22), H256::repeat_byte(0x33)],
+                data: vec![1, 2, 3].into(),
+            }],
+        };
+        let bloom = receipt.logs_bloom();
+        assert!(bloom.0.iter().map(|b| b.count_ones()).sum::<u32>() <= 9);
+        assert_eq!(logs_bloom(&[receipt.clone()]), bloom);
+
+        let expected = response::TransactionReceipt {
+            status: Some(1u64.into()),
+            cumulative_gas_used: 42_000u64.into(),
+            logs_bloom: bloom,
+            logs: receipt
+                .logs
+                .iter()
+                .map(|log| ethers_core::types::Log {
+                    address: log.address,
+                    topics: log.topics.clone(),
+                    data: log.data.clone(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        };
+        assert_eq!(
+            receipt.rlp(),
+            ethers_core::utils::rlp::encode(&expected).to_vec()
+        );
+
+        let typed = Receipt {
+            tx_type: 2,
+            ..receipt.clone()
+        };
+        assert_eq!(typed.rlp()[0], 2);
+        assert_eq!(typed.rlp()[1..], receipt.rlp()[..]);
+    }
+
+    #[test]
+    fn rlp_signed_txs() {
+        let tx_0 = signed_tx(LEGACY_TX_TYPE);
+        let sig = Signature {
+            v: tx_0.v,
+            r: tx_0.r,
+            s: tx_0.s,
+        };
+        assert_eq!(
+            tx_0.rlp_signed(CHAIN_ID).unwrap(),
+            TransactionRequest::from(&tx_0).rlp_signed(&sig)
+        );
+
+        let tx_1 = signed_tx(ACCESS_LIST_TX_TYPE);
+        let sig = Signature {
+            v: tx_1.v,
+            r: tx_1.r,
+            s: tx_1.s,
+        };
+        let req = Eip2930TransactionRequest::new(
+            TransactionRequest::from(&tx_1).chain_id(CHAIN_ID),
+            tx_1.access_list.clone().unwrap(),
+        );
+        assert_eq!(
+            tx_1.rlp_signed(CHAIN_ID).unwrap(),
+            TypedTransaction::Eip2930(req).rlp_signed(&sig)
+        );
+
+        let tx_3 = signed_tx(BLOB_TX_TYPE);
+        let envelope = tx_3.rlp_signed(CHAIN_ID).unwrap();
+        let unsigned = tx_3.rlp_unsigned(CHAIN_ID).unwrap();
+        assert_eq!(envelope[0], 0x03);
+        assert!(envelope.len() > unsigned.len());
+    }
+
+    #[test]
+    fn sign_data_typed_txs() {
+        for transaction_type in [
+            LEGACY_TX_TYPE,
+            ACCESS_LIST_TX_TYPE,
+            DYNAMIC_FEE_TX_TYPE,
+            BLOB_TX_TYPE,
+        ] {
+            let tx = signed_tx(transaction_type);
+            if transaction_type != LEGACY_TX_TYPE {
+                assert!(tx.v <= 1);
+            }
+            assert_sign_data_recovers_sender(&tx);
+        }
+    }
+
+    #[test]
+    fn sign_data_pre_eip155_tx() {
+        let mut tx = tx(LEGACY_TX_TYPE);
+        // Any of 27 or 28 marks the tx as signed without the chain id.
+        tx.v = 27;
+        assert!(!tx.is_replay_protected());
+        let msg = tx.rlp_unsigned(CHAIN_ID).unwrap();
+        assert_eq!(msg, TransactionRequest::from(&tx).rlp());
+
+        let sig = wallet().sign_hash(H256(keccak256(&msg))).unwrap();
+        tx.v = sig.v;
+        tx.r = sig.r;
+        tx.s = sig.s;
+        assert_sign_data_recovers_sender(&tx);
+
+        // Legacy txs signed following EIP-155 commit to the chain id.
+        let signed_tx = signed_tx(LEGACY_TX_TYPE);
+        assert!(signed_tx.is_replay_protected());
+        assert_ne!(signed_tx.rlp_unsigned(CHAIN_ID).unwrap(), msg);
+    }
+
+    #[test]
+    fn sign_data_typed_tx_eip155_v() {
+        // Some signers encode `v` following EIP-155 for typed txs too.
+        let mut tx = signed_tx(DYNAMIC_FEE_TX_TYPE);
+        tx.v += 35 + CHAIN_ID * 2;
+        assert_sign_data_recovers_sender(&tx);
     }
 }
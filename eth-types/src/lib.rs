@@ -20,6 +20,7 @@ pub mod evm_types;
 pub mod geth_types;
 pub mod keccak;
 pub mod sign_types;
+pub mod trie;
 pub use keccak::{keccak256, Keccak};
 
 pub use bytecode::Bytecode;
@@ -39,7 +40,7 @@ pub use ethers_core::{
     },
 };
 
-use serde::{de, Deserialize, Serialize};
+use serde::{de, ser::SerializeStruct, Deserialize, Serialize, Serializer};
 use std::{collections::HashMap, fmt, str::FromStr};
 
 /// Trait used to reduce verbosity with the declaration of the [`PrimeField`]
@@ -281,7 +282,7 @@ impl<F: Field> ToScalar<F> for usize {
 }
 
 /// Struct used to define the storage proof
-#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct StorageProof {
     /// Storage key
     pub key: U256,
@@ -292,7 +293,7 @@ pub struct StorageProof {
 }
 
 /// Struct used to define the result of `eth_getProof` call
-#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct EIP1186ProofResponse {
     /// Account address
@@ -335,7 +336,7 @@ struct GethExecStepInternal {
 
 /// The execution step type returned by geth RPC debug_trace* methods.
 /// Corresponds to `StructLogRes` in `go-ethereum/internal/ethapi/api.go`.
-#[derive(Clone, Eq, PartialEq, Serialize)]
+#[derive(Clone, Eq, PartialEq)]
 #[doc(hidden)]
 pub struct GethExecStep {
     pub pc: u64,
@@ -388,6 +389,40 @@ impl fmt::Debug for GethExecStep {
     }
 }
 
+// Serializes in the same format as geth, so that the step can be deserialized
+// back.
+impl Serialize for GethExecStep {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let op = match self.op {
+            OpcodeId::INVALID(0xfe) => "INVALID".to_string(),
+            OpcodeId::INVALID(byte) => format!("opcode {byte:#x} not defined"),
+            op => format!("{op:?}"),
+        };
+        let memory = self
+            .memory
+            .0
+            .chunks(32)
+            .map(hex::encode)
+            .collect::<Vec<_>>();
+
+        let mut step = serializer.serialize_struct("GethExecStep", 10)?;
+        step.serialize_field("pc", &self.pc)?;
+        step.serialize_field("op", &op)?;
+        step.serialize_field("gas", &self.gas)?;
+        step.serialize_field("gasCost", &self.gas_cost)?;
+        step.serialize_field("refund", &self.refund)?;
+        step.serialize_field("depth", &self.depth)?;
+        step.serialize_field("error", &self.error)?;
+        step.serialize_field("stack", &self.stack)?;
+        step.serialize_field("memory", &memory)?;
+        step.serialize_field("storage", &self.storage)?;
+        step.end()
+    }
+}
+
 impl<'de> Deserialize<'de> for GethExecStep {
     fn deserialize<D>(deserializer: D) -> Result<GethExecStep, D::Error>
     where
@@ -435,6 +470,41 @@ pub struct ResultGethExecTrace {
     pub result: GethExecTrace,
 }
 
+/// Helper type built to deal with the `result` field added between the
+/// prestates in `debug_traceBlockByNumber` Geth JSON-RPC calls with the
+/// `prestateTracer`.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize)]
+#[doc(hidden)]
+pub struct ResultGethPrestateTraces(pub Vec<ResultGethPrestateTrace>);
+
+/// Helper type built to deal with the `result` field added between the
+/// prestates in `debug_traceBlockByNumber` Geth JSON-RPC calls with the
+/// `prestateTracer`.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize)]
+#[doc(hidden)]
+pub struct ResultGethPrestateTrace {
+    pub result: HashMap<Address, GethPrestateAccount>,
+}
+
+/// The state of an account accessed by a transaction, before the transaction
+/// is executed, as returned by the geth `prestateTracer`.  Only the accessed
+/// storage slots are included.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, Eq, PartialEq)]
+pub struct GethPrestateAccount {
+    /// Balance
+    #[serde(default)]
+    pub balance: Word,
+    /// Nonce
+    #[serde(default)]
+    pub nonce: u64,
+    /// EVM Code
+    #[serde(default)]
+    pub code: Bytes,
+    /// Accessed storage slots
+    #[serde(default)]
+    pub storage: HashMap<Word, Word>,
+}
+
 /// The execution trace type returned by geth RPC debug_trace* methods.
 /// Corresponds to `ExecutionResult` in `go-ethereum/internal/ethapi/api.go`.
 /// The deserialization truncates the memory of each step in `struct_logs` to
@@ -629,6 +699,14 @@ mod tests {
                 ],
             }
         );
+
+        // The serialized trace deserializes back to the same trace.
+        let serialized = serde_json::to_string(&trace).expect("json-serialize GethExecTrace");
+        assert_eq!(
+            serde_json::from_str::<GethExecTrace>(&serialized)
+                .expect("json-deserialize GethExecTrace"),
+            trace
+        );
     }
 }
 
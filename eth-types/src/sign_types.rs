@@ -1,6 +1,6 @@
 //! secp256k1 signature types and helper functions.
 
-use crate::{ToBigEndian, Word};
+use crate::{keccak256, Address, ToBigEndian, Word};
 use halo2_proofs::{
     arithmetic::{CurveAffine, Field},
     halo2curves::{
@@ -16,19 +16,22 @@ use lazy_static::lazy_static;
 use num_bigint::BigUint;
 use subtle::CtOption;
 
-/// Do a secp256k1 signature with a given randomness value.
+/// Do a secp256k1 signature with a given randomness value.  Returns the `(r, s)`
+/// components of the signature and its recovery id, which is the parity of the
+/// y coordinate of the signature point.
 pub fn sign(
     randomness: secp256k1::Fq,
     sk: secp256k1::Fq,
     msg_hash: secp256k1::Fq,
-) -> (secp256k1::Fq, secp256k1::Fq) {
+) -> (secp256k1::Fq, secp256k1::Fq, u8) {
     let randomness_inv =
         Option::<secp256k1::Fq>::from(randomness.invert()).expect("cannot invert randomness");
     let generator = Secp256k1Affine::generator();
     let sig_point = generator * randomness;
-    let x = *Option::<Coordinates<_>>::from(sig_point.to_affine().coordinates())
-        .expect("point is the identity")
-        .x();
+    let sig_point_coord = Option::<Coordinates<_>>::from(sig_point.to_affine().coordinates())
+        .expect("point is the identity");
+    let x = *sig_point_coord.x();
+    let v = sig_point_coord.y().to_bytes()[0] & 1;
 
     let x_repr = &mut vec![0u8; 32];
     x_repr.copy_from_slice(x.to_bytes().as_slice());
@@ -38,7 +41,7 @@ pub fn sign(
 
     let sig_r = secp256k1::Fq::from_uniform_bytes(&x_bytes); // get x coordinate (E::Base) on E::Scalar
     let sig_s = randomness_inv * (msg_hash + sig_r * sk);
-    (sig_r, sig_s)
+    (sig_r, sig_s, v)
 }
 
 /// Signature data required by the SignVerify Chip as input to verify a
@@ -47,10 +50,28 @@ pub fn sign(
 pub struct SignData {
     /// Secp256k1 signature point
     pub signature: (secp256k1::Fq, secp256k1::Fq),
-    /// Secp256k1 public key
+    /// Secp256k1 public key, or the identity for a signature from which no
+    /// public key is recovered
     pub pk: Secp256k1Affine,
     /// Hash of the message that is being signed
     pub msg_hash: secp256k1::Fq,
+    /// Recovery id of the signature, which is 0 or 1
+    pub v: u8,
+}
+
+impl SignData {
+    /// Return whether a public key is recovered from the signature.
+    pub fn is_recovered(&self) -> bool {
+        bool::from(self.pk.coordinates().is_some())
+    }
+
+    /// Return the address of the public key that signed the message.
+    pub fn get_addr(&self) -> Address {
+        let pk_le = pk_bytes_le(&self.pk);
+        let pk_be = pk_bytes_swap_endianness(&pk_le);
+        let pk_hash = keccak256(pk_be);
+        Address::from_slice(&pk_hash[12..])
+    }
 }
 
 lazy_static! {
@@ -61,12 +82,13 @@ lazy_static! {
         let pk = pk.to_affine();
         let msg_hash = secp256k1::Fq::ONE;
         let randomness = secp256k1::Fq::ONE;
-        let (sig_r, sig_s) = sign(randomness, sk, msg_hash);
+        let (sig_r, sig_s, v) = sign(randomness, sk, msg_hash);
 
         SignData {
             signature: (sig_r, sig_s),
             pk,
             msg_hash,
+            v,
         }
     };
 }
@@ -90,6 +112,13 @@ pub fn biguint_to_32bytes_le(v: BigUint) -> [u8; 32] {
     res
 }
 
+/// Convert a big endian message hash into a secp256k1 scalar, reducing it
+/// modulo the curve order.
+pub fn msg_hash_to_scalar(msg_hash: &[u8; 32]) -> secp256k1::Fq {
+    let msg_hash = BigUint::from_bytes_be(msg_hash.as_slice()) % &*SECP256K1_Q;
+    secp256k1::Fq::from_repr(biguint_to_32bytes_le(msg_hash)).expect("reduced modulo the order")
+}
+
 /// Recover the public key from a secp256k1 signature and the message hash.
 pub fn recover_pk(
     v: u8,
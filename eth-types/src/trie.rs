@@ -0,0 +1,249 @@
+//! Merkle Patricia Tries built from the ordered lists of a block (transactions,
+//! receipts and withdrawals), where the key of each value is the RLP encoding of
+//! its index in the list.
+
+use crate::{keccak256, H256};
+use ethers_core::utils::rlp::{self, RlpStream};
+
+/// Kind of a trie node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrieNodeKind {
+    /// The root of an empty trie, encoded as the empty string.
+    Empty,
+    /// Branch node with 16 children and an (always empty) value.
+    Branch,
+    /// Extension node holding a shared path and a child.
+    Extension,
+    /// Leaf node holding the remaining path and a value.
+    Leaf,
+}
+
+/// Node of a trie which is referenced by hash, either from its parent or as
+/// the root of the trie.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrieNode {
+    /// Kind of the node
+    pub kind: TrieNodeKind,
+    /// Nibbles of the path from the root to this node
+    pub path: Vec<u8>,
+    /// RLP encoding of the node
+    pub rlp: Vec<u8>,
+    /// Index of the value for leaf nodes
+    pub index: Option<usize>,
+}
+
+impl TrieNode {
+    /// Return the hash of the node.
+    pub fn hash(&self) -> H256 {
+        H256(keccak256(&self.rlp))
+    }
+}
+
+/// Root of the empty trie, `keccak256(rlp(""))`.
+pub fn empty_trie_root() -> H256 {
+    H256(keccak256(&rlp::NULL_RLP))
+}
+
+/// Return the key of the value at `index` in an ordered trie.
+pub fn ordered_trie_key(index: usize) -> Vec<u8> {
+    rlp::encode(&index).to_vec()
+}
+
+/// Return the root of the ordered trie of `values`.
+pub fn ordered_trie_root<V: AsRef<[u8]>>(values: &[V]) -> H256 {
+    ordered_trie_nodes(values)[0].hash()
+}
+
+/// Return the nodes of the ordered trie of `values` which are referenced by
+/// hash. The root comes first, followed by the inner nodes in depth first
+/// order, followed by the leaves sorted by the index of their value. Nodes
+/// shorter than 32 bytes are embedded in their parent and not returned.
+pub fn ordered_trie_nodes<V: AsRef<[u8]>>(values: &[V]) -> Vec<TrieNode> {
+    if values.is_empty() {
+        return vec![TrieNode {
+            kind: TrieNodeKind::Empty,
+            path: vec![],
+            rlp: rlp::NULL_RLP.to_vec(),
+            index: None,
+        }];
+    }
+
+    let mut items: Vec<_> = values
+        .iter()
+        .enumerate()
+        .map(|(index, value)| (to_nibbles(&ordered_trie_key(index)), index, value.as_ref()))
+        .collect();
+    items.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut nodes = Vec::new();
+    let root = build_node(&items, 0, &mut nodes);
+    let (mut leaves, inner): (Vec<_>, Vec<_>) = nodes
+        .into_iter()
+        .rev()
+        .partition(|node| node.kind == TrieNodeKind::Leaf);
+    leaves.sort_by_key(|node| node.index);
+    let mut nodes = vec![root];
+    nodes.extend(inner);
+    nodes.extend(leaves);
+    nodes
+}
+
+/// Build the node holding `items` (key nibbles, index, value) below `depth`,
+/// pushing the nodes that are referenced by hash into `nodes`.
+fn build_node(
+    items: &[(Vec<u8>, usize, &[u8])],
+    depth: usize,
+    nodes: &mut Vec<TrieNode>,
+) -> TrieNode {
+    let path = items[0].0[..depth].to_vec();
+
+    let (kind, rlp, index) = if items.len() == 1 {
+        let (key, index, value) = &items[0];
+        let mut stream = RlpStream::new_list(2);
+        stream.append(&hex_prefix(&key[depth..], true).as_slice());
+        stream.append(value);
+        (TrieNodeKind::Leaf, stream.out().to_vec(), Some(*index))
+    } else {
+        let shared = (depth..items[0].0.len())
+            .take_while(|i| {
+                items
+                    .iter()
+                    .all(|item| item.0.get(*i) == items[0].0.get(*i))
+            })
+            .count();
+        let stream = if shared > 0 {
+            let child = build_node(items, depth + shared, nodes);
+            let mut stream = RlpStream::new_list(2);
+            stream.append(&hex_prefix(&items[0].0[depth..depth + shared], false).as_slice());
+            append_child(&mut stream, child, nodes);
+            stream
+        } else {
+            let mut stream = RlpStream::new_list(17);
+            for nibble in 0..16 {
+                let start = items.partition_point(|item| item.0[depth] < nibble);
+                let end = items.partition_point(|item| item.0[depth] <= nibble);
+                if start == end {
+                    stream.append_empty_data();
+                } else {
+                    let child = build_node(&items[start..end], depth + 1, nodes);
+                    append_child(&mut stream, child, nodes);
+                }
+            }
+            stream.append_empty_data();
+            stream
+        };
+        let kind = if shared > 0 {
+            TrieNodeKind::Extension
+        } else {
+            TrieNodeKind::Branch
+        };
+        (kind, stream.out().to_vec(), None)
+    };
+
+    TrieNode {
+        kind,
+        path,
+        rlp,
+        index,
+    }
+}
+
+/// Append the reference to `child` to the RLP list of its parent: its hash
+/// when it's at least 32 bytes long, its RLP otherwise.
+fn append_child(stream: &mut RlpStream, child: TrieNode, nodes: &mut Vec<TrieNode>) {
+    if child.rlp.len() < 32 {
+        stream.append_raw(&child.rlp, 1);
+    } else {
+        stream.append(&child.hash());
+        nodes.push(child);
+    }
+}
+
+/// Split bytes into nibbles, most significant first.
+pub fn to_nibbles(bytes: &[u8]) -> Vec<u8> {
+    bytes
+        .iter()
+        .flat_map(|byte| [byte >> 4, byte & 0xf])
+        .collect()
+}
+
+/// Hex prefix encoding of a path of nibbles.
+pub fn hex_prefix(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+    let flag = 2 * is_leaf as u8 + (nibbles.len() % 2) as u8;
+    let (first, rest) = if nibbles.len() % 2 == 1 {
+        ((flag << 4) | nibbles[0], &nibbles[1..])
+    } else {
+        (flag << 4, nibbles)
+    };
+    std::iter::once(first)
+        .chain(rest.chunks(2).map(|pair| (pair[0] << 4) | pair[1]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn empty_root() {
+        assert_eq!(
+            ordered_trie_root::<Vec<u8>>(&[]),
+            H256::from_str("0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421")
+                .unwrap()
+        );
+        assert_eq!(ordered_trie_root::<Vec<u8>>(&[]), empty_trie_root());
+    }
+
+    #[test]
+    fn hex_prefix_encoding() {
+        assert_eq!(hex_prefix(&[1, 2, 3, 4, 5], false), vec![0x11, 0x23, 0x45]);
+        assert_eq!(
+            hex_prefix(&[0, 1, 2, 3, 4, 5], false),
+            vec![0x00, 0x01, 0x23, 0x45]
+        );
+        assert_eq!(
+            hex_prefix(&[0, 0xf, 1, 0xc, 0xb, 8], true),
+            vec![0x20, 0x0f, 0x1c, 0xb8]
+        );
+        assert_eq!(
+            hex_prefix(&[0xf, 1, 0xc, 0xb, 8], true),
+            vec![0x3f, 0x1c, 0xb8]
+        );
+    }
+
+    #[test]
+    fn ordered_trie() {
+        // Single value stored in a leaf at the root.
+        let value = vec![0xaa; 40];
+        let nodes = ordered_trie_nodes(&[&value]);
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0].kind, TrieNodeKind::Leaf);
+        let mut stream = RlpStream::new_list(2);
+        stream.append(&[0x20u8, 0x80].as_slice());
+        stream.append(&value.as_slice());
+        assert_eq!(nodes[0].rlp, stream.out().to_vec());
+
+        // Keys 0x80, 0x01, 0x02 branch at the first nibble.
+        let values: Vec<_> = (0..200u8).map(|i| vec![i; 40]).collect();
+        let nodes = ordered_trie_nodes(&values);
+        assert!(nodes[0].path.is_empty());
+        assert_eq!(nodes[0].kind, TrieNodeKind::Branch);
+        let leaves: Vec<_> = nodes
+            .iter()
+            .filter(|node| node.kind == TrieNodeKind::Leaf)
+            .collect();
+        assert_eq!(leaves.len(), values.len());
+        for (index, leaf) in leaves.iter().enumerate() {
+            assert_eq!(leaf.index, Some(index));
+            assert!(to_nibbles(&ordered_trie_key(index)).starts_with(&leaf.path));
+        }
+        // Every node but the root is referenced by hash from another node.
+        for node in nodes.iter().skip(1) {
+            let hash = node.hash();
+            assert!(nodes
+                .iter()
+                .any(|parent| parent.rlp.windows(32).any(|w| w == hash.as_bytes())));
+        }
+    }
+}
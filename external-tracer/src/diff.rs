@@ -0,0 +1,220 @@
+//! Differential checking of the traces of two tracers.
+
+use crate::{TraceConfig, Tracer};
+use eth_types::{Error, GethExecStep, GethExecTrace};
+use std::fmt;
+
+/// First difference found between two lists of traces of the same
+/// transactions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    /// Index of the transaction whose traces diverge.
+    pub tx_index: usize,
+    /// Index of the diverging step in the struct logs, `None` when the traces
+    /// only diverge in the execution result.
+    pub step_index: Option<usize>,
+    /// Name of the diverging field.
+    pub field: &'static str,
+    /// Value of the field in the left hand side trace.
+    pub lhs: String,
+    /// Value of the field in the right hand side trace.
+    pub rhs: String,
+    /// Left hand side step, to locate the divergence in the bytecode.
+    pub step: Option<GethExecStep>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "tx {}", self.tx_index)?;
+        if let Some(step_index) = self.step_index {
+            write!(f, " step {step_index}")?;
+        }
+        if let Some(step) = &self.step {
+            write!(f, " (pc 0x{:04x} {:?})", step.pc, step.op)?;
+        }
+        write!(
+            f,
+            ": {} differs\n  lhs: {}\n  rhs: {}",
+            self.field, self.lhs, self.rhs
+        )
+    }
+}
+
+/// Traces the config with both tracers and returns the first divergence
+/// between their traces, if any.
+pub fn diff_tracers(
+    config: &TraceConfig,
+    lhs: &impl Tracer,
+    rhs: &impl Tracer,
+) -> Result<Option<Divergence>, Error> {
+    Ok(compare_traces(&lhs.trace(config)?, &rhs.trace(config)?))
+}
+
+/// Returns the first divergence between two lists of traces of the same
+/// transactions.  The struct logs are compared step by step before the
+/// execution results, so that the divergence points to its cause.
+pub fn compare_traces(lhs: &[GethExecTrace], rhs: &[GethExecTrace]) -> Option<Divergence> {
+    if lhs.len() != rhs.len() {
+        return Some(Divergence {
+            tx_index: lhs.len().min(rhs.len()),
+            step_index: None,
+            field: "traces",
+            lhs: format!("{} traces", lhs.len()),
+            rhs: format!("{} traces", rhs.len()),
+            step: None,
+        });
+    }
+    lhs.iter()
+        .zip(rhs.iter())
+        .enumerate()
+        .find_map(|(tx_index, (lhs, rhs))| compare_trace(tx_index, lhs, rhs))
+}
+
+fn compare_trace(tx_index: usize, lhs: &GethExecTrace, rhs: &GethExecTrace) -> Option<Divergence> {
+    let divergence = |step_index, field, lhs: String, rhs: String, step: Option<&GethExecStep>| {
+        Some(Divergence {
+            tx_index,
+            step_index,
+            field,
+            lhs,
+            rhs,
+            step: step.cloned(),
+        })
+    };
+
+    for (step_index, (l, r)) in lhs
+        .struct_logs
+        .iter()
+        .zip(rhs.struct_logs.iter())
+        .enumerate()
+    {
+        if let Some((field, lhs, rhs)) = compare_step(l, r) {
+            return divergence(Some(step_index), field, lhs, rhs, Some(l));
+        }
+    }
+    let (lhs_len, rhs_len) = (lhs.struct_logs.len(), rhs.struct_logs.len());
+    if lhs_len != rhs_len {
+        return divergence(
+            Some(lhs_len.min(rhs_len)),
+            "struct_logs",
+            format!("{lhs_len} steps"),
+            format!("{rhs_len} steps"),
+            lhs.struct_logs.get(rhs_len),
+        );
+    }
+
+    macro_rules! compare_fields {
+        ($($field:ident),*) => {
+            $(
+                if lhs.$field != rhs.$field {
+                    return divergence(
+                        None,
+                        stringify!($field),
+                        format!("{:?}", lhs.$field),
+                        format!("{:?}", rhs.$field),
+                        None,
+                    );
+                }
+            )*
+        };
+    }
+    compare_fields!(gas, failed, invalid, return_value);
+    None
+}
+
+// Returns the name and values of the first field that differs between the
+// steps.
+fn compare_step(lhs: &GethExecStep, rhs: &GethExecStep) -> Option<(&'static str, String, String)> {
+    macro_rules! compare_fields {
+        ($($field:ident),*) => {
+            $(
+                if lhs.$field != rhs.$field {
+                    return Some((
+                        stringify!($field),
+                        format!("{:?}", lhs.$field),
+                        format!("{:?}", rhs.$field),
+                    ));
+                }
+            )*
+        };
+    }
+    compare_fields!(pc, op, gas, gas_cost, depth, error, stack, memory, storage, refund);
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eth_types::{
+        evm_types::{OpcodeId, Storage},
+        Word,
+    };
+
+    fn step(pc: u64, op: OpcodeId, gas: u64, stack: Vec<Word>) -> GethExecStep {
+        GethExecStep {
+            pc,
+            op,
+            gas,
+            gas_cost: 3,
+            refund: 0,
+            depth: 1,
+            error: None,
+            stack: stack.into(),
+            memory: Default::default(),
+            storage: Storage::empty(),
+        }
+    }
+
+    fn trace(struct_logs: Vec<GethExecStep>) -> GethExecTrace {
+        GethExecTrace {
+            gas: 21006,
+            failed: false,
+            invalid: false,
+            return_value: String::new(),
+            struct_logs,
+        }
+    }
+
+    fn push_push_steps(second: Word) -> Vec<GethExecStep> {
+        vec![
+            step(0, OpcodeId::PUSH1, 100, vec![]),
+            step(2, OpcodeId::PUSH1, 97, vec![Word::from(1)]),
+            step(4, OpcodeId::STOP, 94, vec![Word::from(1), second]),
+        ]
+    }
+
+    #[test]
+    fn identical_traces() {
+        let traces = vec![trace(push_push_steps(Word::from(2)))];
+        assert_eq!(compare_traces(&traces, &traces.clone()), None);
+    }
+
+    #[test]
+    fn first_diverging_step() {
+        let lhs = vec![trace(push_push_steps(Word::from(2)))];
+        let mut rhs = vec![trace(push_push_steps(Word::from(3)))];
+        rhs[0].gas = 0;
+
+        let divergence = compare_traces(&lhs, &rhs).unwrap();
+        assert_eq!(divergence.tx_index, 0);
+        assert_eq!(divergence.step_index, Some(2));
+        assert_eq!(divergence.field, "stack");
+        assert_eq!(divergence.step.unwrap().op, OpcodeId::STOP);
+    }
+
+    #[test]
+    fn missing_steps_and_results() {
+        let lhs = vec![trace(push_push_steps(Word::from(2)))];
+        let mut rhs = lhs.clone();
+        rhs[0].struct_logs.pop();
+        let divergence = compare_traces(&lhs, &rhs).unwrap();
+        assert_eq!(divergence.step_index, Some(2));
+        assert_eq!(divergence.field, "struct_logs");
+
+        let mut rhs = lhs.clone();
+        rhs[0].failed = true;
+        let divergence = compare_traces(&lhs, &rhs).unwrap();
+        assert_eq!(divergence.step_index, None);
+        assert_eq!(divergence.field, "failed");
+    }
+}
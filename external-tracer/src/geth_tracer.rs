@@ -0,0 +1,24 @@
+//! Tracer backed by go-ethereum through the `geth-utils` FFI.
+
+use crate::{check_invalid_txs, TraceConfig, Tracer};
+use eth_types::{Error, GethExecTrace};
+
+/// Tracer that serializes the [`TraceConfig`] to JSON and runs it in geth.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct GethTracer;
+
+impl Tracer for GethTracer {
+    fn trace(&self, config: &TraceConfig) -> Result<Vec<GethExecTrace>, Error> {
+        // Get the trace
+        let trace_string = geth_utils::trace(&serde_json::to_string(&config).unwrap()).map_err(
+            |error| match error {
+                geth_utils::Error::TracingError(error) => Error::TracingError(error),
+            },
+        )?;
+
+        let trace: Vec<GethExecTrace> =
+            serde_json::from_str(&trace_string).map_err(Error::SerdeError)?;
+        check_invalid_txs(&trace)?;
+        Ok(trace)
+    }
+}
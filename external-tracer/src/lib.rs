@@ -61,19 +61,44 @@ impl LoggerConfig {
     }
 }
 
-/// Creates a trace for the specified config
+/// A backend that executes the transactions of a [`TraceConfig`] and returns
+/// one geth-style struct logs trace per transaction.
+pub trait Tracer {
+    /// Creates a trace for the specified config
+    fn trace(&self, config: &TraceConfig) -> Result<Vec<GethExecTrace>, Error>;
+}
+
+pub mod diff;
+#[cfg(feature = "geth")]
+mod geth_tracer;
+#[cfg(feature = "revm")]
+mod revm_tracer;
+
+#[cfg(feature = "geth")]
+pub use geth_tracer::GethTracer;
+#[cfg(feature = "revm")]
+pub use revm_tracer::RevmTracer;
+
+#[cfg(not(any(feature = "geth", feature = "revm")))]
+compile_error!("external-tracer requires the `geth` or the `revm` feature");
+
+/// Tracer used by [`trace`]: geth when the `geth` feature is enabled, revm
+/// otherwise.
+#[cfg(feature = "geth")]
+pub type DefaultTracer = GethTracer;
+/// Tracer used by [`trace`]: geth when the `geth` feature is enabled, revm
+/// otherwise.
+#[cfg(all(feature = "revm", not(feature = "geth")))]
+pub type DefaultTracer = RevmTracer;
+
+/// Creates a trace for the specified config with the [`DefaultTracer`]
 pub fn trace(config: &TraceConfig) -> Result<Vec<GethExecTrace>, Error> {
-    // Get the trace
-    let trace_string = geth_utils::trace(&serde_json::to_string(&config).unwrap()).map_err(
-        |error| match error {
-            geth_utils::Error::TracingError(error) => Error::TracingError(error),
-        },
-    )?;
+    DefaultTracer::default().trace(config)
+}
 
-    let trace: Vec<GethExecTrace> =
-        serde_json::from_str(&trace_string).map_err(Error::SerdeError)?;
-    // Don't throw only for specific invalid transactions we support.
-    for trace in trace.iter() {
+// Don't throw only for specific invalid transactions we support.
+fn check_invalid_txs(traces: &[GethExecTrace]) -> Result<(), Error> {
+    for trace in traces.iter() {
         let error = &trace.return_value;
         let allowed_cases = error.starts_with("nonce too low")
             || error.starts_with("nonce too high")
@@ -83,5 +108,5 @@ pub fn trace(config: &TraceConfig) -> Result<Vec<GethExecTrace>, Error> {
             return Err(Error::TracingError(error.clone()));
         }
     }
-    Ok(trace)
+    Ok(())
 }
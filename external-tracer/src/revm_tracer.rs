@@ -0,0 +1,534 @@
+//! Tracer backed by revm, which doesn't need a Go toolchain.
+//!
+//! The transactions are executed with the same setup as `geth-utils`, and an
+//! [`Inspector`] records the steps the way geth's `StructLogger` does, so that
+//! both tracers emit identical [`GethExecTrace`]s.
+
+use crate::{check_invalid_txs, LoggerConfig, TraceConfig, Tracer};
+use eth_types::{
+    evm_types::{Hardfork, Memory, OpcodeId, Stack, Storage},
+    geth_types::Account,
+    Address, Error, GethExecStep, GethExecTrace, ToBigEndian, Word,
+};
+use revm::{
+    db::{CacheDB, EmptyDB},
+    interpreter::{CallInputs, CreateInputs, Gas, InstructionResult, Interpreter},
+    primitives::{
+        AccountInfo, Address as RevmAddress, Bytecode, Bytes as RevmBytes, EVMError,
+        ExecutionResult, InvalidTransaction, SpecId, TransactTo, B256, U256,
+    },
+    Database, EVMData, Inspector, EVM,
+};
+use std::collections::HashMap;
+
+/// Tracer that executes the [`TraceConfig`] in revm.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RevmTracer;
+
+impl Tracer for RevmTracer {
+    fn trace(&self, config: &TraceConfig) -> Result<Vec<GethExecTrace>, Error> {
+        let txs_gas_limit: u64 = config
+            .transactions
+            .iter()
+            .map(|tx| tx.gas_limit.as_u64())
+            .sum();
+        let block_gas_limit = config.block_constants.gas_limit.low_u64();
+        if txs_gas_limit > block_gas_limit {
+            return Err(Error::TracingError(format!(
+                "txs total gas: {txs_gas_limit} Exceeds block gas limit: {block_gas_limit}"
+            )));
+        }
+
+        let mut evm = EVM::new();
+        evm.database(state_db(config));
+        setup_env(&mut evm.env, config);
+
+        let mut traces = Vec::with_capacity(config.transactions.len());
+        for tx in config.transactions.iter() {
+            let env_tx = &mut evm.env.tx;
+            env_tx.caller = to_revm_address(tx.from);
+            env_tx.transact_to = match tx.to {
+                Some(to) => TransactTo::Call(to_revm_address(to)),
+                None => TransactTo::create(),
+            };
+            env_tx.nonce = Some(tx.nonce.as_u64());
+            env_tx.gas_limit = tx.gas_limit.as_u64();
+            // Like in geth-utils, the gas price is always specified directly, so
+            // the tx is treated as legacy type.
+            env_tx.gas_price = to_revm_word(tx.gas_price);
+            env_tx.gas_priority_fee = None;
+            env_tx.value = to_revm_word(tx.value);
+            env_tx.data = tx.call_data.to_vec().into();
+            env_tx.access_list = tx
+                .access_list
+                .iter()
+                .flat_map(|access_list| access_list.0.iter())
+                .map(|item| {
+                    (
+                        to_revm_address(item.address),
+                        item.storage_keys
+                            .iter()
+                            .map(|key| U256::from_be_bytes(key.to_fixed_bytes()))
+                            .collect(),
+                    )
+                })
+                .collect();
+            env_tx.blob_hashes = tx
+                .blob_versioned_hashes
+                .iter()
+                .map(|hash| B256::from(hash.to_fixed_bytes()))
+                .collect();
+            env_tx.max_fee_per_blob_gas = tx
+                .is_blob_tx()
+                .then(|| to_revm_word(tx.max_fee_per_blob_gas));
+
+            let mut logger = StructLogger::new(&config.logger_config);
+            let trace = match evm.inspect_commit(&mut logger) {
+                Ok(result) => {
+                    let (failed, output) = match &result {
+                        ExecutionResult::Success { output, .. } => (false, output.data().clone()),
+                        ExecutionResult::Revert { output, .. } => (true, output.clone()),
+                        ExecutionResult::Halt { .. } => (true, RevmBytes::default()),
+                    };
+                    GethExecTrace {
+                        gas: result.gas_used(),
+                        failed,
+                        invalid: false,
+                        return_value: hex::encode(output),
+                        struct_logs: logger.struct_logs,
+                    }
+                }
+                Err(EVMError::Transaction(error)) => GethExecTrace {
+                    gas: 0,
+                    failed: true,
+                    invalid: true,
+                    return_value: invalid_tx_error(tx.from, error),
+                    struct_logs: vec![],
+                },
+                Err(error) => return Err(Error::TracingError(format!("{error:?}"))),
+            };
+            traces.push(trace);
+        }
+
+        check_invalid_txs(&traces)?;
+        Ok(traces)
+    }
+}
+
+fn setup_env(env: &mut revm::primitives::Env, config: &TraceConfig) {
+    let block_constants = &config.block_constants;
+
+    env.cfg.chain_id = config.chain_id.low_u64();
+    env.cfg.spec_id = match block_constants.hardfork {
+        Hardfork::Berlin => SpecId::BERLIN,
+        Hardfork::London => SpecId::LONDON,
+        Hardfork::Shanghai => SpecId::SHANGHAI,
+        Hardfork::Cancun => SpecId::CANCUN,
+    };
+    // geth-utils runs with `NoBaseFee`, so that txs with a zero gas price
+    // are accepted.
+    env.cfg.disable_base_fee = true;
+
+    env.block.number = U256::from(block_constants.number.as_u64());
+    env.block.coinbase = to_revm_address(block_constants.coinbase);
+    env.block.timestamp = to_revm_word(block_constants.timestamp);
+    env.block.gas_limit = to_revm_word(block_constants.gas_limit);
+    env.block.basefee = to_revm_word(block_constants.base_fee);
+    env.block.difficulty = to_revm_word(block_constants.difficulty);
+    // For opcode PREVRANDAO, the difficulty is one of MixHash or Difficulty.
+    env.block.prevrandao = Some(B256::from(block_constants.difficulty.to_be_bytes()));
+    if block_constants.hardfork >= Hardfork::Cancun {
+        env.block
+            .set_blob_excess_gas_and_price(block_constants.excess_blob_gas.as_u64());
+    }
+}
+
+/// Setup state db with accounts and history hashes from the config.
+fn state_db(config: &TraceConfig) -> CacheDB<EmptyDB> {
+    let mut db = CacheDB::new(EmptyDB::default());
+
+    // The latest history hash is the one of the parent block.
+    let number = config.block_constants.number.as_u64();
+    let oldest = number.saturating_sub(config.history_hashes.len() as u64);
+    for (n, hash) in (oldest..number).zip(config.history_hashes.iter()) {
+        db.block_hashes
+            .insert(U256::from(n), B256::from(hash.to_be_bytes()));
+    }
+
+    for (address, account) in config.accounts.iter() {
+        insert_account(&mut db, *address, account);
+    }
+    db
+}
+
+fn insert_account(db: &mut CacheDB<EmptyDB>, address: Address, account: &Account) {
+    let address = to_revm_address(address);
+    let code = Bytecode::new_raw(account.code.to_vec().into());
+    db.insert_account_info(
+        address,
+        AccountInfo {
+            balance: to_revm_word(account.balance),
+            nonce: account.nonce.as_u64(),
+            code_hash: code.hash_slow(),
+            code: Some(code),
+        },
+    );
+    for (key, value) in account.storage.iter() {
+        db.insert_account_storage(address, to_revm_word(*key), to_revm_word(*value))
+            .expect("CacheDB with EmptyDB is infallible");
+    }
+}
+
+// Formats the errors the same way as geth, which we rely on to detect the
+// invalid txs we support.
+fn invalid_tx_error(from: Address, error: InvalidTransaction) -> String {
+    match error {
+        InvalidTransaction::NonceTooLow { tx, state } => {
+            format!("nonce too low: address {from:?}, tx: {tx} state: {state}")
+        }
+        InvalidTransaction::NonceTooHigh { tx, state } => {
+            format!("nonce too high: address {from:?}, tx: {tx} state: {state}")
+        }
+        InvalidTransaction::CallGasCostMoreThanGasLimit => "intrinsic gas too low".to_string(),
+        InvalidTransaction::LackOfFundForMaxFee { fee, balance } => format!(
+            "insufficient funds for gas * price + value: address {from:?} have {balance} want {fee}"
+        ),
+        error => format!("{error:?}"),
+    }
+}
+
+/// Step being executed, whose struct log is completed once the step ends.
+struct PendingStep {
+    index: usize,
+    gas: u64,
+    stack_len: usize,
+    // Key of the storage slot read by SLOAD, whose value is only known at the
+    // end of the step.
+    sload_key: Option<Word>,
+}
+
+/// [`Inspector`] that records the steps like geth's `StructLogger`.
+struct StructLogger {
+    config: LoggerConfig,
+    struct_logs: Vec<GethExecStep>,
+    pending_steps: Vec<PendingStep>,
+    // Refund counter of each call frame, as geth reports the accumulated one.
+    refunds: Vec<i64>,
+    // Storage slots accessed by SLOAD and SSTORE of each contract.
+    storage: HashMap<RevmAddress, HashMap<Word, Word>>,
+    // Gas returned by the last call or create, and gas forwarded to the last
+    // create, which geth doesn't include in the gas cost of CREATE/CREATE2.
+    returned_gas: u64,
+    create_gas: u64,
+}
+
+impl StructLogger {
+    fn new(config: &LoggerConfig) -> Self {
+        Self {
+            config: config.clone(),
+            struct_logs: Vec::new(),
+            pending_steps: Vec::new(),
+            refunds: Vec::new(),
+            storage: HashMap::new(),
+            returned_gas: 0,
+            create_gas: 0,
+        }
+    }
+}
+
+impl<DB: Database> Inspector<DB> for StructLogger {
+    fn step(&mut self, interp: &mut Interpreter<'_>, data: &mut EVMData<'_, DB>) {
+        let op = OpcodeId::from(interp.current_opcode());
+        let depth = data.journaled_state.depth() as usize;
+        self.refunds.resize(depth, 0);
+        self.refunds[depth - 1] = interp.gas.refunded();
+
+        let stack = interp.stack.data();
+        let mut sload_key = None;
+        let mut storage = Storage::empty();
+        if !self.config.disable_storage {
+            let contract_storage = self.storage.entry(interp.contract.address).or_default();
+            let peek = |n: usize| to_word(stack[stack.len() - 1 - n]);
+            match op {
+                OpcodeId::SLOAD if !stack.is_empty() => {
+                    sload_key = Some(peek(0));
+                }
+                OpcodeId::SSTORE if stack.len() >= 2 => {
+                    contract_storage.insert(peek(0), peek(1));
+                    storage = Storage::new(contract_storage.clone());
+                }
+                _ => {}
+            }
+        }
+
+        self.pending_steps.push(PendingStep {
+            index: self.struct_logs.len(),
+            gas: interp.gas.remaining(),
+            stack_len: stack.len(),
+            sload_key,
+        });
+        self.struct_logs.push(GethExecStep {
+            pc: interp.program_counter() as u64,
+            op,
+            gas: interp.gas.remaining(),
+            gas_cost: 0,
+            refund: self.refunds.iter().sum::<i64>() as u64,
+            depth: depth as u16,
+            error: None,
+            stack: if self.config.disable_stack {
+                Stack::from(vec![])
+            } else {
+                Stack::from(stack.iter().copied().map(to_word).collect::<Vec<_>>())
+            },
+            memory: if self.config.enable_memory {
+                Memory::from(interp.shared_memory.context_memory().to_vec())
+            } else {
+                Memory::default()
+            },
+            storage,
+        });
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter<'_>, _data: &mut EVMData<'_, DB>) {
+        let pending = self.pending_steps.pop().expect("step_end without step");
+        let returned_gas = std::mem::take(&mut self.returned_gas);
+        let create_gas = std::mem::take(&mut self.create_gas);
+        let step = &mut self.struct_logs[pending.index];
+
+        // CALL-like steps include the gas forwarded to the callee in the cost,
+        // while CREATE-like steps don't.
+        step.gas_cost = (pending.gas + returned_gas)
+            .saturating_sub(interp.gas.remaining())
+            .saturating_sub(create_gas);
+
+        if let Some(key) = pending.sload_key {
+            if let Some(value) = interp.stack.data().last() {
+                let contract_storage = self.storage.entry(interp.contract.address).or_default();
+                contract_storage.insert(key, to_word(*value));
+                step.storage = Storage::new(contract_storage.clone());
+            }
+        }
+
+        step.error = step_error(interp.instruction_result, pending.stack_len);
+    }
+
+    fn call_end(
+        &mut self,
+        _data: &mut EVMData<'_, DB>,
+        _inputs: &CallInputs,
+        remaining_gas: Gas,
+        ret: InstructionResult,
+        out: RevmBytes,
+    ) -> (InstructionResult, Gas, RevmBytes) {
+        self.returned_gas = remaining_gas.remaining();
+        (ret, remaining_gas, out)
+    }
+
+    fn create(
+        &mut self,
+        _data: &mut EVMData<'_, DB>,
+        inputs: &mut CreateInputs,
+    ) -> (InstructionResult, Option<RevmAddress>, Gas, RevmBytes) {
+        self.create_gas = inputs.gas_limit;
+        (
+            InstructionResult::Continue,
+            None,
+            Gas::new(0),
+            RevmBytes::default(),
+        )
+    }
+
+    fn create_end(
+        &mut self,
+        _data: &mut EVMData<'_, DB>,
+        _inputs: &CreateInputs,
+        ret: InstructionResult,
+        address: Option<RevmAddress>,
+        remaining_gas: Gas,
+        out: RevmBytes,
+    ) -> (InstructionResult, Option<RevmAddress>, Gas, RevmBytes) {
+        self.returned_gas = remaining_gas.remaining();
+        (ret, address, remaining_gas, out)
+    }
+}
+
+// Returns the error geth reports for a step that halted before being executed.
+fn step_error(result: InstructionResult, stack_len: usize) -> Option<String> {
+    Some(match result {
+        InstructionResult::OutOfGas
+        | InstructionResult::MemoryOOG
+        | InstructionResult::MemoryLimitOOG
+        | InstructionResult::PrecompileOOG
+        | InstructionResult::InvalidOperandOOG => "out of gas".to_string(),
+        InstructionResult::StackUnderflow => format!("stack underflow ({stack_len})"),
+        InstructionResult::StackOverflow => format!("stack limit reached {stack_len} (1024)"),
+        InstructionResult::InvalidJump => "invalid jump destination".to_string(),
+        InstructionResult::StateChangeDuringStaticCall => "write protection".to_string(),
+        InstructionResult::OutOfOffset => "return data out of bounds".to_string(),
+        InstructionResult::OpcodeNotFound
+        | InstructionResult::NotActivated
+        | InstructionResult::InvalidFEOpcode => "invalid opcode".to_string(),
+        InstructionResult::CreateInitcodeSizeLimit => "max initcode size exceeded".to_string(),
+        _ => return None,
+    })
+}
+
+fn to_revm_address(address: Address) -> RevmAddress {
+    RevmAddress::from(address.to_fixed_bytes())
+}
+
+fn to_revm_word(word: Word) -> U256 {
+    U256::from_be_bytes(word.to_be_bytes())
+}
+
+fn to_word(word: U256) -> Word {
+    Word::from_big_endian(&word.to_be_bytes::<32>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eth_types::{geth_types::Transaction, U64};
+
+    #[test]
+    fn minimal_call_tx() {
+        let from = Address::repeat_byte(0xfe);
+        let config = TraceConfig {
+            block_constants: eth_types::geth_types::BlockConstants {
+                gas_limit: Word::from(0x52080),
+                ..Default::default()
+            },
+            accounts: HashMap::from([(
+                from,
+                Account {
+                    address: from,
+                    balance: Word::from(10).pow(20.into()),
+                    ..Default::default()
+                },
+            )]),
+            transactions: vec![Transaction {
+                from,
+                to: Some(Address::repeat_byte(0xff)),
+                gas_limit: U64::from(21000),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let traces = RevmTracer.trace(&config).unwrap();
+        assert_eq!(traces.len(), 1);
+        assert_eq!(traces[0].gas, 21000);
+        assert!(!traces[0].failed);
+        assert!(traces[0].struct_logs.is_empty());
+    }
+
+    #[test]
+    fn struct_logs_of_contract_call() {
+        let from = Address::repeat_byte(0xfe);
+        let to = Address::repeat_byte(0xff);
+        let code = eth_types::bytecode! {
+            PUSH1(0x2a)
+            PUSH1(0)
+            MSTORE
+            PUSH1(1)
+            PUSH1(0)
+            SSTORE
+            PUSH1(0)
+            SLOAD
+            STOP
+        };
+        let config = TraceConfig {
+            block_constants: eth_types::geth_types::BlockConstants {
+                gas_limit: Word::from(0x52080),
+                ..Default::default()
+            },
+            accounts: HashMap::from([
+                (
+                    from,
+                    Account {
+                        address: from,
+                        balance: Word::from(10).pow(20.into()),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    to,
+                    Account {
+                        address: to,
+                        code: code.into(),
+                        ..Default::default()
+                    },
+                ),
+            ]),
+            transactions: vec![Transaction {
+                from,
+                to: Some(to),
+                gas_limit: U64::from(100_000),
+                ..Default::default()
+            }],
+            logger_config: LoggerConfig::enable_memory(),
+            ..Default::default()
+        };
+
+        let step = |pc: u64, op: OpcodeId, gas: u64, gas_cost: u64, stack: Vec<u64>| GethExecStep {
+            pc,
+            op,
+            gas,
+            gas_cost,
+            refund: 0,
+            depth: 1,
+            error: None,
+            stack: Stack::from(stack.into_iter().map(Word::from).collect::<Vec<_>>()),
+            memory: Memory::default(),
+            storage: Storage::empty(),
+        };
+        let mut memory = vec![0; 32];
+        memory[31] = 0x2a;
+        let storage = Storage::new(HashMap::from([(Word::zero(), Word::one())]));
+        let mut expected = vec![
+            step(0, OpcodeId::PUSH1, 79000, 3, vec![]),
+            step(2, OpcodeId::PUSH1, 78997, 3, vec![0x2a]),
+            step(4, OpcodeId::MSTORE, 78994, 6, vec![0x2a, 0]),
+            step(5, OpcodeId::PUSH1, 78988, 3, vec![]),
+            step(7, OpcodeId::PUSH1, 78985, 3, vec![1]),
+            // Cold slot set from zero
+            step(9, OpcodeId::SSTORE, 78982, 22100, vec![1, 0]),
+            step(10, OpcodeId::PUSH1, 56882, 3, vec![]),
+            // Warm slot
+            step(12, OpcodeId::SLOAD, 56879, 100, vec![0]),
+            step(13, OpcodeId::STOP, 56779, 0, vec![1]),
+        ];
+        for step in expected[3..].iter_mut() {
+            step.memory = Memory::from(memory.clone());
+        }
+        expected[5].storage = storage.clone();
+        expected[7].storage = storage;
+
+        let traces = RevmTracer.trace(&config).unwrap();
+        assert_eq!(traces.len(), 1);
+        assert_eq!(traces[0].gas, 100_000 - 56779);
+        assert!(!traces[0].failed);
+        assert_eq!(traces[0].struct_logs, expected);
+    }
+
+    #[test]
+    fn nonce_too_high_is_allowed() {
+        let config = TraceConfig {
+            block_constants: eth_types::geth_types::BlockConstants {
+                gas_limit: Word::from(0x52080),
+                ..Default::default()
+            },
+            transactions: vec![Transaction {
+                from: Address::repeat_byte(0xfe),
+                nonce: U64::from(1),
+                gas_limit: U64::from(21000),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let traces = RevmTracer.trace(&config).unwrap();
+        assert!(traces[0].invalid);
+        assert!(traces[0].return_value.starts_with("nonce too high"));
+    }
+}
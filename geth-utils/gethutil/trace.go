@@ -89,6 +89,7 @@ type Block struct {
 	Difficulty *hexutil.Big   `json:"difficulty"`
 	GasLimit   *hexutil.Big   `json:"gas_limit"`
 	BaseFee    *hexutil.Big   `json:"base_fee"`
+	Hardfork   string         `json:"hardfork"`
 }
 
 type Account struct {
@@ -156,6 +157,20 @@ func Trace(config TraceConfig) ([]*ExecutionResult, error) {
 		TerminalTotalDifficultyPassed: true,
 	}
 
+	// Activate the hardforks up to the one the block is executed with.
+	switch config.Block.Hardfork {
+	case "berlin":
+		chainConfig.LondonBlock = nil
+		chainConfig.ShanghaiTime = nil
+	case "london":
+		chainConfig.ShanghaiTime = nil
+	case "shanghai":
+	case "cancun":
+		chainConfig.CancunTime = newUint64(0)
+	default:
+		return nil, fmt.Errorf("unsupported hardfork %q", config.Block.Hardfork)
+	}
+
 	var txsGasLimit uint64
 	blockGasLimit := toBigInt(config.Block.GasLimit).Uint64()
 	messages := make([]core.Message, len(config.Transactions))
@@ -0,0 +1,85 @@
+//! Dump the witness bundle of a block from the integration test geth instance,
+//! or load a witness bundle and generate the circuit inputs of its block
+//! without network access.
+//!
+//! Usage:
+//! - `witness_bundle dump <block_num> <circuits_params.json> <bundle.json>`
+//! - `witness_bundle load <bundle.json>`
+
+use bus_mapping::{
+    circuit_input_builder::{BuilderClient, FixedCParams},
+    witness_bundle::WitnessBundle,
+};
+use integration_tests::{get_client, log_init};
+use log::info;
+use std::{
+    env,
+    fs::File,
+    io::{BufReader, BufWriter},
+    process,
+};
+
+const USAGE: &str = "usage:
+  witness_bundle dump <block_num> <circuits_params.json> <bundle.json>
+  witness_bundle load <bundle.json>";
+
+async fn dump(block_num: &str, params_path: &str, bundle_path: &str) {
+    let block_num: u64 = block_num.parse().expect("invalid block number");
+    let circuits_params: FixedCParams = serde_json::from_reader(BufReader::new(
+        File::open(params_path).expect("cannot read circuits params file"),
+    ))
+    .expect("cannot deserialize circuits params");
+
+    let cli = BuilderClient::new(get_client(), circuits_params)
+        .await
+        .expect("cannot create builder client");
+    let bundle = cli
+        .gen_witness_bundle(block_num)
+        .await
+        .expect("cannot generate witness bundle");
+    bundle
+        .write(BufWriter::new(
+            File::create(bundle_path).expect("cannot create bundle file"),
+        ))
+        .expect("cannot write witness bundle");
+    info!(
+        "Witness bundle of block {} written to {}",
+        block_num, bundle_path
+    );
+}
+
+fn load(bundle_path: &str) {
+    let bundle = WitnessBundle::read(BufReader::new(
+        File::open(bundle_path).expect("cannot read bundle file"),
+    ))
+    .expect("cannot read witness bundle");
+    let builder = bundle.gen_inputs().expect("cannot generate circuit inputs");
+    info!(
+        "Circuit inputs of block {:?} generated: {} txs, {} steps",
+        bundle.eth_block.number,
+        builder.block.txs().len(),
+        builder
+            .block
+            .txs()
+            .iter()
+            .map(|tx| tx.steps().len())
+            .sum::<usize>()
+    );
+}
+
+#[tokio::main]
+async fn main() {
+    log_init();
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["dump", block_num, params_path, bundle_path] => {
+            dump(block_num, params_path, bundle_path).await
+        }
+        ["load", bundle_path] => load(bundle_path),
+        _ => {
+            eprintln!("{USAGE}");
+            process::exit(1);
+        }
+    }
+}
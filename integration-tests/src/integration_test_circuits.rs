@@ -1,7 +1,8 @@
-use crate::{get_client, GenDataOutput};
+use crate::{get_client, GenDataOutput, GETH0_URL};
 use bus_mapping::{
     circuit_input_builder::{BuilderClient, CircuitInputBuilder, FixedCParams},
     mock::BlockData,
+    precompile::PrecompileEcParams,
 };
 use eth_types::geth_types::GethData;
 use halo2_proofs::{
@@ -24,6 +25,7 @@ use halo2_proofs::{
 };
 use lazy_static::lazy_static;
 use mock::TestContext;
+use mpt_witness_generator::get_block_witness;
 use rand_chacha::rand_core::SeedableRng;
 use rand_xorshift::XorShiftRng;
 use std::{collections::HashMap, marker::PhantomData, sync::Mutex};
@@ -66,9 +68,32 @@ const MAX_EVM_ROWS: usize = 10000;
 const MAX_EXP_STEPS: usize = 1000;
 
 const MAX_KECCAK_ROWS: usize = 38000;
+/// MAX_ECRECOVER
+const MAX_ECRECOVER: usize = 0;
+/// MAX_SHA256_ROWS
+const MAX_SHA256_ROWS: usize = 2000;
+/// MAX_RIPEMD160_ROWS
+const MAX_RIPEMD160_ROWS: usize = 2000;
+/// MAX_MODEXP
+const MAX_MODEXP: usize = 1;
+/// MAX_EC_OPS
+const MAX_EC_OPS: PrecompileEcParams = PrecompileEcParams {
+    ec_add: 0,
+    ec_mul: 0,
+    ec_pairing: 0,
+};
+/// MAX_BLAKE2F_ROWS
+const MAX_BLAKE2F_ROWS: usize = 2000;
+/// MAX_POINT_EVALUATIONS
+const MAX_POINT_EVALUATIONS: usize = 1;
+/// MAX_LOG_BYTES
+const MAX_LOG_BYTES: usize = 5000;
+/// MAX_MPT_ROWS
+const MAX_MPT_ROWS: usize = 50000;
 
 const CIRCUITS_PARAMS: FixedCParams = FixedCParams {
     max_rws: MAX_RWS,
+    max_blocks: 1,
     max_txs: MAX_TXS,
     max_withdrawals: MAX_WITHDRAWALS,
     max_calldata: MAX_CALLDATA,
@@ -77,6 +102,15 @@ const CIRCUITS_PARAMS: FixedCParams = FixedCParams {
     max_evm_rows: MAX_EVM_ROWS,
     max_exp_steps: MAX_EXP_STEPS,
     max_keccak_rows: MAX_KECCAK_ROWS,
+    max_ecrecover: MAX_ECRECOVER,
+    max_sha256_rows: MAX_SHA256_ROWS,
+    max_ripemd160_rows: MAX_RIPEMD160_ROWS,
+    max_modexp: MAX_MODEXP,
+    max_ec_ops: MAX_EC_OPS,
+    max_blake2f_rows: MAX_BLAKE2F_ROWS,
+    max_point_evaluations: MAX_POINT_EVALUATIONS,
+    max_log_bytes: MAX_LOG_BYTES,
+    max_mpt_rows: MAX_MPT_ROWS,
 };
 
 const EVM_CIRCUIT_DEGREE: u32 = 18;
@@ -415,6 +449,8 @@ impl<C: SubCircuit<Fr> + Circuit<Fr>> IntegrationTest<C> {
         );
         let mut block = block_convert(&builder).unwrap();
         block.randomness = Fr::from(TEST_MOCK_RANDOMNESS);
+        let mpt_witness = get_block_witness(block_num, &block.mpt_updates, &GETH0_URL);
+        block.set_mpt_witness(mpt_witness);
         let circuit = C::new_from_block(&block);
         let instance = circuit.instance();
 
@@ -1,7 +1,8 @@
 #![cfg(feature = "circuit_input_builder")]
 
-use bus_mapping::circuit_input_builder::{
-    build_state_code_db, get_state_accesses, BuilderClient, FixedCParams,
+use bus_mapping::{
+    circuit_input_builder::{build_state_code_db, get_state_accesses, BuilderClient, FixedCParams},
+    precompile::PrecompileEcParams,
 };
 use integration_tests::{get_client, log_init, GenDataOutput};
 use lazy_static::lazy_static;
@@ -17,6 +18,7 @@ async fn test_circuit_input_builder_block(block_num: u64) {
         cli,
         FixedCParams {
             max_rws: 16384,
+            max_blocks: 1,
             max_txs: 1,
             max_withdrawals: 1,
             max_calldata: 4000,
@@ -25,6 +27,15 @@ async fn test_circuit_input_builder_block(block_num: u64) {
             max_evm_rows: 0,
             max_exp_steps: 1000,
             max_keccak_rows: 0,
+            max_ecrecover: 0,
+            max_sha256_rows: 0,
+            max_ripemd160_rows: 0,
+            max_modexp: 0,
+            max_ec_ops: PrecompileEcParams::default(),
+            max_blake2f_rows: 0,
+            max_point_evaluations: 0,
+            max_log_bytes: 512,
+            max_mpt_rows: 0,
         },
     )
     .await
@@ -19,9 +19,9 @@ use halo2_proofs::{
 };
 
 use zkevm_circuits::{
-    mpt_circuit::{MPTCircuit, MPTCircuitParams, MPTConfig},
+    mpt_circuit::{MPTCircuit, MPTCircuitConfigArgs, MPTCircuitParams, MPTConfig},
     table::{KeccakTable, MptTable},
-    util::{word, Challenges},
+    util::{word, Challenges, SubCircuitConfig},
 };
 
 use super::witness::{
@@ -31,7 +31,7 @@ use super::witness::{
 #[cfg(not(feature = "disable-keccak"))]
 use zkevm_circuits::{
     keccak_circuit::{KeccakCircuit, KeccakCircuitConfig, KeccakCircuitConfigArgs},
-    util::{SubCircuit, SubCircuitConfig},
+    util::SubCircuit,
 };
 
 pub const DEFAULT_MAX_PROOF_COUNT: usize = 20;
@@ -107,13 +107,32 @@ impl<F: Field> Circuit<F> for StateUpdateCircuit<F> {
                 challenges: challenges_expr.clone(),
             },
         );
-        let mpt_config = MPTConfig::new(meta, challenges_expr, keccak_table, params);
+        let mpt_table = MptTable {
+            q_enable: meta.fixed_column(),
+            address: meta.advice_column(),
+            storage_key: word::Word::new([meta.advice_column(), meta.advice_column()]),
+            proof_type: meta.advice_column(),
+            new_root: word::Word::new([meta.advice_column(), meta.advice_column()]),
+            old_root: word::Word::new([meta.advice_column(), meta.advice_column()]),
+            new_value: word::Word::new([meta.advice_column(), meta.advice_column()]),
+            old_value: word::Word::new([meta.advice_column(), meta.advice_column()]),
+        };
+        let mpt_config = MPTConfig::new(
+            meta,
+            MPTCircuitConfigArgs {
+                mpt_table,
+                keccak_table,
+                challenges: challenges_expr,
+                params,
+            },
+        );
 
         let is_first = meta.fixed_column();
         let count = meta.advice_column();
         let q_enable = meta.complex_selector();
         let pi_instance = meta.instance_column();
         let pi_mpt = MptTable {
+            q_enable: meta.fixed_column(),
             address: meta.advice_column(),
             storage_key: word::Word::new([meta.advice_column(), meta.advice_column()]),
             proof_type: meta.advice_column(),
@@ -244,22 +263,21 @@ impl<F: Field> Circuit<F> for StateUpdateCircuit<F> {
                     meta.query_advice(pi_mpt.storage_key.hi(), Rotation::cur()),
                     meta.query_advice(mpt_config.mpt_table.storage_key.hi(), Rotation::cur()),
                 ),
-                // TODO: MPT_table new/old roots are reversed
                 (
                     meta.query_advice(pi_mpt.old_root.lo(), Rotation::cur()),
-                    meta.query_advice(mpt_config.mpt_table.new_root.lo(), Rotation::cur()),
+                    meta.query_advice(mpt_config.mpt_table.old_root.lo(), Rotation::cur()),
                 ),
                 (
                     meta.query_advice(pi_mpt.old_root.hi(), Rotation::cur()),
-                    meta.query_advice(mpt_config.mpt_table.new_root.hi(), Rotation::cur()),
+                    meta.query_advice(mpt_config.mpt_table.old_root.hi(), Rotation::cur()),
                 ),
                 (
                     meta.query_advice(pi_mpt.new_root.lo(), Rotation::cur()),
-                    meta.query_advice(mpt_config.mpt_table.old_root.lo(), Rotation::cur()),
+                    meta.query_advice(mpt_config.mpt_table.new_root.lo(), Rotation::cur()),
                 ),
                 (
                     meta.query_advice(pi_mpt.new_root.hi(), Rotation::cur()),
-                    meta.query_advice(mpt_config.mpt_table.old_root.hi(), Rotation::cur()),
+                    meta.query_advice(mpt_config.mpt_table.new_root.hi(), Rotation::cur()),
                 ),
             ];
 
@@ -301,10 +319,12 @@ impl<F: Field> Circuit<F> for StateUpdateCircuit<F> {
 
         // assign MPT witness
 
-        let height =
-            config
-                .mpt_config
-                .assign(&mut layouter, &self.mpt_circuit.nodes, &challenges)?;
+        let height = config.mpt_config.assign(
+            &mut layouter,
+            &self.mpt_circuit.nodes,
+            &challenges,
+            self.mpt_circuit.max_rows,
+        )?;
         config.mpt_config.load_fixed_table(&mut layouter)?;
         config
             .mpt_config
@@ -490,6 +510,7 @@ impl StateUpdateCircuit<Fr> {
             keccak_data: keccak_data.clone(),
             degree,
             disable_preimage_check,
+            max_rows: 0,
             _marker: std::marker::PhantomData,
         };
 
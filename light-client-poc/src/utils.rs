@@ -151,6 +151,7 @@ pub fn verify_mpt_witness(nodes: Vec<Node>) -> Result<()> {
         keccak_data,
         degree,
         disable_preimage_check,
+        max_rows: 0,
         _marker: std::marker::PhantomData,
     };
 
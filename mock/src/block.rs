@@ -4,11 +4,14 @@ use crate::{
     withdrawal::MockWithdrawal, MockTransaction, MOCK_BASEFEE, MOCK_CHAIN_ID, MOCK_DIFFICULTY,
     MOCK_GASLIMIT,
 };
-use eth_types::{Address, Block, Bytes, Hash, Transaction, Word, H64, U64};
-use ethers_core::{
-    types::{Bloom, OtherFields},
-    utils::keccak256,
+use eth_types::{
+    evm_types::Hardfork,
+    geth_types::{
+        blob_block_other_fields, transactions_root, withdrawals_root, BlockHeader, Withdrawal,
+    },
+    Address, Block, Bytes, Hash, Transaction, Word, H64, U64,
 };
+use ethers_core::{types::Bloom, utils::keccak256};
 
 #[derive(Clone, Debug)]
 /// Mock structure which represents an Ethereum Block and can be used for tests.
@@ -22,7 +25,7 @@ pub struct MockBlock {
     uncles_hash: Hash,
     author: Address,
     state_root: Hash,
-    transactions_root: Hash,
+    transactions_root: Option<Hash>,
     receipts_root: Hash,
     logs_bloom: Option<Bloom>,
     difficulty: Word,
@@ -35,6 +38,7 @@ pub struct MockBlock {
     nonce: H64,
     base_fee_per_gas: Option<Word>, // London upgrade, EIP-1559
     withdrawal_hash: Option<Hash>,  // Shanghai upgrade, EIP-4895
+    excess_blob_gas: Option<u64>,   // Cancun upgrade, EIP-4844
     // Other information
     total_difficulty: Word,
     seal_fields: Vec<Bytes>,
@@ -46,18 +50,21 @@ pub struct MockBlock {
     // Also, the field is stored in the block_table since we don't have a chain_config
     // structure/table.
     pub(crate) chain_id: Word,
+    // The hardfork is not part of the block header, so it's handled here and passed to the
+    // tracer alongside the chain id.
+    pub(crate) hardfork: Hardfork,
 }
 
 impl Default for MockBlock {
     fn default() -> Self {
         MockBlock {
-            hash: Some(Hash::zero()),
+            hash: None,
             // Header
             parent_hash: Hash::zero(),
             uncles_hash: Hash::zero(),
             author: Address::zero(),
             state_root: Hash::zero(),
-            transactions_root: Hash::zero(),
+            transactions_root: None,
             receipts_root: Hash::zero(),
             logs_bloom: None,
             difficulty: *MOCK_DIFFICULTY,
@@ -70,6 +77,7 @@ impl Default for MockBlock {
             nonce: H64::zero(),
             base_fee_per_gas: Some(*MOCK_BASEFEE),
             withdrawal_hash: None,
+            excess_blob_gas: None,
             // Other information
             total_difficulty: Word::zero(),
             seal_fields: Vec::new(),
@@ -78,20 +86,21 @@ impl Default for MockBlock {
             withdrawals: Vec::new(),
             size: Word::zero(),
             chain_id: *MOCK_CHAIN_ID,
+            hardfork: Hardfork::default(),
         }
     }
 }
 
 impl From<MockBlock> for Block<Transaction> {
     fn from(mut mock: MockBlock) -> Self {
-        Block {
-            hash: mock.hash.or_else(|| Some(Hash::default())),
+        let mut block = Block {
+            hash: mock.hash,
             // Header
             parent_hash: mock.parent_hash,
             uncles_hash: mock.uncles_hash,
             author: Some(mock.author),
             state_root: mock.state_root,
-            transactions_root: mock.transactions_root,
+            transactions_root: mock.tx_trie_root(),
             receipts_root: mock.receipts_root,
             logs_bloom: mock.logs_bloom,
             difficulty: mock.difficulty,
@@ -113,28 +122,36 @@ impl From<MockBlock> for Block<Transaction> {
                 .map(|mock_tx| (mock_tx.chain_id(mock.chain_id).to_owned()).into())
                 .collect::<Vec<Transaction>>(),
             size: Some(mock.size),
-            other: OtherFields::default(),
-            withdrawals_root: mock.withdrawal_hash,
+            other: mock
+                .excess_blob_gas
+                .map(blob_block_other_fields)
+                .unwrap_or_default(),
+            withdrawals_root: mock.withdrawals_trie_root(),
             withdrawals: Some(
                 mock.withdrawals
                     .iter_mut()
                     .map(|mock_wd| mock_wd.to_owned().into())
                     .collect(),
             ),
-        }
+        };
+        // Unless given, the block hash is the one of the header in the hardfork of the block.
+        block.hash = block
+            .hash
+            .or_else(|| Some(BlockHeader::from(&block).hash(mock.hardfork)));
+        block
     }
 }
 
 impl From<MockBlock> for Block<()> {
     fn from(mock: MockBlock) -> Self {
-        Block {
-            hash: mock.hash.or_else(|| Some(Hash::default())),
+        let mut block = Block {
+            hash: mock.hash,
             // Header
             parent_hash: mock.parent_hash,
             uncles_hash: mock.uncles_hash,
             author: Some(mock.author),
             state_root: mock.state_root,
-            transactions_root: mock.transactions_root,
+            transactions_root: mock.tx_trie_root(),
             receipts_root: mock.receipts_root,
             logs_bloom: mock.logs_bloom,
             difficulty: mock.difficulty,
@@ -152,27 +169,65 @@ impl From<MockBlock> for Block<()> {
             uncles: mock.uncles,
             transactions: vec![],
             size: Some(mock.size),
-            other: OtherFields::default(),
-            withdrawals_root: mock.withdrawal_hash,
+            other: mock
+                .excess_blob_gas
+                .map(blob_block_other_fields)
+                .unwrap_or_default(),
+            withdrawals_root: mock.withdrawals_trie_root(),
             withdrawals: Some(
                 mock.withdrawals
                     .iter()
                     .map(|mock_wd| mock_wd.to_owned().into())
                     .collect(),
             ),
-        }
+        };
+        // Unless given, the block hash is the one of the header in the hardfork of the block.
+        block.hash = block
+            .hash
+            .or_else(|| Some(BlockHeader::from(&block).hash(mock.hardfork)));
+        block
     }
 }
 
 impl MockBlock {
+    /// Return the transactions root of the block, which unless given is the
+    /// root of the trie of its transactions.
+    fn tx_trie_root(&self) -> Hash {
+        self.transactions_root.unwrap_or_else(|| {
+            let txs: Vec<Transaction> = self
+                .transactions
+                .iter()
+                .map(|mock_tx| mock_tx.clone().chain_id(self.chain_id).to_owned().into())
+                .collect();
+            transactions_root(self.chain_id.as_u64(), &txs)
+                .expect("transactions of the block are supported")
+        })
+    }
+
+    /// Return the withdrawals root of the block, which unless given is the
+    /// root of the trie of its withdrawals from the Shanghai upgrade on.
+    fn withdrawals_trie_root(&self) -> Option<Hash> {
+        self.withdrawal_hash.or_else(|| {
+            (self.hardfork >= Hardfork::Shanghai).then(|| {
+                let withdrawals: Vec<Withdrawal> = self
+                    .withdrawals
+                    .iter()
+                    .map(|mock_wd| mock_wd.clone().into())
+                    .collect();
+                withdrawals_root(&withdrawals)
+            })
+        })
+    }
+
     /// Compute the hash of the block's header
     // For more details, look at https://ethereum.stackexchange.com/questions/67055/block-header-hash-verification?noredirect=1&lq=1
     // and add "withdrawalRoot" at the end for Shanghai blocks
     pub fn hash(&mut self) -> &mut Self {
+        let withdrawals_root = self.withdrawals_trie_root();
         let block_hash = {
             let mut stream = ethers_core::utils::rlp::RlpStream::new();
             // We encode the BaseFee only for upgrades higher or equal to London
-            let list_length: usize = match (self.base_fee_per_gas, self.withdrawal_hash) {
+            let list_length: usize = match (self.base_fee_per_gas, withdrawals_root) {
                 (Some(_), Some(_)) => 17,
                 (Some(_), None) => 16,
                 (None, Some(_)) => panic!("withdrawalsRoot given, baseFeePerGas missing"),
@@ -183,7 +238,7 @@ impl MockBlock {
             stream.append(&self.uncles_hash);
             stream.append(&self.author);
             stream.append(&self.state_root);
-            stream.append(&self.transactions_root);
+            stream.append(&self.tx_trie_root());
             stream.append(&self.receipts_root);
             stream.append(&self.logs_bloom.unwrap()); //
             stream.append(&self.difficulty);
@@ -198,8 +253,8 @@ impl MockBlock {
                 Some(base_fee_per_gas) => stream.append(&base_fee_per_gas),
                 _ => &mut stream,
             };
-            match self.withdrawal_hash {
-                Some(withdrawal_hash) => stream.append(&withdrawal_hash),
+            match withdrawals_root {
+                Some(withdrawals_root) => stream.append(&withdrawals_root),
                 _ => &mut stream,
             };
             let rlp_encoding = stream.out().to_vec();
@@ -236,7 +291,7 @@ impl MockBlock {
 
     /// Set transactions_root field for the MockBlock.
     pub fn transactions_root(&mut self, transactions_root: Hash) -> &mut Self {
-        self.transactions_root = transactions_root;
+        self.transactions_root = Some(transactions_root);
         self
     }
 
@@ -312,6 +367,12 @@ impl MockBlock {
         self
     }
 
+    /// Set excess_blob_gas field for the MockBlock.
+    pub fn excess_blob_gas(&mut self, excess_blob_gas: Option<u64>) -> &mut Self {
+        self.excess_blob_gas = excess_blob_gas;
+        self
+    }
+
     /// Set total_difficulty field for the MockBlock.
     pub fn total_difficulty(&mut self, total_difficulty: Word) -> &mut Self {
         self.total_difficulty = total_difficulty;
@@ -351,6 +412,12 @@ impl MockBlock {
         self
     }
 
+    /// Set hardfork field for the MockBlock.
+    pub fn hardfork(&mut self, hardfork: Hardfork) -> &mut Self {
+        self.hardfork = hardfork;
+        self
+    }
+
     /// Finalizes the current MockBlock under construction returning a new
     /// instance to it.
     pub fn build(&mut self) -> Self {
@@ -361,7 +428,9 @@ impl MockBlock {
 #[cfg(test)]
 mod block_tests {
     use crate::MockBlock;
-    use eth_types::{Address, Bytes, Hash, Word, H64};
+    use eth_types::{
+        evm_types::Hardfork, geth_types::BlockHeader, Address, Block, Bytes, Hash, Word, H64,
+    };
     use ethers_core::types::Bloom;
     use std::str::FromStr;
 
@@ -411,6 +480,11 @@ mod block_tests {
             Hash::from_str("0x5d15649e25d8f3e2c0374946078539d200710afc977cdfc6a977bd23f20fa8e8")
                 .unwrap();
         assert!(expected_hash.eq(&mock_block.hash.unwrap()));
+        let block: Block<()> = mock_block.clone().into();
+        assert_eq!(
+            BlockHeader::from(&block).hash(Hardfork::Berlin),
+            expected_hash
+        );
 
         // Checking block from London upgrade
         // curl -X POST --data '{"id":1,"jsonrpc":2.0,"method":"eth_getBlockByNumber","params":["0x1000000",false]}' https://mainnet.infura.io/v3/<API_KEY>
@@ -456,6 +530,11 @@ mod block_tests {
             Hash::from_str("0xb1214baed59ee19bce48b3a2df4d9c485848ac91ac3cb286298f93a274eecd3c")
                 .unwrap();
         assert!(expected_hash.eq(&mock_block.hash.unwrap()));
+        let block: Block<()> = mock_block.clone().into();
+        assert_eq!(
+            BlockHeader::from(&block).hash(Hardfork::London),
+            expected_hash
+        );
 
         // Checking block from Shanghai upgrade
         // curl -X POST --data '{"id":1,"jsonrpc":2.0,"method":"eth_getBlockByNumber","params":["0x10a7606",false]}' https://mainnet.infura.io/v3/<API_KEY>
@@ -505,5 +584,10 @@ mod block_tests {
             Hash::from_str("0xd15d1ed6795d3f5cc849c2f19fc1c7350c8ab816c6b832ff639e5de00893f249")
                 .unwrap();
         assert!(expected_hash.eq(&mock_block.hash.unwrap()));
+        let block: Block<()> = mock_block.clone().into();
+        assert_eq!(
+            BlockHeader::from(&block).hash(Hardfork::Shanghai),
+            expected_hash
+        );
     }
 }
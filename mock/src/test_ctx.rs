@@ -1,10 +1,14 @@
 //! Mock types and functions to generate Test environments for ZKEVM tests
 
-use crate::{eth, MockAccount, MockBlock, MockTransaction, TestContext2};
+use crate::{
+    eth, test_ctx2::diff_geth_traces, MockAccount, MockBlock, MockTransaction, TestContext2,
+};
 use eth_types::{
+    evm_types::Hardfork,
     geth_types::{Account, GethData},
     Bytecode, Error, Word,
 };
+use external_tracer::{diff::Divergence, Tracer};
 use helpers::*;
 
 pub use external_tracer::LoggerConfig;
@@ -77,6 +81,8 @@ pub use external_tracer::LoggerConfig;
 pub struct TestContext<const NACC: usize, const NTX: usize> {
     /// chain id
     pub chain_id: Word,
+    /// hardfork
+    pub hardfork: Hardfork,
     /// Account list
     pub accounts: [Account; NACC],
     /// history hashes contains most recent 256 block hashes in history, where
@@ -92,6 +98,7 @@ impl<const NACC: usize, const NTX: usize> From<TestContext<NACC, NTX>> for GethD
     fn from(ctx: TestContext<NACC, NTX>) -> GethData {
         GethData {
             chain_id: ctx.chain_id,
+            hardfork: ctx.hardfork,
             history_hashes: ctx.history_hashes,
             eth_block: ctx.eth_block,
             geth_traces: ctx.geth_traces.to_vec(),
@@ -124,6 +131,7 @@ impl<const NACC: usize, const NTX: usize> TestContext<NACC, NTX> {
 
         Ok(Self {
             chain_id: test_ctx2.chain_id,
+            hardfork: test_ctx2.hardfork,
             accounts: test_ctx2.accounts,
             history_hashes: test_ctx2.history_hashes.clone(),
             eth_block: test_ctx2.eth_block,
@@ -157,6 +165,24 @@ impl<const NACC: usize, const NTX: usize> TestContext<NACC, NTX> {
         )
     }
 
+    /// Re-executes the transactions of the context with the `lhs` and `rhs`
+    /// tracers and returns the first divergence between their traces.
+    pub fn diff_tracers(
+        &self,
+        lhs: &impl Tracer,
+        rhs: &impl Tracer,
+    ) -> Result<Option<Divergence>, Error> {
+        diff_geth_traces(
+            self.chain_id,
+            self.hardfork,
+            &self.eth_block,
+            &self.accounts,
+            self.history_hashes.clone(),
+            lhs,
+            rhs,
+        )
+    }
+
     /// Returns a simple TestContext setup with a single tx executing the
     /// bytecode passed as parameters. The balances of the 2 accounts and
     /// addresses are the ones used in [`TestContext::
@@ -243,4 +269,27 @@ mod tests {
         assert_eq!(block.accounts[0].nonce, U64::from(0));
         assert_eq!(block.accounts[1].nonce, U64::from(100));
     }
+
+    #[cfg(all(feature = "geth-tracer", feature = "revm-tracer"))]
+    #[test]
+    fn test_diff_tracers() {
+        use eth_types::bytecode;
+        use external_tracer::{GethTracer, RevmTracer};
+
+        let code = bytecode! {
+            PUSH1(0x20)
+            PUSH1(0x40)
+            MSTORE
+            PUSH1(0x01)
+            PUSH1(0x02)
+            SSTORE
+            PUSH1(0x02)
+            SLOAD
+            STOP
+        };
+        let ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap();
+        if let Some(divergence) = ctx.diff_tracers(&GethTracer, &RevmTracer).unwrap() {
+            panic!("{divergence}");
+        }
+    }
 }
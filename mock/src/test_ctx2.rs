@@ -2,10 +2,14 @@
 
 use crate::{withdrawal::MockWithdrawal, MockAccount, MockBlock, MockTransaction};
 use eth_types::{
+    evm_types::Hardfork,
     geth_types::{Account, BlockConstants, GethData, Withdrawal},
-    Block, Error, GethExecTrace, Transaction, Word,
+    Block, Error, GethExecTrace, Hash, ToBigEndian, Transaction, Word,
+};
+use external_tracer::{
+    diff::{diff_tracers, Divergence},
+    trace, TraceConfig, Tracer,
 };
-use external_tracer::{trace, TraceConfig};
 use itertools::Itertools;
 
 pub use external_tracer::LoggerConfig;
@@ -19,6 +23,8 @@ pub use external_tracer::LoggerConfig;
 pub struct TestContext2<const NACC: usize, const NTX: usize, const NWD: usize> {
     /// chain id
     pub chain_id: Word,
+    /// hardfork
+    pub hardfork: Hardfork,
     /// Account list
     pub accounts: [Account; NACC],
     /// history hashes contains most recent 256 block hashes in history, where
@@ -36,6 +42,7 @@ impl<const NACC: usize, const NTX: usize, const NWD: usize> From<TestContext2<NA
     fn from(ctx: TestContext2<NACC, NTX, NWD>) -> GethData {
         GethData {
             chain_id: ctx.chain_id,
+            hardfork: ctx.hardfork,
             history_hashes: ctx.history_hashes,
             eth_block: ctx.eth_block,
             geth_traces: ctx.geth_traces.to_vec(),
@@ -112,9 +119,15 @@ impl<const NACC: usize, const NTX: usize, const NWD: usize> TestContext2<NACC, N
         let mut block = MockBlock::default();
         block.transactions.extend_from_slice(&transactions);
         block.withdrawals.extend_from_slice(&withdrawals);
+        // The parent of the block is the latest of the history hashes, unless
+        // overridden by `func_block`.
+        if let Some(parent_hash) = history_hashes.as_ref().and_then(|hashes| hashes.last()) {
+            block.parent_hash(Hash::from(parent_hash.to_be_bytes()));
+        }
         func_block(&mut block, transactions.clone()).build();
 
         let chain_id = block.chain_id;
+        let hardfork = block.hardfork;
         let block = Block::<Transaction>::from(block);
         let accounts: [Account; NACC] = accounts
             .iter()
@@ -134,6 +147,7 @@ impl<const NACC: usize, const NTX: usize, const NWD: usize> TestContext2<NACC, N
 
         let geth_traces = gen_geth_traces(
             chain_id,
+            hardfork,
             block.clone(),
             accounts.to_vec(),
             withdrawals.to_vec(),
@@ -159,6 +173,7 @@ impl<const NACC: usize, const NTX: usize, const NWD: usize> TestContext2<NACC, N
 
         Ok(Self {
             chain_id,
+            hardfork,
             accounts,
             history_hashes: history_hashes.unwrap_or_default(),
             eth_block: block,
@@ -194,22 +209,92 @@ impl<const NACC: usize, const NTX: usize, const NWD: usize> TestContext2<NACC, N
             LoggerConfig::default(),
         )
     }
+
+    /// Re-executes the transactions of the context with the `lhs` and `rhs`
+    /// tracers and returns the first divergence between their traces.
+    pub fn diff_tracers(
+        &self,
+        lhs: &impl Tracer,
+        rhs: &impl Tracer,
+    ) -> Result<Option<Divergence>, Error> {
+        diff_geth_traces(
+            self.chain_id,
+            self.hardfork,
+            &self.eth_block,
+            &self.accounts,
+            self.history_hashes.clone(),
+            lhs,
+            rhs,
+        )
+    }
 }
 
 /// Generates execution traces for the transactions included in the provided
 /// Block
 pub fn gen_geth_traces(
     chain_id: Word,
+    hardfork: Hardfork,
     block: Block<Transaction>,
     accounts: Vec<Account>,
     withdrawals: Vec<Withdrawal>,
     history_hashes: Option<Vec<Word>>,
     logger_config: LoggerConfig,
 ) -> Result<Vec<GethExecTrace>, Error> {
-    let trace_config = TraceConfig {
+    let trace_config = gen_trace_config(
+        chain_id,
+        hardfork,
+        &block,
+        &accounts,
+        withdrawals,
+        history_hashes,
+        logger_config,
+    )?;
+    let traces = trace(&trace_config)?;
+    Ok(traces)
+}
+
+/// Traces the transactions included in the provided Block with two tracers,
+/// capturing the memory, and returns the first divergence between their
+/// traces.
+pub fn diff_geth_traces(
+    chain_id: Word,
+    hardfork: Hardfork,
+    block: &Block<Transaction>,
+    accounts: &[Account],
+    history_hashes: Vec<Word>,
+    lhs: &impl Tracer,
+    rhs: &impl Tracer,
+) -> Result<Option<Divergence>, Error> {
+    // Withdrawals are processed after the transactions, so they don't change
+    // the traces.
+    let trace_config = gen_trace_config(
+        chain_id,
+        hardfork,
+        block,
+        accounts,
+        vec![],
+        Some(history_hashes),
+        LoggerConfig::enable_memory(),
+    )?;
+    diff_tracers(&trace_config, lhs, rhs)
+}
+
+fn gen_trace_config(
+    chain_id: Word,
+    hardfork: Hardfork,
+    block: &Block<Transaction>,
+    accounts: &[Account],
+    withdrawals: Vec<Withdrawal>,
+    history_hashes: Option<Vec<Word>>,
+    logger_config: LoggerConfig,
+) -> Result<TraceConfig, Error> {
+    Ok(TraceConfig {
         chain_id,
         history_hashes: history_hashes.unwrap_or_default(),
-        block_constants: BlockConstants::try_from(&block)?,
+        block_constants: BlockConstants {
+            hardfork,
+            ..BlockConstants::try_from(block)?
+        },
         accounts: accounts
             .iter()
             .map(|account| (account.address, account.clone()))
@@ -221,7 +306,5 @@ pub fn gen_geth_traces(
             .collect(),
         withdrawals,
         logger_config,
-    };
-    let traces = trace(&trace_config)?;
-    Ok(traces)
+    })
 }
@@ -2,8 +2,10 @@
 
 use super::{MOCK_ACCOUNTS, MOCK_CHAIN_ID, MOCK_GASPRICE};
 use eth_types::{
-    geth_types::Transaction as GethTransaction, word, AccessList, Address, Bytes, Hash,
-    Transaction, Word, U64,
+    geth_types::{
+        blob_tx_other_fields, Transaction as GethTransaction, BLOB_TX_TYPE, LEGACY_TX_TYPE,
+    },
+    keccak256, word, AccessList, Address, Bytes, Hash, Transaction, Word, H256, U64,
 };
 use ethers_core::{
     rand::{CryptoRng, RngCore},
@@ -135,6 +137,8 @@ pub struct MockTransaction {
     pub access_list: AccessList,
     pub max_priority_fee_per_gas: Word,
     pub max_fee_per_gas: Word,
+    pub max_fee_per_blob_gas: Word,
+    pub blob_versioned_hashes: Vec<Hash>,
     pub chain_id: Word,
     pub invalid: bool,
 }
@@ -160,6 +164,8 @@ impl Default for MockTransaction {
             access_list: AccessList::default(),
             max_priority_fee_per_gas: Word::zero(),
             max_fee_per_gas: Word::zero(),
+            max_fee_per_blob_gas: Word::zero(),
+            blob_versioned_hashes: Vec::new(),
             chain_id: *MOCK_CHAIN_ID,
             invalid: false,
         }
@@ -188,7 +194,11 @@ impl From<MockTransaction> for Transaction {
             max_priority_fee_per_gas: Some(mock.max_priority_fee_per_gas),
             max_fee_per_gas: Some(mock.max_fee_per_gas),
             chain_id: Some(mock.chain_id),
-            other: OtherFields::default(),
+            other: if mock.transaction_type.as_u64() == BLOB_TX_TYPE {
+                blob_tx_other_fields(mock.max_fee_per_blob_gas, &mock.blob_versioned_hashes)
+            } else {
+                OtherFields::default()
+            },
         }
     }
 }
@@ -299,6 +309,19 @@ impl MockTransaction {
         self
     }
 
+    /// Set max_fee_per_blob_gas field for the MockTransaction.
+    pub fn max_fee_per_blob_gas(&mut self, max_fee_per_blob_gas: Word) -> &mut Self {
+        self.max_fee_per_blob_gas = max_fee_per_blob_gas;
+        self
+    }
+
+    /// Set blob_versioned_hashes field for the MockTransaction.
+    /// Only carried by transactions of type [`BLOB_TX_TYPE`].
+    pub fn blob_versioned_hashes(&mut self, blob_versioned_hashes: Vec<Hash>) -> &mut Self {
+        self.blob_versioned_hashes = blob_versioned_hashes;
+        self
+    }
+
     /// Set chain_id field for the MockTransaction.
     pub(crate) fn chain_id(&mut self, chain_id: Word) -> &mut Self {
         self.chain_id = chain_id;
@@ -322,12 +345,19 @@ impl MockTransaction {
             (None, None, None) => {
                 // Compute sig params and set them in case we have a wallet as `from` attr.
                 if self.from.is_wallet() && self.hash.is_none() {
-                    let sig = self
-                        .from
-                        .as_wallet()
-                        .with_chain_id(self.chain_id.low_u64())
-                        .sign_transaction_sync(&tx.into())
-                        .unwrap();
+                    let wallet = self.from.as_wallet().with_chain_id(self.chain_id.low_u64());
+                    let sig = if self.transaction_type.as_u64() == LEGACY_TX_TYPE {
+                        wallet.sign_transaction_sync(&tx.into()).unwrap()
+                    } else {
+                        // Typed txs are signed over their EIP-2718 envelope and carry the
+                        // y parity of the signature as `v`.
+                        let msg = GethTransaction::from(self.to_owned())
+                            .rlp_unsigned(self.chain_id.low_u64())
+                            .unwrap();
+                        let mut sig = wallet.sign_hash(H256(keccak256(&msg))).unwrap();
+                        sig.v -= 27;
+                        sig
+                    };
                     // Set sig parameters
                     self.sig_data((sig.v, sig.r, sig.s));
                 }
@@ -6,7 +6,9 @@ use std::{
 };
 
 use num_enum::IntoPrimitive;
-use zkevm_circuits::{mpt_circuit::witness_row::Node, util::U256};
+use zkevm_circuits::{
+    mpt_circuit::witness_row::Node, table::MPTProofType, util::U256, witness::MptUpdates,
+};
 
 mod golang {
     use super::*;
@@ -76,11 +78,7 @@ pub fn get_witness(block_no: u64, mods: &[TrieModification], node_url: &str) ->
         .map(|m| TrieModificationJson {
             typ: m.typ as u8,
             key: m.key,
-            value: {
-                let mut bytes = [0u8; 32];
-                m.value.to_big_endian(&mut bytes);
-                H256::from_slice(&bytes)
-            },
+            value: word_to_h256(m.value),
             address: m.address,
             nonce: m.nonce.as_u64(),
             balance: serde_json::Number::from_string_unchecked(format!("{}", m.balance)),
@@ -123,6 +121,64 @@ pub fn get_witness(block_no: u64, mods: &[TrieModification], node_url: &str) ->
     nodes
 }
 
+fn word_to_h256(word: U256) -> H256 {
+    let mut bytes = [0u8; 32];
+    word.to_big_endian(&mut bytes);
+    H256::from_slice(&bytes)
+}
+
+impl From<MPTProofType> for ProofType {
+    fn from(proof_type: MPTProofType) -> Self {
+        match proof_type {
+            MPTProofType::Disabled => Self::Disabled,
+            MPTProofType::NonceChanged => Self::NonceChanged,
+            MPTProofType::BalanceChanged => Self::BalanceChanged,
+            MPTProofType::CodeHashChanged => Self::CodeHashChanged,
+            MPTProofType::AccountDestructed => Self::AccountDestructed,
+            MPTProofType::AccountDoesNotExist => Self::AccountDoesNotExist,
+            MPTProofType::StorageChanged => Self::StorageChanged,
+            MPTProofType::StorageDoesNotExist => Self::StorageDoesNotExist,
+        }
+    }
+}
+
+/// Get the MPT witness proving the `updates` of block `block_no`, in the order
+/// expected by `Block::set_mpt_witness`.  The proofs start from the state root
+/// of the previous block, so the genesis block can't have updates.
+pub fn get_block_witness(block_no: u64, updates: &MptUpdates, node_url: &str) -> Vec<Node> {
+    if updates.iter().next().is_none() {
+        return Vec::new();
+    }
+    let prev_block_no = block_no
+        .checked_sub(1)
+        .expect("the genesis block has no previous state to update");
+
+    let mods: Vec<_> = updates
+        .iter()
+        .map(|update| {
+            let typ = ProofType::from(update.mpt_proof_type());
+            let new_value = update.new_value();
+            let mut m = TrieModification {
+                typ,
+                address: update.address(),
+                ..Default::default()
+            };
+            match typ {
+                ProofType::NonceChanged => m.nonce = U64::from(new_value.as_u64()),
+                ProofType::BalanceChanged => m.balance = new_value,
+                ProofType::CodeHashChanged => m.code_hash = word_to_h256(new_value),
+                ProofType::StorageChanged | ProofType::StorageDoesNotExist => {
+                    m.key = word_to_h256(update.storage_key());
+                    m.value = new_value;
+                }
+                _ => {}
+            }
+            m
+        })
+        .collect();
+    get_witness(prev_block_no, &mods, node_url)
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
@@ -12,8 +12,8 @@ use compiler::Compiler;
 use config::Config;
 use log::info;
 use statetest::{
-    geth_trace, load_statetests_suite, run_statetests_suite, run_test, CircuitsConfig, Results,
-    StateTest,
+    diff_tracers, geth_trace, load_statetests_suite, run_statetests_suite, run_test,
+    CircuitsConfig, Results, StateTest,
 };
 use std::{collections::HashSet, path::PathBuf, time::SystemTime};
 use strum_macros::EnumString;
@@ -74,6 +74,10 @@ struct Args {
     /// Verbose
     #[clap(short, long)]
     v: bool,
+
+    /// Do not execute the circuits, just compare the traces of geth and revm
+    #[clap(long)]
+    diff_tracers: bool,
 }
 
 fn run_single_test(test: StateTest, circuits_config: CircuitsConfig) -> Result<()> {
@@ -87,6 +91,26 @@ fn run_single_test(test: StateTest, circuits_config: CircuitsConfig) -> Result<(
     Ok(())
 }
 
+// Reports the first divergence between the geth and revm traces of each test,
+// and returns the number of diverging tests.
+fn diff_tests_tracers(tests: Vec<StateTest>) -> usize {
+    let mut diverging = 0;
+    for test in tests {
+        match diff_tracers(test.clone()) {
+            Ok(None) => {}
+            Ok(Some(divergence)) => {
+                diverging += 1;
+                log::error!("{}: {}", test.id, divergence);
+            }
+            Err(err) => {
+                diverging += 1;
+                log::error!("{}: {}", test.id, err);
+            }
+        }
+    }
+    diverging
+}
+
 fn go() -> Result<()> {
     //  RAYON_NUM_THREADS=1 RUST_BACKTRACE=1 cargo run -- --path
     // "tests/src/GeneralStateTestsFiller/**/" --skip-state-circuit
@@ -100,6 +124,12 @@ fn go() -> Result<()> {
 
     if let Some(oneliner) = &args.oneliner {
         let test = StateTest::parse_oneline_spec(oneliner)?;
+        if args.diff_tracers {
+            if diff_tests_tracers(vec![test]) > 0 {
+                bail!("traces diverge");
+            }
+            return Ok(());
+        }
         run_single_test(test, circuits_config)?;
         return Ok(());
     }
@@ -123,6 +153,14 @@ fn go() -> Result<()> {
         }
         return Ok(());
     }
+    if args.diff_tracers {
+        log::info!("Comparing the traces of geth and revm...");
+        let diverging = diff_tests_tracers(state_tests);
+        if diverging > 0 {
+            bail!("traces of {} tests diverge", diverging);
+        }
+        return Ok(());
+    }
     if let Some(test_id) = args.inspect {
         // Test only one and return
         let mut state_tests_filtered: Vec<_> =
@@ -3,14 +3,18 @@ use crate::config::TestSuite;
 use bus_mapping::{
     circuit_input_builder::{CircuitInputBuilder, FixedCParams},
     mock::BlockData,
+    precompile::PrecompileEcParams,
+};
+use eth_types::{
+    evm_types::{blob_base_fee, Hardfork},
+    geth_types, Address, Bytes, Error, GethExecTrace, U256, U64,
 };
-use eth_types::{geth_types, Address, Bytes, Error, GethExecTrace, U256, U64};
 use ethers_core::{
     k256::ecdsa::SigningKey,
     types::{transaction::eip2718::TypedTransaction, TransactionRequest, Withdrawal},
 };
 use ethers_signers::{LocalWallet, Signer};
-use external_tracer::TraceConfig;
+use external_tracer::{diff::Divergence, GethTracer, LoggerConfig, RevmTracer, TraceConfig};
 use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
 use std::{collections::HashMap, str::FromStr};
 use thiserror::Error;
@@ -45,8 +49,6 @@ pub enum StateTestError {
     SkipTestMaxGasLimit(u64),
     #[error("SkipTestMaxSteps({0})")]
     SkipTestMaxSteps(usize),
-    #[error("SkipTestSelfDestruct")]
-    SkipTestSelfDestruct,
     #[error("SkipTestDifficulty")]
     SkipTestDifficulty,
     #[error("SkipTestBalanceOverflow")]
@@ -64,9 +66,7 @@ impl StateTestError {
 
         matches!(
             self,
-            StateTestError::SkipTestMaxSteps(_)
-                | StateTestError::SkipTestMaxGasLimit(_)
-                | StateTestError::SkipTestSelfDestruct
+            StateTestError::SkipTestMaxSteps(_) | StateTestError::SkipTestMaxGasLimit(_)
         )
     }
 }
@@ -168,9 +168,14 @@ fn into_traceconfig(st: StateTest) -> (String, TraceConfig, StateTestResult) {
                 difficulty: st.env.current_difficulty,
                 gas_limit: U256::from(st.env.current_gas_limit),
                 base_fee: st.env.current_base_fee,
+                excess_blob_gas: U64::zero(),
+                blob_base_fee: blob_base_fee(0),
+                // Matches `TEST_FORK`, which selects the expectations of the tests.
+                hardfork: Hardfork::Shanghai,
             },
 
             transactions: vec![geth_types::Transaction {
+                transaction_type: U64::zero(),
                 from: st.from,
                 to: st.to,
                 nonce: U64::from(st.nonce),
@@ -181,6 +186,8 @@ fn into_traceconfig(st: StateTest) -> (String, TraceConfig, StateTestResult) {
                 gas_tip_cap: U256::zero(),
                 call_data: st.data,
                 access_list: None,
+                max_fee_per_blob_gas: U256::zero(),
+                blob_versioned_hashes: vec![],
                 v: sig.v,
                 r: sig.r,
                 s: sig.s,
@@ -201,20 +208,21 @@ pub fn geth_trace(st: StateTest) -> Result<GethExecTrace, StateTestError> {
     Ok(geth_traces.remove(0))
 }
 
+/// Traces the state test with geth and revm and returns the first divergence
+/// between their traces.
+pub fn diff_tracers(st: StateTest) -> Result<Option<Divergence>, StateTestError> {
+    let (_, mut trace_config, _) = into_traceconfig(st);
+    trace_config.logger_config = LoggerConfig::enable_memory();
+
+    external_tracer::diff::diff_tracers(&trace_config, &GethTracer, &RevmTracer)
+        .map_err(|err| StateTestError::CircuitInput(err.to_string()))
+}
+
 fn check_geth_traces(
     geth_traces: &[GethExecTrace],
     suite: &TestSuite,
     verbose: bool,
 ) -> Result<(), StateTestError> {
-    if geth_traces.iter().any(|gt| {
-        gt.struct_logs.iter().any(|sl| {
-            sl.op == eth_types::evm_types::OpcodeId::SELFDESTRUCT
-                || sl.op == eth_types::evm_types::OpcodeId::INVALID(0xff)
-        })
-    }) {
-        return Err(StateTestError::SkipTestSelfDestruct);
-    }
-
     if geth_traces[0].struct_logs.len() as u64 > suite.max_steps {
         return Err(StateTestError::SkipTestMaxSteps(
             geth_traces[0].struct_logs.len(),
@@ -318,6 +326,7 @@ pub fn run_test(
     // process the transaction
     let mut geth_data = eth_types::geth_types::GethData {
         chain_id: trace_config.chain_id,
+        hardfork: trace_config.block_constants.hardfork,
         history_hashes: trace_config.history_hashes.clone(),
         geth_traces: geth_traces.clone(),
         accounts: trace_config.accounts.values().cloned().collect(),
@@ -328,6 +337,7 @@ pub fn run_test(
 
     if !circuits_config.super_circuit {
         let circuits_params = FixedCParams {
+            max_blocks: 1,
             max_txs: 1,
             max_withdrawals: 1,
             max_rws: 55000,
@@ -337,6 +347,15 @@ pub fn run_test(
             max_evm_rows: 0,
             max_exp_steps: 5000,
             max_keccak_rows: 0,
+            max_ecrecover: 0,
+            max_sha256_rows: 0,
+            max_ripemd160_rows: 0,
+            max_modexp: 0,
+            max_ec_ops: PrecompileEcParams::default(),
+            max_blake2f_rows: 0,
+            max_point_evaluations: 0,
+            max_log_bytes: 5000,
+            max_mpt_rows: 0,
         };
         let block_data = BlockData::new_from_geth_data_with_params(geth_data, circuits_params);
 
@@ -366,6 +385,7 @@ pub fn run_test(
         geth_data.sign(&wallets);
 
         let circuits_params = FixedCParams {
+            max_blocks: 1,
             max_txs: 1,
             max_withdrawals: 1,
             max_calldata: 32,
@@ -375,6 +395,15 @@ pub fn run_test(
             max_bytecode: 512,
             max_evm_rows: 0,
             max_keccak_rows: 0,
+            max_ecrecover: 0,
+            max_sha256_rows: 0,
+            max_ripemd160_rows: 0,
+            max_modexp: 0,
+            max_ec_ops: PrecompileEcParams::default(),
+            max_blake2f_rows: 0,
+            max_point_evaluations: 0,
+            max_log_bytes: 512,
+            max_mpt_rows: 0,
         };
         let (k, circuit, instance, _builder) =
             SuperCircuit::<Fr>::build(geth_data, circuits_params, Fr::from(0x100)).unwrap();
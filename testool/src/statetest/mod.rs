@@ -6,7 +6,7 @@ pub mod spec;
 mod suite;
 mod yaml;
 
-pub use executor::{geth_trace, run_test, CircuitsConfig, StateTestError};
+pub use executor::{diff_tracers, geth_trace, run_test, CircuitsConfig, StateTestError};
 pub use json::JsonStateTestBuilder;
 pub use results::{ResultLevel, Results};
 pub use spec::{AccountMatch, Env, StateTest, StateTestResult};
@@ -173,7 +173,6 @@ impl Report {
             .iter()
             .filter(|(_, result)| OUTPUT_ALL_RESULT_LEVELS.contains(&result.level))
             .filter(|(_, result)| result.level != ResultLevel::Success)
-            .filter(|(_, result)| !result.details.starts_with("SkipTestSelfDestruct"))
             // no big test
             .filter(|(_, result)| {
                 !result.details.starts_with("SkipTestMaxGasLimit")
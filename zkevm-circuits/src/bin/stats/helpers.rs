@@ -8,10 +8,15 @@ use cli_table::{
     format::{Justify, Separator},
     print_stdout, Table, WithTitle,
 };
-use eth_types::{bytecode, evm_types::OpcodeId, geth_types::GethData, Address, Bytecode, ToWord};
+use eth_types::{
+    bytecode,
+    evm_types::{Hardfork, OpcodeId},
+    geth_types::GethData,
+    Address, Bytecode, ToWord,
+};
 use mock::{eth, test_ctx::TestContext, MOCK_ACCOUNTS};
 use strum::IntoEnumIterator;
-use zkevm_circuits::evm_circuit::step::ExecutionState;
+use zkevm_circuits::evm_circuit::step::{ExecutionState, ResponsibleOp};
 
 /// Generate the prefix bytecode to trigger a big amount of rw operations
 pub(crate) fn bytecode_prefix_op_big_rws(opcode: OpcodeId) -> Bytecode {
@@ -148,6 +153,13 @@ pub(crate) fn print_circuit_stats_by_states(
             continue;
         }
         for responsible_op in state.responsible_opcodes() {
+            // The test block is executed with the default hardfork.
+            if matches!(
+                responsible_op,
+                ResponsibleOp::InvalidOpcode(_, hardfork) if hardfork != Hardfork::default()
+            ) {
+                continue;
+            }
             let opcode = responsible_op.opcode();
             let mut code = bytecode! {
                 PUSH2(0x00)
@@ -32,12 +32,7 @@ fn main() {
 /// Prints the stats of EVM circuit per execution state.
 fn evm_states_stats() {
     print_circuit_stats_by_states(
-        |state| {
-            !matches!(
-                state,
-                ExecutionState::ErrorInvalidOpcode | ExecutionState::SELFDESTRUCT
-            )
-        },
+        |state| !matches!(state, ExecutionState::ErrorInvalidOpcode),
         |opcode| match opcode {
             OpcodeId::RETURNDATACOPY => {
                 bytecode! {
@@ -66,12 +61,7 @@ fn evm_states_stats() {
 /// Prints the stats of State circuit per execution state.
 fn state_states_stats() {
     print_circuit_stats_by_states(
-        |state| {
-            !matches!(
-                state,
-                ExecutionState::ErrorInvalidOpcode | ExecutionState::SELFDESTRUCT
-            )
-        },
+        |state| !matches!(state, ExecutionState::ErrorInvalidOpcode),
         bytecode_prefix_op_big_rws,
         |block, _, step_index| {
             let step = &block.txs[0].steps()[step_index];
@@ -200,6 +190,20 @@ fn get_exec_steps_occupancy() {
         keccak_table,
         LOOKUP_CONFIG[6].1,
         exp_table,
-        LOOKUP_CONFIG[7].1
+        LOOKUP_CONFIG[7].1,
+        sig_table,
+        LOOKUP_CONFIG[8].1,
+        sha256_table,
+        LOOKUP_CONFIG[9].1,
+        modexp_table,
+        LOOKUP_CONFIG[10].1,
+        ecc_table,
+        LOOKUP_CONFIG[11].1,
+        ripemd160_table,
+        LOOKUP_CONFIG[12].1,
+        blake2f_table,
+        LOOKUP_CONFIG[13].1,
+        kzg_table,
+        LOOKUP_CONFIG[14].1
     );
 }
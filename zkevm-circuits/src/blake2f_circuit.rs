@@ -0,0 +1,950 @@
+//! The blake2f circuit implementation.
+//!
+//! A compression of the BLAKE2F precompile takes segments of
+//! [`NUM_ROWS_PER_SEGMENT`] rows: an input segment parsing the 213 bytes of the
+//! input, a segment per round, and an output segment computing the compressed
+//! state.  Each row of a round segment applies one operation of the mixing
+//! function `G` to the state words `v`, either an addition or a xor followed by
+//! a rotation, the operands being decomposed in bits.  The kind of a segment is
+//! given by the number of rounds left, so that the number of round segments of
+//! a compression is the number of rounds of its input.
+/// Blake2f multi
+pub(crate) mod blake2f_multi;
+mod param;
+/// Util
+mod util;
+
+#[cfg(any(test, feature = "test-circuits"))]
+mod dev;
+#[cfg(test)]
+mod test;
+#[cfg(feature = "test-circuits")]
+pub use dev::Blake2fCircuit as TestBlake2fCircuit;
+
+use std::{array, marker::PhantomData};
+pub use Blake2fCircuitConfig as Blake2fConfig;
+
+use self::{
+    blake2f_multi::{get_num_blake2f_segments, multi_blake2f, Blake2fRow, Blake2fSelectors},
+    param::*,
+    util::*,
+};
+use crate::{
+    evm_circuit::util::{
+        constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+        rlc,
+    },
+    table::{Blake2fTable, LookupTable},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use eth_types::Field;
+use gadgets::util::{not, select, sum, Expr};
+use halo2_proofs::{
+    circuit::{Layouter, Region, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, VirtualCells,
+    },
+    poly::Rotation,
+};
+
+/// Blake2fConfig
+#[derive(Clone, Debug)]
+pub struct Blake2fCircuitConfig<F> {
+    q_enable: Column<Fixed>,
+    q_first: Column<Fixed>,
+    q_seg_start: Column<Fixed>,
+    q_seg_end: Column<Fixed>,
+    q_parse_rounds: Column<Fixed>,
+    q_parse_h: Column<Fixed>,
+    q_parse_m: Column<Fixed>,
+    q_parse_t0: Column<Fixed>,
+    q_parse_t1: Column<Fixed>,
+    q_parse_f: Column<Fixed>,
+    q_dst: [Column<Fixed>; NUM_STATE_WORDS],
+    q_src: [Column<Fixed>; NUM_STATE_WORDS],
+    q_add: Column<Fixed>,
+    q_xor: [Column<Fixed>; ROTATIONS.len()],
+    q_message: [Column<Fixed>; NUM_MESSAGE_WORDS],
+    q_output: [Column<Fixed>; NUM_HASH_WORDS],
+    is_init: Column<Advice>,
+    is_round: Column<Advice>,
+    is_final: Column<Advice>,
+    v: [Column<Advice>; NUM_STATE_WORDS],
+    m: [Column<Advice>; NUM_MESSAGE_WORDS],
+    h: [Column<Advice>; NUM_HASH_WORDS],
+    rounds_left: Column<Advice>,
+    rounds_left_inv: Column<Advice>,
+    permutation: [Column<Advice>; NUM_PERMUTATIONS],
+    dst: [Column<Advice>; NUM_BITS_PER_WORD],
+    src: [Column<Advice>; NUM_BITS_PER_WORD],
+    word: [Column<Advice>; NUM_BITS_PER_WORD],
+    carry: [Column<Advice>; NUM_CARRY_BITS],
+    x: Column<Advice>,
+    result: Column<Advice>,
+    is_valid_inv: Column<Advice>,
+    data_rlcs: [Column<Advice>; NUM_BYTES_PER_WORD],
+    output_acc: Column<Advice>,
+    /// The columns for other circuits to lookup Blake2f compression results
+    pub blake2f_table: Blake2fTable,
+    _marker: PhantomData<F>,
+}
+
+/// Circuit configuration arguments
+pub struct Blake2fCircuitConfigArgs<F: Field> {
+    /// Blake2fTable
+    pub blake2f_table: Blake2fTable,
+    /// Challenges randomness
+    pub challenges: Challenges<Expression<F>>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for Blake2fCircuitConfig<F> {
+    type ConfigArgs = Blake2fCircuitConfigArgs<F>;
+
+    /// Return a new Blake2fCircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            blake2f_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let q_enable = meta.fixed_column();
+        let q_first = meta.fixed_column();
+        let q_seg_start = meta.fixed_column();
+        let q_seg_end = meta.fixed_column();
+        let q_parse_rounds = meta.fixed_column();
+        let q_parse_h = meta.fixed_column();
+        let q_parse_m = meta.fixed_column();
+        let q_parse_t0 = meta.fixed_column();
+        let q_parse_t1 = meta.fixed_column();
+        let q_parse_f = meta.fixed_column();
+        let q_dst = array::from_fn(|_| meta.fixed_column());
+        let q_src = array::from_fn(|_| meta.fixed_column());
+        let q_add = meta.fixed_column();
+        let q_xor = array::from_fn(|_| meta.fixed_column());
+        let q_message = array::from_fn(|_| meta.fixed_column());
+        let q_output = array::from_fn(|_| meta.fixed_column());
+
+        let is_init = meta.advice_column();
+        let is_round = meta.advice_column();
+        let is_final = meta.advice_column();
+        let v = array::from_fn(|_| meta.advice_column());
+        let m = array::from_fn(|_| meta.advice_column());
+        let h = array::from_fn(|_| meta.advice_column());
+        let rounds_left = meta.advice_column();
+        let rounds_left_inv = meta.advice_column();
+        let permutation = array::from_fn(|_| meta.advice_column());
+        let dst = array::from_fn(|_| meta.advice_column());
+        let src = array::from_fn(|_| meta.advice_column());
+        let word = array::from_fn(|_| meta.advice_column());
+        let carry = array::from_fn(|_| meta.advice_column());
+        let x = meta.advice_column();
+        let result = meta.advice_column();
+        let is_valid_inv = meta.advice_column();
+        // The data rlc after the last byte of a row is the one exposed in the table.
+        let data_rlcs = array::from_fn(|idx| {
+            if idx == NUM_BYTES_PER_WORD - 1 {
+                blake2f_table.input_rlc
+            } else {
+                meta.advice_column_in(SecondPhase)
+            }
+        });
+        let output_acc = meta.advice_column_in(SecondPhase);
+        let is_enabled = blake2f_table.is_enabled;
+        let rounds = blake2f_table.rounds;
+        let is_valid = blake2f_table.is_valid;
+        let output_rlc = blake2f_table.output_rlc;
+        let r = challenges.keccak_input();
+
+        let query_bits = |meta: &mut VirtualCells<F>,
+                          columns: &[Column<Advice>; NUM_BITS_PER_WORD]|
+         -> Bits<F> {
+            array::from_fn(|i| meta.query_advice(columns[i], Rotation::cur()))
+        };
+        // The value of the state word selected by `selectors` before the operation.
+        let query_operand =
+            |meta: &mut VirtualCells<F>, selectors: &[Column<Fixed>], words: &[Column<Advice>]| {
+                sum::expr(selectors.iter().zip(words.iter()).map(|(selector, word)| {
+                    meta.query_fixed(*selector, Rotation::cur())
+                        * meta.query_advice(*word, Rotation::prev())
+                }))
+            };
+        let pow_2_64 = Expression::Constant(F::from_u128(1 << NUM_BITS_PER_WORD));
+
+        meta.create_gate("boolean checks", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for column in dst
+                .iter()
+                .chain(src.iter())
+                .chain(word.iter())
+                .chain(carry.iter())
+                .chain(permutation.iter())
+                .chain([is_init, is_round, is_final, is_valid].iter())
+            {
+                cb.require_boolean("boolean", meta.query_advice(*column, Rotation::cur()));
+            }
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        // A segment is either an input, a round or an output segment, and a round
+        // segment uses one of the permutations of the message words.
+        meta.create_gate("segment", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_seg_start = meta.query_fixed(q_seg_start, Rotation::cur());
+            let kinds = [is_init, is_round, is_final];
+            cb.require_equal(
+                "one kind of segment",
+                sum::expr(kinds.map(|column| meta.query_advice(column, Rotation::cur()))),
+                1.expr(),
+            );
+            cb.require_equal(
+                "one permutation",
+                sum::expr(permutation.map(|column| meta.query_advice(column, Rotation::cur()))),
+                1.expr(),
+            );
+            cb.condition(not::expr(q_seg_start), |cb| {
+                for column in kinds.iter().chain(permutation.iter()) {
+                    cb.require_equal(
+                        "the kind and permutation are the same on all rows of a segment",
+                        meta.query_advice(*column, Rotation::cur()),
+                        meta.query_advice(*column, Rotation::prev()),
+                    );
+                }
+            });
+            let rounds_left = meta.query_advice(rounds_left, Rotation::cur());
+            let rounds_left_inv = meta.query_advice(rounds_left_inv, Rotation::cur());
+            cb.require_zero(
+                "rounds_left_inv is the inverse of rounds_left when it's not zero",
+                rounds_left.clone() * (1.expr() - rounds_left * rounds_left_inv),
+            );
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("first segment", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            cb.require_equal(
+                "the first segment is an input segment",
+                meta.query_advice(is_init, Rotation::cur()),
+                1.expr(),
+            );
+            cb.gate(meta.query_fixed(q_first, Rotation::cur()))
+        });
+
+        // An input segment is followed by the round segments while there are rounds
+        // left, then by the output segment, then by the input segment of the next
+        // compression.  The permutation is rotated after each round.
+        meta.create_gate("segment transition", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let [is_init_prev, is_round_prev, is_final_prev] = [is_init, is_round, is_final]
+                .map(|column| meta.query_advice(column, Rotation::prev()));
+            let no_rounds_left_prev = 1.expr()
+                - meta.query_advice(rounds_left, Rotation::prev())
+                    * meta.query_advice(rounds_left_inv, Rotation::prev());
+            cb.require_equal(
+                "an input segment follows an output segment",
+                meta.query_advice(is_init, Rotation::cur()),
+                is_final_prev.clone(),
+            );
+            cb.require_equal(
+                "an output segment follows the last round",
+                meta.query_advice(is_final, Rotation::cur()),
+                not::expr(is_final_prev) * no_rounds_left_prev,
+            );
+            cb.condition(meta.query_advice(is_round, Rotation::cur()), |cb| {
+                for (idx, column) in permutation.iter().enumerate() {
+                    let permutation_prev = meta.query_advice(
+                        permutation[(idx + NUM_PERMUTATIONS - 1) % NUM_PERMUTATIONS],
+                        Rotation::prev(),
+                    );
+                    cb.require_equal(
+                        "the permutation is rotated after each round",
+                        meta.query_advice(*column, Rotation::cur()),
+                        is_init_prev.clone() * (idx == 0).expr()
+                            + is_round_prev.clone() * permutation_prev,
+                    );
+                }
+            });
+            cb.gate(
+                meta.query_fixed(q_seg_start, Rotation::cur())
+                    - meta.query_fixed(q_first, Rotation::cur()),
+            )
+        });
+
+        meta.create_gate("rounds left", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_seg_start = meta.query_fixed(q_seg_start, Rotation::cur());
+            let rounds_left_cur = meta.query_advice(rounds_left, Rotation::cur());
+            let rounds_left_prev = meta.query_advice(rounds_left, Rotation::prev());
+            cb.condition(
+                meta.query_advice(is_init, Rotation::cur())
+                    * meta.query_fixed(q_seg_end, Rotation::cur()),
+                |cb| {
+                    cb.require_equal(
+                        "an invalid input has no rounds",
+                        rounds_left_cur.clone(),
+                        meta.query_advice(is_valid, Rotation::cur())
+                            * meta.query_advice(rounds, Rotation::cur()),
+                    );
+                },
+            );
+            cb.condition(meta.query_advice(is_round, Rotation::cur()), |cb| {
+                cb.require_equal(
+                    "rounds_left decreases with each round",
+                    rounds_left_cur,
+                    rounds_left_prev - q_seg_start,
+                );
+            });
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        // Parse the input, one word per row.
+        meta.create_gate("input", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let is_init = meta.query_advice(is_init, Rotation::cur());
+            let q_seg_start = meta.query_fixed(q_seg_start, Rotation::cur());
+            let q_seg_end = meta.query_fixed(q_seg_end, Rotation::cur());
+            let [q_parse_rounds, q_parse_h, q_parse_m, q_parse_t0, q_parse_t1, q_parse_f] = [
+                q_parse_rounds,
+                q_parse_h,
+                q_parse_m,
+                q_parse_t0,
+                q_parse_t1,
+                q_parse_f,
+            ]
+            .map(|column| meta.query_fixed(column, Rotation::cur()));
+            let word_bits = query_bits(meta, &word);
+            let word_value = decode::expr(&word_bits);
+            let v_prev = v.map(|column| meta.query_advice(column, Rotation::prev()));
+            let v = v.map(|column| meta.query_advice(column, Rotation::cur()));
+
+            // The number of rounds is big-endian, the other words little-endian.
+            let bytes: [Expression<F>; NUM_BYTES_PER_WORD] = array::from_fn(|idx| {
+                let byte = |idx: usize| {
+                    let start = idx * NUM_BITS_PER_BYTE;
+                    decode::expr(&word_bits[start..start + NUM_BITS_PER_BYTE])
+                };
+                let byte_rounds = if idx < NUM_BYTES_ROUNDS {
+                    byte(NUM_BYTES_ROUNDS - 1 - idx)
+                } else {
+                    0.expr()
+                };
+                select::expr(q_parse_rounds.clone(), byte_rounds, byte(idx))
+            });
+            let data_rlcs_cur = data_rlcs.map(|column| meta.query_advice(column, Rotation::cur()));
+            let data_rlc_prev = not::expr(q_seg_start.clone())
+                * meta.query_advice(data_rlcs[NUM_BYTES_PER_WORD - 1], Rotation::prev());
+            cb.condition(is_init.clone(), |cb| {
+                for idx in 0..NUM_BYTES_PER_WORD {
+                    let is_parsed = q_parse_rounds.clone() * (idx < NUM_BYTES_ROUNDS).expr()
+                        + q_parse_h.clone()
+                        + q_parse_m.clone()
+                        + q_parse_t0.clone()
+                        + q_parse_t1.clone()
+                        + q_parse_f.clone() * (idx == 0).expr();
+                    let data_rlc_before = if idx == 0 {
+                        data_rlc_prev.clone()
+                    } else {
+                        data_rlcs_cur[idx - 1].clone()
+                    };
+                    cb.require_equal(
+                        "data rlc accumulates the parsed bytes",
+                        data_rlcs_cur[idx].clone(),
+                        select::expr(
+                            is_parsed,
+                            data_rlc_before.clone() * r.clone() + bytes[idx].clone(),
+                            data_rlc_before,
+                        ),
+                    );
+                }
+            });
+
+            cb.condition(is_init.clone() * q_parse_rounds, |cb| {
+                cb.require_zero(
+                    "the number of rounds is a 4-byte word",
+                    decode::expr(&word_bits[NUM_BYTES_ROUNDS * NUM_BITS_PER_BYTE..]),
+                );
+                cb.require_equal(
+                    "number of rounds",
+                    meta.query_advice(rounds, Rotation::cur()),
+                    word_value.clone(),
+                );
+            });
+            for (q_parse, columns) in [(q_parse_h, &h[..]), (q_parse_m, &m[..])] {
+                cb.condition(is_init.clone() * q_parse, |cb| {
+                    let n = columns.len();
+                    cb.require_equal(
+                        "the parsed word is shifted in",
+                        meta.query_advice(columns[n - 1], Rotation::cur()),
+                        word_value.clone(),
+                    );
+                    for idx in 0..n - 1 {
+                        cb.require_equal(
+                            "the previous words are shifted",
+                            meta.query_advice(columns[idx], Rotation::cur()),
+                            meta.query_advice(columns[idx + 1], Rotation::prev()),
+                        );
+                    }
+                });
+            }
+            for (idx, q_parse, iv) in [(12, q_parse_t0, IV[4]), (13, q_parse_t1, IV[5])] {
+                cb.condition(is_init.clone() * q_parse.clone(), |cb| {
+                    cb.require_equal(
+                        "the counter words are xored with the initial value",
+                        v[idx].clone(),
+                        decode::expr(&xor_constant(&word_bits, iv)),
+                    );
+                });
+                cb.condition(
+                    is_init.clone() * not::expr(q_parse + q_seg_start.clone()),
+                    |cb| {
+                        cb.require_equal("v is carried over", v[idx].clone(), v_prev[idx].clone());
+                    },
+                );
+            }
+            cb.condition(is_init.clone() * q_parse_f.clone(), |cb| {
+                cb.require_zero(
+                    "the final block flag is a byte",
+                    decode::expr(&word_bits[NUM_BITS_PER_BYTE..]),
+                );
+                // `v[14] = IV[6] ^ 0xff..ff` when the flag is set
+                cb.require_equal(
+                    "the final block flag inverts the initial value",
+                    v[14].clone(),
+                    IV[6].expr()
+                        + meta.query_advice(is_valid, Rotation::cur())
+                            * word_value.clone()
+                            * Expression::Constant(F::from(u64::MAX) - F::from(2 * IV[6])),
+                );
+            });
+            cb.condition(is_init.clone() * not::expr(q_parse_f + q_seg_start), |cb| {
+                cb.require_equal("v is carried over", v[14].clone(), v_prev[14].clone());
+            });
+            cb.condition(is_init * q_seg_end, |cb| {
+                for idx in 0..NUM_HASH_WORDS {
+                    cb.require_equal(
+                        "the state starts with the hash words",
+                        v[idx].clone(),
+                        meta.query_advice(h[idx], Rotation::cur()),
+                    );
+                }
+                for idx in [8, 9, 10, 11, 15] {
+                    cb.require_equal(
+                        "the state ends with the initial value",
+                        v[idx].clone(),
+                        IV[idx - 8].expr(),
+                    );
+                }
+            });
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        // The input is valid when the final block flag is 0 or 1.
+        meta.create_gate("final block flag", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let flag = decode::expr(&query_bits(meta, &word)[..NUM_BITS_PER_BYTE]);
+            let not_boolean = flag.clone() * (flag - 1.expr());
+            let is_valid = meta.query_advice(is_valid, Rotation::cur());
+            cb.condition(meta.query_advice(is_init, Rotation::cur()), |cb| {
+                cb.require_equal(
+                    "is_valid is 1 when the flag is boolean",
+                    is_valid.clone(),
+                    1.expr()
+                        - not_boolean.clone() * meta.query_advice(is_valid_inv, Rotation::cur()),
+                );
+                cb.require_zero(
+                    "is_valid is 0 when the flag is not boolean",
+                    not_boolean * is_valid,
+                );
+            });
+            cb.gate(meta.query_fixed(q_parse_f, Rotation::cur()))
+        });
+
+        // The data of the compression is carried over its segments.
+        meta.create_gate("carry over data", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_seg_start = meta.query_fixed(q_seg_start, Rotation::cur());
+            let is_init = meta.query_advice(is_init, Rotation::cur());
+            let is_input_start = is_init.clone() * q_seg_start;
+            for (columns, q_parse) in [
+                (&[rounds][..], None),
+                (&[is_valid][..], Some(q_parse_f)),
+                (&h[..], Some(q_parse_h)),
+                (&m[..], Some(q_parse_m)),
+                (&[data_rlcs[NUM_BYTES_PER_WORD - 1]][..], None),
+            ] {
+                let is_set = is_input_start.clone()
+                    + q_parse.map_or(0.expr(), |q_parse| {
+                        is_init.clone() * meta.query_fixed(q_parse, Rotation::cur())
+                    });
+                cb.condition(not::expr(is_set), |cb| {
+                    for column in columns {
+                        cb.require_equal(
+                            "data is carried over",
+                            meta.query_advice(*column, Rotation::cur()),
+                            meta.query_advice(*column, Rotation::prev()),
+                        );
+                    }
+                });
+            }
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        // Apply an operation of the mixing function `G` to the state.
+        meta.create_gate("round", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let dst_value = query_operand(meta, &q_dst, &v);
+            let src_value = query_operand(meta, &q_src, &v);
+            let q_add = meta.query_fixed(q_add, Rotation::cur());
+            let q_xor = q_xor.map(|column| meta.query_fixed(column, Rotation::cur()));
+            let permutation = permutation.map(|column| meta.query_advice(column, Rotation::cur()));
+            let m = m.map(|column| meta.query_advice(column, Rotation::cur()));
+            let x = meta.query_advice(x, Rotation::cur());
+            let result = meta.query_advice(result, Rotation::cur());
+            let [dst_bits, src_bits, word_bits] =
+                [dst, src, word].map(|columns| query_bits(meta, &columns));
+            let carry = carry.map(|column| meta.query_advice(column, Rotation::cur()));
+
+            let is_round = meta.query_advice(is_round, Rotation::cur());
+
+            let message =
+                sum::expr(q_message.iter().enumerate().map(|(idx, q_message)| {
+                    meta.query_fixed(*q_message, Rotation::cur())
+                        * sum::expr(permutation.iter().zip(SIGMA.iter()).map(
+                            |(permutation, sigma)| permutation.clone() * m[sigma[idx]].clone(),
+                        ))
+                }));
+            cb.condition(is_round.clone(), |cb| {
+                cb.require_equal("message word of the round", x.clone(), message);
+            });
+            cb.condition(is_round.clone() * q_add.clone(), |cb| {
+                cb.require_equal(
+                    "addition",
+                    decode::expr(&word_bits) + decode::expr(&carry) * pow_2_64.clone(),
+                    dst_value.clone() + src_value.clone() + x,
+                );
+            });
+            cb.condition(is_round.clone() * sum::expr(q_xor.iter()), |cb| {
+                cb.require_equal("xor operand", decode::expr(&dst_bits), dst_value);
+                cb.require_equal("xor operand", decode::expr(&src_bits), src_value);
+            });
+            cb.condition(is_round, |cb| {
+                let xored = xor(&dst_bits, &src_bits);
+                cb.require_equal(
+                    "result",
+                    result.clone(),
+                    q_add * decode::expr(&word_bits)
+                        + sum::expr(q_xor.iter().zip(ROTATIONS.iter()).map(|(q_xor, rotation)| {
+                            q_xor.clone() * decode::expr(&rotate_right(&xored, *rotation))
+                        })),
+                );
+                for (q_dst, v) in q_dst.iter().zip(v.iter()) {
+                    let v_prev = meta.query_advice(*v, Rotation::prev());
+                    cb.require_equal(
+                        "the result is written to the destination word",
+                        meta.query_advice(*v, Rotation::cur()),
+                        v_prev.clone()
+                            + meta.query_fixed(*q_dst, Rotation::cur()) * (result.clone() - v_prev),
+                    );
+                }
+            });
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        // The compressed state is `h[i] ^ v[i] ^ v[i + 8]`, its words are little-endian.
+        meta.create_gate("output", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let [dst_bits, src_bits, word_bits] =
+                [dst, src, word].map(|columns| query_bits(meta, &columns));
+            cb.condition(meta.query_advice(is_final, Rotation::cur()), |cb| {
+                for (bits, words) in [
+                    (&dst_bits, &v[..NUM_HASH_WORDS]),
+                    (&src_bits, &v[NUM_HASH_WORDS..]),
+                    (&word_bits, &h[..]),
+                ] {
+                    cb.require_equal(
+                        "output operand",
+                        decode::expr(bits),
+                        sum::expr(q_output.iter().zip(words.iter()).map(|(q_output, word)| {
+                            meta.query_fixed(*q_output, Rotation::cur())
+                                * meta.query_advice(*word, Rotation::cur())
+                        })),
+                    );
+                }
+                let xored = xor(&xor(&dst_bits, &src_bits), &word_bits);
+                let bytes = (0..NUM_BYTES_PER_WORD)
+                    .rev()
+                    .map(|idx| {
+                        let start = idx * NUM_BITS_PER_BYTE;
+                        decode::expr(&xored[start..start + NUM_BITS_PER_BYTE])
+                    })
+                    .collect::<Vec<_>>();
+                let r_pow_word = (0..NUM_BYTES_PER_WORD).fold(1.expr(), |acc, _| acc * r.clone());
+                cb.require_equal(
+                    "output accumulates the compressed words",
+                    meta.query_advice(output_acc, Rotation::cur()),
+                    not::expr(meta.query_fixed(q_output[0], Rotation::cur()))
+                        * meta.query_advice(output_acc, Rotation::prev())
+                        * r_pow_word
+                        + rlc::expr(&bytes, r.clone()),
+                );
+            });
+            cb.gate(sum::expr(
+                q_output.map(|column| meta.query_fixed(column, Rotation::cur())),
+            ))
+        });
+
+        meta.create_gate("table", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let is_enabled = meta.query_advice(is_enabled, Rotation::cur());
+            let q_output =
+                sum::expr(q_output.map(|column| meta.query_fixed(column, Rotation::cur())));
+            let is_final = meta.query_advice(is_final, Rotation::cur());
+            cb.require_equal(
+                "the table is enabled on the last row of an output segment",
+                is_enabled.clone(),
+                meta.query_fixed(q_seg_end, Rotation::cur()) * is_final.clone(),
+            );
+            cb.condition(is_final.clone(), |cb| {
+                for column in v {
+                    cb.require_equal(
+                        "v is carried over",
+                        meta.query_advice(column, Rotation::cur()),
+                        meta.query_advice(column, Rotation::prev()),
+                    );
+                }
+            });
+            cb.condition(is_final * not::expr(q_output), |cb| {
+                cb.require_equal(
+                    "output is carried over",
+                    meta.query_advice(output_acc, Rotation::cur()),
+                    meta.query_advice(output_acc, Rotation::prev()),
+                );
+            });
+            cb.condition(is_enabled, |cb| {
+                cb.require_equal(
+                    "output rlc is 0 when the input is not valid",
+                    meta.query_advice(output_rlc, Rotation::cur()),
+                    meta.query_advice(is_valid, Rotation::cur())
+                        * meta.query_advice(output_acc, Rotation::cur()),
+                );
+            });
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        Blake2fCircuitConfig {
+            q_enable,
+            q_first,
+            q_seg_start,
+            q_seg_end,
+            q_parse_rounds,
+            q_parse_h,
+            q_parse_m,
+            q_parse_t0,
+            q_parse_t1,
+            q_parse_f,
+            q_dst,
+            q_src,
+            q_add,
+            q_xor,
+            q_message,
+            q_output,
+            is_init,
+            is_round,
+            is_final,
+            v,
+            m,
+            h,
+            rounds_left,
+            rounds_left_inv,
+            permutation,
+            dst,
+            src,
+            word,
+            carry,
+            x,
+            result,
+            is_valid_inv,
+            data_rlcs,
+            output_acc,
+            blake2f_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> Blake2fCircuitConfig<F> {
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        witness: &[Blake2fRow<F>],
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "assign blake2f rows",
+            |mut region| {
+                for (offset, blake2f_row) in witness.iter().enumerate() {
+                    self.set_row(&mut region, offset, blake2f_row)?;
+                }
+                self.blake2f_table.annotate_columns_in_region(&mut region);
+                self.annotate_circuit(&mut region);
+                Ok(())
+            },
+        )
+    }
+
+    fn set_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        row: &Blake2fRow<F>,
+    ) -> Result<(), Error> {
+        let selectors = Blake2fSelectors::new(offset);
+
+        // Fixed selectors
+        for (name, column, value) in [
+            ("q_enable", self.q_enable, true),
+            ("q_first", self.q_first, offset == 0),
+            ("q_seg_start", self.q_seg_start, selectors.q_seg_start),
+            ("q_seg_end", self.q_seg_end, selectors.q_seg_end),
+            (
+                "q_parse_rounds",
+                self.q_parse_rounds,
+                selectors.q_parse_rounds,
+            ),
+            ("q_parse_h", self.q_parse_h, selectors.q_parse_h),
+            ("q_parse_m", self.q_parse_m, selectors.q_parse_m),
+            ("q_parse_t0", self.q_parse_t0, selectors.q_parse_t0),
+            ("q_parse_t1", self.q_parse_t1, selectors.q_parse_t1),
+            ("q_parse_f", self.q_parse_f, selectors.q_parse_f),
+            ("q_add", self.q_add, selectors.q_add),
+        ]
+        .into_iter()
+        .chain(
+            [
+                ("q_dst", &self.q_dst[..], &selectors.q_dst[..]),
+                ("q_src", &self.q_src[..], &selectors.q_src[..]),
+                ("q_xor", &self.q_xor[..], &selectors.q_xor[..]),
+                ("q_message", &self.q_message[..], &selectors.q_message[..]),
+                ("q_output", &self.q_output[..], &selectors.q_output[..]),
+            ]
+            .into_iter()
+            .flat_map(|(name, columns, values)| {
+                columns
+                    .iter()
+                    .zip(values.iter())
+                    .map(move |(column, value)| (name, *column, *value))
+            }),
+        ) {
+            region.assign_fixed(
+                || format!("assign {} {}", name, offset),
+                column,
+                offset,
+                || Value::known(F::from(value as u64)),
+            )?;
+        }
+
+        self.blake2f_table.assign_row(
+            region,
+            offset,
+            [
+                Value::known(F::from(row.is_enabled as u64)),
+                row.data_rlcs[NUM_BYTES_PER_WORD - 1],
+                Value::known(F::from(row.rounds as u64)),
+                Value::known(F::from(row.is_valid as u64)),
+                row.output_rlc,
+            ],
+        )?;
+
+        // Segment
+        let rounds_left = F::from(row.rounds_left);
+        for (name, column, value) in [
+            ("is_init", self.is_init, F::from(row.is_init as u64)),
+            ("is_round", self.is_round, F::from(row.is_round as u64)),
+            ("is_final", self.is_final, F::from(row.is_final as u64)),
+            ("rounds_left", self.rounds_left, rounds_left),
+            (
+                "rounds_left_inv",
+                self.rounds_left_inv,
+                rounds_left.invert().unwrap_or(F::ZERO),
+            ),
+            ("x", self.x, F::from(row.x)),
+            ("result", self.result, F::from(row.result)),
+            ("is_valid_inv", self.is_valid_inv, row.is_valid_inv),
+        ]
+        .into_iter()
+        .chain(self.permutation.iter().enumerate().map(|(idx, column)| {
+            (
+                "permutation",
+                *column,
+                F::from((idx == row.permutation) as u64),
+            )
+        })) {
+            region.assign_advice(
+                || format!("assign {} {}", name, offset),
+                column,
+                offset,
+                || Value::known(value),
+            )?;
+        }
+
+        // Words
+        for (name, columns, values) in [
+            ("v", &self.v[..], &row.v[..]),
+            ("m", &self.m[..], &row.m[..]),
+            ("h", &self.h[..], &row.h[..]),
+        ] {
+            for (idx, (column, value)) in columns.iter().zip(values.iter()).enumerate() {
+                region.assign_advice(
+                    || format!("assign {}{} {}", name, idx, offset),
+                    *column,
+                    offset,
+                    || Value::known(F::from(*value)),
+                )?;
+            }
+        }
+
+        // Bits
+        for (name, columns, value) in [
+            ("dst", &self.dst[..], row.dst),
+            ("src", &self.src[..], row.src),
+            ("word", &self.word[..], row.word),
+            ("carry", &self.carry[..], row.carry),
+        ] {
+            for (idx, column) in columns.iter().enumerate() {
+                region.assign_advice(
+                    || format!("assign {} bit {} {}", name, idx, offset),
+                    *column,
+                    offset,
+                    || Value::known(F::from((value >> idx) & 1)),
+                )?;
+            }
+        }
+
+        // Data
+        for (column, value) in self
+            .data_rlcs
+            .iter()
+            .zip(row.data_rlcs.iter())
+            .take(NUM_BYTES_PER_WORD - 1)
+        {
+            region.assign_advice(
+                || format!("assign data_rlc {}", offset),
+                *column,
+                offset,
+                || *value,
+            )?;
+        }
+        region.assign_advice(
+            || format!("assign output_acc {}", offset),
+            self.output_acc,
+            offset,
+            || row.output_acc,
+        )?;
+
+        Ok(())
+    }
+
+    fn annotate_circuit(&self, region: &mut Region<F>) {
+        region.name_column(|| "BLAKE2F_q_enable", self.q_enable);
+        region.name_column(|| "BLAKE2F_q_first", self.q_first);
+        region.name_column(|| "BLAKE2F_q_seg_start", self.q_seg_start);
+        region.name_column(|| "BLAKE2F_q_seg_end", self.q_seg_end);
+        region.name_column(|| "BLAKE2F_q_parse_rounds", self.q_parse_rounds);
+        region.name_column(|| "BLAKE2F_q_parse_h", self.q_parse_h);
+        region.name_column(|| "BLAKE2F_q_parse_m", self.q_parse_m);
+        region.name_column(|| "BLAKE2F_q_parse_t0", self.q_parse_t0);
+        region.name_column(|| "BLAKE2F_q_parse_t1", self.q_parse_t1);
+        region.name_column(|| "BLAKE2F_q_parse_f", self.q_parse_f);
+        region.name_column(|| "BLAKE2F_q_add", self.q_add);
+        region.name_column(|| "BLAKE2F_is_init", self.is_init);
+        region.name_column(|| "BLAKE2F_is_round", self.is_round);
+        region.name_column(|| "BLAKE2F_is_final", self.is_final);
+        region.name_column(|| "BLAKE2F_rounds_left", self.rounds_left);
+        region.name_column(|| "BLAKE2F_x", self.x);
+        region.name_column(|| "BLAKE2F_result", self.result);
+        region.name_column(|| "BLAKE2F_output_acc", self.output_acc);
+    }
+}
+
+/// Blake2fCircuit
+#[derive(Default, Clone, Debug)]
+pub struct Blake2fCircuit<F: Field> {
+    inputs: Vec<Vec<u8>>,
+    num_rows: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> SubCircuit<F> for Blake2fCircuit<F> {
+    type Config = Blake2fCircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // No column is queried at more than 2 distinct rotations, Rotation(0)
+        // and Rotation(-1), so returns 6 unusable rows.
+        6
+    }
+
+    /// The `block.circuits_params.max_blake2f_rows` parameter, when set, sets
+    /// up the circuit to support a fixed number of segments, independently of
+    /// the segments required by the blake2f precompile calls of the block.
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        Self::new(
+            block.circuits_params.max_blake2f_rows,
+            block
+                .precompile_events
+                .get_blake2f_events()
+                .into_iter()
+                .map(|event| event.input)
+                .collect(),
+        )
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        (
+            block
+                .precompile_events
+                .get_blake2f_events()
+                .iter()
+                .map(|event| get_num_blake2f_segments(&event.input) * NUM_ROWS_PER_SEGMENT)
+                .sum(),
+            block.circuits_params.max_blake2f_rows,
+        )
+    }
+
+    /// Make the assignments to the Blake2fCircuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let witness = self.generate_witness(*challenges);
+        config.assign(layouter, witness.as_slice())
+    }
+}
+
+impl<F: Field> Blake2fCircuit<F> {
+    /// Creates a new circuit instance
+    pub fn new(num_rows: usize, inputs: Vec<Vec<u8>>) -> Self {
+        Blake2fCircuit {
+            inputs,
+            num_rows,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The number of blake2f segments that can be assigned in this circuit
+    pub fn capacity(&self) -> Option<usize> {
+        if self.num_rows > 0 {
+            Some((self.num_rows - Self::unusable_rows()) / NUM_ROWS_PER_SEGMENT)
+        } else {
+            None
+        }
+    }
+
+    /// Sets the witness using the inputs to be compressed
+    pub(crate) fn generate_witness(&self, challenges: Challenges<Value<F>>) -> Vec<Blake2fRow<F>> {
+        multi_blake2f(self.inputs.as_slice(), challenges, self.capacity())
+            .expect("Too many inputs for given capacity")
+    }
+}
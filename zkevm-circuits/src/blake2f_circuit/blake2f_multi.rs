@@ -0,0 +1,303 @@
+use super::{param::*, util::*};
+use crate::{evm_circuit::util::rlc, util::Challenges};
+use bus_mapping::precompile::N_BYTES_BLAKE2F_INPUT;
+use eth_types::Field;
+use halo2_proofs::{circuit::Value, plonk::Error};
+use log::debug;
+
+/// Blake2fRow
+#[derive(Clone, Debug)]
+pub(crate) struct Blake2fRow<F: Field> {
+    pub(crate) is_init: bool,
+    pub(crate) is_round: bool,
+    pub(crate) is_final: bool,
+    pub(crate) v: [u64; NUM_STATE_WORDS],
+    pub(crate) m: [u64; NUM_MESSAGE_WORDS],
+    pub(crate) h: [u64; NUM_HASH_WORDS],
+    pub(crate) rounds_left: u64,
+    pub(crate) permutation: usize,
+    pub(crate) dst: u64,
+    pub(crate) src: u64,
+    pub(crate) word: u64,
+    pub(crate) carry: u64,
+    pub(crate) x: u64,
+    pub(crate) result: u64,
+    pub(crate) rounds: u32,
+    pub(crate) is_valid: bool,
+    pub(crate) is_valid_inv: F,
+    pub(crate) data_rlcs: [Value<F>; NUM_BYTES_PER_WORD],
+    pub(crate) output_acc: Value<F>,
+    pub(crate) is_enabled: bool,
+    pub(crate) output_rlc: Value<F>,
+}
+
+/// The selectors of a row, which only depend on the position of the row in
+/// its segment.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Blake2fSelectors {
+    pub(crate) q_seg_start: bool,
+    pub(crate) q_seg_end: bool,
+    pub(crate) q_parse_rounds: bool,
+    pub(crate) q_parse_h: bool,
+    pub(crate) q_parse_m: bool,
+    pub(crate) q_parse_t0: bool,
+    pub(crate) q_parse_t1: bool,
+    pub(crate) q_parse_f: bool,
+    pub(crate) q_dst: [bool; NUM_STATE_WORDS],
+    pub(crate) q_src: [bool; NUM_STATE_WORDS],
+    pub(crate) q_add: bool,
+    pub(crate) q_xor: [bool; ROTATIONS.len()],
+    pub(crate) q_message: [bool; NUM_MESSAGE_WORDS],
+    pub(crate) q_output: [bool; NUM_HASH_WORDS],
+}
+
+impl Blake2fSelectors {
+    /// Returns the selectors of the row at `offset` in the circuit.
+    pub(crate) fn new(offset: usize) -> Self {
+        let idx = offset % NUM_ROWS_PER_SEGMENT;
+        let operation = Operation::new(idx);
+        let mut selectors = Self {
+            q_seg_start: idx == 0,
+            q_seg_end: idx == NUM_ROWS_PER_SEGMENT - 1,
+            q_parse_rounds: idx == ROW_ROUNDS,
+            q_parse_h: (ROW_FIRST_H..ROW_FIRST_M).contains(&idx),
+            q_parse_m: (ROW_FIRST_M..ROW_T0).contains(&idx),
+            q_parse_t0: idx == ROW_T0,
+            q_parse_t1: idx == ROW_T1,
+            q_parse_f: idx == ROW_F,
+            q_add: operation.rotation.is_none(),
+            ..Default::default()
+        };
+        selectors.q_dst[operation.dst] = true;
+        selectors.q_src[operation.src] = true;
+        if let Some(rotation) = operation.rotation {
+            selectors.q_xor[rotation] = true;
+        }
+        if let Some(message) = operation.message {
+            selectors.q_message[message] = true;
+        }
+        if idx < NUM_HASH_WORDS {
+            selectors.q_output[idx] = true;
+        }
+        selectors
+    }
+}
+
+/// Returns the number of bytes of the input parsed by the row `idx` of the
+/// input segment.
+fn num_parsed_bytes(idx: usize) -> usize {
+    match idx {
+        ROW_ROUNDS => NUM_BYTES_ROUNDS,
+        ROW_F => 1,
+        idx if idx < ROW_F => NUM_BYTES_PER_WORD,
+        _ => 0,
+    }
+}
+
+fn blake2f<F: Field>(
+    rows: &mut Vec<Blake2fRow<F>>,
+    input: &[u8],
+    challenges: Challenges<Value<F>>,
+) {
+    assert_eq!(input.len(), N_BYTES_BLAKE2F_INPUT);
+    let word = |idx: usize| {
+        let start = NUM_BYTES_ROUNDS + (idx - ROW_FIRST_H) * NUM_BYTES_PER_WORD;
+        u64::from_le_bytes(input[start..start + NUM_BYTES_PER_WORD].try_into().unwrap())
+    };
+    let rounds = u32::from_be_bytes(input[..NUM_BYTES_ROUNDS].try_into().unwrap());
+    let f = input[N_BYTES_BLAKE2F_INPUT - 1] as u64;
+    let is_valid = f <= 1;
+    let is_valid_inv = F::from(f * f.wrapping_sub(1)).invert().unwrap_or(F::ZERO);
+    let num_rounds = if is_valid { rounds as u64 } else { 0 };
+
+    let new_row = |is_init, is_round, is_final| Blake2fRow {
+        is_init,
+        is_round,
+        is_final,
+        v: [0; NUM_STATE_WORDS],
+        m: [0; NUM_MESSAGE_WORDS],
+        h: [0; NUM_HASH_WORDS],
+        rounds_left: num_rounds,
+        permutation: 0,
+        dst: 0,
+        src: 0,
+        word: 0,
+        carry: 0,
+        x: 0,
+        result: 0,
+        rounds,
+        is_valid,
+        is_valid_inv: F::ZERO,
+        data_rlcs: [Value::known(F::ZERO); NUM_BYTES_PER_WORD],
+        output_acc: Value::known(F::ZERO),
+        is_enabled: false,
+        output_rlc: Value::known(F::ZERO),
+    };
+
+    // Parse the input, the hash and message words are shifted in.
+    let mut v = [0; NUM_STATE_WORDS];
+    let mut m = [0; NUM_MESSAGE_WORDS];
+    let mut h = [0; NUM_HASH_WORDS];
+    let mut data_rlc = Value::known(F::ZERO);
+    let mut bytes = input.iter();
+    for idx in 0..NUM_ROWS_PER_SEGMENT {
+        let mut row = new_row(true, false, false);
+        row.word = match idx {
+            ROW_ROUNDS => rounds as u64,
+            ROW_F => f,
+            idx if idx < ROW_F => word(idx),
+            _ => 0,
+        };
+        match idx {
+            idx if (ROW_FIRST_H..ROW_FIRST_M).contains(&idx) => {
+                h.rotate_left(1);
+                h[NUM_HASH_WORDS - 1] = row.word;
+            }
+            idx if (ROW_FIRST_M..ROW_T0).contains(&idx) => {
+                m.rotate_left(1);
+                m[NUM_MESSAGE_WORDS - 1] = row.word;
+            }
+            ROW_T0 => v[12] = row.word ^ IV[4],
+            ROW_T1 => v[13] = row.word ^ IV[5],
+            ROW_F => {
+                v[14] = if f == 1 { !IV[6] } else { IV[6] };
+                row.is_valid_inv = is_valid_inv;
+            }
+            _ => (),
+        }
+        if idx == NUM_ROWS_PER_SEGMENT - 1 {
+            v[..NUM_HASH_WORDS].copy_from_slice(&h);
+            v[8..12].copy_from_slice(&IV[..4]);
+            v[15] = IV[7];
+        }
+        for data_rlc_row in row.data_rlcs.iter_mut().take(num_parsed_bytes(idx)) {
+            let byte = *bytes.next().unwrap();
+            data_rlc = data_rlc
+                .zip(challenges.keccak_input())
+                .map(|(data_rlc, r)| data_rlc * r + F::from(byte as u64));
+            *data_rlc_row = data_rlc;
+        }
+        for data_rlc_row in row.data_rlcs.iter_mut().skip(num_parsed_bytes(idx)) {
+            *data_rlc_row = data_rlc;
+        }
+        rows.push(Blake2fRow { v, m, h, ..row });
+    }
+
+    // Rounds, one operation of the mixing function per row.
+    let data_rlcs = [data_rlc; NUM_BYTES_PER_WORD];
+    for round in 0..num_rounds {
+        let permutation = round as usize % NUM_PERMUTATIONS;
+        for idx in 0..NUM_ROWS_PER_SEGMENT {
+            let operation = Operation::new(idx);
+            let (dst, src) = (v[operation.dst], v[operation.src]);
+            let mut row = Blake2fRow {
+                rounds_left: num_rounds - round - 1,
+                permutation,
+                ..new_row(false, true, false)
+            };
+            if let Some(rotation) = operation.rotation {
+                row.dst = dst;
+                row.src = src;
+                row.result = (dst ^ src).rotate_right(ROTATIONS[rotation] as u32);
+            } else {
+                row.x = operation
+                    .message
+                    .map(|idx| m[SIGMA[permutation][idx]])
+                    .unwrap_or_default();
+                let sum = dst as u128 + src as u128 + row.x as u128;
+                row.word = sum as u64;
+                row.carry = (sum >> NUM_BITS_PER_WORD) as u64;
+                row.result = row.word;
+            }
+            v[operation.dst] = row.result;
+            rows.push(Blake2fRow {
+                v,
+                m,
+                h,
+                data_rlcs,
+                ..row
+            });
+        }
+    }
+
+    // Output, the words of the compressed state are little-endian.
+    let output = (0..NUM_HASH_WORDS)
+        .flat_map(|i| (h[i] ^ v[i] ^ v[i + 8]).to_le_bytes())
+        .collect::<Vec<_>>();
+    let mut output_acc = Value::known(F::ZERO);
+    for idx in 0..NUM_ROWS_PER_SEGMENT {
+        let mut row = Blake2fRow {
+            rounds_left: 0,
+            ..new_row(false, false, true)
+        };
+        if idx < NUM_HASH_WORDS {
+            row.dst = v[idx];
+            row.src = v[idx + 8];
+            row.word = h[idx];
+            for byte in &output[idx * NUM_BYTES_PER_WORD..(idx + 1) * NUM_BYTES_PER_WORD] {
+                output_acc = output_acc
+                    .zip(challenges.keccak_input())
+                    .map(|(acc, r)| acc * r + F::from(*byte as u64));
+            }
+        }
+        row.output_acc = output_acc;
+        if idx == NUM_ROWS_PER_SEGMENT - 1 {
+            row.is_enabled = true;
+            if is_valid {
+                row.output_rlc = output_acc;
+            }
+        }
+        rows.push(Blake2fRow {
+            v,
+            m,
+            h,
+            data_rlcs,
+            ..row
+        });
+    }
+    debug!("blake2f output: {}", hex::encode(output));
+}
+
+/// Witness generation for multiple blake2f compressions.  The rows of
+/// `capacity` segments are returned when it's set, padding with the
+/// compressions of all-zero inputs.
+pub(crate) fn multi_blake2f<F: Field>(
+    inputs: &[Vec<u8>],
+    challenges: Challenges<Value<F>>,
+    capacity: Option<usize>,
+) -> Result<Vec<Blake2fRow<F>>, Error> {
+    let mut rows: Vec<Blake2fRow<F>> = Vec::new();
+    for input in inputs {
+        blake2f(&mut rows, input, challenges);
+    }
+    if let Some(capacity) = capacity {
+        let padding_rows = {
+            let mut rows = Vec::new();
+            blake2f(&mut rows, &[0; N_BYTES_BLAKE2F_INPUT], challenges);
+            rows
+        };
+        // Pad with zero-round compressions to the expected capacity, the last
+        // segment may only be the input segment of a compression.
+        while rows.len() < capacity * NUM_ROWS_PER_SEGMENT {
+            let num_rows = capacity * NUM_ROWS_PER_SEGMENT - rows.len();
+            rows.extend(padding_rows.iter().take(num_rows).cloned());
+        }
+        // Check that we are not over capacity
+        if rows.len() > capacity * NUM_ROWS_PER_SEGMENT {
+            log::error!(
+                "Blake2f inputs exceed capacity.  needed_rows = {}, available_rows = {}",
+                rows.len(),
+                capacity * NUM_ROWS_PER_SEGMENT
+            );
+            return Err(Error::BoundsFailure);
+        }
+    }
+    Ok(rows)
+}
+
+/// Returns the number of segments needed to compress the input.
+pub(crate) fn get_num_blake2f_segments(input: &[u8]) -> usize {
+    let rounds = u32::from_be_bytes(input[..NUM_BYTES_ROUNDS].try_into().unwrap());
+    let is_valid = input[N_BYTES_BLAKE2F_INPUT - 1] <= 1;
+    2 + if is_valid { rounds as usize } else { 0 }
+}
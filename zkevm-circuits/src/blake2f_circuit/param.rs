@@ -0,0 +1,69 @@
+pub(crate) const MAX_DEGREE: usize = 5;
+
+pub(crate) const NUM_BITS_PER_BYTE: usize = 8;
+pub(crate) const NUM_BYTES_PER_WORD: usize = 8;
+pub(crate) const NUM_BITS_PER_WORD: usize = NUM_BYTES_PER_WORD * NUM_BITS_PER_BYTE;
+pub(crate) const NUM_CARRY_BITS: usize = 2;
+
+pub(crate) const NUM_HASH_WORDS: usize = 8;
+pub(crate) const NUM_MESSAGE_WORDS: usize = 16;
+pub(crate) const NUM_STATE_WORDS: usize = 16;
+pub(crate) const NUM_PERMUTATIONS: usize = 10;
+
+// A compression takes a segment to parse the input, a segment per round and a
+// segment for the output.  A round applies the mixing function `G` 8 times, each
+// one made of 8 operations of one row.
+pub(crate) const NUM_ROWS_PER_SEGMENT: usize = 64;
+pub(crate) const NUM_MIXINGS: usize = 8;
+pub(crate) const NUM_OPERATIONS_PER_MIXING: usize = 8;
+
+// The rows of the input segment parsing the input, one word per row.  The
+// number of rounds is a 4-byte big-endian integer, the other words are 8-byte
+// little-endian integers, and the final block flag is a single byte.
+pub(crate) const NUM_BYTES_ROUNDS: usize = 4;
+pub(crate) const ROW_ROUNDS: usize = 0;
+pub(crate) const ROW_FIRST_H: usize = ROW_ROUNDS + 1;
+pub(crate) const ROW_FIRST_M: usize = ROW_FIRST_H + NUM_HASH_WORDS;
+pub(crate) const ROW_T0: usize = ROW_FIRST_M + NUM_MESSAGE_WORDS;
+pub(crate) const ROW_T1: usize = ROW_T0 + 1;
+pub(crate) const ROW_F: usize = ROW_T1 + 1;
+
+// The rotations of the `G` mixing function.
+pub(crate) const ROTATIONS: [usize; 4] = [32, 24, 16, 63];
+
+pub(crate) const IV: [u64; NUM_HASH_WORDS] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+// The message word permutations, round `i` uses `SIGMA[i % 10]`.
+pub(crate) const SIGMA: [[usize; NUM_MESSAGE_WORDS]; NUM_PERMUTATIONS] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+// The state words `a, b, c, d` mixed by each application of `G` in a round.
+pub(crate) const MIXING_WORDS: [[usize; 4]; NUM_MIXINGS] = [
+    [0, 4, 8, 12],
+    [1, 5, 9, 13],
+    [2, 6, 10, 14],
+    [3, 7, 11, 15],
+    [0, 5, 10, 15],
+    [1, 6, 11, 12],
+    [2, 7, 8, 13],
+    [3, 4, 9, 14],
+];
@@ -0,0 +1,164 @@
+use super::*;
+use crate::util::unusable_rows;
+use halo2_proofs::{
+    dev::{CellValue, MockProver},
+    halo2curves::bn256::Fr,
+    plonk::Circuit,
+};
+use itertools::izip;
+use log::error;
+
+#[test]
+fn blake2f_circuit_unusable_rows() {
+    assert_eq!(
+        Blake2fCircuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, Blake2fCircuit::<Fr>>(()),
+    )
+}
+
+// The input of the test vectors of EIP-152, with the given number of rounds and
+// final block flag.
+fn input(rounds: u32, flag: u8) -> Vec<u8> {
+    [
+        rounds.to_be_bytes().to_vec(),
+        hex::decode(
+            "48c9bdf267e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5\
+             d182e6ad7f520e511f6c3e2b8c68059b6bbd41fbabd9831f79217e1319cde05b",
+        )
+        .unwrap(),
+        b"abc".to_vec(),
+        vec![0; 125],
+        3u64.to_le_bytes().to_vec(),
+        0u64.to_le_bytes().to_vec(),
+        vec![flag],
+    ]
+    .concat()
+}
+
+const ZERO_INPUT_OUTPUT: &str = "08c9bcf367e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5\
+                                 d282e6ad7f520e511f6c3e2b8c68059b9442be0454267ce079217e1319cde05b";
+
+fn verify<F: Field>(k: u32, inputs: Vec<Vec<u8>>, outputs: Vec<&str>, success: bool) {
+    let circuit = Blake2fCircuit::new(2usize.pow(k), inputs.clone());
+    let prover = MockProver::<F>::run(k, &circuit, vec![]).unwrap();
+    let (config, challenges) = Blake2fCircuit::configure(&mut ConstraintSystem::<F>::default());
+    let input_challenge = prover.get_challenge(challenges.keccak_input());
+
+    // Check constraints.
+    let verify_result = prover.verify();
+    if verify_result.is_ok() != success {
+        if let Some(errors) = verify_result.err() {
+            for error in errors.iter() {
+                error!("{}", error);
+            }
+        }
+        panic!();
+    }
+
+    // Extract the content of the lookup table.
+    let compression_lookup_table = {
+        let is_enabled = prover.advice_values(config.blake2f_table.is_enabled);
+        let input_rlc = prover.advice_values(config.blake2f_table.input_rlc);
+        let rounds = prover.advice_values(config.blake2f_table.rounds);
+        let is_valid = prover.advice_values(config.blake2f_table.is_valid);
+        let output_rlc = prover.advice_values(config.blake2f_table.output_rlc);
+
+        // Keep the rows that are supposed to contain compression results.
+        izip!(is_enabled, input_rlc, rounds, is_valid, output_rlc)
+            .filter_map(|(enabled, input_rlc, rounds, is_valid, output_rlc)| {
+                assigned_non_zero(enabled).then(|| {
+                    (
+                        unwrap(input_rlc),
+                        unwrap(rounds),
+                        unwrap(is_valid),
+                        unwrap(output_rlc),
+                    )
+                })
+            })
+            .collect::<Vec<(F, F, F, F)>>()
+    };
+
+    let rlc = |bytes: &[u8]| rlc::value(bytes.iter().rev(), input_challenge);
+
+    // Check that all the outputs are there.
+    assert!(compression_lookup_table.len() >= inputs.len());
+    assert_eq!(inputs.len(), outputs.len());
+    for (input, output, compression) in izip!(&inputs, &outputs, &compression_lookup_table) {
+        let rounds = u32::from_be_bytes(input[..4].try_into().unwrap());
+        let expected = (
+            rlc(input),
+            F::from(rounds as u64),
+            F::from((input[input.len() - 1] <= 1) as u64),
+            rlc(&hex::decode(output).unwrap()),
+        );
+        assert_eq!(*compression, expected);
+    }
+
+    // Check that other outputs are the compression of the all-zero input.
+    let zero_compression = (
+        F::ZERO,
+        F::ZERO,
+        F::ONE,
+        rlc(&hex::decode(ZERO_INPUT_OUTPUT).unwrap()),
+    );
+    for compression in compression_lookup_table.iter().skip(inputs.len()) {
+        assert_eq!(*compression, zero_compression);
+    }
+}
+
+fn assigned_non_zero<F: Field>(cv: &CellValue<F>) -> bool {
+    match *cv {
+        CellValue::Assigned(v) => !v.is_zero_vartime(),
+        _ => false,
+    }
+}
+
+fn unwrap<F: Field>(cv: &CellValue<F>) -> F {
+    match *cv {
+        CellValue::Assigned(f) => f,
+        _ => panic!("the cell should be assigned"),
+    }
+}
+
+#[test]
+fn multi_blake2f_simple() {
+    let k = 12;
+    let inputs = vec![
+        input(0, 1),
+        input(12, 1),
+        input(12, 0),
+        input(1, 1),
+        input(12, 2),
+    ];
+    let outputs = vec![
+        "08c9bcf367e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5\
+         d282e6ad7f520e511f6c3e2b8c68059b9442be0454267ce079217e1319cde05b",
+        "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d1\
+         7d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923",
+        "75ab69d3190a562c51aef8d88f1c2775876944407270c42c9844252c26d28752\
+         98743e7f6d5ea2f2d3e8d226039cd31b4e426ac4f2d3d666a610c2116fde4735",
+        "b63a380cb2897d521994a85234ee2c181b5f844d2c624c002677e9703449d2fb\
+         a551b3a8333bcdf5f2f7e08993d53923de3d64fcc68c034e717b9293fed7a421",
+        // An invalid final block flag has no output.
+        "",
+    ];
+    verify::<Fr>(k, inputs, outputs, true);
+}
+
+#[test]
+fn variadic_size_check() {
+    let k = 11;
+    let num_rows = 2usize.pow(k);
+    // Empty
+    let inputs = vec![];
+    let circuit = Blake2fCircuit::new(num_rows, inputs);
+    let prover1 = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
+
+    // Non-empty
+    let inputs = vec![input(0, 1), input(12, 1), input(1, 2)];
+    let circuit = Blake2fCircuit::new(num_rows, inputs);
+    let prover2 = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
+
+    assert_eq!(prover1.fixed(), prover2.fixed());
+    assert_eq!(prover1.permutation(), prover2.permutation());
+}
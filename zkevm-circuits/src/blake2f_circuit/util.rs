@@ -0,0 +1,81 @@
+use super::param::*;
+use eth_types::Field;
+use gadgets::util::Expr;
+use halo2_proofs::plonk::Expression;
+use std::array;
+
+/// A 64-bit word decomposed in bits, least significant bit first.
+pub(crate) type Bits<F> = [Expression<F>; NUM_BITS_PER_WORD];
+
+/// Decomposes a word in bits, least significant bit first.
+pub(crate) fn to_bits(value: u64) -> [bool; NUM_BITS_PER_WORD] {
+    array::from_fn(|i| (value >> i) & 1 == 1)
+}
+
+/// Rotates the bits of a word to the right.
+pub(crate) fn rotate_right<F: Field>(bits: &Bits<F>, n: usize) -> Bits<F> {
+    array::from_fn(|i| bits[(i + n) % NUM_BITS_PER_WORD].clone())
+}
+
+/// Xors the bits of two words.
+pub(crate) fn xor<F: Field>(x: &Bits<F>, y: &Bits<F>) -> Bits<F> {
+    array::from_fn(|i| x[i].clone() + y[i].clone() - 2.expr() * x[i].clone() * y[i].clone())
+}
+
+/// Xors the bits of a word with a constant.
+pub(crate) fn xor_constant<F: Field>(bits: &Bits<F>, constant: u64) -> Bits<F> {
+    array::from_fn(|i| {
+        if (constant >> i) & 1 == 1 {
+            1.expr() - bits[i].clone()
+        } else {
+            bits[i].clone()
+        }
+    })
+}
+
+/// Recombines bits into the word value
+pub(crate) mod decode {
+    use super::*;
+
+    pub(crate) fn expr<F: Field>(bits: &[Expression<F>]) -> Expression<F> {
+        bits.iter()
+            .rev()
+            .fold(0.expr(), |acc, bit| acc * 2.expr() + bit.clone())
+    }
+}
+
+/// An operation of the mixing function `G` on the state words, either
+/// `v[dst] = v[dst] + v[src] (+ m)` or `v[dst] = (v[dst] ^ v[src]) >>> r`.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Operation {
+    pub(crate) dst: usize,
+    pub(crate) src: usize,
+    /// The index of the message word added, before the permutation
+    pub(crate) message: Option<usize>,
+    /// The index of the rotation in [`ROTATIONS`] of a xor
+    pub(crate) rotation: Option<usize>,
+}
+
+impl Operation {
+    /// Returns the operation of the row `idx` of a round segment.
+    pub(crate) fn new(idx: usize) -> Self {
+        let mixing = idx / NUM_OPERATIONS_PER_MIXING;
+        let [a, b, c, d] = MIXING_WORDS[mixing];
+        let (dst, src, message, rotation) = match idx % NUM_OPERATIONS_PER_MIXING {
+            0 => (a, b, Some(2 * mixing), None),
+            1 => (d, a, None, Some(0)),
+            2 => (c, d, None, None),
+            3 => (b, c, None, Some(1)),
+            4 => (a, b, Some(2 * mixing + 1), None),
+            5 => (d, a, None, Some(2)),
+            6 => (c, d, None, None),
+            _ => (b, c, None, Some(3)),
+        };
+        Self {
+            dst,
+            src,
+            message,
+            rotation,
+        }
+    }
+}
@@ -1,6 +1,6 @@
 //! The Copy circuit implements constraints and lookups for read-write steps for
 //! copied bytes while execution opcodes such as CALLDATACOPY, CODECOPY, LOGS,
-//! etc.
+//! MCOPY, etc.
 pub(crate) mod util;
 
 #[cfg(any(test, feature = "test-circuits"))]
@@ -11,6 +11,7 @@ mod test;
 pub use dev::CopyCircuit as TestCopyCircuit;
 
 use crate::{
+    copy_circuit::util::number_or_hash_to_word,
     evm_circuit::util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
     table::{
         BytecodeFieldTag, BytecodeTable, CopyTable, LookupTable, RwTable, TxContextFieldTag,
@@ -28,6 +29,7 @@ use bus_mapping::{
 use eth_types::Field;
 use gadgets::{
     binary_number::{BinaryNumberChip, BinaryNumberConfig},
+    is_zero::{IsZeroChip, IsZeroConfig, IsZeroInstruction},
     less_than::{LtChip, LtConfig, LtInstruction},
     util::{and, not, or, Expr},
 };
@@ -64,6 +66,13 @@ pub struct CopyCircuitConfig<F> {
     /// In case of a bytecode tag, this denotes whether or not the copied byte
     /// is an opcode or push data byte.
     pub is_code: Column<Advice>,
+    /// Whether the copy event copies within the memory of a single call
+    /// (MCOPY). Such events read all the source bytes before writing any
+    /// destination byte.
+    pub is_memory_copy: Column<Advice>,
+    /// IsZero chip to check whether the read and write rows of a step have
+    /// the same id.
+    pub is_id_unchanged: IsZeroConfig<F>,
     /// Whether the row is enabled or not.
     pub q_enable: Column<Fixed>,
     /// The Copy Table contains the columns that are exposed via the lookup
@@ -120,6 +129,8 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
         let value_acc_rlc = meta.advice_column_in(SecondPhase);
         let is_code = meta.advice_column();
         let is_pad = meta.advice_column();
+        let is_memory_copy = meta.advice_column();
+        let is_id_unchanged_inv = meta.advice_column();
         let is_first = copy_table.is_first;
         let id = copy_table.id;
         let addr = copy_table.addr;
@@ -143,6 +154,16 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
             |meta| meta.query_advice(src_addr_end, Rotation::cur()),
         );
 
+        let is_id_unchanged = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_selector(q_step),
+            |meta| {
+                meta.query_advice(id.lo(), Rotation::cur())
+                    - meta.query_advice(id.lo(), Rotation::next())
+            },
+            is_id_unchanged_inv,
+        );
+
         meta.create_gate("verify row", |meta| {
             let mut cb = BaseConstraintBuilder::default();
 
@@ -175,7 +196,7 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
                 - meta.query_advice(is_last, Rotation::cur())
                 - meta.query_advice(is_last, Rotation::next());
             cb.condition(
-                not_last_two_rows
+                not_last_two_rows.clone()
                     * (not::expr(tag.value_equals(CopyDataType::Padding, Rotation::cur())(
                         meta,
                     ))),
@@ -210,8 +231,22 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
                 ]),
                 not::expr(meta.query_advice(is_pad, Rotation::cur())),
             ]);
+            let is_memory_copy = meta.query_advice(is_memory_copy, Rotation::cur());
             cb.condition(
                 not::expr(meta.query_advice(is_last, Rotation::cur())),
+                |cb| {
+                    cb.require_equal(
+                        "rows[0].rlc_acc == rows[1].rlc_acc",
+                        meta.query_advice(rlc_acc, Rotation::cur()),
+                        meta.query_advice(rlc_acc, Rotation::next()),
+                    );
+                },
+            );
+            cb.condition(
+                and::expr([
+                    not::expr(meta.query_advice(is_last, Rotation::cur())),
+                    not::expr(is_memory_copy.clone()),
+                ]),
                 |cb| {
                     cb.require_equal(
                         "rows[0].rw_counter + rw_diff == rows[1].rw_counter",
@@ -223,13 +258,33 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
                         meta.query_advice(rwc_inc_left, Rotation::cur()) - rw_diff.clone(),
                         meta.query_advice(rwc_inc_left, Rotation::next()),
                     );
+                },
+            );
+            // A memory copy does all of its reads before its writes: reads
+            // and writes each take consecutive rw counters, and the writes
+            // start right after the last read.
+            cb.condition(
+                and::expr([
+                    not::expr(meta.query_advice(is_last, Rotation::cur())),
+                    is_memory_copy.clone(),
+                ]),
+                |cb| {
                     cb.require_equal(
-                        "rows[0].rlc_acc == rows[1].rlc_acc",
-                        meta.query_advice(rlc_acc, Rotation::cur()),
-                        meta.query_advice(rlc_acc, Rotation::next()),
+                        "rows[0].rw_counter + rows[0].rwc_inc_left == rows[1].rw_counter + rows[1].rwc_inc_left",
+                        meta.query_advice(rw_counter, Rotation::cur())
+                            + meta.query_advice(rwc_inc_left, Rotation::cur()),
+                        meta.query_advice(rw_counter, Rotation::next())
+                            + meta.query_advice(rwc_inc_left, Rotation::next()),
                     );
                 },
             );
+            cb.condition(not_last_two_rows * is_memory_copy, |cb| {
+                cb.require_equal(
+                    "rows[0].rw_counter + 1 == rows[2].rw_counter for memory copy",
+                    meta.query_advice(rw_counter, Rotation::cur()) + 1.expr(),
+                    meta.query_advice(rw_counter, Rotation(2)),
+                );
+            });
             cb.condition(meta.query_advice(is_last, Rotation::cur()), |cb| {
                 cb.require_equal(
                     "rwc_inc_left == rw_diff for last row in the copy slot",
@@ -338,6 +393,31 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
                 "is_pad == 0 for write row",
                 meta.query_advice(is_pad, Rotation::next()),
             );
+            cb.require_boolean(
+                "is_memory_copy is boolean",
+                meta.query_advice(is_memory_copy, Rotation::cur()),
+            );
+            cb.require_equal(
+                "is_memory_copy is same for read-write rows",
+                meta.query_advice(is_memory_copy, Rotation::cur()),
+                meta.query_advice(is_memory_copy, Rotation::next()),
+            );
+            cb.require_equal(
+                "is_memory_copy == memory to memory copy within the same id",
+                meta.query_advice(is_memory_copy, Rotation::cur()),
+                and::expr([
+                    tag.value_equals(CopyDataType::Memory, Rotation::cur())(meta),
+                    tag.value_equals(CopyDataType::Memory, Rotation::next())(meta),
+                    is_id_unchanged.expr(),
+                ]),
+            );
+            cb.condition(meta.query_advice(is_memory_copy, Rotation::cur()), |cb| {
+                cb.require_equal(
+                    "rwc_inc_left of write row == bytes_left for memory copy",
+                    meta.query_advice(rwc_inc_left, Rotation::next()),
+                    meta.query_advice(bytes_left, Rotation::cur()),
+                );
+            });
 
             cb.gate(and::expr([meta.query_selector(q_step)]))
         });
@@ -468,6 +548,8 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
             value_acc_rlc,
             is_pad,
             is_code,
+            is_memory_copy,
+            is_id_unchanged,
             q_enable,
             addr_lt_addr_end,
             copy_table,
@@ -509,9 +591,13 @@ impl<F: Field> CopyCircuitConfig<F> {
         offset: &mut usize,
         tag_chip: &BinaryNumberChip<F, CopyDataType, 3>,
         lt_chip: &LtChip<F, 8>,
+        is_id_unchanged_chip: &IsZeroChip<F>,
         challenges: Challenges<Value<F>>,
         copy_event: &CopyEvent,
     ) -> Result<(), Error> {
+        let is_memory_copy = Value::known(F::from(copy_event.is_memory_copy() as u64));
+        let id_diff = number_or_hash_to_word::<F>(&copy_event.src_id).lo()
+            - number_or_hash_to_word::<F>(&copy_event.dst_id).lo();
         for (step_idx, (tag, table_row, circuit_row)) in
             CopyTable::assignments(copy_event, challenges)
                 .iter()
@@ -568,9 +654,28 @@ impl<F: Field> CopyCircuitConfig<F> {
                 )?;
             }
 
+            // is_memory_copy
+            region.assign_advice(
+                || format!("is_memory_copy at row: {}", *offset),
+                self.is_memory_copy,
+                *offset,
+                || is_memory_copy,
+            )?;
+
             // tag
             tag_chip.assign(region, *offset, tag)?;
 
+            // is_id_unchanged chip
+            is_id_unchanged_chip.assign(
+                region,
+                *offset,
+                if is_read {
+                    id_diff
+                } else {
+                    Value::known(F::ZERO)
+                },
+            )?;
+
             // lt chip
             if is_read {
                 lt_chip.assign(
@@ -607,6 +712,7 @@ impl<F: Field> CopyCircuitConfig<F> {
 
         let tag_chip = BinaryNumberChip::construct(self.copy_table.tag);
         let lt_chip = LtChip::construct(self.addr_lt_addr_end);
+        let is_id_unchanged_chip = IsZeroChip::construct(self.is_id_unchanged.clone());
 
         lt_chip.load(layouter)?;
 
@@ -617,6 +723,7 @@ impl<F: Field> CopyCircuitConfig<F> {
                 region.name_column(|| "value", self.value);
                 region.name_column(|| "is_code", self.is_code);
                 region.name_column(|| "is_pad", self.is_pad);
+                region.name_column(|| "is_memory_copy", self.is_memory_copy);
 
                 let mut offset = 0;
                 for copy_event in copy_events.iter() {
@@ -625,18 +732,33 @@ impl<F: Field> CopyCircuitConfig<F> {
                         &mut offset,
                         &tag_chip,
                         &lt_chip,
+                        &is_id_unchanged_chip,
                         challenges,
                         copy_event,
                     )?;
                 }
 
                 for _ in 0..filler_rows {
-                    self.assign_padding_row(&mut region, &mut offset, true, &tag_chip, &lt_chip)?;
+                    self.assign_padding_row(
+                        &mut region,
+                        &mut offset,
+                        true,
+                        &tag_chip,
+                        &lt_chip,
+                        &is_id_unchanged_chip,
+                    )?;
                 }
                 assert_eq!(offset % 2, 0, "enabled rows must come in pairs");
 
                 for _ in 0..DISABLED_ROWS {
-                    self.assign_padding_row(&mut region, &mut offset, false, &tag_chip, &lt_chip)?;
+                    self.assign_padding_row(
+                        &mut region,
+                        &mut offset,
+                        false,
+                        &tag_chip,
+                        &lt_chip,
+                        &is_id_unchanged_chip,
+                    )?;
                 }
 
                 Ok(())
@@ -651,6 +773,7 @@ impl<F: Field> CopyCircuitConfig<F> {
         enabled: bool,
         tag_chip: &BinaryNumberChip<F, CopyDataType, 3>,
         lt_chip: &LtChip<F, 8>,
+        is_id_unchanged_chip: &IsZeroChip<F>,
     ) -> Result<(), Error> {
         // q_enable
         region.assign_fixed(
@@ -761,8 +884,17 @@ impl<F: Field> CopyCircuitConfig<F> {
             *offset,
             || Value::known(F::ZERO),
         )?;
+        // is_memory_copy
+        region.assign_advice(
+            || format!("assign is_memory_copy {}", *offset),
+            self.is_memory_copy,
+            *offset,
+            || Value::known(F::ZERO),
+        )?;
         // tag
         tag_chip.assign(region, *offset, &CopyDataType::Padding)?;
+        // Assign IsZero gadget
+        is_id_unchanged_chip.assign(region, *offset, Value::known(F::ZERO))?;
         // Assign LT gadget
         lt_chip.assign(region, *offset, Value::known(F::ZERO), Value::known(F::ONE))?;
 
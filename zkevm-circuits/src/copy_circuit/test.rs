@@ -149,6 +149,29 @@ fn gen_sha3_data() -> CircuitInputBuilder<FixedCParams> {
         .unwrap()
 }
 
+fn gen_mcopy_data() -> CircuitInputBuilder<FixedCParams> {
+    // The destination overlaps the source.
+    let code = bytecode! {
+        PUSH32(Word::from_big_endian(&rand_bytes(0x20)))
+        PUSH1(0x00)
+        MSTORE
+        PUSH32(Word::from_big_endian(&rand_bytes(0x20)))
+        PUSH1(0x20)
+        MSTORE
+        PUSH32(Word::from(0x40))
+        PUSH32(Word::from(0x00))
+        PUSH32(Word::from(0x10))
+        MCOPY
+        STOP
+    };
+    let test_ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap();
+    let block: GethData = test_ctx.into();
+    let builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+    builder
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap()
+}
+
 fn gen_tx_log_data() -> CircuitInputBuilder<FixedCParams> {
     let code = bytecode! {
         PUSH32(200)         // value
@@ -200,6 +223,13 @@ fn copy_circuit_valid_sha3() {
     assert_eq!(test_copy_circuit_from_block(14, block), Ok(()));
 }
 
+#[test]
+fn copy_circuit_valid_mcopy() {
+    let builder = gen_mcopy_data();
+    let block = block_convert::<Fr>(&builder).unwrap();
+    assert_eq!(test_copy_circuit_from_block(10, block), Ok(()));
+}
+
 #[test]
 fn copy_circuit_valid_tx_log() {
     let builder = gen_tx_log_data();
@@ -271,6 +301,22 @@ fn copy_circuit_invalid_sha3() {
     );
 }
 
+#[test]
+fn copy_circuit_invalid_mcopy() {
+    let mut builder = gen_mcopy_data();
+
+    // modify first byte of first copy event
+    builder.block.copy_events[0].bytes[0].0 =
+        builder.block.copy_events[0].bytes[0].0.wrapping_add(1);
+
+    let block = block_convert::<Fr>(&builder).unwrap();
+
+    assert_error_matches(
+        test_copy_circuit_from_block(10, block),
+        vec!["Memory lookup", "Memory lookup"],
+    );
+}
+
 #[test]
 fn copy_circuit_invalid_tx_log() {
     let mut builder = gen_tx_log_data();
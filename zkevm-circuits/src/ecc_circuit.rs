@@ -0,0 +1,933 @@
+//! The ECC circuit implementation.
+//!
+//! The circuit verifies the operations of the ECADD and ECMUL precompile calls
+//! on the BN254 curve with the non-native `ecc` and `integer` chips, and
+//! exposes them in the [`EccTable`].  The coordinates of the points are
+//! assigned as integers of the base field, which are decomposed in bytes to
+//! build the words of the table.
+//!
+//! The precompiles encode the point at infinity as `(0, 0)`, which can't be
+//! represented by the ecc chip, so that:
+//! - an addition verifies the curve equation on the points, and computes the sum with the slope
+//!   `lambda` of the line through the points, or of the tangent for a doubling.  The sum is `Q`
+//!   when `P` is the identity, `P` when `Q` is, and the identity when `P = -Q`.
+//! - a multiplication is done by the ecc chip when the point is on the curve and the scalar is not
+//!   zero, and the product is the identity otherwise.  The generator is multiplied by one instead
+//!   in the latter case, and the result is discarded.
+//!
+//! The outputs of an operation on invalid points are zero.
+//!
+//! The pairing checks of the ECPAIRING precompile calls can't be verified by
+//! the circuit, as halo2wrong has no pairing chip, so that they are public
+//! inputs.  Every pairing takes [`ROWS_PER_PAIRING`] rows at the start of the
+//! table, one per byte of an input of up to [`N_PAIRING_PER_OP`] pairs, which
+//! are accumulated in the `input_rlc` and `input_len` columns of the table,
+//! enabled on the last row of the pairing.  They're given by four instance
+//! columns, after the one of the main gate:
+//! - the input bytes of the pairings, one per row, padded with zeroes.
+//! - whether the row holds an input byte.
+//! - the result of the pairing check, on the last row of the pairing, and zero otherwise.
+//! - whether the points are valid, on the last row of the pairing, and zero otherwise.
+//!
+//! The verifier runs ECPAIRING on the input bytes of every group of
+//! [`ROWS_PER_PAIRING`] rows, which rejects bytes greater than 255, and checks
+//! its result and success against the last two columns.  The padding pairings
+//! have an empty input, whose check succeeds.
+#[cfg(any(test, feature = "test-circuits"))]
+mod dev;
+#[cfg(test)]
+mod test;
+#[cfg(feature = "test-circuits")]
+pub use dev::EccCircuit as TestEccCircuit;
+
+use std::{iter, marker::PhantomData};
+
+use crate::{
+    evm_circuit::util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+    table::{EccOpType, EccTable, LookupTable},
+    tx_circuit::sign_verify::{
+        integer_to_bytes_le, word_from_bytes_le, BIT_LEN_LIMB, NUMBER_OF_LIMBS,
+    },
+    util::{word::Word, Challenges, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use bus_mapping::{
+    circuit_input_builder::{EcAddOp, EcMulOp, EcPairingOp},
+    precompile::{PrecompileEcParams, N_BYTES_EC_PAIR},
+};
+use ecc::{maingate, AssignedPoint, EccConfig, GeneralEccChip};
+use eth_types::{Field, ToLittleEndian, U256};
+use gadgets::util::{not, Expr};
+use halo2_proofs::{
+    arithmetic::CurveAffine,
+    circuit::{Layouter, Region, Value},
+    halo2curves::{
+        bn256::{Fq, Fr, G1Affine},
+        ff::{Field as _, PrimeField},
+        group::{Curve, Group},
+    },
+    plonk::{Column, ConstraintSystem, Error, Expression, Fixed, Instance},
+    poly::Rotation,
+};
+use integer::{AssignedInteger, IntegerChip, IntegerInstructions, Range};
+use itertools::Itertools;
+use log::error;
+use maingate::{
+    AssignedCondition, AssignedValue, MainGate, MainGateConfig, MainGateInstructions, RangeChip,
+    RangeConfig, RangeInstructions, RegionCtx,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+
+type EccChip<F> = GeneralEccChip<G1Affine, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>;
+type AssignedBase<F> = AssignedInteger<Fq, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>;
+
+/// Maximum number of pairs of G1 and G2 points of a pairing check supported by
+/// the circuit.
+pub const N_PAIRING_PER_OP: usize = 4;
+
+/// Number of rows taken by a pairing check, one per byte of its input.
+pub const ROWS_PER_PAIRING: usize = N_PAIRING_PER_OP * N_BYTES_EC_PAIR;
+
+/// EccCircuitConfig
+#[derive(Clone, Debug)]
+pub struct EccCircuitConfig<F> {
+    main_gate_config: MainGateConfig,
+    range_config: RangeConfig,
+    q_pairing: Column<Fixed>,
+    q_pairing_first: Column<Fixed>,
+    q_pairing_last: Column<Fixed>,
+    pairing_input: Column<Instance>,
+    pairing_is_input: Column<Instance>,
+    pairing_output: Column<Instance>,
+    pairing_is_valid: Column<Instance>,
+    /// The columns for other circuits to lookup the ECC operations
+    pub ecc_table: EccTable,
+    _marker: PhantomData<F>,
+}
+
+/// Circuit configuration arguments
+pub struct EccCircuitConfigArgs<F: Field> {
+    /// EccTable
+    pub ecc_table: EccTable,
+    /// Challenges randomness
+    pub challenges: Challenges<Expression<F>>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for EccCircuitConfig<F> {
+    type ConfigArgs = EccCircuitConfigArgs<F>;
+
+    /// Return a new EccCircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            ecc_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let (rns_base, rns_scalar) = EccChip::<F>::rns();
+        let main_gate_config = MainGate::<F>::configure(meta);
+        let range_config = RangeChip::<F>::configure(
+            meta,
+            &main_gate_config,
+            vec![BIT_LEN_LIMB / NUMBER_OF_LIMBS, 8],
+            [rns_base.overflow_lengths(), rns_scalar.overflow_lengths()].concat(),
+        );
+
+        let q_pairing = meta.fixed_column();
+        let q_pairing_first = meta.fixed_column();
+        let q_pairing_last = meta.fixed_column();
+        let pairing_input = meta.instance_column();
+        let pairing_is_input = meta.instance_column();
+        let pairing_output = meta.instance_column();
+        let pairing_is_valid = meta.instance_column();
+
+        meta.create_gate("pairing input rlc", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let byte = meta.query_instance(pairing_input, Rotation::cur());
+            let is_input = meta.query_instance(pairing_is_input, Rotation::cur());
+            let not_first = not::expr(meta.query_fixed(q_pairing_first, Rotation::cur()));
+            let input_rlc = meta.query_advice(ecc_table.input_rlc, Rotation::cur());
+            let input_rlc_prev =
+                not_first.expr() * meta.query_advice(ecc_table.input_rlc, Rotation::prev());
+            let input_len = meta.query_advice(ecc_table.input_len, Rotation::cur());
+            let input_len_prev =
+                not_first * meta.query_advice(ecc_table.input_len, Rotation::prev());
+            cb.require_boolean("is_input is boolean", is_input.expr());
+            cb.require_equal(
+                "input_rlc = input_rlc_prev * r + byte for an input byte",
+                input_rlc,
+                input_rlc_prev.expr()
+                    + is_input.expr()
+                        * (input_rlc_prev * (challenges.keccak_input() - 1.expr()) + byte),
+            );
+            cb.require_equal(
+                "input_len = input_len_prev + 1 for an input byte",
+                input_len,
+                input_len_prev + is_input,
+            );
+
+            cb.gate(meta.query_fixed(q_pairing, Rotation::cur()))
+        });
+
+        meta.create_gate("pairing table row", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_equal(
+                "op_type is Pairing",
+                meta.query_advice(ecc_table.op_type, Rotation::cur()),
+                EccOpType::Pairing.expr(),
+            );
+            for column in [
+                ecc_table.arg1,
+                ecc_table.arg2,
+                ecc_table.arg3,
+                ecc_table.arg4,
+                ecc_table.output2,
+            ]
+            .iter()
+            .flat_map(|word| [word.lo(), word.hi()])
+            .chain(iter::once(ecc_table.output1.hi()))
+            {
+                cb.require_zero(
+                    "args and unused outputs are zero",
+                    meta.query_advice(column, Rotation::cur()),
+                );
+            }
+            cb.require_equal(
+                "output is a public input",
+                meta.query_advice(ecc_table.output1.lo(), Rotation::cur()),
+                meta.query_instance(pairing_output, Rotation::cur()),
+            );
+            cb.require_equal(
+                "is_valid is a public input",
+                meta.query_advice(ecc_table.is_valid, Rotation::cur()),
+                meta.query_instance(pairing_is_valid, Rotation::cur()),
+            );
+
+            cb.gate(meta.query_fixed(q_pairing_last, Rotation::cur()))
+        });
+
+        Self {
+            main_gate_config,
+            range_config,
+            q_pairing,
+            q_pairing_first,
+            q_pairing_last,
+            pairing_input,
+            pairing_is_input,
+            pairing_output,
+            pairing_is_valid,
+            ecc_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> EccCircuitConfig<F> {
+    /// Load the RangeChip table.
+    pub(crate) fn load_aux_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        let range_chip = RangeChip::<F>::new(self.range_config.clone());
+        range_chip.load_table(layouter)
+    }
+
+    fn ecc_chip_config(&self) -> EccConfig {
+        EccConfig::new(self.range_config.clone(), self.main_gate_config.clone())
+    }
+
+    fn annotate_circuit(&self, region: &mut Region<F>) {
+        region.name_column(|| "ECC_q_pairing", self.q_pairing);
+        region.name_column(|| "ECC_q_pairing_first", self.q_pairing_first);
+        region.name_column(|| "ECC_q_pairing_last", self.q_pairing_last);
+        region.name_column(|| "ECC_pairing_input", self.pairing_input);
+        region.name_column(|| "ECC_pairing_is_input", self.pairing_is_input);
+        region.name_column(|| "ECC_pairing_output", self.pairing_output);
+        region.name_column(|| "ECC_pairing_is_valid", self.pairing_is_valid);
+    }
+}
+
+/// Helper structure pass around references to all the chips required for the
+/// ECC operations.
+struct ChipsRef<'a, F: Field> {
+    main_gate: &'a MainGate<F>,
+    range_chip: &'a RangeChip<F>,
+    ecc_chip: &'a EccChip<F>,
+    base_chip: &'a IntegerChip<Fq, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+    scalar_chip: &'a IntegerChip<Fr, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+}
+
+/// The cells of an addition or a multiplication to copy into the EccTable.
+struct AssignedEccOp<F: Field> {
+    op_type: AssignedValue<F>,
+    args: [Word<AssignedValue<F>>; 4],
+    outputs: [Word<AssignedValue<F>>; 2],
+    is_valid: AssignedCondition<F>,
+    /// Zero, for the input rlc and length of the table row.
+    zero: AssignedValue<F>,
+}
+
+impl<F: Field> AssignedEccOp<F> {
+    /// Returns the cells in the order of the advice columns of the EccTable.
+    fn table_cells(&self) -> Vec<AssignedValue<F>> {
+        iter::once(self.op_type.clone())
+            .chain(self.args.iter().flat_map(|arg| [arg.lo(), arg.hi()]))
+            .chain([self.zero.clone(), self.zero.clone()])
+            .chain(
+                self.outputs
+                    .iter()
+                    .flat_map(|output| [output.lo(), output.hi()]),
+            )
+            .chain(iter::once(self.is_valid.clone()))
+            .collect()
+    }
+}
+
+// The coordinates of the operations are lower than the modulus of the base
+// field, and the scalars lower than the order of the curve.
+fn base_from_word(word: U256) -> Fq {
+    Fq::from_bytes(&word.to_le_bytes()).unwrap()
+}
+
+fn scalar_from_word(word: U256) -> Fr {
+    Fr::from_bytes(&word.to_le_bytes()).unwrap()
+}
+
+fn is_on_curve(x: Fq, y: Fq) -> bool {
+    y.square() == x.square() * x + Fq::from(3)
+}
+
+fn assign_base<F: Field>(
+    ctx: &mut RegionCtx<'_, F>,
+    chips: &ChipsRef<F>,
+    value: Fq,
+) -> Result<AssignedBase<F>, Error> {
+    let unassigned = chips.ecc_chip.new_unassigned_base(Value::known(value));
+    chips
+        .base_chip
+        .assign_integer(ctx, unassigned, Range::Remainder)
+}
+
+// Return the word of an integer, adding the constraints of its decomposition
+// in bytes.
+fn assign_word<F: Field, FE: PrimeField>(
+    ctx: &mut RegionCtx<'_, F>,
+    chips: &ChipsRef<F>,
+    int: &AssignedInteger<FE, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+) -> Result<Word<AssignedValue<F>>, Error> {
+    let powers_of_256 = iter::successors(Some(F::ONE), |coeff| Some(F::from(256) * coeff))
+        .take(16)
+        .collect_vec();
+    let bytes_le = integer_to_bytes_le(ctx, chips.range_chip, int)?;
+    word_from_bytes_le(ctx, chips.main_gate, &bytes_le, &powers_of_256)
+}
+
+// Return whether the point encoded by the words is `(0, 0)`.
+fn is_identity<F: Field>(
+    ctx: &mut RegionCtx<'_, F>,
+    main_gate: &MainGate<F>,
+    x: &Word<AssignedValue<F>>,
+    y: &Word<AssignedValue<F>>,
+) -> Result<AssignedCondition<F>, Error> {
+    let mut is_zero = main_gate.is_zero(ctx, &x.lo())?;
+    for cell in [x.hi(), y.lo(), y.hi()] {
+        let is_zero_cell = main_gate.is_zero(ctx, &cell)?;
+        is_zero = main_gate.and(ctx, &is_zero, &is_zero_cell)?;
+    }
+    Ok(is_zero)
+}
+
+// Return whether the integer is zero modulo the base field, from a witnessed
+// bit which is constrained both ways.
+fn assign_is_zero<F: Field>(
+    ctx: &mut RegionCtx<'_, F>,
+    chips: &ChipsRef<F>,
+    value: &AssignedBase<F>,
+    is_zero: bool,
+) -> Result<AssignedCondition<F>, Error> {
+    let base_chip = chips.base_chip;
+    let bit = chips
+        .main_gate
+        .assign_bit(ctx, Value::known(F::from(is_zero as u64)))?;
+    let zero = base_chip.assign_constant(ctx, Fq::ZERO)?;
+    let one = base_chip.assign_constant(ctx, Fq::ONE)?;
+    // value == 0 when the bit is set
+    let selected = base_chip.cond_select(ctx, value, &zero, &bit)?;
+    base_chip.assert_zero(ctx, &selected)?;
+    // value != 0 otherwise
+    let selected = base_chip.cond_select(ctx, &one, value, &bit)?;
+    base_chip.assert_not_zero(ctx, &selected)?;
+    Ok(bit)
+}
+
+// Return whether the point verifies the curve equation `y^2 = x^3 + 3`.
+fn assign_is_on_curve<F: Field>(
+    ctx: &mut RegionCtx<'_, F>,
+    chips: &ChipsRef<F>,
+    (x, y): (&AssignedBase<F>, &AssignedBase<F>),
+    (x_value, y_value): (Fq, Fq),
+) -> Result<AssignedCondition<F>, Error> {
+    let base_chip = chips.base_chip;
+    let y_square = base_chip.square(ctx, y)?;
+    let x_square = base_chip.square(ctx, x)?;
+    let x_cube = base_chip.mul(ctx, &x_square, x)?;
+    let b = base_chip.assign_constant(ctx, Fq::from(3))?;
+    let rhs = base_chip.add(ctx, &x_cube, &b)?;
+    let diff = base_chip.sub(ctx, &y_square, &rhs)?;
+    assign_is_zero(ctx, chips, &diff, is_on_curve(x_value, y_value))
+}
+
+/// EccCircuit
+#[derive(Clone, Debug)]
+pub struct EccCircuit<F: Field> {
+    add_ops: Vec<EcAddOp>,
+    mul_ops: Vec<EcMulOp>,
+    pairing_ops: Vec<EcPairingOp>,
+    max_ec_ops: PrecompileEcParams,
+    /// Aux generator for EccChip
+    aux_generator: G1Affine,
+    /// Window size for EccChip
+    window_size: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> Default for EccCircuit<F> {
+    fn default() -> Self {
+        Self {
+            add_ops: Vec::new(),
+            mul_ops: Vec::new(),
+            pairing_ops: Vec::new(),
+            max_ec_ops: PrecompileEcParams::default(),
+            aux_generator: G1Affine::default(),
+            window_size: 4,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> EccCircuit<F> {
+    /// Creates a new circuit instance
+    pub fn new(
+        max_ec_ops: PrecompileEcParams,
+        add_ops: Vec<EcAddOp>,
+        mul_ops: Vec<EcMulOp>,
+        pairing_ops: Vec<EcPairingOp>,
+    ) -> Self {
+        // The aux generator is chosen as in `SignVerifyChip`.
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let aux_generator = <G1Affine as CurveAffine>::CurveExt::random(&mut rng).to_affine();
+        Self {
+            add_ops,
+            mul_ops,
+            pairing_ops,
+            max_ec_ops,
+            aux_generator,
+            window_size: 4,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return the minimum number of rows required to prove the operations.
+    pub fn min_num_rows(num_add: usize, num_mul: usize, num_pairing: usize) -> usize {
+        // These are estimates of the rows taken by the chips, the range chip
+        // table being the same as in `SignVerifyChip::min_num_rows`.
+        let rows_range_chip_table = 295188;
+        let rows_ecc_chip_aux = 226;
+        let rows_add = 3000;
+        let rows_mul = 40000;
+        let rows_table = ROWS_PER_PAIRING * num_pairing + num_add + num_mul;
+        std::cmp::max(
+            rows_range_chip_table,
+            std::cmp::max(
+                rows_ecc_chip_aux + rows_add * num_add + rows_mul * num_mul,
+                rows_table,
+            ),
+        )
+    }
+
+    /// Returns the operations to verify, padded up to the maximum numbers of
+    /// `max_ec_ops` when they're set, with operations on the identity and
+    /// pairings of no points.
+    #[allow(clippy::type_complexity)]
+    fn padded_ops(&self) -> Result<(Vec<EcAddOp>, Vec<EcMulOp>, Vec<EcPairingOp>), Error> {
+        let mut add_ops = self.add_ops.clone();
+        let mut mul_ops = self.mul_ops.clone();
+        let mut pairing_ops = self.pairing_ops.clone();
+        for (name, len, max) in [
+            ("ecAdd", add_ops.len(), self.max_ec_ops.ec_add),
+            ("ecMul", mul_ops.len(), self.max_ec_ops.ec_mul),
+            (
+                "ecPairing",
+                self.pairing_ops.len(),
+                self.max_ec_ops.ec_pairing,
+            ),
+        ] {
+            if max > 0 && len > max {
+                error!(
+                    "{} ops exceed capacity.  needed = {}, available = {}",
+                    name, len, max
+                );
+                return Err(Error::BoundsFailure);
+            }
+        }
+        if let Some(op) = pairing_ops
+            .iter()
+            .find(|op| op.input.len() > N_PAIRING_PER_OP * N_BYTES_EC_PAIR)
+        {
+            error!(
+                "ecPairing input exceeds capacity.  needed = {} pairs, available = {}",
+                op.input.len() / N_BYTES_EC_PAIR,
+                N_PAIRING_PER_OP
+            );
+            return Err(Error::BoundsFailure);
+        }
+        if self.max_ec_ops.ec_add > 0 {
+            add_ops.resize(
+                self.max_ec_ops.ec_add,
+                EcAddOp {
+                    is_valid: true,
+                    ..Default::default()
+                },
+            );
+        }
+        if self.max_ec_ops.ec_mul > 0 {
+            mul_ops.resize(
+                self.max_ec_ops.ec_mul,
+                EcMulOp {
+                    is_valid: true,
+                    ..Default::default()
+                },
+            );
+        }
+        if self.max_ec_ops.ec_pairing > 0 {
+            pairing_ops.resize(
+                self.max_ec_ops.ec_pairing,
+                EcPairingOp {
+                    input: Vec::new(),
+                    output: true,
+                    is_valid: true,
+                },
+            );
+        }
+        Ok((add_ops, mul_ops, pairing_ops))
+    }
+
+    fn assign_add(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        op: &EcAddOp,
+    ) -> Result<AssignedEccOp<F>, Error> {
+        let (main_gate, base_chip) = (chips.main_gate, chips.base_chip);
+
+        let values = [op.p.0, op.p.1, op.q.0, op.q.1, op.r.0, op.r.1].map(base_from_word);
+        let integers = values
+            .iter()
+            .map(|value| assign_base(ctx, chips, *value))
+            .collect::<Result<Vec<_>, _>>()?;
+        let words = integers
+            .iter()
+            .map(|integer| assign_word(ctx, chips, integer))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (x_p, y_p, x_q, y_q, x_r, y_r) = (
+            &integers[0],
+            &integers[1],
+            &integers[2],
+            &integers[3],
+            &integers[4],
+            &integers[5],
+        );
+        let [x_p_value, y_p_value, x_q_value, y_q_value, _, _] = values;
+
+        let is_identity_p = is_identity(ctx, main_gate, &words[0], &words[1])?;
+        let is_identity_q = is_identity(ctx, main_gate, &words[2], &words[3])?;
+        let is_on_curve_p = assign_is_on_curve(ctx, chips, (x_p, y_p), (x_p_value, y_p_value))?;
+        let is_on_curve_q = assign_is_on_curve(ctx, chips, (x_q, y_q), (x_q_value, y_q_value))?;
+        let is_valid_p = main_gate.or(ctx, &is_on_curve_p, &is_identity_p)?;
+        let is_valid_q = main_gate.or(ctx, &is_on_curve_q, &is_identity_q)?;
+        let is_valid = main_gate.and(ctx, &is_valid_p, &is_valid_q)?;
+
+        // P = Q or P = -Q when the x-coordinates are equal.
+        let dx = base_chip.sub(ctx, x_q, x_p)?;
+        let dy = base_chip.sub(ctx, y_q, y_p)?;
+        let is_x_equal = assign_is_zero(ctx, chips, &dx, x_p_value == x_q_value)?;
+        let is_y_equal = assign_is_zero(ctx, chips, &dy, y_p_value == y_q_value)?;
+        let is_not_identity = {
+            let is_not_identity_p = main_gate.not(ctx, &is_identity_p)?;
+            let is_not_identity_q = main_gate.not(ctx, &is_identity_q)?;
+            main_gate.and(ctx, &is_not_identity_p, &is_not_identity_q)?
+        };
+        let is_opposite = {
+            let is_y_not_equal = main_gate.not(ctx, &is_y_equal)?;
+            let is_x_equal = main_gate.and(ctx, &is_not_identity, &is_x_equal)?;
+            main_gate.and(ctx, &is_x_equal, &is_y_not_equal)?
+        };
+        let uses_lambda = {
+            let is_not_opposite = main_gate.not(ctx, &is_opposite)?;
+            let is_valid_not_identity = main_gate.and(ctx, &is_valid, &is_not_identity)?;
+            main_gate.and(ctx, &is_valid_not_identity, &is_not_opposite)?
+        };
+
+        // lambda * (x_q - x_p) = y_q - y_p, or lambda * 2 * y_p = 3 * x_p^2 for a
+        // doubling.
+        let lambda_value =
+            if !(is_on_curve(x_p_value, y_p_value) && is_on_curve(x_q_value, y_q_value)) {
+                Fq::ZERO
+            } else if x_p_value != x_q_value {
+                (y_q_value - y_p_value) * (x_q_value - x_p_value).invert().unwrap()
+            } else if y_p_value == y_q_value {
+                x_p_value.square() * Fq::from(3) * (y_p_value.double()).invert().unwrap()
+            } else {
+                Fq::ZERO
+            };
+        let lambda = assign_base(ctx, chips, lambda_value)?;
+        let zero = base_chip.assign_constant(ctx, Fq::ZERO)?;
+        let x_p_square = base_chip.square(ctx, x_p)?;
+        let three_x_p_square = {
+            let two_x_p_square = base_chip.add(ctx, &x_p_square, &x_p_square)?;
+            base_chip.add(ctx, &two_x_p_square, &x_p_square)?
+        };
+        let two_y_p = base_chip.add(ctx, y_p, y_p)?;
+        let numerator = base_chip.cond_select(ctx, &three_x_p_square, &dy, &is_x_equal)?;
+        let denominator = base_chip.cond_select(ctx, &two_y_p, &dx, &is_x_equal)?;
+        let lambda_denominator = base_chip.mul(ctx, &lambda, &denominator)?;
+        let diff = base_chip.sub(ctx, &lambda_denominator, &numerator)?;
+        let diff = base_chip.cond_select(ctx, &diff, &zero, &uses_lambda)?;
+        base_chip.assert_zero(ctx, &diff)?;
+
+        // x = lambda^2 - x_p - x_q, y = lambda * (x_p - x) - y_p
+        let x_sum = {
+            let lambda_square = base_chip.square(ctx, &lambda)?;
+            let diff = base_chip.sub(ctx, &lambda_square, x_p)?;
+            base_chip.sub(ctx, &diff, x_q)?
+        };
+        let y_sum = {
+            let diff = base_chip.sub(ctx, x_p, &x_sum)?;
+            let product = base_chip.mul(ctx, &lambda, &diff)?;
+            base_chip.sub(ctx, &product, y_p)?
+        };
+
+        for (sum, p, q, r) in [(&x_sum, x_p, x_q, x_r), (&y_sum, y_p, y_q, y_r)] {
+            let expected = base_chip.cond_select(ctx, &zero, sum, &is_opposite)?;
+            let expected = base_chip.cond_select(ctx, p, &expected, &is_identity_q)?;
+            let expected = base_chip.cond_select(ctx, q, &expected, &is_identity_p)?;
+            let expected = base_chip.cond_select(ctx, &expected, &zero, &is_valid)?;
+            base_chip.assert_equal(ctx, &expected, r)?;
+            base_chip.assert_in_field(ctx, r)?;
+        }
+
+        Ok(AssignedEccOp {
+            op_type: main_gate.assign_constant(ctx, F::from(EccOpType::Add as u64))?,
+            args: [
+                words[0].clone(),
+                words[1].clone(),
+                words[2].clone(),
+                words[3].clone(),
+            ],
+            outputs: [words[4].clone(), words[5].clone()],
+            is_valid,
+            zero: main_gate.assign_constant(ctx, F::ZERO)?,
+        })
+    }
+
+    fn assign_mul(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        op: &EcMulOp,
+    ) -> Result<AssignedEccOp<F>, Error> {
+        let (main_gate, base_chip, scalar_chip) =
+            (chips.main_gate, chips.base_chip, chips.scalar_chip);
+
+        let values = [op.p.0, op.p.1, op.r.0, op.r.1].map(base_from_word);
+        let integers = values
+            .iter()
+            .map(|value| assign_base(ctx, chips, *value))
+            .collect::<Result<Vec<_>, _>>()?;
+        let words = integers
+            .iter()
+            .map(|integer| assign_word(ctx, chips, integer))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (x_p, y_p, x_r, y_r) = (&integers[0], &integers[1], &integers[2], &integers[3]);
+        let s = scalar_chip.assign_integer(
+            ctx,
+            chips
+                .ecc_chip
+                .new_unassigned_scalar(Value::known(scalar_from_word(op.s))),
+            Range::Remainder,
+        )?;
+        let s_word = assign_word(ctx, chips, &s)?;
+
+        let is_identity_p = is_identity(ctx, main_gate, &words[0], &words[1])?;
+        let is_on_curve_p = assign_is_on_curve(ctx, chips, (x_p, y_p), (values[0], values[1]))?;
+        let is_valid = main_gate.or(ctx, &is_on_curve_p, &is_identity_p)?;
+        let is_s_zero = {
+            let is_zero_lo = main_gate.is_zero(ctx, &s_word.lo())?;
+            let is_zero_hi = main_gate.is_zero(ctx, &s_word.hi())?;
+            main_gate.and(ctx, &is_zero_lo, &is_zero_hi)?
+        };
+        // The identity isn't on the curve, so that the product is the identity
+        // otherwise.
+        let uses_mul = {
+            let is_s_not_zero = main_gate.not(ctx, &is_s_zero)?;
+            main_gate.and(ctx, &is_on_curve_p, &is_s_not_zero)?
+        };
+
+        // Multiply the generator (1, 2) by one when the product isn't used.
+        let one = base_chip.assign_constant(ctx, Fq::ONE)?;
+        let two = base_chip.assign_constant(ctx, Fq::from(2))?;
+        let x = base_chip.cond_select(ctx, x_p, &one, &uses_mul)?;
+        let y = base_chip.cond_select(ctx, y_p, &two, &uses_mul)?;
+        let scalar_one = scalar_chip.assign_constant(ctx, Fr::ONE)?;
+        let scalar = scalar_chip.cond_select(ctx, &s, &scalar_one, &uses_mul)?;
+        let product =
+            chips
+                .ecc_chip
+                .mul(ctx, &AssignedPoint::new(x, y), &scalar, self.window_size)?;
+
+        let zero = base_chip.assign_constant(ctx, Fq::ZERO)?;
+        for (coordinate, r) in [(product.x(), x_r), (product.y(), y_r)] {
+            let expected = base_chip.cond_select(ctx, coordinate, &zero, &uses_mul)?;
+            base_chip.assert_equal(ctx, &expected, r)?;
+            base_chip.assert_in_field(ctx, r)?;
+        }
+
+        let zero = main_gate.assign_constant(ctx, F::ZERO)?;
+        Ok(AssignedEccOp {
+            op_type: main_gate.assign_constant(ctx, F::from(EccOpType::Mul as u64))?,
+            args: [
+                words[0].clone(),
+                words[1].clone(),
+                s_word,
+                Word::new([zero.clone(), zero.clone()]),
+            ],
+            outputs: [words[2].clone(), words[3].clone()],
+            is_valid,
+            zero,
+        })
+    }
+
+    /// Assign the pairings to the EccTable, followed by the verified operations.
+    fn assign_ecc_table(
+        &self,
+        config: &EccCircuitConfig<F>,
+        layouter: &mut impl Layouter<F>,
+        pairing_ops: &[EcPairingOp],
+        assigned_ops: &[AssignedEccOp<F>],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        let ecc_table = &config.ecc_table;
+        let columns = <EccTable as LookupTable<F>>::advice_columns(ecc_table);
+        layouter.assign_region(
+            || "ecc table",
+            |mut region| {
+                for (idx, op) in pairing_ops.iter().enumerate() {
+                    self.assign_pairing(
+                        config,
+                        &mut region,
+                        idx * ROWS_PER_PAIRING,
+                        op,
+                        challenges,
+                    )?;
+                }
+                for (idx, assigned_op) in assigned_ops.iter().enumerate() {
+                    let offset = pairing_ops.len() * ROWS_PER_PAIRING + idx;
+                    region.assign_fixed(
+                        || format!("ecc table q_enable {offset}"),
+                        ecc_table.q_enable,
+                        offset,
+                        || Value::known(F::ONE),
+                    )?;
+                    for (column, assigned) in columns.iter().zip(assigned_op.table_cells()) {
+                        assigned.copy_advice(
+                            || format!("ecc table row {offset}"),
+                            &mut region,
+                            *column,
+                            offset,
+                        )?;
+                    }
+                }
+                ecc_table.annotate_columns_in_region(&mut region);
+                config.annotate_circuit(&mut region);
+                Ok(())
+            },
+        )
+    }
+
+    /// Assign the rows of a pairing, accumulating its input bytes, whose last
+    /// row is the table row.
+    fn assign_pairing(
+        &self,
+        config: &EccCircuitConfig<F>,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        op: &EcPairingOp,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        let ecc_table = &config.ecc_table;
+        let mut input_rlc = Value::known(F::ZERO);
+        for row_idx in 0..ROWS_PER_PAIRING {
+            let offset = offset + row_idx;
+            let is_last = row_idx == ROWS_PER_PAIRING - 1;
+            for (name, column, value) in [
+                ("q_pairing", config.q_pairing, true),
+                ("q_pairing_first", config.q_pairing_first, row_idx == 0),
+                ("q_pairing_last", config.q_pairing_last, is_last),
+                ("q_enable", ecc_table.q_enable, is_last),
+            ] {
+                region.assign_fixed(
+                    || format!("assign {} {}", name, offset),
+                    column,
+                    offset,
+                    || Value::known(F::from(value as u64)),
+                )?;
+            }
+            if let Some(byte) = op.input.get(row_idx) {
+                input_rlc =
+                    input_rlc * challenges.keccak_input() + Value::known(F::from(*byte as u64));
+            }
+            let input_len = op.input.len().min(row_idx + 1);
+            region.assign_advice(
+                || format!("assign input_rlc {}", offset),
+                ecc_table.input_rlc,
+                offset,
+                || input_rlc,
+            )?;
+            region.assign_advice(
+                || format!("assign input_len {}", offset),
+                ecc_table.input_len,
+                offset,
+                || Value::known(F::from(input_len as u64)),
+            )?;
+        }
+
+        // The other columns of the table row are constrained against the public
+        // inputs.
+        let offset = offset + ROWS_PER_PAIRING - 1;
+        for (column, value) in <EccTable as LookupTable<F>>::advice_columns(ecc_table)
+            .into_iter()
+            .zip(EccTable::pairing_assignment(op, challenges))
+            .filter(|(column, _)| ![ecc_table.input_rlc, ecc_table.input_len].contains(column))
+        {
+            region.assign_advice(
+                || format!("ecc table row {offset}"),
+                column,
+                offset,
+                || value,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl<F: Field> SubCircuit<F> for EccCircuit<F> {
+    type Config = EccCircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // The main gate queries its columns at 2 distinct rotations, so returns
+        // 5 unusable rows.
+        5
+    }
+
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        Self::new(
+            block.circuits_params.max_ec_ops,
+            block.precompile_events.get_ec_add_events(),
+            block.precompile_events.get_ec_mul_events(),
+            block.precompile_events.get_ec_pairing_events(),
+        )
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        let events = &block.precompile_events;
+        let max_ec_ops = block.circuits_params.max_ec_ops;
+        (
+            Self::min_num_rows(
+                events.get_ec_add_events().len(),
+                events.get_ec_mul_events().len(),
+                events.get_ec_pairing_events().len(),
+            ),
+            Self::min_num_rows(max_ec_ops.ec_add, max_ec_ops.ec_mul, max_ec_ops.ec_pairing),
+        )
+    }
+
+    /// Returns the input bytes, the results and the validity of the pairings,
+    /// to be checked natively by the verifier.
+    fn instance(&self) -> Vec<Vec<F>> {
+        // The operations exceeding the capacity are rejected by `synthesize_sub`.
+        let pairing_ops = self
+            .padded_ops()
+            .map(|(_, _, pairing_ops)| pairing_ops)
+            .unwrap_or_default();
+        let mut input = Vec::new();
+        let mut is_input = Vec::new();
+        let mut output = Vec::new();
+        let mut is_valid = Vec::new();
+        for op in pairing_ops.iter() {
+            for row_idx in 0..ROWS_PER_PAIRING {
+                let byte = op.input.get(row_idx);
+                let is_last = row_idx == ROWS_PER_PAIRING - 1;
+                input.push(F::from(byte.copied().unwrap_or_default() as u64));
+                is_input.push(F::from(byte.is_some() as u64));
+                output.push(F::from((is_last && op.output) as u64));
+                is_valid.push(F::from((is_last && op.is_valid) as u64));
+            }
+        }
+        // The maingate expects an instance column, but we don't use it, so we return an
+        // "empty" instance column
+        vec![vec![], input, is_input, output, is_valid]
+    }
+
+    /// Make the assignments to the EccCircuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.load_aux_tables(layouter)?;
+
+        let (add_ops, mul_ops, pairing_ops) = self.padded_ops()?;
+        let main_gate = MainGate::new(config.main_gate_config.clone());
+        let range_chip = RangeChip::new(config.range_config.clone());
+        let mut ecc_chip = EccChip::<F>::new(config.ecc_chip_config());
+
+        layouter.assign_region(
+            || "ecc chip aux",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+                ecc_chip.assign_aux_generator(&mut ctx, Value::known(self.aux_generator))?;
+                ecc_chip.assign_aux(&mut ctx, self.window_size, 1)?;
+                log::debug!("ecc chip aux: {} rows", ctx.offset());
+                Ok(())
+            },
+        )?;
+
+        let chips = ChipsRef {
+            main_gate: &main_gate,
+            range_chip: &range_chip,
+            ecc_chip: &ecc_chip,
+            base_chip: ecc_chip.base_field_chip(),
+            scalar_chip: ecc_chip.scalar_field_chip(),
+        };
+
+        let assigned_ops = layouter.assign_region(
+            || "ecc chip operations",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+                let mut assigned_ops = Vec::new();
+                for op in add_ops.iter() {
+                    assigned_ops.push(self.assign_add(&mut ctx, &chips, op)?);
+                }
+                for op in mul_ops.iter() {
+                    assigned_ops.push(self.assign_mul(&mut ctx, &chips, op)?);
+                }
+                log::debug!("ecc chip operations: {} rows", ctx.offset());
+                Ok(assigned_ops)
+            },
+        )?;
+
+        self.assign_ecc_table(config, layouter, &pairing_ops, &assigned_ops, challenges)
+    }
+}
@@ -0,0 +1,249 @@
+use super::*;
+use crate::util::unusable_rows;
+use eth_types::Word as U256Word;
+use halo2_proofs::{dev::MockProver, halo2curves::bn256::G1};
+
+#[test]
+fn ecc_circuit_unusable_rows() {
+    assert_eq!(
+        EccCircuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, EccCircuit::<Fr>>(()),
+    )
+}
+
+fn word(value: Fq) -> U256Word {
+    U256Word::from_little_endian(&value.to_bytes())
+}
+
+fn point(point: G1) -> (U256Word, U256Word) {
+    let point = point.to_affine();
+    if bool::from(point.is_identity()) {
+        (U256Word::zero(), U256Word::zero())
+    } else {
+        (word(point.x), word(point.y))
+    }
+}
+
+fn add_op(p: G1, q: G1) -> EcAddOp {
+    EcAddOp {
+        p: point(p),
+        q: point(q),
+        r: point(p + q),
+        is_valid: true,
+    }
+}
+
+fn mul_op(p: G1, s: u64) -> EcMulOp {
+    EcMulOp {
+        p: point(p),
+        s: s.into(),
+        r: point(p * Fr::from(s)),
+        is_valid: true,
+    }
+}
+
+fn invalid_point() -> (U256Word, U256Word) {
+    (1.into(), 3.into())
+}
+
+fn verify(
+    max_ec_ops: PrecompileEcParams,
+    add_ops: Vec<EcAddOp>,
+    mul_ops: Vec<EcMulOp>,
+    success: bool,
+) {
+    // The range chip table takes 2^18 rows, and the multiplications 2^16 rows
+    // each.
+    let k = 20;
+    let circuit = EccCircuit::<Fr>::new(max_ec_ops, add_ops, mul_ops, vec![]);
+    let prover = MockProver::<Fr>::run(k, &circuit, circuit.instance()).unwrap();
+    assert_eq!(prover.verify().is_ok(), success);
+}
+
+#[test]
+fn ecc_circuit_add() {
+    let g = G1::generator();
+    verify(
+        PrecompileEcParams::default(),
+        vec![
+            add_op(g, g.double()),
+            add_op(g, g),
+            add_op(g, G1::identity()),
+            add_op(G1::identity(), g),
+            add_op(G1::identity(), G1::identity()),
+            add_op(g, -g),
+        ],
+        vec![],
+        true,
+    );
+}
+
+#[test]
+fn ecc_circuit_add_invalid_point() {
+    let g = G1::generator();
+    let op = EcAddOp {
+        p: point(g),
+        q: invalid_point(),
+        r: (U256Word::zero(), U256Word::zero()),
+        is_valid: false,
+    };
+    verify(PrecompileEcParams::default(), vec![op], vec![], true);
+}
+
+#[test]
+fn ecc_circuit_mul() {
+    let g = G1::generator();
+    verify(
+        PrecompileEcParams::default(),
+        vec![],
+        vec![mul_op(g, 2), mul_op(g, 0), mul_op(G1::identity(), 3)],
+        true,
+    );
+}
+
+#[test]
+fn ecc_circuit_mul_invalid_point() {
+    let op = EcMulOp {
+        p: invalid_point(),
+        s: 2.into(),
+        r: (U256Word::zero(), U256Word::zero()),
+        is_valid: false,
+    };
+    verify(PrecompileEcParams::default(), vec![], vec![op], true);
+}
+
+#[test]
+fn ecc_circuit_padding() {
+    let g = G1::generator();
+    verify(
+        PrecompileEcParams {
+            ec_add: 2,
+            ec_mul: 1,
+            ec_pairing: 0,
+        },
+        vec![add_op(g, g)],
+        vec![],
+        true,
+    );
+}
+
+#[test]
+fn ecc_circuit_wrong_result() {
+    let g = G1::generator();
+    let mut add = add_op(g, g);
+    add.r = point(g);
+    verify(PrecompileEcParams::default(), vec![add], vec![], false);
+
+    let mut mul = mul_op(g, 2);
+    mul.r = point(g);
+    verify(PrecompileEcParams::default(), vec![], vec![mul], false);
+}
+
+#[test]
+fn ecc_circuit_over_capacity() {
+    let g = G1::generator();
+    let circuit = EccCircuit::<Fr>::new(
+        PrecompileEcParams {
+            ec_add: 1,
+            ..Default::default()
+        },
+        vec![add_op(g, g); 2],
+        vec![],
+        vec![],
+    );
+    assert!(MockProver::<Fr>::run(20, &circuit, circuit.instance()).is_err());
+}
+
+fn pairing_op(n_pairs: usize, output: bool, is_valid: bool) -> EcPairingOp {
+    EcPairingOp {
+        input: vec![0; n_pairs * N_BYTES_EC_PAIR],
+        output,
+        is_valid,
+    }
+}
+
+fn verify_pairing(circuit: EccCircuit<Fr>, instance: Vec<Vec<Fr>>, success: bool) {
+    let k = 20;
+    let prover = MockProver::<Fr>::run(k, &circuit, instance).unwrap();
+    assert_eq!(prover.verify().is_ok(), success);
+}
+
+#[test]
+fn ecc_circuit_pairing() {
+    let g = G1::generator();
+    // The pairings of the identity are the identity.
+    let circuit = EccCircuit::<Fr>::new(
+        PrecompileEcParams::default(),
+        vec![add_op(g, g)],
+        vec![],
+        vec![
+            pairing_op(0, true, true),
+            pairing_op(1, true, true),
+            pairing_op(N_PAIRING_PER_OP, true, true),
+        ],
+    );
+    let instance = circuit.instance();
+    verify_pairing(circuit, instance, true);
+}
+
+#[test]
+fn ecc_circuit_pairing_padding() {
+    let circuit = EccCircuit::<Fr>::new(
+        PrecompileEcParams {
+            ec_add: 0,
+            ec_mul: 0,
+            ec_pairing: 2,
+        },
+        vec![],
+        vec![],
+        vec![pairing_op(1, true, true)],
+    );
+    let instance = circuit.instance();
+    assert_eq!(instance[1].len(), 2 * ROWS_PER_PAIRING);
+    verify_pairing(circuit, instance, true);
+}
+
+#[test]
+fn ecc_circuit_pairing_wrong_result() {
+    let circuit = EccCircuit::<Fr>::new(
+        PrecompileEcParams::default(),
+        vec![],
+        vec![],
+        vec![pairing_op(1, true, true)],
+    );
+    for op in [pairing_op(1, false, true), pairing_op(1, false, false)] {
+        let instance =
+            EccCircuit::<Fr>::new(PrecompileEcParams::default(), vec![], vec![], vec![op])
+                .instance();
+        verify_pairing(circuit.clone(), instance, false);
+    }
+}
+
+#[test]
+fn ecc_circuit_pairing_wrong_input() {
+    let circuit = EccCircuit::<Fr>::new(
+        PrecompileEcParams::default(),
+        vec![],
+        vec![],
+        vec![pairing_op(1, true, true)],
+    );
+    let mut instance = circuit.instance();
+    instance[1][0] += Fr::from(1);
+    verify_pairing(circuit.clone(), instance, false);
+
+    // The input is one byte longer.
+    let mut instance = circuit.instance();
+    instance[2][N_BYTES_EC_PAIR] = Fr::from(1);
+    verify_pairing(circuit, instance, false);
+}
+
+#[test]
+fn ecc_circuit_pairing_over_capacity() {
+    let circuit = EccCircuit::<Fr>::new(
+        PrecompileEcParams::default(),
+        vec![],
+        vec![],
+        vec![pairing_op(N_PAIRING_PER_OP + 1, true, true)],
+    );
+    assert!(MockProver::<Fr>::run(20, &circuit, circuit.instance()).is_err());
+}
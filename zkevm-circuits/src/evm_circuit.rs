@@ -21,8 +21,9 @@ pub use crate::witness;
 use crate::{
     evm_circuit::param::{MAX_STEP_HEIGHT, STEP_STATE_HEIGHT},
     table::{
-        BlockTable, BytecodeTable, CopyTable, ExpTable, KeccakTable, LookupTable, RwTable, TxTable,
-        UXTable,
+        Blake2fTable, BlockTable, BytecodeTable, CopyTable, EccTable, ExpTable, KeccakTable,
+        KzgTable, LookupTable, ModExpTable, Ripemd160Table, RwTable, Sha256Table, SigTable,
+        TxTable, UXTable, WdTable,
     },
     util::{Challenges, SubCircuit, SubCircuitConfig},
 };
@@ -50,6 +51,14 @@ pub struct EvmCircuitConfig<F> {
     copy_table: CopyTable,
     keccak_table: KeccakTable,
     exp_table: ExpTable,
+    sig_table: SigTable,
+    sha256_table: Sha256Table,
+    modexp_table: ModExpTable,
+    ecc_table: EccTable,
+    ripemd160_table: Ripemd160Table,
+    blake2f_table: Blake2fTable,
+    kzg_table: KzgTable,
+    wd_table: WdTable,
 }
 
 /// Circuit configuration arguments
@@ -70,6 +79,22 @@ pub struct EvmCircuitConfigArgs<F: Field> {
     pub keccak_table: KeccakTable,
     /// ExpTable
     pub exp_table: ExpTable,
+    /// SigTable
+    pub sig_table: SigTable,
+    /// Sha256Table
+    pub sha256_table: Sha256Table,
+    /// ModExpTable
+    pub modexp_table: ModExpTable,
+    /// EccTable
+    pub ecc_table: EccTable,
+    /// Ripemd160Table
+    pub ripemd160_table: Ripemd160Table,
+    /// Blake2fTable
+    pub blake2f_table: Blake2fTable,
+    /// KzgTable
+    pub kzg_table: KzgTable,
+    /// WdTable
+    pub wd_table: WdTable,
     /// U8Table
     pub u8_table: UXTable<8>,
     /// U16Table
@@ -91,6 +116,14 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
             copy_table,
             keccak_table,
             exp_table,
+            sig_table,
+            sha256_table,
+            modexp_table,
+            ecc_table,
+            ripemd160_table,
+            blake2f_table,
+            kzg_table,
+            wd_table,
             u8_table,
             u16_table,
         }: Self::ConfigArgs,
@@ -109,6 +142,14 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
             &copy_table,
             &keccak_table,
             &exp_table,
+            &sig_table,
+            &sha256_table,
+            &modexp_table,
+            &ecc_table,
+            &ripemd160_table,
+            &blake2f_table,
+            &kzg_table,
+            &wd_table,
         ));
 
         u8_table.annotate_columns(meta);
@@ -123,6 +164,14 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
         copy_table.annotate_columns(meta);
         keccak_table.annotate_columns(meta);
         exp_table.annotate_columns(meta);
+        sig_table.annotate_columns(meta);
+        sha256_table.annotate_columns(meta);
+        modexp_table.annotate_columns(meta);
+        ecc_table.annotate_columns(meta);
+        ripemd160_table.annotate_columns(meta);
+        blake2f_table.annotate_columns(meta);
+        kzg_table.annotate_columns(meta);
+        wd_table.annotate_columns(meta);
         u8_table.annotate_columns(meta);
         u16_table.annotate_columns(meta);
 
@@ -138,6 +187,14 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
             copy_table,
             keccak_table,
             exp_table,
+            sig_table,
+            sha256_table,
+            modexp_table,
+            ecc_table,
+            ripemd160_table,
+            blake2f_table,
+            kzg_table,
+            wd_table,
         }
     }
 }
@@ -222,6 +279,9 @@ impl<F: Field> EvmCircuit<F> {
                 num_rows += step.execution_state().get_step_height();
             }
         }
+        for step in block.withdrawal_steps.iter().chain(&block.end_blocks) {
+            num_rows += step.execution_state().get_step_height();
+        }
 
         // It must have one row for EndBlock and at least one unused one
         num_rows + 2
@@ -371,6 +431,14 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
         let copy_table = CopyTable::construct(meta, q_copy_table);
         let keccak_table = KeccakTable::construct(meta);
         let exp_table = ExpTable::construct(meta);
+        let sig_table = SigTable::construct(meta);
+        let sha256_table = Sha256Table::construct(meta);
+        let modexp_table = ModExpTable::construct(meta);
+        let ecc_table = EccTable::construct(meta);
+        let ripemd160_table = Ripemd160Table::construct(meta);
+        let blake2f_table = Blake2fTable::construct(meta);
+        let kzg_table = KzgTable::construct(meta);
+        let wd_table = WdTable::construct(meta);
         let u8_table = UXTable::construct(meta);
         let u16_table = UXTable::construct(meta);
         let challenges = Challenges::construct(meta);
@@ -388,6 +456,14 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
                     copy_table,
                     keccak_table,
                     exp_table,
+                    sig_table,
+                    sha256_table,
+                    modexp_table,
+                    ecc_table,
+                    ripemd160_table,
+                    blake2f_table,
+                    kzg_table,
+                    wd_table,
                     u8_table,
                     u16_table,
                 },
@@ -421,12 +497,40 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
         config
             .bytecode_table
             .load(&mut layouter, block.bytecodes.clone())?;
-        config.block_table.load(&mut layouter, &block.context)?;
+        config.block_table.load(&mut layouter, &block.contexts)?;
         config.copy_table.load(&mut layouter, block, &challenges)?;
         config
             .keccak_table
             .dev_load(&mut layouter, &block.sha3_inputs, &challenges)?;
         config.exp_table.load(&mut layouter, block)?;
+        config.sig_table.dev_load(&mut layouter, block)?;
+        config.sha256_table.dev_load(
+            &mut layouter,
+            &block.precompile_events.get_sha256_events(),
+            &challenges,
+        )?;
+        config.modexp_table.dev_load(&mut layouter, block)?;
+        config
+            .ecc_table
+            .dev_load(&mut layouter, block, &challenges)?;
+        config.ripemd160_table.dev_load(
+            &mut layouter,
+            &block.precompile_events.get_ripemd160_events(),
+            &challenges,
+        )?;
+        config.blake2f_table.dev_load(
+            &mut layouter,
+            &block.precompile_events.get_blake2f_events(),
+            &challenges,
+        )?;
+        config
+            .kzg_table
+            .dev_load(&mut layouter, block, &challenges)?;
+        config.wd_table.load(
+            &mut layouter,
+            &block.withdrawals(),
+            block.circuits_params.max_withdrawals,
+        )?;
 
         config.u8_table.load(&mut layouter)?;
         config.u16_table.load(&mut layouter)?;
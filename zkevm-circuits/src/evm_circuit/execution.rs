@@ -37,6 +37,7 @@ use halo2_proofs::{
     },
     poly::Rotation,
 };
+use itertools::Itertools;
 use std::{
     collections::{BTreeSet, HashMap},
     iter,
@@ -49,6 +50,8 @@ mod address;
 mod balance;
 mod begin_tx;
 mod bitwise;
+mod blobbasefee;
+mod blobhash;
 mod block_ctx;
 mod blockhash;
 mod byte;
@@ -63,11 +66,12 @@ mod codecopy;
 mod codesize;
 mod comparator;
 mod create;
-mod dummy;
 mod dup;
 mod end_block;
 mod end_tx;
 mod error_code_store;
+mod error_depth;
+mod error_insufficient_balance;
 mod error_invalid_creation_code;
 mod error_invalid_jump;
 mod error_invalid_opcode;
@@ -77,9 +81,11 @@ mod error_oog_constant;
 mod error_oog_create;
 mod error_oog_dynamic_memory;
 mod error_oog_exp;
+mod error_oog_extcodecopy;
 mod error_oog_log;
 mod error_oog_memory_copy;
 mod error_oog_precompile;
+mod error_oog_self_destruct;
 mod error_oog_sha3;
 mod error_oog_sload_sstore;
 mod error_oog_static_memory;
@@ -99,6 +105,7 @@ mod jump;
 mod jumpdest;
 mod jumpi;
 mod logs;
+mod mcopy;
 mod memory;
 mod msize;
 mod mul_div_mod;
@@ -116,6 +123,7 @@ mod returndatasize;
 mod sar;
 mod sdiv_smod;
 mod selfbalance;
+mod selfdestruct;
 mod sha3;
 mod shl_shr;
 mod signed_comparator;
@@ -124,6 +132,9 @@ mod sload;
 mod sstore;
 mod stop;
 mod swap;
+mod tload;
+mod tstore;
+mod withdrawal;
 
 use self::{block_ctx::BlockCtxGadget, sha3::Sha3Gadget};
 use add_sub::AddSubGadget;
@@ -132,6 +143,8 @@ use address::AddressGadget;
 use balance::BalanceGadget;
 use begin_tx::BeginTxGadget;
 use bitwise::BitwiseGadget;
+use blobbasefee::BlobBaseFeeGadget;
+use blobhash::BlobHashGadget;
 use blockhash::BlockHashGadget;
 use byte::ByteGadget;
 use calldatacopy::CallDataCopyGadget;
@@ -145,11 +158,12 @@ use codecopy::CodeCopyGadget;
 use codesize::CodesizeGadget;
 use comparator::ComparatorGadget;
 use create::CreateGadget;
-use dummy::DummyGadget;
 use dup::DupGadget;
 use end_block::EndBlockGadget;
 use end_tx::EndTxGadget;
 use error_code_store::ErrorCodeStoreGadget;
+use error_depth::ErrorDepthGadget;
+use error_insufficient_balance::ErrorInsufficientBalanceGadget;
 use error_invalid_creation_code::ErrorInvalidCreationCodeGadget;
 use error_invalid_jump::ErrorInvalidJumpGadget;
 use error_invalid_opcode::ErrorInvalidOpcodeGadget;
@@ -159,8 +173,10 @@ use error_oog_constant::ErrorOOGConstantGadget;
 use error_oog_create::ErrorOOGCreateGadget;
 use error_oog_dynamic_memory::ErrorOOGDynamicMemoryGadget;
 use error_oog_exp::ErrorOOGExpGadget;
+use error_oog_extcodecopy::ErrorOOGExtCodeCopyGadget;
 use error_oog_log::ErrorOOGLogGadget;
 use error_oog_memory_copy::ErrorOOGMemoryCopyGadget;
+use error_oog_self_destruct::ErrorOOGSelfDestructGadget;
 use error_oog_sha3::ErrorOOGSha3Gadget;
 use error_oog_sload_sstore::ErrorOOGSloadSstoreGadget;
 use error_oog_static_memory::ErrorOOGStaticMemoryGadget;
@@ -182,6 +198,7 @@ use jumpi::JumpiGadget;
 use logs::LogGadget;
 
 use crate::evm_circuit::execution::error_oog_precompile::ErrorOOGPrecompileGadget;
+use mcopy::McopyGadget;
 use memory::MemoryGadget;
 use msize::MsizeGadget;
 use mul_div_mod::MulDivModGadget;
@@ -190,7 +207,10 @@ use opcode_not::NotGadget;
 use origin::OriginGadget;
 use pc::PcGadget;
 use pop::PopGadget;
-use precompiles::IdentityGadget;
+use precompiles::{
+    Blake2fGadget, EcAddGadget, EcMulGadget, EcPairingGadget, EcrecoverGadget, IdentityGadget,
+    ModExpGadget, PointEvaluationGadget, Ripemd160Gadget, Sha256Gadget,
+};
 use push::PushGadget;
 use return_revert::ReturnRevertGadget;
 use returndatacopy::ReturnDataCopyGadget;
@@ -198,6 +218,7 @@ use returndatasize::ReturnDataSizeGadget;
 use sar::SarGadget;
 use sdiv_smod::SignedDivModGadget;
 use selfbalance::SelfbalanceGadget;
+use selfdestruct::SelfDestructGadget;
 use shl_shr::ShlShrGadget;
 use signed_comparator::SignedComparatorGadget;
 use signextend::SignextendGadget;
@@ -205,6 +226,9 @@ use sload::SloadGadget;
 use sstore::SstoreGadget;
 use stop::StopGadget;
 use swap::SwapGadget;
+use tload::TloadGadget;
+use tstore::TstoreGadget;
+use withdrawal::WithdrawalGadget;
 
 pub(crate) trait ExecutionGadget<F: Field> {
     const NAME: &'static str;
@@ -257,6 +281,8 @@ pub struct ExecutionConfig<F> {
     address_gadget: Box<AddressGadget<F>>,
     balance_gadget: Box<BalanceGadget<F>>,
     bitwise_gadget: Box<BitwiseGadget<F>>,
+    blobbasefee_gadget: Box<BlobBaseFeeGadget<F>>,
+    blobhash_gadget: Box<BlobHashGadget<F>>,
     byte_gadget: Box<ByteGadget<F>>,
     call_op_gadget: Box<CallOpGadget<F>>,
     call_value_gadget: Box<CallValueGadget<F>>,
@@ -280,6 +306,7 @@ pub struct ExecutionConfig<F> {
     jumpdest_gadget: Box<JumpdestGadget<F>>,
     jumpi_gadget: Box<JumpiGadget<F>>,
     log_gadget: Box<LogGadget<F>>,
+    mcopy_gadget: Box<McopyGadget<F>>,
     memory_gadget: Box<MemoryGadget<F>>,
     msize_gadget: Box<MsizeGadget<F>>,
     mul_div_mod_gadget: Box<MulDivModGadget<F>>,
@@ -299,13 +326,15 @@ pub struct ExecutionConfig<F> {
     returndatacopy_gadget: Box<ReturnDataCopyGadget<F>>,
     create_gadget: Box<CreateGadget<F, false, { ExecutionState::CREATE }>>,
     create2_gadget: Box<CreateGadget<F, true, { ExecutionState::CREATE2 }>>,
-    selfdestruct_gadget: Box<DummyGadget<F, 1, 0, { ExecutionState::SELFDESTRUCT }>>,
+    selfdestruct_gadget: Box<SelfDestructGadget<F>>,
     signed_comparator_gadget: Box<SignedComparatorGadget<F>>,
     signextend_gadget: Box<SignextendGadget<F>>,
     sload_gadget: Box<SloadGadget<F>>,
     sstore_gadget: Box<SstoreGadget<F>>,
     stop_gadget: Box<StopGadget<F>>,
     swap_gadget: Box<SwapGadget<F>>,
+    tload_gadget: Box<TloadGadget<F>>,
+    tstore_gadget: Box<TstoreGadget<F>>,
     blockhash_gadget: Box<BlockHashGadget<F>>,
     block_ctx_gadget: Box<BlockCtxGadget<F>>,
     // error gadgets
@@ -322,23 +351,31 @@ pub struct ExecutionConfig<F> {
     error_oog_log: Box<ErrorOOGLogGadget<F>>,
     error_oog_sha3: Box<ErrorOOGSha3Gadget<F>>,
     error_oog_account_access: Box<ErrorOOGAccountAccessGadget<F>>,
-    error_oog_ext_codecopy: Box<DummyGadget<F, 0, 0, { ExecutionState::ErrorOutOfGasEXTCODECOPY }>>,
+    error_oog_ext_codecopy: Box<ErrorOOGExtCodeCopyGadget<F>>,
     error_oog_create: Box<ErrorOOGCreateGadget<F>>,
-    error_oog_self_destruct:
-        Box<DummyGadget<F, 0, 0, { ExecutionState::ErrorOutOfGasSELFDESTRUCT }>>,
+    error_oog_self_destruct: Box<ErrorOOGSelfDestructGadget<F>>,
     error_oog_code_store: Box<ErrorCodeStoreGadget<F>>,
     error_invalid_jump: Box<ErrorInvalidJumpGadget<F>>,
     error_invalid_opcode: Box<ErrorInvalidOpcodeGadget<F>>,
-    #[allow(dead_code, reason = "under active development")]
-    error_depth: Box<DummyGadget<F, 0, 0, { ExecutionState::ErrorDepth }>>,
-    #[allow(dead_code, reason = "under active development")]
+    error_depth: Box<ErrorDepthGadget<F>>,
+    error_insufficient_balance: Box<ErrorInsufficientBalanceGadget<F>>,
     error_contract_address_collision:
-        Box<DummyGadget<F, 0, 0, { ExecutionState::ErrorContractAddressCollision }>>,
+        Box<CreateGadget<F, true, { ExecutionState::ErrorContractAddressCollision }>>,
     error_invalid_creation_code: Box<ErrorInvalidCreationCodeGadget<F>>,
     error_precompile_failed: Box<ErrorPrecompileFailedGadget<F>>,
     error_return_data_out_of_bound: Box<ErrorReturnDataOutOfBoundGadget<F>>,
+    precompile_ecrecover_gadget: Box<EcrecoverGadget<F>>,
+    precompile_sha256_gadget: Box<Sha256Gadget<F>>,
+    precompile_ripemd160_gadget: Box<Ripemd160Gadget<F>>,
     precompile_identity_gadget: Box<IdentityGadget<F>>,
+    precompile_modexp_gadget: Box<ModExpGadget<F>>,
+    precompile_ec_add_gadget: Box<EcAddGadget<F>>,
+    precompile_ec_mul_gadget: Box<EcMulGadget<F>>,
+    precompile_ec_pairing_gadget: Box<EcPairingGadget<F>>,
+    precompile_blake2f_gadget: Box<Blake2fGadget<F>>,
+    precompile_point_evaluation_gadget: Box<PointEvaluationGadget<F>>,
     invalid_tx: Box<InvalidTxGadget<F>>,
+    withdrawal_gadget: Box<WithdrawalGadget<F>>,
 }
 
 impl<F: Field> ExecutionConfig<F> {
@@ -357,6 +394,14 @@ impl<F: Field> ExecutionConfig<F> {
         copy_table: &dyn LookupTable<F>,
         keccak_table: &dyn LookupTable<F>,
         exp_table: &dyn LookupTable<F>,
+        sig_table: &dyn LookupTable<F>,
+        sha256_table: &dyn LookupTable<F>,
+        modexp_table: &dyn LookupTable<F>,
+        ecc_table: &dyn LookupTable<F>,
+        ripemd160_table: &dyn LookupTable<F>,
+        blake2f_table: &dyn LookupTable<F>,
+        kzg_table: &dyn LookupTable<F>,
+        wd_table: &dyn LookupTable<F>,
     ) -> Self {
         let mut instrument = Instrument::default();
         let q_usable = meta.complex_selector();
@@ -397,14 +442,15 @@ impl<F: Field> ExecutionConfig<F> {
 
             // NEW: Enabled, this will break hand crafted tests, maybe we can remove them?
             let first_step_check = {
-                let begin_tx_invalid_tx_end_block_selector = step_curr.execution_state_selector([
+                let first_step_selector = step_curr.execution_state_selector([
                     ExecutionState::BeginTx,
                     ExecutionState::InvalidTx,
+                    ExecutionState::Withdrawal,
                     ExecutionState::EndBlock,
                 ]);
                 iter::once((
-                    "First step should be BeginTx, InvalidTx or EndBlock",
-                    q_step_first * (1.expr() - begin_tx_invalid_tx_end_block_selector),
+                    "First step should be BeginTx, InvalidTx, Withdrawal or EndBlock",
+                    q_step_first * (1.expr() - first_step_selector),
                 ))
             };
 
@@ -525,10 +571,13 @@ impl<F: Field> ExecutionConfig<F> {
             end_block_gadget: configure_gadget!(),
             end_tx_gadget: configure_gadget!(),
             invalid_tx: configure_gadget!(),
+            withdrawal_gadget: configure_gadget!(),
             // opcode gadgets
             add_sub_gadget: configure_gadget!(),
             addmod_gadget: configure_gadget!(),
             bitwise_gadget: configure_gadget!(),
+            blobbasefee_gadget: configure_gadget!(),
+            blobhash_gadget: configure_gadget!(),
             byte_gadget: configure_gadget!(),
             call_op_gadget: configure_gadget!(),
             call_value_gadget: configure_gadget!(),
@@ -550,6 +599,7 @@ impl<F: Field> ExecutionConfig<F> {
             jumpdest_gadget: configure_gadget!(),
             jumpi_gadget: configure_gadget!(),
             log_gadget: configure_gadget!(),
+            mcopy_gadget: configure_gadget!(),
             memory_gadget: configure_gadget!(),
             msize_gadget: configure_gadget!(),
             mul_div_mod_gadget: configure_gadget!(),
@@ -581,6 +631,8 @@ impl<F: Field> ExecutionConfig<F> {
             sstore_gadget: configure_gadget!(),
             stop_gadget: configure_gadget!(),
             swap_gadget: configure_gadget!(),
+            tload_gadget: configure_gadget!(),
+            tstore_gadget: configure_gadget!(),
             block_ctx_gadget: configure_gadget!(),
             // error gadgets
             error_oog_constant: configure_gadget!(),
@@ -603,12 +655,22 @@ impl<F: Field> ExecutionConfig<F> {
             error_invalid_opcode: configure_gadget!(),
             error_write_protection: configure_gadget!(),
             error_depth: configure_gadget!(),
+            error_insufficient_balance: configure_gadget!(),
             error_contract_address_collision: configure_gadget!(),
             error_invalid_creation_code: configure_gadget!(),
             error_precompile_failed: configure_gadget!(),
             error_return_data_out_of_bound: configure_gadget!(),
             // precompile calls
+            precompile_ecrecover_gadget: configure_gadget!(),
+            precompile_sha256_gadget: configure_gadget!(),
+            precompile_ripemd160_gadget: configure_gadget!(),
             precompile_identity_gadget: configure_gadget!(),
+            precompile_modexp_gadget: configure_gadget!(),
+            precompile_ec_add_gadget: configure_gadget!(),
+            precompile_ec_mul_gadget: configure_gadget!(),
+            precompile_ec_pairing_gadget: configure_gadget!(),
+            precompile_blake2f_gadget: configure_gadget!(),
+            precompile_point_evaluation_gadget: configure_gadget!(),
             // step and presets
             step: step_curr,
             height_map,
@@ -629,6 +691,14 @@ impl<F: Field> ExecutionConfig<F> {
             copy_table,
             keccak_table,
             exp_table,
+            sig_table,
+            sha256_table,
+            modexp_table,
+            ecc_table,
+            ripemd160_table,
+            blake2f_table,
+            kzg_table,
+            wd_table,
             &challenges,
             &cell_manager,
         );
@@ -787,18 +857,29 @@ impl<F: Field> ExecutionConfig<F> {
                 .chain(
                     IntoIterator::into_iter([
                         (
-                            "EndTx can only transit to BeginTx, InvalidTx or EndBlock",
+                            "EndTx can only transit to BeginTx, InvalidTx, Withdrawal or EndBlock",
                             ExecutionState::EndTx,
                             vec![
                                 ExecutionState::BeginTx,
                                 ExecutionState::InvalidTx,
+                                ExecutionState::Withdrawal,
                                 ExecutionState::EndBlock,
                             ],
                         ),
                         (
-                            "EndBlock can only transit to EndBlock",
+                            "Withdrawal can only transit to Withdrawal or EndBlock",
+                            ExecutionState::Withdrawal,
+                            vec![ExecutionState::Withdrawal, ExecutionState::EndBlock],
+                        ),
+                        (
+                            "EndBlock can only transit to BeginTx, InvalidTx, Withdrawal or EndBlock",
                             ExecutionState::EndBlock,
-                            vec![ExecutionState::EndBlock],
+                            vec![
+                                ExecutionState::BeginTx,
+                                ExecutionState::InvalidTx,
+                                ExecutionState::Withdrawal,
+                                ExecutionState::EndBlock,
+                            ],
                         ),
                     ])
                     .filter(move |(_, from, _)| *from == execution_state)
@@ -807,14 +888,22 @@ impl<F: Field> ExecutionConfig<F> {
                 .chain(
                     IntoIterator::into_iter([
                         (
-                            "Only EndTx and InvalidTx can transit to InvalidTx",
+                            "Only EndTx, InvalidTx or EndBlock can transit to InvalidTx",
                             ExecutionState::InvalidTx,
-                            vec![ExecutionState::EndTx, ExecutionState::InvalidTx],
+                            vec![
+                                ExecutionState::EndTx,
+                                ExecutionState::InvalidTx,
+                                ExecutionState::EndBlock,
+                            ],
                         ),
                         (
-                            "Only EndTx and InvalidTx can transit to BeginTx",
+                            "Only EndTx, InvalidTx or EndBlock can transit to BeginTx",
                             ExecutionState::BeginTx,
-                            vec![ExecutionState::EndTx, ExecutionState::InvalidTx],
+                            vec![
+                                ExecutionState::EndTx,
+                                ExecutionState::InvalidTx,
+                                ExecutionState::EndBlock,
+                            ],
                         ),
                         (
                             "Only ExecutionState which halts or BeginTx can transit to EndTx",
@@ -825,11 +914,22 @@ impl<F: Field> ExecutionConfig<F> {
                                 .collect(),
                         ),
                         (
-                            "Only EndTx, InvalidTx or EndBlock can transit to EndBlock",
+                            "Only EndTx, InvalidTx, Withdrawal or EndBlock can transit to Withdrawal",
+                            ExecutionState::Withdrawal,
+                            vec![
+                                ExecutionState::EndTx,
+                                ExecutionState::InvalidTx,
+                                ExecutionState::Withdrawal,
+                                ExecutionState::EndBlock,
+                            ],
+                        ),
+                        (
+                            "Only EndTx, InvalidTx, Withdrawal or EndBlock can transit to EndBlock",
                             ExecutionState::EndBlock,
                             vec![
                                 ExecutionState::EndTx,
                                 ExecutionState::InvalidTx,
+                                ExecutionState::Withdrawal,
                                 ExecutionState::EndBlock,
                             ],
                         ),
@@ -863,6 +963,14 @@ impl<F: Field> ExecutionConfig<F> {
         copy_table: &dyn LookupTable<F>,
         keccak_table: &dyn LookupTable<F>,
         exp_table: &dyn LookupTable<F>,
+        sig_table: &dyn LookupTable<F>,
+        sha256_table: &dyn LookupTable<F>,
+        modexp_table: &dyn LookupTable<F>,
+        ecc_table: &dyn LookupTable<F>,
+        ripemd160_table: &dyn LookupTable<F>,
+        blake2f_table: &dyn LookupTable<F>,
+        kzg_table: &dyn LookupTable<F>,
+        wd_table: &dyn LookupTable<F>,
         challenges: &Challenges<Expression<F>>,
         cell_manager: &CellManager<CMFixedWidthStrategy>,
     ) {
@@ -882,6 +990,14 @@ impl<F: Field> ExecutionConfig<F> {
                         Table::Copy => copy_table,
                         Table::Keccak => keccak_table,
                         Table::Exp => exp_table,
+                        Table::Sig => sig_table,
+                        Table::Sha256 => sha256_table,
+                        Table::ModExp => modexp_table,
+                        Table::Ecc => ecc_table,
+                        Table::Ripemd160 => ripemd160_table,
+                        Table::Blake2f => blake2f_table,
+                        Table::Kzg => kzg_table,
+                        Table::Wd => wd_table,
                     }
                     .table_exprs(meta);
                     vec![(
@@ -960,14 +1076,52 @@ impl<F: Field> ExecutionConfig<F> {
                     .unwrap_or_else(Call::default);
                 let end_block_not_last = &block.end_block_not_last;
                 let end_block_last = &block.end_block_last;
-                // Collect all steps
+                // The withdrawals and the EndBlock of each block of the chunk are
+                // assigned with the call of the last tx processed before them.
+                let block_last_calls = block
+                    .contexts
+                    .iter()
+                    .map(|context| {
+                        block
+                            .txs
+                            .iter()
+                            .rev()
+                            .find(|tx| tx.block_num <= context.number.as_u64())
+                            .map(|tx| tx.calls()[0].clone())
+                            .unwrap_or_else(Call::default)
+                    })
+                    .collect_vec();
+                // Collect all steps, block by block
                 let mut steps = block
-                    .txs
+                    .contexts
                     .iter()
-                    .flat_map(|tx| {
-                        tx.steps()
+                    .zip(&block_last_calls)
+                    .enumerate()
+                    .flat_map(|(index, (context, block_last_call))| {
+                        let number = context.number.as_u64();
+                        let withdrawal_offset = context.withdrawal_offset as usize;
+                        let dummy_tx = &dummy_tx;
+                        block
+                            .txs
                             .iter()
-                            .map(move |step| (tx, &tx.calls()[step.call_index], step))
+                            .filter(move |tx| tx.block_num == number)
+                            .flat_map(|tx| {
+                                tx.steps()
+                                    .iter()
+                                    .map(move |step| (tx, &tx.calls()[step.call_index], step))
+                            })
+                            .chain(
+                                block.withdrawal_steps[withdrawal_offset
+                                    ..withdrawal_offset + context.withdrawals.len()]
+                                    .iter()
+                                    .map(move |step| (dummy_tx, block_last_call, step)),
+                            )
+                            .chain(
+                                block
+                                    .end_blocks
+                                    .get(index)
+                                    .map(move |step| (dummy_tx, block_last_call, step)),
+                            )
                     })
                     .chain(std::iter::once((&dummy_tx, &last_call, end_block_not_last)))
                     .peekable();
@@ -1260,12 +1414,15 @@ impl<F: Field> ExecutionConfig<F> {
             ExecutionState::EndTx => assign_exec_step!(self.end_tx_gadget),
             ExecutionState::EndBlock => assign_exec_step!(self.end_block_gadget),
             ExecutionState::InvalidTx => assign_exec_step!(self.invalid_tx),
+            ExecutionState::Withdrawal => assign_exec_step!(self.withdrawal_gadget),
             // opcode
             ExecutionState::ADD_SUB => assign_exec_step!(self.add_sub_gadget),
             ExecutionState::ADDMOD => assign_exec_step!(self.addmod_gadget),
             ExecutionState::ADDRESS => assign_exec_step!(self.address_gadget),
             ExecutionState::BALANCE => assign_exec_step!(self.balance_gadget),
             ExecutionState::BITWISE => assign_exec_step!(self.bitwise_gadget),
+            ExecutionState::BLOBBASEFEE => assign_exec_step!(self.blobbasefee_gadget),
+            ExecutionState::BLOBHASH => assign_exec_step!(self.blobhash_gadget),
             ExecutionState::BYTE => assign_exec_step!(self.byte_gadget),
             ExecutionState::CALL_OP => assign_exec_step!(self.call_op_gadget),
             ExecutionState::CALLDATACOPY => assign_exec_step!(self.calldatacopy_gadget),
@@ -1288,6 +1445,7 @@ impl<F: Field> ExecutionConfig<F> {
             ExecutionState::JUMPDEST => assign_exec_step!(self.jumpdest_gadget),
             ExecutionState::JUMPI => assign_exec_step!(self.jumpi_gadget),
             ExecutionState::LOG => assign_exec_step!(self.log_gadget),
+            ExecutionState::MCOPY => assign_exec_step!(self.mcopy_gadget),
             ExecutionState::MEMORY => assign_exec_step!(self.memory_gadget),
             ExecutionState::MSIZE => assign_exec_step!(self.msize_gadget),
             ExecutionState::MUL_DIV_MOD => assign_exec_step!(self.mul_div_mod_gadget),
@@ -1306,20 +1464,19 @@ impl<F: Field> ExecutionConfig<F> {
             ExecutionState::BLOCKCTX => assign_exec_step!(self.block_ctx_gadget),
             ExecutionState::BLOCKHASH => assign_exec_step!(self.blockhash_gadget),
             ExecutionState::SELFBALANCE => assign_exec_step!(self.selfbalance_gadget),
-            // dummy gadgets
             ExecutionState::EXTCODECOPY => assign_exec_step!(self.extcodecopy_gadget),
             ExecutionState::CREATE => assign_exec_step!(self.create_gadget),
             ExecutionState::CREATE2 => assign_exec_step!(self.create2_gadget),
             ExecutionState::SELFDESTRUCT => assign_exec_step!(self.selfdestruct_gadget),
-            // end of dummy gadgets
             ExecutionState::SHA3 => assign_exec_step!(self.sha3_gadget),
             ExecutionState::SHL_SHR => assign_exec_step!(self.shl_shr_gadget),
             ExecutionState::SIGNEXTEND => assign_exec_step!(self.signextend_gadget),
             ExecutionState::SLOAD => assign_exec_step!(self.sload_gadget),
             ExecutionState::SSTORE => assign_exec_step!(self.sstore_gadget),
+            ExecutionState::TLOAD => assign_exec_step!(self.tload_gadget),
+            ExecutionState::TSTORE => assign_exec_step!(self.tstore_gadget),
             ExecutionState::STOP => assign_exec_step!(self.stop_gadget),
             ExecutionState::SWAP => assign_exec_step!(self.swap_gadget),
-            // dummy errors
             ExecutionState::ErrorOutOfGasStaticMemoryExpansion => {
                 assign_exec_step!(self.error_oog_static_memory_gadget)
             }
@@ -1379,6 +1536,15 @@ impl<F: Field> ExecutionConfig<F> {
             ExecutionState::ErrorWriteProtection => {
                 assign_exec_step!(self.error_write_protection)
             }
+            ExecutionState::ErrorDepth => {
+                assign_exec_step!(self.error_depth)
+            }
+            ExecutionState::ErrorInsufficientBalance => {
+                assign_exec_step!(self.error_insufficient_balance)
+            }
+            ExecutionState::ErrorContractAddressCollision => {
+                assign_exec_step!(self.error_contract_address_collision)
+            }
             ExecutionState::ErrorInvalidCreationCode => {
                 assign_exec_step!(self.error_invalid_creation_code)
             }
@@ -1389,9 +1555,36 @@ impl<F: Field> ExecutionConfig<F> {
                 assign_exec_step!(self.error_precompile_failed)
             }
             // precompile calls
+            ExecutionState::PrecompileEcRecover => {
+                assign_exec_step!(self.precompile_ecrecover_gadget)
+            }
+            ExecutionState::PrecompileSha256 => {
+                assign_exec_step!(self.precompile_sha256_gadget)
+            }
+            ExecutionState::PrecompileRipemd160 => {
+                assign_exec_step!(self.precompile_ripemd160_gadget)
+            }
             ExecutionState::PrecompileIdentity => {
                 assign_exec_step!(self.precompile_identity_gadget)
             }
+            ExecutionState::PrecompileBigModExp => {
+                assign_exec_step!(self.precompile_modexp_gadget)
+            }
+            ExecutionState::PrecompileBn256Add => {
+                assign_exec_step!(self.precompile_ec_add_gadget)
+            }
+            ExecutionState::PrecompileBn256ScalarMul => {
+                assign_exec_step!(self.precompile_ec_mul_gadget)
+            }
+            ExecutionState::PrecompileBn256Pairing => {
+                assign_exec_step!(self.precompile_ec_pairing_gadget)
+            }
+            ExecutionState::PrecompileBlake2f => {
+                assign_exec_step!(self.precompile_blake2f_gadget)
+            }
+            ExecutionState::PrecompilePointEvaluation => {
+                assign_exec_step!(self.precompile_point_evaluation_gadget)
+            }
 
             unimpl_state => evm_unimplemented!("unimplemented ExecutionState: {:?}", unimpl_state),
         }
@@ -27,7 +27,10 @@ use crate::{
     },
 };
 use bus_mapping::state_db::CodeDB;
-use eth_types::{evm_types::PRECOMPILE_COUNT, keccak256, Field, ToWord, U256};
+use eth_types::{
+    evm_types::{Hardfork, PRECOMPILE_COUNT},
+    keccak256, Field, ToWord, U256,
+};
 use halo2_proofs::{
     circuit::Value,
     plonk::{Error, Expression},
@@ -107,8 +110,9 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             None,
         ); // rwc_delta += 1
 
-        // Add precompile contract address to access list
-        for addr in 1..=PRECOMPILE_COUNT {
+        // Add precompile contract address to access list. The precompiles
+        // after the first `PRECOMPILE_COUNT - 1` only exist since Cancun.
+        for addr in 1..PRECOMPILE_COUNT {
             cb.account_access_list_write_unchecked(
                 tx_id.expr(),
                 Word::new([addr.expr(), 0.expr()]),
@@ -116,7 +120,19 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 0.expr(),
                 None,
             );
-        } // rwc_delta += PRECOMPILE_COUNT
+        }
+        let is_cancun = tx.hardfork.is_active(Hardfork::Cancun);
+        cb.condition(is_cancun.expr(), |cb| {
+            cb.account_access_list_write_unchecked(
+                tx_id.expr(),
+                Word::new([PRECOMPILE_COUNT.expr(), 0.expr()]),
+                1.expr(),
+                0.expr(),
+                None,
+            );
+        });
+        // rwc_delta += precompile_count
+        let precompile_count = (PRECOMPILE_COUNT - 1).expr() + is_cancun;
 
         // Prepare access list of caller and callee
         cb.account_access_list_write_unchecked(
@@ -142,7 +158,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         let is_coinbase_warm = cb.query_bool();
         cb.block_lookup(
             BlockContextFieldTag::Coinbase.expr(),
-            None,
+            Some(tx.block_number.expr()),
             coinbase.to_word(),
         );
         cb.account_access_list_write_unchecked(
@@ -180,7 +196,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             not::expr(callee_not_exists.expr()),
             or::expr([tx.is_create.expr(), callee_not_exists.expr()]),
             tx.value.clone(),
-            tx.mul_gas_fee_by_gas.product().clone(),
+            tx.fee(),
             &mut reversion_info,
         );
 
@@ -225,6 +241,13 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 Word::zero(),
                 Some(&mut reversion_info),
             );
+            cb.tx_created_account_write(
+                tx_id.expr(),
+                call_callee_address.to_word(),
+                1.expr(),
+                0.expr(),
+                Some(&mut reversion_info),
+            );
             for (field_tag, value) in [
                 (CallContextFieldTag::Depth, Word::one()),
                 (
@@ -262,18 +285,19 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             }
 
             cb.require_step_state_transition(StepStateTransition {
-                // 21 + a reads and writes:
+                // 22 + a reads and writes:
                 //   - Write CallContext TxId
                 //   - Write CallContext RwCounterEndOfReversion
                 //   - Write CallContext IsPersistent
                 //   - Write CallContext IsSuccess
                 //   - Write Account (Caller) Nonce
-                //   - Write TxAccessListAccount (Precompile) x PRECOMPILE_COUNT
+                //   - Write TxAccessListAccount (Precompile) x precompile_count
                 //   - Write TxAccessListAccount (Caller)
                 //   - Write TxAccessListAccount (Callee)
                 //   - Write TxAccessListAccount (Coinbase) for EIP-3651
                 //   - a TransferWithGasFeeGadget
                 //   - Write Account (Callee) Nonce (Reversible)
+                //   - Write TxCreatedAccount (Callee) (Reversible)
                 //   - Write CallContext Depth
                 //   - Write CallContext CallerAddress
                 //   - Write CallContext CalleeAddress
@@ -288,17 +312,18 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 //   - Write CallContext IsCreate
                 //   - Write CallContext CodeHash
                 rw_counter: Delta(
-                    23.expr() + transfer_with_gas_fee.rw_delta() + PRECOMPILE_COUNT.expr(),
+                    24.expr() + transfer_with_gas_fee.rw_delta() + precompile_count.expr(),
                 ),
                 call_id: To(call_id.expr()),
                 is_root: To(true.expr()),
                 is_create: To(tx.is_create.expr()),
                 code_hash: To(cb.curr.state.code_hash.to_word()),
                 gas_left: To(gas_left.clone()),
-                // There are a + 1 reversible writes:
+                // There are a + 2 reversible writes:
                 //  - a TransferWithGasFeeGadget
                 //  - Callee Account Nonce
-                reversible_write_counter: To(transfer_with_gas_fee.reversible_w_delta() + 1.expr()),
+                //  - Callee TxCreatedAccount
+                reversible_write_counter: To(transfer_with_gas_fee.reversible_w_delta() + 2.expr()),
                 log_id: To(0.expr()),
                 ..StepStateTransition::new_context()
             });
@@ -328,14 +353,14 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                     //   - Write CallContext IsPersistent
                     //   - Write CallContext IsSuccess
                     //   - Write Account Nonce
-                    //   - Write TxAccessListAccount (Precompile) x PRECOMPILE_COUNT
+                    //   - Write TxAccessListAccount (Precompile) x precompile_count
                     //   - Write TxAccessListAccount (Caller)
                     //   - Write TxAccessListAccount (Callee)
                     //   - Write TxAccessListAccount (Coinbase) for EIP-3651
                     //   - Read Account CodeHash
                     //   - a TransferWithGasFeeGadget
                     rw_counter: Delta(
-                        9.expr() + transfer_with_gas_fee.rw_delta() + PRECOMPILE_COUNT.expr(),
+                        9.expr() + transfer_with_gas_fee.rw_delta() + precompile_count.expr(),
                     ),
                     call_id: To(call_id.expr()),
                     ..StepStateTransition::any()
@@ -391,7 +416,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                     //   - Write CallContext IsPersistent
                     //   - Write CallContext IsSuccess
                     //   - Write Account Nonce
-                    //   - Write TxAccessListAccount (Precompile) x PRECOMPILE_COUNT
+                    //   - Write TxAccessListAccount (Precompile) x precompile_count
                     //   - Write TxAccessListAccount (Caller)
                     //   - Write TxAccessListAccount (Callee)
                     //   - Write TxAccessListAccount (Coinbase) for EIP-3651
@@ -411,7 +436,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                     //   - Write CallContext IsCreate
                     //   - Write CallContext CodeHash
                     rw_counter: Delta(
-                        22.expr() + transfer_with_gas_fee.rw_delta() + PRECOMPILE_COUNT.expr(),
+                        22.expr() + transfer_with_gas_fee.rw_delta() + precompile_count.expr(),
                     ),
                     call_id: To(call_id.expr()),
                     is_root: To(true.expr()),
@@ -453,21 +478,21 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         call: &Call,
         step: &ExecStep,
     ) -> Result<(), Error> {
-        let gas_fee = tx.gas_price * tx.gas();
+        let gas_fee = tx.gas_price * tx.gas() + tx.blob_gas_fee(block.tx_context(tx).blob_base_fee);
         let zero = eth_types::Word::zero();
 
         let mut rws = StepRws::new(block, step);
         rws.offset_add(7);
 
-        rws.offset_add(PRECOMPILE_COUNT as usize);
+        rws.offset_add(block.context.hardfork.precompile_count() as usize);
 
         let is_coinbase_warm = rws.next().tx_access_list_value_pair().1;
         let mut callee_code_hash = zero;
-        if !is_precompiled(&tx.to_or_contract_addr()) {
+        if !is_precompiled(&tx.to_or_contract_addr(), block.context.hardfork) {
             callee_code_hash = rws.next().account_codehash_pair().1;
         }
-        let callee_exists =
-            is_precompiled(&tx.to_or_contract_addr()) || !callee_code_hash.is_zero();
+        let callee_exists = is_precompiled(&tx.to_or_contract_addr(), block.context.hardfork)
+            || !callee_code_hash.is_zero();
         let caller_balance_sub_fee_pair = rws.next().account_balance_pair();
         let must_create = tx.is_create();
         if !callee_exists && (!tx.value.is_zero() || must_create) {
@@ -481,7 +506,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         };
 
         self.begin_tx.assign(region, offset, tx)?;
-        self.tx.assign(region, offset, tx)?;
+        self.tx.assign(region, offset, block, tx)?;
 
         self.tx_caller_address_is_zero.assign_u256(
             region,
@@ -546,7 +571,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         )?;
 
         self.coinbase
-            .assign_h160(region, offset, block.context.coinbase)?;
+            .assign_h160(region, offset, block.tx_context(tx).coinbase)?;
         self.is_coinbase_warm.assign(
             region,
             offset,
@@ -561,7 +586,10 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
 mod test {
     use crate::{evm_circuit::test::rand_bytes, test_util::CircuitTestBuilder};
     use bus_mapping::evm::OpcodeId;
-    use eth_types::{self, bytecode, evm_types::GasCost, word, Address, Bytecode, Word};
+    use eth_types::{
+        self, bytecode, evm_types::GasCost, geth_types::BLOB_TX_TYPE, word, Address, Bytecode,
+        Word, H256,
+    };
     use ethers_core::utils::get_contract_address;
     use mock::{eth, gwei, MockTransaction, TestContext, MOCK_ACCOUNTS};
     use std::vec;
@@ -754,6 +782,37 @@ mod test {
         CircuitTestBuilder::new_from_test_ctx(ctx).run();
     }
 
+    #[test]
+    fn begin_tx_blob_tx() {
+        // The blob gas fee is deducted from the caller upfront, together with
+        // the gas fee.
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(MOCK_ACCOUNTS[0]).balance(eth(10));
+                accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+            },
+            |mut txs, _accs| {
+                txs[0]
+                    .from(MOCK_ACCOUNTS[1])
+                    .to(MOCK_ACCOUNTS[0])
+                    .gas_price(gwei(2))
+                    .value(eth(1))
+                    .transaction_type(BLOB_TX_TYPE)
+                    .max_fee_per_blob_gas(gwei(1))
+                    .blob_versioned_hashes(vec![H256::repeat_byte(0x01), H256::repeat_byte(0x02)]);
+            },
+            |block, _tx| {
+                block
+                    .number(0xcafeu64)
+                    .excess_blob_gas(Some(10 * 1024 * 1024))
+            },
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
     fn begin_tx_deploy(nonce: u64) {
         let code = bytecode! {
             // [ADDRESS, STOP]
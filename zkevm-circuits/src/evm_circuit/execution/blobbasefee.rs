@@ -0,0 +1,138 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        step::ExecutionState,
+        util::{
+            common_gadget::{HardforkGadget, SameContextGadget},
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
+                Transition::Delta,
+            },
+            CachedRegion, Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::{BlockContextFieldTag, CallContextFieldTag, TxContextFieldTag},
+    util::{
+        word::{WordCell, WordExpr},
+        Expr,
+    },
+};
+use bus_mapping::evm::OpcodeId;
+use eth_types::{evm_types::Hardfork, Field};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+#[derive(Clone, Debug)]
+pub(crate) struct BlobBaseFeeGadget<F> {
+    same_context: SameContextGadget<F>,
+    hardfork: HardforkGadget<F>,
+    tx_id: Cell<F>,
+    block_number: Cell<F>,
+    blob_base_fee: WordCell<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for BlobBaseFeeGadget<F> {
+    const NAME: &'static str = "BLOBBASEFEE";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::BLOBBASEFEE;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // BLOBBASEFEE is only defined since Cancun (EIP-7516).
+        let hardfork = HardforkGadget::construct(cb);
+        cb.require_equal(
+            "BLOBBASEFEE is defined in the hardfork",
+            hardfork.is_active(Hardfork::Cancun),
+            1.expr(),
+        );
+
+        // The blob base fee is taken from the block of the tx
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+        let block_number = cb.tx_context(tx_id.expr(), TxContextFieldTag::BlockNumber, None);
+
+        let blob_base_fee = cb.query_word_unchecked();
+
+        // Push the value to the stack
+        cb.stack_push(blob_base_fee.to_word());
+
+        // Lookup block table with blob_base_fee
+        cb.block_lookup(
+            BlockContextFieldTag::BlobBaseFee.expr(),
+            Some(block_number.expr()),
+            blob_base_fee.to_word(),
+        );
+
+        // State transition
+        let opcode = cb.query_cell();
+        let step_state_transition = StepStateTransition {
+            rw_counter: Delta(2.expr()),
+            program_counter: Delta(1.expr()),
+            stack_pointer: Delta((-1).expr()),
+            gas_left: Delta(-OpcodeId::BLOBBASEFEE.constant_gas_cost().expr()),
+            ..Default::default()
+        };
+        let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
+
+        Self {
+            same_context,
+            hardfork,
+            tx_id,
+            block_number,
+            blob_base_fee,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        tx: &Transaction,
+        _: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.same_context.assign_exec_step(region, offset, step)?;
+        self.hardfork
+            .assign(region, offset, block.context.hardfork)?;
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx.id)))?;
+        self.block_number
+            .assign(region, offset, Value::known(F::from(tx.block_num)))?;
+        let blob_base_fee = block.get_rws(step, 1).stack_value();
+
+        self.blob_base_fee
+            .assign_u256(region, offset, blob_base_fee)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::bytecode;
+    use mock::test_ctx::{helpers::*, TestContext};
+
+    fn test_ok(excess_blob_gas: Option<u64>) {
+        let bytecode = bytecode! {
+            #[start]
+            BLOBBASEFEE
+            STOP
+        };
+
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode),
+            tx_from_1_to_0,
+            |block, _tx| block.number(0xcafeu64).excess_blob_gas(excess_blob_gas),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn blobbasefee_gadget_test() {
+        test_ok(None);
+        test_ok(Some(0x60000));
+        test_ok(Some(10 * 1024 * 1024));
+    }
+}
@@ -0,0 +1,182 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::N_BYTES_U64,
+        step::ExecutionState,
+        util::{
+            common_gadget::{HardforkGadget, SameContextGadget, WordByteCapGadget},
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
+                Transition::Delta,
+            },
+            CachedRegion, Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::{CallContextFieldTag, TxContextFieldTag},
+    util::{
+        word::{Word, WordCell, WordExpr},
+        Expr,
+    },
+};
+use bus_mapping::evm::OpcodeId;
+use eth_types::{evm_types::Hardfork, Field};
+use gadgets::util::not;
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+#[derive(Clone, Debug)]
+pub(crate) struct BlobHashGadget<F> {
+    same_context: SameContextGadget<F>,
+    hardfork: HardforkGadget<F>,
+    tx_id: Cell<F>,
+    blob_versioned_hashes_len: Cell<F>,
+    /// The blob index is valid only when it is less than the number of blob
+    /// versioned hashes carried by the transaction.
+    index: WordByteCapGadget<F, N_BYTES_U64>,
+    blob_versioned_hash: WordCell<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for BlobHashGadget<F> {
+    const NAME: &'static str = "BLOBHASH";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::BLOBHASH;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // BLOBHASH is only defined since Cancun (EIP-4844).
+        let hardfork = HardforkGadget::construct(cb);
+        cb.require_equal(
+            "BLOBHASH is defined in the hardfork",
+            hardfork.is_active(Hardfork::Cancun),
+            1.expr(),
+        );
+
+        let blob_versioned_hashes_len = cb.query_cell();
+        let index = WordByteCapGadget::construct(cb, blob_versioned_hashes_len.expr());
+        cb.stack_pop(index.original_word().to_word());
+
+        // Lookup in call_ctx the TxId and the number of blobs of the tx
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+        cb.tx_context_lookup(
+            tx_id.expr(),
+            TxContextFieldTag::BlobVersionedHashesLen,
+            None,
+            Word::from_lo_unchecked(blob_versioned_hashes_len.expr()),
+        );
+
+        let blob_versioned_hash = cb.query_word_unchecked();
+        cb.condition(index.lt_cap(), |cb| {
+            cb.tx_context_lookup(
+                tx_id.expr(),
+                TxContextFieldTag::BlobVersionedHash,
+                Some(index.valid_value()),
+                blob_versioned_hash.to_word(),
+            );
+        });
+        cb.condition(not::expr(index.lt_cap()), |cb| {
+            cb.require_zero_word(
+                "BLOBHASH returns zero for an out of range index",
+                blob_versioned_hash.to_word(),
+            );
+        });
+
+        cb.stack_push(blob_versioned_hash.to_word());
+
+        let step_state_transition = StepStateTransition {
+            rw_counter: Delta(3.expr()),
+            program_counter: Delta(1.expr()),
+            gas_left: Delta(-OpcodeId::BLOBHASH.constant_gas_cost().expr()),
+            ..Default::default()
+        };
+        let opcode = cb.query_cell();
+        let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
+
+        Self {
+            same_context,
+            hardfork,
+            tx_id,
+            blob_versioned_hashes_len,
+            index,
+            blob_versioned_hash,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        tx: &Transaction,
+        _: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.same_context.assign_exec_step(region, offset, step)?;
+        self.hardfork
+            .assign(region, offset, block.context.hardfork)?;
+
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx.id)))?;
+
+        let blob_versioned_hashes_len = F::from(tx.blob_versioned_hashes.len() as u64);
+        self.blob_versioned_hashes_len.assign(
+            region,
+            offset,
+            Value::known(blob_versioned_hashes_len),
+        )?;
+
+        let index = block.get_rws(step, 0).stack_value();
+        self.index
+            .assign(region, offset, index, blob_versioned_hashes_len)?;
+
+        self.blob_versioned_hash.assign_u256(
+            region,
+            offset,
+            block.get_rws(step, 2).stack_value(),
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::{bytecode, geth_types::BLOB_TX_TYPE, Word, H256};
+    use mock::test_ctx::{helpers::*, TestContext};
+
+    fn test_ok(index: Word) {
+        let code = bytecode! {
+            PUSH32(index)
+            BLOBHASH
+            STOP
+        };
+
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .transaction_type(BLOB_TX_TYPE)
+                    .max_fee_per_blob_gas(Word::from(10))
+                    .blob_versioned_hashes(vec![H256::repeat_byte(0x01), H256::repeat_byte(0x02)]);
+            },
+            |block, _tx| block.number(0xcafeu64).excess_blob_gas(Some(0)),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn blobhash_gadget_in_range() {
+        test_ok(Word::zero());
+        test_ok(Word::one());
+    }
+
+    #[test]
+    fn blobhash_gadget_out_of_range() {
+        test_ok(Word::from(2));
+        test_ok(Word::MAX);
+    }
+}
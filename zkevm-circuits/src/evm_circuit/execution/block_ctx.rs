@@ -2,28 +2,36 @@ use crate::{
     evm_circuit::{
         step::ExecutionState,
         util::{
-            common_gadget::SameContextGadget,
-            constraint_builder::{EVMConstraintBuilder, StepStateTransition, Transition::Delta},
-            CachedRegion,
+            common_gadget::{HardforkGadget, SameContextGadget},
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
+                Transition::Delta,
+            },
+            math_gadget::IsZeroGadget,
+            not, CachedRegion, Cell,
         },
         witness::{Block, Call, ExecStep, Transaction},
     },
-    table::BlockContextFieldTag,
+    table::{BlockContextFieldTag, CallContextFieldTag, TxContextFieldTag},
     util::{
         word::{WordCell, WordExpr},
         Expr,
     },
 };
 use bus_mapping::evm::OpcodeId;
-use eth_types::Field;
-use halo2_proofs::plonk::Error;
+use eth_types::{evm_types::Hardfork, Field};
+use halo2_proofs::{circuit::Value, plonk::Error};
 
 use super::ExecutionGadget;
 
 #[derive(Clone, Debug)]
 pub(crate) struct BlockCtxGadget<F> {
     same_context: SameContextGadget<F>,
+    tx_id: Cell<F>,
+    block_number: Cell<F>,
     value: WordCell<F>,
+    is_basefee: IsZeroGadget<F>,
+    hardfork: HardforkGadget<F>,
 }
 
 impl<F: Field> ExecutionGadget<F> for BlockCtxGadget<F> {
@@ -32,6 +40,10 @@ impl<F: Field> ExecutionGadget<F> for BlockCtxGadget<F> {
     const EXECUTION_STATE: ExecutionState = ExecutionState::BLOCKCTX;
 
     fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // The value is taken from the block of the tx
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+        let block_number = cb.tx_context(tx_id.expr(), TxContextFieldTag::BlockNumber, None);
+
         let value = cb.query_word_unchecked(); // block table lookup below
 
         cb.stack_push(value.to_word());
@@ -43,11 +55,19 @@ impl<F: Field> ExecutionGadget<F> for BlockCtxGadget<F> {
 
         // Lookup block table with block context ops
         // TIMESTAMP/NUMBER/GASLIMIT, COINBASE and DIFFICULTY/BASEFEE
-        cb.block_lookup(blockctx_tag, None, value.to_word());
+        cb.block_lookup(blockctx_tag, Some(block_number.expr()), value.to_word());
+
+        // BASEFEE is only defined since London (EIP-3198).
+        let is_basefee = IsZeroGadget::construct(cb, opcode.expr() - OpcodeId::BASEFEE.expr());
+        let hardfork = HardforkGadget::construct(cb);
+        cb.require_zero(
+            "BASEFEE is defined in the hardfork",
+            is_basefee.expr() * not::expr(hardfork.is_active(Hardfork::London)),
+        );
 
         // State transition
         let step_state_transition = StepStateTransition {
-            rw_counter: Delta(1.expr()),
+            rw_counter: Delta(2.expr()),
             program_counter: Delta(1.expr()),
             stack_pointer: Delta((-1).expr()),
             gas_left: Delta(-OpcodeId::TIMESTAMP.constant_gas_cost().expr()),
@@ -57,7 +77,11 @@ impl<F: Field> ExecutionGadget<F> for BlockCtxGadget<F> {
 
         Self {
             same_context,
+            tx_id,
+            block_number,
             value,
+            is_basefee,
+            hardfork,
         }
     }
 
@@ -66,16 +90,29 @@ impl<F: Field> ExecutionGadget<F> for BlockCtxGadget<F> {
         region: &mut CachedRegion<'_, '_, F>,
         offset: usize,
         block: &Block<F>,
-        _: &Transaction,
+        tx: &Transaction,
         _: &Call,
         step: &ExecStep,
     ) -> Result<(), Error> {
         self.same_context.assign_exec_step(region, offset, step)?;
 
-        let value = block.get_rws(step, 0).stack_value();
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx.id)))?;
+        self.block_number
+            .assign(region, offset, Value::known(F::from(tx.block_num)))?;
+        let value = block.get_rws(step, 1).stack_value();
 
         self.value.assign_u256(region, offset, value)?;
 
+        let opcode = step.opcode().unwrap();
+        self.is_basefee.assign(
+            region,
+            offset,
+            F::from(opcode.as_u64()) - F::from(OpcodeId::BASEFEE.as_u64()),
+        )?;
+        self.hardfork
+            .assign(region, offset, block.context.hardfork)?;
+
         Ok(())
     }
 }
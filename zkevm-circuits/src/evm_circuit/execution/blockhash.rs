@@ -15,17 +15,18 @@ use crate::{
         },
         witness::{Block, Call, ExecStep, Transaction},
     },
-    table::BlockContextFieldTag,
+    table::{BlockContextFieldTag, CallContextFieldTag, TxContextFieldTag},
     util::word::WordExpr,
 };
 use bus_mapping::evm::OpcodeId;
-use eth_types::{Field, ToScalar};
+use eth_types::Field;
 use gadgets::util::{not, Expr};
 use halo2_proofs::{circuit::Value, plonk::Error};
 
 #[derive(Clone, Debug)]
 pub(crate) struct BlockHashGadget<F> {
     same_context: SameContextGadget<F>,
+    tx_id: Cell<F>,
     block_number: WordByteCapGadget<F, N_BYTES_U64>,
     current_block_number: Cell<F>,
     block_hash: Word<Cell<F>>,
@@ -38,12 +39,10 @@ impl<F: Field> ExecutionGadget<F> for BlockHashGadget<F> {
     const EXECUTION_STATE: ExecutionState = ExecutionState::BLOCKHASH;
 
     fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
-        let current_block_number = cb.query_cell();
-        cb.block_lookup(
-            BlockContextFieldTag::Number.expr(),
-            None,
-            Word::from_lo_unchecked(current_block_number.expr()),
-        );
+        // The current block is the block of the tx
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+        let current_block_number =
+            cb.tx_context(tx_id.expr(), TxContextFieldTag::BlockNumber, None);
 
         let block_number = WordByteCapGadget::construct(cb, current_block_number.expr());
         cb.stack_pop(block_number.original_word().to_word());
@@ -76,7 +75,7 @@ impl<F: Field> ExecutionGadget<F> for BlockHashGadget<F> {
         cb.stack_push(block_hash.to_word());
 
         let step_state_transition = StepStateTransition {
-            rw_counter: Delta(2.expr()),
+            rw_counter: Delta(3.expr()),
             program_counter: Delta(1.expr()),
             gas_left: Delta(-OpcodeId::BLOCKHASH.constant_gas_cost().expr()),
             ..Default::default()
@@ -86,6 +85,7 @@ impl<F: Field> ExecutionGadget<F> for BlockHashGadget<F> {
         let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
         Self {
             same_context,
+            tx_id,
             block_number,
             current_block_number,
             block_hash,
@@ -98,18 +98,17 @@ impl<F: Field> ExecutionGadget<F> for BlockHashGadget<F> {
         region: &mut CachedRegion<'_, '_, F>,
         offset: usize,
         block: &Block<F>,
-        _: &Transaction,
+        tx: &Transaction,
         _: &Call,
         step: &ExecStep,
     ) -> Result<(), Error> {
         self.same_context.assign_exec_step(region, offset, step)?;
 
-        let current_block_number = block.context.number;
-        let current_block_number = current_block_number
-            .to_scalar()
-            .expect("unexpected U256 -> Scalar conversion failure");
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx.id)))?;
+        let current_block_number = F::from(tx.block_num);
 
-        let block_number = block.get_rws(step, 0).stack_value();
+        let block_number = block.get_rws(step, 1).stack_value();
         self.block_number
             .assign(region, offset, block_number, current_block_number)?;
 
@@ -117,7 +116,7 @@ impl<F: Field> ExecutionGadget<F> for BlockHashGadget<F> {
             .assign(region, offset, Value::known(current_block_number))?;
 
         self.block_hash
-            .assign_u256(region, offset, block.get_rws(step, 1).stack_value())?;
+            .assign_u256(region, offset, block.get_rws(step, 2).stack_value())?;
 
         self.diff_lt.assign(
             region,
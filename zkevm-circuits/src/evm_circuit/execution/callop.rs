@@ -5,7 +5,7 @@ use crate::{
         step::ExecutionState,
         util::{
             and,
-            common_gadget::{CommonCallGadget, TransferGadget},
+            common_gadget::{CommonCallGadget, HardforkGadget, TransferGadget},
             constraint_builder::{
                 ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
                 Transition::{Delta, To},
@@ -29,7 +29,7 @@ use crate::{
 use bus_mapping::{
     circuit_input_builder::CopyDataType,
     evm::OpcodeId,
-    precompile::{is_precompiled, PrecompileCalls},
+    precompile::{is_precompiled, PrecompileCalls, MODEXP_HEADER_LEN},
 };
 use eth_types::{evm_types::GAS_STIPEND_CALL_WITH_VALUE, Field, ToAddress, ToScalar, U256};
 use halo2_proofs::{circuit::Value, plonk::Error};
@@ -66,6 +66,7 @@ pub(crate) struct CallOpGadget<F> {
     one_64th_gas: ConstantDivisionGadget<F, N_BYTES_GAS>,
     capped_callee_gas_left: MinMaxGadget<F, N_BYTES_GAS>,
     // check if the call is a precompile call.
+    hardfork: HardforkGadget<F>,
     is_code_address_zero: IsZeroGadget<F>,
     is_precompile_lt: LtGadget<F, N_BYTES_ACCOUNT_ADDRESS>,
     precompile_gadget: PrecompileGadget<F>,
@@ -212,10 +213,15 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
         });
 
         // whether the call is to a precompiled contract.
-        // precompile contracts are stored from address 0x01 to 0x09.
+        // precompile contracts are stored from address 0x01 to the precompile
+        // count of the block's hardfork.
+        let hardfork = HardforkGadget::construct(cb);
         let is_code_address_zero = IsZeroGadget::construct(cb, call_gadget.callee_address.expr());
-        let is_precompile_lt =
-            LtGadget::construct(cb, call_gadget.callee_address.expr(), 0x0A.expr());
+        let is_precompile_lt = LtGadget::construct(
+            cb,
+            call_gadget.callee_address.expr(),
+            hardfork.select(|hardfork| hardfork.precompile_count()) + 1.expr(),
+        );
         let is_precompile = and::expr([
             not::expr(is_code_address_zero.expr()),
             is_precompile_lt.expr(),
@@ -503,6 +509,7 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
                     cb.curr.state.call_id.expr(),
                     call_gadget.cd_address.offset(),
                     call_gadget.cd_address.length(),
+                    precompile_input_len.expr(),
                     call_gadget.rd_address.offset(),
                     call_gadget.rd_address.length(),
                     precompile_return_length.expr(),
@@ -768,6 +775,7 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             one_64th_gas,
             capped_callee_gas_left,
             // precompile related fields.
+            hardfork,
             is_code_address_zero,
             is_precompile_lt,
             precompile_gadget,
@@ -959,26 +967,33 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
 
         let (_is_precompile_call, precompile_addr) = {
             let precompile_addr = callee_address.to_address();
-            let is_precompiled_call = is_precompiled(&precompile_addr);
+            let is_precompiled_call = is_precompiled(&precompile_addr, block.context.hardfork);
             (is_precompiled_call, precompile_addr)
         };
         let code_address: F = callee_address.to_address().to_scalar().unwrap();
+        self.hardfork
+            .assign(region, offset, block.context.hardfork)?;
         self.is_code_address_zero
             .assign(region, offset, code_address)?;
-        self.is_precompile_lt
-            .assign(region, offset, code_address, 0x0Au64.into())?;
-        let precompile_return_length = if is_precompiled(&callee_address.to_address()) {
-            rws.offset_add(14); // skip
-            let value_rw = rws.next();
-            assert_eq!(
-                value_rw.field_tag(),
-                Some(CallContextFieldTag::LastCalleeReturnDataLength as u64),
-                "expect LastCalleeReturnDataLength"
-            );
-            value_rw.call_context_value()
-        } else {
-            0.into()
-        };
+        self.is_precompile_lt.assign(
+            region,
+            offset,
+            code_address,
+            (block.context.hardfork.precompile_count() + 1).into(),
+        )?;
+        let precompile_return_length =
+            if is_precompiled(&callee_address.to_address(), block.context.hardfork) {
+                rws.offset_add(14); // skip
+                let value_rw = rws.next();
+                assert_eq!(
+                    value_rw.field_tag(),
+                    Some(CallContextFieldTag::LastCalleeReturnDataLength as u64),
+                    "expect LastCalleeReturnDataLength"
+                );
+                value_rw.call_context_value()
+            } else {
+                0.into()
+            };
         self.precompile_return_length.assign(
             region,
             offset,
@@ -1004,17 +1019,16 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             input_rws,
             output_rws,
             return_rws,
-        ) = if is_precheck_ok && is_precompiled(&callee_address.to_address()) {
+        ) = if is_precheck_ok
+            && is_precompiled(&callee_address.to_address(), block.context.hardfork)
+        {
             let precompile_call: PrecompileCalls = precompile_addr.0[19].into();
-            let input_len = if let Some(input_len) = precompile_call.input_len() {
-                min(input_len, cd_length.as_usize())
-            } else {
-                cd_length.as_usize()
-            };
-
-            let input_bytes = (0..input_len)
+            // MODEXP declares the length of its input in the header, which is read first.
+            let mut input_bytes = (0..min(MODEXP_HEADER_LEN, cd_length.as_usize()))
                 .map(|_| rws.next().memory_value())
                 .collect::<Vec<_>>();
+            let input_len = precompile_call.input_len_of(cd_length.as_usize(), &input_bytes);
+            input_bytes.extend((input_bytes.len()..input_len).map(|_| rws.next().memory_value()));
             let output_bytes = (0..precompile_return_length.as_u64())
                 .map(|_| rws.next().memory_value())
                 .collect::<Vec<_>>();
@@ -1089,7 +1103,7 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             F::from_u128(remainder),
         )?;
 
-        if is_precompiled(&callee_address.to_address()) {
+        if is_precompiled(&callee_address.to_address(), block.context.hardfork) {
             self.precompile_gadget.assign(
                 region,
                 offset,
@@ -7,7 +7,7 @@ use crate::{
         },
         step::ExecutionState,
         util::{
-            common_gadget::TransferGadget,
+            common_gadget::{HardforkGadget, TransferGadget},
             constraint_builder::{
                 ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
                 Transition::{Delta, To},
@@ -32,12 +32,9 @@ use crate::{
 use bus_mapping::{
     circuit_input_builder::CopyDataType, evm::OpcodeId, operation::Target, state_db::CodeDB,
 };
-use eth_types::{
-    evm_types::{GasCost, INIT_CODE_WORD_GAS},
-    Field, ToBigEndian, ToScalar, ToWord, U256,
-};
+use eth_types::{evm_types::GasCost, Field, ToBigEndian, ToScalar, ToWord, U256};
 use ethers_core::utils::keccak256;
-use gadgets::util::{and, select};
+use gadgets::util::and;
 use halo2_proofs::{
     circuit::Value,
     plonk::{Error, Expression},
@@ -45,13 +42,16 @@ use halo2_proofs::{
 
 use std::iter::once;
 
-/// Gadget for CREATE and CREATE2 opcodes
+/// Gadget for CREATE and CREATE2 opcodes. It is also used for
+/// [`ExecutionState::ErrorContractAddressCollision`], in which case only the
+/// address collision case is accepted.
 #[derive(Clone, Debug)]
 pub(crate) struct CreateGadget<F, const IS_CREATE2: bool, const S: ExecutionState> {
     opcode: Cell<F>,
     tx_id: Cell<F>,
     reversion_info: ReversionInfo<F>,
     depth: Cell<F>,
+    current_callee_address: WordCell<F>,
 
     is_create2: IsZeroGadget<F>,
     is_success: Cell<F>,
@@ -68,6 +68,7 @@ pub(crate) struct CreateGadget<F, const IS_CREATE2: bool, const S: ExecutionStat
 
     init_code: MemoryAddressGadget<F>,
     init_code_word_size: ConstantDivisionGadget<F, N_BYTES_MEMORY_ADDRESS>,
+    hardfork: HardforkGadget<F>,
     init_code_rlc: Cell<F>,
     keccak_output: Word32Cell<F>,
 
@@ -106,9 +107,16 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
         let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
         let depth = cb.call_context(None, CallContextFieldTag::Depth);
         let mut reversion_info = cb.reversion_info_read(None);
+        let current_callee_address =
+            cb.call_context_read_as_word(None, CallContextFieldTag::CalleeAddress);
 
         let keccak_output = cb.query_word32();
         let create = ContractCreateGadget::construct(cb);
+        cb.require_equal_word(
+            "caller address is the current callee address",
+            create.caller_address(),
+            current_callee_address.to_word(),
+        );
         let contract_addr = AccountAddress::new(
             keccak_output.limbs[..N_BYTES_ACCOUNT_ADDRESS]
                 .to_vec()
@@ -161,12 +169,11 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
             init_code.length() + (N_BYTES_WORD - 1).expr(),
             N_BYTES_WORD as u64,
         );
+        // The init code is only metered since EIP-3860 (Shanghai).
+        let hardfork = HardforkGadget::construct(cb);
         let keccak_gas_cost = init_code_word_size.quotient()
-            * select::expr(
-                is_create2.expr(),
-                (INIT_CODE_WORD_GAS + GasCost::COPY_SHA3).expr(),
-                INIT_CODE_WORD_GAS.expr(),
-            );
+            * (is_create2.expr() * GasCost::COPY_SHA3.expr()
+                + hardfork.select(|hardfork| hardfork.init_code_word_gas()));
         let gas_cost = GasCost::CREATE.expr() + memory_expansion.gas_cost() + keccak_gas_cost;
         let gas_remaining = cb.curr.state.gas_left.expr() - gas_cost.clone();
         let gas_left = ConstantDivisionGadget::construct(cb, gas_remaining.clone(), 64);
@@ -262,6 +269,14 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
         // So we don't need to put a `keccak_table_lookup` here since it is implicitly
         // done inside bytecode circuit.
         cb.condition(is_precheck_ok.clone(), |cb| {
+            // keccak table lookup to verify contract address, which is also needed to
+            // prove an address collision.
+            cb.keccak_table_lookup(
+                create.input_rlc(cb),
+                create.input_length(),
+                keccak_output.to_word(),
+            );
+
             cb.condition(init_code.has_length(), |cb| {
                 // the init code is being copied from memory to bytecode, so a copy table lookup
                 // to verify that the associated fields for the copy event.
@@ -313,13 +328,6 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
         let transfer = cb.condition(
             and::expr([is_precheck_ok.clone(), not_address_collision.expr()]),
             |cb| {
-                // keccak table lookup to verify contract address.
-                cb.keccak_table_lookup(
-                    create.input_rlc(cb),
-                    create.input_length(),
-                    keccak_output.to_word(),
-                );
-
                 // propagate is_persistent
                 cb.require_equal(
                     "callee_is_persistent == is_persistent ⋅ is_success",
@@ -347,6 +355,15 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
                     Some(&mut callee_reversion_info),
                 );
 
+                // EIP 6780, mark the newly created contract as created in this tx
+                cb.tx_created_account_write(
+                    tx_id.expr(),
+                    contract_addr.to_word(),
+                    1.expr(),
+                    0.expr(),
+                    Some(&mut callee_reversion_info),
+                );
+
                 cb.condition(init_code.has_length(), |cb| {
                     for (field_tag, value) in [
                         (
@@ -403,7 +420,7 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
                         code_hash: To(create.code_hash()),
                         gas_left: To(callee_gas_left),
                         reversible_write_counter: To(
-                            1.expr() + transfer.reversible_w_delta().expr()
+                            2.expr() + transfer.reversible_w_delta().expr()
                         ),
                         ..StepStateTransition::new_context()
                     })
@@ -428,7 +445,7 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
                         stack_pointer: Delta(2.expr() + is_create2.expr()),
                         gas_left: Delta(-gas_cost.expr()),
                         reversible_write_counter: Delta(
-                            3.expr() + transfer.reversible_w_delta().expr(),
+                            4.expr() + transfer.reversible_w_delta().expr(),
                         ),
                         ..Default::default()
                     })
@@ -450,33 +467,42 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
         );
 
         // Case3: Handle the case where an error of ErrContractAddressCollision occurred.
-        cb.condition(
-            and::expr([is_precheck_ok, not::expr(not_address_collision.expr())]),
-            |cb| {
-                // Save caller's call state
-                cb.call_context_lookup_write(
-                    None,
-                    CallContextFieldTag::LastCalleeId,
-                    Word::from_lo_unchecked(callee_call_id.expr()),
-                );
-                for field_tag in [
-                    CallContextFieldTag::LastCalleeReturnDataOffset,
-                    CallContextFieldTag::LastCalleeReturnDataLength,
-                ] {
-                    cb.call_context_lookup_write(None, field_tag, Word::zero());
-                }
+        let is_address_collision =
+            and::expr([is_precheck_ok, not::expr(not_address_collision.expr())]);
+        if S == ExecutionState::ErrorContractAddressCollision {
+            cb.require_true(
+                "ErrContractAddressCollision occurs",
+                is_address_collision.expr(),
+            );
+            cb.require_true(
+                "ErrContractAddressCollision only happens in CREATE2",
+                is_create2.expr(),
+            );
+        }
+        cb.condition(is_address_collision, |cb| {
+            // Save caller's call state
+            cb.call_context_lookup_write(
+                None,
+                CallContextFieldTag::LastCalleeId,
+                Word::from_lo_unchecked(callee_call_id.expr()),
+            );
+            for field_tag in [
+                CallContextFieldTag::LastCalleeReturnDataOffset,
+                CallContextFieldTag::LastCalleeReturnDataLength,
+            ] {
+                cb.call_context_lookup_write(None, field_tag, Word::zero());
+            }
 
-                cb.require_step_state_transition(StepStateTransition {
-                    rw_counter: Delta(cb.rw_counter_offset()),
-                    program_counter: Delta(1.expr()),
-                    stack_pointer: Delta(2.expr() + is_create2.expr()),
-                    reversible_write_counter: Delta(2.expr()),
-                    memory_word_size: To(memory_expansion.next_memory_word_size()),
-                    gas_left: To(gas_left.quotient()),
-                    ..StepStateTransition::default()
-                });
-            },
-        );
+            cb.require_step_state_transition(StepStateTransition {
+                rw_counter: Delta(cb.rw_counter_offset()),
+                program_counter: Delta(1.expr()),
+                stack_pointer: Delta(2.expr() + is_create2.expr()),
+                reversible_write_counter: Delta(2.expr()),
+                memory_word_size: To(memory_expansion.next_memory_word_size()),
+                gas_left: To(gas_left.quotient()),
+                ..StepStateTransition::default()
+            });
+        });
 
         Self {
             opcode,
@@ -485,6 +511,7 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
             was_warm,
             value,
             depth,
+            current_callee_address,
             callee_reversion_info,
             transfer,
             init_code,
@@ -492,6 +519,7 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
             memory_expansion,
             gas_left,
             init_code_word_size,
+            hardfork,
             create,
             caller_balance,
             is_depth_in_range,
@@ -542,6 +570,8 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
             rws.next().call_context_value().as_usize(),
             rws.next().call_context_value().as_usize() != 0,
         )?;
+        self.current_callee_address
+            .assign_u256(region, offset, rws.next().call_context_value())?;
         let [value, init_code_start, init_code_length] = [(); 3].map(|_| rws.next().stack_value());
         self.value.assign_u256(region, offset, value)?;
         let salt = if is_create2 {
@@ -616,12 +646,11 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
             offset,
             (31u64 + init_code_length.as_u64()).into(),
         )?;
+        self.hardfork
+            .assign(region, offset, block.context.hardfork)?;
         let initcode_gas_cost = u64::try_from(init_code_word_size).unwrap()
-            * if is_create2 {
-                INIT_CODE_WORD_GAS + GasCost::COPY_SHA3
-            } else {
-                INIT_CODE_WORD_GAS
-            };
+            * (if is_create2 { GasCost::COPY_SHA3 } else { 0 }
+                + block.context.hardfork.init_code_word_gas());
         let gas_left =
             step.gas_left - GasCost::CREATE - memory_expansion_gas_cost - initcode_gas_cost;
         self.gas_left.assign(region, offset, gas_left.into())?;
@@ -709,6 +738,7 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
                 F::ONE
             } else {
                 rws.next(); // callee nonce += 1
+                rws.next(); // callee created in tx
                 rws.next(); // caller id
                 let rw = rws.next();
                 debug_assert_eq!(rw.tag(), Target::CallContext);
@@ -4,14 +4,15 @@ use crate::{
         step::ExecutionState,
         util::{
             constraint_builder::{
-                ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition, Transition::Same,
+                ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
+                Transition::{Delta, Same},
             },
             math_gadget::{IsEqualGadget, IsZeroGadget},
             not, CachedRegion, Cell,
         },
         witness::{Block, Call, ExecStep, Transaction},
     },
-    table::{CallContextFieldTag, TxContextFieldTag},
+    table::{BlockContextFieldTag, CallContextFieldTag, TxContextFieldTag},
     util::{word::Word, Expr},
 };
 use eth_types::Field;
@@ -22,9 +23,13 @@ use halo2_proofs::{circuit::Value, plonk::Error};
 pub(crate) struct EndBlockGadget<F> {
     total_txs: Cell<F>,
     total_txs_is_max_txs: IsEqualGadget<F>,
-    is_empty_block: IsZeroGadget<F>,
+    total_txs_is_zero: IsZeroGadget<F>,
+    total_wds_is_max_wds: IsEqualGadget<F>,
     max_rws: Cell<F>,
     max_txs: Cell<F>,
+    max_wds: Cell<F>,
+    is_chunk_end: Cell<F>,
+    next_block_number: Cell<F>,
 }
 
 impl<F: Field> ExecutionGadget<F> for EndBlockGadget<F> {
@@ -35,27 +40,26 @@ impl<F: Field> ExecutionGadget<F> for EndBlockGadget<F> {
     fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
         let max_txs = cb.query_copy_cell();
         let max_rws = cb.query_copy_cell();
+        let max_wds = cb.query_copy_cell();
         let total_txs = cb.query_cell();
         let total_txs_is_max_txs = IsEqualGadget::construct(cb, total_txs.expr(), max_txs.expr());
-        // Note that rw_counter starts at 1
-        let is_empty_block =
-            IsZeroGadget::construct(cb, cb.curr.state.rw_counter.clone().expr() - 1.expr());
+        let total_txs_is_zero = IsZeroGadget::construct(cb, total_txs.expr());
+        // The program counter holds the number of processed withdrawals
+        let total_wds = cb.curr.state.program_counter.clone();
+        let total_wds_is_max_wds = IsEqualGadget::construct(cb, total_wds.expr(), max_wds.expr());
 
+        // Note that rw_counter starts at 1
         let total_rws_before_padding = cb.curr.state.rw_counter.clone().expr() - 1.expr()
             + select::expr(
-                is_empty_block.expr(),
+                total_txs_is_zero.expr(),
                 0.expr(),
-                1.expr(), // If the block is not empty, we will do 1 call_context lookup below
+                1.expr(), // If there are txs, we will do 1 call_context lookup below
             );
 
-        // 1. Constraint total_rws and total_txs witness values depending on the empty
-        // block case.
-        cb.condition(is_empty_block.expr(), |cb| {
-            // 1a.
-            cb.require_equal("total_txs is 0 in empty block", total_txs.expr(), 0.expr());
-        });
-        cb.condition(not::expr(is_empty_block.expr()), |cb| {
-            // 1b. total_txs matches the tx_id that corresponds to the final step.
+        // 1. Constraint total_txs witness value depending on the block having txs.  A
+        // block without txs can still have rws from its withdrawals.
+        cb.condition(not::expr(total_txs_is_zero.expr()), |cb| {
+            // 1a. total_txs matches the tx_id that corresponds to the final step.
             cb.call_context_lookup_read(
                 None,
                 CallContextFieldTag::TxId,
@@ -63,56 +67,154 @@ impl<F: Field> ExecutionGadget<F> for EndBlockGadget<F> {
             );
         });
 
-        // 2. If total_txs == max_txs, we know we have covered all txs from the
-        // tx_table. If not, we need to check that the rest of txs in the
-        // table are padding.
-        cb.condition(not::expr(total_txs_is_max_txs.expr()), |cb| {
-            // Verify that there are at most total_txs meaningful txs in the tx_table, by
-            // showing that the Tx following the last processed one has
-            // CallerAddress = 0x0 (which means padding tx).
-            cb.tx_context_lookup(
-                total_txs.expr() + 1.expr(),
-                TxContextFieldTag::CallerAddress,
-                None,
-                Word::zero(),
+        // An EndBlock step either ends the chunk, or ends a block of the chunk
+        // that is followed by another block.  Only the former checks the
+        // padding of the tables and ends the EVM circuit.
+        let is_chunk_end = cb.query_bool();
+        cb.step_last(|cb| {
+            cb.require_equal(
+                "last EndBlock ends the chunk",
+                is_chunk_end.expr(),
+                1.expr(),
             );
-            // Since every tx lookup done in the EVM circuit must succeed
-            // and uses a unique tx_id, we know that at
-            // least there are total_tx meaningful txs in
-            // the tx_table. We conclude that the number of
-            // meaningful txs in the tx_table is total_tx.
         });
 
-        // 3. Verify rw_counter counts to the same number of meaningful rows in
-        // rw_table to ensure there is no malicious insertion.
-        // Verify that there are at most total_rws meaningful entries in the rw_table
-        cb.rw_table_start_lookup(1.expr());
-        cb.rw_table_start_lookup(max_rws.expr() - total_rws_before_padding.expr());
-        // Since every lookup done in the EVM circuit must succeed and uses
-        // a unique rw_counter, we know that at least there are
-        // total_rws meaningful entries in the rw_table.
-        // We conclude that the number of meaningful entries in the rw_table
-        // is total_rws.
+        cb.step_first(|cb| {
+            cb.require_zero("no withdrawals processed in first step", total_wds.expr());
+        });
+        cb.condition(is_chunk_end.expr(), |cb| {
+            // 2. If total_txs == max_txs, we know we have covered all txs from the
+            // tx_table. If not, we need to check that the rest of txs in the
+            // table are padding.
+            cb.condition(not::expr(total_txs_is_max_txs.expr()), |cb| {
+                // Verify that there are at most total_txs meaningful txs in the tx_table, by
+                // showing that the Tx following the last processed one has
+                // CallerAddress = 0x0 (which means padding tx).
+                cb.tx_context_lookup(
+                    total_txs.expr() + 1.expr(),
+                    TxContextFieldTag::CallerAddress,
+                    None,
+                    Word::zero(),
+                );
+                // Since every tx lookup done in the EVM circuit must succeed
+                // and uses a unique tx_id, we know that at
+                // least there are total_tx meaningful txs in
+                // the tx_table. We conclude that the number of
+                // meaningful txs in the tx_table is total_tx.
+            });
+
+            // 3. Same as above for the withdrawals: if total_wds != max_wds, verify that the
+            // withdrawal following the last processed one is padding.
+            cb.condition(not::expr(total_wds_is_max_wds.expr()), |cb| {
+                cb.wd_table_lookup(
+                    total_wds.expr() + 1.expr(),
+                    0.expr(),
+                    0.expr(),
+                    Word::zero(),
+                    0.expr(),
+                );
+            });
+
+            // 4. Verify rw_counter counts to the same number of meaningful rows in
+            // rw_table to ensure there is no malicious insertion.
+            // Verify that there are at most total_rws meaningful entries in the rw_table
+            cb.rw_table_start_lookup(1.expr());
+            cb.rw_table_start_lookup(max_rws.expr() - total_rws_before_padding.expr());
+            // Since every lookup done in the EVM circuit must succeed and uses
+            // a unique rw_counter, we know that at least there are
+            // total_rws meaningful entries in the rw_table.
+            // We conclude that the number of meaningful entries in the rw_table
+            // is total_rws.
+        });
 
         // cb.step_last(|cb| {
         //     // TODO: Handle reward to coinbase.  Depends on spec:
         //     // https://github.com/privacy-scaling-explorations/zkevm-specs/issues/290
         // });
         cb.not_step_last(|cb| {
-            // Propagate rw_counter and call_id all the way down.
-            cb.require_step_state_transition(StepStateTransition {
-                rw_counter: Same,
-                call_id: Same,
-                ..StepStateTransition::any()
+            cb.condition(is_chunk_end.expr(), |cb| {
+                // Propagate rw_counter, call_id and program_counter all the way down.
+                cb.require_step_state_transition(StepStateTransition {
+                    rw_counter: Same,
+                    call_id: Same,
+                    program_counter: Same,
+                    ..StepStateTransition::any()
+                });
             });
         });
 
+        // 5. Between two blocks of the chunk, the next block starts with its
+        // first tx, its first withdrawal or its own EndBlock.
+        let rw_counter_offset = 1.expr() - total_txs_is_zero.expr();
+        let next_block_number = cb.query_cell();
+        cb.condition(
+            not::expr(is_chunk_end.expr())
+                * cb.next
+                    .execution_state_selector([ExecutionState::BeginTx, ExecutionState::InvalidTx]),
+            |cb| {
+                let next_step_rwc = cb.next.state.rw_counter.expr();
+                // lookup use next step initial rwc, thus lead to same record on rw table
+                cb.call_context_lookup_write_with_counter(
+                    next_step_rwc.clone(),
+                    Some(next_step_rwc),
+                    CallContextFieldTag::TxId,
+                    Word::from_lo_unchecked(total_txs.expr() + 1.expr()),
+                );
+                // All the withdrawals of the previous blocks have been
+                // processed before the first tx of the next block.
+                cb.tx_context_lookup(
+                    total_txs.expr() + 1.expr(),
+                    TxContextFieldTag::BlockNumber,
+                    None,
+                    Word::from_lo_unchecked(next_block_number.expr()),
+                );
+                cb.block_lookup(
+                    BlockContextFieldTag::WithdrawalOffset.expr(),
+                    Some(next_block_number.expr()),
+                    Word::from_lo_unchecked(total_wds.expr()),
+                );
+                cb.require_step_state_transition(StepStateTransition {
+                    rw_counter: Delta(rw_counter_offset.clone()),
+                    ..StepStateTransition::any()
+                });
+            },
+        );
+        cb.condition(
+            not::expr(is_chunk_end.expr())
+                * cb.next
+                    .execution_state_selector([ExecutionState::Withdrawal]),
+            |cb| {
+                cb.require_step_state_transition(StepStateTransition {
+                    rw_counter: Delta(rw_counter_offset.clone()),
+                    call_id: Same,
+                    program_counter: Delta(1.expr()),
+                    ..StepStateTransition::any()
+                });
+            },
+        );
+        cb.condition(
+            not::expr(is_chunk_end.expr())
+                * cb.next.execution_state_selector([ExecutionState::EndBlock]),
+            |cb| {
+                cb.require_step_state_transition(StepStateTransition {
+                    rw_counter: Delta(rw_counter_offset.clone()),
+                    call_id: Same,
+                    program_counter: Same,
+                    ..StepStateTransition::any()
+                });
+            },
+        );
+
         Self {
             max_txs,
             max_rws,
+            max_wds,
             total_txs,
             total_txs_is_max_txs,
-            is_empty_block,
+            total_txs_is_zero,
+            total_wds_is_max_wds,
+            is_chunk_end,
+            next_block_number,
         }
     }
 
@@ -125,23 +227,37 @@ impl<F: Field> ExecutionGadget<F> for EndBlockGadget<F> {
         _: &Call,
         step: &ExecStep,
     ) -> Result<(), Error> {
-        self.is_empty_block
-            .assign(region, offset, F::from(u64::from(step.rwc) - 1))?;
         let max_rws = F::from(block.circuits_params.max_rws as u64);
         let max_rws_assigned = self.max_rws.assign(region, offset, Value::known(max_rws))?;
 
-        let total_txs = F::from(block.txs.len() as u64);
+        let is_chunk_end = block.is_chunk_end(step);
+        self.is_chunk_end
+            .assign(region, offset, Value::known(F::from(is_chunk_end as u64)))?;
+        let total_txs = block.txs_before_end_block(step);
+        let next_block_number = block.txs.get(total_txs).map_or(0, |tx| tx.block_num);
+        self.next_block_number
+            .assign(region, offset, Value::known(F::from(next_block_number)))?;
+        let total_txs = F::from(total_txs as u64);
         let max_txs = F::from(block.circuits_params.max_txs as u64);
         self.total_txs
             .assign(region, offset, Value::known(total_txs))?;
         self.total_txs_is_max_txs
             .assign(region, offset, total_txs, max_txs)?;
+        self.total_txs_is_zero.assign(region, offset, total_txs)?;
         let max_txs_assigned = self.max_txs.assign(region, offset, Value::known(max_txs))?;
-        // When rw_indices is not empty, we're at the last row (at a fixed offset),
-        // where we need to access the max_rws and max_txs constant.
-        if step.rw_indices_len() != 0 {
+
+        let total_wds = F::from(step.pc);
+        let max_wds = F::from(block.circuits_params.max_withdrawals as u64);
+        self.total_wds_is_max_wds
+            .assign(region, offset, total_wds, max_wds)?;
+        let max_wds_assigned = self.max_wds.assign(region, offset, Value::known(max_wds))?;
+        // When rw_indices is not empty at the end of the chunk, we're at the last
+        // row (at a fixed offset), where we need to access the max_rws, max_txs and
+        // max_wds constant.
+        if is_chunk_end && step.rw_indices_len() != 0 {
             region.constrain_constant(max_rws_assigned, max_rws)?;
             region.constrain_constant(max_txs_assigned, max_txs)?;
+            region.constrain_constant(max_wds_assigned, max_wds)?;
         }
         Ok(())
     }
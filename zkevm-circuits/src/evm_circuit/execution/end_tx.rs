@@ -4,12 +4,13 @@ use crate::{
         param::N_BYTES_GAS,
         step::ExecutionState,
         util::{
-            common_gadget::{TransferToGadget, UpdateBalanceGadget},
-            constraint_builder::EVMConstraintBuilder,
+            common_gadget::{HardforkGadget, TransferToGadget, UpdateBalanceGadget},
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
             math_gadget::{
                 AddWordsGadget, ConstantDivisionGadget, IsZeroWordGadget, MinMaxGadget,
                 MulWordByU64Gadget,
             },
+            select,
             tx::EndTxHelperGadget,
             CachedRegion, Cell,
         },
@@ -21,14 +22,18 @@ use crate::{
         Expr,
     },
 };
-use eth_types::{evm_types::MAX_REFUND_QUOTIENT_OF_GAS_USED, Field};
+use eth_types::{evm_types::Hardfork, Field};
 use halo2_proofs::{circuit::Value, plonk::Error};
 
 #[derive(Clone, Debug)]
 pub(crate) struct EndTxGadget<F> {
     tx_id: Cell<F>,
+    block_number: Cell<F>,
     tx_gas: Cell<F>,
-    max_refund: ConstantDivisionGadget<F, N_BYTES_GAS>,
+    hardfork: HardforkGadget<F>,
+    max_refund_london: ConstantDivisionGadget<F, N_BYTES_GAS>,
+    max_refund_berlin: ConstantDivisionGadget<F, N_BYTES_GAS>,
+    max_refund: Cell<F>,
     refund: Cell<F>,
     effective_refund: MinMaxGadget<F, N_BYTES_GAS>,
     mul_gas_price_by_refund: MulWordByU64Gadget<F>,
@@ -52,24 +57,41 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
     fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
         let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
         let is_persistent = cb.call_context(None, CallContextFieldTag::IsPersistent);
+        let block_number = cb.tx_context(tx_id.expr(), TxContextFieldTag::BlockNumber, None);
 
         let tx_gas = cb.tx_context(tx_id.expr(), TxContextFieldTag::Gas, None);
         let tx_caller_address =
             cb.tx_context_as_word(tx_id.expr(), TxContextFieldTag::CallerAddress, None);
         let tx_gas_price = cb.tx_context_as_word32(tx_id.expr(), TxContextFieldTag::GasPrice, None);
 
-        // Calculate effective gas to refund
+        // Calculate effective gas to refund, whose cap was reduced by EIP-3529
+        // (London).
         let gas_used = tx_gas.expr() - cb.curr.state.gas_left.expr();
-        let max_refund = ConstantDivisionGadget::construct(
-            cb,
-            gas_used.clone(),
-            MAX_REFUND_QUOTIENT_OF_GAS_USED as u64,
+        let hardfork = HardforkGadget::construct(cb);
+        let [max_refund_london, max_refund_berlin] =
+            [Hardfork::London, Hardfork::Berlin].map(|hardfork| {
+                ConstantDivisionGadget::construct(
+                    cb,
+                    gas_used.clone(),
+                    hardfork.max_refund_quotient_of_gas_used(),
+                )
+            });
+        let max_refund = cb.query_cell();
+        cb.require_equal(
+            "max_refund == gas_used / max_refund_quotient_of_gas_used",
+            max_refund.expr(),
+            select::expr(
+                hardfork.is_active(Hardfork::London),
+                max_refund_london.quotient(),
+                max_refund_berlin.quotient(),
+            ),
         );
         let refund = cb.query_cell();
         cb.tx_refund_read(tx_id.expr(), Word::from_lo_unchecked(refund.expr()));
-        let effective_refund = MinMaxGadget::construct(cb, max_refund.quotient(), refund.expr());
+        let effective_refund = MinMaxGadget::construct(cb, max_refund.expr(), refund.expr());
 
-        // Add effective_refund * tx_gas_price back to caller's balance
+        // Add effective_refund * tx_gas_price back to caller's balance. The blob gas fee of
+        // EIP-4844 is burned, so it's never refunded.
         let mul_gas_price_by_refund = MulWordByU64Gadget::construct(
             cb,
             tx_gas_price.clone(),
@@ -97,7 +119,7 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
             (BlockContextFieldTag::Coinbase, coinbase.to_word()),
             (BlockContextFieldTag::BaseFee, base_fee.to_word()),
         ] {
-            cb.block_lookup(tag.expr(), None, value);
+            cb.block_lookup(tag.expr(), Some(block_number.expr()), value);
         }
         let effective_tip = cb.query_word32();
         let sub_gas_price_by_base_fee =
@@ -117,6 +139,7 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
         let end_tx = EndTxHelperGadget::construct(
             cb,
             tx_id.expr(),
+            block_number.expr(),
             is_persistent.expr(),
             gas_used,
             10.expr() + coinbase_reward.rw_delta(),
@@ -124,7 +147,11 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
 
         Self {
             tx_id,
+            block_number,
             tx_gas,
+            hardfork,
+            max_refund_london,
+            max_refund_berlin,
             max_refund,
             refund,
             effective_refund,
@@ -158,18 +185,24 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
 
         self.tx_id
             .assign(region, offset, Value::known(F::from(tx.id)))?;
+        self.block_number
+            .assign(region, offset, Value::known(F::from(tx.block_num)))?;
         self.tx_gas
             .assign(region, offset, Value::known(F::from(tx.gas())))?;
-        let (max_refund, _) = self.max_refund.assign(region, offset, gas_used as u128)?;
+        self.hardfork
+            .assign(region, offset, block.context.hardfork)?;
+        self.max_refund_london
+            .assign(region, offset, gas_used as u128)?;
+        self.max_refund_berlin
+            .assign(region, offset, gas_used as u128)?;
+        let max_refund = gas_used / block.context.hardfork.max_refund_quotient_of_gas_used();
+        self.max_refund
+            .assign(region, offset, Value::known(F::from(max_refund)))?;
         self.refund
             .assign(region, offset, Value::known(F::from(refund)))?;
-        self.effective_refund.assign(
-            region,
-            offset,
-            F::from(max_refund as u64),
-            F::from(refund),
-        )?;
-        let effective_refund = refund.min(max_refund as u64);
+        self.effective_refund
+            .assign(region, offset, F::from(max_refund), F::from(refund))?;
+        let effective_refund = refund.min(max_refund);
         let gas_fee_refund = tx.gas_price * (effective_refund + step.gas_left);
         self.mul_gas_price_by_refund.assign(
             region,
@@ -187,12 +220,13 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
             vec![gas_fee_refund],
             caller_balance,
         )?;
-        let effective_tip = tx.gas_price - block.context.base_fee;
+        let context = block.tx_context(tx);
+        let effective_tip = tx.gas_price - context.base_fee;
         let coinbase_reward = effective_tip * gas_used;
         self.sub_gas_price_by_base_fee.assign(
             region,
             offset,
-            [effective_tip, block.context.base_fee],
+            [effective_tip, context.base_fee],
             tx.gas_price,
         )?;
         self.mul_effective_tip_by_gas_used.assign(
@@ -203,7 +237,7 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
             coinbase_reward,
         )?;
         self.coinbase
-            .assign_h160(region, offset, block.context.coinbase)?;
+            .assign_h160(region, offset, context.coinbase)?;
         self.coinbase_code_hash
             .assign_u256(region, offset, coinbase_code_hash_prev)?;
         self.coinbase_code_hash_is_zero
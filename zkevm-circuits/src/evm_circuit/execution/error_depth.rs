@@ -0,0 +1,163 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        step::ExecutionState,
+        util::{
+            common_gadget::{CallPrecheckFailureGadget, CreatePrecheckFailureGadget},
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::IsZeroGadget,
+            not, CachedRegion, Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    util::Expr,
+};
+use eth_types::{evm_types::OpcodeId, Field};
+use gadgets::util::select;
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget for CALL, CALLCODE, DELEGATECALL, STATICCALL, CREATE and CREATE2
+/// when the call depth limit of 1024 is exceeded. Unlike other errors the
+/// current call is not halted: zero is pushed to the stack and the execution
+/// continues in the caller.
+#[derive(Clone, Debug)]
+pub(crate) struct ErrorDepthGadget<F> {
+    opcode: Cell<F>,
+    is_create: IsZeroGadget<F>,
+    is_create2: IsZeroGadget<F>,
+    call: CallPrecheckFailureGadget<F>,
+    create: CreatePrecheckFailureGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for ErrorDepthGadget<F> {
+    const NAME: &'static str = "ErrorDepth";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::ErrorDepth;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+        cb.opcode_lookup(opcode.expr(), 1.expr());
+        let is_create = IsZeroGadget::construct(cb, opcode.expr() - OpcodeId::CREATE.expr());
+        let is_create2 = IsZeroGadget::construct(cb, opcode.expr() - OpcodeId::CREATE2.expr());
+        let is_create_op = is_create.expr() + is_create2.expr();
+
+        // The opcode is constrained to be one of the call opcodes by
+        // `CommonCallGadget` in the call case.
+        let call = cb.condition(not::expr(is_create_op.expr()), |cb| {
+            CallPrecheckFailureGadget::construct(cb, opcode.expr())
+        });
+        let create = cb.condition(is_create_op.expr(), |cb| {
+            CreatePrecheckFailureGadget::construct(cb, is_create2.expr())
+        });
+
+        cb.require_equal(
+            "ErrDepth occurs at call depth 1025",
+            select::expr(is_create_op, create.depth(), call.depth()),
+            1025.expr(),
+        );
+
+        Self {
+            opcode,
+            is_create,
+            is_create2,
+            call,
+            create,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let opcode = step.opcode().unwrap();
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
+        self.is_create.assign(
+            region,
+            offset,
+            F::from(opcode.as_u64()) - F::from(OpcodeId::CREATE.as_u64()),
+        )?;
+        self.is_create2.assign(
+            region,
+            offset,
+            F::from(opcode.as_u64()) - F::from(OpcodeId::CREATE2.as_u64()),
+        )?;
+
+        if opcode.is_create() {
+            self.create.assign(region, offset, block, call, step)?;
+        } else {
+            self.call.assign(region, offset, block, call, step)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use bus_mapping::circuit_input_builder::FixedCParams;
+    use eth_types::{bytecode, evm_types::OpcodeId, word};
+    use mock::{test_ctx::helpers::account_0_code_account_1_no_code, TestContext};
+
+    fn test_recursive_call(opcode: OpcodeId) {
+        // Recurse into itself until the call depth limit is hit, then unwind.
+        let mut code = bytecode! {
+            PUSH1(0x00)
+            PUSH1(0x00)
+            PUSH1(0x00)
+            PUSH1(0x00)
+        };
+        if opcode == OpcodeId::CALL || opcode == OpcodeId::CALLCODE {
+            code.push(1, 0x00);
+        }
+        code.append(&bytecode! {
+            ADDRESS
+            PUSH2(0xffff)
+            GAS
+            SUB
+        });
+        code.write_op(opcode);
+        code.append(&bytecode! {
+            PUSH1(0x01)
+            SUB
+        });
+
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            |mut txs, accs| {
+                txs[0]
+                    .to(accs[0].address)
+                    .from(accs[1].address)
+                    .gas(word!("0x2386F26FC10000"));
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(FixedCParams {
+                max_rws: 300000,
+                ..Default::default()
+            })
+            .run();
+    }
+
+    #[test]
+    fn test_error_depth_delegatecall() {
+        test_recursive_call(OpcodeId::DELEGATECALL);
+    }
+
+    // Ignore this test case. It could run successfully but slow for CI.
+    #[ignore]
+    #[test]
+    fn test_error_depth_staticcall() {
+        test_recursive_call(OpcodeId::STATICCALL);
+    }
+}
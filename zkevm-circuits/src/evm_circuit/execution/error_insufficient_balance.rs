@@ -0,0 +1,191 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::N_BYTES_U64,
+        step::ExecutionState,
+        util::{
+            common_gadget::{CallPrecheckFailureGadget, CreatePrecheckFailureGadget},
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::{IsZeroGadget, LtGadget, LtWordGadget},
+            not, CachedRegion, Cell, Word,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    util::Expr,
+};
+use eth_types::{evm_types::OpcodeId, Field};
+use gadgets::util::select;
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget for CALL, CALLCODE, CREATE and CREATE2 when the caller balance is
+/// lower than the value to transfer. Unlike other errors the current call is
+/// not halted: zero is pushed to the stack and the execution continues in the
+/// caller.
+#[derive(Clone, Debug)]
+pub(crate) struct ErrorInsufficientBalanceGadget<F> {
+    opcode: Cell<F>,
+    is_create: IsZeroGadget<F>,
+    is_create2: IsZeroGadget<F>,
+    call: CallPrecheckFailureGadget<F>,
+    create: CreatePrecheckFailureGadget<F>,
+    is_depth_ok: LtGadget<F, N_BYTES_U64>,
+    is_insufficient_balance: LtWordGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for ErrorInsufficientBalanceGadget<F> {
+    const NAME: &'static str = "ErrorInsufficientBalance";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::ErrorInsufficientBalance;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+        cb.opcode_lookup(opcode.expr(), 1.expr());
+        let is_create = IsZeroGadget::construct(cb, opcode.expr() - OpcodeId::CREATE.expr());
+        let is_create2 = IsZeroGadget::construct(cb, opcode.expr() - OpcodeId::CREATE2.expr());
+        let is_create_op = is_create.expr() + is_create2.expr();
+
+        // The opcode is constrained to be one of the call opcodes by
+        // `CommonCallGadget` in the call case. DELEGATECALL and STATICCALL never
+        // transfer value, so the balance check below can't hold for them.
+        let call = cb.condition(not::expr(is_create_op.expr()), |cb| {
+            CallPrecheckFailureGadget::construct(cb, opcode.expr())
+        });
+        let create = cb.condition(is_create_op.expr(), |cb| {
+            CreatePrecheckFailureGadget::construct(cb, is_create2.expr())
+        });
+
+        // ErrDepth is checked before ErrInsufficientBalance.
+        let is_depth_ok = LtGadget::construct(
+            cb,
+            select::expr(is_create_op.expr(), create.depth(), call.depth()),
+            1025.expr(),
+        );
+        cb.require_true("call depth is within the limit", is_depth_ok.expr());
+
+        let is_insufficient_balance = LtWordGadget::construct(
+            cb,
+            &Word::select(
+                is_create_op.expr(),
+                create.caller_balance(),
+                call.caller_balance(),
+            ),
+            &Word::select(is_create_op, create.value(), call.value()),
+        );
+        cb.require_true(
+            "caller balance is lower than the value",
+            is_insufficient_balance.expr(),
+        );
+
+        Self {
+            opcode,
+            is_create,
+            is_create2,
+            call,
+            create,
+            is_depth_ok,
+            is_insufficient_balance,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let opcode = step.opcode().unwrap();
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
+        self.is_create.assign(
+            region,
+            offset,
+            F::from(opcode.as_u64()) - F::from(OpcodeId::CREATE.as_u64()),
+        )?;
+        self.is_create2.assign(
+            region,
+            offset,
+            F::from(opcode.as_u64()) - F::from(OpcodeId::CREATE2.as_u64()),
+        )?;
+
+        let (depth, caller_balance, value) = if opcode.is_create() {
+            self.create.assign(region, offset, block, call, step)?
+        } else {
+            self.call.assign(region, offset, block, call, step)?
+        };
+        self.is_depth_ok
+            .assign(region, offset, F::from(depth), F::from(1025))?;
+        self.is_insufficient_balance
+            .assign(region, offset, caller_balance, value)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::{bytecode, evm_types::OpcodeId, Bytecode, Word};
+    use mock::{eth, TestContext, MOCK_ACCOUNTS};
+
+    fn test_ok(caller_code: Bytecode, caller_balance: Word) {
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(caller_balance)
+                    .code(caller_code);
+                accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[1].address).to(accs[0].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn test_error_insufficient_balance_call() {
+        for opcode in [OpcodeId::CALL, OpcodeId::CALLCODE] {
+            let mut code = bytecode! {
+                PUSH1(0x00) // retLength
+                PUSH1(0x00) // retOffset
+                PUSH1(0x00) // argsLength
+                PUSH1(0x00) // argsOffset
+                PUSH2(0x1000) // value
+                PUSH20(MOCK_ACCOUNTS[1]) // address
+                PUSH2(0xffff) // gas
+            };
+            code.write_op(opcode);
+            code.op_stop();
+
+            test_ok(code, Word::from(0x0fff));
+        }
+    }
+
+    #[test]
+    fn test_error_insufficient_balance_create() {
+        for opcode in [OpcodeId::CREATE, OpcodeId::CREATE2] {
+            let mut code = if opcode == OpcodeId::CREATE2 {
+                bytecode! { PUSH1(0x45) } // salt
+            } else {
+                bytecode! {}
+            };
+            code.append(&bytecode! {
+                PUSH1(0x00) // length
+                PUSH1(0x00) // offset
+                PUSH2(0x1000) // value
+            });
+            code.write_op(opcode);
+            code.op_stop();
+
+            test_ok(code, Word::from(0x0fff));
+        }
+    }
+}
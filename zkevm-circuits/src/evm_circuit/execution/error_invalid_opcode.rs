@@ -3,8 +3,9 @@ use crate::evm_circuit::{
     step::ExecutionState,
     table::{FixedTableTag, Lookup},
     util::{
-        common_gadget::CommonErrorGadget, constraint_builder::EVMConstraintBuilder, CachedRegion,
-        Cell,
+        common_gadget::{CommonErrorGadget, HardforkGadget},
+        constraint_builder::EVMConstraintBuilder,
+        CachedRegion, Cell,
     },
     witness::{Block, Call, ExecStep, Transaction},
 };
@@ -13,10 +14,11 @@ use gadgets::util::Expr;
 use halo2_proofs::{circuit::Value, plonk::Error};
 
 /// Gadget for invalid opcodes. It verifies by a fixed lookup for
-/// ResponsibleOpcode.
+/// ResponsibleOpcode that the opcode is undefined in the hardfork of the block.
 #[derive(Clone, Debug)]
 pub(crate) struct ErrorInvalidOpcodeGadget<F> {
     opcode: Cell<F>,
+    hardfork: HardforkGadget<F>,
     common_error_gadget: CommonErrorGadget<F>,
 }
 
@@ -27,6 +29,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorInvalidOpcodeGadget<F> {
 
     fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
         let opcode = cb.query_cell();
+        let hardfork = HardforkGadget::construct(cb);
         cb.add_lookup(
             "Responsible opcode lookup",
             Lookup::Fixed {
@@ -34,7 +37,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorInvalidOpcodeGadget<F> {
                 values: [
                     Self::EXECUTION_STATE.as_u64().expr(),
                     opcode.expr(),
-                    0.expr(),
+                    hardfork.expr(),
                 ],
             },
         );
@@ -43,6 +46,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorInvalidOpcodeGadget<F> {
 
         Self {
             opcode,
+            hardfork,
             common_error_gadget,
         }
     }
@@ -58,6 +62,8 @@ impl<F: Field> ExecutionGadget<F> for ErrorInvalidOpcodeGadget<F> {
     ) -> Result<(), Error> {
         let opcode = F::from(step.opcode().unwrap().as_u64());
         self.opcode.assign(region, offset, Value::known(opcode))?;
+        self.hardfork
+            .assign(region, offset, block.context.hardfork)?;
 
         self.common_error_gadget
             .assign(region, offset, block, call, step, 2)?;
@@ -68,9 +74,13 @@ impl<F: Field> ExecutionGadget<F> for ErrorInvalidOpcodeGadget<F> {
 #[cfg(test)]
 mod test {
     use crate::{evm_circuit::test::rand_bytes, test_util::CircuitTestBuilder};
-    use eth_types::{bytecode::Bytecode, Word};
+    use eth_types::{bytecode::Bytecode, evm_types::Hardfork, Word};
     use lazy_static::lazy_static;
-    use mock::{generate_mock_call_bytecode, MockCallBytecodeParams, TestContext};
+    use mock::{
+        generate_mock_call_bytecode,
+        test_ctx::helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
+        MockCallBytecodeParams, TestContext,
+    };
 
     lazy_static! {
         static ref TESTING_INVALID_CODES: [Vec<u8>; 6] = [
@@ -81,37 +91,74 @@ mod test {
             vec![0xf6],
             vec![0xfe],
             // Multiple invalid opcodes
-            vec![0x5c, 0x5e],
+            vec![0x0c, 0x0d],
+        ];
+        // Opcodes which are only undefined before the hardfork introducing them
+        static ref TESTING_HARDFORK_INVALID_CODES: [(Vec<u8>, Hardfork); 5] = [
+            // TLOAD, MCOPY
+            (vec![0x5c, 0x5e], Hardfork::Shanghai),
+            // BLOBHASH
+            (vec![0x49], Hardfork::Shanghai),
+            // PUSH0
+            (vec![0x5f], Hardfork::London),
+            // BASEFEE
+            (vec![0x48], Hardfork::Berlin),
+            // Undefined in every hardfork
+            (vec![0x0e], Hardfork::Berlin),
         ];
     }
 
     #[test]
     fn invalid_opcode_root() {
         for invalid_code in TESTING_INVALID_CODES.iter() {
-            test_root_ok(invalid_code);
+            test_root_ok(invalid_code, Hardfork::default());
         }
     }
 
     #[test]
     fn invalid_opcode_internal() {
         for invalid_code in TESTING_INVALID_CODES.iter() {
-            test_internal_ok(0x20, 0x00, invalid_code);
+            test_internal_ok(0x20, 0x00, invalid_code, Hardfork::default());
         }
     }
 
-    fn test_root_ok(invalid_code: &[u8]) {
+    #[test]
+    fn invalid_opcode_by_hardfork_root() {
+        for (invalid_code, hardfork) in TESTING_HARDFORK_INVALID_CODES.iter() {
+            test_root_ok(invalid_code, *hardfork);
+        }
+    }
+
+    #[test]
+    fn invalid_opcode_by_hardfork_internal() {
+        for (invalid_code, hardfork) in TESTING_HARDFORK_INVALID_CODES.iter() {
+            test_internal_ok(0x20, 0x00, invalid_code, *hardfork);
+        }
+    }
+
+    fn test_root_ok(invalid_code: &[u8], hardfork: Hardfork) {
         let mut code = Bytecode::default();
         invalid_code.iter().for_each(|b| {
             code.write(*b, true);
         });
 
-        CircuitTestBuilder::new_from_test_ctx(
-            TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap(),
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block.hardfork(hardfork),
         )
-        .run();
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
     }
 
-    fn test_internal_ok(call_data_offset: usize, call_data_length: usize, invalid_code: &[u8]) {
+    fn test_internal_ok(
+        call_data_offset: usize,
+        call_data_length: usize,
+        invalid_code: &[u8],
+        hardfork: Hardfork,
+    ) {
         let (addr_a, addr_b) = (mock::MOCK_ACCOUNTS[0], mock::MOCK_ACCOUNTS[1]);
 
         // Code B gets called by code A, so the call is an internal call.
@@ -141,7 +188,7 @@ mod test {
             |mut txs, accs| {
                 txs[0].to(accs[1].address).from(accs[2].address);
             },
-            |block, _tx| block,
+            |block, _tx| block.hardfork(hardfork),
         )
         .unwrap();
 
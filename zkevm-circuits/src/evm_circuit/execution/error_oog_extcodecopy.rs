@@ -0,0 +1,306 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{N_BYTES_GAS, N_BYTES_MEMORY_WORD_SIZE},
+        step::ExecutionState,
+        util::{
+            common_gadget::CommonErrorGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::LtGadget,
+            memory_gadget::{
+                CommonMemoryAddressGadget, MemoryCopierGasGadget, MemoryExpandedAddressGadget,
+                MemoryExpansionGadget,
+            },
+            or, select, AccountAddress, CachedRegion, Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::CallContextFieldTag,
+    util::{
+        word::{WordCell, WordExpr},
+        Expr,
+    },
+};
+use eth_types::{
+    evm_types::{GasCost, OpcodeId},
+    Field, ToAddress,
+};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget to implement the corresponding out of gas errors for
+/// [`OpcodeId::EXTCODECOPY`].
+#[derive(Clone, Debug)]
+pub(crate) struct ErrorOOGExtCodeCopyGadget<F> {
+    opcode: Cell<F>,
+    external_address: AccountAddress<F>,
+    tx_id: Cell<F>,
+    is_warm: Cell<F>,
+    /// Source offset in the external code
+    code_offset: WordCell<F>,
+    /// Destination offset and size to copy
+    memory_address: MemoryExpandedAddressGadget<F>,
+    memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
+    memory_copier_gas: MemoryCopierGasGadget<F, { GasCost::COPY }>,
+    insufficient_gas: LtGadget<F, N_BYTES_GAS>,
+    common_error_gadget: CommonErrorGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for ErrorOOGExtCodeCopyGadget<F> {
+    const NAME: &'static str = "ErrorOutOfGasEXTCODECOPY";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::ErrorOutOfGasEXTCODECOPY;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+        cb.require_equal(
+            "ErrorOutOfGasEXTCODECOPY opcode must be EXTCODECOPY",
+            opcode.expr(),
+            OpcodeId::EXTCODECOPY.expr(),
+        );
+
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+        let external_address = cb.query_account_address();
+        let is_warm = cb.query_bool();
+        cb.account_access_list_read(tx_id.expr(), external_address.to_word(), is_warm.expr());
+
+        let code_offset = cb.query_word_unchecked();
+        let memory_address = MemoryExpandedAddressGadget::construct_self(cb);
+        cb.stack_pop(external_address.to_word());
+        cb.stack_pop(memory_address.offset_word());
+        cb.stack_pop(code_offset.to_word());
+        cb.stack_pop(memory_address.length_word());
+
+        let memory_expansion = MemoryExpansionGadget::construct(cb, [memory_address.address()]);
+        let memory_copier_gas = MemoryCopierGasGadget::construct(
+            cb,
+            memory_address.length(),
+            memory_expansion.gas_cost(),
+        );
+
+        // According to EIP-2929, EXTCODECOPY constant gas cost is different for cold
+        // and warm accounts.
+        let constant_gas_cost = select::expr(
+            is_warm.expr(),
+            GasCost::WARM_ACCESS.expr(),
+            GasCost::COLD_ACCOUNT_ACCESS.expr(),
+        );
+
+        let insufficient_gas = LtGadget::construct(
+            cb,
+            cb.curr.state.gas_left.expr(),
+            constant_gas_cost + memory_copier_gas.gas_cost(),
+        );
+
+        cb.require_equal(
+            "Memory address is overflow or gas left is less than cost",
+            or::expr([memory_address.overflow(), insufficient_gas.expr()]),
+            1.expr(),
+        );
+
+        let common_error_gadget =
+            CommonErrorGadget::construct(cb, opcode.expr(), cb.rw_counter_offset());
+
+        Self {
+            opcode,
+            external_address,
+            tx_id,
+            is_warm,
+            code_offset,
+            memory_address,
+            memory_expansion,
+            memory_copier_gas,
+            insufficient_gas,
+            common_error_gadget,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        transaction: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let opcode = step.opcode().unwrap();
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(transaction.id)))?;
+
+        let is_warm = block.get_rws(step, 1).tx_access_list_value_pair().0;
+        let [external_address, memory_offset, code_offset, copy_size] =
+            [2, 3, 4, 5].map(|index| block.get_rws(step, index).stack_value());
+
+        self.external_address
+            .assign_h160(region, offset, external_address.to_address())?;
+        self.is_warm
+            .assign(region, offset, Value::known(F::from(u64::from(is_warm))))?;
+        self.code_offset.assign_u256(region, offset, code_offset)?;
+        let memory_address =
+            self.memory_address
+                .assign(region, offset, memory_offset, copy_size)?;
+        let (_, memory_expansion_cost) = self.memory_expansion.assign(
+            region,
+            offset,
+            step.memory_word_size(),
+            [memory_address],
+        )?;
+        let memory_copier_gas = self.memory_copier_gas.assign(
+            region,
+            offset,
+            MemoryExpandedAddressGadget::<F>::length_value(memory_offset, copy_size),
+            memory_expansion_cost,
+        )?;
+        let constant_gas_cost = if is_warm {
+            GasCost::WARM_ACCESS
+        } else {
+            GasCost::COLD_ACCOUNT_ACCESS
+        };
+        self.insufficient_gas.assign_value(
+            region,
+            offset,
+            Value::known(F::from(step.gas_left)),
+            Value::known(F::from(constant_gas_cost + memory_copier_gas)),
+        )?;
+        self.common_error_gadget
+            .assign(region, offset, block, call, step, 8)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::CircuitTestBuilder;
+    use bus_mapping::circuit_input_builder::FixedCParams;
+    use eth_types::{
+        bytecode,
+        evm_types::{gas_utils::memory_copier_gas_cost, GasCost, OpcodeId},
+        Bytecode, ToWord, U256,
+    };
+    use mock::{
+        eth, test_ctx::helpers::account_0_code_account_1_no_code, TestContext, MOCK_ACCOUNTS,
+        MOCK_BLOCK_GAS_LIMIT,
+    };
+
+    #[test]
+    fn test_oog_extcodecopy() {
+        for is_warm in [false, true] {
+            for (memory_offset, copy_size) in [(0x20, 0), (0x40, 20), (0x2000, 0x200)] {
+                let code = extcodecopy_code(is_warm, memory_offset, copy_size);
+                // Decrease expected gas cost (by 1) to trigger out of gas error.
+                let gas = extcodecopy_gas_cost(is_warm, memory_offset, copy_size) - 1;
+                test_root(code.clone(), gas);
+                test_internal(code, gas);
+            }
+        }
+    }
+
+    #[test]
+    fn test_oog_extcodecopy_max_u64_address() {
+        for is_warm in [false, true] {
+            let code = extcodecopy_code(is_warm, u64::MAX, u64::MAX);
+            test_root(code.clone(), MOCK_BLOCK_GAS_LIMIT);
+            test_internal(code, MOCK_BLOCK_GAS_LIMIT);
+        }
+    }
+
+    fn extcodecopy_code(is_warm: bool, memory_offset: u64, copy_size: u64) -> Bytecode {
+        let external_address = MOCK_ACCOUNTS[4];
+        let mut code = Bytecode::default();
+        // Touch the external account first to make it warm.
+        if is_warm {
+            code.append(&bytecode! {
+                PUSH20(external_address.to_word())
+                EXTCODESIZE
+                POP
+            });
+        }
+        code.append(&bytecode! {
+            PUSH32(copy_size)
+            PUSH32(U256::zero())
+            PUSH32(memory_offset)
+            PUSH20(external_address.to_word())
+            EXTCODECOPY
+        });
+        code
+    }
+
+    fn extcodecopy_gas_cost(is_warm: bool, memory_offset: u64, copy_size: u64) -> u64 {
+        let memory_word_size = (memory_offset + copy_size + 31) / 32;
+        let gas_cost = OpcodeId::PUSH32.constant_gas_cost() * 3
+            + OpcodeId::PUSH20.constant_gas_cost()
+            + memory_copier_gas_cost(0, memory_word_size, copy_size);
+        if is_warm {
+            gas_cost
+                + OpcodeId::PUSH20.constant_gas_cost()
+                + GasCost::COLD_ACCOUNT_ACCESS
+                + OpcodeId::POP.constant_gas_cost()
+                + GasCost::WARM_ACCESS
+        } else {
+            gas_cost + GasCost::COLD_ACCOUNT_ACCESS
+        }
+    }
+
+    fn test_root(code: Bytecode, gas: u64) {
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .gas((GasCost::TX + gas).min(MOCK_BLOCK_GAS_LIMIT).into());
+            },
+            |block, _tx| block.number(0xcafe_u64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(FixedCParams {
+                max_copy_rows: 1750,
+                ..Default::default()
+            })
+            .run();
+    }
+
+    fn test_internal(code_b: Bytecode, gas_b: u64) {
+        let (addr_a, addr_b) = (MOCK_ACCOUNTS[0], MOCK_ACCOUNTS[1]);
+
+        // code B gets called by code A, so the call is an internal call.
+        let code_a = bytecode! {
+            PUSH1(0x00) // retLength
+            PUSH1(0x00) // retOffset
+            PUSH1(0x00) // argsLength
+            PUSH1(0x00) // argsOffset
+            PUSH1(0x00) // value
+            PUSH32(addr_b.to_word()) // addr
+            PUSH32(gas_b) // gas
+            CALL
+            STOP
+        };
+
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(addr_b).code(code_b);
+                accs[1].address(addr_a).code(code_a);
+                accs[2].address(MOCK_ACCOUNTS[2]).balance(eth(10));
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[2].address).to(accs[1].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(FixedCParams {
+                max_copy_rows: 1750,
+                ..Default::default()
+            })
+            .run();
+    }
+}
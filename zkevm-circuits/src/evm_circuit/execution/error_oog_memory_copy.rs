@@ -11,41 +11,37 @@ use crate::{
                 CommonMemoryAddressGadget, MemoryCopierGasGadget, MemoryExpandedAddressGadget,
                 MemoryExpansionGadget,
             },
-            or, select, AccountAddress, CachedRegion, Cell,
+            or, CachedRegion, Cell,
         },
         witness::{Block, Call, ExecStep, Transaction},
     },
-    table::CallContextFieldTag,
     util::{
-        word::{Word, WordCell, WordExpr},
+        word::{WordCell, WordExpr},
         Expr,
     },
 };
 use eth_types::{
     evm_types::{GasCost, OpcodeId},
-    Field, ToAddress, U256,
+    Field, U256,
 };
 use halo2_proofs::{circuit::Value, plonk::Error};
 
 /// Gadget to implement the corresponding out of gas errors for
 /// [`OpcodeId::CALLDATACOPY`], [`OpcodeId::CODECOPY`],
-/// [`OpcodeId::EXTCODECOPY`] and [`OpcodeId::RETURNDATACOPY`].
+/// [`OpcodeId::RETURNDATACOPY`] and [`OpcodeId::MCOPY`].
 #[derive(Clone, Debug)]
 pub(crate) struct ErrorOOGMemoryCopyGadget<F> {
     opcode: Cell<F>,
-    /// Check if `EXTCODECOPY` external address is warm
-    is_warm: Cell<F>,
-    tx_id: Cell<F>,
-    /// Extra stack pop for `EXTCODECOPY`
-    external_address: AccountAddress<F>,
     /// Source offset
     src_offset: WordCell<F>,
+    /// Source offset and size to copy for `MCOPY`, which also expands memory
+    src_memory_addr: MemoryExpandedAddressGadget<F>,
     /// Destination offset and size to copy
     dst_memory_addr: MemoryExpandedAddressGadget<F>,
-    memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
+    memory_expansion: MemoryExpansionGadget<F, 2, N_BYTES_MEMORY_WORD_SIZE>,
     memory_copier_gas: MemoryCopierGasGadget<F, { GasCost::COPY }>,
     insufficient_gas: LtGadget<F, N_BYTES_GAS>,
-    is_extcodecopy: IsZeroGadget<F>,
+    is_mcopy: IsZeroGadget<F>,
     common_error_gadget: CommonErrorGadget<F>,
 }
 
@@ -57,62 +53,54 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
     fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
         let opcode = cb.query_cell();
         cb.require_in_set(
-            "ErrorOutOfGasMemoryCopy opcode must be CALLDATACOPY, CODECOPY, EXTCODECOPY or RETURNDATACOPY",
+            "ErrorOutOfGasMemoryCopy opcode must be CALLDATACOPY, CODECOPY, RETURNDATACOPY or MCOPY",
             opcode.expr(),
             vec![
                 OpcodeId::CALLDATACOPY.expr(),
                 OpcodeId::CODECOPY.expr(),
-                OpcodeId::EXTCODECOPY.expr(),
                 OpcodeId::RETURNDATACOPY.expr(),
+                OpcodeId::MCOPY.expr(),
             ],
         );
 
         let src_offset = cb.query_word_unchecked();
-        let external_address = cb.query_account_address();
-        let is_warm = cb.query_bool();
-        let tx_id = cb.query_cell();
-
-        let is_extcodecopy =
-            IsZeroGadget::construct(cb, opcode.expr() - OpcodeId::EXTCODECOPY.expr());
-
-        cb.condition(is_extcodecopy.expr(), |cb| {
-            cb.call_context_lookup_read(
-                None,
-                CallContextFieldTag::TxId,
-                Word::from_lo_unchecked(tx_id.expr()),
-            );
-
-            // Check if EXTCODECOPY external address is warm.
-            cb.account_access_list_read(tx_id.expr(), external_address.to_word(), is_warm.expr());
-
-            // EXTCODECOPY has an extra stack pop for external address.
-            cb.stack_pop(external_address.to_word());
-        });
+        let is_mcopy = IsZeroGadget::construct(cb, opcode.expr() - OpcodeId::MCOPY.expr());
 
         let dst_memory_addr = MemoryExpandedAddressGadget::construct_self(cb);
         cb.stack_pop(dst_memory_addr.offset_word());
         cb.stack_pop(src_offset.to_word());
         cb.stack_pop(dst_memory_addr.length_word());
 
-        let memory_expansion = MemoryExpansionGadget::construct(cb, [dst_memory_addr.address()]);
+        // MCOPY also expands memory to cover the source range.
+        let src_memory_addr = MemoryExpandedAddressGadget::construct_self(cb);
+        cb.condition(is_mcopy.expr(), |cb| {
+            cb.require_equal_word(
+                "MCOPY source offset",
+                src_memory_addr.offset_word(),
+                src_offset.to_word(),
+            );
+            cb.require_equal_word(
+                "MCOPY source and destination have the same size",
+                src_memory_addr.length_word(),
+                dst_memory_addr.length_word(),
+            );
+        });
+
+        let memory_expansion = MemoryExpansionGadget::construct(
+            cb,
+            [
+                dst_memory_addr.address(),
+                is_mcopy.expr() * src_memory_addr.address(),
+            ],
+        );
         let memory_copier_gas = MemoryCopierGasGadget::construct(
             cb,
             dst_memory_addr.length(),
             memory_expansion.gas_cost(),
         );
 
-        let constant_gas_cost = select::expr(
-            is_extcodecopy.expr(),
-            // According to EIP-2929, EXTCODECOPY constant gas cost is different for cold and warm
-            // accounts.
-            select::expr(
-                is_warm.expr(),
-                GasCost::WARM_ACCESS.expr(),
-                GasCost::COLD_ACCOUNT_ACCESS.expr(),
-            ),
-            // Constant gas cost is same for CALLDATACOPY, CODECOPY and RETURNDATACOPY.
-            OpcodeId::CALLDATACOPY.constant_gas_cost().expr(),
-        );
+        // Constant gas cost is same for CALLDATACOPY, CODECOPY, RETURNDATACOPY and MCOPY.
+        let constant_gas_cost = OpcodeId::CALLDATACOPY.constant_gas_cost().expr();
 
         let insufficient_gas = LtGadget::construct(
             cb,
@@ -122,7 +110,11 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
 
         cb.require_equal(
             "Memory address is overflow or gas left is less than cost",
-            or::expr([dst_memory_addr.overflow(), insufficient_gas.expr()]),
+            or::expr([
+                dst_memory_addr.overflow(),
+                is_mcopy.expr() * src_memory_addr.overflow(),
+                insufficient_gas.expr(),
+            ]),
             1.expr(),
         );
 
@@ -131,15 +123,13 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
 
         Self {
             opcode,
-            is_warm,
-            tx_id,
-            external_address,
             src_offset,
+            src_memory_addr,
             dst_memory_addr,
             memory_expansion,
             memory_copier_gas,
             insufficient_gas,
-            is_extcodecopy,
+            is_mcopy,
             common_error_gadget,
         }
     }
@@ -149,12 +139,12 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
         region: &mut CachedRegion<'_, '_, F>,
         offset: usize,
         block: &Block<F>,
-        transaction: &Transaction,
+        _: &Transaction,
         call: &Call,
         step: &ExecStep,
     ) -> Result<(), Error> {
         let opcode = step.opcode().unwrap();
-        let is_extcodecopy = opcode == OpcodeId::EXTCODECOPY;
+        let is_mcopy = opcode == OpcodeId::MCOPY;
 
         log::debug!(
             "ErrorOutOfGasMemoryCopy: opcode = {}, gas_left = {}, gas_cost = {}",
@@ -163,70 +153,47 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
             step.gas_cost,
         );
 
-        let (is_warm, external_address) = if is_extcodecopy {
-            (
-                block.get_rws(step, 1).tx_access_list_value_pair().0,
-                block.get_rws(step, 2).stack_value(),
-            )
-        } else {
-            (false, U256::zero())
-        };
-
-        let rw_offset = if is_extcodecopy { 3 } else { 0 };
-        let [dst_offset, src_offset, copy_size] = [rw_offset, rw_offset + 1, rw_offset + 2]
-            .map(|index| block.get_rws(step, index).stack_value());
+        let [dst_offset, src_offset, copy_size] =
+            [0, 1, 2].map(|index| block.get_rws(step, index).stack_value());
 
         self.opcode
             .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
-        self.is_warm
-            .assign(region, offset, Value::known(F::from(u64::from(is_warm))))?;
-        self.tx_id
-            .assign(region, offset, Value::known(F::from(transaction.id)))?;
-        self.external_address
-            .assign_h160(region, offset, external_address.to_address())?;
         self.src_offset.assign_u256(region, offset, src_offset)?;
         let memory_addr = self
             .dst_memory_addr
             .assign(region, offset, dst_offset, copy_size)?;
-        let (_, memory_expansion_cost) =
-            self.memory_expansion
-                .assign(region, offset, step.memory_word_size(), [memory_addr])?;
+        let src_memory_addr = if is_mcopy {
+            self.src_memory_addr
+                .assign(region, offset, src_offset, copy_size)?
+        } else {
+            self.src_memory_addr
+                .assign(region, offset, U256::zero(), U256::zero())?
+        };
+        let (_, memory_expansion_cost) = self.memory_expansion.assign(
+            region,
+            offset,
+            step.memory_word_size(),
+            [memory_addr, src_memory_addr],
+        )?;
         let memory_copier_gas = self.memory_copier_gas.assign(
             region,
             offset,
             MemoryExpandedAddressGadget::<F>::length_value(dst_offset, copy_size),
             memory_expansion_cost,
         )?;
-        let constant_gas_cost = if is_extcodecopy {
-            if is_warm {
-                GasCost::WARM_ACCESS
-            } else {
-                GasCost::COLD_ACCOUNT_ACCESS
-            }
-        } else {
-            GasCost::FASTEST
-        };
         self.insufficient_gas.assign_value(
             region,
             offset,
             Value::known(F::from(step.gas_left)),
-            Value::known(F::from(constant_gas_cost + memory_copier_gas)),
+            Value::known(F::from(GasCost::FASTEST + memory_copier_gas)),
         )?;
-        self.is_extcodecopy.assign(
+        self.is_mcopy.assign(
             region,
             offset,
-            F::from(opcode.as_u64()) - F::from(OpcodeId::EXTCODECOPY.as_u64()),
-        )?;
-        self.common_error_gadget.assign(
-            region,
-            offset,
-            block,
-            call,
-            step,
-            // EXTCODECOPY has extra 1 call context lookup (tx_id), 1 account access list
-            // read (is_warm), and 1 stack pop (external_address).
-            5 + if is_extcodecopy { 3 } else { 0 },
+            F::from(opcode.as_u64()) - F::from(OpcodeId::MCOPY.as_u64()),
         )?;
+        self.common_error_gadget
+            .assign(region, offset, block, call, step, 5)?;
 
         Ok(())
     }
@@ -286,6 +253,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_oog_memory_copy_for_mcopy() {
+        for (src_offset, (dst_offset, copy_size)) in [0x00, 0x30, 0x3000]
+            .iter()
+            .cartesian_product(TESTING_DST_OFFSET_COPY_SIZE_PAIRS.iter())
+        {
+            let testing_data =
+                TestingData::new_for_mcopy(*dst_offset, *src_offset, *copy_size, None);
+
+            test_root(&testing_data);
+            test_internal(&testing_data);
+        }
+    }
+
     #[test]
     fn test_oog_memory_copy_max_expanded_address() {
         // 0xffffffff1 + 0xffffffff0 = 0x1fffffffe1
@@ -298,6 +279,16 @@ mod tests {
         test_for_edge_memory_size(u64::MAX, u64::MAX);
     }
 
+    #[test]
+    fn test_oog_memory_copy_mcopy_max_u64_src_address() {
+        // Only the source range of MCOPY overflows.
+        let testing_data =
+            TestingData::new_for_mcopy(0x20, u64::MAX, 0x20, Some(MOCK_BLOCK_GAS_LIMIT));
+
+        test_root(&testing_data);
+        test_internal(&testing_data);
+    }
+
     struct TestingData {
         bytecode: Bytecode,
         gas_cost: u64,
@@ -373,6 +364,35 @@ mod tests {
 
             Self { bytecode, gas_cost }
         }
+
+        pub fn new_for_mcopy(
+            dst_offset: u64,
+            src_offset: u64,
+            copy_size: u64,
+            gas_cost: Option<u64>,
+        ) -> Self {
+            let bytecode = bytecode! {
+                PUSH32(copy_size)
+                PUSH32(src_offset)
+                PUSH32(dst_offset)
+                MCOPY
+            };
+
+            let gas_cost = gas_cost.unwrap_or_else(|| {
+                // Both the source and destination ranges expand memory.
+                let memory_word_size = if copy_size == 0 {
+                    0
+                } else {
+                    (dst_offset.max(src_offset) + copy_size + 31) / 32
+                };
+
+                OpcodeId::PUSH32.constant_gas_cost() * 3
+                    + OpcodeId::MCOPY.constant_gas_cost()
+                    + memory_copier_gas_cost(0, memory_word_size, copy_size)
+            });
+
+            Self { bytecode, gas_cost }
+        }
     }
 
     fn test_root(testing_data: &TestingData) {
@@ -479,5 +499,11 @@ mod tests {
             test_root(&testing_data);
             test_internal(&testing_data);
         });
+
+        let testing_data =
+            TestingData::new_for_mcopy(dst_offset, 0, copy_size, Some(MOCK_BLOCK_GAS_LIMIT));
+
+        test_root(&testing_data);
+        test_internal(&testing_data);
     }
 }
@@ -13,7 +13,7 @@ use crate::{
     table::CallContextFieldTag,
     witness::{Block, Call, ExecStep, Transaction},
 };
-use bus_mapping::precompile::PrecompileCalls;
+use bus_mapping::precompile::{PrecompileCalls, N_BYTES_EC_PAIR};
 use eth_types::{evm_types::GasCost, Field, ToScalar};
 use gadgets::util::{sum, Expr};
 use halo2_proofs::{circuit::Value, plonk::Error};
@@ -23,7 +23,7 @@ pub(crate) struct ErrorOOGPrecompileGadget<F> {
     precompile_addr: Cell<F>,
     addr_bits: BinaryNumberGadget<F, 4>,
     call_data_length: Cell<F>,
-    // n_pairs: ConstantDivisionGadget<F, N_BYTES_MEMORY_WORD_SIZE>,
+    n_pairs: ConstantDivisionGadget<F, N_BYTES_MEMORY_WORD_SIZE>,
     n_words: ConstantDivisionGadget<F, N_BYTES_MEMORY_WORD_SIZE>,
     required_gas: Cell<F>,
     insufficient_gas: LtGadget<F, N_BYTES_GAS>,
@@ -44,52 +44,69 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGPrecompileGadget<F> {
 
         // read call data length
         let call_data_length = cb.call_context(None, CallContextFieldTag::CallDataLength);
-        // let n_pairs = cb.condition(
-        //     addr_bits.value_equals(PrecompileCalls::Bn128Pairing),
-        //     |cb| {
-        //         ConstantDivisionGadget::construct(
-        //             cb,
-        //             call_data_length.expr(),
-        //             N_BYTES_EC_PAIR as u64,
-        //         )
-        //     },
-        // );
-        let n_words = cb.condition(addr_bits.value_equals(PrecompileCalls::Identity), |cb| {
-            ConstantDivisionGadget::construct(
-                cb,
-                call_data_length.expr() + (N_BYTES_WORD - 1).expr(),
-                N_BYTES_WORD as u64,
-            )
-        });
+        let n_pairs = cb.condition(
+            addr_bits.value_equals(PrecompileCalls::Bn128Pairing),
+            |cb| {
+                ConstantDivisionGadget::construct(
+                    cb,
+                    call_data_length.expr(),
+                    N_BYTES_EC_PAIR as u64,
+                )
+            },
+        );
+        let n_words = cb.condition(
+            addr_bits.value_equals(PrecompileCalls::Sha256)
+                + addr_bits.value_equals(PrecompileCalls::Ripemd160)
+                + addr_bits.value_equals(PrecompileCalls::Identity),
+            |cb| {
+                ConstantDivisionGadget::construct(
+                    cb,
+                    call_data_length.expr() + (N_BYTES_WORD - 1).expr(),
+                    N_BYTES_WORD as u64,
+                )
+            },
+        );
 
         // calculate required gas for precompile
         let precompiles_required_gas = vec![
-            // (
-            //     addr_bits.value_equals(PrecompileCalls::ECRecover),
-            //     GasCost::PRECOMPILE_ECRECOVER_BASE.expr(),
-            // ),
-            // addr_bits.value_equals(PrecompileCalls::Sha256),
-            // addr_bits.value_equals(PrecompileCalls::Ripemd160),
-            // addr_bits.value_equals(PrecompileCalls::Blake2F),
+            (
+                addr_bits.value_equals(PrecompileCalls::ECRecover),
+                GasCost::PRECOMPILE_ECRECOVER_BASE.expr(),
+            ),
+            (
+                addr_bits.value_equals(PrecompileCalls::Sha256),
+                GasCost::PRECOMPILE_SHA256_BASE.expr()
+                    + n_words.quotient() * GasCost::PRECOMPILE_SHA256_PER_WORD.expr(),
+            ),
+            (
+                addr_bits.value_equals(PrecompileCalls::Ripemd160),
+                GasCost::PRECOMPILE_RIPEMD160_BASE.expr()
+                    + n_words.quotient() * GasCost::PRECOMPILE_RIPEMD160_PER_WORD.expr(),
+            ),
+            // blake2f is handled in Blake2fGadget
             (
                 addr_bits.value_equals(PrecompileCalls::Identity),
                 GasCost::PRECOMPILE_IDENTITY_BASE.expr()
                     + n_words.quotient() * GasCost::PRECOMPILE_IDENTITY_PER_WORD.expr(),
             ),
             // modexp is handled in ModExpGadget
-            // (
-            //     addr_bits.value_equals(PrecompileCalls::Bn128Add),
-            //     GasCost::PRECOMPILE_BN256ADD.expr(),
-            // ),
-            // (
-            //     addr_bits.value_equals(PrecompileCalls::Bn128Mul),
-            //     GasCost::PRECOMPILE_BN256MUL.expr(),
-            // ),
-            // (
-            //     addr_bits.value_equals(PrecompileCalls::Bn128Pairing),
-            //     GasCost::PRECOMPILE_BN256PAIRING.expr()
-            //         + n_pairs.quotient() * GasCost::PRECOMPILE_BN256PAIRING_PER_PAIR.expr(),
-            // ),
+            (
+                addr_bits.value_equals(PrecompileCalls::Bn128Add),
+                GasCost::PRECOMPILE_BN256ADD.expr(),
+            ),
+            (
+                addr_bits.value_equals(PrecompileCalls::Bn128Mul),
+                GasCost::PRECOMPILE_BN256MUL.expr(),
+            ),
+            (
+                addr_bits.value_equals(PrecompileCalls::Bn128Pairing),
+                GasCost::PRECOMPILE_BN256PAIRING.expr()
+                    + n_pairs.quotient() * GasCost::PRECOMPILE_BN256PAIRING_PER_PAIR.expr(),
+            ),
+            (
+                addr_bits.value_equals(PrecompileCalls::PointEvaluation),
+                GasCost::PRECOMPILE_POINT_EVALUATION.expr(),
+            ),
         ];
 
         cb.require_equal(
@@ -133,7 +150,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGPrecompileGadget<F> {
             precompile_addr,
             required_gas,
             insufficient_gas,
-            // n_pairs,
+            n_pairs,
             n_words,
             addr_bits,
             call_data_length,
@@ -168,9 +185,9 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGPrecompileGadget<F> {
         )?;
 
         // n_pairs
-        // let n_pairs = call.call_data_length / 192;
-        // self.n_pairs
-        //     .assign(region, offset, call.call_data_length as u128)?;
+        let (n_pairs, _) = self
+            .n_pairs
+            .assign(region, offset, call.call_data_length as u128)?;
 
         // n_words
         self.n_words.assign(
@@ -182,17 +199,26 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGPrecompileGadget<F> {
         // required_gas
         let precompile_call: PrecompileCalls = precompile_addr.to_fixed_bytes()[19].into();
         let required_gas = match precompile_call {
-            // PrecompileCalls::Bn128Pairing => {
-            //     precompile_call.base_gas_cost()
-            //         + n_pairs * GasCost::PRECOMPILE_BN256PAIRING_PER_PAIR
-            // }
+            PrecompileCalls::Bn128Pairing => {
+                precompile_call.base_gas_cost()
+                    + n_pairs as u64 * GasCost::PRECOMPILE_BN256PAIRING_PER_PAIR
+            }
+            PrecompileCalls::Sha256 => {
+                let n_words = (call.call_data_length + 31) / 32;
+                precompile_call.base_gas_cost() + n_words * GasCost::PRECOMPILE_SHA256_PER_WORD
+            }
+            PrecompileCalls::Ripemd160 => {
+                let n_words = (call.call_data_length + 31) / 32;
+                precompile_call.base_gas_cost() + n_words * GasCost::PRECOMPILE_RIPEMD160_PER_WORD
+            }
             PrecompileCalls::Identity => {
                 let n_words = (call.call_data_length + 31) / 32;
                 precompile_call.base_gas_cost() + n_words * GasCost::PRECOMPILE_IDENTITY_PER_WORD
             }
-            // PrecompileCalls::Bn128Add | PrecompileCalls::Bn128Mul | PrecompileCalls::ECRecover =>
-            // {     precompile_call.base_gas_cost()
-            // }
+            PrecompileCalls::ECRecover
+            | PrecompileCalls::Bn128Add
+            | PrecompileCalls::Bn128Mul
+            | PrecompileCalls::PointEvaluation => precompile_call.base_gas_cost(),
             _ => unreachable!(),
         };
 
@@ -253,6 +279,86 @@ mod test {
                         - 1).to_word(),
                     ..Default::default()
                 },
+                PrecompileCallArgs {
+                    name: "ecrecover (insufficient gas)",
+                    setup_code: bytecode! {},
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x80.into(),
+                    ret_offset: 0x80.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::ECRecover.address().to_word(),
+                    gas: (PrecompileCalls::ECRecover.base_gas_cost() - 1).to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "sha256 (insufficient gas)",
+                    setup_code: bytecode! {
+                        PUSH16(word!("0x0123456789abcdef0f1e2d3c4b5a6978"))
+                        PUSH1(0x00)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x21.into(),
+                    ret_offset: 0x40.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::Sha256.address().to_word(),
+                    gas: (PrecompileCalls::Sha256.base_gas_cost()
+                        + 2 * GasCost::PRECOMPILE_SHA256_PER_WORD
+                        - 1).to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ripemd160 (insufficient gas)",
+                    setup_code: bytecode! {
+                        PUSH16(word!("0x0123456789abcdef0f1e2d3c4b5a6978"))
+                        PUSH1(0x00)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x21.into(),
+                    ret_offset: 0x40.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::Ripemd160.address().to_word(),
+                    gas: (PrecompileCalls::Ripemd160.base_gas_cost()
+                        + 2 * GasCost::PRECOMPILE_RIPEMD160_PER_WORD
+                        - 1).to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ecAdd (insufficient gas)",
+                    setup_code: bytecode! {},
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x80.into(),
+                    ret_offset: 0x80.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::Bn128Add.address().to_word(),
+                    gas: (PrecompileCalls::Bn128Add.base_gas_cost() - 1).to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ecPairing (insufficient gas)",
+                    setup_code: bytecode! {},
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0xc0.into(),
+                    ret_offset: 0x00.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::Bn128Pairing.address().to_word(),
+                    gas: (PrecompileCalls::Bn128Pairing.base_gas_cost()
+                        + GasCost::PRECOMPILE_BN256PAIRING_PER_PAIR
+                        - 1).to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "point evaluation (insufficient gas)",
+                    setup_code: bytecode! {},
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0xc0.into(),
+                    ret_offset: 0x00.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::PointEvaluation.address().to_word(),
+                    gas: (PrecompileCalls::PointEvaluation.base_gas_cost() - 1).to_word(),
+                    ..Default::default()
+                },
             ]
         };
     }
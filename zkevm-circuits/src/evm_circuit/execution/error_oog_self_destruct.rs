@@ -0,0 +1,307 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::N_BYTES_GAS,
+        step::ExecutionState,
+        util::{
+            common_gadget::CommonErrorGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::{IsZeroWordGadget, LtGadget},
+            not, select, AccountAddress, CachedRegion, Cell, StepRws,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::{AccountFieldTag, CallContextFieldTag},
+    util::{
+        word::{Word32Cell, WordCell, WordExpr},
+        Expr,
+    },
+};
+use eth_types::{
+    evm_types::{GasCost, OpcodeId},
+    Field, ToAddress,
+};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget to implement the corresponding out of gas errors for
+/// [`OpcodeId::SELFDESTRUCT`].
+#[derive(Clone, Debug)]
+pub(crate) struct ErrorOOGSelfDestructGadget<F> {
+    opcode: Cell<F>,
+    beneficiary: AccountAddress<F>,
+    tx_id: Cell<F>,
+    callee_address: WordCell<F>,
+    is_warm: Cell<F>,
+    beneficiary_code_hash: WordCell<F>,
+    beneficiary_not_exists: IsZeroWordGadget<F, WordCell<F>>,
+    balance: Word32Cell<F>,
+    balance_is_zero: IsZeroWordGadget<F, Word32Cell<F>>,
+    insufficient_gas: LtGadget<F, N_BYTES_GAS>,
+    common_error_gadget: CommonErrorGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for ErrorOOGSelfDestructGadget<F> {
+    const NAME: &'static str = "ErrorOutOfGasSELFDESTRUCT";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::ErrorOutOfGasSELFDESTRUCT;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+        cb.require_equal(
+            "ErrorOutOfGasSELFDESTRUCT opcode must be SELFDESTRUCT",
+            opcode.expr(),
+            OpcodeId::SELFDESTRUCT.expr(),
+        );
+
+        let beneficiary = cb.query_account_address();
+        cb.stack_pop(beneficiary.to_word());
+
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+        let callee_address = cb.call_context_read_as_word(None, CallContextFieldTag::CalleeAddress);
+
+        let is_warm = cb.query_bool();
+        cb.account_access_list_read(tx_id.expr(), beneficiary.to_word(), is_warm.expr());
+
+        // For non-existing accounts the code_hash must be 0 in the rw_table.
+        let beneficiary_code_hash = cb.query_word_unchecked();
+        cb.account_read(
+            beneficiary.to_word(),
+            AccountFieldTag::CodeHash,
+            beneficiary_code_hash.to_word(),
+        );
+        let beneficiary_not_exists = IsZeroWordGadget::construct(cb, &beneficiary_code_hash);
+
+        let balance = cb.query_word32();
+        cb.account_read(
+            callee_address.to_word(),
+            AccountFieldTag::Balance,
+            balance.to_word(),
+        );
+        let balance_is_zero = IsZeroWordGadget::construct(cb, &balance);
+
+        // A new account is charged when a non-zero balance is sent to a
+        // non-existing beneficiary.
+        let gas_cost = GasCost::SELFDESTRUCT.expr()
+            + select::expr(
+                is_warm.expr(),
+                0.expr(),
+                GasCost::COLD_ACCOUNT_ACCESS.expr(),
+            )
+            + beneficiary_not_exists.expr()
+                * not::expr(balance_is_zero.expr())
+                * GasCost::NEW_ACCOUNT.expr();
+
+        let insufficient_gas = LtGadget::construct(cb, cb.curr.state.gas_left.expr(), gas_cost);
+        cb.require_equal(
+            "Gas left is less than gas cost",
+            insufficient_gas.expr(),
+            1.expr(),
+        );
+
+        let common_error_gadget =
+            CommonErrorGadget::construct(cb, opcode.expr(), cb.rw_counter_offset());
+
+        Self {
+            opcode,
+            beneficiary,
+            tx_id,
+            callee_address,
+            is_warm,
+            beneficiary_code_hash,
+            beneficiary_not_exists,
+            balance,
+            balance_is_zero,
+            insufficient_gas,
+            common_error_gadget,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let opcode = step.opcode().unwrap();
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
+
+        let mut rws = StepRws::new(block, step);
+        let beneficiary = rws.next().stack_value();
+        let tx_id = rws.next().call_context_value();
+        let callee_address = rws.next().call_context_value();
+        let (_, is_warm) = rws.next().tx_access_list_value_pair();
+        let beneficiary_code_hash = rws.next().account_codehash_pair().0;
+        let balance = rws.next().account_balance_pair().0;
+
+        self.beneficiary
+            .assign_h160(region, offset, beneficiary.to_address())?;
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx_id.low_u64())))?;
+        self.callee_address
+            .assign_u256(region, offset, callee_address)?;
+        self.is_warm
+            .assign(region, offset, Value::known(F::from(is_warm as u64)))?;
+        self.beneficiary_code_hash
+            .assign_u256(region, offset, beneficiary_code_hash)?;
+        self.beneficiary_not_exists
+            .assign_u256(region, offset, beneficiary_code_hash)?;
+        self.balance.assign_u256(region, offset, balance)?;
+        self.balance_is_zero.assign_u256(region, offset, balance)?;
+
+        let gas_cost = GasCost::SELFDESTRUCT
+            + if is_warm {
+                0
+            } else {
+                GasCost::COLD_ACCOUNT_ACCESS
+            }
+            + if beneficiary_code_hash.is_zero() && !balance.is_zero() {
+                GasCost::NEW_ACCOUNT
+            } else {
+                0
+            };
+        self.insufficient_gas.assign_value(
+            region,
+            offset,
+            Value::known(F::from(step.gas_left)),
+            Value::known(F::from(gas_cost)),
+        )?;
+        self.common_error_gadget
+            .assign(region, offset, block, call, step, 8)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::{
+        address, bytecode,
+        evm_types::{GasCost, OpcodeId},
+        Address, Bytecode, ToWord, Word,
+    };
+    use lazy_static::lazy_static;
+    use mock::{eth, TestContext, MOCK_ACCOUNTS};
+
+    lazy_static! {
+        static ref BENEFICIARY: Address = address!("0xaabbccddee000000000000000000000000000000");
+    }
+
+    #[test]
+    fn test_oog_self_destruct() {
+        for (is_warm, beneficiary_exists, balance) in [
+            (false, false, Word::zero()),
+            (false, false, Word::from(100)),
+            (false, true, Word::from(100)),
+            (true, false, Word::from(100)),
+            (true, true, Word::zero()),
+        ] {
+            let (code, gas_cost) = self_destruct_code(is_warm, beneficiary_exists, balance);
+            test_root(code.clone(), beneficiary_exists, balance, gas_cost - 1);
+            test_internal(code, beneficiary_exists, balance, gas_cost - 1);
+        }
+    }
+
+    /// Return the bytecode and the gas it needs to succeed.
+    fn self_destruct_code(
+        is_warm: bool,
+        beneficiary_exists: bool,
+        balance: Word,
+    ) -> (Bytecode, u64) {
+        let mut code = Bytecode::default();
+        let mut gas_cost = 0;
+        if is_warm {
+            code.append(&bytecode! {
+                PUSH20(BENEFICIARY.to_word())
+                BALANCE
+                POP
+            });
+            gas_cost += OpcodeId::PUSH20.constant_gas_cost()
+                + GasCost::COLD_ACCOUNT_ACCESS
+                + OpcodeId::POP.constant_gas_cost();
+        }
+        code.append(&bytecode! {
+            PUSH20(BENEFICIARY.to_word())
+            SELFDESTRUCT
+        });
+        gas_cost += OpcodeId::PUSH20.constant_gas_cost() + GasCost::SELFDESTRUCT;
+        if !is_warm {
+            gas_cost += GasCost::COLD_ACCOUNT_ACCESS;
+        }
+        if !beneficiary_exists && !balance.is_zero() {
+            gas_cost += GasCost::NEW_ACCOUNT;
+        }
+
+        (code, gas_cost)
+    }
+
+    fn test_root(code: Bytecode, beneficiary_exists: bool, balance: Word, gas: u64) {
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(balance)
+                    .code(code);
+                accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+                if beneficiary_exists {
+                    accs[2].address(*BENEFICIARY).balance(eth(1));
+                } else {
+                    accs[2].address(MOCK_ACCOUNTS[2]).balance(eth(1));
+                }
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .gas((GasCost::TX + gas).into());
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    fn test_internal(code_b: Bytecode, beneficiary_exists: bool, balance: Word, gas_b: u64) {
+        let (addr_a, addr_b) = (MOCK_ACCOUNTS[0], MOCK_ACCOUNTS[1]);
+
+        // code B gets called by code A, so the call is an internal call.
+        let code_a = bytecode! {
+            PUSH1(0x00) // retLength
+            PUSH1(0x00) // retOffset
+            PUSH1(0x00) // argsLength
+            PUSH1(0x00) // argsOffset
+            PUSH1(0x00) // value
+            PUSH32(addr_b.to_word()) // addr
+            PUSH32(gas_b) // gas
+            CALL
+            STOP
+        };
+
+        let ctx = TestContext::<4, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(addr_b).balance(balance).code(code_b);
+                accs[1].address(addr_a).code(code_a);
+                accs[2].address(MOCK_ACCOUNTS[2]).balance(eth(10));
+                if beneficiary_exists {
+                    accs[3].address(*BENEFICIARY).balance(eth(1));
+                } else {
+                    accs[3].address(MOCK_ACCOUNTS[3]).balance(eth(1));
+                }
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[2].address).to(accs[1].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+}
@@ -47,11 +47,12 @@ impl<F: Field> ExecutionGadget<F> for ErrorWriteProtectionGadget<F> {
         // max_degree. otherwise need to do fixed lookup for these opcodes
         // checking.
         cb.require_in_set(
-            "ErrorWriteProtection only happens in [CALL, SSTORE, CREATE, CREATE2, SELFDESTRUCT, LOG0..4 ]",
+            "ErrorWriteProtection only happens in [CALL, SSTORE, TSTORE, CREATE, CREATE2, SELFDESTRUCT, LOG0..4 ]",
             opcode.expr(),
             vec![
                 OpcodeId::CALL.expr(),
                 OpcodeId::SSTORE.expr(),
+                OpcodeId::TSTORE.expr(),
                 OpcodeId::CREATE.expr(),
                 OpcodeId::CREATE2.expr(),
                 OpcodeId::SELFDESTRUCT.expr(),
@@ -155,13 +156,15 @@ mod test {
     #[test]
     fn test_write_protection() {
         // test sstore with write protection error
-        test_internal_write_protection(false);
+        test_internal_write_protection(false, false);
         // test call with write protection error
-        test_internal_write_protection(true);
+        test_internal_write_protection(true, false);
+        // test tstore with write protection error
+        test_internal_write_protection(false, true);
     }
 
     // ErrorWriteProtection error happen in internal call
-    fn test_internal_write_protection(is_call: bool) {
+    fn test_internal_write_protection(is_call: bool, is_tstore: bool) {
         let mut caller_bytecode = bytecode! {
             PUSH1(0)
             PUSH1(0)
@@ -195,6 +198,12 @@ mod test {
                 RETURN
                 STOP
             });
+        } else if is_tstore {
+            callee_bytecode.append(&bytecode! {
+                // this TSTORE got error: ErrorWriteProtection
+                TSTORE
+                STOP
+            });
         } else {
             callee_bytecode.append(&bytecode! {
                 // this SSTORE got error: ErrorWriteProtection
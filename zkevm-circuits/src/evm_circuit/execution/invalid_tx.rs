@@ -53,7 +53,8 @@ impl<F: Field> ExecutionGadget<F> for InvalidTxGadget<F> {
         let insufficient_gas_limit =
             LtGadget::<F, N_BYTES_GAS>::construct(cb, tx.gas.expr(), tx.intrinsic_gas());
 
-        // Check if the balance is sufficient to pay for the total tx cost (intrinsic gas + value)
+        // Check if the balance is sufficient to pay for the total tx cost (gas fee + blob gas fee +
+        // value)
         let balance = cb.query_word32();
         cb.account_read(
             tx.caller_address.to_word(),
@@ -74,6 +75,7 @@ impl<F: Field> ExecutionGadget<F> for InvalidTxGadget<F> {
         let end_tx = EndTxHelperGadget::construct(
             cb,
             begin_tx.tx_id.expr(),
+            tx.block_number.expr(),
             false.expr(),
             0.expr(),
             8.expr(),
@@ -111,7 +113,7 @@ impl<F: Field> ExecutionGadget<F> for InvalidTxGadget<F> {
             .expect("unexpected U256 -> Scalar conversion failure");
         let balance = rws.next().account_balance_pair().0;
         self.begin_tx.assign(region, offset, tx)?;
-        self.tx.assign(region, offset, tx)?;
+        self.tx.assign(region, offset, block, tx)?;
         self.account_nonce
             .assign(region, offset, Value::known(account_nonce))?;
         self.is_nonce_match
@@ -120,14 +122,16 @@ impl<F: Field> ExecutionGadget<F> for InvalidTxGadget<F> {
             region,
             offset,
             tx.gas().scalar(),
-            tx.intrinsic_gas_cost().scalar(),
+            tx.intrinsic_gas_cost(block.context.hardfork).scalar(),
         )?;
         self.balance.assign_u256(region, offset, balance)?;
         self.insufficient_balance.assign(
             region,
             offset,
             balance,
-            tx.gas_price * tx.gas() + tx.value,
+            tx.gas_price * tx.gas()
+                + tx.blob_gas_fee(block.tx_context(tx).blob_base_fee)
+                + tx.value,
         )?;
         self.end_tx.assign(region, offset, block, tx)?;
 
@@ -130,6 +130,24 @@ impl<F: Field> ExecutionGadget<F> for LogGadget<F> {
         // access
         let memory_expansion = MemoryExpansionGadget::construct(cb, [memory_address.address()]);
 
+        // the lengths are needed to RLP-encode the log in the tx receipt
+        cb.condition(is_persistent.expr(), |cb| {
+            cb.tx_log_lookup(
+                tx_id.expr(),
+                cb.curr.state.log_id.expr() + 1.expr(),
+                TxLogFieldTag::TopicLength,
+                0.expr(),
+                Word::from_lo_unchecked(topic_count.clone()),
+            );
+            cb.tx_log_lookup(
+                tx_id.expr(),
+                cb.curr.state.log_id.expr() + 1.expr(),
+                TxLogFieldTag::DataLength,
+                0.expr(),
+                memory_address.length_word(),
+            );
+        });
+
         let copy_rwc_inc = cb.query_cell();
         let dst_addr = build_tx_log_expression(
             0.expr(),
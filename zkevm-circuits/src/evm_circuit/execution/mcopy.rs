@@ -0,0 +1,230 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::N_BYTES_MEMORY_WORD_SIZE,
+        step::ExecutionState,
+        util::{
+            common_gadget::{HardforkGadget, SameContextGadget},
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
+                Transition::{Delta, To},
+            },
+            memory_gadget::{
+                CommonMemoryAddressGadget, MemoryAddressGadget, MemoryCopierGasGadget,
+                MemoryExpansionGadget,
+            },
+            CachedRegion,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    util::{
+        word::{Word, WordExpr},
+        Expr,
+    },
+};
+use bus_mapping::{circuit_input_builder::CopyDataType, evm::OpcodeId};
+use eth_types::{
+    evm_types::{GasCost, Hardfork},
+    Field,
+};
+use halo2_proofs::plonk::Error;
+
+#[derive(Clone, Debug)]
+pub(crate) struct McopyGadget<F> {
+    same_context: SameContextGadget<F>,
+    hardfork: HardforkGadget<F>,
+    /// The memory range which is read from.
+    src_memory_addr: MemoryAddressGadget<F>,
+    /// The memory range which is written to.
+    dst_memory_addr: MemoryAddressGadget<F>,
+    /// Opcode MCOPY has a dynamic gas cost:
+    /// gas_code = static_gas * minimum_word_size + memory_expansion_cost
+    /// where the memory expansion covers both the source and destination.
+    memory_expansion: MemoryExpansionGadget<F, 2, N_BYTES_MEMORY_WORD_SIZE>,
+    /// Opcode MCOPY needs to copy data within memory. We account for the
+    /// copying costs using the memory copier gas gadget.
+    memory_copier_gas: MemoryCopierGasGadget<F, { GasCost::COPY }>,
+}
+
+impl<F: Field> ExecutionGadget<F> for McopyGadget<F> {
+    const NAME: &'static str = "MCOPY";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::MCOPY;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // MCOPY is only defined since Cancun (EIP-5656).
+        let hardfork = HardforkGadget::construct(cb);
+        cb.require_equal(
+            "MCOPY is defined in the hardfork",
+            hardfork.is_active(Hardfork::Cancun),
+            1.expr(),
+        );
+
+        let opcode = cb.query_cell();
+
+        let dst_offset = cb.query_word_unchecked();
+        let src_offset = cb.query_word_unchecked();
+        let length = cb.query_memory_address();
+
+        // Pop dst_offset, src_offset and length from the stack
+        cb.stack_pop(dst_offset.to_word());
+        cb.stack_pop(src_offset.to_word());
+        cb.stack_pop(Word::from_lo_unchecked(length.expr()));
+
+        let src_memory_addr = MemoryAddressGadget::construct(cb, src_offset, length.clone());
+        let dst_memory_addr = MemoryAddressGadget::construct(cb, dst_offset, length);
+
+        // Calculate the next memory size and the gas cost for this memory
+        // access. This also accounts for the dynamic gas required to copy bytes
+        // within memory.
+        let memory_expansion = MemoryExpansionGadget::construct(
+            cb,
+            [src_memory_addr.address(), dst_memory_addr.address()],
+        );
+        let memory_copier_gas = MemoryCopierGasGadget::construct(
+            cb,
+            dst_memory_addr.length(),
+            memory_expansion.gas_cost(),
+        );
+
+        // Every byte is read from and written to memory, and the copy circuit
+        // reads the whole source range before writing to the destination.
+        cb.condition(dst_memory_addr.has_length(), |cb| {
+            cb.copy_table_lookup(
+                Word::from_lo_unchecked(cb.curr.state.call_id.expr()),
+                CopyDataType::Memory.expr(),
+                Word::from_lo_unchecked(cb.curr.state.call_id.expr()),
+                CopyDataType::Memory.expr(),
+                src_memory_addr.offset(),
+                src_memory_addr.address(),
+                dst_memory_addr.offset(),
+                dst_memory_addr.length(),
+                0.expr(), // for MCOPY rlc_acc is 0
+                dst_memory_addr.length() + dst_memory_addr.length(),
+            );
+        });
+
+        let step_state_transition = StepStateTransition {
+            rw_counter: Delta(cb.rw_counter_offset()),
+            program_counter: Delta(1.expr()),
+            stack_pointer: Delta(3.expr()),
+            gas_left: Delta(
+                -(OpcodeId::MCOPY.constant_gas_cost().expr() + memory_copier_gas.gas_cost()),
+            ),
+            memory_word_size: To(memory_expansion.next_memory_word_size()),
+            ..Default::default()
+        };
+        let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
+
+        Self {
+            same_context,
+            hardfork,
+            src_memory_addr,
+            dst_memory_addr,
+            memory_expansion,
+            memory_copier_gas,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _: &Transaction,
+        _: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.same_context.assign_exec_step(region, offset, step)?;
+        self.hardfork
+            .assign(region, offset, block.context.hardfork)?;
+
+        let [dst_offset, src_offset, length] =
+            [0, 1, 2].map(|index| block.get_rws(step, index).stack_value());
+
+        let src_address = self
+            .src_memory_addr
+            .assign(region, offset, src_offset, length)?;
+        let dst_address = self
+            .dst_memory_addr
+            .assign(region, offset, dst_offset, length)?;
+
+        // assign to gadgets handling memory expansion cost and copying cost.
+        let (_, memory_expansion_cost) = self.memory_expansion.assign(
+            region,
+            offset,
+            step.memory_word_size(),
+            [src_address, dst_address],
+        )?;
+        self.memory_copier_gas
+            .assign(region, offset, length.as_u64(), memory_expansion_cost)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{evm_circuit::test::rand_bytes, test_util::CircuitTestBuilder};
+    use eth_types::{bytecode, Word};
+    use mock::TestContext;
+
+    fn test_ok(dst_offset: Word, src_offset: Word, length: usize) {
+        let code = bytecode! {
+            PUSH32(Word::from_big_endian(&rand_bytes(0x20)))
+            PUSH1(0x00)
+            MSTORE
+            PUSH32(Word::from_big_endian(&rand_bytes(0x20)))
+            PUSH1(0x20)
+            MSTORE
+            PUSH32(length)
+            PUSH32(src_offset)
+            PUSH32(dst_offset)
+            MCOPY
+            STOP
+        };
+
+        CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap(),
+        )
+        .run();
+    }
+
+    #[test]
+    fn mcopy_gadget_simple() {
+        test_ok(0x40.into(), 0x00.into(), 0x20);
+    }
+
+    #[test]
+    fn mcopy_gadget_overlap_forward() {
+        // destination starts inside the source range
+        test_ok(0x10.into(), 0x00.into(), 0x30);
+    }
+
+    #[test]
+    fn mcopy_gadget_overlap_backward() {
+        // source starts inside the destination range
+        test_ok(0x00.into(), 0x10.into(), 0x30);
+    }
+
+    #[test]
+    fn mcopy_gadget_same_offset() {
+        test_ok(0x08.into(), 0x08.into(), 0x20);
+    }
+
+    #[test]
+    fn mcopy_gadget_expand_source() {
+        // source extends beyond the memory written so far
+        test_ok(0x00.into(), 0x30.into(), 0x60);
+    }
+
+    #[test]
+    fn mcopy_gadget_zero_length() {
+        test_ok(0x20.into(), 0x00.into(), 0);
+    }
+
+    #[test]
+    fn mcopy_gadget_overflow_offset_and_zero_length() {
+        test_ok(Word::MAX, Word::MAX, 0);
+    }
+}
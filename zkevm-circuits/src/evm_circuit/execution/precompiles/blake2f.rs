@@ -0,0 +1,385 @@
+use bus_mapping::{
+    circuit_input_builder::Call,
+    precompile::{PrecompileAuxData, N_BYTES_BLAKE2F_INPUT},
+};
+use eth_types::{evm_types::GasCost, Field, ToScalar};
+use gadgets::util::{not, select, Expr};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::N_BYTES_GAS,
+        step::ExecutionState,
+        util::{
+            common_gadget::RestoreContextGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::{IsEqualGadget, LtGadget},
+            rlc, CachedRegion, Cell,
+        },
+    },
+    table::CallContextFieldTag,
+    witness::{Block, ExecStep, Transaction},
+};
+
+/// Length in bytes of the output of a successful call, the compressed state.
+const N_BYTES_BLAKE2F_OUTPUT: u64 = 64;
+
+#[derive(Clone, Debug)]
+pub struct Blake2fGadget<F> {
+    // The first cells are shared with `PrecompileGadget`, which constrains them
+    // against the caller's view of the call. Keep them in this order.
+    output_len: Cell<F>,
+    input_bytes_rlc: Cell<F>,
+    output_bytes_rlc: Cell<F>,
+
+    is_input_len_valid: IsEqualGadget<F>,
+    /// Number of rounds, from the first 4 bytes of the input.
+    rounds: Cell<F>,
+    /// Whether the final block flag is either 0 or 1.
+    is_flag_valid: Cell<F>,
+    /// Rlc of the compressed state, from the BLAKE2F table.
+    compressed_rlc: Cell<F>,
+    insufficient_gas: LtGadget<F, N_BYTES_GAS>,
+
+    is_success: Cell<F>,
+    callee_address: Cell<F>,
+    caller_id: Cell<F>,
+    call_data_offset: Cell<F>,
+    call_data_length: Cell<F>,
+    return_data_offset: Cell<F>,
+    return_data_length: Cell<F>,
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for Blake2fGadget<F> {
+    const EXECUTION_STATE: ExecutionState = ExecutionState::PrecompileBlake2f;
+
+    const NAME: &'static str = "BLAKE2F";
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let (output_len, input_bytes_rlc, output_bytes_rlc) = (
+            cb.query_cell(),
+            cb.query_cell_phase2(),
+            cb.query_cell_phase2(),
+        );
+
+        let [is_success, callee_address, caller_id, call_data_offset, call_data_length, return_data_offset, return_data_length] =
+            [
+                CallContextFieldTag::IsSuccess,
+                CallContextFieldTag::CalleeAddress,
+                CallContextFieldTag::CallerId,
+                CallContextFieldTag::CallDataOffset,
+                CallContextFieldTag::CallDataLength,
+                CallContextFieldTag::ReturnDataOffset,
+                CallContextFieldTag::ReturnDataLength,
+            ]
+            .map(|tag| cb.call_context(None, tag));
+
+        // The whole call data is taken as input, which must be of exactly 213
+        // bytes.  The BLAKE2F circuit then parses the rounds and the final block
+        // flag, and compresses the input if the flag is valid.
+        let is_input_len_valid =
+            IsEqualGadget::construct(cb, call_data_length.expr(), N_BYTES_BLAKE2F_INPUT.expr());
+        let rounds = cb.query_cell();
+        let is_flag_valid = cb.query_bool();
+        let compressed_rlc = cb.query_cell_phase2();
+        cb.condition(is_input_len_valid.expr(), |cb| {
+            cb.blake2f_table_lookup(
+                input_bytes_rlc.expr(),
+                rounds.expr(),
+                is_flag_valid.expr(),
+                compressed_rlc.expr(),
+            );
+        });
+
+        // The gas cost is the number of rounds, the call fails for an invalid
+        // input or insufficient gas, which consumes all the gas left.
+        let gas_left = cb.curr.state.gas_left.expr();
+        let gas = rounds.expr() * GasCost::PRECOMPILE_BLAKE2F_PER_ROUND.expr();
+        let insufficient_gas = LtGadget::construct(cb, gas_left.clone(), gas.clone());
+        cb.require_equal(
+            "call succeeds iff the input is valid and there is enough gas",
+            is_success.expr(),
+            is_input_len_valid.expr() * is_flag_valid.expr() * not::expr(insufficient_gas.expr()),
+        );
+        let gas_cost = select::expr(is_success.expr(), gas, gas_left);
+
+        cb.require_equal(
+            "output length is 64 if the call succeeds, 0 otherwise",
+            output_len.expr(),
+            is_success.expr() * N_BYTES_BLAKE2F_OUTPUT.expr(),
+        );
+        cb.require_equal(
+            "output bytes rlc is the compressed state",
+            output_bytes_rlc.expr(),
+            is_success.expr() * compressed_rlc.expr(),
+        );
+
+        cb.precompile_info_lookup(
+            cb.execution_state().as_u64().expr(),
+            callee_address.expr(),
+            cb.execution_state().precompile_base_gas_cost().expr(),
+        );
+
+        let restore_context = RestoreContextGadget::construct2(
+            cb,
+            is_success.expr(),
+            gas_cost.expr(),
+            0.expr(),
+            0x00.expr(),       // ReturnDataOffset
+            output_len.expr(), // ReturnDataLength
+            0.expr(),
+            0.expr(),
+        );
+
+        Self {
+            output_len,
+            input_bytes_rlc,
+            output_bytes_rlc,
+            is_input_len_valid,
+            rounds,
+            is_flag_valid,
+            compressed_rlc,
+            insufficient_gas,
+            is_success,
+            callee_address,
+            caller_id,
+            call_data_offset,
+            call_data_length,
+            return_data_offset,
+            return_data_length,
+            restore_context,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let aux_data = if let Some(PrecompileAuxData::Blake2F(aux_data)) = &step.aux_data {
+            aux_data
+        } else {
+            unreachable!("must exist for blake2f precompile call")
+        };
+
+        let output_len = if call.is_success {
+            N_BYTES_BLAKE2F_OUTPUT
+        } else {
+            0
+        };
+        self.output_len
+            .assign(region, offset, Value::known(F::from(output_len)))?;
+        let keccak_input = region.challenges().keccak_input();
+        self.input_bytes_rlc.assign(
+            region,
+            offset,
+            keccak_input
+                .map(|randomness| rlc::value(aux_data.input_bytes.iter().rev(), randomness)),
+        )?;
+        self.output_bytes_rlc.assign(
+            region,
+            offset,
+            keccak_input
+                .map(|randomness| rlc::value(aux_data.output_bytes.iter().rev(), randomness)),
+        )?;
+
+        self.is_input_len_valid.assign(
+            region,
+            offset,
+            F::from(call.call_data_length),
+            F::from(N_BYTES_BLAKE2F_INPUT as u64),
+        )?;
+        let rounds = aux_data.rounds() as u64;
+        self.rounds
+            .assign(region, offset, Value::known(F::from(rounds)))?;
+        let is_flag_valid = aux_data.input_bytes.len() == N_BYTES_BLAKE2F_INPUT
+            && aux_data.input_bytes[N_BYTES_BLAKE2F_INPUT - 1] <= 1;
+        self.is_flag_valid
+            .assign(region, offset, Value::known(F::from(is_flag_valid as u64)))?;
+        self.compressed_rlc.assign(
+            region,
+            offset,
+            keccak_input.map(|randomness| rlc::value(aux_data.compressed.iter().rev(), randomness)),
+        )?;
+        self.insufficient_gas.assign(
+            region,
+            offset,
+            F::from(step.gas_left),
+            F::from(rounds * GasCost::PRECOMPILE_BLAKE2F_PER_ROUND),
+        )?;
+
+        self.is_success.assign(
+            region,
+            offset,
+            Value::known(F::from(u64::from(call.is_success))),
+        )?;
+        self.callee_address.assign(
+            region,
+            offset,
+            Value::known(call.code_address().unwrap().to_scalar().unwrap()),
+        )?;
+        self.caller_id.assign(
+            region,
+            offset,
+            Value::known(F::from(call.caller_id.try_into().unwrap())),
+        )?;
+        self.call_data_offset.assign(
+            region,
+            offset,
+            Value::known(F::from(call.call_data_offset)),
+        )?;
+        self.call_data_length.assign(
+            region,
+            offset,
+            Value::known(F::from(call.call_data_length)),
+        )?;
+        self.return_data_offset.assign(
+            region,
+            offset,
+            Value::known(F::from(call.return_data_offset)),
+        )?;
+        self.return_data_length.assign(
+            region,
+            offset,
+            Value::known(F::from(call.return_data_length)),
+        )?;
+        self.restore_context
+            .assign(region, offset, block, call, step, 7)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bus_mapping::{
+        evm::{OpcodeId, PrecompileCallArgs},
+        precompile::{PrecompileCalls, N_BYTES_BLAKE2F_INPUT},
+    };
+    use eth_types::{Bytecode, ToWord, Word};
+    use itertools::Itertools;
+    use mock::TestContext;
+
+    use crate::test_util::CircuitTestBuilder;
+
+    /// Stores at memory offset 0 the input of the EIP-152 test vectors, i.e.
+    /// the compression of "abc" with the given rounds and final block flag.
+    fn setup_code(rounds: u32, f: u8) -> Bytecode {
+        let mut input = rounds.to_be_bytes().to_vec();
+        input.extend(
+            hex::decode(
+                "48c9bdf267e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5\
+                 d182e6ad7f520e511f6c3e2b8c68059b6bbd41fbabd9831f79217e1319cde05b",
+            )
+            .unwrap(),
+        );
+        input.extend(b"abc");
+        input.resize(4 + 64 + 128, 0);
+        input.extend(3u64.to_le_bytes());
+        input.extend(0u64.to_le_bytes());
+        input.push(f);
+        assert_eq!(input.len(), N_BYTES_BLAKE2F_INPUT);
+
+        input.resize(7 * 32, 0);
+        let mut code = Bytecode::default();
+        for (i, chunk) in input.chunks(32).enumerate() {
+            code.push(32, Word::from_big_endian(chunk))
+                .push(32, Word::from(i * 32))
+                .write_op(OpcodeId::MSTORE);
+        }
+        code
+    }
+
+    lazy_static::lazy_static! {
+        static ref TEST_VECTOR: Vec<PrecompileCallArgs> = {
+            vec![
+                PrecompileCallArgs {
+                    name: "12 rounds, final block",
+                    setup_code: setup_code(12, 1),
+                    call_data_offset: 0x00.into(),
+                    call_data_length: N_BYTES_BLAKE2F_INPUT.into(),
+                    ret_offset: 0x00.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::Blake2F.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "zero rounds, output partially returned",
+                    setup_code: setup_code(0, 1),
+                    call_data_offset: 0x00.into(),
+                    call_data_length: N_BYTES_BLAKE2F_INPUT.into(),
+                    ret_offset: 0x20.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::Blake2F.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "not the final block",
+                    setup_code: setup_code(12, 0),
+                    call_data_offset: 0x00.into(),
+                    call_data_length: N_BYTES_BLAKE2F_INPUT.into(),
+                    ret_offset: 0x00.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::Blake2F.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "invalid input length",
+                    setup_code: setup_code(12, 1),
+                    call_data_offset: 0x00.into(),
+                    call_data_length: (N_BYTES_BLAKE2F_INPUT - 1).into(),
+                    ret_offset: 0x00.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::Blake2F.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "invalid final block flag",
+                    setup_code: setup_code(12, 2),
+                    call_data_offset: 0x00.into(),
+                    call_data_length: N_BYTES_BLAKE2F_INPUT.into(),
+                    ret_offset: 0x00.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::Blake2F.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "insufficient gas",
+                    setup_code: setup_code(12, 1),
+                    call_data_offset: 0x00.into(),
+                    call_data_length: N_BYTES_BLAKE2F_INPUT.into(),
+                    ret_offset: 0x00.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::Blake2F.address().to_word(),
+                    gas: 0x0b.into(),
+                    ..Default::default()
+                },
+            ]
+        };
+    }
+
+    #[test]
+    fn precompile_blake2f_test() {
+        let call_kinds = vec![
+            OpcodeId::CALL,
+            OpcodeId::STATICCALL,
+            OpcodeId::DELEGATECALL,
+            OpcodeId::CALLCODE,
+        ];
+
+        for (test_vector, &call_kind) in TEST_VECTOR.iter().cartesian_product(&call_kinds) {
+            let bytecode = test_vector.with_call_op(call_kind);
+
+            CircuitTestBuilder::new_from_test_ctx(
+                TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+            )
+            .run();
+        }
+    }
+}
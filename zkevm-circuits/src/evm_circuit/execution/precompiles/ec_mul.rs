@@ -0,0 +1,501 @@
+use bus_mapping::{
+    circuit_input_builder::Call,
+    precompile::{PrecompileAuxData, PrecompileCalls, BN254_FQ_MODULUS, BN254_FR_MODULUS},
+};
+use eth_types::{evm_types::GasCost, Field, ToBigEndian, ToScalar, U256};
+use gadgets::util::{select, Expr};
+use halo2_proofs::{
+    circuit::Value,
+    plonk::{Error, Expression},
+};
+
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::N_BYTES_U64,
+        step::ExecutionState,
+        util::{
+            common_gadget::RestoreContextGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::{IsEqualGadget, LtWordGadget, MinMaxGadget, RandPowGadget},
+            pow_of_two_expr, rlc, CachedRegion, Cell,
+        },
+    },
+    table::{CallContextFieldTag, EccOpType},
+    util::word::{Word, Word32Cell, WordExpr},
+    witness::{Block, ExecStep, Transaction},
+};
+
+/// Number of bits needed to represent the zero padding of the input, which is
+/// at most 96 bytes.
+const N_BITS_PADDING: usize = 7;
+
+#[derive(Clone, Debug)]
+pub struct EcMulGadget<F> {
+    // The first cells are shared with `PrecompileGadget`, which constrains them
+    // against the caller's view of the call. Keep them in this order.
+    input_len: Cell<F>,
+    output_len: Cell<F>,
+    input_bytes_rlc: Cell<F>,
+    output_bytes_rlc: Cell<F>,
+
+    p_x: Word32Cell<F>,
+    p_y: Word32Cell<F>,
+    s_raw: Word32Cell<F>,
+    s: Word32Cell<F>,
+    /// Quotient of the division of `s_raw` by the curve order, at most 5.
+    s_overflow: Cell<F>,
+    /// Carry from the low to the high half of `s + s_overflow * n`.
+    s_carry: Cell<F>,
+    s_lt_n: LtWordGadget<F>,
+    r_x: Word32Cell<F>,
+    r_y: Word32Cell<F>,
+    /// Whether each coordinate of the point is lower than the field modulus.
+    coordinates_lt_modulus: [LtWordGadget<F>; 2],
+    all_coordinates_lt_modulus: IsEqualGadget<F>,
+    /// Whether the point is on the curve, as verified by the ECC circuit.
+    is_valid: Cell<F>,
+
+    input_len_min: MinMaxGadget<F, N_BYTES_U64>,
+    /// `r^(96 - input_len)`, to right pad the input with zero bytes.
+    padding: RandPowGadget<F, N_BITS_PADDING>,
+
+    is_success: Cell<F>,
+    callee_address: Cell<F>,
+    caller_id: Cell<F>,
+    call_data_offset: Cell<F>,
+    call_data_length: Cell<F>,
+    return_data_offset: Cell<F>,
+    return_data_length: Cell<F>,
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for EcMulGadget<F> {
+    const EXECUTION_STATE: ExecutionState = ExecutionState::PrecompileBn256ScalarMul;
+
+    const NAME: &'static str = "ECMUL";
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let (input_len, output_len, input_bytes_rlc, output_bytes_rlc) = (
+            cb.query_cell(),
+            cb.query_cell(),
+            cb.query_cell_phase2(),
+            cb.query_cell_phase2(),
+        );
+
+        let p_x = cb.query_word32();
+        let p_y = cb.query_word32();
+        let s_raw = cb.query_word32();
+        let s = cb.query_word32();
+        let s_overflow = cb.query_cell();
+        let s_carry = cb.query_cell();
+        let r_x = cb.query_word32();
+        let r_y = cb.query_word32();
+        let is_valid = cb.query_bool();
+
+        let [is_success, callee_address, caller_id, call_data_offset, call_data_length, return_data_offset, return_data_length] =
+            [
+                CallContextFieldTag::IsSuccess,
+                CallContextFieldTag::CalleeAddress,
+                CallContextFieldTag::CallerId,
+                CallContextFieldTag::CallDataOffset,
+                CallContextFieldTag::CallDataLength,
+                CallContextFieldTag::ReturnDataOffset,
+                CallContextFieldTag::ReturnDataLength,
+            ]
+            .map(|tag| cb.call_context(None, tag));
+
+        // The precompile takes the first 96 bytes of call data, right padded with
+        // zeroes.
+        let input_len_min = MinMaxGadget::construct(cb, call_data_length.expr(), 96.expr());
+        cb.require_equal(
+            "input length is min(call data length, 96)",
+            input_len.expr(),
+            input_len_min.min(),
+        );
+        let padding = RandPowGadget::construct(cb, 96.expr() - input_len.expr());
+
+        // The input is laid out as p_x, p_y, s, each a 32 bytes big-endian word,
+        // and the rlc is computed with the least significant byte at power 0.
+        let keccak_input = cb.challenges().keccak_input();
+        let input_bytes = [&s_raw, &p_y, &p_x]
+            .iter()
+            .flat_map(|word| word.limbs.iter().map(|cell| cell.expr()))
+            .collect::<Vec<_>>();
+        cb.require_equal(
+            "input bytes rlc padded to 96 bytes",
+            input_bytes_rlc.expr() * padding.expr(),
+            rlc::expr(&input_bytes, keccak_input.clone()),
+        );
+
+        // The ecc table holds the scalar reduced modulo the curve order:
+        // s_raw = s + overflow * n, where s < n.  As n is larger than 2^253, the
+        // overflow and the carry are both lower than 16.
+        let bn254_n = Word::<F>::from(*BN254_FR_MODULUS).map(Expression::Constant);
+        let (raw_lo, raw_hi) = s_raw.to_word().to_lo_hi();
+        let (reduced_lo, reduced_hi) = s.to_word().to_lo_hi();
+        cb.range_lookup(s_overflow.expr(), 16);
+        cb.range_lookup(s_carry.expr(), 16);
+        cb.require_equal(
+            "s_raw lo == s lo + overflow * n lo",
+            raw_lo + s_carry.expr() * pow_of_two_expr(128),
+            reduced_lo + s_overflow.expr() * bn254_n.lo(),
+        );
+        cb.require_equal(
+            "s_raw hi == s hi + overflow * n hi + carry",
+            raw_hi,
+            reduced_hi + s_overflow.expr() * bn254_n.hi() + s_carry.expr(),
+        );
+        let s_lt_n = LtWordGadget::construct(cb, &s.to_word(), &bn254_n);
+        cb.require_equal("s < n", s_lt_n.expr(), 1.expr());
+
+        // A coordinate out of the field is rejected, otherwise the ECC circuit
+        // verifies whether the point is on the curve and computes the product.
+        let modulus = Word::<F>::from(*BN254_FQ_MODULUS).map(Expression::Constant);
+        let coordinates_lt_modulus = [&p_x, &p_y]
+            .map(|coordinate| LtWordGadget::construct(cb, &coordinate.to_word(), &modulus));
+        let all_coordinates_lt_modulus = IsEqualGadget::construct(
+            cb,
+            coordinates_lt_modulus[0].expr() + coordinates_lt_modulus[1].expr(),
+            2.expr(),
+        );
+        cb.condition(all_coordinates_lt_modulus.expr(), |cb| {
+            cb.ecc_table_lookup(
+                EccOpType::Mul.expr(),
+                p_x.to_word(),
+                p_y.to_word(),
+                s.to_word(),
+                Word::zero(),
+                0.expr(),
+                0.expr(),
+                r_x.to_word(),
+                r_y.to_word(),
+                is_valid.expr(),
+            );
+        });
+        cb.require_equal(
+            "call succeeds iff the point is valid",
+            is_success.expr(),
+            all_coordinates_lt_modulus.expr() * is_valid.expr(),
+        );
+
+        // The product is returned as two 32 bytes big-endian words.
+        cb.require_equal(
+            "output length is 64 bytes on success",
+            output_len.expr(),
+            is_success.expr() * 64.expr(),
+        );
+        let output_bytes = [&r_y, &r_x]
+            .iter()
+            .flat_map(|word| word.limbs.iter().map(|cell| cell.expr()))
+            .collect::<Vec<_>>();
+        cb.require_equal(
+            "output bytes rlc is the product",
+            output_bytes_rlc.expr(),
+            is_success.expr() * rlc::expr(&output_bytes, keccak_input),
+        );
+
+        let gas_cost = select::expr(
+            is_success.expr(),
+            GasCost::PRECOMPILE_BN256MUL.expr(),
+            cb.curr.state.gas_left.expr(),
+        );
+
+        cb.precompile_info_lookup(
+            cb.execution_state().as_u64().expr(),
+            callee_address.expr(),
+            cb.execution_state().precompile_base_gas_cost().expr(),
+        );
+
+        // Insufficient gas is handled in the ErrorOogPrecompile gadget, an invalid
+        // point consumes all the gas and returns no data.
+        let restore_context = RestoreContextGadget::construct2(
+            cb,
+            is_success.expr(),
+            gas_cost.expr(),
+            0.expr(),
+            0x00.expr(),       // ReturnDataOffset
+            output_len.expr(), // ReturnDataLength
+            0.expr(),
+            0.expr(),
+        );
+
+        Self {
+            input_len,
+            output_len,
+            input_bytes_rlc,
+            output_bytes_rlc,
+            p_x,
+            p_y,
+            s_raw,
+            s,
+            s_overflow,
+            s_carry,
+            s_lt_n,
+            r_x,
+            r_y,
+            coordinates_lt_modulus,
+            all_coordinates_lt_modulus,
+            is_valid,
+            input_len_min,
+            padding,
+            is_success,
+            callee_address,
+            caller_id,
+            call_data_offset,
+            call_data_length,
+            return_data_offset,
+            return_data_length,
+            restore_context,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let aux_data = if let Some(PrecompileAuxData::EcMul(aux_data)) = &step.aux_data {
+            aux_data
+        } else {
+            unreachable!("must exist for ecMul precompile call")
+        };
+
+        let input_len = call
+            .call_data_length
+            .min(PrecompileCalls::Bn128Mul.input_len().unwrap() as u64);
+        self.input_len
+            .assign(region, offset, Value::known(F::from(input_len)))?;
+        let output_len = if call.is_success { 64 } else { 0 };
+        self.output_len
+            .assign(region, offset, Value::known(F::from(output_len)))?;
+        self.input_len_min
+            .assign(region, offset, F::from(call.call_data_length), F::from(96))?;
+        self.padding
+            .assign(region, offset, 96 - input_len as usize)?;
+
+        let input_bytes = [aux_data.p_x, aux_data.p_y, aux_data.s_raw]
+            .iter()
+            .flat_map(|word| word.to_be_bytes())
+            .collect::<Vec<_>>();
+        let keccak_input = region.challenges().keccak_input();
+        self.input_bytes_rlc.assign(
+            region,
+            offset,
+            keccak_input.map(|randomness| {
+                rlc::value(input_bytes[..input_len as usize].iter().rev(), randomness)
+            }),
+        )?;
+        let output_bytes = [aux_data.r_x, aux_data.r_y]
+            .iter()
+            .flat_map(|word| word.to_be_bytes())
+            .collect::<Vec<_>>();
+        self.output_bytes_rlc.assign(
+            region,
+            offset,
+            if call.is_success {
+                keccak_input.map(|randomness| rlc::value(output_bytes.iter().rev(), randomness))
+            } else {
+                Value::known(F::ZERO)
+            },
+        )?;
+
+        let s = aux_data.s();
+        let s_overflow = aux_data.s_raw / *BN254_FR_MODULUS;
+        let s_carry = ((U256::from(s.low_u128())
+            + s_overflow * U256::from(BN254_FR_MODULUS.low_u128()))
+            >> 128)
+            .as_u64();
+        self.p_x.assign_u256(region, offset, aux_data.p_x)?;
+        self.p_y.assign_u256(region, offset, aux_data.p_y)?;
+        self.s_raw.assign_u256(region, offset, aux_data.s_raw)?;
+        self.s.assign_u256(region, offset, s)?;
+        self.s_overflow
+            .assign(region, offset, Value::known(F::from(s_overflow.as_u64())))?;
+        self.s_carry
+            .assign(region, offset, Value::known(F::from(s_carry)))?;
+        self.s_lt_n.assign(region, offset, s, *BN254_FR_MODULUS)?;
+        self.r_x.assign_u256(region, offset, aux_data.r_x)?;
+        self.r_y.assign_u256(region, offset, aux_data.r_y)?;
+
+        let n_lt_modulus = self
+            .coordinates_lt_modulus
+            .iter()
+            .zip([aux_data.p_x, aux_data.p_y])
+            .map(|(lt, coordinate)| lt.assign(region, offset, coordinate, *BN254_FQ_MODULUS))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .sum::<F>();
+        self.all_coordinates_lt_modulus
+            .assign(region, offset, n_lt_modulus, F::from(2))?;
+        // The call only fails on invalid points here, which are rejected by the
+        // ECC circuit when the coordinates are in the field.
+        self.is_valid.assign(
+            region,
+            offset,
+            Value::known(F::from(u64::from(call.is_success))),
+        )?;
+
+        self.is_success.assign(
+            region,
+            offset,
+            Value::known(F::from(u64::from(call.is_success))),
+        )?;
+        self.callee_address.assign(
+            region,
+            offset,
+            Value::known(call.code_address().unwrap().to_scalar().unwrap()),
+        )?;
+        self.caller_id.assign(
+            region,
+            offset,
+            Value::known(F::from(call.caller_id.try_into().unwrap())),
+        )?;
+        self.call_data_offset.assign(
+            region,
+            offset,
+            Value::known(F::from(call.call_data_offset)),
+        )?;
+        self.call_data_length.assign(
+            region,
+            offset,
+            Value::known(F::from(call.call_data_length)),
+        )?;
+        self.return_data_offset.assign(
+            region,
+            offset,
+            Value::known(F::from(call.return_data_offset)),
+        )?;
+        self.return_data_length.assign(
+            region,
+            offset,
+            Value::known(F::from(call.return_data_length)),
+        )?;
+        self.restore_context
+            .assign(region, offset, block, call, step, 7)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bus_mapping::{
+        evm::{OpcodeId, PrecompileCallArgs},
+        precompile::PrecompileCalls,
+    };
+    use eth_types::{bytecode, word, ToWord};
+    use itertools::Itertools;
+    use mock::TestContext;
+
+    use crate::test_util::CircuitTestBuilder;
+
+    lazy_static::lazy_static! {
+        static ref TEST_VECTOR: Vec<PrecompileCallArgs> = {
+            vec![
+                PrecompileCallArgs {
+                    name: "2 * G1",
+                    setup_code: bytecode! {
+                        // P = G1 = (1, 2)
+                        PUSH1(0x01)
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH1(0x02)
+                        PUSH1(0x20)
+                        MSTORE
+                        // s = 2
+                        PUSH1(0x02)
+                        PUSH1(0x40)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x60.into(),
+                    ret_offset: 0x60.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::Bn128Mul.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "scalar larger than the curve order",
+                    setup_code: bytecode! {
+                        PUSH1(0x01)
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH1(0x02)
+                        PUSH1(0x20)
+                        MSTORE
+                        PUSH32(word!("0xffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"))
+                        PUSH1(0x40)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x60.into(),
+                    ret_offset: 0x60.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::Bn128Mul.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "truncated input, zero scalar",
+                    setup_code: bytecode! {
+                        PUSH1(0x01)
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH1(0x02)
+                        PUSH1(0x20)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x40.into(),
+                    ret_offset: 0x60.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::Bn128Mul.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "point not on the curve",
+                    setup_code: bytecode! {
+                        PUSH1(0x01)
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH1(0x03)
+                        PUSH1(0x20)
+                        MSTORE
+                        PUSH1(0x02)
+                        PUSH1(0x40)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x60.into(),
+                    ret_offset: 0x60.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::Bn128Mul.address().to_word(),
+                    ..Default::default()
+                },
+            ]
+        };
+    }
+
+    #[test]
+    fn precompile_ec_mul_test() {
+        let call_kinds = vec![
+            OpcodeId::CALL,
+            OpcodeId::STATICCALL,
+            OpcodeId::DELEGATECALL,
+            OpcodeId::CALLCODE,
+        ];
+
+        for (test_vector, &call_kind) in TEST_VECTOR.iter().cartesian_product(&call_kinds) {
+            let bytecode = test_vector.with_call_op(call_kind);
+
+            CircuitTestBuilder::new_from_test_ctx(
+                TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+            )
+            .run();
+        }
+    }
+}
@@ -0,0 +1,328 @@
+use bus_mapping::{
+    circuit_input_builder::Call,
+    precompile::{PrecompileAuxData, N_BYTES_EC_PAIR},
+};
+use eth_types::{evm_types::GasCost, Field, ToScalar};
+use gadgets::util::{select, Expr};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::N_BYTES_U64,
+        step::ExecutionState,
+        util::{
+            common_gadget::RestoreContextGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::{ConstantDivisionGadget, IsZeroGadget},
+            rlc, CachedRegion, Cell,
+        },
+    },
+    table::{CallContextFieldTag, EccOpType},
+    util::word::Word,
+    witness::{Block, ExecStep, Transaction},
+};
+
+#[derive(Clone, Debug)]
+pub struct EcPairingGadget<F> {
+    // The first cells are shared with `PrecompileGadget`, which constrains them
+    // against the caller's view of the call. Keep them in this order.
+    input_len: Cell<F>,
+    output_len: Cell<F>,
+    input_bytes_rlc: Cell<F>,
+    output_bytes_rlc: Cell<F>,
+
+    /// Number of pairs of G1 and G2 points, the remainder must be zero.
+    n_pairs: ConstantDivisionGadget<F, N_BYTES_U64>,
+    is_len_valid: IsZeroGadget<F>,
+    /// Result of the pairing check.
+    output: Cell<F>,
+    /// Whether the points are valid, as verified by the ECC circuit.
+    is_valid: Cell<F>,
+
+    is_success: Cell<F>,
+    callee_address: Cell<F>,
+    caller_id: Cell<F>,
+    call_data_offset: Cell<F>,
+    call_data_length: Cell<F>,
+    return_data_offset: Cell<F>,
+    return_data_length: Cell<F>,
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
+    const EXECUTION_STATE: ExecutionState = ExecutionState::PrecompileBn256Pairing;
+
+    const NAME: &'static str = "ECPAIRING";
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let (input_len, output_len, input_bytes_rlc, output_bytes_rlc) = (
+            cb.query_cell(),
+            cb.query_cell(),
+            cb.query_cell_phase2(),
+            cb.query_cell_phase2(),
+        );
+
+        let output = cb.query_bool();
+        let is_valid = cb.query_bool();
+
+        let [is_success, callee_address, caller_id, call_data_offset, call_data_length, return_data_offset, return_data_length] =
+            [
+                CallContextFieldTag::IsSuccess,
+                CallContextFieldTag::CalleeAddress,
+                CallContextFieldTag::CallerId,
+                CallContextFieldTag::CallDataOffset,
+                CallContextFieldTag::CallDataLength,
+                CallContextFieldTag::ReturnDataOffset,
+                CallContextFieldTag::ReturnDataLength,
+            ]
+            .map(|tag| cb.call_context(None, tag));
+
+        // The precompile takes the whole call data, which must hold a whole number
+        // of pairs.
+        cb.require_equal(
+            "input length is the call data length",
+            input_len.expr(),
+            call_data_length.expr(),
+        );
+        let n_pairs =
+            ConstantDivisionGadget::construct(cb, call_data_length.expr(), N_BYTES_EC_PAIR as u64);
+        let is_len_valid = IsZeroGadget::construct(cb, n_pairs.remainder());
+
+        // The ECC circuit verifies whether the points are valid and computes the
+        // result of the pairing check over the input bytes.
+        cb.condition(is_len_valid.expr(), |cb| {
+            cb.ecc_table_lookup(
+                EccOpType::Pairing.expr(),
+                Word::zero(),
+                Word::zero(),
+                Word::zero(),
+                Word::zero(),
+                input_bytes_rlc.expr(),
+                input_len.expr(),
+                Word::from_lo_unchecked(output.expr()),
+                Word::zero(),
+                is_valid.expr(),
+            );
+        });
+        cb.require_equal(
+            "call succeeds iff the length and the points are valid",
+            is_success.expr(),
+            is_len_valid.expr() * is_valid.expr(),
+        );
+
+        // The result is returned as a 32 bytes big-endian word, whose rlc is the
+        // result itself.
+        cb.require_equal(
+            "output length is 32 bytes on success",
+            output_len.expr(),
+            is_success.expr() * 32.expr(),
+        );
+        cb.require_equal(
+            "output bytes rlc is the result",
+            output_bytes_rlc.expr(),
+            is_success.expr() * output.expr(),
+        );
+
+        let gas_cost = select::expr(
+            is_success.expr(),
+            GasCost::PRECOMPILE_BN256PAIRING.expr()
+                + n_pairs.quotient() * GasCost::PRECOMPILE_BN256PAIRING_PER_PAIR.expr(),
+            cb.curr.state.gas_left.expr(),
+        );
+
+        cb.precompile_info_lookup(
+            cb.execution_state().as_u64().expr(),
+            callee_address.expr(),
+            cb.execution_state().precompile_base_gas_cost().expr(),
+        );
+
+        // Insufficient gas is handled in the ErrorOogPrecompile gadget, an invalid
+        // input consumes all the gas and returns no data.
+        let restore_context = RestoreContextGadget::construct2(
+            cb,
+            is_success.expr(),
+            gas_cost.expr(),
+            0.expr(),
+            0x00.expr(),       // ReturnDataOffset
+            output_len.expr(), // ReturnDataLength
+            0.expr(),
+            0.expr(),
+        );
+
+        Self {
+            input_len,
+            output_len,
+            input_bytes_rlc,
+            output_bytes_rlc,
+            n_pairs,
+            is_len_valid,
+            output,
+            is_valid,
+            is_success,
+            callee_address,
+            caller_id,
+            call_data_offset,
+            call_data_length,
+            return_data_offset,
+            return_data_length,
+            restore_context,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let aux_data = if let Some(PrecompileAuxData::EcPairing(aux_data)) = &step.aux_data {
+            aux_data
+        } else {
+            unreachable!("must exist for ecPairing precompile call")
+        };
+
+        self.input_len
+            .assign(region, offset, Value::known(F::from(call.call_data_length)))?;
+        let output_len = if call.is_success { 32 } else { 0 };
+        self.output_len
+            .assign(region, offset, Value::known(F::from(output_len)))?;
+        let keccak_input = region.challenges().keccak_input();
+        self.input_bytes_rlc.assign(
+            region,
+            offset,
+            keccak_input
+                .map(|randomness| rlc::value(aux_data.input_bytes.iter().rev(), randomness)),
+        )?;
+        let output = aux_data.output.as_u64();
+        self.output_bytes_rlc
+            .assign(region, offset, Value::known(F::from(output)))?;
+
+        let (_, remainder) = self
+            .n_pairs
+            .assign(region, offset, call.call_data_length as u128)?;
+        self.is_len_valid
+            .assign(region, offset, F::from(remainder as u64))?;
+        self.output
+            .assign(region, offset, Value::known(F::from(output)))?;
+        // The call only fails on invalid points here, which are rejected by the
+        // ECC circuit when the input length is valid.
+        self.is_valid.assign(
+            region,
+            offset,
+            Value::known(F::from(u64::from(call.is_success))),
+        )?;
+
+        self.is_success.assign(
+            region,
+            offset,
+            Value::known(F::from(u64::from(call.is_success))),
+        )?;
+        self.callee_address.assign(
+            region,
+            offset,
+            Value::known(call.code_address().unwrap().to_scalar().unwrap()),
+        )?;
+        self.caller_id.assign(
+            region,
+            offset,
+            Value::known(F::from(call.caller_id.try_into().unwrap())),
+        )?;
+        self.call_data_offset.assign(
+            region,
+            offset,
+            Value::known(F::from(call.call_data_offset)),
+        )?;
+        self.call_data_length.assign(
+            region,
+            offset,
+            Value::known(F::from(call.call_data_length)),
+        )?;
+        self.return_data_offset.assign(
+            region,
+            offset,
+            Value::known(F::from(call.return_data_offset)),
+        )?;
+        self.return_data_length.assign(
+            region,
+            offset,
+            Value::known(F::from(call.return_data_length)),
+        )?;
+        self.restore_context
+            .assign(region, offset, block, call, step, 7)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bus_mapping::{
+        evm::{OpcodeId, PrecompileCallArgs},
+        precompile::PrecompileCalls,
+    };
+    use eth_types::{bytecode, ToWord};
+    use itertools::Itertools;
+    use mock::TestContext;
+
+    use crate::test_util::CircuitTestBuilder;
+
+    lazy_static::lazy_static! {
+        static ref TEST_VECTOR: Vec<PrecompileCallArgs> = {
+            vec![
+                PrecompileCallArgs {
+                    name: "empty input",
+                    setup_code: bytecode! {},
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x00.into(),
+                    ret_offset: 0x00.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::Bn128Pairing.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "single pair of identity points",
+                    setup_code: bytecode! {},
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0xc0.into(),
+                    ret_offset: 0x00.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::Bn128Pairing.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "input length not a multiple of 192",
+                    setup_code: bytecode! {},
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x40.into(),
+                    ret_offset: 0x00.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::Bn128Pairing.address().to_word(),
+                    ..Default::default()
+                },
+            ]
+        };
+    }
+
+    #[test]
+    fn precompile_ec_pairing_test() {
+        let call_kinds = vec![
+            OpcodeId::CALL,
+            OpcodeId::STATICCALL,
+            OpcodeId::DELEGATECALL,
+            OpcodeId::CALLCODE,
+        ];
+
+        for (test_vector, &call_kind) in TEST_VECTOR.iter().cartesian_product(&call_kinds) {
+            let bytecode = test_vector.with_call_op(call_kind);
+
+            CircuitTestBuilder::new_from_test_ctx(
+                TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+            )
+            .run();
+        }
+    }
+}
@@ -0,0 +1,714 @@
+use bus_mapping::{
+    circuit_input_builder::Call,
+    precompile::{PrecompileAuxData, PrecompileCalls},
+};
+use eth_types::{evm_types::GasCost, word, Field, ToBigEndian, ToScalar, U256};
+use gadgets::util::{not, select, Expr};
+use halo2_proofs::{
+    circuit::Value,
+    plonk::{Error, Expression},
+};
+
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::N_BYTES_U64,
+        step::ExecutionState,
+        util::{
+            common_gadget::RestoreContextGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::{IsEqualGadget, IsZeroGadget, LtWordGadget, MinMaxGadget, RandPowGadget},
+            pow_of_two_expr, rlc, AccountAddress, CachedRegion, Cell,
+        },
+    },
+    table::CallContextFieldTag,
+    util::word::{Word, Word32Cell, WordExpr},
+    witness::{Block, ExecStep, Transaction},
+};
+
+lazy_static::lazy_static! {
+    /// Order of the secp256k1 curve, the signature's message hash is reduced modulo it.
+    static ref SECP256K1_N: U256 =
+        word!("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141");
+}
+
+/// Number of bits needed to represent the zero padding of the input, which is
+/// at most 128 bytes.
+const N_BITS_PADDING: usize = 8;
+
+#[derive(Clone, Debug)]
+pub struct EcrecoverGadget<F> {
+    // The first cells are shared with `PrecompileGadget`, which constrains them
+    // against the caller's view of the call. Keep them in this order.
+    is_recovered: Cell<F>,
+    input_len: Cell<F>,
+    input_bytes_rlc: Cell<F>,
+    output_bytes_rlc: Cell<F>,
+
+    msg_hash_raw: Word32Cell<F>,
+    msg_hash: Word32Cell<F>,
+    msg_hash_overflow: Cell<F>,
+    msg_hash_carry: Cell<F>,
+    msg_hash_lt_n: LtWordGadget<F>,
+    sig_v: Word32Cell<F>,
+    sig_r: Word32Cell<F>,
+    sig_s: Word32Cell<F>,
+    recovered_addr: AccountAddress<F>,
+
+    sig_v_hi_is_zero: IsZeroGadget<F>,
+    sig_v_is_27: IsEqualGadget<F>,
+    sig_v_is_28: IsEqualGadget<F>,
+    sig_r_is_zero: IsZeroGadget<F>,
+    sig_r_lt_n: LtWordGadget<F>,
+    sig_s_is_zero: IsZeroGadget<F>,
+    sig_s_lt_n: LtWordGadget<F>,
+    is_valid_sig_v: Cell<F>,
+    is_valid_sig_r: Cell<F>,
+    is_valid_sig_s: Cell<F>,
+    /// v is 27 or 28 and 0 < r, s < n, which is required to recover an
+    /// address.
+    is_valid_sig: Cell<F>,
+
+    input_len_min: MinMaxGadget<F, N_BYTES_U64>,
+    /// `r^(128 - input_len)`, to right pad the input with zero bytes.
+    padding: RandPowGadget<F, N_BITS_PADDING>,
+
+    is_success: Cell<F>,
+    callee_address: Cell<F>,
+    caller_id: Cell<F>,
+    call_data_offset: Cell<F>,
+    call_data_length: Cell<F>,
+    return_data_offset: Cell<F>,
+    return_data_length: Cell<F>,
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for EcrecoverGadget<F> {
+    const EXECUTION_STATE: ExecutionState = ExecutionState::PrecompileEcRecover;
+
+    const NAME: &'static str = "ECRECOVER";
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let (is_recovered, input_len, input_bytes_rlc, output_bytes_rlc) = (
+            cb.query_bool(),
+            cb.query_cell(),
+            cb.query_cell_phase2(),
+            cb.query_cell_phase2(),
+        );
+
+        let msg_hash_raw = cb.query_word32();
+        let msg_hash = cb.query_word32();
+        let msg_hash_overflow = cb.query_bool();
+        let msg_hash_carry = cb.query_bool();
+        let sig_v = cb.query_word32();
+        let sig_r = cb.query_word32();
+        let sig_s = cb.query_word32();
+        let recovered_addr = cb.query_account_address();
+
+        let [is_success, callee_address, caller_id, call_data_offset, call_data_length, return_data_offset, return_data_length] =
+            [
+                CallContextFieldTag::IsSuccess,
+                CallContextFieldTag::CalleeAddress,
+                CallContextFieldTag::CallerId,
+                CallContextFieldTag::CallDataOffset,
+                CallContextFieldTag::CallDataLength,
+                CallContextFieldTag::ReturnDataOffset,
+                CallContextFieldTag::ReturnDataLength,
+            ]
+            .map(|tag| cb.call_context(None, tag));
+
+        // The precompile takes the first 128 bytes of call data, right padded with
+        // zeroes.
+        let input_len_min = MinMaxGadget::construct(cb, call_data_length.expr(), 128.expr());
+        cb.require_equal(
+            "input length is min(call data length, 128)",
+            input_len.expr(),
+            input_len_min.min(),
+        );
+
+        let padding = RandPowGadget::construct(cb, 128.expr() - input_len.expr());
+
+        // The input is laid out as msg_hash, v, r, s, each a 32 bytes big-endian word,
+        // and the rlc is computed with the least significant byte at power 0.
+        let input_bytes = [&sig_s, &sig_r, &sig_v, &msg_hash_raw]
+            .iter()
+            .flat_map(|word| word.limbs.iter().map(|cell| cell.expr()))
+            .collect::<Vec<_>>();
+        cb.require_equal(
+            "input bytes rlc padded to 128 bytes",
+            input_bytes_rlc.expr() * padding.expr(),
+            rlc::expr(&input_bytes, cb.challenges().keccak_input()),
+        );
+
+        // The sig table holds the message hash reduced modulo the curve order:
+        // msg_hash_raw = msg_hash + overflow * n, where msg_hash < n.
+        let secp256k1_n = Word::<F>::from(*SECP256K1_N).map(Expression::Constant);
+        let (raw_lo, raw_hi) = msg_hash_raw.to_word().to_lo_hi();
+        let (reduced_lo, reduced_hi) = msg_hash.to_word().to_lo_hi();
+        cb.require_equal(
+            "msg_hash_raw lo == msg_hash lo + overflow * n lo",
+            raw_lo + msg_hash_carry.expr() * pow_of_two_expr(128),
+            reduced_lo + msg_hash_overflow.expr() * secp256k1_n.lo(),
+        );
+        cb.require_equal(
+            "msg_hash_raw hi == msg_hash hi + overflow * n hi + carry",
+            raw_hi,
+            reduced_hi + msg_hash_overflow.expr() * secp256k1_n.hi() + msg_hash_carry.expr(),
+        );
+        let msg_hash_lt_n = LtWordGadget::construct(cb, &msg_hash.to_word(), &secp256k1_n);
+        cb.require_equal("msg_hash < n", msg_hash_lt_n.expr(), 1.expr());
+
+        let (sig_v_lo, sig_v_hi) = sig_v.to_word().to_lo_hi();
+        let sig_v_hi_is_zero = IsZeroGadget::construct(cb, sig_v_hi);
+        let sig_v_is_27 = IsEqualGadget::construct(cb, sig_v_lo.expr(), 27.expr());
+        let sig_v_is_28 = IsEqualGadget::construct(cb, sig_v_lo.expr(), 28.expr());
+        // The limbs of r and s are smaller than 2^128, so their sum is zero only when
+        // both of them are zero.
+        let [(sig_r_is_zero, sig_r_lt_n), (sig_s_is_zero, sig_s_lt_n)] =
+            [&sig_r, &sig_s].map(|sig| {
+                let (lo, hi) = sig.to_word().to_lo_hi();
+                (
+                    IsZeroGadget::construct(cb, lo + hi),
+                    LtWordGadget::construct(cb, &sig.to_word(), &secp256k1_n),
+                )
+            });
+        let [is_valid_sig_v, is_valid_sig_r, is_valid_sig_s, is_valid_sig] =
+            [(); 4].map(|_| cb.query_bool());
+        cb.require_equal(
+            "is_valid_sig_v = v is 27 or 28",
+            is_valid_sig_v.expr(),
+            sig_v_hi_is_zero.expr() * (sig_v_is_27.expr() + sig_v_is_28.expr()),
+        );
+        cb.require_equal(
+            "is_valid_sig_r = 0 < r < n",
+            is_valid_sig_r.expr(),
+            not::expr(sig_r_is_zero.expr()) * sig_r_lt_n.expr(),
+        );
+        cb.require_equal(
+            "is_valid_sig_s = 0 < s < n",
+            is_valid_sig_s.expr(),
+            not::expr(sig_s_is_zero.expr()) * sig_s_lt_n.expr(),
+        );
+        cb.require_equal(
+            "is_valid_sig = v, r and s are valid",
+            is_valid_sig.expr(),
+            is_valid_sig_v.expr() * is_valid_sig_r.expr() * is_valid_sig_s.expr(),
+        );
+
+        // A valid signature is in the sig table, whose validity column tells whether it
+        // recovers the address, as verified by the SignVerify chip, or no public key, as
+        // proven by the SignVerify chip.
+        cb.condition(not::expr(is_valid_sig.expr()), |cb| {
+            cb.require_zero(
+                "an invalid signature recovers no address",
+                is_recovered.expr(),
+            );
+        });
+        cb.condition(is_valid_sig.expr(), |cb| {
+            cb.sig_table_lookup(
+                msg_hash.to_word(),
+                sig_v_lo - 27.expr(),
+                sig_r.to_word(),
+                sig_s.to_word(),
+                recovered_addr.to_word(),
+                is_recovered.expr(),
+            );
+        });
+        cb.condition(is_recovered.expr(), |cb| {
+            // The output is the recovered address left padded to 32 bytes.
+            cb.require_equal(
+                "output bytes rlc is the recovered address",
+                output_bytes_rlc.expr(),
+                rlc::expr(
+                    &recovered_addr.limbs.clone().map(|cell| cell.expr()),
+                    cb.challenges().keccak_input(),
+                ),
+            );
+        });
+
+        let gas_cost = select::expr(
+            is_success.expr(),
+            GasCost::PRECOMPILE_ECRECOVER_BASE.expr(),
+            cb.curr.state.gas_left.expr(),
+        );
+
+        cb.precompile_info_lookup(
+            cb.execution_state().as_u64().expr(),
+            callee_address.expr(),
+            cb.execution_state().precompile_base_gas_cost().expr(),
+        );
+
+        // Insufficient gas is the only failure of the call, which is handled in
+        // the ErrorOogPrecompile gadget. An invalid signature just returns no
+        // data.
+        let restore_context = RestoreContextGadget::construct2(
+            cb,
+            is_success.expr(),
+            gas_cost.expr(),
+            0.expr(),
+            0x00.expr(),                     // ReturnDataOffset
+            is_recovered.expr() * 32.expr(), // ReturnDataLength
+            0.expr(),
+            0.expr(),
+        );
+
+        Self {
+            is_recovered,
+            input_len,
+            input_bytes_rlc,
+            output_bytes_rlc,
+            msg_hash_raw,
+            msg_hash,
+            msg_hash_overflow,
+            msg_hash_carry,
+            msg_hash_lt_n,
+            sig_v,
+            sig_r,
+            sig_s,
+            recovered_addr,
+            sig_v_hi_is_zero,
+            sig_v_is_27,
+            sig_v_is_28,
+            sig_r_is_zero,
+            sig_r_lt_n,
+            sig_s_is_zero,
+            sig_s_lt_n,
+            is_valid_sig_v,
+            is_valid_sig_r,
+            is_valid_sig_s,
+            is_valid_sig,
+            input_len_min,
+            padding,
+            is_success,
+            callee_address,
+            caller_id,
+            call_data_offset,
+            call_data_length,
+            return_data_offset,
+            return_data_length,
+            restore_context,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let aux_data = if let Some(PrecompileAuxData::Ecrecover(aux_data)) = &step.aux_data {
+            aux_data
+        } else {
+            unreachable!("must exist for ecrecover precompile call")
+        };
+
+        let is_recovered = !aux_data.recovered_addr.is_zero();
+        self.is_recovered
+            .assign(region, offset, Value::known(F::from(is_recovered as u64)))?;
+
+        let input_len = call
+            .call_data_length
+            .min(PrecompileCalls::ECRecover.input_len().unwrap() as u64);
+        self.input_len
+            .assign(region, offset, Value::known(F::from(input_len)))?;
+        self.input_len_min
+            .assign(region, offset, F::from(call.call_data_length), F::from(128))?;
+
+        let input_bytes = [
+            aux_data.msg_hash,
+            aux_data.sig_v,
+            aux_data.sig_r,
+            aux_data.sig_s,
+        ]
+        .iter()
+        .flat_map(|word| word.to_be_bytes())
+        .collect::<Vec<_>>();
+        let padding_len = 128 - input_len as usize;
+        let keccak_input = region.challenges().keccak_input();
+        self.input_bytes_rlc.assign(
+            region,
+            offset,
+            keccak_input.map(|randomness| {
+                rlc::value(input_bytes[..input_len as usize].iter().rev(), randomness)
+            }),
+        )?;
+        self.padding.assign(region, offset, padding_len)?;
+
+        let output_bytes = aux_data.recovered_addr.to_fixed_bytes();
+        self.output_bytes_rlc.assign(
+            region,
+            offset,
+            if is_recovered {
+                keccak_input.map(|randomness| rlc::value(output_bytes.iter().rev(), randomness))
+            } else {
+                Value::known(F::ZERO)
+            },
+        )?;
+
+        let (msg_hash, msg_hash_overflow) = if aux_data.msg_hash >= *SECP256K1_N {
+            (aux_data.msg_hash - *SECP256K1_N, true)
+        } else {
+            (aux_data.msg_hash, false)
+        };
+        let msg_hash_carry = msg_hash_overflow
+            && msg_hash
+                .low_u128()
+                .checked_add(SECP256K1_N.low_u128())
+                .is_none();
+        self.msg_hash_raw
+            .assign_u256(region, offset, aux_data.msg_hash)?;
+        self.msg_hash.assign_u256(region, offset, msg_hash)?;
+        self.msg_hash_overflow.assign(
+            region,
+            offset,
+            Value::known(F::from(msg_hash_overflow as u64)),
+        )?;
+        self.msg_hash_carry
+            .assign(region, offset, Value::known(F::from(msg_hash_carry as u64)))?;
+        self.msg_hash_lt_n
+            .assign(region, offset, msg_hash, *SECP256K1_N)?;
+        self.sig_v.assign_u256(region, offset, aux_data.sig_v)?;
+        self.sig_r.assign_u256(region, offset, aux_data.sig_r)?;
+        self.sig_s.assign_u256(region, offset, aux_data.sig_s)?;
+        self.recovered_addr
+            .assign_h160(region, offset, aux_data.recovered_addr)?;
+
+        let sig_v = Word::<F>::from(aux_data.sig_v);
+        self.sig_v_hi_is_zero.assign(region, offset, sig_v.hi())?;
+        self.sig_v_is_27
+            .assign(region, offset, sig_v.lo(), F::from(27))?;
+        self.sig_v_is_28
+            .assign(region, offset, sig_v.lo(), F::from(28))?;
+        let is_valid_sig_v = aux_data.recovery_id().is_some();
+        self.is_valid_sig_v
+            .assign(region, offset, Value::known(F::from(is_valid_sig_v as u64)))?;
+        let mut is_valid_sig = is_valid_sig_v;
+        for (sig, is_zero, lt_n, is_valid) in [
+            (
+                aux_data.sig_r,
+                &self.sig_r_is_zero,
+                &self.sig_r_lt_n,
+                &self.is_valid_sig_r,
+            ),
+            (
+                aux_data.sig_s,
+                &self.sig_s_is_zero,
+                &self.sig_s_lt_n,
+                &self.is_valid_sig_s,
+            ),
+        ] {
+            let sig_word = Word::<F>::from(sig);
+            is_zero.assign(region, offset, sig_word.lo() + sig_word.hi())?;
+            lt_n.assign(region, offset, sig, *SECP256K1_N)?;
+            let is_valid_field = !sig.is_zero() && sig < *SECP256K1_N;
+            is_valid.assign(region, offset, Value::known(F::from(is_valid_field as u64)))?;
+            is_valid_sig &= is_valid_field;
+        }
+        self.is_valid_sig
+            .assign(region, offset, Value::known(F::from(is_valid_sig as u64)))?;
+
+        self.is_success.assign(
+            region,
+            offset,
+            Value::known(F::from(u64::from(call.is_success))),
+        )?;
+        self.callee_address.assign(
+            region,
+            offset,
+            Value::known(call.code_address().unwrap().to_scalar().unwrap()),
+        )?;
+        self.caller_id.assign(
+            region,
+            offset,
+            Value::known(F::from(call.caller_id.try_into().unwrap())),
+        )?;
+        self.call_data_offset.assign(
+            region,
+            offset,
+            Value::known(F::from(call.call_data_offset)),
+        )?;
+        self.call_data_length.assign(
+            region,
+            offset,
+            Value::known(F::from(call.call_data_length)),
+        )?;
+        self.return_data_offset.assign(
+            region,
+            offset,
+            Value::known(F::from(call.return_data_offset)),
+        )?;
+        self.return_data_length.assign(
+            region,
+            offset,
+            Value::known(F::from(call.return_data_length)),
+        )?;
+        self.restore_context
+            .assign(region, offset, block, call, step, 7)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bus_mapping::{
+        evm::{OpcodeId, PrecompileCallArgs},
+        precompile::PrecompileCalls,
+    };
+    use eth_types::{bytecode, word, ToWord};
+    use itertools::Itertools;
+    use mock::TestContext;
+
+    use crate::test_util::CircuitTestBuilder;
+
+    lazy_static::lazy_static! {
+        static ref TEST_VECTOR: Vec<PrecompileCallArgs> = {
+            vec![
+                PrecompileCallArgs {
+                    name: "ecrecover (valid sig, addr recovered)",
+                    setup_code: bytecode! {
+                        // msg hash from 0x00
+                        PUSH32(word!("0x456e9aea5e197a1f1af7a3e85a3212fa4049a3ba34c2289b4c860fc0b0c64ef3"))
+                        PUSH1(0x00)
+                        MSTORE
+                        // signature v from 0x20
+                        PUSH1(28)
+                        PUSH1(0x20)
+                        MSTORE
+                        // signature r from 0x40
+                        PUSH32(word!("0x9242685bf161793cc25603c231bc2f568eb630ea16aa137d2664ac8038825608"))
+                        PUSH1(0x40)
+                        MSTORE
+                        // signature s from 0x60
+                        PUSH32(word!("0x4f8ae3bd7535248d0bd448298cc2e2071e56992d0774dc340c368ae950852ada"))
+                        PUSH1(0x60)
+                        MSTORE
+                    },
+                    // copy 128 bytes from memory addr 0. This is ecrecover input.
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x80.into(),
+                    // return 32 bytes and write from memory addr 128. This is ecrecover output.
+                    ret_offset: 0x80.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::ECRecover.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ecrecover (msg_hash overflows the curve order)",
+                    setup_code: bytecode! {
+                        // msg hash from 0x00
+                        PUSH32(word!("0xffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"))
+                        PUSH1(0x00)
+                        MSTORE
+                        // signature v from 0x20
+                        PUSH1(28)
+                        PUSH1(0x20)
+                        MSTORE
+                        // signature r from 0x40
+                        PUSH32(word!("0x9242685bf161793cc25603c231bc2f568eb630ea16aa137d2664ac8038825608"))
+                        PUSH1(0x40)
+                        MSTORE
+                        // signature s from 0x60
+                        PUSH32(word!("0x4f8ae3bd7535248d0bd448298cc2e2071e56992d0774dc340c368ae950852ada"))
+                        PUSH1(0x60)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x80.into(),
+                    ret_offset: 0x80.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::ECRecover.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ecrecover (more than 128 bytes of call data)",
+                    setup_code: bytecode! {
+                        PUSH32(word!("0x456e9aea5e197a1f1af7a3e85a3212fa4049a3ba34c2289b4c860fc0b0c64ef3"))
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH1(28)
+                        PUSH1(0x20)
+                        MSTORE
+                        PUSH32(word!("0x9242685bf161793cc25603c231bc2f568eb630ea16aa137d2664ac8038825608"))
+                        PUSH1(0x40)
+                        MSTORE
+                        PUSH32(word!("0x4f8ae3bd7535248d0bd448298cc2e2071e56992d0774dc340c368ae950852ada"))
+                        PUSH1(0x60)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0xa0.into(),
+                    ret_offset: 0xa0.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::ECRecover.address().to_word(),
+                    ..Default::default()
+                },
+            ]
+        };
+
+        static ref TEST_VECTOR_INVALID: Vec<PrecompileCallArgs> = {
+            vec![
+                PrecompileCallArgs {
+                    name: "ecrecover (invalid sig, v is not 27 or 28)",
+                    setup_code: bytecode! {
+                        PUSH32(word!("0x456e9aea5e197a1f1af7a3e85a3212fa4049a3ba34c2289b4c860fc0b0c64ef3"))
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH1(29)
+                        PUSH1(0x20)
+                        MSTORE
+                        PUSH32(word!("0x9242685bf161793cc25603c231bc2f568eb630ea16aa137d2664ac8038825608"))
+                        PUSH1(0x40)
+                        MSTORE
+                        PUSH32(word!("0x4f8ae3bd7535248d0bd448298cc2e2071e56992d0774dc340c368ae950852ada"))
+                        PUSH1(0x60)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x80.into(),
+                    ret_offset: 0x80.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::ECRecover.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ecrecover (invalid sig, r is zero)",
+                    setup_code: bytecode! {
+                        PUSH32(word!("0x456e9aea5e197a1f1af7a3e85a3212fa4049a3ba34c2289b4c860fc0b0c64ef3"))
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH1(28)
+                        PUSH1(0x20)
+                        MSTORE
+                        PUSH32(word!("0x4f8ae3bd7535248d0bd448298cc2e2071e56992d0774dc340c368ae950852ada"))
+                        PUSH1(0x60)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x80.into(),
+                    ret_offset: 0x80.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::ECRecover.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ecrecover (invalid sig, s is not less than the curve order)",
+                    setup_code: bytecode! {
+                        PUSH32(word!("0x456e9aea5e197a1f1af7a3e85a3212fa4049a3ba34c2289b4c860fc0b0c64ef3"))
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH1(28)
+                        PUSH1(0x20)
+                        MSTORE
+                        PUSH32(word!("0x9242685bf161793cc25603c231bc2f568eb630ea16aa137d2664ac8038825608"))
+                        PUSH1(0x40)
+                        MSTORE
+                        PUSH32(word!("0xfffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141"))
+                        PUSH1(0x60)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x80.into(),
+                    ret_offset: 0x80.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::ECRecover.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ecrecover (invalid sig, r is not the x coordinate of a point)",
+                    setup_code: bytecode! {
+                        PUSH32(word!("0x456e9aea5e197a1f1af7a3e85a3212fa4049a3ba34c2289b4c860fc0b0c64ef3"))
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH1(28)
+                        PUSH1(0x20)
+                        MSTORE
+                        PUSH1(5)
+                        PUSH1(0x40)
+                        MSTORE
+                        PUSH32(word!("0x4f8ae3bd7535248d0bd448298cc2e2071e56992d0774dc340c368ae950852ada"))
+                        PUSH1(0x60)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x80.into(),
+                    ret_offset: 0x80.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::ECRecover.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ecrecover (empty call data)",
+                    setup_code: bytecode! {},
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x00.into(),
+                    ret_offset: 0x00.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::ECRecover.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "ecrecover (call data shorter than 128 bytes)",
+                    setup_code: bytecode! {
+                        PUSH32(word!("0x456e9aea5e197a1f1af7a3e85a3212fa4049a3ba34c2289b4c860fc0b0c64ef3"))
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH1(28)
+                        PUSH1(0x20)
+                        MSTORE
+                        PUSH32(word!("0x9242685bf161793cc25603c231bc2f568eb630ea16aa137d2664ac8038825608"))
+                        PUSH1(0x40)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x61.into(),
+                    ret_offset: 0x80.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::ECRecover.address().to_word(),
+                    ..Default::default()
+                },
+            ]
+        };
+    }
+
+    #[test]
+    fn precompile_ecrecover_test() {
+        let call_kinds = vec![
+            OpcodeId::CALL,
+            OpcodeId::STATICCALL,
+            OpcodeId::DELEGATECALL,
+            OpcodeId::CALLCODE,
+        ];
+
+        for (test_vector, &call_kind) in TEST_VECTOR.iter().cartesian_product(&call_kinds) {
+            let bytecode = test_vector.with_call_op(call_kind);
+
+            CircuitTestBuilder::new_from_test_ctx(
+                TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+            )
+            .run();
+        }
+    }
+
+    #[test]
+    fn precompile_ecrecover_invalid_sig_test() {
+        let call_kinds = vec![
+            OpcodeId::CALL,
+            OpcodeId::STATICCALL,
+            OpcodeId::DELEGATECALL,
+            OpcodeId::CALLCODE,
+        ];
+
+        for (test_vector, &call_kind) in TEST_VECTOR_INVALID.iter().cartesian_product(&call_kinds) {
+            let bytecode = test_vector.with_call_op(call_kind);
+
+            CircuitTestBuilder::new_from_test_ctx(
+                TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+            )
+            .run();
+        }
+    }
+}
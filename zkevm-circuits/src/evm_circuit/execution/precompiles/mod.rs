@@ -1,2 +1,29 @@
+mod blake2f;
+pub use blake2f::Blake2fGadget;
+
+mod ec_add;
+pub use ec_add::EcAddGadget;
+
+mod ec_mul;
+pub use ec_mul::EcMulGadget;
+
+mod ec_pairing;
+pub use ec_pairing::EcPairingGadget;
+
+mod ecrecover;
+pub use ecrecover::EcrecoverGadget;
+
 mod identity;
 pub use identity::IdentityGadget;
+
+mod modexp;
+pub use modexp::ModExpGadget;
+
+mod point_evaluation;
+pub use point_evaluation::PointEvaluationGadget;
+
+mod ripemd160;
+pub use ripemd160::Ripemd160Gadget;
+
+mod sha256;
+pub use sha256::Sha256Gadget;
@@ -0,0 +1,734 @@
+use bus_mapping::{
+    circuit_input_builder::Call,
+    precompile::{PrecompileAuxData, MODEXP_HEADER_LEN},
+};
+use eth_types::{evm_types::GasCost, Field, ToBigEndian, ToScalar};
+use gadgets::util::{not, select, Expr};
+use halo2_proofs::{
+    circuit::Value,
+    plonk::{Error, Expression},
+};
+use std::cmp::max;
+
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{N_BYTES_GAS, N_BYTES_U64, N_BYTES_WORD},
+        step::ExecutionState,
+        util::{
+            common_gadget::RestoreContextGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::{
+                ByteSizeGadget, ConstantDivisionGadget, IsZeroWordGadget, LtGadget, MinMaxGadget,
+                RandPowGadget,
+            },
+            rlc, sum, CachedRegion, Cell,
+        },
+    },
+    table::CallContextFieldTag,
+    util::word::{Word32Cell, WordExpr},
+    witness::{Block, ExecStep, Transaction},
+};
+
+/// Number of bits needed to represent the zero padding of the input, which is
+/// at most `3 * 32` bytes.
+const N_BITS_PADDING: usize = 8;
+
+/// Length in bytes of a base, exponent or modulus, which is at most 32 bytes.
+#[derive(Clone, Debug)]
+struct OperandLenGadget<F> {
+    /// One-hot encoding of the length, the bytes from the turned on index are
+    /// zero.
+    index: [Cell<F>; N_BYTES_WORD + 1],
+}
+
+impl<F: Field> OperandLenGadget<F> {
+    fn construct(cb: &mut EVMConstraintBuilder<F>, bytes: &[Expression<F>; N_BYTES_WORD]) -> Self {
+        let index = [(); N_BYTES_WORD + 1].map(|_| cb.query_bool());
+        cb.require_equal(
+            "exactly one cell in indices is 1",
+            sum::expr(&index),
+            1.expr(),
+        );
+        for (i, index) in index.iter().enumerate().take(N_BYTES_WORD) {
+            cb.condition(index.expr(), |cb| {
+                cb.require_zero("bytes beyond the length are 0", sum::expr(&bytes[i..]));
+            });
+        }
+
+        Self { index }
+    }
+
+    fn expr(&self) -> Expression<F> {
+        sum::expr(
+            self.index
+                .iter()
+                .enumerate()
+                .map(|(i, cell)| i.expr() * cell.expr()),
+        )
+    }
+
+    /// Returns `r^len`, given the powers `r^1..=r^32` of the randomness.
+    fn rand_pow(&self, powers_of_randomness: &[Expression<F>]) -> Expression<F> {
+        self.index[0].expr()
+            + sum::expr(
+                self.index
+                    .iter()
+                    .skip(1)
+                    .zip(powers_of_randomness)
+                    .map(|(cell, power)| cell.expr() * power.clone()),
+            )
+    }
+
+    fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        len: usize,
+    ) -> Result<(), Error> {
+        for (i, index) in self.index.iter().enumerate() {
+            index.assign(region, offset, Value::known(F::from((i == len) as u64)))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ModExpGadget<F> {
+    // The first cells are shared with `PrecompileGadget`, which constrains them
+    // against the caller's view of the call. Keep them in this order.
+    input_len: Cell<F>,
+    output_len: Cell<F>,
+    input_bytes_rlc: Cell<F>,
+    output_bytes_rlc: Cell<F>,
+
+    base: Word32Cell<F>,
+    exponent: Word32Cell<F>,
+    modulus: Word32Cell<F>,
+    result: Word32Cell<F>,
+    /// Lengths of the base, exponent and modulus declared in the header.
+    base_len: OperandLenGadget<F>,
+    exponent_len: OperandLenGadget<F>,
+    modulus_len: OperandLenGadget<F>,
+    modulus_is_zero: IsZeroWordGadget<F, Word32Cell<F>>,
+
+    input_len_min: MinMaxGadget<F, N_BYTES_U64>,
+    /// `r^(96 + base_len + exponent_len + modulus_len - input_len)`, to right
+    /// pad the input with zero bytes.
+    padding: RandPowGadget<F, N_BITS_PADDING>,
+    /// Rlc of the padded input up to the end of the base and the exponent.
+    input_acc_rlc: [Cell<F>; 2],
+
+    exponent_byte_size: ByteSizeGadget<F>,
+    /// One-hot encoding of the bit length of the most significant byte of the
+    /// exponent.
+    exponent_msb_bit_len: [Cell<F>; 9],
+    exponent_msb_lt_pow: LtGadget<F, 2>,
+    exponent_msb_lt_half_pow: LtGadget<F, 2>,
+    iteration_count: MinMaxGadget<F, 2>,
+    max_len: MinMaxGadget<F, 1>,
+    words: ConstantDivisionGadget<F, 1>,
+    gas_div: ConstantDivisionGadget<F, 2>,
+    gas: MinMaxGadget<F, 2>,
+    insufficient_gas: LtGadget<F, N_BYTES_GAS>,
+
+    is_success: Cell<F>,
+    callee_address: Cell<F>,
+    caller_id: Cell<F>,
+    call_data_offset: Cell<F>,
+    call_data_length: Cell<F>,
+    return_data_offset: Cell<F>,
+    return_data_length: Cell<F>,
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for ModExpGadget<F> {
+    const EXECUTION_STATE: ExecutionState = ExecutionState::PrecompileBigModExp;
+
+    const NAME: &'static str = "MODEXP";
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let (input_len, output_len, input_bytes_rlc, output_bytes_rlc) = (
+            cb.query_cell(),
+            cb.query_cell(),
+            cb.query_cell_phase2(),
+            cb.query_cell_phase2(),
+        );
+
+        let base = cb.query_word32();
+        let exponent = cb.query_word32();
+        let modulus = cb.query_word32();
+        let result = cb.query_word32();
+        let [base_bytes, exponent_bytes, modulus_bytes, result_bytes] =
+            [&base, &exponent, &modulus, &result]
+                .map(|word| word.limbs.clone().map(|cell| cell.expr()));
+
+        let [is_success, callee_address, caller_id, call_data_offset, call_data_length, return_data_offset, return_data_length] =
+            [
+                CallContextFieldTag::IsSuccess,
+                CallContextFieldTag::CalleeAddress,
+                CallContextFieldTag::CallerId,
+                CallContextFieldTag::CallDataOffset,
+                CallContextFieldTag::CallDataLength,
+                CallContextFieldTag::ReturnDataOffset,
+                CallContextFieldTag::ReturnDataLength,
+            ]
+            .map(|tag| cb.call_context(None, tag));
+
+        // Lengths above 32 bytes are not supported, so the header is made of the
+        // three lengths as 32 bytes big-endian words, each fitting in one byte.
+        let base_len = OperandLenGadget::construct(cb, &base_bytes);
+        let exponent_len = OperandLenGadget::construct(cb, &exponent_bytes);
+        let modulus_len = OperandLenGadget::construct(cb, &modulus_bytes);
+
+        // The precompile takes the header and the operands from the call data,
+        // right padded with zeroes.
+        let padded_input_len =
+            MODEXP_HEADER_LEN.expr() + base_len.expr() + exponent_len.expr() + modulus_len.expr();
+        let input_len_min =
+            MinMaxGadget::construct(cb, call_data_length.expr(), padded_input_len.clone());
+        cb.require_equal(
+            "input length is min(call data length, 96 + base, exponent and modulus lengths)",
+            input_len.expr(),
+            input_len_min.min(),
+        );
+        let padding = RandPowGadget::construct(cb, padded_input_len - input_len.expr());
+
+        let keccak_input = cb.challenges().keccak_input();
+        let powers_of_randomness = cb.challenges().keccak_powers_of_randomness::<64>();
+        let input_acc_rlc = [(); 2].map(|_| cb.query_cell_phase2());
+        cb.require_equal(
+            "input rlc up to the end of the base",
+            input_acc_rlc[0].expr(),
+            (base_len.expr() * powers_of_randomness[63].clone()
+                + exponent_len.expr() * powers_of_randomness[31].clone()
+                + modulus_len.expr())
+                * base_len.rand_pow(&powers_of_randomness)
+                + rlc::expr(&base_bytes, keccak_input.clone()),
+        );
+        cb.require_equal(
+            "input rlc up to the end of the exponent",
+            input_acc_rlc[1].expr(),
+            input_acc_rlc[0].expr() * exponent_len.rand_pow(&powers_of_randomness)
+                + rlc::expr(&exponent_bytes, keccak_input.clone()),
+        );
+        cb.require_equal(
+            "input bytes rlc padded to the declared lengths",
+            input_bytes_rlc.expr() * padding.expr(),
+            input_acc_rlc[1].expr() * modulus_len.rand_pow(&powers_of_randomness)
+                + rlc::expr(&modulus_bytes, keccak_input.clone()),
+        );
+
+        // EIP-2565 gas cost, where the iteration count is the bit length of the
+        // exponent minus one, and at least one.
+        let exponent_byte_size = ByteSizeGadget::construct(cb, exponent_bytes.clone());
+        let exponent_msb = exponent_byte_size.most_significant_byte(&exponent_bytes);
+        let exponent_msb_bit_len = [(); 9].map(|_| cb.query_bool());
+        cb.require_equal(
+            "exactly one cell in the msb bit length is 1",
+            sum::expr(&exponent_msb_bit_len),
+            1.expr(),
+        );
+        let exponent_msb_lt_pow = LtGadget::construct(
+            cb,
+            exponent_msb.clone(),
+            sum::expr(
+                exponent_msb_bit_len
+                    .iter()
+                    .enumerate()
+                    .map(|(k, cell)| cell.expr() * (1u64 << k).expr()),
+            ),
+        );
+        let exponent_msb_lt_half_pow = LtGadget::construct(
+            cb,
+            exponent_msb,
+            sum::expr(
+                exponent_msb_bit_len
+                    .iter()
+                    .enumerate()
+                    .skip(1)
+                    .map(|(k, cell)| cell.expr() * (1u64 << (k - 1)).expr()),
+            ),
+        );
+        cb.require_equal("msb < 2^bit_len", exponent_msb_lt_pow.expr(), 1.expr());
+        cb.require_zero("msb >= 2^(bit_len - 1)", exponent_msb_lt_half_pow.expr());
+        // The bit length of a non-zero exponent is 8 * (byte_size - 1) + msb_bit_len,
+        // so that max(bit_len - 1, 1) = max(8 * byte_size + msb_bit_len, 10) - 9,
+        // which also holds for a zero exponent.
+        let iteration_count = MinMaxGadget::construct(
+            cb,
+            8.expr() * exponent_byte_size.byte_size()
+                + sum::expr(
+                    exponent_msb_bit_len
+                        .iter()
+                        .enumerate()
+                        .map(|(k, cell)| k.expr() * cell.expr()),
+                ),
+            10.expr(),
+        );
+        let max_len = MinMaxGadget::construct(cb, base_len.expr(), modulus_len.expr());
+        let words = ConstantDivisionGadget::construct(cb, max_len.max() + 7.expr(), 8);
+        let gas_div = ConstantDivisionGadget::construct(
+            cb,
+            words.quotient() * words.quotient() * (iteration_count.max() - 9.expr()),
+            3,
+        );
+        let gas = MinMaxGadget::construct(
+            cb,
+            GasCost::PRECOMPILE_MODEXP_MIN.expr(),
+            gas_div.quotient(),
+        );
+
+        // Running out of gas is the only failure of the call, which returns no
+        // data then.
+        let gas_left = cb.curr.state.gas_left.expr();
+        let insufficient_gas = LtGadget::construct(cb, gas_left.clone(), gas.max());
+        cb.require_equal(
+            "call succeeds iff there is enough gas",
+            is_success.expr(),
+            not::expr(insufficient_gas.expr()),
+        );
+        let gas_cost = select::expr(is_success.expr(), gas.max(), gas_left);
+
+        // The result is returned as a big-endian word of the modulus length, it
+        // is lower than the modulus so that it fits, or zero for a zero modulus.
+        let modulus_is_zero = IsZeroWordGadget::construct(cb, &modulus);
+        cb.condition(modulus_is_zero.expr(), |cb| {
+            cb.require_zero_word("result is 0 for a zero modulus", result.to_word());
+        });
+        cb.condition(
+            is_success.expr() * not::expr(modulus_is_zero.expr()),
+            |cb| {
+                cb.modexp_table_lookup(
+                    base.to_word(),
+                    exponent.to_word(),
+                    modulus.to_word(),
+                    result.to_word(),
+                );
+            },
+        );
+        cb.require_equal(
+            "output length is the modulus length",
+            output_len.expr(),
+            is_success.expr() * modulus_len.expr(),
+        );
+        cb.require_equal(
+            "output bytes rlc is the result",
+            output_bytes_rlc.expr(),
+            is_success.expr() * rlc::expr(&result_bytes, keccak_input),
+        );
+
+        cb.precompile_info_lookup(
+            cb.execution_state().as_u64().expr(),
+            callee_address.expr(),
+            cb.execution_state().precompile_base_gas_cost().expr(),
+        );
+
+        let restore_context = RestoreContextGadget::construct2(
+            cb,
+            is_success.expr(),
+            gas_cost.expr(),
+            0.expr(),
+            0x00.expr(),       // ReturnDataOffset
+            output_len.expr(), // ReturnDataLength
+            0.expr(),
+            0.expr(),
+        );
+
+        Self {
+            input_len,
+            output_len,
+            input_bytes_rlc,
+            output_bytes_rlc,
+            base,
+            exponent,
+            modulus,
+            result,
+            base_len,
+            exponent_len,
+            modulus_len,
+            modulus_is_zero,
+            input_len_min,
+            padding,
+            input_acc_rlc,
+            exponent_byte_size,
+            exponent_msb_bit_len,
+            exponent_msb_lt_pow,
+            exponent_msb_lt_half_pow,
+            iteration_count,
+            max_len,
+            words,
+            gas_div,
+            gas,
+            insufficient_gas,
+            is_success,
+            callee_address,
+            caller_id,
+            call_data_offset,
+            call_data_length,
+            return_data_offset,
+            return_data_length,
+            restore_context,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let aux_data = if let Some(PrecompileAuxData::Modexp(aux_data)) = &step.aux_data {
+            aux_data
+        } else {
+            unreachable!("must exist for modexp precompile call")
+        };
+        let [base_len, exponent_len, modulus_len] = aux_data.input_lens;
+        let [base, exponent, modulus] = aux_data.inputs;
+
+        let padded_input_len = MODEXP_HEADER_LEN + base_len + exponent_len + modulus_len;
+        let input_len = call.call_data_length.min(padded_input_len as u64);
+        self.input_len
+            .assign(region, offset, Value::known(F::from(input_len)))?;
+        let output_len = if call.is_success { modulus_len } else { 0 };
+        self.output_len
+            .assign(region, offset, Value::known(F::from(output_len as u64)))?;
+
+        let mut padded_input = aux_data.input_bytes.clone();
+        padded_input.resize(padded_input_len, 0);
+        let keccak_input = region.challenges().keccak_input();
+        self.input_bytes_rlc.assign(
+            region,
+            offset,
+            keccak_input.map(|randomness| {
+                rlc::value(padded_input[..input_len as usize].iter().rev(), randomness)
+            }),
+        )?;
+        let output_bytes = aux_data.output.to_be_bytes();
+        self.output_bytes_rlc.assign(
+            region,
+            offset,
+            if call.is_success {
+                keccak_input.map(|randomness| rlc::value(output_bytes.iter().rev(), randomness))
+            } else {
+                Value::known(F::ZERO)
+            },
+        )?;
+
+        for (word, value) in [
+            (&self.base, base),
+            (&self.exponent, exponent),
+            (&self.modulus, modulus),
+            (&self.result, aux_data.output),
+        ] {
+            word.assign_u256(region, offset, value)?;
+        }
+        for (len_gadget, len) in [
+            (&self.base_len, base_len),
+            (&self.exponent_len, exponent_len),
+            (&self.modulus_len, modulus_len),
+        ] {
+            len_gadget.assign(region, offset, len)?;
+        }
+        self.modulus_is_zero.assign_u256(region, offset, modulus)?;
+
+        self.input_len_min.assign(
+            region,
+            offset,
+            F::from(call.call_data_length),
+            F::from(padded_input_len as u64),
+        )?;
+        self.padding
+            .assign(region, offset, padded_input_len - input_len as usize)?;
+        for (acc, end) in self.input_acc_rlc.iter().zip([
+            MODEXP_HEADER_LEN + base_len,
+            MODEXP_HEADER_LEN + base_len + exponent_len,
+        ]) {
+            acc.assign(
+                region,
+                offset,
+                keccak_input
+                    .map(|randomness| rlc::value(padded_input[..end].iter().rev(), randomness)),
+            )?;
+        }
+
+        let byte_size = (exponent.bits() + 7) / 8;
+        self.exponent_byte_size.assign(region, offset, exponent)?;
+        let msb = if byte_size == 0 {
+            0
+        } else {
+            exponent.byte(byte_size - 1)
+        };
+        let msb_bit_len = (u8::BITS - msb.leading_zeros()) as usize;
+        for (k, cell) in self.exponent_msb_bit_len.iter().enumerate() {
+            cell.assign(
+                region,
+                offset,
+                Value::known(F::from((k == msb_bit_len) as u64)),
+            )?;
+        }
+        self.exponent_msb_lt_pow.assign(
+            region,
+            offset,
+            F::from(msb as u64),
+            F::from(1u64 << msb_bit_len),
+        )?;
+        self.exponent_msb_lt_half_pow.assign(
+            region,
+            offset,
+            F::from(msb as u64),
+            F::from((1u64 << msb_bit_len) >> 1),
+        )?;
+        let adjusted_bit_len = 8 * byte_size + msb_bit_len;
+        self.iteration_count.assign(
+            region,
+            offset,
+            F::from(adjusted_bit_len as u64),
+            F::from(10),
+        )?;
+        let iteration_count = max(adjusted_bit_len, 10) - 9;
+        let max_len = max(base_len, modulus_len);
+        self.max_len.assign(
+            region,
+            offset,
+            F::from(base_len as u64),
+            F::from(modulus_len as u64),
+        )?;
+        let (words, _) = self.words.assign(region, offset, (max_len + 7) as u128)?;
+        let (gas_div, _) =
+            self.gas_div
+                .assign(region, offset, words * words * iteration_count as u128)?;
+        self.gas.assign(
+            region,
+            offset,
+            F::from(GasCost::PRECOMPILE_MODEXP_MIN),
+            F::from(gas_div as u64),
+        )?;
+        let gas = max(GasCost::PRECOMPILE_MODEXP_MIN, gas_div as u64);
+        self.insufficient_gas
+            .assign(region, offset, F::from(step.gas_left), F::from(gas))?;
+
+        self.is_success.assign(
+            region,
+            offset,
+            Value::known(F::from(u64::from(call.is_success))),
+        )?;
+        self.callee_address.assign(
+            region,
+            offset,
+            Value::known(call.code_address().unwrap().to_scalar().unwrap()),
+        )?;
+        self.caller_id.assign(
+            region,
+            offset,
+            Value::known(F::from(call.caller_id.try_into().unwrap())),
+        )?;
+        self.call_data_offset.assign(
+            region,
+            offset,
+            Value::known(F::from(call.call_data_offset)),
+        )?;
+        self.call_data_length.assign(
+            region,
+            offset,
+            Value::known(F::from(call.call_data_length)),
+        )?;
+        self.return_data_offset.assign(
+            region,
+            offset,
+            Value::known(F::from(call.return_data_offset)),
+        )?;
+        self.return_data_length.assign(
+            region,
+            offset,
+            Value::known(F::from(call.return_data_length)),
+        )?;
+        self.restore_context
+            .assign(region, offset, block, call, step, 7)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bus_mapping::{
+        evm::{OpcodeId, PrecompileCallArgs},
+        precompile::PrecompileCalls,
+    };
+    use eth_types::{bytecode, word, ToWord};
+    use itertools::Itertools;
+    use mock::TestContext;
+
+    use crate::test_util::CircuitTestBuilder;
+
+    lazy_static::lazy_static! {
+        static ref TEST_VECTOR: Vec<PrecompileCallArgs> = {
+            vec![
+                PrecompileCallArgs {
+                    name: "single-byte operands",
+                    setup_code: bytecode! {
+                        // base length
+                        PUSH1(0x01)
+                        PUSH1(0x00)
+                        MSTORE
+                        // exponent length
+                        PUSH1(0x01)
+                        PUSH1(0x20)
+                        MSTORE
+                        // modulus length
+                        PUSH1(0x01)
+                        PUSH1(0x40)
+                        MSTORE
+                        // 3 ^ 5 % 7
+                        PUSH32(word!("0x0305070000000000000000000000000000000000000000000000000000000000"))
+                        PUSH1(0x60)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x63.into(),
+                    ret_offset: 0x00.into(),
+                    ret_size: 0x01.into(),
+                    address: PrecompileCalls::Modexp.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "zero exponent, result left padded",
+                    setup_code: bytecode! {
+                        PUSH1(0x01)
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH1(0x02)
+                        PUSH1(0x40)
+                        MSTORE
+                        // 5 ^ 0 % 0x0101
+                        PUSH32(word!("0x0501010000000000000000000000000000000000000000000000000000000000"))
+                        PUSH1(0x60)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x63.into(),
+                    ret_offset: 0x20.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::Modexp.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "32 bytes operands",
+                    setup_code: bytecode! {
+                        PUSH1(0x20)
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH1(0x20)
+                        PUSH1(0x20)
+                        MSTORE
+                        PUSH1(0x20)
+                        PUSH1(0x40)
+                        MSTORE
+                        PUSH32(word!("0x0123456789abcdef0f1e2d3c4b5a6978aabbccdd001122331039abcdefefef84"))
+                        PUSH1(0x60)
+                        MSTORE
+                        PUSH32(word!("0xaabbccdd001122331039abcdefefef840123456789abcdef0f1e2d3c4b5a6978"))
+                        PUSH1(0x80)
+                        MSTORE
+                        PUSH32(word!("0xfffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f"))
+                        PUSH1(0xa0)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0xc0.into(),
+                    ret_offset: 0x00.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::Modexp.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "modulus cut from the call data is zero",
+                    setup_code: bytecode! {
+                        PUSH1(0x01)
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH1(0x01)
+                        PUSH1(0x20)
+                        MSTORE
+                        PUSH1(0x01)
+                        PUSH1(0x40)
+                        MSTORE
+                        PUSH32(word!("0x0305070000000000000000000000000000000000000000000000000000000000"))
+                        PUSH1(0x60)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x62.into(),
+                    ret_offset: 0x00.into(),
+                    ret_size: 0x01.into(),
+                    address: PrecompileCalls::Modexp.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "header cut from the call data",
+                    setup_code: bytecode! {
+                        PUSH1(0x01)
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH1(0x01)
+                        PUSH1(0x20)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x40.into(),
+                    ret_offset: 0x00.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::Modexp.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "insufficient gas",
+                    setup_code: bytecode! {
+                        PUSH1(0x01)
+                        PUSH1(0x00)
+                        MSTORE
+                        PUSH1(0x01)
+                        PUSH1(0x20)
+                        MSTORE
+                        PUSH1(0x01)
+                        PUSH1(0x40)
+                        MSTORE
+                        PUSH32(word!("0x0305070000000000000000000000000000000000000000000000000000000000"))
+                        PUSH1(0x60)
+                        MSTORE
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x63.into(),
+                    ret_offset: 0x00.into(),
+                    ret_size: 0x01.into(),
+                    address: PrecompileCalls::Modexp.address().to_word(),
+                    gas: 0x64.into(),
+                    ..Default::default()
+                },
+            ]
+        };
+    }
+
+    #[test]
+    fn precompile_modexp_test() {
+        let call_kinds = vec![
+            OpcodeId::CALL,
+            OpcodeId::STATICCALL,
+            OpcodeId::DELEGATECALL,
+            OpcodeId::CALLCODE,
+        ];
+
+        for (test_vector, &call_kind) in TEST_VECTOR.iter().cartesian_product(&call_kinds) {
+            let bytecode = test_vector.with_call_op(call_kind);
+
+            CircuitTestBuilder::new_from_test_ctx(
+                TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+            )
+            .run();
+        }
+    }
+}
@@ -0,0 +1,351 @@
+use bus_mapping::{
+    circuit_input_builder::Call,
+    precompile::{point_evaluation_output, PrecompileAuxData, N_BYTES_POINT_EVALUATION_INPUT},
+};
+use eth_types::{evm_types::GasCost, Field, ToScalar};
+use gadgets::util::{select, Expr};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        step::ExecutionState,
+        util::{
+            common_gadget::RestoreContextGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::IsEqualGadget,
+            rlc, CachedRegion, Cell,
+        },
+    },
+    table::CallContextFieldTag,
+    witness::{Block, ExecStep, Transaction},
+};
+
+/// Length in bytes of the output of a successful call, the number of field
+/// elements per blob and the modulus of the BLS12-381 scalar field.
+const N_BYTES_POINT_EVALUATION_OUTPUT: u64 = 64;
+
+#[derive(Clone, Debug)]
+pub struct PointEvaluationGadget<F> {
+    // The first cells are shared with `PrecompileGadget`, which constrains them
+    // against the caller's view of the call. Keep them in this order.
+    output_len: Cell<F>,
+    input_bytes_rlc: Cell<F>,
+    output_bytes_rlc: Cell<F>,
+
+    is_input_len_valid: IsEqualGadget<F>,
+    /// Whether the point evaluation is valid, from the KZG table.
+    is_valid: Cell<F>,
+
+    is_success: Cell<F>,
+    callee_address: Cell<F>,
+    caller_id: Cell<F>,
+    call_data_offset: Cell<F>,
+    call_data_length: Cell<F>,
+    return_data_offset: Cell<F>,
+    return_data_length: Cell<F>,
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for PointEvaluationGadget<F> {
+    const EXECUTION_STATE: ExecutionState = ExecutionState::PrecompilePointEvaluation;
+
+    const NAME: &'static str = "POINT_EVALUATION";
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let (output_len, input_bytes_rlc, output_bytes_rlc) = (
+            cb.query_cell(),
+            cb.query_cell_phase2(),
+            cb.query_cell_phase2(),
+        );
+
+        let [is_success, callee_address, caller_id, call_data_offset, call_data_length, return_data_offset, return_data_length] =
+            [
+                CallContextFieldTag::IsSuccess,
+                CallContextFieldTag::CalleeAddress,
+                CallContextFieldTag::CallerId,
+                CallContextFieldTag::CallDataOffset,
+                CallContextFieldTag::CallDataLength,
+                CallContextFieldTag::ReturnDataOffset,
+                CallContextFieldTag::ReturnDataLength,
+            ]
+            .map(|tag| cb.call_context(None, tag));
+
+        // The whole call data is taken as input, which must be of exactly 192
+        // bytes.  The KZG table then gives whether the versioned hash matches the
+        // commitment and the proof is valid.
+        let is_input_len_valid = IsEqualGadget::construct(
+            cb,
+            call_data_length.expr(),
+            N_BYTES_POINT_EVALUATION_INPUT.expr(),
+        );
+        let is_valid = cb.query_bool();
+        cb.condition(is_input_len_valid.expr(), |cb| {
+            cb.kzg_table_lookup(input_bytes_rlc.expr(), is_valid.expr());
+        });
+        cb.require_equal(
+            "call succeeds iff the input is valid",
+            is_success.expr(),
+            is_input_len_valid.expr() * is_valid.expr(),
+        );
+
+        // The output of a successful call is constant.
+        let keccak_input = cb.challenges().keccak_input();
+        let output_bytes = point_evaluation_output()
+            .iter()
+            .rev()
+            .map(|byte| byte.expr())
+            .collect::<Vec<_>>();
+        cb.require_equal(
+            "output length is 64 if the call succeeds, 0 otherwise",
+            output_len.expr(),
+            is_success.expr() * N_BYTES_POINT_EVALUATION_OUTPUT.expr(),
+        );
+        cb.require_equal(
+            "output bytes rlc is the blob parameters",
+            output_bytes_rlc.expr(),
+            is_success.expr() * rlc::expr(&output_bytes, keccak_input),
+        );
+
+        let gas_cost = select::expr(
+            is_success.expr(),
+            GasCost::PRECOMPILE_POINT_EVALUATION.expr(),
+            cb.curr.state.gas_left.expr(),
+        );
+
+        cb.precompile_info_lookup(
+            cb.execution_state().as_u64().expr(),
+            callee_address.expr(),
+            cb.execution_state().precompile_base_gas_cost().expr(),
+        );
+
+        // Insufficient gas is handled in the ErrorOogPrecompile gadget, an invalid
+        // input consumes all the gas and returns no data.
+        let restore_context = RestoreContextGadget::construct2(
+            cb,
+            is_success.expr(),
+            gas_cost.expr(),
+            0.expr(),
+            0x00.expr(),       // ReturnDataOffset
+            output_len.expr(), // ReturnDataLength
+            0.expr(),
+            0.expr(),
+        );
+
+        Self {
+            output_len,
+            input_bytes_rlc,
+            output_bytes_rlc,
+            is_input_len_valid,
+            is_valid,
+            is_success,
+            callee_address,
+            caller_id,
+            call_data_offset,
+            call_data_length,
+            return_data_offset,
+            return_data_length,
+            restore_context,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let aux_data = if let Some(PrecompileAuxData::PointEvaluation(aux_data)) = &step.aux_data {
+            aux_data
+        } else {
+            unreachable!("must exist for point evaluation precompile call")
+        };
+
+        let output_len = if call.is_success {
+            N_BYTES_POINT_EVALUATION_OUTPUT
+        } else {
+            0
+        };
+        self.output_len
+            .assign(region, offset, Value::known(F::from(output_len)))?;
+        let keccak_input = region.challenges().keccak_input();
+        self.input_bytes_rlc.assign(
+            region,
+            offset,
+            keccak_input
+                .map(|randomness| rlc::value(aux_data.input_bytes.iter().rev(), randomness)),
+        )?;
+        self.output_bytes_rlc.assign(
+            region,
+            offset,
+            keccak_input
+                .map(|randomness| rlc::value(aux_data.output_bytes.iter().rev(), randomness)),
+        )?;
+
+        self.is_input_len_valid.assign(
+            region,
+            offset,
+            F::from(call.call_data_length),
+            F::from(N_BYTES_POINT_EVALUATION_INPUT as u64),
+        )?;
+        // A call with an input of the right length fails only if the evaluation
+        // is invalid.
+        self.is_valid.assign(
+            region,
+            offset,
+            Value::known(F::from(u64::from(call.is_success))),
+        )?;
+
+        self.is_success.assign(
+            region,
+            offset,
+            Value::known(F::from(u64::from(call.is_success))),
+        )?;
+        self.callee_address.assign(
+            region,
+            offset,
+            Value::known(call.code_address().unwrap().to_scalar().unwrap()),
+        )?;
+        self.caller_id.assign(
+            region,
+            offset,
+            Value::known(F::from(call.caller_id.try_into().unwrap())),
+        )?;
+        self.call_data_offset.assign(
+            region,
+            offset,
+            Value::known(F::from(call.call_data_offset)),
+        )?;
+        self.call_data_length.assign(
+            region,
+            offset,
+            Value::known(F::from(call.call_data_length)),
+        )?;
+        self.return_data_offset.assign(
+            region,
+            offset,
+            Value::known(F::from(call.return_data_offset)),
+        )?;
+        self.return_data_length.assign(
+            region,
+            offset,
+            Value::known(F::from(call.return_data_length)),
+        )?;
+        self.restore_context
+            .assign(region, offset, block, call, step, 7)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bus_mapping::{
+        evm::{OpcodeId, PrecompileCallArgs},
+        precompile::{PrecompileCalls, N_BYTES_POINT_EVALUATION_INPUT},
+    };
+    use eth_types::{Bytecode, ToWord, Word};
+    use itertools::Itertools;
+    use mock::TestContext;
+
+    use crate::test_util::CircuitTestBuilder;
+
+    /// Test vector of the point evaluation precompile of go-ethereum, i.e. the
+    /// versioned hash, `z`, `y`, the commitment and the proof.
+    const INPUT: &str = "01e798154708fe7789429634053cbf9f99b619f9f084048927333fce637f549b564c0a11a0f704f4fc3e8acfe0f8245f0ad1347b378fbf96e206da11a5d3630624d25032e67a7e6a4910df5834b8fe70e6bcfeeac0352434196bdf4b2485d5a18f59a8d2a1a625a17f3fea0fe5eb8c896db3764f3185481bc22f91b4aaffcca25f26936857bc3a7c2539ea8ec3a952b7873033e038326e87ed3e1276fd140253fa08e9fc25fb2d9a98527fc22a2c9612fbeafdad446cbc7bcdbdcd780af2c16a";
+
+    /// Stores at memory offset 0 the test vector with the given byte
+    /// overwritten, if any.
+    fn setup_code(patch: Option<(usize, u8)>) -> Bytecode {
+        let mut input = hex::decode(INPUT).unwrap();
+        if let Some((idx, byte)) = patch {
+            input[idx] = byte;
+        }
+        let mut code = Bytecode::default();
+        for (i, chunk) in input.chunks(32).enumerate() {
+            code.push(32, Word::from_big_endian(chunk))
+                .push(32, Word::from(i * 32))
+                .write_op(OpcodeId::MSTORE);
+        }
+        code
+    }
+
+    lazy_static::lazy_static! {
+        static ref TEST_VECTOR: Vec<PrecompileCallArgs> = {
+            vec![
+                PrecompileCallArgs {
+                    name: "valid evaluation",
+                    setup_code: setup_code(None),
+                    call_data_offset: 0x00.into(),
+                    call_data_length: N_BYTES_POINT_EVALUATION_INPUT.into(),
+                    ret_offset: 0x00.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::PointEvaluation.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "valid evaluation, output partially returned",
+                    setup_code: setup_code(None),
+                    call_data_offset: 0x00.into(),
+                    call_data_length: N_BYTES_POINT_EVALUATION_INPUT.into(),
+                    ret_offset: 0x20.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::PointEvaluation.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "invalid input length",
+                    setup_code: setup_code(None),
+                    call_data_offset: 0x00.into(),
+                    call_data_length: (N_BYTES_POINT_EVALUATION_INPUT - 1).into(),
+                    ret_offset: 0x00.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::PointEvaluation.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "invalid versioned hash",
+                    setup_code: setup_code(Some((0, 0x02))),
+                    call_data_offset: 0x00.into(),
+                    call_data_length: N_BYTES_POINT_EVALUATION_INPUT.into(),
+                    ret_offset: 0x00.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::PointEvaluation.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "invalid proof",
+                    setup_code: setup_code(Some((N_BYTES_POINT_EVALUATION_INPUT - 1, 0x6b))),
+                    call_data_offset: 0x00.into(),
+                    call_data_length: N_BYTES_POINT_EVALUATION_INPUT.into(),
+                    ret_offset: 0x00.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::PointEvaluation.address().to_word(),
+                    ..Default::default()
+                },
+            ]
+        };
+    }
+
+    #[test]
+    fn precompile_point_evaluation_test() {
+        let call_kinds = vec![
+            OpcodeId::CALL,
+            OpcodeId::STATICCALL,
+            OpcodeId::DELEGATECALL,
+            OpcodeId::CALLCODE,
+        ];
+
+        for (test_vector, &call_kind) in TEST_VECTOR.iter().cartesian_product(&call_kinds) {
+            let bytecode = test_vector.with_call_op(call_kind);
+
+            CircuitTestBuilder::new_from_test_ctx(
+                TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+            )
+            .run();
+        }
+    }
+}
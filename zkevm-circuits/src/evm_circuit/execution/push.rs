@@ -5,7 +5,7 @@ use crate::{
         step::ExecutionState,
         util::{
             and,
-            common_gadget::SameContextGadget,
+            common_gadget::{HardforkGadget, SameContextGadget},
             constraint_builder::{
                 ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
                 Transition::Delta,
@@ -21,13 +21,17 @@ use crate::{
     },
 };
 use array_init::array_init;
-use eth_types::{evm_types::OpcodeId, Field};
+use eth_types::{
+    evm_types::{Hardfork, OpcodeId},
+    Field,
+};
 use halo2_proofs::{circuit::Value, plonk::Error};
 
 #[derive(Clone, Debug)]
 pub(crate) struct PushGadget<F> {
     same_context: SameContextGadget<F>,
     is_push0: IsZeroGadget<F>,
+    hardfork: HardforkGadget<F>,
     value: Word32Cell<F>,
     is_pushed: [Cell<F>; 32],
     is_padding: [Cell<F>; 32],
@@ -44,6 +48,13 @@ impl<F: Field> ExecutionGadget<F> for PushGadget<F> {
         let opcode = cb.query_cell();
         let is_push0 = IsZeroGadget::construct(cb, opcode.expr() - OpcodeId::PUSH0.expr());
 
+        // PUSH0 is only defined since Shanghai (EIP-3855).
+        let hardfork = HardforkGadget::construct(cb);
+        cb.require_zero(
+            "PUSH0 is defined in the hardfork",
+            is_push0.expr() * not::expr(hardfork.is_active(Hardfork::Shanghai)),
+        );
+
         let value = cb.query_word32();
         cb.stack_push(value.to_word());
 
@@ -147,6 +158,7 @@ impl<F: Field> ExecutionGadget<F> for PushGadget<F> {
         Self {
             same_context,
             is_push0,
+            hardfork,
             value,
             is_pushed,
             is_padding,
@@ -172,6 +184,8 @@ impl<F: Field> ExecutionGadget<F> for PushGadget<F> {
             offset,
             F::from(opcode.as_u64() - OpcodeId::PUSH0.as_u64()),
         )?;
+        self.hardfork
+            .assign(region, offset, block.context.hardfork)?;
 
         let bytecode = block
             .bytecodes
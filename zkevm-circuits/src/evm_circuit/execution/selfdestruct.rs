@@ -0,0 +1,478 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        step::ExecutionState,
+        util::{
+            common_gadget::{RestoreContextGadget, TransferGadget},
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
+                Transition::{Delta, Same},
+            },
+            math_gadget::{IsEqualWordGadget, IsZeroWordGadget},
+            not, select, AccountAddress, CachedRegion, Cell, StepRws,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::{AccountFieldTag, CallContextFieldTag},
+    util::{
+        word::{Word, Word32Cell, WordCell, WordExpr},
+        Expr,
+    },
+};
+use bus_mapping::evm::OpcodeId;
+use eth_types::{evm_types::GasCost, Field, ToAddress, ToWord, U256};
+use halo2_proofs::{
+    circuit::Value,
+    plonk::{Error, Expression},
+};
+
+/// Gadget for SELFDESTRUCT following EIP-6780: the whole balance is moved to
+/// the beneficiary, and the account is deleted only when it was created in
+/// the same transaction.
+#[derive(Clone, Debug)]
+pub(crate) struct SelfDestructGadget<F> {
+    opcode: Cell<F>,
+    beneficiary: AccountAddress<F>,
+    tx_id: Cell<F>,
+    callee_address: WordCell<F>,
+    reversion_info: ReversionInfo<F>,
+    is_warm: Cell<F>,
+    beneficiary_code_hash: WordCell<F>,
+    beneficiary_not_exists: IsZeroWordGadget<F, WordCell<F>>,
+    balance: Word32Cell<F>,
+    is_created: Cell<F>,
+    is_beneficiary_self: IsEqualWordGadget<F, Word<Expression<F>>, Word<Expression<F>>>,
+    transfer_value: Word32Cell<F>,
+    transfer: TransferGadget<F>,
+    callee_nonce: Cell<F>,
+    callee_code_hash: WordCell<F>,
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for SelfDestructGadget<F> {
+    const NAME: &'static str = "SELFDESTRUCT";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::SELFDESTRUCT;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+        cb.opcode_lookup(opcode.expr(), 1.expr());
+
+        // We do the responsible opcode check explicitly here because we're not using
+        // the `SameContextGadget` for `SELFDESTRUCT`.
+        cb.require_equal(
+            "Opcode should be SELFDESTRUCT",
+            opcode.expr(),
+            OpcodeId::SELFDESTRUCT.expr(),
+        );
+
+        let beneficiary = cb.query_account_address();
+        cb.stack_pop(beneficiary.to_word());
+
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+        // Call ends with SELFDESTRUCT must be successful
+        cb.call_context_lookup_read(None, CallContextFieldTag::IsSuccess, Word::one());
+        let callee_address = cb.call_context_read_as_word(None, CallContextFieldTag::CalleeAddress);
+        let mut reversion_info = cb.reversion_info_read(None);
+
+        let is_warm = cb.query_bool();
+        cb.account_access_list_write_unchecked(
+            tx_id.expr(),
+            beneficiary.to_word(),
+            1.expr(),
+            is_warm.expr(),
+            Some(&mut reversion_info),
+        );
+
+        // For non-existing accounts the code_hash must be 0 in the rw_table.
+        let beneficiary_code_hash = cb.query_word_unchecked();
+        cb.account_read(
+            beneficiary.to_word(),
+            AccountFieldTag::CodeHash,
+            beneficiary_code_hash.to_word(),
+        );
+        let beneficiary_not_exists = IsZeroWordGadget::construct(cb, &beneficiary_code_hash);
+
+        let balance = cb.query_word32();
+        cb.account_read(
+            callee_address.to_word(),
+            AccountFieldTag::Balance,
+            balance.to_word(),
+        );
+
+        let is_created = cb.query_bool();
+        cb.tx_created_account_read(tx_id.expr(), callee_address.to_word(), is_created.expr());
+
+        // Move the whole balance to the beneficiary. Nothing is moved when the
+        // beneficiary is the account itself.
+        let is_beneficiary_self =
+            IsEqualWordGadget::construct(cb, &beneficiary.to_word(), &callee_address.to_word());
+        let transfer_value = cb.query_word32();
+        cb.condition(is_beneficiary_self.expr(), |cb| {
+            cb.require_zero_word(
+                "transfer_value is 0 when beneficiary is self",
+                transfer_value.to_word(),
+            );
+        });
+        cb.condition(not::expr(is_beneficiary_self.expr()), |cb| {
+            cb.require_equal_word(
+                "transfer_value is the whole balance",
+                transfer_value.to_word(),
+                balance.to_word(),
+            );
+        });
+        let transfer = TransferGadget::construct(
+            cb,
+            callee_address.to_word(),
+            beneficiary.to_word(),
+            not::expr(beneficiary_not_exists.expr()),
+            0.expr(),
+            transfer_value.clone(),
+            &mut reversion_info,
+        );
+
+        // EIP-6780, delete the account only if it was created in this tx. A
+        // balance sent to itself is burnt.
+        let callee_nonce = cb.query_cell();
+        let callee_code_hash = cb.query_word_unchecked();
+        cb.condition(is_created.expr(), |cb| {
+            for (field_tag, value_prev) in [
+                (
+                    AccountFieldTag::Balance,
+                    balance.to_word().mul_selector(is_beneficiary_self.expr()),
+                ),
+                (
+                    AccountFieldTag::Nonce,
+                    Word::from_lo_unchecked(callee_nonce.expr()),
+                ),
+                (AccountFieldTag::CodeHash, callee_code_hash.to_word()),
+            ] {
+                cb.account_write(
+                    callee_address.to_word(),
+                    field_tag,
+                    Word::zero(),
+                    value_prev,
+                    Some(&mut reversion_info),
+                );
+            }
+        });
+
+        // No refund since EIP-3529.
+        let gas_cost = GasCost::SELFDESTRUCT.expr()
+            + select::expr(
+                is_warm.expr(),
+                0.expr(),
+                GasCost::COLD_ACCOUNT_ACCESS.expr(),
+            )
+            + beneficiary_not_exists.expr()
+                * not::expr(transfer.value_is_zero.expr())
+                * GasCost::NEW_ACCOUNT.expr();
+
+        let reversible_write_counter_increase =
+            1.expr() + transfer.reversible_w_delta() + is_created.expr() * 3.expr();
+
+        let is_to_end_tx = cb.next.execution_state_selector([ExecutionState::EndTx]);
+        cb.require_equal(
+            "Go to EndTx only when is_root",
+            cb.curr.state.is_root.expr(),
+            is_to_end_tx,
+        );
+
+        // When it's a root call
+        cb.condition(cb.curr.state.is_root.expr(), |cb| {
+            cb.require_step_state_transition(StepStateTransition {
+                call_id: Same,
+                rw_counter: Delta(cb.rw_counter_offset()),
+                gas_left: Delta(-gas_cost.clone()),
+                reversible_write_counter: Delta(reversible_write_counter_increase.clone()),
+                ..StepStateTransition::any()
+            });
+        });
+
+        // When it's an internal call, the gas cost is charged like a memory
+        // expansion cost since there's no return data.
+        let restore_context = cb.condition(not::expr(cb.curr.state.is_root.expr()), |cb| {
+            RestoreContextGadget::construct(
+                cb,
+                true.expr(),
+                0.expr(),
+                0.expr(),
+                0.expr(),
+                gas_cost,
+                reversible_write_counter_increase,
+            )
+        });
+
+        Self {
+            opcode,
+            beneficiary,
+            tx_id,
+            callee_address,
+            reversion_info,
+            is_warm,
+            beneficiary_code_hash,
+            beneficiary_not_exists,
+            balance,
+            is_created,
+            is_beneficiary_self,
+            transfer_value,
+            transfer,
+            callee_nonce,
+            callee_code_hash,
+            restore_context,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let opcode = step.opcode().unwrap();
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
+
+        let mut rws = StepRws::new(block, step);
+
+        let beneficiary = rws.next().stack_value().to_address();
+        self.beneficiary.assign_h160(region, offset, beneficiary)?;
+
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx.id)))?;
+        self.callee_address
+            .assign_h160(region, offset, call.address)?;
+        self.reversion_info.assign(
+            region,
+            offset,
+            call.rw_counter_end_of_reversion,
+            call.is_persistent,
+        )?;
+        rws.offset_add(5);
+
+        let (_, is_warm) = rws.next().tx_access_list_value_pair();
+        self.is_warm
+            .assign(region, offset, Value::known(F::from(is_warm as u64)))?;
+
+        let beneficiary_code_hash = rws.next().account_codehash_pair().0;
+        self.beneficiary_code_hash
+            .assign_u256(region, offset, beneficiary_code_hash)?;
+        self.beneficiary_not_exists
+            .assign_u256(region, offset, beneficiary_code_hash)?;
+
+        let balance = rws.next().account_balance_pair().0;
+        self.balance.assign_u256(region, offset, balance)?;
+
+        let (is_created, _) = rws.next().tx_created_account_value_pair();
+        self.is_created
+            .assign(region, offset, Value::known(F::from(is_created as u64)))?;
+
+        let is_beneficiary_self = beneficiary == call.address;
+        self.is_beneficiary_self.assign_u256(
+            region,
+            offset,
+            beneficiary.to_word(),
+            call.address.to_word(),
+        )?;
+
+        let transfer_value = if is_beneficiary_self {
+            U256::zero()
+        } else {
+            balance
+        };
+        self.transfer_value
+            .assign_u256(region, offset, transfer_value)?;
+        if !transfer_value.is_zero() {
+            if beneficiary_code_hash.is_zero() {
+                rws.next(); // beneficiary code hash
+            }
+            let sender_balance_pair = rws.next().account_balance_pair();
+            let receiver_balance_pair = rws.next().account_balance_pair();
+            self.transfer.assign(
+                region,
+                offset,
+                sender_balance_pair,
+                receiver_balance_pair,
+                transfer_value,
+            )?;
+        }
+
+        let (callee_nonce, callee_code_hash) = if is_created {
+            rws.next(); // balance
+            let callee_nonce = rws.next().account_nonce_pair().1;
+            let callee_code_hash = rws.next().account_codehash_pair().1;
+            (callee_nonce, callee_code_hash)
+        } else {
+            (U256::zero(), U256::zero())
+        };
+        self.callee_nonce.assign(
+            region,
+            offset,
+            Value::known(F::from(callee_nonce.low_u64())),
+        )?;
+        self.callee_code_hash
+            .assign_u256(region, offset, callee_code_hash)?;
+
+        if !call.is_root {
+            let transfer_rws = if transfer_value.is_zero() {
+                0
+            } else {
+                2 + beneficiary_code_hash.is_zero() as usize
+            };
+            let rw_offset = 10 + transfer_rws + if is_created { 3 } else { 0 };
+            self.restore_context
+                .assign(region, offset, block, call, step, rw_offset)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::{address, bytecode, evm_types::OpcodeId, Address, Bytecode, ToWord, Word};
+    use mock::{eth, TestContext};
+
+    const CONTRACT_ADDRESS: Address = Address::repeat_byte(0xfe);
+    const CALLER_ADDRESS: Address = Address::repeat_byte(0x34);
+    const BENEFICIARY_ADDRESS: Address = Address::repeat_byte(0xbe);
+
+    fn selfdestruct_bytecode(beneficiary: Address) -> Bytecode {
+        bytecode! {
+            PUSH20(beneficiary.to_word())
+            SELFDESTRUCT
+        }
+    }
+
+    // Deploy a contract whose init code selfdestructs, so the account is
+    // created and destructed in the same transaction.
+    fn create_bytecode(beneficiary: Address, value: Word) -> Bytecode {
+        let init_code = selfdestruct_bytecode(beneficiary).code();
+        let mut code = bytecode! {
+            PUSH32(Word::from_big_endian(&init_code))
+            PUSH1(0)
+            MSTORE
+            PUSH1(init_code.len()) // size
+            PUSH1(32 - init_code.len()) // offset
+            PUSH2(value) // value
+        };
+        code.write_op(OpcodeId::CREATE);
+        code.append(&bytecode! { STOP });
+        code
+    }
+
+    fn test_root_ok(code: Bytecode, beneficiary_exists: bool) {
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x000000000000000000000000000000000000cafe"))
+                    .balance(eth(10));
+                accs[1]
+                    .address(CONTRACT_ADDRESS)
+                    .balance(Word::from(1u64 << 20))
+                    .code(code);
+                if beneficiary_exists {
+                    accs[2].address(BENEFICIARY_ADDRESS).balance(eth(1));
+                } else {
+                    accs[2]
+                        .address(address!("0x0000000000000000000000000000000000000020"))
+                        .balance(eth(1));
+                }
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .gas(Word::from(1_000_000u64));
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    fn test_internal_ok(code: Bytecode, is_persistent: bool) {
+        let mut caller_code = bytecode! {
+            PUSH1(0) // retLength
+            PUSH1(0) // retOffset
+            PUSH1(0) // argsLength
+            PUSH1(0) // argsOffset
+            PUSH1(0) // value
+            PUSH20(CONTRACT_ADDRESS.to_word())
+            GAS
+            CALL
+        };
+        if is_persistent {
+            caller_code.append(&bytecode! { STOP });
+        } else {
+            caller_code.append(&bytecode! {
+                PUSH1(0)
+                PUSH1(0)
+                REVERT
+            });
+        }
+
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x000000000000000000000000000000000000cafe"))
+                    .balance(eth(10));
+                accs[1]
+                    .address(CALLER_ADDRESS)
+                    .balance(Word::from(1u64 << 20))
+                    .code(caller_code);
+                accs[2]
+                    .address(CONTRACT_ADDRESS)
+                    .balance(Word::from(1u64 << 20))
+                    .code(code);
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .gas(Word::from(1_000_000u64));
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn selfdestruct_gadget_to_non_existing_beneficiary() {
+        test_root_ok(selfdestruct_bytecode(BENEFICIARY_ADDRESS), false);
+    }
+
+    #[test]
+    fn selfdestruct_gadget_to_existing_beneficiary() {
+        test_root_ok(selfdestruct_bytecode(BENEFICIARY_ADDRESS), true);
+    }
+
+    #[test]
+    fn selfdestruct_gadget_to_self() {
+        test_root_ok(selfdestruct_bytecode(CONTRACT_ADDRESS), false);
+    }
+
+    #[test]
+    fn selfdestruct_gadget_internal_call() {
+        test_internal_ok(selfdestruct_bytecode(BENEFICIARY_ADDRESS), true);
+        test_internal_ok(selfdestruct_bytecode(BENEFICIARY_ADDRESS), false);
+    }
+
+    #[test]
+    fn selfdestruct_gadget_created_in_same_tx() {
+        test_root_ok(
+            create_bytecode(BENEFICIARY_ADDRESS, Word::from(0x100)),
+            true,
+        );
+        test_root_ok(create_bytecode(BENEFICIARY_ADDRESS, Word::zero()), false);
+    }
+}
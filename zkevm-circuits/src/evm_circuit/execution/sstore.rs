@@ -4,7 +4,7 @@ use crate::{
         param::N_BYTES_GAS,
         step::ExecutionState,
         util::{
-            common_gadget::{SameContextGadget, SstoreGasGadget},
+            common_gadget::{HardforkGadget, SameContextGadget, SstoreGasGadget},
             constraint_builder::{
                 ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
                 Transition::Delta,
@@ -21,7 +21,10 @@ use crate::{
     },
 };
 
-use eth_types::{evm_types::GasCost, Field};
+use eth_types::{
+    evm_types::{GasCost, Hardfork},
+    Field,
+};
 use halo2_proofs::{
     circuit::Value,
     plonk::{Error, Expression},
@@ -43,6 +46,7 @@ pub(crate) struct SstoreGadget<F> {
     // Constrain for SSTORE reentrancy sentry.
     sufficient_gas_sentry: LtGadget<F, N_BYTES_GAS>,
     gas_cost: SstoreGasGadget<F, Word32Cell<F>>,
+    hardfork: HardforkGadget<F>,
     tx_refund: SstoreTxRefundGadget<F>,
 }
 
@@ -119,6 +123,8 @@ impl<F: Field> ExecutionGadget<F> for SstoreGadget<F> {
             original_value.clone(),
         );
 
+        // The refund of a storage clear was reduced by EIP-3529 (London).
+        let hardfork = HardforkGadget::construct(cb);
         let tx_refund_prev = cb.query_u64();
         let tx_refund = SstoreTxRefundGadget::construct(
             cb,
@@ -126,6 +132,7 @@ impl<F: Field> ExecutionGadget<F> for SstoreGadget<F> {
             value.clone(),
             value_prev.clone(),
             original_value.clone(),
+            hardfork.select(|hardfork| hardfork.sstore_clears_schedule()),
         );
         cb.tx_refund_write(
             tx_id.expr(),
@@ -158,6 +165,7 @@ impl<F: Field> ExecutionGadget<F> for SstoreGadget<F> {
             tx_refund_prev,
             sufficient_gas_sentry,
             gas_cost,
+            hardfork,
             tx_refund,
         }
     }
@@ -214,6 +222,8 @@ impl<F: Field> ExecutionGadget<F> for SstoreGadget<F> {
         self.gas_cost
             .assign(region, offset, value, value_prev, original_value, is_warm)?;
 
+        self.hardfork
+            .assign(region, offset, block.context.hardfork)?;
         self.tx_refund.assign(
             region,
             offset,
@@ -222,6 +232,7 @@ impl<F: Field> ExecutionGadget<F> for SstoreGadget<F> {
             value,
             value_prev,
             original_value,
+            block.context.hardfork,
         )?;
         Ok(())
     }
@@ -246,6 +257,7 @@ impl<F: Field> SstoreTxRefundGadget<F> {
         value: T,
         value_prev: T,
         original_value: T,
+        sstore_clears_schedule: Expression<F>,
     ) -> Self {
         let value_prev_is_zero_gadget = IsZeroWordGadget::construct(cb, &value_prev.to_word());
         let value_is_zero_gadget = IsZeroWordGadget::construct(cb, &value.to_word());
@@ -285,10 +297,10 @@ impl<F: Field> SstoreTxRefundGadget<F> {
             not::expr(prev_eq_value) * not::expr(original_eq_prev) * (value_prev_is_zero);
 
         let tx_refund_new = tx_refund_old.expr()
-            + delete_slot * GasCost::SSTORE_CLEARS_SCHEDULE.expr()
+            + delete_slot * sstore_clears_schedule.expr()
             + reset_existing * (GasCost::SSTORE_RESET.expr() - GasCost::WARM_ACCESS.expr())
             + reset_inexistent * (GasCost::SSTORE_SET.expr() - GasCost::WARM_ACCESS.expr())
-            - recreate_slot * (GasCost::SSTORE_CLEARS_SCHEDULE.expr());
+            - recreate_slot * sstore_clears_schedule;
 
         Self {
             tx_refund_old,
@@ -317,6 +329,7 @@ impl<F: Field> SstoreTxRefundGadget<F> {
         value: eth_types::Word,
         value_prev: eth_types::Word,
         original_value: eth_types::Word,
+        hardfork: Hardfork,
     ) -> Result<(), Error> {
         self.tx_refund_old
             .assign(region, offset, Some(tx_refund_old.to_le_bytes()))?;
@@ -345,7 +358,7 @@ impl<F: Field> SstoreTxRefundGadget<F> {
             Word::from(value_prev),
         )?;
         debug_assert_eq!(
-            calc_expected_tx_refund(tx_refund_old, value, value_prev, original_value),
+            calc_expected_tx_refund(tx_refund_old, value, value_prev, original_value, hardfork),
             tx_refund
         );
         Ok(())
@@ -357,6 +370,7 @@ fn calc_expected_tx_refund(
     value: eth_types::Word,
     value_prev: eth_types::Word,
     original_value: eth_types::Word,
+    hardfork: Hardfork,
 ) -> u64 {
     // Same clause tags(like "delete slot (2.1.2b)") used as [`makeGasSStoreFunc` in go-ethereum](https://github.com/ethereum/go-ethereum/blob/9fd8825d5a196edde6d8ef81382979875145b346/core/vm/operations_acl.go#L27)
     // Control flow of this function try to follow `makeGasSStoreFunc` for better
@@ -383,11 +397,11 @@ fn calc_expected_tx_refund(
         if !original_value.is_zero() {
             if value_prev.is_zero() {
                 // recreate slot (2.2.1.1)
-                tx_refund_new -= GasCost::SSTORE_CLEARS_SCHEDULE
+                tx_refund_new -= hardfork.sstore_clears_schedule()
             }
             if value.is_zero() {
                 // delete slot (2.2.1.2)
-                tx_refund_new += GasCost::SSTORE_CLEARS_SCHEDULE
+                tx_refund_new += hardfork.sstore_clears_schedule()
             }
         }
 
@@ -410,7 +424,7 @@ fn calc_expected_tx_refund(
 mod test {
 
     use crate::test_util::CircuitTestBuilder;
-    use eth_types::{bytecode, Word};
+    use eth_types::{bytecode, evm_types::Hardfork, Word};
     use mock::{test_ctx::helpers::tx_from_1_to_0, TestContext, MOCK_ACCOUNTS};
 
     #[test]
@@ -474,7 +488,38 @@ mod test {
         );
     }
 
+    #[test]
+    fn sstore_gadget_clears_schedule_by_hardfork() {
+        // Before EIP-3529, clearing a slot is refunded with 15000 gas.
+        for hardfork in [Hardfork::Berlin, Hardfork::London] {
+            test_ok_with_hardfork(
+                0x030201.into(),
+                0x0.into(),
+                0x060505.into(),
+                0x060506.into(),
+                hardfork,
+            );
+            test_ok_with_hardfork(
+                0x030201.into(),
+                0x060504.into(),
+                0x0.into(),
+                0x060506.into(),
+                hardfork,
+            );
+        }
+    }
+
     fn test_ok(key: Word, value: Word, value_prev: Word, original_value: Word) {
+        test_ok_with_hardfork(key, value, value_prev, original_value, Hardfork::default());
+    }
+
+    fn test_ok_with_hardfork(
+        key: Word,
+        value: Word,
+        value_prev: Word,
+        original_value: Word,
+        hardfork: Hardfork,
+    ) {
         // Here we use two bytecodes to test both is_persistent(STOP) or not(REVERT)
         // Besides, in bytecode we use two SSTOREs,
         // the first SSTORE is used to test cold,  and the second is used to test warm
@@ -512,7 +557,7 @@ mod test {
                         .balance(Word::from(10u64.pow(19)));
                 },
                 tx_from_1_to_0,
-                |block, _txs| block,
+                |block, _txs| block.hardfork(hardfork),
             )
             .unwrap();
 
@@ -0,0 +1,243 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        step::ExecutionState,
+        util::{
+            common_gadget::TransferToGadget,
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
+                Transition::{Delta, Same},
+            },
+            math_gadget::IsZeroWordGadget,
+            CachedRegion, Cell, Word32Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::AccountFieldTag,
+    util::{
+        word::{Word, WordCell, WordExpr},
+        Expr,
+    },
+};
+use eth_types::Field;
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gwei to Wei conversion factor of the withdrawal amount
+const GWEI: u64 = 1_000_000_000;
+
+#[derive(Clone, Debug)]
+pub(crate) struct WithdrawalGadget<F> {
+    id: Cell<F>,
+    validator_id: Cell<F>,
+    address: WordCell<F>,
+    amount: Cell<F>,
+    amount_in_wei: Word32Cell<F>,
+    code_hash: WordCell<F>,
+    code_hash_is_zero: IsZeroWordGadget<F, WordCell<F>>,
+    transfer: TransferToGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for WithdrawalGadget<F> {
+    const NAME: &'static str = "Withdrawal";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::Withdrawal;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // The program counter holds the index of the withdrawal, starting from 1
+        let index = cb.curr.state.program_counter.clone();
+        cb.step_first(|cb| {
+            cb.require_equal("first withdrawal has index 1", index.expr(), 1.expr());
+        });
+
+        let id = cb.query_cell();
+        let validator_id = cb.query_cell();
+        let address = cb.query_word_unchecked();
+        let amount = cb.query_cell();
+        cb.wd_table_lookup(
+            index.expr(),
+            id.expr(),
+            validator_id.expr(),
+            address.to_word(),
+            amount.expr(),
+        );
+
+        // The amount in the wd table is a u64 in Gwei, so the amount in Wei fits in
+        // the low 128 bits.
+        let amount_in_wei = cb.query_word32();
+        cb.require_equal_word(
+            "amount_in_wei == amount * 1e9",
+            amount_in_wei.to_word(),
+            Word::from_lo_unchecked(amount.expr() * GWEI.expr()),
+        );
+
+        // Credit the amount to the address, creating the account if it doesn't exist
+        let code_hash = cb.query_word_unchecked();
+        let code_hash_is_zero = IsZeroWordGadget::construct(cb, &code_hash);
+        cb.account_read(
+            address.to_word(),
+            AccountFieldTag::CodeHash,
+            code_hash.to_word(),
+        );
+        let transfer = TransferToGadget::construct(
+            cb,
+            address.to_word(),
+            1.expr() - code_hash_is_zero.expr(),
+            false.expr(),
+            amount_in_wei.clone(),
+            None,
+            true,
+        );
+
+        let rw_counter = Delta(1.expr() + transfer.rw_delta());
+        cb.condition(
+            cb.next
+                .execution_state_selector([ExecutionState::Withdrawal]),
+            |cb| {
+                cb.require_step_state_transition(StepStateTransition {
+                    rw_counter: rw_counter.clone(),
+                    call_id: Same,
+                    program_counter: Delta(1.expr()),
+                    ..StepStateTransition::any()
+                });
+            },
+        );
+        cb.condition(
+            cb.next.execution_state_selector([ExecutionState::EndBlock]),
+            |cb| {
+                // EndBlock gets the number of processed withdrawals from the program counter
+                cb.require_step_state_transition(StepStateTransition {
+                    rw_counter,
+                    call_id: Same,
+                    program_counter: Same,
+                    ..StepStateTransition::any()
+                });
+            },
+        );
+
+        Self {
+            id,
+            validator_id,
+            address,
+            amount,
+            amount_in_wei,
+            code_hash,
+            code_hash_is_zero,
+            transfer,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _: &Transaction,
+        _: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let withdrawal = &block.withdrawals()[step.pc as usize - 1];
+        let amount_in_wei = withdrawal.amount_in_wei();
+
+        self.id
+            .assign(region, offset, Value::known(F::from(withdrawal.id)))?;
+        self.validator_id.assign(
+            region,
+            offset,
+            Value::known(F::from(withdrawal.validator_id)),
+        )?;
+        self.address
+            .assign_h160(region, offset, withdrawal.address)?;
+        self.amount
+            .assign(region, offset, Value::known(F::from(withdrawal.amount)))?;
+        self.amount_in_wei
+            .assign_u256(region, offset, amount_in_wei)?;
+
+        let (code_hash_prev, _) = block.get_rws(step, 0).account_codehash_pair();
+        self.code_hash.assign_u256(region, offset, code_hash_prev)?;
+        self.code_hash_is_zero
+            .assign_u256(region, offset, code_hash_prev)?;
+        if !amount_in_wei.is_zero() {
+            let balance_pair = block
+                .get_rws(step, step.rw_indices_len() - 1)
+                .account_balance_pair();
+            self.transfer
+                .assign(region, offset, balance_pair, amount_in_wei)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{test_util::CircuitTestBuilder, witness::block_convert};
+    use bus_mapping::{circuit_input_builder::FixedCParams, mock::BlockData};
+    use eth_types::{address, bytecode, geth_types::GethData, Address, Word};
+    use halo2_proofs::halo2curves::bn256::Fr;
+    use mock::{TestContext2, MOCK_ACCOUNTS};
+
+    fn test_ok<const NWD: usize>(withdrawals: [(Address, u64); NWD]) {
+        let ctx = TestContext2::<2, 1, NWD>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(Word::from(10u64.pow(19)));
+                accs[1].address(MOCK_ACCOUNTS[1]).code(bytecode! { STOP });
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[0].address).to(accs[1].address);
+            },
+            |wds| {
+                for (i, (wd, (address, amount))) in wds.into_iter().zip(withdrawals).enumerate() {
+                    wd.id(1000 + i as u64)
+                        .validator_id(20000 + i as u64)
+                        .address(address)
+                        .amount(amount);
+                }
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        let block: GethData = ctx.into();
+        let mut builder = BlockData::new_from_geth_data_with_params(
+            block.clone(),
+            FixedCParams {
+                max_withdrawals: NWD,
+                ..Default::default()
+            },
+        )
+        .new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder).unwrap();
+
+        CircuitTestBuilder::<2, 1>::new_from_block(block).run();
+    }
+
+    #[test]
+    fn withdrawal_to_existing_account() {
+        test_ok([(MOCK_ACCOUNTS[0], 32)]);
+    }
+
+    #[test]
+    fn withdrawal_to_new_account() {
+        test_ok([(address!("0x00000000000000000000000000000000000cafe1"), 32)]);
+    }
+
+    #[test]
+    fn withdrawal_zero_amount() {
+        test_ok([(address!("0x00000000000000000000000000000000000cafe1"), 0)]);
+    }
+
+    #[test]
+    fn withdrawals_multiple() {
+        test_ok([
+            (MOCK_ACCOUNTS[1], 1),
+            (MOCK_ACCOUNTS[0], u64::MAX),
+            (MOCK_ACCOUNTS[1], 2),
+        ]);
+    }
+}
@@ -24,7 +24,7 @@ pub const N_PHASE1_COLUMNS: usize =
     STEP_WIDTH - EVM_LOOKUP_COLS - N_PHASE2_COLUMNS - N_COPY_COLUMNS - N_U8_LOOKUPS - N_U16_LOOKUPS;
 
 /// Number of copy columns
-pub const N_COPY_COLUMNS: usize = 2;
+pub const N_COPY_COLUMNS: usize = 3;
 
 /// Number of columns reserved for u8 lookup
 pub const N_U8_LOOKUPS: usize = 24;
@@ -41,7 +41,15 @@ pub(crate) const EVM_LOOKUP_COLS: usize = FIXED_TABLE_LOOKUPS
     + BLOCK_TABLE_LOOKUPS
     + COPY_TABLE_LOOKUPS
     + KECCAK_TABLE_LOOKUPS
-    + EXP_TABLE_LOOKUPS;
+    + EXP_TABLE_LOOKUPS
+    + SIG_TABLE_LOOKUPS
+    + SHA256_TABLE_LOOKUPS
+    + MODEXP_TABLE_LOOKUPS
+    + ECC_TABLE_LOOKUPS
+    + RIPEMD160_TABLE_LOOKUPS
+    + BLAKE2F_TABLE_LOOKUPS
+    + KZG_TABLE_LOOKUPS
+    + WD_TABLE_LOOKUPS;
 
 /// Lookups done per row.
 pub const LOOKUP_CONFIG: &[(Table, usize)] = &[
@@ -53,6 +61,14 @@ pub const LOOKUP_CONFIG: &[(Table, usize)] = &[
     (Table::Copy, COPY_TABLE_LOOKUPS),
     (Table::Keccak, KECCAK_TABLE_LOOKUPS),
     (Table::Exp, EXP_TABLE_LOOKUPS),
+    (Table::Sig, SIG_TABLE_LOOKUPS),
+    (Table::Sha256, SHA256_TABLE_LOOKUPS),
+    (Table::ModExp, MODEXP_TABLE_LOOKUPS),
+    (Table::Ecc, ECC_TABLE_LOOKUPS),
+    (Table::Ripemd160, RIPEMD160_TABLE_LOOKUPS),
+    (Table::Blake2f, BLAKE2F_TABLE_LOOKUPS),
+    (Table::Kzg, KZG_TABLE_LOOKUPS),
+    (Table::Wd, WD_TABLE_LOOKUPS),
 ];
 
 /// Fixed Table lookups done in EVMCircuit
@@ -79,6 +95,30 @@ pub const KECCAK_TABLE_LOOKUPS: usize = 1;
 /// Exp Table lookups done in EVMCircuit
 pub const EXP_TABLE_LOOKUPS: usize = 1;
 
+/// Sig Table lookups done in EVMCircuit
+pub const SIG_TABLE_LOOKUPS: usize = 1;
+
+/// Sha256 Table lookups done in EVMCircuit
+pub const SHA256_TABLE_LOOKUPS: usize = 1;
+
+/// ModExp Table lookups done in EVMCircuit
+pub const MODEXP_TABLE_LOOKUPS: usize = 1;
+
+/// Ecc Table lookups done in EVMCircuit
+pub const ECC_TABLE_LOOKUPS: usize = 1;
+
+/// Ripemd160 Table lookups done in EVMCircuit
+pub const RIPEMD160_TABLE_LOOKUPS: usize = 1;
+
+/// Blake2f Table lookups done in EVMCircuit
+pub const BLAKE2F_TABLE_LOOKUPS: usize = 1;
+
+/// Kzg Table lookups done in EVMCircuit
+pub const KZG_TABLE_LOOKUPS: usize = 1;
+
+/// Wd Table lookups done in EVMCircuit
+pub const WD_TABLE_LOOKUPS: usize = 1;
+
 /// Maximum number of bytes that an integer can fit in field without wrapping
 /// around.
 pub(crate) const MAX_N_BYTES_INTEGER: usize = 31;
@@ -125,6 +165,9 @@ pub(crate) const N_BYTES_BASE_FEE: usize = N_BYTES_WORD;
 pub(crate) const N_BYTES_CHAIN_ID: usize = N_BYTES_U64;
 pub(crate) const N_BYTES_PREV_HASH: usize = 256 * N_BYTES_WORD;
 pub(crate) const N_BYTES_WITHDRAWAL_ROOT: usize = N_BYTES_WORD;
+pub(crate) const N_BYTES_BLOB_BASE_FEE: usize = N_BYTES_WORD;
+pub(crate) const N_BYTES_HARDFORK: usize = N_BYTES_U64;
+pub(crate) const N_BYTES_WITHDRAWAL_OFFSET: usize = N_BYTES_U64;
 
 pub(crate) const N_BYTES_BLOCK: usize = N_BYTES_COINBASE
     + N_BYTES_GAS_LIMIT
@@ -134,11 +177,17 @@ pub(crate) const N_BYTES_BLOCK: usize = N_BYTES_COINBASE
     + N_BYTES_BASE_FEE
     + N_BYTES_CHAIN_ID
     + N_BYTES_PREV_HASH
-    + N_BYTES_WITHDRAWAL_ROOT;
+    + N_BYTES_WITHDRAWAL_ROOT
+    + N_BYTES_BLOB_BASE_FEE
+    + N_BYTES_HARDFORK
+    + N_BYTES_WITHDRAWAL_OFFSET;
 
 pub(crate) const N_BYTES_EXTRA_VALUE: usize = N_BYTES_WORD // block hash
     + N_BYTES_WORD // state root
-    + N_BYTES_WORD; // prev state root
+    + N_BYTES_WORD // prev state root
+    + N_BYTES_U64 // excess blob gas
+    + N_BYTES_WORD // transactions root
+    + N_BYTES_WORD; // receipts root
 
 // Number of bytes that will be used for tx values
 pub(crate) const N_BYTES_TX_NONCE: usize = N_BYTES_U64;
@@ -150,6 +199,12 @@ pub(crate) const N_BYTES_TX_IS_CREATE: usize = N_BYTES_U64;
 pub(crate) const N_BYTES_TX_VALUE: usize = N_BYTES_WORD;
 pub(crate) const N_BYTES_TX_CALLDATA_LEN: usize = N_BYTES_CALLDATASIZE;
 pub(crate) const N_BYTES_TX_CALLDATA_GASCOST: usize = N_BYTES_U64;
+pub(crate) const N_BYTES_TX_BLOB_VERSIONED_HASHES_LEN: usize = N_BYTES_U64;
+pub(crate) const N_BYTES_TX_TYPE: usize = N_BYTES_U64;
+pub(crate) const N_BYTES_TX_ACCESS_LIST_ADDRESSES_LEN: usize = N_BYTES_U64;
+pub(crate) const N_BYTES_TX_MAX_FEE_PER_GAS: usize = N_BYTES_WORD;
+pub(crate) const N_BYTES_TX_IS_REPLAY_PROTECTED: usize = N_BYTES_U64;
+pub(crate) const N_BYTES_TX_BLOCK_NUMBER: usize = N_BYTES_U64;
 pub(crate) const N_BYTES_TX_TXSIGNHASH: usize = N_BYTES_WORD;
 pub(crate) const N_BYTES_TX: usize = N_BYTES_TX_NONCE
     + N_BYTES_TX_GAS_LIMIT
@@ -160,8 +215,19 @@ pub(crate) const N_BYTES_TX: usize = N_BYTES_TX_NONCE
     + N_BYTES_TX_VALUE
     + N_BYTES_TX_CALLDATA_LEN
     + N_BYTES_TX_CALLDATA_GASCOST
+    + N_BYTES_TX_BLOB_VERSIONED_HASHES_LEN
+    + N_BYTES_TX_TYPE
+    + N_BYTES_TX_ACCESS_LIST_ADDRESSES_LEN
+    + N_BYTES_TX_MAX_FEE_PER_GAS
+    + N_BYTES_TX_IS_REPLAY_PROTECTED
+    + N_BYTES_TX_BLOCK_NUMBER
     + N_BYTES_TX_TXSIGNHASH;
 
+// Number of bytes that will be used for a blob versioned hash row of the tx table
+pub(crate) const N_BYTES_BLOB_VERSIONED_HASH: usize = N_BYTES_U64 // tx id
+    + N_BYTES_U64 // index
+    + N_BYTES_WORD; // versioned hash
+
 pub(crate) const N_BYTES_WITHDRAWAL: usize = N_BYTES_U64 //id 
     + N_BYTES_U64 // validator id
     + N_BYTES_ACCOUNT_ADDRESS // address
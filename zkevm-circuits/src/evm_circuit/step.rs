@@ -20,11 +20,11 @@ use crate::{
 };
 use bus_mapping::{
     circuit_input_builder::ExecState,
-    error::{DepthError, ExecError, InsufficientBalanceError, NonceUintOverflowError, OogError},
+    error::{ExecError, NonceUintOverflowError, OogError},
     evm::OpcodeId,
     precompile::PrecompileCalls,
 };
-use eth_types::{evm_unimplemented, Field, ToWord};
+use eth_types::{evm_types::Hardfork, Field, ToWord};
 use halo2_proofs::{
     circuit::Value,
     plonk::{Advice, Column, ConstraintSystem, Error, Expression},
@@ -45,6 +45,7 @@ impl From<PrecompileCalls> for ExecutionState {
             PrecompileCalls::Bn128Mul => ExecutionState::PrecompileBn256ScalarMul,
             PrecompileCalls::Bn128Pairing => ExecutionState::PrecompileBn256Pairing,
             PrecompileCalls::Blake2F => ExecutionState::PrecompileBlake2f,
+            PrecompileCalls::PointEvaluation => ExecutionState::PrecompilePointEvaluation,
         }
     }
 }
@@ -59,6 +60,7 @@ pub enum ExecutionState {
     EndTx,
     EndBlock,
     InvalidTx,
+    Withdrawal,
     // Opcode successful cases
     STOP,
     /// ADD and SUB opcodes share this state
@@ -105,11 +107,16 @@ pub enum ExecutionState {
     BLOCKCTX,
     CHAINID,
     SELFBALANCE,
+    BLOBHASH,
+    BLOBBASEFEE,
     POP,
     /// MLOAD, MSTORE, MSTORE8
     MEMORY,
+    MCOPY,
     SLOAD,
     SSTORE,
+    TLOAD,
+    TSTORE,
     JUMP,
     JUMPI,
     PC,
@@ -167,6 +174,7 @@ pub enum ExecutionState {
     PrecompileBn256ScalarMul,
     PrecompileBn256Pairing,
     PrecompileBlake2f,
+    PrecompilePointEvaluation,
 }
 
 impl Default for ExecutionState {
@@ -186,23 +194,13 @@ impl From<&ExecError> for ExecutionState {
             ExecError::InvalidOpcode => ExecutionState::ErrorInvalidOpcode,
             ExecError::StackOverflow | ExecError::StackUnderflow => ExecutionState::ErrorStack,
             ExecError::WriteProtection => ExecutionState::ErrorWriteProtection,
-            ExecError::Depth(depth_error) => match depth_error {
-                DepthError::Call => ExecutionState::CALL_OP,
-                DepthError::Create => ExecutionState::CREATE,
-                DepthError::Create2 => ExecutionState::CREATE2,
-            },
-            ExecError::InsufficientBalance(insufficient_balance_err) => {
-                match insufficient_balance_err {
-                    InsufficientBalanceError::Call => ExecutionState::CALL_OP,
-                    InsufficientBalanceError::Create => ExecutionState::CREATE,
-                    InsufficientBalanceError::Create2 => ExecutionState::CREATE2,
-                }
-            }
+            ExecError::Depth(_) => ExecutionState::ErrorDepth,
+            ExecError::InsufficientBalance(_) => ExecutionState::ErrorInsufficientBalance,
             ExecError::NonceUintOverflow(nonce_overflow_err) => match nonce_overflow_err {
                 NonceUintOverflowError::Create => ExecutionState::CREATE,
                 NonceUintOverflowError::Create2 => ExecutionState::CREATE2,
             },
-            ExecError::ContractAddressCollision => ExecutionState::CREATE2,
+            ExecError::ContractAddressCollision => ExecutionState::ErrorContractAddressCollision,
             ExecError::InvalidCreationCode => ExecutionState::ErrorInvalidCreationCode,
             ExecError::InvalidJump => ExecutionState::ErrorInvalidJump,
             ExecError::ReturnDataOutOfBounds => ExecutionState::ErrorReturnDataOutOfBound,
@@ -219,6 +217,7 @@ impl From<&ExecError> for ExecutionState {
                     ExecutionState::ErrorOutOfGasDynamicMemoryExpansion
                 }
                 OogError::MemoryCopy => ExecutionState::ErrorOutOfGasMemoryCopy,
+                OogError::ExtCodeCopy => ExecutionState::ErrorOutOfGasEXTCODECOPY,
                 OogError::AccountAccess => ExecutionState::ErrorOutOfGasAccountAccess,
                 OogError::CodeStore => ExecutionState::ErrorCodeStore,
                 OogError::Log => ExecutionState::ErrorOutOfGasLOG,
@@ -253,13 +252,6 @@ impl From<&ExecStep> for ExecutionState {
                     return ExecutionState::LOG;
                 }
 
-                macro_rules! dummy {
-                    ($name:expr) => {{
-                        evm_unimplemented!("{:?} is implemented with DummyGadget", $name);
-                        $name
-                    }};
-                }
-
                 match op {
                     OpcodeId::ADD | OpcodeId::SUB => ExecutionState::ADD_SUB,
                     OpcodeId::ADDMOD => ExecutionState::ADDMOD,
@@ -282,6 +274,7 @@ impl From<&ExecStep> for ExecutionState {
                     OpcodeId::MLOAD => ExecutionState::MEMORY,
                     OpcodeId::MSTORE => ExecutionState::MEMORY,
                     OpcodeId::MSTORE8 => ExecutionState::MEMORY,
+                    OpcodeId::MCOPY => ExecutionState::MCOPY,
                     OpcodeId::JUMPDEST => ExecutionState::JUMPDEST,
                     OpcodeId::JUMP => ExecutionState::JUMP,
                     OpcodeId::JUMPI => ExecutionState::JUMPI,
@@ -306,9 +299,13 @@ impl From<&ExecStep> for ExecutionState {
                     OpcodeId::SHL | OpcodeId::SHR => ExecutionState::SHL_SHR,
                     OpcodeId::SLOAD => ExecutionState::SLOAD,
                     OpcodeId::SSTORE => ExecutionState::SSTORE,
+                    OpcodeId::TLOAD => ExecutionState::TLOAD,
+                    OpcodeId::TSTORE => ExecutionState::TSTORE,
                     OpcodeId::CALLDATASIZE => ExecutionState::CALLDATASIZE,
                     OpcodeId::CALLDATACOPY => ExecutionState::CALLDATACOPY,
                     OpcodeId::CHAINID => ExecutionState::CHAINID,
+                    OpcodeId::BLOBHASH => ExecutionState::BLOBHASH,
+                    OpcodeId::BLOBBASEFEE => ExecutionState::BLOBBASEFEE,
                     OpcodeId::ISZERO => ExecutionState::ISZERO,
                     OpcodeId::CALL
                     | OpcodeId::CALLCODE
@@ -324,8 +321,7 @@ impl From<&ExecStep> for ExecutionState {
                     OpcodeId::RETURNDATACOPY => ExecutionState::RETURNDATACOPY,
                     OpcodeId::CREATE => ExecutionState::CREATE,
                     OpcodeId::CREATE2 => ExecutionState::CREATE2,
-                    // dummy ops
-                    OpcodeId::SELFDESTRUCT => dummy!(ExecutionState::SELFDESTRUCT),
+                    OpcodeId::SELFDESTRUCT => ExecutionState::SELFDESTRUCT,
                     _ => unimplemented!("unimplemented opcode {:?}", op),
                 }
             }
@@ -339,11 +335,13 @@ impl From<&ExecStep> for ExecutionState {
                 PrecompileCalls::Bn128Mul => ExecutionState::PrecompileBn256ScalarMul,
                 PrecompileCalls::Bn128Pairing => ExecutionState::PrecompileBn256Pairing,
                 PrecompileCalls::Blake2F => ExecutionState::PrecompileBlake2f,
+                PrecompileCalls::PointEvaluation => ExecutionState::PrecompilePointEvaluation,
             },
             ExecState::BeginTx => ExecutionState::BeginTx,
             ExecState::EndTx => ExecutionState::EndTx,
             ExecState::EndBlock => ExecutionState::EndBlock,
             ExecState::InvalidTx => ExecutionState::InvalidTx,
+            ExecState::Withdrawal => ExecutionState::Withdrawal,
         }
     }
 }
@@ -379,6 +377,7 @@ impl ExecutionState {
                 | Self::PrecompileBn256ScalarMul
                 | Self::PrecompileBn256Pairing
                 | Self::PrecompileBlake2f
+                | Self::PrecompilePointEvaluation
         )
     }
 
@@ -393,6 +392,7 @@ impl ExecutionState {
             Self::PrecompileBn256ScalarMul => PrecompileCalls::Bn128Mul,
             Self::PrecompileBn256Pairing => PrecompileCalls::Bn128Pairing,
             Self::PrecompileBlake2f => PrecompileCalls::Blake2F,
+            Self::PrecompilePointEvaluation => PrecompileCalls::PointEvaluation,
             _ => return 0,
         })
         .base_gas_cost()
@@ -442,6 +442,16 @@ impl ExecutionState {
                 })
                 .collect();
         }
+        if matches!(self, Self::ErrorInvalidOpcode) {
+            return Hardfork::iter()
+                .flat_map(|hardfork| {
+                    (u8::MIN..=u8::MAX)
+                        .map(OpcodeId::from)
+                        .filter(move |op| !hardfork.is_opcode_enabled(op))
+                        .map(move |op| ResponsibleOp::InvalidOpcode(op, hardfork))
+                })
+                .collect();
+        }
 
         match self {
             Self::STOP => vec![OpcodeId::STOP],
@@ -488,12 +498,17 @@ impl ExecutionState {
             ],
             Self::CHAINID => vec![OpcodeId::CHAINID],
             Self::SELFBALANCE => vec![OpcodeId::SELFBALANCE],
+            Self::BLOBHASH => vec![OpcodeId::BLOBHASH],
+            Self::BLOBBASEFEE => vec![OpcodeId::BLOBBASEFEE],
             Self::POP => vec![OpcodeId::POP],
             Self::MEMORY => {
                 vec![OpcodeId::MLOAD, OpcodeId::MSTORE, OpcodeId::MSTORE8]
             }
+            Self::MCOPY => vec![OpcodeId::MCOPY],
             Self::SLOAD => vec![OpcodeId::SLOAD],
             Self::SSTORE => vec![OpcodeId::SSTORE],
+            Self::TLOAD => vec![OpcodeId::TLOAD],
+            Self::TSTORE => vec![OpcodeId::TSTORE],
             Self::JUMP => vec![OpcodeId::JUMP],
             Self::JUMPI => vec![OpcodeId::JUMPI],
             Self::PC => vec![OpcodeId::PC],
@@ -588,7 +603,6 @@ impl ExecutionState {
             Self::RETURN_REVERT => vec![OpcodeId::RETURN, OpcodeId::REVERT],
             Self::CREATE2 => vec![OpcodeId::CREATE2],
             Self::SELFDESTRUCT => vec![OpcodeId::SELFDESTRUCT],
-            Self::ErrorInvalidOpcode => OpcodeId::invalid_opcodes(),
             _ => vec![],
         }
         .into_iter()
@@ -614,6 +628,9 @@ pub enum ResponsibleOp {
     Op(OpcodeId),
     /// Corresponding to ExecutionState::ErrorStack
     InvalidStackPtr(OpcodeId, u32),
+    /// Corresponding to ExecutionState::ErrorInvalidOpcode, an opcode which is
+    /// undefined in the hardfork
+    InvalidOpcode(OpcodeId, Hardfork),
 }
 
 /// Helper for easy transform from a raw OpcodeId to ResponsibleOp.
@@ -629,6 +646,7 @@ impl ResponsibleOp {
         *match self {
             ResponsibleOp::Op(opcode) => opcode,
             ResponsibleOp::InvalidStackPtr(opcode, _) => opcode,
+            ResponsibleOp::InvalidOpcode(opcode, _) => opcode,
         }
     }
 }
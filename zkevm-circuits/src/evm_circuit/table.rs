@@ -108,6 +108,9 @@ impl FixedTableTag {
                                 ResponsibleOp::InvalidStackPtr(op, stack_ptr) => {
                                     (op, F::from(u64::from(stack_ptr)))
                                 }
+                                ResponsibleOp::InvalidOpcode(op, hardfork) => {
+                                    (op, F::from(hardfork.as_u64()))
+                                }
                             };
                             [
                                 tag,
@@ -150,6 +153,7 @@ impl FixedTableTag {
                     PrecompileCalls::Bn128Mul,
                     PrecompileCalls::Bn128Pairing,
                     PrecompileCalls::Blake2F,
+                    PrecompileCalls::PointEvaluation,
                 ]
                 .into_iter()
                 .map(move |precompile| {
@@ -191,6 +195,22 @@ pub enum Table {
     Keccak,
     /// Lookup for exp table
     Exp,
+    /// Lookup for sig table
+    Sig,
+    /// Lookup for sha256 table
+    Sha256,
+    /// Lookup for modexp table
+    ModExp,
+    /// Lookup for ecc table
+    Ecc,
+    /// Lookup for ripemd160 table
+    Ripemd160,
+    /// Lookup for blake2f table
+    Blake2f,
+    /// Lookup for kzg table
+    Kzg,
+    /// Lookup for withdrawal table
+    Wd,
 }
 
 #[derive(Clone, Debug)]
@@ -359,6 +379,105 @@ pub(crate) enum Lookup<F> {
         exponent_lo_hi: [Expression<F>; 2],
         exponentiation_lo_hi: [Expression<F>; 2],
     },
+    /// Lookup to signature table.
+    SigTable {
+        /// Keccak hash of the message that's signed.
+        msg_hash: Word<Expression<F>>,
+        /// Recovery id of the signature, which is 0 or 1.
+        sig_v: Expression<F>,
+        /// The r-component of the signature.
+        sig_r: Word<Expression<F>>,
+        /// The s-component of the signature.
+        sig_s: Word<Expression<F>>,
+        /// The recovered address, i.e. the 20-bytes address that must have signed
+        /// the message.
+        recovered_addr: Word<Expression<F>>,
+        /// Whether a public key is recovered from the signature.
+        is_valid: Expression<F>,
+    },
+    /// Lookup to sha256 table.
+    Sha256Table {
+        /// Accumulator to the input.
+        input_rlc: Expression<F>,
+        /// Length of input that is being hashed.
+        input_len: Expression<F>,
+        /// Accumulator to the output digest.
+        output_rlc: Expression<F>,
+    },
+    /// Lookup to modexp table.
+    ModExpTable {
+        /// Base of the exponentiation.
+        base: Word<Expression<F>>,
+        /// Exponent of the exponentiation.
+        exponent: Word<Expression<F>>,
+        /// Modulus of the exponentiation.
+        modulus: Word<Expression<F>>,
+        /// `base ^ exponent % modulus`.
+        result: Word<Expression<F>>,
+    },
+    /// Lookup to ecc table.
+    EccTable {
+        /// Tag of the operation, see [`EccOpType`](crate::table::EccOpType).
+        op_type: Expression<F>,
+        /// First argument of the operation.
+        arg1: Word<Expression<F>>,
+        /// Second argument of the operation.
+        arg2: Word<Expression<F>>,
+        /// Third argument of the operation.
+        arg3: Word<Expression<F>>,
+        /// Fourth argument of the operation.
+        arg4: Word<Expression<F>>,
+        /// Input bytes of a pairing as `RLC(reversed(input))`.
+        input_rlc: Expression<F>,
+        /// Length of the input bytes of a pairing.
+        input_len: Expression<F>,
+        /// First output of the operation.
+        output1: Word<Expression<F>>,
+        /// Second output of the operation.
+        output2: Word<Expression<F>>,
+        /// Whether the points of the operation are valid.
+        is_valid: Expression<F>,
+    },
+    /// Lookup to ripemd160 table.
+    Ripemd160Table {
+        /// Accumulator to the input.
+        input_rlc: Expression<F>,
+        /// Length of input that is being hashed.
+        input_len: Expression<F>,
+        /// Accumulator to the output digest.
+        output_rlc: Expression<F>,
+    },
+    /// Lookup to blake2f table.
+    Blake2fTable {
+        /// Accumulator to the 213 bytes input.
+        input_rlc: Expression<F>,
+        /// Number of rounds of the compression.
+        rounds: Expression<F>,
+        /// Whether the final block flag is valid.
+        is_valid: Expression<F>,
+        /// Accumulator to the compressed state, zero for an invalid input.
+        output_rlc: Expression<F>,
+    },
+    /// Lookup to kzg table.
+    KzgTable {
+        /// Accumulator to the 192 bytes input.
+        input_rlc: Expression<F>,
+        /// Whether the point evaluation is valid.
+        is_valid: Expression<F>,
+    },
+    /// Lookup to withdrawal table.
+    WdTable {
+        /// Index of the withdrawal in the block, starting from 1.
+        index: Expression<F>,
+        /// Id of the withdrawal.
+        id: Expression<F>,
+        /// Id of the validator.
+        validator_id: Expression<F>,
+        /// Address the withdrawal is credited to.
+        address: Word<Expression<F>>,
+        /// Amount of the withdrawal in Gwei.
+        amount: Expression<F>,
+    },
     /// Conditional lookup enabled by the first element.
     Conditional(Expression<F>, Box<Lookup<F>>),
 }
@@ -378,6 +497,14 @@ impl<F: Field> Lookup<F> {
             Self::CopyTable { .. } => Table::Copy,
             Self::KeccakTable { .. } => Table::Keccak,
             Self::ExpTable { .. } => Table::Exp,
+            Self::SigTable { .. } => Table::Sig,
+            Self::Sha256Table { .. } => Table::Sha256,
+            Self::ModExpTable { .. } => Table::ModExp,
+            Self::EccTable { .. } => Table::Ecc,
+            Self::Ripemd160Table { .. } => Table::Ripemd160,
+            Self::Blake2fTable { .. } => Table::Blake2f,
+            Self::KzgTable { .. } => Table::Kzg,
+            Self::WdTable { .. } => Table::Wd,
             Self::Conditional(_, lookup) => lookup.table(),
         }
     }
@@ -496,6 +623,126 @@ impl<F: Field> Lookup<F> {
                 exponentiation_lo_hi[0].clone(),
                 exponentiation_lo_hi[1].clone(),
             ],
+            Self::SigTable {
+                msg_hash,
+                sig_v,
+                sig_r,
+                sig_s,
+                recovered_addr,
+                is_valid,
+            } => vec![
+                1.expr(), // q_enable
+                msg_hash.lo(),
+                msg_hash.hi(),
+                sig_v.clone(),
+                sig_r.lo(),
+                sig_r.hi(),
+                sig_s.lo(),
+                sig_s.hi(),
+                recovered_addr.lo(),
+                recovered_addr.hi(),
+                is_valid.clone(),
+            ],
+            Self::Sha256Table {
+                input_rlc,
+                input_len,
+                output_rlc,
+            } => vec![
+                1.expr(), // is_enabled
+                input_rlc.clone(),
+                input_len.clone(),
+                output_rlc.clone(),
+            ],
+            Self::ModExpTable {
+                base,
+                exponent,
+                modulus,
+                result,
+            } => vec![
+                1.expr(), // q_enable
+                base.lo(),
+                base.hi(),
+                exponent.lo(),
+                exponent.hi(),
+                modulus.lo(),
+                modulus.hi(),
+                result.lo(),
+                result.hi(),
+            ],
+            Self::EccTable {
+                op_type,
+                arg1,
+                arg2,
+                arg3,
+                arg4,
+                input_rlc,
+                input_len,
+                output1,
+                output2,
+                is_valid,
+            } => vec![
+                1.expr(), // q_enable
+                op_type.clone(),
+                arg1.lo(),
+                arg1.hi(),
+                arg2.lo(),
+                arg2.hi(),
+                arg3.lo(),
+                arg3.hi(),
+                arg4.lo(),
+                arg4.hi(),
+                input_rlc.clone(),
+                input_len.clone(),
+                output1.lo(),
+                output1.hi(),
+                output2.lo(),
+                output2.hi(),
+                is_valid.clone(),
+            ],
+            Self::Ripemd160Table {
+                input_rlc,
+                input_len,
+                output_rlc,
+            } => vec![
+                1.expr(), // is_enabled
+                input_rlc.clone(),
+                input_len.clone(),
+                output_rlc.clone(),
+            ],
+            Self::Blake2fTable {
+                input_rlc,
+                rounds,
+                is_valid,
+                output_rlc,
+            } => vec![
+                1.expr(), // is_enabled
+                input_rlc.clone(),
+                rounds.clone(),
+                is_valid.clone(),
+                output_rlc.clone(),
+            ],
+            Self::KzgTable {
+                input_rlc,
+                is_valid,
+            } => vec![
+                1.expr(), // q_enable
+                input_rlc.clone(),
+                is_valid.clone(),
+            ],
+            Self::WdTable {
+                index,
+                id,
+                validator_id,
+                address,
+                amount,
+            } => vec![
+                index.clone(),
+                id.clone(),
+                validator_id.clone(),
+                address.lo(),
+                address.hi(),
+                amount.clone(),
+            ],
             Self::Conditional(condition, lookup) => lookup
                 .input_exprs()
                 .into_iter()
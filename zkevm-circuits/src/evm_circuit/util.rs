@@ -13,7 +13,7 @@ use crate::{
     util::{cell_manager::CMFixedWidthStrategyDistribution, int_decomposition::IntDecomposition},
     witness::{Block, ExecStep, Rw, RwMap},
 };
-use eth_types::{Address, Field, U256};
+use eth_types::{evm_types::Hardfork, Address, Field, U256};
 use halo2_proofs::{
     circuit::{AssignedCell, Region, Value},
     plonk::{Advice, Assigned, Column, ConstraintSystem, Error, Expression},
@@ -446,8 +446,9 @@ pub(crate) fn transpose_val_ret<F, E>(value: Value<Result<F, E>>) -> Result<Valu
     ret
 }
 
-pub(crate) fn is_precompiled(address: &Address) -> bool {
-    address.0[0..19] == [0u8; 19] && (1..=9).contains(&address.0[19])
+pub(crate) fn is_precompiled(address: &Address, hardfork: Hardfork) -> bool {
+    address.0[0..19] == [0u8; 19]
+        && (1..=hardfork.precompile_count()).contains(&(address.0[19] as u64))
 }
 
 const BASE_128_BYTES: [u8; 32] = [
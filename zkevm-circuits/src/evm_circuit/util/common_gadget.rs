@@ -1,13 +1,15 @@
 use super::{
     constraint_builder::ConstrainBuilderCommon,
     from_bytes,
-    math_gadget::{IsEqualWordGadget, IsZeroGadget, IsZeroWordGadget, LtGadget},
-    memory_gadget::{CommonMemoryAddressGadget, MemoryExpansionGadget},
-    AccountAddress, CachedRegion,
+    math_gadget::{
+        ConstantDivisionGadget, IsEqualWordGadget, IsZeroGadget, IsZeroWordGadget, LtGadget,
+    },
+    memory_gadget::{CommonMemoryAddressGadget, MemoryAddressGadget, MemoryExpansionGadget},
+    AccountAddress, CachedRegion, StepRws,
 };
 use crate::{
     evm_circuit::{
-        param::{N_BYTES_GAS, N_BYTES_MEMORY_WORD_SIZE},
+        param::{N_BYTES_GAS, N_BYTES_MEMORY_ADDRESS, N_BYTES_MEMORY_WORD_SIZE, N_BYTES_WORD},
         step::ExecutionState,
         table::{FixedTableTag, Lookup},
         util::{
@@ -20,15 +22,18 @@ use crate::{
             not, or, Cell,
         },
     },
-    table::{AccountFieldTag, CallContextFieldTag},
+    table::{AccountFieldTag, BlockContextFieldTag, CallContextFieldTag},
     util::{
         word::{Word, Word32, Word32Cell, WordCell, WordExpr},
         Expr,
     },
     witness::{Block, Call, ExecStep},
 };
-use bus_mapping::state_db::CodeDB;
-use eth_types::{evm_types::GasCost, Field, ToAddress, ToLittleEndian, ToScalar, ToWord, U256};
+use bus_mapping::{evm::OpcodeId, state_db::CodeDB};
+use eth_types::{
+    evm_types::{GasCost, Hardfork, GAS_STIPEND_CALL_WITH_VALUE},
+    Field, ToAddress, ToLittleEndian, ToScalar, ToWord, U256,
+};
 use gadgets::util::{select, sum};
 use halo2_proofs::{
     circuit::Value,
@@ -932,6 +937,512 @@ impl<F: Field, MemAddrGadget: CommonMemoryAddressGadget<F>, const IS_SUCCESS_CAL
     }
 }
 
+/// Gadget for CALL, CALLCODE, DELEGATECALL and STATICCALL whose precheck on
+/// the call depth or the caller balance fails. The callee is added to the
+/// access list but never entered, zero is pushed to the stack and the caller
+/// gets back the gas it would have sent to the callee.
+#[derive(Clone, Debug)]
+pub(crate) struct CallPrecheckFailureGadget<F> {
+    is_call: IsZeroGadget<F>,
+    is_callcode: IsZeroGadget<F>,
+    is_delegatecall: IsZeroGadget<F>,
+    is_staticcall: IsZeroGadget<F>,
+    tx_id: Cell<F>,
+    reversion_info: ReversionInfo<F>,
+    is_static: Cell<F>,
+    depth: Cell<F>,
+    current_callee_address: WordCell<F>,
+    current_caller_address: WordCell<F>,
+    current_value: WordCell<F>,
+    call: CommonCallGadget<F, MemoryAddressGadget<F>, true>,
+    is_warm_prev: Cell<F>,
+    callee_reversion_info: ReversionInfo<F>,
+    caller_balance: WordCell<F>,
+}
+
+impl<F: Field> CallPrecheckFailureGadget<F> {
+    pub(crate) fn construct(cb: &mut EVMConstraintBuilder<F>, opcode: Expression<F>) -> Self {
+        let is_call = IsZeroGadget::construct(cb, opcode.expr() - OpcodeId::CALL.expr());
+        let is_callcode = IsZeroGadget::construct(cb, opcode.expr() - OpcodeId::CALLCODE.expr());
+        let is_delegatecall =
+            IsZeroGadget::construct(cb, opcode.expr() - OpcodeId::DELEGATECALL.expr());
+        let is_staticcall =
+            IsZeroGadget::construct(cb, opcode.expr() - OpcodeId::STATICCALL.expr());
+
+        // Use rw_counter of the step which triggers next call as its call_id.
+        let callee_call_id = cb.curr.state.rw_counter.clone();
+
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+        let mut reversion_info = cb.reversion_info_read(None);
+        let [is_static, depth] = [CallContextFieldTag::IsStatic, CallContextFieldTag::Depth]
+            .map(|field_tag| cb.call_context(None, field_tag));
+        let current_callee_address =
+            cb.call_context_read_as_word(None, CallContextFieldTag::CalleeAddress);
+        let (current_caller_address, current_value) = cb.condition(is_delegatecall.expr(), |cb| {
+            (
+                cb.call_context_read_as_word(None, CallContextFieldTag::CallerAddress),
+                cb.call_context_read_as_word(None, CallContextFieldTag::Value),
+            )
+        });
+
+        let call: CommonCallGadget<F, MemoryAddressGadget<F>, true> = CommonCallGadget::construct(
+            cb,
+            is_call.expr(),
+            is_callcode.expr(),
+            is_delegatecall.expr(),
+            is_staticcall.expr(),
+        );
+        cb.require_zero(
+            "stack write result is zero when the precheck fails",
+            call.is_success.expr(),
+        );
+        cb.condition(not::expr(is_call.expr() + is_callcode.expr()), |cb| {
+            cb.require_zero_word(
+                "for non call/call code, value is zero",
+                call.value.to_word(),
+            );
+        });
+        cb.condition(is_call.expr() * call.has_value.clone(), |cb| {
+            cb.require_zero(
+                "CALL with value must not be in static call stack",
+                is_static.expr(),
+            );
+        });
+
+        // Add callee to access list
+        let is_warm_prev = cb.query_bool();
+        cb.account_access_list_write_unchecked(
+            tx_id.expr(),
+            call.callee_address(),
+            1.expr(),
+            is_warm_prev.expr(),
+            Some(&mut reversion_info),
+        );
+
+        // The callee is never entered, so it can't be persistent.
+        let callee_reversion_info = cb.reversion_info_write_unchecked(Some(callee_call_id.expr()));
+        cb.require_zero(
+            "callee_is_persistent == 0",
+            callee_reversion_info.is_persistent(),
+        );
+
+        let caller_address = Word::select(
+            is_delegatecall.expr(),
+            current_caller_address.to_word(),
+            current_callee_address.to_word(),
+        );
+        let caller_balance = cb.query_word_unchecked();
+        cb.account_read(
+            caller_address,
+            AccountFieldTag::Balance,
+            caller_balance.to_word(),
+        );
+
+        // Save caller's call state
+        cb.call_context_lookup_write(
+            None,
+            CallContextFieldTag::LastCalleeId,
+            Word::from_lo_unchecked(callee_call_id.expr()),
+        );
+        for field_tag in [
+            CallContextFieldTag::LastCalleeReturnDataOffset,
+            CallContextFieldTag::LastCalleeReturnDataLength,
+        ] {
+            cb.call_context_lookup_write(None, field_tag, Word::zero());
+        }
+
+        // The gas sent to the callee, including the stipend of a call with
+        // value, goes back to the caller.
+        let gas_cost = call.gas_cost_expr(is_warm_prev.expr(), is_call.expr());
+        let stack_pointer_delta =
+            select::expr(is_call.expr() + is_callcode.expr(), 6.expr(), 5.expr());
+        cb.require_step_state_transition(StepStateTransition {
+            rw_counter: Delta(cb.rw_counter_offset()),
+            program_counter: Delta(1.expr()),
+            stack_pointer: Delta(stack_pointer_delta),
+            gas_left: Delta(call.has_value.clone() * GAS_STIPEND_CALL_WITH_VALUE.expr() - gas_cost),
+            memory_word_size: To(call.memory_expansion.next_memory_word_size()),
+            reversible_write_counter: Delta(1.expr()),
+            ..StepStateTransition::default()
+        });
+
+        Self {
+            is_call,
+            is_callcode,
+            is_delegatecall,
+            is_staticcall,
+            tx_id,
+            reversion_info,
+            is_static,
+            depth,
+            current_callee_address,
+            current_caller_address,
+            current_value,
+            call,
+            is_warm_prev,
+            callee_reversion_info,
+            caller_balance,
+        }
+    }
+
+    pub(crate) fn depth(&self) -> Expression<F> {
+        self.depth.expr()
+    }
+
+    pub(crate) fn caller_balance(&self) -> Word<Expression<F>> {
+        self.caller_balance.to_word()
+    }
+
+    pub(crate) fn value(&self) -> Word<Expression<F>> {
+        self.call.value.to_word()
+    }
+
+    /// Assign the call and return its depth, caller balance and value.
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(u64, U256, U256), Error> {
+        let opcode = step.opcode().unwrap();
+        let is_call = opcode == OpcodeId::CALL;
+        let is_callcode = opcode == OpcodeId::CALLCODE;
+        let is_delegatecall = opcode == OpcodeId::DELEGATECALL;
+        let mut rws = StepRws::new(block, step);
+
+        let tx_id = rws.next().call_context_value();
+        rws.offset_add(2); // RwCounterEndOfReversion, IsPersistent
+        let is_static = rws.next().call_context_value();
+        let depth = rws.next().call_context_value().low_u64();
+        let current_callee_address = rws.next().call_context_value();
+        let [current_caller_address, current_value] = if is_delegatecall {
+            [(); 2].map(|_| rws.next().call_context_value())
+        } else {
+            [U256::zero(); 2]
+        };
+
+        let gas = rws.next().stack_value();
+        let callee_address = rws.next().stack_value();
+        let value = if is_call || is_callcode {
+            rws.next().stack_value()
+        } else {
+            U256::zero()
+        };
+        let [cd_offset, cd_length, rd_offset, rd_length, is_success] =
+            [(); 5].map(|_| rws.next().stack_value());
+
+        let callee_code_hash = rws.next().account_codehash_pair().0;
+        let (_, is_warm_prev) = rws.next().tx_access_list_value_pair();
+        let [callee_rw_counter_end_of_reversion, callee_is_persistent] =
+            [(); 2].map(|_| rws.next().call_context_value());
+        let caller_balance = rws.next().account_balance_pair().0;
+
+        for (gadget, opcode_id) in [
+            (&self.is_call, OpcodeId::CALL),
+            (&self.is_callcode, OpcodeId::CALLCODE),
+            (&self.is_delegatecall, OpcodeId::DELEGATECALL),
+            (&self.is_staticcall, OpcodeId::STATICCALL),
+        ] {
+            gadget.assign(
+                region,
+                offset,
+                F::from(opcode.as_u64()) - F::from(opcode_id.as_u64()),
+            )?;
+        }
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx_id.low_u64())))?;
+        self.reversion_info.assign(
+            region,
+            offset,
+            call.rw_counter_end_of_reversion,
+            call.is_persistent,
+        )?;
+        self.is_static
+            .assign(region, offset, Value::known(F::from(is_static.low_u64())))?;
+        self.depth
+            .assign(region, offset, Value::known(F::from(depth)))?;
+        self.current_callee_address
+            .assign_u256(region, offset, current_callee_address)?;
+        self.current_caller_address
+            .assign_u256(region, offset, current_caller_address)?;
+        self.current_value
+            .assign_u256(region, offset, current_value)?;
+        self.call.assign(
+            region,
+            offset,
+            gas,
+            callee_address,
+            value,
+            is_success,
+            cd_offset,
+            cd_length,
+            rd_offset,
+            rd_length,
+            step.memory_word_size(),
+            callee_code_hash,
+        )?;
+        self.is_warm_prev
+            .assign(region, offset, Value::known(F::from(is_warm_prev as u64)))?;
+        self.callee_reversion_info.assign(
+            region,
+            offset,
+            callee_rw_counter_end_of_reversion.low_u64() as usize,
+            callee_is_persistent.low_u64() != 0,
+        )?;
+        self.caller_balance
+            .assign_u256(region, offset, caller_balance)?;
+
+        Ok((depth, caller_balance, value))
+    }
+}
+
+/// Gadget for CREATE and CREATE2 whose precheck on the call depth or the
+/// caller balance fails. The init code is never run, zero is pushed to the
+/// stack and the caller gets back the gas it would have sent to the callee.
+#[derive(Clone, Debug)]
+pub(crate) struct CreatePrecheckFailureGadget<F> {
+    tx_id: Cell<F>,
+    depth: Cell<F>,
+    reversion_info: ReversionInfo<F>,
+    caller_address: WordCell<F>,
+    value: Word32Cell<F>,
+    salt: WordCell<F>,
+    caller_balance: WordCell<F>,
+    caller_nonce: Cell<F>,
+    init_code: MemoryAddressGadget<F>,
+    memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
+    init_code_word_size: ConstantDivisionGadget<F, N_BYTES_MEMORY_ADDRESS>,
+    hardfork: HardforkGadget<F>,
+    gas_left: ConstantDivisionGadget<F, N_BYTES_GAS>,
+    callee_reversion_info: ReversionInfo<F>,
+}
+
+impl<F: Field> CreatePrecheckFailureGadget<F> {
+    pub(crate) fn construct(cb: &mut EVMConstraintBuilder<F>, is_create2: Expression<F>) -> Self {
+        // Use rw_counter of the step which triggers next call as its call_id.
+        let callee_call_id = cb.curr.state.rw_counter.clone();
+
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+        let depth = cb.call_context(None, CallContextFieldTag::Depth);
+        let reversion_info = cb.reversion_info_read(None);
+        let caller_address = cb.call_context_read_as_word(None, CallContextFieldTag::CalleeAddress);
+
+        let value = cb.query_word32();
+        let length = cb.query_memory_address();
+        let offset = cb.query_word_unchecked();
+        let salt = cb.query_word_unchecked();
+        cb.stack_pop(value.to_word());
+        cb.stack_pop(offset.to_word());
+        cb.stack_pop(length.to_word());
+        cb.condition(is_create2.expr(), |cb| {
+            cb.stack_pop(salt.to_word());
+        });
+        cb.stack_push(Word::zero());
+
+        let caller_balance = cb.query_word_unchecked();
+        cb.account_read(
+            caller_address.to_word(),
+            AccountFieldTag::Balance,
+            caller_balance.to_word(),
+        );
+        let caller_nonce = cb.query_cell();
+        cb.account_read(
+            caller_address.to_word(),
+            AccountFieldTag::Nonce,
+            Word::from_lo_unchecked(caller_nonce.expr()),
+        );
+
+        let init_code = MemoryAddressGadget::construct(cb, offset, length);
+        let memory_expansion = MemoryExpansionGadget::construct(cb, [init_code.address()]);
+        let init_code_word_size = ConstantDivisionGadget::construct(
+            cb,
+            init_code.length() + (N_BYTES_WORD - 1).expr(),
+            N_BYTES_WORD as u64,
+        );
+        // The init code is only metered since EIP-3860 (Shanghai).
+        let hardfork = HardforkGadget::construct(cb);
+        let keccak_gas_cost = init_code_word_size.quotient()
+            * (is_create2.expr() * GasCost::COPY_SHA3.expr()
+                + hardfork.select(|hardfork| hardfork.init_code_word_gas()));
+        let gas_cost = GasCost::CREATE.expr() + memory_expansion.gas_cost() + keccak_gas_cost;
+        let gas_left = ConstantDivisionGadget::construct(
+            cb,
+            cb.curr.state.gas_left.expr() - gas_cost.clone(),
+            64,
+        );
+
+        for (field_tag, value) in [
+            (
+                CallContextFieldTag::ProgramCounter,
+                Word::from_lo_unchecked(cb.curr.state.program_counter.expr() + 1.expr()),
+            ),
+            (
+                CallContextFieldTag::StackPointer,
+                Word::from_lo_unchecked(
+                    cb.curr.state.stack_pointer.expr() + 2.expr() + is_create2.expr(),
+                ),
+            ),
+            (
+                CallContextFieldTag::GasLeft,
+                Word::from_lo_unchecked(gas_left.quotient()),
+            ),
+            (
+                CallContextFieldTag::MemorySize,
+                Word::from_lo_unchecked(memory_expansion.next_memory_word_size()),
+            ),
+            (
+                CallContextFieldTag::ReversibleWriteCounter,
+                Word::from_lo_unchecked(cb.curr.state.reversible_write_counter.expr() + 2.expr()),
+            ),
+        ] {
+            cb.call_context_lookup_write(None, field_tag, value);
+        }
+
+        // The init code is never run, so the callee can't be persistent.
+        let callee_reversion_info = cb.reversion_info_write_unchecked(Some(callee_call_id.expr()));
+        cb.require_zero(
+            "callee_is_persistent == 0",
+            callee_reversion_info.is_persistent(),
+        );
+
+        // Save caller's call state
+        cb.call_context_lookup_write(
+            None,
+            CallContextFieldTag::LastCalleeId,
+            Word::from_lo_unchecked(callee_call_id.expr()),
+        );
+        for field_tag in [
+            CallContextFieldTag::LastCalleeReturnDataOffset,
+            CallContextFieldTag::LastCalleeReturnDataLength,
+        ] {
+            cb.call_context_lookup_write(None, field_tag, Word::zero());
+        }
+
+        cb.require_step_state_transition(StepStateTransition {
+            rw_counter: Delta(cb.rw_counter_offset()),
+            program_counter: Delta(1.expr()),
+            stack_pointer: Delta(2.expr() + is_create2.expr()),
+            memory_word_size: To(memory_expansion.next_memory_word_size()),
+            gas_left: Delta(-gas_cost),
+            ..StepStateTransition::default()
+        });
+
+        Self {
+            tx_id,
+            depth,
+            reversion_info,
+            caller_address,
+            value,
+            salt,
+            caller_balance,
+            caller_nonce,
+            init_code,
+            memory_expansion,
+            init_code_word_size,
+            hardfork,
+            gas_left,
+            callee_reversion_info,
+        }
+    }
+
+    pub(crate) fn depth(&self) -> Expression<F> {
+        self.depth.expr()
+    }
+
+    pub(crate) fn caller_balance(&self) -> Word<Expression<F>> {
+        self.caller_balance.to_word()
+    }
+
+    pub(crate) fn value(&self) -> Word<Expression<F>> {
+        self.value.to_word()
+    }
+
+    /// Assign the creation and return its depth, caller balance and value.
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(u64, U256, U256), Error> {
+        let is_create2 = step.opcode() == Some(OpcodeId::CREATE2);
+        let mut rws = StepRws::new(block, step);
+
+        let tx_id = rws.next().call_context_value();
+        let depth = rws.next().call_context_value().low_u64();
+        rws.offset_add(2); // RwCounterEndOfReversion, IsPersistent
+        let caller_address = rws.next().call_context_value();
+        let [value, init_code_start, init_code_length] = [(); 3].map(|_| rws.next().stack_value());
+        let salt = if is_create2 {
+            rws.next().stack_value()
+        } else {
+            U256::zero()
+        };
+        rws.next(); // stack push
+        let caller_balance = rws.next().account_balance_pair().1;
+        let caller_nonce = rws.next().account_nonce_pair().1;
+        rws.offset_add(5); // caller call context writes
+        let [callee_rw_counter_end_of_reversion, callee_is_persistent] =
+            [(); 2].map(|_| rws.next().call_context_value());
+
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx_id.low_u64())))?;
+        self.depth
+            .assign(region, offset, Value::known(F::from(depth)))?;
+        self.reversion_info.assign(
+            region,
+            offset,
+            call.rw_counter_end_of_reversion,
+            call.is_persistent,
+        )?;
+        self.caller_address
+            .assign_u256(region, offset, caller_address)?;
+        self.value.assign_u256(region, offset, value)?;
+        self.salt.assign_u256(region, offset, salt)?;
+        self.caller_balance
+            .assign_u256(region, offset, caller_balance)?;
+        self.caller_nonce.assign(
+            region,
+            offset,
+            Value::known(F::from(caller_nonce.low_u64())),
+        )?;
+
+        let init_code_address =
+            self.init_code
+                .assign(region, offset, init_code_start, init_code_length)?;
+        let (_, memory_expansion_gas_cost) = self.memory_expansion.assign(
+            region,
+            offset,
+            step.memory_word_size(),
+            [init_code_address],
+        )?;
+        let (init_code_word_size, _) = self.init_code_word_size.assign(
+            region,
+            offset,
+            (31u64 + init_code_length.as_u64()).into(),
+        )?;
+        self.hardfork
+            .assign(region, offset, block.context.hardfork)?;
+        let init_code_gas_cost = u64::try_from(init_code_word_size).unwrap()
+            * (if is_create2 { GasCost::COPY_SHA3 } else { 0 }
+                + block.context.hardfork.init_code_word_gas());
+        let gas_left =
+            step.gas_left - GasCost::CREATE - memory_expansion_gas_cost - init_code_gas_cost;
+        self.gas_left.assign(region, offset, gas_left.into())?;
+        self.callee_reversion_info.assign(
+            region,
+            offset,
+            callee_rw_counter_end_of_reversion.low_u64() as usize,
+            callee_is_persistent.low_u64() != 0,
+        )?;
+
+        Ok((depth, caller_balance, value))
+    }
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct SloadGasGadget<F> {
     gas_cost: Expression<F>,
@@ -1295,3 +1806,95 @@ impl<F: Field, const VALID_BYTES: usize> WordByteRangeGadget<F, VALID_BYTES> {
         self.not_overflow.expr()
     }
 }
+
+/// Looks up the hardfork of the block, and decodes it into one flag per
+/// hardfork after Berlin, so that gadgets can select opcode availability and
+/// gas schedule values by hardfork.
+#[derive(Clone, Debug)]
+pub(crate) struct HardforkGadget<F> {
+    hardfork: Cell<F>,
+    is_london: Cell<F>,
+    is_shanghai: Cell<F>,
+    is_cancun: Cell<F>,
+}
+
+impl<F: Field> HardforkGadget<F> {
+    pub(crate) fn construct(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let hardfork = cb.query_cell();
+        let [is_london, is_shanghai, is_cancun] = [(); 3].map(|_| cb.query_bool());
+
+        cb.block_lookup(
+            BlockContextFieldTag::Hardfork.expr(),
+            None,
+            Word::from_lo_unchecked(hardfork.expr()),
+        );
+
+        // Hardforks are activated in order, so the hardfork identifier is the
+        // number of active hardforks after Berlin.
+        cb.require_equal(
+            "hardfork == is_london + is_shanghai + is_cancun",
+            hardfork.expr(),
+            is_london.expr() + is_shanghai.expr() + is_cancun.expr(),
+        );
+        cb.require_zero(
+            "Shanghai is only active after London",
+            is_shanghai.expr() * not::expr(is_london.expr()),
+        );
+        cb.require_zero(
+            "Cancun is only active after Shanghai",
+            is_cancun.expr() * not::expr(is_shanghai.expr()),
+        );
+
+        Self {
+            hardfork,
+            is_london,
+            is_shanghai,
+            is_cancun,
+        }
+    }
+
+    /// Returns 1 if `hardfork` is active in the block, 0 otherwise.
+    pub(crate) fn is_active(&self, hardfork: Hardfork) -> Expression<F> {
+        match hardfork {
+            Hardfork::Berlin => 1.expr(),
+            Hardfork::London => self.is_london.expr(),
+            Hardfork::Shanghai => self.is_shanghai.expr(),
+            Hardfork::Cancun => self.is_cancun.expr(),
+        }
+    }
+
+    /// Returns the value of `f` for the hardfork of the block.
+    pub(crate) fn select(&self, f: impl Fn(Hardfork) -> u64) -> Expression<F> {
+        let value = |hardfork| -> Expression<F> { f(hardfork).expr() };
+        value(Hardfork::Berlin)
+            + self.is_london.expr() * (value(Hardfork::London) - value(Hardfork::Berlin))
+            + self.is_shanghai.expr() * (value(Hardfork::Shanghai) - value(Hardfork::London))
+            + self.is_cancun.expr() * (value(Hardfork::Cancun) - value(Hardfork::Shanghai))
+    }
+
+    pub(crate) fn expr(&self) -> Expression<F> {
+        self.hardfork.expr()
+    }
+
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        hardfork: Hardfork,
+    ) -> Result<(), Error> {
+        self.hardfork
+            .assign(region, offset, Value::known(F::from(hardfork.as_u64())))?;
+        for (cell, activation) in [
+            (&self.is_london, Hardfork::London),
+            (&self.is_shanghai, Hardfork::Shanghai),
+            (&self.is_cancun, Hardfork::Cancun),
+        ] {
+            cell.assign(
+                region,
+                offset,
+                Value::known(F::from((hardfork >= activation) as u64)),
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -283,6 +283,7 @@ impl<F: Field> BaseConstraintBuilder<F> {
 enum ConstraintLocation {
     Step,
     StepFirst,
+    StepLast,
     NotStepLast,
 }
 
@@ -981,6 +982,54 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         );
     }
 
+    // Tx Created Account
+
+    pub(crate) fn tx_created_account_write(
+        &mut self,
+        tx_id: Expression<F>,
+        account_address: Word<Expression<F>>,
+        value: Expression<F>,
+        value_prev: Expression<F>,
+        reversion_info: Option<&mut ReversionInfo<F>>,
+    ) {
+        self.reversible_write(
+            "TxCreatedAccount write",
+            Target::TxCreatedAccount,
+            RwValues::new(
+                tx_id,
+                address_word_to_expr(account_address),
+                0.expr(),
+                Word::zero(),
+                Word::from_lo_unchecked(value),
+                Word::from_lo_unchecked(value_prev),
+                Word::zero(),
+            ),
+            reversion_info,
+        );
+    }
+
+    pub(crate) fn tx_created_account_read(
+        &mut self,
+        tx_id: Expression<F>,
+        account_address: Word<Expression<F>>,
+        value: Expression<F>,
+    ) {
+        self.rw_lookup(
+            "TxCreatedAccount read",
+            false.expr(),
+            Target::TxCreatedAccount,
+            RwValues::new(
+                tx_id,
+                address_word_to_expr(account_address),
+                0.expr(),
+                Word::zero(),
+                Word::from_lo_unchecked(value.clone()),
+                Word::from_lo_unchecked(value),
+                Word::zero(),
+            ),
+        );
+    }
+
     // Account
     pub(crate) fn account_read(
         &mut self,
@@ -1080,6 +1129,56 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         );
     }
 
+    // Transient Storage
+
+    pub(crate) fn transient_storage_read(
+        &mut self,
+        account_address: Word<Expression<F>>,
+        key: Word<Expression<F>>,
+        value: Word<Expression<F>>,
+        tx_id: Expression<F>,
+    ) {
+        self.rw_lookup(
+            "transient_storage_read",
+            false.expr(),
+            Target::TransientStorage,
+            RwValues::new(
+                tx_id,
+                address_word_to_expr(account_address),
+                0.expr(),
+                key,
+                value.clone(),
+                value,
+                Word::zero(),
+            ),
+        );
+    }
+
+    pub(crate) fn transient_storage_write(
+        &mut self,
+        account_address: Word<Expression<F>>,
+        key: Word<Expression<F>>,
+        value: Word<Expression<F>>,
+        value_prev: Word<Expression<F>>,
+        tx_id: Expression<F>,
+        reversion_info: Option<&mut ReversionInfo<F>>,
+    ) {
+        self.reversible_write(
+            "TransientStorage write",
+            Target::TransientStorage,
+            RwValues::new(
+                tx_id,
+                address_word_to_expr(account_address),
+                0.expr(),
+                key,
+                value,
+                value_prev,
+                Word::zero(),
+            ),
+            reversion_info,
+        );
+    }
+
     // Call context
     pub(crate) fn call_context(
         &mut self,
@@ -1419,6 +1518,173 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         );
     }
 
+    // Sig Table
+
+    pub(crate) fn sig_table_lookup(
+        &mut self,
+        msg_hash: Word<Expression<F>>,
+        sig_v: Expression<F>,
+        sig_r: Word<Expression<F>>,
+        sig_s: Word<Expression<F>>,
+        recovered_addr: Word<Expression<F>>,
+        is_valid: Expression<F>,
+    ) {
+        self.add_lookup(
+            "sig table",
+            Lookup::SigTable {
+                msg_hash,
+                sig_v,
+                sig_r,
+                sig_s,
+                recovered_addr,
+                is_valid,
+            },
+        );
+    }
+
+    // Sha256 Table
+
+    pub(crate) fn sha256_table_lookup(
+        &mut self,
+        input_rlc: Expression<F>,
+        input_len: Expression<F>,
+        output_rlc: Expression<F>,
+    ) {
+        self.add_lookup(
+            "sha256 table",
+            Lookup::Sha256Table {
+                input_rlc,
+                input_len,
+                output_rlc,
+            },
+        );
+    }
+
+    // ModExp Table
+
+    pub(crate) fn modexp_table_lookup(
+        &mut self,
+        base: Word<Expression<F>>,
+        exponent: Word<Expression<F>>,
+        modulus: Word<Expression<F>>,
+        result: Word<Expression<F>>,
+    ) {
+        self.add_lookup(
+            "modexp table",
+            Lookup::ModExpTable {
+                base,
+                exponent,
+                modulus,
+                result,
+            },
+        );
+    }
+
+    // Ecc Table
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn ecc_table_lookup(
+        &mut self,
+        op_type: Expression<F>,
+        arg1: Word<Expression<F>>,
+        arg2: Word<Expression<F>>,
+        arg3: Word<Expression<F>>,
+        arg4: Word<Expression<F>>,
+        input_rlc: Expression<F>,
+        input_len: Expression<F>,
+        output1: Word<Expression<F>>,
+        output2: Word<Expression<F>>,
+        is_valid: Expression<F>,
+    ) {
+        self.add_lookup(
+            "ecc table",
+            Lookup::EccTable {
+                op_type,
+                arg1,
+                arg2,
+                arg3,
+                arg4,
+                input_rlc,
+                input_len,
+                output1,
+                output2,
+                is_valid,
+            },
+        );
+    }
+
+    // Ripemd160 Table
+
+    pub(crate) fn ripemd160_table_lookup(
+        &mut self,
+        input_rlc: Expression<F>,
+        input_len: Expression<F>,
+        output_rlc: Expression<F>,
+    ) {
+        self.add_lookup(
+            "ripemd160 table",
+            Lookup::Ripemd160Table {
+                input_rlc,
+                input_len,
+                output_rlc,
+            },
+        );
+    }
+
+    // Blake2f Table
+
+    pub(crate) fn blake2f_table_lookup(
+        &mut self,
+        input_rlc: Expression<F>,
+        rounds: Expression<F>,
+        is_valid: Expression<F>,
+        output_rlc: Expression<F>,
+    ) {
+        self.add_lookup(
+            "blake2f table",
+            Lookup::Blake2fTable {
+                input_rlc,
+                rounds,
+                is_valid,
+                output_rlc,
+            },
+        );
+    }
+
+    // Kzg Table
+
+    pub(crate) fn kzg_table_lookup(&mut self, input_rlc: Expression<F>, is_valid: Expression<F>) {
+        self.add_lookup(
+            "kzg table",
+            Lookup::KzgTable {
+                input_rlc,
+                is_valid,
+            },
+        );
+    }
+
+    // Wd Table
+
+    pub(crate) fn wd_table_lookup(
+        &mut self,
+        index: Expression<F>,
+        id: Expression<F>,
+        validator_id: Expression<F>,
+        address: Word<Expression<F>>,
+        amount: Expression<F>,
+    ) {
+        self.add_lookup(
+            "wd table",
+            Lookup::WdTable {
+                index,
+                id,
+                validator_id,
+                address,
+                amount,
+            },
+        );
+    }
+
     // Keccak Table
     pub(crate) fn keccak_table_lookup(
         &mut self,
@@ -1483,9 +1749,9 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
             sum::expr(&conditions),
         );
 
-        // TODO: constraining the same cells repeatedly requires a height-resetting mechanism
-        // on the cell manager. In this case, since only identity precompile is added
-        // this height management maneuver is temporarily left out.
+        // Each of the next states lays out its cells from the top of the next step, so the
+        // cell manager of the next step is reset before constraining each of them.
+        let next_cell_manager = self.next.cell_manager.clone();
         for ((&next_state, condition), constraint) in next_states
             .iter()
             .zip(conditions.into_iter())
@@ -1493,6 +1759,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         {
             // constrain the next step.
             self.constrain_next_step(next_state, Some(condition), constraint);
+            self.next.cell_manager = next_cell_manager.clone();
         }
     }
 
@@ -1544,6 +1811,11 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         self.constraint_at_location(ConstraintLocation::StepFirst, constraint)
     }
 
+    /// register constraints to be applied on the last step
+    pub(crate) fn step_last<R>(&mut self, constraint: impl FnOnce(&mut Self) -> R) -> R {
+        self.constraint_at_location(ConstraintLocation::StepLast, constraint)
+    }
+
     /// register constraints to be applied on step other than first step
     pub(crate) fn not_step_last<R>(&mut self, constraint: impl FnOnce(&mut Self) -> R) -> R {
         self.constraint_at_location(ConstraintLocation::NotStepLast, constraint)
@@ -1554,6 +1826,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         match self.constraints_location {
             ConstraintLocation::Step => self.constraints.step.push((name, constraint)),
             ConstraintLocation::StepFirst => self.constraints.step_first.push((name, constraint)),
+            ConstraintLocation::StepLast => self.constraints.step_last.push((name, constraint)),
             ConstraintLocation::NotStepLast => {
                 self.constraints.not_step_last.push((name, constraint))
             }
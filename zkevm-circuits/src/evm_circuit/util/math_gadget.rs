@@ -21,6 +21,7 @@ mod mul_add_words;
 mod mul_add_words512;
 mod mul_word_u64;
 mod pair_select;
+mod rand_pow;
 mod range_check;
 mod rlp;
 #[cfg(test)]
@@ -45,6 +46,7 @@ pub(crate) use mul_add_words::MulAddWordsGadget;
 pub(crate) use mul_add_words512::MulAddWords512Gadget;
 pub(crate) use mul_word_u64::MulWordByU64Gadget;
 pub(crate) use pair_select::PairSelectGadget;
+pub(crate) use rand_pow::RandPowGadget;
 pub(crate) use range_check::RangeCheckGadget;
 pub(crate) use rlp::ContractCreateGadget;
 
@@ -0,0 +1,152 @@
+use crate::{
+    evm_circuit::util::{
+        constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+        sum, CachedRegion, Cell,
+    },
+    util::Expr,
+};
+use eth_types::Field;
+use halo2_proofs::{
+    circuit::Value,
+    plonk::{Error, Expression},
+};
+
+/// Returns `r^exponent` for the keccak input randomness `r`, where `exponent`
+/// is lower than `2^N_BITS` and `N_BITS` is at most 8.  The powers `r^(2^i)`
+/// are accumulated over the bits of the exponent.
+#[derive(Clone, Debug)]
+pub(crate) struct RandPowGadget<F, const N_BITS: usize> {
+    /// Bits of the exponent.
+    bits: [Cell<F>; N_BITS],
+    /// Running product of `r^(2^i)` over the set bits.
+    acc: [Cell<F>; N_BITS],
+}
+
+impl<F: Field, const N_BITS: usize> RandPowGadget<F, N_BITS> {
+    pub(crate) fn construct(cb: &mut EVMConstraintBuilder<F>, exponent: Expression<F>) -> Self {
+        let bits = [(); N_BITS].map(|_| cb.query_bool());
+        cb.require_equal(
+            "bits add up to the exponent",
+            sum::expr(
+                bits.iter()
+                    .enumerate()
+                    .map(|(i, bit)| bit.expr() * (1u64 << i).expr()),
+            ),
+            exponent,
+        );
+
+        let powers_of_randomness = cb.challenges().keccak_powers_of_randomness::<128>();
+        let factor = |i: usize| {
+            1.expr() + bits[i].expr() * (powers_of_randomness[(1 << i) - 1].clone() - 1.expr())
+        };
+        let acc = [(); N_BITS].map(|_| cb.query_cell_phase2());
+        for (i, acc_i) in acc.iter().enumerate() {
+            let prev = if i == 0 { 1.expr() } else { acc[i - 1].expr() };
+            cb.require_equal(
+                "accumulator is the product of the previous factors",
+                acc_i.expr(),
+                prev * factor(i),
+            );
+        }
+
+        Self { bits, acc }
+    }
+
+    pub(crate) fn expr(&self) -> Expression<F> {
+        self.acc[N_BITS - 1].expr()
+    }
+
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        exponent: usize,
+    ) -> Result<(), Error> {
+        let keccak_input = region.challenges().keccak_input();
+        for (i, (bit, acc)) in self.bits.iter().zip(self.acc.iter()).enumerate() {
+            bit.assign(
+                region,
+                offset,
+                Value::known(F::from(((exponent >> i) & 1) as u64)),
+            )?;
+            // product of the factors for bits 0..=i
+            let acc_exponent = exponent & ((1 << (i + 1)) - 1);
+            acc.assign(
+                region,
+                offset,
+                keccak_input.map(|randomness| randomness.pow([acc_exponent as u64, 0, 0, 0])),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::test_util::*, *};
+    use eth_types::Word;
+    use halo2_proofs::{halo2curves::bn256::Fr, plonk::Error};
+
+    #[derive(Clone)]
+    /// RandPowGadgetContainer: require(pow = r ^ exponent)
+    struct RandPowGadgetContainer<F, const SHIFT: u64> {
+        rand_pow_gadget: RandPowGadget<F, 8>,
+        exponent: Cell<F>,
+        pow: Cell<F>,
+    }
+
+    impl<F: Field, const SHIFT: u64> MathGadgetContainer<F> for RandPowGadgetContainer<F, SHIFT> {
+        fn configure_gadget_container(cb: &mut EVMConstraintBuilder<F>) -> Self {
+            let exponent = cb.query_cell();
+            let pow = cb.query_cell_phase2();
+            let rand_pow_gadget = RandPowGadget::<F, 8>::construct(cb, exponent.expr());
+            cb.require_equal("pow = r ^ exponent", pow.expr(), rand_pow_gadget.expr());
+            RandPowGadgetContainer {
+                rand_pow_gadget,
+                exponent,
+                pow,
+            }
+        }
+
+        fn assign_gadget_container(
+            &self,
+            witnesses: &[Word],
+            region: &mut CachedRegion<'_, '_, F>,
+        ) -> Result<(), Error> {
+            let exponent = witnesses[0].as_u64();
+            let offset = 0;
+
+            self.exponent
+                .assign(region, offset, Value::known(F::from(exponent)))?;
+            let pow = region
+                .challenges()
+                .keccak_input()
+                .map(|randomness| randomness.pow([exponent + SHIFT, 0, 0, 0]));
+            self.pow.assign(region, offset, pow)?;
+            self.rand_pow_gadget
+                .assign(region, offset, exponent as usize)?;
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_rand_pow_expect() {
+        for exponent in [0u64, 1, 37, 128, 255] {
+            try_test!(
+                RandPowGadgetContainer<Fr, 0>,
+                vec![Word::from(exponent)],
+                true,
+            );
+        }
+    }
+
+    #[test]
+    fn test_rand_pow_unexpect() {
+        try_test!(
+            RandPowGadgetContainer<Fr, 1>,
+            vec![Word::from(37u64)],
+            false,
+        );
+    }
+}
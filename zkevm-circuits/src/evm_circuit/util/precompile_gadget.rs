@@ -25,20 +25,31 @@ impl<F: Field> PrecompileGadget<F> {
         _caller_id: Expression<F>,
         _cd_offset: Expression<F>,
         cd_length: Expression<F>,
+        // number of input bytes taken by the precompile call.
+        input_len: Expression<F>,
         _rd_offset: Expression<F>,
         _rd_length: Expression<F>,
         precompile_return_length: Expression<F>,
         // input bytes to precompile call.
-        _input_bytes_rlc: Expression<F>,
+        input_bytes_rlc: Expression<F>,
         // output result from precompile call.
-        _output_bytes_rlc: Expression<F>,
+        output_bytes_rlc: Expression<F>,
         // returned bytes back to caller.
         _return_bytes_rlc: Expression<F>,
     ) -> Self {
         let address = BinaryNumberGadget::construct(cb, callee_address.expr());
 
         let conditions = vec![
+            address.value_equals(PrecompileCalls::ECRecover),
+            address.value_equals(PrecompileCalls::Sha256),
+            address.value_equals(PrecompileCalls::Ripemd160),
             address.value_equals(PrecompileCalls::Identity),
+            address.value_equals(PrecompileCalls::Modexp),
+            address.value_equals(PrecompileCalls::Bn128Add),
+            address.value_equals(PrecompileCalls::Bn128Mul),
+            address.value_equals(PrecompileCalls::Bn128Pairing),
+            address.value_equals(PrecompileCalls::Blake2F),
+            address.value_equals(PrecompileCalls::PointEvaluation),
             // match more precompiles
         ]
         .into_iter()
@@ -48,10 +59,137 @@ impl<F: Field> PrecompileGadget<F> {
         .collect::<Vec<_>>();
 
         let next_states = vec![
-            ExecutionState::PrecompileIdentity, // add more precompile execution states
+            ExecutionState::PrecompileEcRecover,
+            ExecutionState::PrecompileSha256,
+            ExecutionState::PrecompileRipemd160,
+            ExecutionState::PrecompileIdentity,
+            ExecutionState::PrecompileBigModExp,
+            ExecutionState::PrecompileBn256Add,
+            ExecutionState::PrecompileBn256ScalarMul,
+            ExecutionState::PrecompileBn256Pairing,
+            ExecutionState::PrecompileBlake2f,
+            ExecutionState::PrecompilePointEvaluation, // add more precompile execution states
         ];
 
+        let ecrecover_return_length = precompile_return_length.clone();
+        let sha256_return_length = precompile_return_length.clone();
+        let (sha256_cd_length, sha256_input_len) = (cd_length.clone(), input_len.clone());
+        let (sha256_input_bytes_rlc, sha256_output_bytes_rlc) =
+            (input_bytes_rlc.clone(), output_bytes_rlc.clone());
+        let ripemd160_return_length = precompile_return_length.clone();
+        let (ripemd160_cd_length, ripemd160_input_len) = (cd_length.clone(), input_len.clone());
+        let (ripemd160_input_bytes_rlc, ripemd160_output_bytes_rlc) =
+            (input_bytes_rlc.clone(), output_bytes_rlc.clone());
+        let (modexp_input_len, modexp_return_length) =
+            (input_len.clone(), precompile_return_length.clone());
+        let (modexp_input_bytes_rlc, modexp_output_bytes_rlc) =
+            (input_bytes_rlc.clone(), output_bytes_rlc.clone());
+        let (ec_add_input_len, ec_add_return_length) =
+            (input_len.clone(), precompile_return_length.clone());
+        let (ec_add_input_bytes_rlc, ec_add_output_bytes_rlc) =
+            (input_bytes_rlc.clone(), output_bytes_rlc.clone());
+        let (ec_mul_input_len, ec_mul_return_length) =
+            (input_len.clone(), precompile_return_length.clone());
+        let (ec_mul_input_bytes_rlc, ec_mul_output_bytes_rlc) =
+            (input_bytes_rlc.clone(), output_bytes_rlc.clone());
+        let (ec_pairing_input_len, ec_pairing_return_length) =
+            (input_len.clone(), precompile_return_length.clone());
+        let (ec_pairing_input_bytes_rlc, ec_pairing_output_bytes_rlc) =
+            (input_bytes_rlc.clone(), output_bytes_rlc.clone());
+        let (blake2f_cd_length, blake2f_input_len, blake2f_return_length) = (
+            cd_length.clone(),
+            input_len.clone(),
+            precompile_return_length.clone(),
+        );
+        let (blake2f_input_bytes_rlc, blake2f_output_bytes_rlc) =
+            (input_bytes_rlc.clone(), output_bytes_rlc.clone());
+        let (kzg_cd_length, kzg_input_len, kzg_return_length) = (
+            cd_length.clone(),
+            input_len.clone(),
+            precompile_return_length.clone(),
+        );
+        let (kzg_input_bytes_rlc, kzg_output_bytes_rlc) =
+            (input_bytes_rlc.clone(), output_bytes_rlc.clone());
         let constraints: Vec<BoxedClosure<F>> = vec![
+            Box::new(move |cb| {
+                // EcRecover, the cells are queried in the same order as in `EcrecoverGadget`.
+                let (is_recovered, next_input_len, next_input_bytes_rlc, next_output_bytes_rlc) = (
+                    cb.query_cell(),
+                    cb.query_cell(),
+                    cb.query_cell_phase2(),
+                    cb.query_cell_phase2(),
+                );
+                cb.require_equal(
+                    "ecrecover: input length is the same",
+                    input_len,
+                    next_input_len.expr(),
+                );
+                cb.require_equal(
+                    "ecrecover: input bytes rlc is the same",
+                    input_bytes_rlc,
+                    next_input_bytes_rlc.expr(),
+                );
+                cb.require_equal(
+                    "ecrecover: output bytes rlc is the same",
+                    output_bytes_rlc,
+                    next_output_bytes_rlc.expr(),
+                );
+                cb.require_equal(
+                    "ecrecover: return length is 32 if an address is recovered, 0 otherwise",
+                    ecrecover_return_length,
+                    is_recovered.expr() * 32.expr(),
+                );
+            }),
+            Box::new(move |cb| {
+                // Sha256, the cells are queried in the same order as in `Sha256Gadget`.
+                let (next_input_bytes_rlc, next_output_bytes_rlc) =
+                    (cb.query_cell_phase2(), cb.query_cell_phase2());
+                cb.require_equal(
+                    "sha256: the whole call data is taken as input",
+                    sha256_input_len,
+                    sha256_cd_length,
+                );
+                cb.require_equal(
+                    "sha256: input bytes rlc is the same",
+                    sha256_input_bytes_rlc,
+                    next_input_bytes_rlc.expr(),
+                );
+                cb.require_equal(
+                    "sha256: output bytes rlc is the same",
+                    sha256_output_bytes_rlc,
+                    next_output_bytes_rlc.expr(),
+                );
+                cb.require_equal(
+                    "sha256: precompile return length is 32",
+                    sha256_return_length,
+                    32.expr(),
+                );
+            }),
+            Box::new(move |cb| {
+                // Ripemd160, the cells are queried in the same order as in `Ripemd160Gadget`.
+                let (next_input_bytes_rlc, next_output_bytes_rlc) =
+                    (cb.query_cell_phase2(), cb.query_cell_phase2());
+                cb.require_equal(
+                    "ripemd160: the whole call data is taken as input",
+                    ripemd160_input_len,
+                    ripemd160_cd_length,
+                );
+                cb.require_equal(
+                    "ripemd160: input bytes rlc is the same",
+                    ripemd160_input_bytes_rlc,
+                    next_input_bytes_rlc.expr(),
+                );
+                cb.require_equal(
+                    "ripemd160: output bytes rlc is the same",
+                    ripemd160_output_bytes_rlc,
+                    next_output_bytes_rlc.expr(),
+                );
+                cb.require_equal(
+                    "ripemd160: precompile return length is 32",
+                    ripemd160_return_length,
+                    32.expr(),
+                );
+            }),
             Box::new(|cb| {
                 // Identity
                 cb.require_equal(
@@ -59,6 +197,179 @@ impl<F: Field> PrecompileGadget<F> {
                     cd_length,
                     precompile_return_length,
                 );
+            }),
+            Box::new(move |cb| {
+                // Modexp, the cells are queried in the same order as in `ModExpGadget`.
+                let (next_input_len, next_output_len, next_input_bytes_rlc, next_output_bytes_rlc) = (
+                    cb.query_cell(),
+                    cb.query_cell(),
+                    cb.query_cell_phase2(),
+                    cb.query_cell_phase2(),
+                );
+                cb.require_equal(
+                    "modexp: input length is the same",
+                    modexp_input_len,
+                    next_input_len.expr(),
+                );
+                cb.require_equal(
+                    "modexp: precompile return length is the output length",
+                    modexp_return_length,
+                    next_output_len.expr(),
+                );
+                cb.require_equal(
+                    "modexp: input bytes rlc is the same",
+                    modexp_input_bytes_rlc,
+                    next_input_bytes_rlc.expr(),
+                );
+                cb.require_equal(
+                    "modexp: output bytes rlc is the same",
+                    modexp_output_bytes_rlc,
+                    next_output_bytes_rlc.expr(),
+                );
+            }),
+            Box::new(move |cb| {
+                // EcAdd, the cells are queried in the same order as in `EcAddGadget`.
+                let (next_input_len, next_output_len, next_input_bytes_rlc, next_output_bytes_rlc) = (
+                    cb.query_cell(),
+                    cb.query_cell(),
+                    cb.query_cell_phase2(),
+                    cb.query_cell_phase2(),
+                );
+                cb.require_equal(
+                    "ec_add: input length is the same",
+                    ec_add_input_len,
+                    next_input_len.expr(),
+                );
+                cb.require_equal(
+                    "ec_add: precompile return length is the output length",
+                    ec_add_return_length,
+                    next_output_len.expr(),
+                );
+                cb.require_equal(
+                    "ec_add: input bytes rlc is the same",
+                    ec_add_input_bytes_rlc,
+                    next_input_bytes_rlc.expr(),
+                );
+                cb.require_equal(
+                    "ec_add: output bytes rlc is the same",
+                    ec_add_output_bytes_rlc,
+                    next_output_bytes_rlc.expr(),
+                );
+            }),
+            Box::new(move |cb| {
+                // EcMul, the cells are queried in the same order as in `EcMulGadget`.
+                let (next_input_len, next_output_len, next_input_bytes_rlc, next_output_bytes_rlc) = (
+                    cb.query_cell(),
+                    cb.query_cell(),
+                    cb.query_cell_phase2(),
+                    cb.query_cell_phase2(),
+                );
+                cb.require_equal(
+                    "ec_mul: input length is the same",
+                    ec_mul_input_len,
+                    next_input_len.expr(),
+                );
+                cb.require_equal(
+                    "ec_mul: precompile return length is the output length",
+                    ec_mul_return_length,
+                    next_output_len.expr(),
+                );
+                cb.require_equal(
+                    "ec_mul: input bytes rlc is the same",
+                    ec_mul_input_bytes_rlc,
+                    next_input_bytes_rlc.expr(),
+                );
+                cb.require_equal(
+                    "ec_mul: output bytes rlc is the same",
+                    ec_mul_output_bytes_rlc,
+                    next_output_bytes_rlc.expr(),
+                );
+            }),
+            Box::new(move |cb| {
+                // EcPairing, the cells are queried in the same order as in `EcPairingGadget`.
+                let (next_input_len, next_output_len, next_input_bytes_rlc, next_output_bytes_rlc) = (
+                    cb.query_cell(),
+                    cb.query_cell(),
+                    cb.query_cell_phase2(),
+                    cb.query_cell_phase2(),
+                );
+                cb.require_equal(
+                    "ec_pairing: input length is the same",
+                    ec_pairing_input_len,
+                    next_input_len.expr(),
+                );
+                cb.require_equal(
+                    "ec_pairing: precompile return length is the output length",
+                    ec_pairing_return_length,
+                    next_output_len.expr(),
+                );
+                cb.require_equal(
+                    "ec_pairing: input bytes rlc is the same",
+                    ec_pairing_input_bytes_rlc,
+                    next_input_bytes_rlc.expr(),
+                );
+                cb.require_equal(
+                    "ec_pairing: output bytes rlc is the same",
+                    ec_pairing_output_bytes_rlc,
+                    next_output_bytes_rlc.expr(),
+                );
+            }),
+            Box::new(move |cb| {
+                // Blake2F, the cells are queried in the same order as in `Blake2fGadget`.
+                let (next_output_len, next_input_bytes_rlc, next_output_bytes_rlc) = (
+                    cb.query_cell(),
+                    cb.query_cell_phase2(),
+                    cb.query_cell_phase2(),
+                );
+                cb.require_equal(
+                    "blake2f: the whole call data is taken as input",
+                    blake2f_input_len,
+                    blake2f_cd_length,
+                );
+                cb.require_equal(
+                    "blake2f: precompile return length is the output length",
+                    blake2f_return_length,
+                    next_output_len.expr(),
+                );
+                cb.require_equal(
+                    "blake2f: input bytes rlc is the same",
+                    blake2f_input_bytes_rlc,
+                    next_input_bytes_rlc.expr(),
+                );
+                cb.require_equal(
+                    "blake2f: output bytes rlc is the same",
+                    blake2f_output_bytes_rlc,
+                    next_output_bytes_rlc.expr(),
+                );
+            }),
+            Box::new(move |cb| {
+                // PointEvaluation, the cells are queried in the same order as in
+                // `PointEvaluationGadget`.
+                let (next_output_len, next_input_bytes_rlc, next_output_bytes_rlc) = (
+                    cb.query_cell(),
+                    cb.query_cell_phase2(),
+                    cb.query_cell_phase2(),
+                );
+                cb.require_equal(
+                    "point_evaluation: the whole call data is taken as input",
+                    kzg_input_len,
+                    kzg_cd_length,
+                );
+                cb.require_equal(
+                    "point_evaluation: precompile return length is the output length",
+                    kzg_return_length,
+                    next_output_len.expr(),
+                );
+                cb.require_equal(
+                    "point_evaluation: input bytes rlc is the same",
+                    kzg_input_bytes_rlc,
+                    next_input_bytes_rlc.expr(),
+                );
+                cb.require_equal(
+                    "point_evaluation: output bytes rlc is the same",
+                    kzg_output_bytes_rlc,
+                    next_output_bytes_rlc.expr(),
+                );
             }), // add more precompile constraint closures
         ];
 
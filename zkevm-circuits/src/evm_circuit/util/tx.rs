@@ -3,6 +3,7 @@ use crate::{
         param::N_BYTES_U64,
         step::ExecutionState,
         util::{
+            common_gadget::HardforkGadget,
             constraint_builder::{
                 ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition, Transition::*,
             },
@@ -13,17 +14,18 @@ use crate::{
         },
         witness::{Block, Transaction},
     },
-    table::{CallContextFieldTag, TxContextFieldTag, TxReceiptFieldTag},
-    util::word::{Word32Cell, WordCell},
+    table::{BlockContextFieldTag, CallContextFieldTag, TxContextFieldTag, TxReceiptFieldTag},
+    util::word::{Word32Cell, WordCell, WordExpr},
+};
+use eth_types::{
+    evm_types::{GasCost, GAS_PER_BLOB},
+    Field,
 };
-use bus_mapping::operation::Target;
-use eth_types::{evm_types::GasCost, Field};
 use gadgets::util::{select, Expr, Scalar};
 use halo2_proofs::{
     circuit::Value,
     plonk::{Error, Expression},
 };
-use strum::EnumCount;
 
 /// Gadget for beginning a tx
 #[derive(Clone, Debug)]
@@ -71,17 +73,36 @@ impl<F: Field> BeginTxHelperGadget<F> {
 pub(crate) struct EndTxHelperGadget<F> {
     current_cumulative_gas_used: Cell<F>,
     is_first_tx: IsEqualGadget<F>,
+    // In a chunk of several blocks, the cumulative gas used restarts at the
+    // first tx of each block.
+    prev_block_number: Cell<F>,
+    is_same_block: IsEqualGadget<F>,
+    withdrawal_offset: Cell<F>,
 }
 
 impl<F: Field> EndTxHelperGadget<F> {
     pub(crate) fn construct(
         cb: &mut EVMConstraintBuilder<F>,
         tx_id: Expression<F>,
+        block_number: Expression<F>,
         is_persistent: Expression<F>,
         gas_used: Expression<F>,
         num_rw: Expression<F>,
     ) -> Self {
         let is_first_tx = IsEqualGadget::construct(cb, tx_id.expr(), 1.expr());
+        let prev_block_number = cb.query_cell();
+        cb.condition(1.expr() - is_first_tx.expr(), |cb| {
+            cb.tx_context_lookup(
+                tx_id.expr() - 1.expr(),
+                TxContextFieldTag::BlockNumber,
+                None,
+                Word::from_lo_unchecked(prev_block_number.expr()),
+            );
+        });
+        let is_same_block =
+            IsEqualGadget::construct(cb, prev_block_number.expr(), block_number.expr());
+        let is_first_tx_in_block =
+            1.expr() - (1.expr() - is_first_tx.expr()) * is_same_block.expr();
 
         // Constrain tx receipt fields
         cb.tx_receipt_lookup(
@@ -97,13 +118,13 @@ impl<F: Field> EndTxHelperGadget<F> {
             cb.curr.state.log_id.expr(),
         );
         let current_cumulative_gas_used = cb.query_cell();
-        cb.condition(is_first_tx.expr(), |cb| {
+        cb.condition(is_first_tx_in_block.expr(), |cb| {
             cb.require_zero(
-                "current_cumulative_gas_used is zero when tx is first tx",
+                "current_cumulative_gas_used is zero when tx is first tx of the block",
                 current_cumulative_gas_used.expr(),
             );
         });
-        cb.condition(1.expr() - is_first_tx.expr(), |cb| {
+        cb.condition(1.expr() - is_first_tx_in_block.expr(), |cb| {
             cb.tx_receipt_lookup(
                 0.expr(),
                 tx_id.expr() - 1.expr(),
@@ -118,8 +139,16 @@ impl<F: Field> EndTxHelperGadget<F> {
             gas_used + current_cumulative_gas_used.expr(),
         );
 
+        // The withdrawals processed before the ones of the block of the tx
+        let withdrawal_offset = cb.query_cell();
+        cb.block_lookup(
+            BlockContextFieldTag::WithdrawalOffset.expr(),
+            Some(block_number.expr()),
+            Word::from_lo_unchecked(withdrawal_offset.expr()),
+        );
+
         // Transition
-        let rw_counter_offset = num_rw.expr() - is_first_tx.expr();
+        let rw_counter_offset = num_rw.expr() - is_first_tx_in_block.expr();
         cb.condition(
             cb.next
                 .execution_state_selector([ExecutionState::BeginTx, ExecutionState::InvalidTx]),
@@ -133,6 +162,14 @@ impl<F: Field> EndTxHelperGadget<F> {
                     // tx_id has been lookup and range_check above
                     Word::from_lo_unchecked(tx_id.expr() + 1.expr()),
                 );
+                // The next tx belongs to the same block, otherwise an EndBlock
+                // step comes in between
+                cb.tx_context_lookup(
+                    tx_id.expr() + 1.expr(),
+                    TxContextFieldTag::BlockNumber,
+                    None,
+                    Word::from_lo_unchecked(block_number.expr()),
+                );
                 // minus 1.expr() because `call_context_lookup_write_with_counter` do not bump
                 // rwc
                 cb.require_step_state_transition(StepStateTransition {
@@ -149,6 +186,22 @@ impl<F: Field> EndTxHelperGadget<F> {
                     // We propagate call_id so that EndBlock can get the last tx_id
                     // in order to count processed txs.
                     call_id: Same,
+                    // No withdrawals of the block have been processed before EndBlock.
+                    program_counter: To(withdrawal_offset.expr()),
+                    ..StepStateTransition::any()
+                });
+            },
+        );
+        cb.condition(
+            cb.next
+                .execution_state_selector([ExecutionState::Withdrawal]),
+            |cb| {
+                cb.require_step_state_transition(StepStateTransition {
+                    rw_counter: Delta(rw_counter_offset.expr() - 1.expr()),
+                    // The call_id is propagated through the withdrawals down to EndBlock.
+                    call_id: Same,
+                    // Withdrawals are indexed from 1 in the chunk.
+                    program_counter: To(withdrawal_offset.expr() + 1.expr()),
                     ..StepStateTransition::any()
                 });
             },
@@ -157,6 +210,9 @@ impl<F: Field> EndTxHelperGadget<F> {
         Self {
             is_first_tx,
             current_cumulative_gas_used,
+            prev_block_number,
+            is_same_block,
+            withdrawal_offset,
         }
     }
 
@@ -170,22 +226,35 @@ impl<F: Field> EndTxHelperGadget<F> {
         self.is_first_tx
             .assign(region, offset, F::from(tx.id), F::ONE)?;
 
-        let current_cumulative_gas_used: u64 = if tx.id == 1 {
+        let prev_block_number = if tx.id == 1 {
+            0
+        } else {
+            block.txs[tx.id as usize - 2].block_num
+        };
+        self.prev_block_number
+            .assign(region, offset, Value::known(F::from(prev_block_number)))?;
+        self.is_same_block.assign(
+            region,
+            offset,
+            F::from(prev_block_number),
+            F::from(tx.block_num),
+        )?;
+
+        let current_cumulative_gas_used: u64 = if tx.id == 1 || prev_block_number != tx.block_num {
             0
         } else {
-            // first transaction needs TxReceiptFieldTag::COUNT(3) lookups to tx receipt,
-            // while later transactions need 4 (with one extra cumulative gas read) lookups
-            let rw = &block.rws[(
-                Target::TxReceipt,
-                (tx.id as usize - 2) * (TxReceiptFieldTag::COUNT + 1) + 2,
-            )];
-            rw.receipt_value()
+            block.receipts[tx.id as usize - 2].cumulative_gas_used
         };
         self.current_cumulative_gas_used.assign(
             region,
             offset,
             Value::known(F::from(current_cumulative_gas_used)),
         )?;
+        self.withdrawal_offset.assign(
+            region,
+            offset,
+            Value::known(F::from(block.tx_context(tx).withdrawal_offset)),
+        )?;
 
         Ok(())
     }
@@ -194,6 +263,7 @@ impl<F: Field> EndTxHelperGadget<F> {
 /// Gadget for reading the tx data
 #[derive(Clone, Debug)]
 pub(crate) struct TxDataGadget<F> {
+    pub(crate) block_number: Cell<F>,
     pub(crate) nonce: Cell<F>,
     pub(crate) caller_address: WordCell<F>,
     pub(crate) callee_address: WordCell<F>,
@@ -203,9 +273,15 @@ pub(crate) struct TxDataGadget<F> {
     pub(crate) call_data_gas_cost: Cell<F>,
     pub(crate) gas_price: Word32Cell<F>,
     pub(crate) value: Word32Cell<F>,
+    pub(crate) blob_versioned_hashes_len: Cell<F>,
+    pub(crate) blob_base_fee: Word32Cell<F>,
 
     pub(crate) mul_gas_fee_by_gas: MulWordByU64Gadget<F>,
+    pub(crate) mul_blob_fee_by_blob_gas: MulWordByU64Gadget<F>,
+    pub(crate) gas_fee_plus_blob_fee: AddWordsGadget<F, 2, true>,
     pub(crate) call_data_word_length: ConstantDivisionGadget<F, N_BYTES_U64>,
+    pub(crate) hardfork: HardforkGadget<F>,
+    pub(crate) init_code_gas_cost: Cell<F>,
 
     pub(crate) gas_mul_gas_price_plus_value: Option<AddWordsGadget<F, 2, false>>,
     pub(crate) cost_sum: Option<Word32Cell<F>>,
@@ -217,7 +293,8 @@ impl<F: Field> TxDataGadget<F> {
         tx_id: Expression<F>,
         calculate_total_cost: bool,
     ) -> Self {
-        let [nonce, gas, is_create, call_data_length, call_data_gas_cost] = [
+        let [block_number, nonce, gas, is_create, call_data_length, call_data_gas_cost] = [
+            TxContextFieldTag::BlockNumber,
             TxContextFieldTag::Nonce,
             TxContextFieldTag::Gas,
             TxContextFieldTag::IsCreate,
@@ -239,14 +316,54 @@ impl<F: Field> TxDataGadget<F> {
         // Calculate transaction gas fee
         let mul_gas_fee_by_gas = MulWordByU64Gadget::construct(cb, gas_price.clone(), gas.expr());
 
+        // Calculate the blob gas fee of EIP-4844, which is paid upfront together
+        // with the gas fee and then burned.
+        let blob_versioned_hashes_len = cb.tx_context(
+            tx_id.expr(),
+            TxContextFieldTag::BlobVersionedHashesLen,
+            None,
+        );
+        let blob_base_fee = cb.query_word32();
+        cb.block_lookup(
+            BlockContextFieldTag::BlobBaseFee.expr(),
+            Some(block_number.expr()),
+            blob_base_fee.to_word(),
+        );
+        let mul_blob_fee_by_blob_gas = MulWordByU64Gadget::construct(
+            cb,
+            blob_base_fee.clone(),
+            blob_versioned_hashes_len.expr() * GAS_PER_BLOB.expr(),
+        );
+        let fee = cb.query_word32();
+        let gas_fee_plus_blob_fee = AddWordsGadget::construct(
+            cb,
+            [
+                mul_gas_fee_by_gas.product().clone(),
+                mul_blob_fee_by_blob_gas.product().clone(),
+            ],
+            fee,
+        );
+
         let call_data_word_length =
             ConstantDivisionGadget::construct(cb, call_data_length.expr() + 31.expr(), 32);
 
+        // Calculate gas cost of init code for EIP-3860, which is only charged
+        // since Shanghai.
+        let hardfork = HardforkGadget::construct(cb);
+        let init_code_gas_cost = cb.query_cell();
+        cb.require_equal(
+            "init_code_gas_cost == is_create * call_data_word_length * init_code_word_gas",
+            init_code_gas_cost.expr(),
+            is_create.expr()
+                * call_data_word_length.quotient()
+                * hardfork.select(|hardfork| hardfork.init_code_word_gas()),
+        );
+
         let (cost_sum, gas_mul_gas_price_plus_value) = if calculate_total_cost {
             let cost_sum = cb.query_word32();
             let gas_mul_gas_price_plus_value = AddWordsGadget::construct(
                 cb,
-                [mul_gas_fee_by_gas.product().clone(), value.clone()],
+                [gas_fee_plus_blob_fee.sum().clone(), value.clone()],
                 cost_sum.clone(),
             );
             (Some(cost_sum), Some(gas_mul_gas_price_plus_value))
@@ -255,6 +372,7 @@ impl<F: Field> TxDataGadget<F> {
         };
 
         Self {
+            block_number,
             nonce,
             is_create,
             gas,
@@ -262,8 +380,14 @@ impl<F: Field> TxDataGadget<F> {
             call_data_gas_cost,
             gas_price,
             value,
+            blob_versioned_hashes_len,
+            blob_base_fee,
             mul_gas_fee_by_gas,
+            mul_blob_fee_by_blob_gas,
+            gas_fee_plus_blob_fee,
             call_data_word_length,
+            hardfork,
+            init_code_gas_cost,
             caller_address,
             callee_address,
             gas_mul_gas_price_plus_value,
@@ -272,20 +396,17 @@ impl<F: Field> TxDataGadget<F> {
     }
 
     pub(crate) fn intrinsic_gas(&self) -> Expression<F> {
-        // Calculate gas cost of init code for EIP-3860.
-        let init_code_gas_cost = select::expr(
-            self.is_create.expr(),
-            self.call_data_word_length.quotient().expr()
-                * eth_types::evm_types::INIT_CODE_WORD_GAS.expr(),
-            0.expr(),
-        );
-
         select::expr(
             self.is_create.expr(),
             GasCost::CREATION_TX.expr(),
             GasCost::TX.expr(),
         ) + self.call_data_gas_cost.expr()
-            + init_code_gas_cost.expr()
+            + self.init_code_gas_cost.expr()
+    }
+
+    /// The fee paid upfront by the caller: the gas fee plus the blob gas fee.
+    pub(crate) fn fee(&self) -> Word32Cell<F> {
+        self.gas_fee_plus_blob_fee.sum().clone()
     }
 
     pub(crate) fn total_cost(&self) -> Word32Cell<F> {
@@ -300,10 +421,16 @@ impl<F: Field> TxDataGadget<F> {
         &self,
         region: &mut CachedRegion<'_, '_, F>,
         offset: usize,
+        block: &Block<F>,
         tx: &Transaction,
     ) -> Result<(), Error> {
         let gas_fee = tx.gas_price * tx.gas();
+        let blob_base_fee = block.tx_context(tx).blob_base_fee;
+        let blob_fee = tx.blob_gas_fee(blob_base_fee);
+        let fee = gas_fee + blob_fee;
 
+        self.block_number
+            .assign(region, offset, Value::known(F::from(tx.block_num)))?;
         self.nonce
             .assign(region, offset, Value::known(tx.nonce.as_u64().scalar()))?;
         self.is_create
@@ -322,6 +449,18 @@ impl<F: Field> TxDataGadget<F> {
         )?;
         self.call_data_word_length
             .assign(region, offset, tx.call_data.len() as u128 + 31)?;
+        let hardfork = block.context.hardfork;
+        self.hardfork.assign(region, offset, hardfork)?;
+        let init_code_gas_cost = if tx.is_create() {
+            (tx.call_data.len() as u64 + 31) / 32 * hardfork.init_code_word_gas()
+        } else {
+            0
+        };
+        self.init_code_gas_cost.assign(
+            region,
+            offset,
+            Value::known(F::from(init_code_gas_cost)),
+        )?;
         self.gas_price.assign_u256(region, offset, tx.gas_price)?;
         self.value.assign_u256(region, offset, tx.value)?;
         self.callee_address
@@ -329,7 +468,23 @@ impl<F: Field> TxDataGadget<F> {
         self.caller_address.assign_h160(region, offset, tx.from)?;
         self.mul_gas_fee_by_gas
             .assign(region, offset, tx.gas_price, tx.gas(), gas_fee)?;
-        let sum = gas_fee + tx.value;
+        self.blob_versioned_hashes_len.assign(
+            region,
+            offset,
+            Value::known(F::from(tx.blob_versioned_hashes.len() as u64)),
+        )?;
+        self.blob_base_fee
+            .assign_u256(region, offset, blob_base_fee)?;
+        self.mul_blob_fee_by_blob_gas.assign(
+            region,
+            offset,
+            blob_base_fee,
+            tx.blob_gas(),
+            blob_fee,
+        )?;
+        self.gas_fee_plus_blob_fee
+            .assign(region, offset, [gas_fee, blob_fee], fee)?;
+        let sum = fee + tx.value;
 
         if self.cost_sum.is_some() && self.gas_mul_gas_price_plus_value.is_some() {
             self.cost_sum
@@ -339,7 +494,7 @@ impl<F: Field> TxDataGadget<F> {
             self.gas_mul_gas_price_plus_value.as_ref().unwrap().assign(
                 region,
                 offset,
-                [gas_fee, tx.value],
+                [fee, tx.value],
                 sum,
             )?;
         }
@@ -1,7 +1,12 @@
 //! The instance definition.
 
 use bus_mapping::circuit_input_builder::Withdrawal;
-use eth_types::{geth_types::BlockConstants, BigEndianHash, Field, Keccak};
+use eth_types::{
+    evm_types::MAX_BLOBS_PER_BLOCK,
+    geth_types::{logs_bloom, BlockConstants, BlockHeader, Receipt},
+    trie::{ordered_trie_nodes, TrieNode},
+    BigEndianHash, Bytes, Field, Keccak, H64, U64,
+};
 use std::{iter, ops::Deref};
 
 use eth_types::{geth_types::Transaction, Address, ToBigEndian, Word, H256};
@@ -31,6 +36,12 @@ pub struct BlockValues {
     pub chain_id: u64,
     /// withdrawals_root
     pub withdrawals_root: Word,
+    /// blob_base_fee
+    pub blob_base_fee: Word,
+    /// hardfork
+    pub hardfork: u64,
+    /// withdrawal_offset
+    pub withdrawal_offset: u64,
     /// history_hashes
     pub history_hashes: Vec<H256>,
 }
@@ -56,6 +67,18 @@ pub struct TxValues {
     pub call_data_len: u64,
     /// call_data_gas_cost
     pub call_data_gas_cost: u64,
+    /// blob_versioned_hashes_len
+    pub blob_versioned_hashes_len: u64,
+    /// tx_type
+    pub tx_type: u64,
+    /// access_list_addresses_len
+    pub access_list_addresses_len: u64,
+    /// max_fee_per_gas
+    pub max_fee_per_gas: Word,
+    /// is_replay_protected
+    pub is_replay_protected: u64,
+    /// block_number
+    pub block_number: u64,
     /// tx_sign_hash
     pub tx_sign_hash: [u8; 32],
 }
@@ -69,6 +92,33 @@ pub struct ExtraValues {
     pub state_root: H256,
     /// prev_state_root
     pub prev_state_root: H256,
+    /// excess_blob_gas
+    pub excess_blob_gas: u64,
+    /// transactions_root
+    pub transactions_root: H256,
+    /// receipts_root
+    pub receipts_root: H256,
+}
+
+/// Header values (only committed to through the block hash)
+#[derive(Default, Debug, Clone)]
+pub struct HeaderValues {
+    /// uncles_hash
+    pub uncles_hash: H256,
+    /// difficulty, as found in the header (zero after the merge)
+    pub difficulty: Word,
+    /// gas_used
+    pub gas_used: Word,
+    /// extra_data
+    pub extra_data: Bytes,
+    /// mix_hash, which holds the PREVRANDAO after the merge
+    pub mix_hash: H256,
+    /// nonce
+    pub nonce: H64,
+    /// blob_gas_used
+    pub blob_gas_used: U64,
+    /// parent_beacon_block_root
+    pub parent_beacon_block_root: H256,
 }
 
 /// PublicData contains all the values that the PiCircuit receives as input
@@ -83,16 +133,20 @@ pub struct PublicData {
     pub transactions: Vec<Transaction>,
     /// Block Withdrawals
     pub withdrawals: Vec<Withdrawal>,
+    /// Receipts of the block transactions
+    pub receipts: Vec<Receipt>,
     /// Block State Root
     pub state_root: H256,
     /// Previous block root
     pub prev_state_root: H256,
     /// Constants related to Ethereum block
     pub block_constants: BlockConstants,
-    /// Block Hash
+    /// Block Hash, computed from the header when not given
     pub block_hash: Option<H256>,
-    /// withdrawals_root
-    pub withdrawals_root: H256,
+    /// Header fields not found in the block table
+    pub header: HeaderValues,
+    /// Number of withdrawals of the previous blocks of the chunk
+    pub withdrawal_offset: u64,
 }
 
 impl Default for PublicData {
@@ -102,11 +156,13 @@ impl Default for PublicData {
             history_hashes: vec![],
             transactions: vec![],
             withdrawals: vec![],
+            receipts: vec![],
             state_root: H256::zero(),
             prev_state_root: H256::zero(),
             block_constants: BlockConstants::default(),
             block_hash: None,
-            withdrawals_root: H256::zero(),
+            header: HeaderValues::default(),
+            withdrawal_offset: 0,
         }
     }
 }
@@ -130,7 +186,10 @@ impl PublicData {
             difficulty: self.block_constants.difficulty,
             base_fee: self.block_constants.base_fee,
             chain_id: self.chain_id.as_u64(),
-            withdrawals_root: self.withdrawals_root.as_fixed_bytes().into(),
+            withdrawals_root: self.get_withdrawals_root().as_fixed_bytes().into(),
+            blob_base_fee: self.block_constants.blob_base_fee,
+            hardfork: self.block_constants.hardfork.as_u64(),
+            withdrawal_offset: self.withdrawal_offset,
             history_hashes,
         }
     }
@@ -162,53 +221,274 @@ impl PublicData {
                         NONZERO_BYTE_GAS_COST
                     }
                 }),
+                blob_versioned_hashes_len: tx.blob_versioned_hashes.len() as u64,
+                tx_type: tx.transaction_type.as_u64(),
+                access_list_addresses_len: tx.access_list_addresses_len(),
+                max_fee_per_gas: tx.max_fee_per_gas(),
+                is_replay_protected: tx.is_replay_protected() as u64,
+                block_number: self.block_constants.number.as_u64(),
                 tx_sign_hash: msg_hash_le,
             });
         }
         tx_vals
     }
 
+    /// Returns the block header, where the parent hash is the latest of the
+    /// history hashes
+    pub fn get_block_header(&self) -> BlockHeader {
+        BlockHeader {
+            parent_hash: self
+                .history_hashes
+                .last()
+                .map(|hash| H256::from(hash.to_be_bytes()))
+                .unwrap_or_default(),
+            uncles_hash: self.header.uncles_hash,
+            beneficiary: self.block_constants.coinbase,
+            state_root: self.state_root,
+            transactions_root: self.get_transactions_root(),
+            receipts_root: self.get_receipts_root(),
+            logs_bloom: logs_bloom(&self.receipts),
+            difficulty: self.header.difficulty,
+            number: self.block_constants.number,
+            gas_limit: self.block_constants.gas_limit,
+            gas_used: self.header.gas_used,
+            timestamp: self.block_constants.timestamp,
+            extra_data: self.header.extra_data.clone(),
+            mix_hash: self.header.mix_hash,
+            nonce: self.header.nonce,
+            base_fee: self.block_constants.base_fee,
+            withdrawals_root: self.get_withdrawals_root(),
+            blob_gas_used: self.header.blob_gas_used,
+            excess_blob_gas: self.block_constants.excess_blob_gas,
+            parent_beacon_block_root: self.header.parent_beacon_block_root,
+        }
+    }
+
+    /// Returns the RLP encoding of the block header in the hardfork of the
+    /// block
+    pub fn get_block_header_rlp(&self) -> Bytes {
+        self.get_block_header().rlp(self.block_constants.hardfork)
+    }
+
     /// Returns struct with the extra values
     pub fn get_extra_values(&self) -> ExtraValues {
         ExtraValues {
-            block_hash: self.block_hash.unwrap_or_else(H256::zero),
+            block_hash: self
+                .block_hash
+                .unwrap_or_else(|| self.get_block_header().hash(self.block_constants.hardfork)),
             state_root: self.state_root,
             prev_state_root: self.prev_state_root,
+            excess_blob_gas: self.block_constants.excess_blob_gas.as_u64(),
+            transactions_root: self.get_transactions_root(),
+            receipts_root: self.get_receipts_root(),
+        }
+    }
+
+    /// Returns the nodes of the transactions trie, built from the EIP-2718
+    /// envelopes of the transactions
+    pub fn get_tx_trie_nodes(&self) -> Vec<TrieNode> {
+        let envelopes = self
+            .transactions
+            .iter()
+            .map(|tx| {
+                tx.rlp_signed(self.chain_id.as_u64())
+                    .expect("tx type is supported")
+            })
+            .collect_vec();
+        ordered_trie_nodes(&envelopes)
+    }
+
+    /// Returns the root of the transactions trie
+    pub fn get_transactions_root(&self) -> H256 {
+        self.get_tx_trie_nodes()[0].hash()
+    }
+
+    /// Returns the nodes of the receipts trie, built from the EIP-2718
+    /// envelopes of the receipts
+    pub fn get_receipt_trie_nodes(&self) -> Vec<TrieNode> {
+        let envelopes = self
+            .receipts
+            .iter()
+            .map(|receipt| receipt.rlp())
+            .collect_vec();
+        ordered_trie_nodes(&envelopes)
+    }
+
+    /// Returns the root of the receipts trie
+    pub fn get_receipts_root(&self) -> H256 {
+        self.get_receipt_trie_nodes()[0].hash()
+    }
+
+    /// Returns the nodes of the withdrawals trie, built from the RLP encodings
+    /// of the withdrawals
+    pub fn get_withdrawal_trie_nodes(&self) -> Vec<TrieNode> {
+        let encodings = self.withdrawals.iter().map(|wd| wd.rlp()).collect_vec();
+        ordered_trie_nodes(&encodings)
+    }
+
+    /// Returns the root of the withdrawals trie
+    pub fn get_withdrawals_root(&self) -> H256 {
+        self.get_withdrawal_trie_nodes()[0].hash()
+    }
+}
+
+/// ChunkPublicData contains the PublicData of every block of a chunk of
+/// consecutive blocks, which the PiCircuit commits to with a single digest
+#[derive(Debug, Clone)]
+pub struct ChunkPublicData {
+    /// Public data of the blocks of the chunk, in order
+    pub blocks: Vec<PublicData>,
+}
+
+impl Default for ChunkPublicData {
+    fn default() -> Self {
+        PublicData::default().into()
+    }
+}
+
+impl From<PublicData> for ChunkPublicData {
+    fn from(public_data: PublicData) -> Self {
+        ChunkPublicData {
+            blocks: vec![public_data],
         }
     }
+}
+
+impl ChunkPublicData {
+    /// Returns the transactions of all the blocks of the chunk, in order
+    pub fn transactions(&self) -> impl Iterator<Item = &Transaction> {
+        self.blocks
+            .iter()
+            .flat_map(|block| block.transactions.iter())
+    }
+
+    /// Returns the receipts of all the blocks of the chunk, in order
+    pub fn receipts(&self) -> Vec<Receipt> {
+        self.blocks
+            .iter()
+            .flat_map(|block| block.receipts.iter().cloned())
+            .collect()
+    }
+
+    /// Returns the withdrawals of all the blocks of the chunk, in order
+    pub fn withdrawals(&self) -> Vec<Withdrawal> {
+        self.blocks
+            .iter()
+            .flat_map(|block| block.withdrawals.iter().copied())
+            .collect()
+    }
+
+    /// Returns the block table values and the extra values of the blocks of
+    /// the chunk, padded to `max_blocks`.  A padding block repeats the block
+    /// hash and the state root of the block before it, so that the previous
+    /// state root and the parent hash of every block are chained to the
+    /// block before it.
+    pub fn get_block_values(&self, max_blocks: usize) -> Vec<(BlockValues, ExtraValues)> {
+        assert!(
+            self.blocks.len() <= max_blocks,
+            "blocks.len() <= max_blocks: blocks.len()={}",
+            self.blocks.len()
+        );
+        let mut values = self
+            .blocks
+            .iter()
+            .map(|block| (block.get_block_table_values(), block.get_extra_values()))
+            .collect_vec();
+        while values.len() < max_blocks {
+            let last = values
+                .last()
+                .map(|(_, extra)| extra.clone())
+                .unwrap_or_default();
+            let mut history_hashes = vec![H256::zero(); 256];
+            history_hashes[255] = last.block_hash;
+            values.push((
+                BlockValues {
+                    history_hashes,
+                    ..Default::default()
+                },
+                ExtraValues {
+                    block_hash: last.block_hash,
+                    state_root: last.state_root,
+                    prev_state_root: last.state_root,
+                    ..Default::default()
+                },
+            ));
+        }
+        values
+    }
+
+    /// Returns struct with values for the tx table of the chunk
+    pub fn get_tx_table_values(&self) -> Vec<TxValues> {
+        self.blocks
+            .iter()
+            .flat_map(|block| block.get_tx_table_values())
+            .collect()
+    }
+
+    /// Returns the (tx_id, index, versioned_hash) of every blob carried by
+    /// the chunk transactions, in the order they appear in the tx table
+    pub fn get_blob_versioned_hashes(&self) -> Vec<(u64, u64, H256)> {
+        let blob_hashes = self
+            .transactions()
+            .enumerate()
+            .flat_map(|(i, tx)| {
+                tx.blob_versioned_hashes
+                    .iter()
+                    .enumerate()
+                    .map(move |(index, hash)| (i as u64 + 1, index as u64, *hash))
+            })
+            .collect_vec();
+        assert!(
+            blob_hashes.len() <= MAX_BLOBS_PER_BLOCK,
+            "blob_hashes.len() <= MAX_BLOBS_PER_BLOCK: blob_hashes.len()={}",
+            blob_hashes.len()
+        );
+        blob_hashes
+    }
 
     /// get the serialized public data bytes
     pub fn get_pi_bytes(
         &self,
+        max_blocks: usize,
         max_txs: usize,
         max_withdrawals: usize,
         max_calldata: usize,
     ) -> Vec<u8> {
-        // Assign block table
-        let block_values = self.get_block_table_values();
+        // Assign block table and extra fields of every block
+        let block_bytes_fn = |(block_values, extra_vals): (BlockValues, ExtraValues)| {
+            iter::empty()
+                .chain(block_values.coinbase.to_fixed_bytes()) // coinbase
+                .chain(block_values.gas_limit.to_be_bytes()) // gas_limit
+                .chain(block_values.number.to_be_bytes()) // number
+                .chain(block_values.timestamp.to_be_bytes()) // timestamp
+                .chain(block_values.difficulty.to_be_bytes()) // difficulty
+                .chain(block_values.base_fee.to_be_bytes()) // base_fee
+                .chain(block_values.chain_id.to_be_bytes()) // chain_id
+                .chain(block_values.withdrawals_root.to_be_bytes()) // withdrawals root
+                .chain(block_values.blob_base_fee.to_be_bytes()) // blob base fee
+                .chain(block_values.hardfork.to_be_bytes()) // hardfork
+                .chain(block_values.withdrawal_offset.to_be_bytes()) // withdrawal offset
+                .chain(
+                    block_values
+                        .history_hashes
+                        .iter()
+                        .flat_map(|prev_hash| prev_hash.to_fixed_bytes()),
+                ) // history_hashes
+                .chain(extra_vals.block_hash.to_fixed_bytes()) // block hash
+                .chain(extra_vals.state_root.to_fixed_bytes()) // block state root
+                .chain(extra_vals.prev_state_root.to_fixed_bytes()) // previous block state root
+                .chain(extra_vals.excess_blob_gas.to_be_bytes()) // excess blob gas
+                .chain(extra_vals.transactions_root.to_fixed_bytes()) // transactions root
+                .chain(extra_vals.receipts_root.to_fixed_bytes()) // receipts root
+                .collect_vec()
+        };
         let result = iter::empty()
             .chain(0u8.to_be_bytes()) // zero byte
-            .chain(block_values.coinbase.to_fixed_bytes()) // coinbase
-            .chain(block_values.gas_limit.to_be_bytes()) // gas_limit
-            .chain(block_values.number.to_be_bytes()) // number
-            .chain(block_values.timestamp.to_be_bytes()) // timestamp
-            .chain(block_values.difficulty.to_be_bytes()) // difficulty
-            .chain(block_values.base_fee.to_be_bytes()) // base_fee
-            .chain(block_values.chain_id.to_be_bytes()) // chain_id
-            .chain(block_values.withdrawals_root.to_be_bytes()) // withdrawals root
             .chain(
-                block_values
-                    .history_hashes
-                    .iter()
-                    .flat_map(|prev_hash| prev_hash.to_fixed_bytes()),
-            ); // history_hashes
-
-        // Assign extra fields
-        let extra_vals = self.get_extra_values();
-        let result = result
-            .chain(extra_vals.block_hash.to_fixed_bytes()) // block hash
-            .chain(extra_vals.state_root.to_fixed_bytes()) // block state root
-            .chain(extra_vals.prev_state_root.to_fixed_bytes()); // previous block state root
+                self.get_block_values(max_blocks)
+                    .into_iter()
+                    .flat_map(block_bytes_fn),
+            );
 
         // Assign Tx table
         let tx_field_byte_fn = |tx_id: u64, index: u64, value_bytes: &[u8]| {
@@ -228,6 +508,12 @@ impl PublicData {
                 tx.value.to_be_bytes().to_vec(),                     // value
                 tx.call_data_len.to_be_bytes().to_vec(),             // call_data_len
                 tx.call_data_gas_cost.to_be_bytes().to_vec(),        // call_data_gas_cost
+                tx.blob_versioned_hashes_len.to_be_bytes().to_vec(), // blob_versioned_hashes_len
+                tx.tx_type.to_be_bytes().to_vec(),                   // tx_type
+                tx.access_list_addresses_len.to_be_bytes().to_vec(), // access_list_addresses_len
+                tx.max_fee_per_gas.to_be_bytes().to_vec(),           // max_fee_per_gas
+                tx.is_replay_protected.to_be_bytes().to_vec(),       // is_replay_protected
+                tx.block_number.to_be_bytes().to_vec(),              // block_number
                 tx.tx_sign_hash.iter().rev().copied().collect_vec(), // tx sign hash
             ]
             .iter()
@@ -253,10 +539,19 @@ impl PublicData {
             .chain(tx_field_byte_fn(0, 0, &[0u8; 1])) // empty row
             .chain(all_tx_bytes);
 
+        // Tx Table BlobVersionedHash, padded to MAX_BLOBS_PER_BLOCK
+        let blob_hashes = self.get_blob_versioned_hashes();
+        let blob_hash_padding =
+            (0..MAX_BLOBS_PER_BLOCK - blob_hashes.len()).map(|_| (0, 0, H256::zero()));
+        let all_blob_hash_bytes = iter::empty()
+            .chain(blob_hashes)
+            .chain(blob_hash_padding)
+            .flat_map(|(tx_id, index, hash)| tx_field_byte_fn(tx_id, index, hash.as_fixed_bytes()));
+        let result = result.chain(all_blob_hash_bytes);
+
         // Tx Table CallData
         let all_calldata = self
-            .transactions
-            .iter()
+            .transactions()
             .flat_map(|tx| tx.call_data.0.as_ref().iter().copied())
             .collect_vec();
         let calldata_count = all_calldata.len();
@@ -275,9 +570,11 @@ impl PublicData {
                 .chain(wd.amount.to_be_bytes()) // amount
         };
         let wd_defaults = Withdrawal::default();
+        let withdrawals = self.withdrawals();
+        let wds_len = withdrawals.len();
         let all_wd_bytes = iter::empty()
-            .chain(self.withdrawals.clone())
-            .chain((0..(max_withdrawals - self.withdrawals.len())).map(|_| wd_defaults))
+            .chain(withdrawals)
+            .chain((0..(max_withdrawals - wds_len)).map(|_| wd_defaults))
             .flat_map(wd_bytes_fn);
 
         result.chain(all_wd_bytes).collect_vec()
@@ -286,35 +583,69 @@ impl PublicData {
     /// generate public data from validator perspective
     pub fn get_rpi_digest_word<F: Field>(
         &self,
+        max_blocks: usize,
         max_txs: usize,
         max_withdrawals: usize,
         max_calldata: usize,
     ) -> word::Word<F> {
         let mut keccak = Keccak::default();
-        keccak.update(&self.get_pi_bytes(max_txs, max_withdrawals, max_calldata));
+        keccak.update(&self.get_pi_bytes(max_blocks, max_txs, max_withdrawals, max_calldata));
         let digest = keccak.digest();
         word::Word::from(Word::from_big_endian(&digest))
     }
 }
 
-/// convert witness block to public data
-pub fn public_data_convert<F: Field>(block: &Block<F>) -> PublicData {
-    PublicData {
-        chain_id: block.context.chain_id,
-        history_hashes: block.context.history_hashes.clone(),
-        transactions: block.txs.iter().map(|tx| tx.deref().clone()).collect_vec(),
-        withdrawals: block.withdrawals(),
-        state_root: block.eth_block.state_root,
-        prev_state_root: H256::from_uint(&block.prev_state_root),
-        block_hash: block.eth_block.hash,
-        block_constants: BlockConstants {
-            coinbase: block.context.coinbase,
-            timestamp: block.context.timestamp,
-            number: block.context.number.as_u64().into(),
-            difficulty: block.context.difficulty,
-            gas_limit: block.context.gas_limit.into(),
-            base_fee: block.context.base_fee,
-        },
-        withdrawals_root: block.withdrawals_root(),
-    }
+/// convert witness block to public data, split by the blocks of the chunk
+pub fn public_data_convert<F: Field>(block: &Block<F>) -> ChunkPublicData {
+    let mut prev_state_root = H256::from_uint(&block.prev_state_root);
+    let blocks = block
+        .contexts
+        .iter()
+        .zip_eq(block.eth_blocks.iter())
+        .map(|(context, eth_block)| {
+            let header = BlockHeader::from(eth_block);
+            let (transactions, receipts) = block
+                .txs
+                .iter()
+                .zip_eq(block.receipts.iter())
+                .filter(|(tx, _)| tx.block_num == context.number.as_u64())
+                .map(|(tx, receipt)| (tx.deref().clone(), receipt.clone()))
+                .unzip();
+            let public_data = PublicData {
+                chain_id: context.chain_id,
+                history_hashes: context.history_hashes.clone(),
+                transactions,
+                withdrawals: context.withdrawals.clone(),
+                receipts,
+                state_root: eth_block.state_root,
+                prev_state_root,
+                block_hash: eth_block.hash,
+                block_constants: BlockConstants {
+                    coinbase: context.coinbase,
+                    timestamp: context.timestamp,
+                    number: context.number.as_u64().into(),
+                    difficulty: context.difficulty,
+                    gas_limit: context.gas_limit.into(),
+                    base_fee: context.base_fee,
+                    excess_blob_gas: context.excess_blob_gas.into(),
+                    blob_base_fee: context.blob_base_fee,
+                    hardfork: context.hardfork,
+                },
+                header: HeaderValues {
+                    uncles_hash: header.uncles_hash,
+                    difficulty: header.difficulty,
+                    gas_used: header.gas_used,
+                    extra_data: header.extra_data,
+                    mix_hash: header.mix_hash,
+                    nonce: header.nonce,
+                    blob_gas_used: header.blob_gas_used,
+                    parent_beacon_block_root: header.parent_beacon_block_root,
+                },
+                withdrawal_offset: context.withdrawal_offset,
+            };
+            prev_state_root = eth_block.state_root;
+            public_data
+        })
+        .collect();
+    ChunkPublicData { blocks }
 }
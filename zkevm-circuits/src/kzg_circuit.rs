@@ -0,0 +1,292 @@
+//! The KZG circuit implementation.
+//!
+//! The circuit exposes the KZG point evaluation precompile calls in the
+//! [`KzgTable`].  Every evaluation takes [`ROWS_PER_EVALUATION`] rows, one per
+//! byte of its input, which are accumulated in the `input_rlc` column of the
+//! table, enabled on the last row of the evaluation.
+//!
+//! The BLS12-381 pairing of the KZG opening can't be verified by the circuit,
+//! so that the evaluations are public inputs, in two instance columns:
+//! - the input bytes of the evaluations, one per row.
+//! - whether the evaluation is valid, on the last row of the evaluation, and zero otherwise.
+//!
+//! The verifier runs the point evaluation of EIP-4844 on every group of
+//! [`ROWS_PER_EVALUATION`] bytes of the first column, which rejects bytes
+//! greater than 255, and checks the result against the second column.  The
+//! padding evaluations are all zeros and invalid, as their versioned hash
+//! doesn't have the KZG version.
+#[cfg(any(test, feature = "test-circuits"))]
+mod dev;
+#[cfg(test)]
+mod test;
+#[cfg(feature = "test-circuits")]
+pub use dev::KzgCircuit as TestKzgCircuit;
+
+use std::marker::PhantomData;
+
+use crate::{
+    evm_circuit::util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+    table::{KzgTable, LookupTable},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use bus_mapping::{
+    circuit_input_builder::PointEvaluationEvent, precompile::N_BYTES_POINT_EVALUATION_INPUT,
+};
+use eth_types::Field;
+use gadgets::util::not;
+use halo2_proofs::{
+    circuit::{Layouter, Region, Value},
+    plonk::{Column, ConstraintSystem, Error, Expression, Fixed, Instance},
+    poly::Rotation,
+};
+
+/// Number of rows taken by a point evaluation, one per byte of its input.
+pub const ROWS_PER_EVALUATION: usize = N_BYTES_POINT_EVALUATION_INPUT;
+
+/// KzgCircuitConfig
+#[derive(Clone, Debug)]
+pub struct KzgCircuitConfig<F> {
+    q_enable: Column<Fixed>,
+    q_first: Column<Fixed>,
+    input: Column<Instance>,
+    is_valid: Column<Instance>,
+    /// The columns for other circuits to lookup the point evaluations
+    pub kzg_table: KzgTable,
+    _marker: PhantomData<F>,
+}
+
+/// Circuit configuration arguments
+pub struct KzgCircuitConfigArgs<F: Field> {
+    /// KzgTable
+    pub kzg_table: KzgTable,
+    /// Challenges randomness
+    pub challenges: Challenges<Expression<F>>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for KzgCircuitConfig<F> {
+    type ConfigArgs = KzgCircuitConfigArgs<F>;
+
+    /// Return a new KzgCircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            kzg_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let q_enable = meta.fixed_column();
+        let q_first = meta.fixed_column();
+        let q_last = kzg_table.q_enable;
+        let input = meta.instance_column();
+        let is_valid = meta.instance_column();
+
+        meta.create_gate("input rlc", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let byte = meta.query_instance(input, Rotation::cur());
+            let input_rlc = meta.query_advice(kzg_table.input_rlc, Rotation::cur());
+            let input_rlc_prev = meta.query_advice(kzg_table.input_rlc, Rotation::prev());
+            let q_first = meta.query_fixed(q_first, Rotation::cur());
+            cb.require_equal(
+                "input_rlc = input_rlc_prev * r + byte",
+                input_rlc,
+                not::expr(q_first) * input_rlc_prev * challenges.keccak_input() + byte,
+            );
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("is_valid", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_equal(
+                "is_valid is a public input",
+                meta.query_advice(kzg_table.is_valid, Rotation::cur()),
+                meta.query_instance(is_valid, Rotation::cur()),
+            );
+
+            cb.gate(meta.query_fixed(q_last, Rotation::cur()))
+        });
+
+        Self {
+            q_enable,
+            q_first,
+            input,
+            is_valid,
+            kzg_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> KzgCircuitConfig<F> {
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        events: &[PointEvaluationEvent],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "assign kzg rows",
+            |mut region| {
+                for (idx, event) in events.iter().enumerate() {
+                    self.assign_evaluation(
+                        &mut region,
+                        idx * ROWS_PER_EVALUATION,
+                        event,
+                        challenges,
+                    )?;
+                }
+                self.kzg_table.annotate_columns_in_region(&mut region);
+                self.annotate_circuit(&mut region);
+                Ok(())
+            },
+        )
+    }
+
+    fn assign_evaluation(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        event: &PointEvaluationEvent,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        let mut input_rlc = Value::known(F::ZERO);
+        for (row_idx, byte) in event.input.iter().enumerate() {
+            let offset = offset + row_idx;
+            let is_last = row_idx == ROWS_PER_EVALUATION - 1;
+            for (name, column, value) in [
+                ("q_enable", self.q_enable, true),
+                ("q_first", self.q_first, row_idx == 0),
+                ("q_last", self.kzg_table.q_enable, is_last),
+            ] {
+                region.assign_fixed(
+                    || format!("assign {} {}", name, offset),
+                    column,
+                    offset,
+                    || Value::known(F::from(value as u64)),
+                )?;
+            }
+            input_rlc = input_rlc * challenges.keccak_input() + Value::known(F::from(*byte as u64));
+            region.assign_advice(
+                || format!("assign input_rlc {}", offset),
+                self.kzg_table.input_rlc,
+                offset,
+                || input_rlc,
+            )?;
+            region.assign_advice(
+                || format!("assign is_valid {}", offset),
+                self.kzg_table.is_valid,
+                offset,
+                || Value::known(F::from((is_last && event.is_valid) as u64)),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn annotate_circuit(&self, region: &mut Region<F>) {
+        region.name_column(|| "KZG_q_enable", self.q_enable);
+        region.name_column(|| "KZG_q_first", self.q_first);
+        region.name_column(|| "KZG_input", self.input);
+        region.name_column(|| "KZG_is_valid", self.is_valid);
+    }
+}
+
+/// KzgCircuit
+#[derive(Default, Clone, Debug)]
+pub struct KzgCircuit<F: Field> {
+    events: Vec<PointEvaluationEvent>,
+    max_point_evaluations: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> KzgCircuit<F> {
+    /// Creates a new circuit instance
+    pub fn new(max_point_evaluations: usize, events: Vec<PointEvaluationEvent>) -> Self {
+        Self {
+            events,
+            max_point_evaluations,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the events to verify, padded with invalid all-zero inputs up to
+    /// `max_point_evaluations` when it's set.
+    fn padded_events(&self) -> Result<Vec<PointEvaluationEvent>, Error> {
+        let mut events = self.events.clone();
+        if self.max_point_evaluations > 0 {
+            if events.len() > self.max_point_evaluations {
+                log::error!(
+                    "Point evaluation events exceed capacity.  needed = {}, available = {}",
+                    events.len(),
+                    self.max_point_evaluations
+                );
+                return Err(Error::BoundsFailure);
+            }
+            events.resize(
+                self.max_point_evaluations,
+                PointEvaluationEvent {
+                    input: vec![0; N_BYTES_POINT_EVALUATION_INPUT],
+                    is_valid: false,
+                },
+            );
+        }
+        Ok(events)
+    }
+}
+
+impl<F: Field> SubCircuit<F> for KzgCircuit<F> {
+    type Config = KzgCircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // No column is queried at more than 2 distinct rotations, so returns 5
+        // unusable rows.
+        5
+    }
+
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        Self::new(
+            block.circuits_params.max_point_evaluations,
+            block.precompile_events.get_point_evaluation_events(),
+        )
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        (
+            block.precompile_events.get_point_evaluation_events().len() * ROWS_PER_EVALUATION,
+            block.circuits_params.max_point_evaluations * ROWS_PER_EVALUATION,
+        )
+    }
+
+    /// Returns the input bytes and the validity of the point evaluations, to
+    /// be checked natively by the verifier.
+    fn instance(&self) -> Vec<Vec<F>> {
+        // The events exceeding the capacity are rejected by `synthesize_sub`.
+        let events = self.padded_events().unwrap_or_default();
+        let input = events
+            .iter()
+            .flat_map(|event| event.input.iter().map(|byte| F::from(*byte as u64)))
+            .collect();
+        let is_valid = events
+            .iter()
+            .flat_map(|event| {
+                let mut column = vec![F::ZERO; ROWS_PER_EVALUATION];
+                column[ROWS_PER_EVALUATION - 1] = F::from(event.is_valid as u64);
+                column
+            })
+            .collect();
+        vec![input, is_valid]
+    }
+
+    /// Make the assignments to the KzgCircuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.assign(layouter, &self.padded_events()?, challenges)
+    }
+}
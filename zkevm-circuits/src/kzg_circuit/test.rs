@@ -0,0 +1,63 @@
+use super::*;
+use crate::util::unusable_rows;
+use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
+
+#[test]
+fn kzg_circuit_unusable_rows() {
+    assert_eq!(
+        KzgCircuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, KzgCircuit::<Fr>>(()),
+    )
+}
+
+// Test vector of the point evaluation precompile of go-ethereum.
+const INPUT: &str = "01e798154708fe7789429634053cbf9f99b619f9f084048927333fce637f549b564c0a11a0f704f4fc3e8acfe0f8245f0ad1347b378fbf96e206da11a5d3630624d25032e67a7e6a4910df5834b8fe70e6bcfeeac0352434196bdf4b2485d5a18f59a8d2a1a625a17f3fea0fe5eb8c896db3764f3185481bc22f91b4aaffcca25f26936857bc3a7c2539ea8ec3a952b7873033e038326e87ed3e1276fd140253fa08e9fc25fb2d9a98527fc22a2c9612fbeafdad446cbc7bcdbdcd780af2c16a";
+
+fn event(is_valid: bool) -> PointEvaluationEvent {
+    PointEvaluationEvent {
+        input: hex::decode(INPUT).unwrap(),
+        is_valid,
+    }
+}
+
+fn verify(circuit: KzgCircuit<Fr>, instance: Vec<Vec<Fr>>, success: bool) {
+    let k = 10;
+    let prover = MockProver::<Fr>::run(k, &circuit, instance).unwrap();
+    assert_eq!(prover.verify().is_ok(), success);
+}
+
+#[test]
+fn kzg_circuit_simple() {
+    let circuit = KzgCircuit::<Fr>::new(0, vec![event(true), event(false)]);
+    let instance = circuit.instance();
+    verify(circuit, instance, true);
+}
+
+#[test]
+fn kzg_circuit_padding() {
+    let circuit = KzgCircuit::<Fr>::new(3, vec![event(true)]);
+    let instance = circuit.instance();
+    assert_eq!(instance[0].len(), 3 * ROWS_PER_EVALUATION);
+    verify(circuit, instance, true);
+}
+
+#[test]
+fn kzg_circuit_wrong_is_valid() {
+    let circuit = KzgCircuit::<Fr>::new(0, vec![event(true)]);
+    let instance = KzgCircuit::<Fr>::new(0, vec![event(false)]).instance();
+    verify(circuit, instance, false);
+}
+
+#[test]
+fn kzg_circuit_wrong_input() {
+    let circuit = KzgCircuit::<Fr>::new(0, vec![event(true)]);
+    let mut instance = circuit.instance();
+    instance[0][ROWS_PER_EVALUATION - 1] += Fr::from(1);
+    verify(circuit, instance, false);
+}
+
+#[test]
+fn kzg_circuit_over_capacity() {
+    let circuit = KzgCircuit::<Fr>::new(1, vec![event(true); 2]);
+    assert!(MockProver::<Fr>::run(10, &circuit, vec![vec![], vec![]]).is_err());
+}
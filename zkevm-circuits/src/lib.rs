@@ -1,9 +1,9 @@
 //! # zk_evm
 
 // We should try not to use incomplete_features unless it is really really needed and cannot be
-// avoided like `adt_const_params` used by DummyGadget
+// avoided like `adt_const_params` used by CreateGadget
 #![allow(incomplete_features)]
-// Needed by DummyGadget in evm circuit
+// Needed by CreateGadget in evm circuit
 #![feature(adt_const_params)]
 // Required for adding reasons in allow(dead_code)
 #![feature(lint_reasons)]
@@ -17,17 +17,24 @@
 #![deny(unsafe_code)]
 #![deny(clippy::debug_assert_with_mut_call)]
 
+pub mod blake2f_circuit;
 pub mod bytecode_circuit;
 #[allow(dead_code, reason = "under active development")]
 pub mod circuit_tools;
 pub mod copy_circuit;
+pub mod ecc_circuit;
 pub mod evm_circuit;
 pub mod exp_circuit;
 pub mod keccak_circuit;
+pub mod kzg_circuit;
+pub mod modexp_circuit;
 #[allow(dead_code, reason = "under active development")]
 pub mod mpt_circuit;
 pub mod pi_circuit;
+pub mod receipt_circuit;
+pub mod ripemd160_circuit;
 pub mod root_circuit;
+pub mod sha256_circuit;
 pub mod state_circuit;
 pub mod super_circuit;
 pub mod table;
@@ -0,0 +1,675 @@
+//! The modexp circuit implementation.
+//!
+//! The circuit verifies the modular exponentiations of the MODEXP precompile
+//! calls, whose operands are at most 32 bytes.  Every exponentiation takes
+//! [`ROWS_PER_EXP`] rows computing a square-and-multiply ladder over the 256
+//! bits of the exponent, starting from its most significant bit:
+//! - on the square row of a bit, `x = y = acc`, with `acc = 1` for the first bit and the result of
+//!   the previous square or multiply row, depending on the previous bit, otherwise.
+//! - on the multiply row of a bit, `x` is the result of the square row and `y` is the base.
+//! - the last row exposes the exponentiation in the [`ModExpTable`], after checking that the final
+//!   `acc` is lower than the modulus.
+//!
+//! Each of these rows verifies `x * y = q * m + r`, as an equality of integers,
+//! with the operands decomposed in 16-bit limbs.
+#[cfg(any(test, feature = "test-circuits"))]
+mod dev;
+#[cfg(test)]
+mod test;
+#[cfg(feature = "test-circuits")]
+pub use dev::ModExpCircuit as TestModExpCircuit;
+
+use std::{array, marker::PhantomData};
+
+use crate::{
+    evm_circuit::util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+    table::{LookupTable, ModExpTable, UXTable},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use bus_mapping::circuit_input_builder::ModExpEvent;
+use eth_types::{Field, Word};
+use gadgets::util::{not, select, Expr};
+use halo2_proofs::{
+    circuit::{Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, VirtualCells},
+    poly::Rotation,
+};
+use num::{bigint::BigInt, ToPrimitive};
+
+const MAX_DEGREE: usize = 5;
+
+/// Number of bits of the exponent.
+const N_EXP_BITS: usize = 256;
+/// Number of rows taken by an exponentiation: a square and a multiply row per
+/// bit of the exponent, and a last row exposing the result.
+pub const ROWS_PER_EXP: usize = 2 * N_EXP_BITS + 1;
+
+const N_BITS_LIMB: usize = 16;
+/// Number of 16-bit limbs of a 256-bit operand.
+const N_LIMBS: usize = 16;
+/// The product is checked with 64-bit limbs, made of 4 16-bit limbs.
+const N_LIMBS_PER_U64: usize = 4;
+/// Number of 16-bit limbs of a carry, which lies in `[-2^68, 2^68)` and is
+/// stored with an offset of `2^68`.
+const N_CARRY_LIMBS: usize = 5;
+const N_CARRIES: usize = 3;
+const CARRY_OFFSET_BITS: usize = 68;
+
+/// ModExpCircuitConfig
+#[derive(Clone, Debug)]
+pub struct ModExpCircuitConfig<F> {
+    q_first: Column<Fixed>,
+    q_square: Column<Fixed>,
+    q_mul: Column<Fixed>,
+    q_hi_bit: Column<Fixed>,
+    x: [Column<Advice>; N_LIMBS],
+    y: [Column<Advice>; N_LIMBS],
+    q: [Column<Advice>; N_LIMBS],
+    r: [Column<Advice>; N_LIMBS],
+    m: [Column<Advice>; N_LIMBS],
+    carries: [[Column<Advice>; N_CARRY_LIMBS]; N_CARRIES],
+    bit: Column<Advice>,
+    exp_lo: Column<Advice>,
+    exp_hi: Column<Advice>,
+    lt_carry: Column<Advice>,
+    /// The columns for other circuits to lookup the modular exponentiations
+    pub modexp_table: ModExpTable,
+    _marker: PhantomData<F>,
+}
+
+/// Circuit configuration arguments
+pub struct ModExpCircuitConfigArgs {
+    /// ModExpTable
+    pub modexp_table: ModExpTable,
+    /// U16Table
+    pub u16_table: UXTable<16>,
+}
+
+/// Returns the value of little-endian limbs of `N_BITS_LIMB` bits.
+fn compose<F: Field>(limbs: &[Expression<F>]) -> Expression<F> {
+    limbs.iter().rev().fold(0.expr(), |acc, limb| {
+        acc * (1u64 << N_BITS_LIMB).expr() + limb.clone()
+    })
+}
+
+impl<F: Field> SubCircuitConfig<F> for ModExpCircuitConfig<F> {
+    type ConfigArgs = ModExpCircuitConfigArgs;
+
+    /// Return a new ModExpCircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            modexp_table,
+            u16_table,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let q_first = meta.fixed_column();
+        let q_square = meta.fixed_column();
+        let q_mul = meta.fixed_column();
+        let q_hi_bit = meta.fixed_column();
+        let q_end = modexp_table.q_enable;
+
+        let x = array::from_fn(|_| meta.advice_column());
+        let y = array::from_fn(|_| meta.advice_column());
+        let q = array::from_fn(|_| meta.advice_column());
+        let r = array::from_fn(|_| meta.advice_column());
+        let m = array::from_fn(|_| meta.advice_column());
+        let carries = array::from_fn(|_| array::from_fn(|_| meta.advice_column()));
+        let bit = meta.advice_column();
+        let exp_lo = meta.advice_column();
+        let exp_hi = meta.advice_column();
+        let lt_carry = meta.advice_column();
+
+        let query_limbs = |meta: &mut VirtualCells<F>, columns: &[Column<Advice>], rot: i32| {
+            columns
+                .iter()
+                .map(|column| meta.query_advice(*column, Rotation(rot)))
+                .collect::<Vec<_>>()
+        };
+        let q_enable = |meta: &mut VirtualCells<F>| {
+            meta.query_fixed(q_square, Rotation::cur())
+                + meta.query_fixed(q_mul, Rotation::cur())
+                + meta.query_fixed(q_end, Rotation::cur())
+        };
+
+        for column in x
+            .iter()
+            .chain(y.iter())
+            .chain(q.iter())
+            .chain(r.iter())
+            .chain(m.iter())
+            .chain(carries.iter().flatten())
+        {
+            meta.lookup_any("limb range check", |meta| {
+                let limb = q_enable(meta) * meta.query_advice(*column, Rotation::cur());
+                vec![limb]
+                    .into_iter()
+                    .zip(u16_table.table_exprs(meta))
+                    .collect()
+            });
+        }
+
+        // Verify `x * y = q * m + r` with 64-bit limbs, 128 bits at a time:
+        // - E0: t0 + t1 * 2^64 = c0 * 2^128
+        // - E1: t2 + t3 * 2^64 + c0 = c1 * 2^128
+        // - E2: t4 + t5 * 2^64 + c1 = c2 * 2^128
+        // - E3: t6 + c2 = 0
+        // where `tk` is the sum of the terms of weight `2^(64 * k)`.  The terms and
+        // the carries are small enough for these equations to hold on integers.
+        meta.create_gate("x * y = q * m + r", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let [x, y, q, r, m] = [&x, &y, &q, &r, &m].map(|columns| {
+                let limbs = query_limbs(meta, columns, 0);
+                limbs
+                    .chunks(N_LIMBS_PER_U64)
+                    .map(compose)
+                    .collect::<Vec<_>>()
+            });
+            let t = (0..2 * N_LIMBS_PER_U64 - 1)
+                .map(|k| {
+                    let mut t = if k < N_LIMBS_PER_U64 {
+                        0.expr() - r[k].clone()
+                    } else {
+                        0.expr()
+                    };
+                    for i in 0..N_LIMBS_PER_U64 {
+                        if k >= i && k - i < N_LIMBS_PER_U64 {
+                            t = t + x[i].clone() * y[k - i].clone()
+                                - q[i].clone() * m[k - i].clone();
+                        }
+                    }
+                    t
+                })
+                .collect::<Vec<_>>();
+            let pow_2_64 = Expression::Constant(F::from_u128(1 << 64));
+            let pow_2_128 = pow_2_64.clone() * pow_2_64.clone();
+            let carry_offset = Expression::Constant(F::from_u128(1 << CARRY_OFFSET_BITS));
+            let carries = carries
+                .map(|columns| compose(&query_limbs(meta, &columns, 0)) - carry_offset.clone());
+
+            let mut carry_in = 0.expr();
+            for (idx, carry) in carries.iter().enumerate() {
+                cb.require_equal(
+                    "t_2k + t_2k+1 * 2^64 + carry_in = carry_out * 2^128",
+                    t[2 * idx].clone() + t[2 * idx + 1].clone() * pow_2_64.clone() + carry_in,
+                    carry.clone() * pow_2_128.clone(),
+                );
+                carry_in = carry.clone();
+            }
+            cb.require_zero("t6 + c2 = 0", t[2 * N_CARRIES].clone() + carry_in);
+
+            cb.gate(
+                meta.query_fixed(q_square, Rotation::cur())
+                    + meta.query_fixed(q_mul, Rotation::cur()),
+            )
+        });
+
+        meta.create_gate("square", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_first = meta.query_fixed(q_first, Rotation::cur());
+            let x = query_limbs(meta, &x, 0);
+            let y = query_limbs(meta, &y, 0);
+            for (x, y) in x.iter().zip(y.iter()) {
+                cb.require_equal("y = x", x.clone(), y.clone());
+            }
+            cb.condition(q_first, |cb| {
+                cb.require_equal("acc = 1 for the first bit", compose(&x), 1.expr());
+            });
+            cb.gate(meta.query_fixed(q_square, Rotation::cur()))
+        });
+
+        // The accumulator after a bit is the result of the multiply row when the bit is
+        // set, and the result of the square row otherwise.
+        meta.create_gate("acc", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let bit = meta.query_advice(bit, Rotation::prev());
+            let x = query_limbs(meta, &x, 0);
+            let r_mul = query_limbs(meta, &r, -1);
+            let r_square = query_limbs(meta, &r, -2);
+            for ((x, r_mul), r_square) in x.iter().zip(r_mul.iter()).zip(r_square.iter()) {
+                cb.require_equal(
+                    "acc = bit ? r_mul : r_square",
+                    x.clone(),
+                    select::expr(bit.clone(), r_mul.clone(), r_square.clone()),
+                );
+            }
+            cb.gate(
+                meta.query_fixed(q_square, Rotation::cur())
+                    - meta.query_fixed(q_first, Rotation::cur())
+                    + meta.query_fixed(q_end, Rotation::cur()),
+            )
+        });
+
+        meta.create_gate("multiply", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let is_first_bit = meta.query_fixed(q_first, Rotation::prev());
+            let q_hi_bit = meta.query_fixed(q_hi_bit, Rotation::cur());
+            let bit = meta.query_advice(bit, Rotation::cur());
+            cb.require_boolean("bit is boolean", bit.clone());
+
+            let x = query_limbs(meta, &x, 0);
+            let r_square = query_limbs(meta, &r, -1);
+            for (x, r_square) in x.iter().zip(r_square.iter()) {
+                cb.require_equal("x = r_square", x.clone(), r_square.clone());
+            }
+            let y_prev = query_limbs(meta, &y, -2);
+            let y = query_limbs(meta, &y, 0);
+            cb.condition(not::expr(is_first_bit.clone()), |cb| {
+                for (y, y_prev) in y.iter().zip(y_prev.iter()) {
+                    cb.require_equal("y is the base", y.clone(), y_prev.clone());
+                }
+            });
+
+            // The exponent bits are accumulated in its high half, and then in its low half.
+            for (name, column, is_bit) in [
+                ("exp_hi", exp_hi, q_hi_bit.clone()),
+                ("exp_lo", exp_lo, not::expr(q_hi_bit)),
+            ] {
+                let exp = meta.query_advice(column, Rotation::cur());
+                let exp_prev =
+                    not::expr(is_first_bit.clone()) * meta.query_advice(column, Rotation(-2));
+                cb.require_equal(
+                    name,
+                    exp,
+                    select::expr(is_bit, exp_prev.clone() * 2.expr() + bit.clone(), exp_prev),
+                );
+            }
+            cb.gate(meta.query_fixed(q_mul, Rotation::cur()))
+        });
+
+        meta.create_gate("modulus", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let m_prev = query_limbs(meta, &m, -1);
+            let m = query_limbs(meta, &m, 0);
+            for (m, m_prev) in m.iter().zip(m_prev.iter()) {
+                cb.require_equal("m is the same on all rows", m.clone(), m_prev.clone());
+            }
+            cb.gate(q_enable(meta) - meta.query_fixed(q_first, Rotation::cur()))
+        });
+
+        // The result is reduced, `x + 1 + d = m` with `d` in the `q` columns.
+        meta.create_gate("end", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let x = query_limbs(meta, &x, 0);
+            let d = query_limbs(meta, &q, 0);
+            let m = query_limbs(meta, &m, 0);
+            let base = query_limbs(meta, &y, -1);
+            let lt_carry = meta.query_advice(lt_carry, Rotation::cur());
+            let pow_2_128 = Expression::Constant(F::from_u128(1 << 64))
+                * Expression::Constant(F::from_u128(1 << 64));
+            let half = N_LIMBS / 2;
+
+            cb.require_boolean("lt_carry is boolean", lt_carry.clone());
+            cb.require_equal(
+                "x_lo + 1 + d_lo = m_lo + lt_carry * 2^128",
+                compose(&x[..half]) + 1.expr() + compose(&d[..half]),
+                compose(&m[..half]) + lt_carry.clone() * pow_2_128,
+            );
+            cb.require_equal(
+                "x_hi + d_hi + lt_carry = m_hi",
+                compose(&x[half..]) + compose(&d[half..]) + lt_carry,
+                compose(&m[half..]),
+            );
+
+            for (name, table_column, value) in [
+                ("base lo", modexp_table.base.lo(), compose(&base[..half])),
+                ("base hi", modexp_table.base.hi(), compose(&base[half..])),
+                (
+                    "exponent lo",
+                    modexp_table.exponent.lo(),
+                    meta.query_advice(exp_lo, Rotation::prev()),
+                ),
+                (
+                    "exponent hi",
+                    modexp_table.exponent.hi(),
+                    meta.query_advice(exp_hi, Rotation::prev()),
+                ),
+                ("modulus lo", modexp_table.modulus.lo(), compose(&m[..half])),
+                ("modulus hi", modexp_table.modulus.hi(), compose(&m[half..])),
+                ("result lo", modexp_table.result.lo(), compose(&x[..half])),
+                ("result hi", modexp_table.result.hi(), compose(&x[half..])),
+            ] {
+                cb.require_equal(
+                    name,
+                    meta.query_advice(table_column, Rotation::cur()),
+                    value,
+                );
+            }
+            cb.gate(meta.query_fixed(q_end, Rotation::cur()))
+        });
+
+        Self {
+            q_first,
+            q_square,
+            q_mul,
+            q_hi_bit,
+            x,
+            y,
+            q,
+            r,
+            m,
+            carries,
+            bit,
+            exp_lo,
+            exp_hi,
+            lt_carry,
+            modexp_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// The witness of a row of the modexp circuit.
+#[derive(Clone, Debug, Default)]
+struct ModExpRow {
+    x: Word,
+    y: Word,
+    q: Word,
+    r: Word,
+    m: Word,
+    carries: [u128; N_CARRIES],
+    bit: bool,
+    exp_lo: u128,
+    exp_hi: u128,
+    lt_carry: bool,
+}
+
+impl ModExpRow {
+    /// Returns the row computing `x * y` modulo `m`.
+    fn modmul(x: Word, y: Word, m: Word) -> Self {
+        // `x` is either 1 or reduced, so that the quotient fits in 256 bits.
+        let (q, r) = x.full_mul(y).div_mod(m.into());
+        let (q, r) = (
+            Word::try_from(q).expect("quotient fits in 256 bits"),
+            Word::try_from(r).expect("remainder is lower than the modulus"),
+        );
+
+        let limbs = |value: &Word| value.0.map(BigInt::from);
+        let [x_limbs, y_limbs, q_limbs, m_limbs, r_limbs] = [&x, &y, &q, &m, &r].map(limbs);
+        let t = |k: usize| {
+            let mut t = if k < N_LIMBS_PER_U64 {
+                -r_limbs[k].clone()
+            } else {
+                BigInt::from(0)
+            };
+            for i in 0..N_LIMBS_PER_U64 {
+                if k >= i && k - i < N_LIMBS_PER_U64 {
+                    t += &x_limbs[i] * &y_limbs[k - i] - &q_limbs[i] * &m_limbs[k - i];
+                }
+            }
+            t
+        };
+        let mut carry = BigInt::from(0);
+        let carries = array::from_fn(|idx| {
+            carry = (t(2 * idx) + (t(2 * idx + 1) << 64) + &carry) >> 128;
+            (&carry + (BigInt::from(1) << CARRY_OFFSET_BITS))
+                .to_u128()
+                .expect("carry is in range")
+        });
+
+        Self {
+            x,
+            y,
+            q,
+            r,
+            m,
+            carries,
+            ..Default::default()
+        }
+    }
+}
+
+/// Returns the rows verifying a modular exponentiation.
+fn modexp_rows(event: &ModExpEvent) -> Vec<ModExpRow> {
+    let mut rows = Vec::with_capacity(ROWS_PER_EXP);
+    let (mut exp_lo, mut exp_hi) = (0u128, 0u128);
+    let mut acc = Word::one();
+    for idx in 0..N_EXP_BITS {
+        let bit = event.exponent.bit(N_EXP_BITS - 1 - idx);
+        if idx < N_EXP_BITS / 2 {
+            exp_hi = exp_hi * 2 + bit as u128;
+        } else {
+            exp_lo = exp_lo * 2 + bit as u128;
+        }
+
+        let square = ModExpRow::modmul(acc, acc, event.modulus);
+        let mul = ModExpRow {
+            bit,
+            exp_lo,
+            exp_hi,
+            ..ModExpRow::modmul(square.r, event.base, event.modulus)
+        };
+        acc = if bit { mul.r } else { square.r };
+        rows.push(square);
+        rows.push(mul);
+    }
+
+    let d = event.modulus - acc - Word::one();
+    let lo_mask = Word::from(u128::MAX);
+    rows.push(ModExpRow {
+        x: acc,
+        q: d,
+        m: event.modulus,
+        lt_carry: ((acc & lo_mask) + (d & lo_mask) + Word::one()) >> 128 == Word::one(),
+        ..Default::default()
+    });
+    rows
+}
+
+impl<F: Field> ModExpCircuitConfig<F> {
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        events: &[ModExpEvent],
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "assign modexp rows",
+            |mut region| {
+                for (idx, event) in events.iter().enumerate() {
+                    for (row_idx, row) in modexp_rows(event).iter().enumerate() {
+                        self.set_row(&mut region, idx * ROWS_PER_EXP + row_idx, row_idx, row)?;
+                    }
+                    self.set_table_row(&mut region, (idx + 1) * ROWS_PER_EXP - 1, event)?;
+                }
+                self.modexp_table.annotate_columns_in_region(&mut region);
+                self.annotate_circuit(&mut region);
+                Ok(())
+            },
+        )
+    }
+
+    fn set_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        row_idx: usize,
+        row: &ModExpRow,
+    ) -> Result<(), Error> {
+        let is_end = row_idx == ROWS_PER_EXP - 1;
+        let is_mul = row_idx % 2 == 1;
+
+        // Fixed selectors
+        for (name, column, value) in [
+            ("q_first", self.q_first, row_idx == 0),
+            ("q_square", self.q_square, !is_end && !is_mul),
+            ("q_mul", self.q_mul, is_mul),
+            ("q_hi_bit", self.q_hi_bit, is_mul && row_idx < N_EXP_BITS),
+            ("q_end", self.modexp_table.q_enable, is_end),
+        ] {
+            region.assign_fixed(
+                || format!("assign {} {}", name, offset),
+                column,
+                offset,
+                || Value::known(F::from(value as u64)),
+            )?;
+        }
+
+        // Operands
+        for (name, columns, value) in [
+            ("x", &self.x, row.x),
+            ("y", &self.y, row.y),
+            ("q", &self.q, row.q),
+            ("r", &self.r, row.r),
+            ("m", &self.m, row.m),
+        ] {
+            for (idx, column) in columns.iter().enumerate() {
+                let limb = (value >> (idx * N_BITS_LIMB)).low_u64() & 0xffff;
+                region.assign_advice(
+                    || format!("assign {} limb {} {}", name, idx, offset),
+                    *column,
+                    offset,
+                    || Value::known(F::from(limb)),
+                )?;
+            }
+        }
+        for (columns, carry) in self.carries.iter().zip(row.carries) {
+            for (idx, column) in columns.iter().enumerate() {
+                let limb = (carry >> (idx * N_BITS_LIMB)) as u64 & 0xffff;
+                region.assign_advice(
+                    || format!("assign carry limb {} {}", idx, offset),
+                    *column,
+                    offset,
+                    || Value::known(F::from(limb)),
+                )?;
+            }
+        }
+
+        // Exponent
+        for (name, column, value) in [
+            ("bit", self.bit, F::from(row.bit as u64)),
+            ("exp_lo", self.exp_lo, F::from_u128(row.exp_lo)),
+            ("exp_hi", self.exp_hi, F::from_u128(row.exp_hi)),
+            ("lt_carry", self.lt_carry, F::from(row.lt_carry as u64)),
+        ] {
+            region.assign_advice(
+                || format!("assign {} {}", name, offset),
+                column,
+                offset,
+                || Value::known(value),
+            )?;
+        }
+
+        if !is_end {
+            for column in <ModExpTable as LookupTable<F>>::advice_columns(&self.modexp_table) {
+                region.assign_advice(
+                    || format!("assign modexp table {}", offset),
+                    column,
+                    offset,
+                    || Value::known(F::ZERO),
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn set_table_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        event: &ModExpEvent,
+    ) -> Result<(), Error> {
+        for (column, value) in <ModExpTable as LookupTable<F>>::advice_columns(&self.modexp_table)
+            .into_iter()
+            .zip(ModExpTable::assignment::<F>(event))
+        {
+            region.assign_advice(
+                || format!("assign modexp table {}", offset),
+                column,
+                offset,
+                || Value::known(value),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn annotate_circuit(&self, region: &mut Region<F>) {
+        region.name_column(|| "MODEXP_q_first", self.q_first);
+        region.name_column(|| "MODEXP_q_square", self.q_square);
+        region.name_column(|| "MODEXP_q_mul", self.q_mul);
+        region.name_column(|| "MODEXP_q_hi_bit", self.q_hi_bit);
+        region.name_column(|| "MODEXP_bit", self.bit);
+        region.name_column(|| "MODEXP_exp_lo", self.exp_lo);
+        region.name_column(|| "MODEXP_exp_hi", self.exp_hi);
+        region.name_column(|| "MODEXP_lt_carry", self.lt_carry);
+    }
+}
+
+/// ModExpCircuit
+#[derive(Default, Clone, Debug)]
+pub struct ModExpCircuit<F: Field> {
+    events: Vec<ModExpEvent>,
+    max_modexp: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> ModExpCircuit<F> {
+    /// Creates a new circuit instance
+    pub fn new(max_modexp: usize, events: Vec<ModExpEvent>) -> Self {
+        Self {
+            events,
+            max_modexp,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the events to verify, padded with `0 ^ 0 % 1` up to
+    /// `max_modexp` when it's set.
+    fn padded_events(&self) -> Result<Vec<ModExpEvent>, Error> {
+        let mut events = self.events.clone();
+        if self.max_modexp > 0 {
+            if events.len() > self.max_modexp {
+                log::error!(
+                    "ModExp events exceed capacity.  needed = {}, available = {}",
+                    events.len(),
+                    self.max_modexp
+                );
+                return Err(Error::BoundsFailure);
+            }
+            events.resize(
+                self.max_modexp,
+                ModExpEvent {
+                    modulus: Word::one(),
+                    ..Default::default()
+                },
+            );
+        }
+        Ok(events)
+    }
+}
+
+impl<F: Field> SubCircuit<F> for ModExpCircuit<F> {
+    type Config = ModExpCircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // No column is queried at more than 3 distinct rotations, so returns 6
+        // unusable rows.
+        6
+    }
+
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        Self::new(
+            block.circuits_params.max_modexp,
+            block.precompile_events.get_modexp_events(),
+        )
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        (
+            block.precompile_events.get_modexp_events().len() * ROWS_PER_EXP,
+            block.circuits_params.max_modexp * ROWS_PER_EXP,
+        )
+    }
+
+    /// Make the assignments to the ModExpCircuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        _challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.assign(layouter, &self.padded_events()?)
+    }
+}
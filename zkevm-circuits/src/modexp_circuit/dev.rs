@@ -0,0 +1,47 @@
+pub use super::ModExpCircuit;
+
+use crate::{
+    modexp_circuit::{ModExpCircuitConfig, ModExpCircuitConfigArgs},
+    table::{ModExpTable, UXTable},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+};
+use eth_types::Field;
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+impl<F: Field> Circuit<F> for ModExpCircuit<F> {
+    type Config = (ModExpCircuitConfig<F>, UXTable<16>, Challenges);
+    type FloorPlanner = SimpleFloorPlanner;
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let modexp_table = ModExpTable::construct(meta);
+        let u16_table = UXTable::construct(meta);
+        let challenges = Challenges::construct(meta);
+
+        let config = ModExpCircuitConfig::new(
+            meta,
+            ModExpCircuitConfigArgs {
+                modexp_table,
+                u16_table,
+            },
+        );
+        (config, u16_table, challenges)
+    }
+
+    fn synthesize(
+        &self,
+        (config, u16_table, challenges): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let challenges = challenges.values(&mut layouter);
+        u16_table.load(&mut layouter)?;
+        self.synthesize_sub(&config, &challenges, &mut layouter)
+    }
+}
@@ -0,0 +1,83 @@
+use super::*;
+use crate::util::unusable_rows;
+use eth_types::word;
+use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
+
+#[test]
+fn modexp_circuit_unusable_rows() {
+    assert_eq!(
+        ModExpCircuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, ModExpCircuit::<Fr>>(()),
+    )
+}
+
+fn modexp(base: Word, exponent: Word, modulus: Word) -> Word {
+    let modmul = |a: Word, b: Word| Word::try_from(a.full_mul(b) % modulus).unwrap();
+    let mut result = Word::one() % modulus;
+    for idx in (0..256).rev() {
+        result = modmul(result, result);
+        if exponent.bit(idx) {
+            result = modmul(result, base);
+        }
+    }
+    result
+}
+
+fn event(base: Word, exponent: Word, modulus: Word) -> ModExpEvent {
+    ModExpEvent {
+        base,
+        exponent,
+        modulus,
+        result: modexp(base, exponent, modulus),
+    }
+}
+
+fn verify(max_modexp: usize, events: Vec<ModExpEvent>, success: bool) {
+    // The u16 table takes 2^16 rows.
+    let k = 17;
+    let circuit = ModExpCircuit::<Fr>::new(max_modexp, events);
+    let prover = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
+    assert_eq!(prover.verify().is_ok(), success);
+}
+
+#[test]
+fn modexp_circuit_simple() {
+    verify(
+        0,
+        vec![
+            event(3.into(), 5.into(), 7.into()),
+            event(0.into(), 0.into(), 1.into()),
+            event(2.into(), 0.into(), 0x10.into()),
+            event(
+                word!("0x8000000000000000000000000000000000000000000000000000000000003039"),
+                Word::MAX,
+                word!("0xfffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f"),
+            ),
+            event(
+                word!("0x1234"),
+                word!("0xffffffffffffffffffffffffffffffff"),
+                Word::MAX,
+            ),
+        ],
+        true,
+    );
+}
+
+#[test]
+fn modexp_circuit_padding() {
+    verify(4, vec![event(3.into(), 5.into(), 7.into())], true);
+}
+
+#[test]
+fn modexp_circuit_wrong_result() {
+    let mut event = event(3.into(), 5.into(), 7.into());
+    event.result = 12.into();
+    verify(0, vec![event], false);
+}
+
+#[test]
+fn modexp_circuit_over_capacity() {
+    let events = vec![event(3.into(), 5.into(), 7.into()); 2];
+    let circuit = ModExpCircuit::<Fr>::new(1, events);
+    assert!(MockProver::<Fr>::run(17, &circuit, vec![]).is_err());
+}
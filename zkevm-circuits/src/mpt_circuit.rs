@@ -2,7 +2,7 @@
 use eth_types::Field;
 use gadgets::{impl_expr, util::Scalar};
 use halo2_proofs::{
-    circuit::{Layouter, SimpleFloorPlanner, Value},
+    circuit::{Layouter, Region, SimpleFloorPlanner, Value},
     plonk::{
         Advice, Circuit, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase,
         VirtualCells,
@@ -10,7 +10,7 @@ use halo2_proofs::{
     poly::Rotation,
 };
 
-use std::{convert::TryInto, env::var, marker::PhantomData};
+use std::{convert::TryInto, env::var, marker::PhantomData, slice};
 
 mod account_leaf;
 mod branch;
@@ -22,6 +22,8 @@ pub mod mod_extension;
 mod param;
 mod rlp_gadgets;
 mod start;
+/// In-memory state trie generating the MPT witness
+pub mod state_trie;
 mod storage_leaf;
 /// MPT witness row
 pub mod witness_row;
@@ -50,12 +52,17 @@ use crate::{
         storage_leaf::StorageLeafConfig,
     },
     table::{KeccakTable, MPTProofType, MptTable},
-    util::Challenges,
+    util::{log2_ceil, Challenges, SubCircuit, SubCircuitConfig},
+    witness,
 };
 
 use extension_branch::ExtensionBranchConfig;
 use param::HASH_WIDTH;
 
+/// Height of the state machine cell manager, so the number of rotations at
+/// which the state machine cell columns are queried.
+const STATE_CELL_MANAGER_HEIGHT: usize = 50;
+
 #[derive(Debug, Eq, PartialEq)]
 pub(crate) enum MPTRegion {
     Default,
@@ -209,20 +216,35 @@ pub enum FixedTableTag {
 }
 impl_expr!(FixedTableTag);
 
-impl<F: Field> MPTConfig<F> {
+/// Circuit configuration arguments
+pub struct MPTCircuitConfigArgs<F: Field> {
+    /// MptTable
+    pub mpt_table: MptTable,
+    /// KeccakTable
+    pub keccak_table: KeccakTable,
+    /// Challenges
+    pub challenges: Challenges<Expression<F>>,
+    /// MPT circuit configuration parameters
+    pub params: MPTCircuitParams,
+}
+
+impl<F: Field> SubCircuitConfig<F> for MPTConfig<F> {
+    type ConfigArgs = MPTCircuitConfigArgs<F>;
+
     /// Configure MPT Circuit
-    pub fn new(
+    fn new(
         meta: &mut ConstraintSystem<F>,
-        challenges: Challenges<Expression<F>>,
-        keccak_table: KeccakTable,
-        params: MPTCircuitParams,
+        Self::ConfigArgs {
+            mpt_table,
+            keccak_table,
+            challenges,
+            params,
+        }: Self::ConfigArgs,
     ) -> Self {
         let q_enable = meta.fixed_column();
         let q_first = meta.fixed_column();
         let q_last = meta.fixed_column();
 
-        let mpt_table = MptTable::construct(meta);
-
         let fixed_table: [Column<Fixed>; 6] = (0..6)
             .map(|_| meta.fixed_column())
             .collect::<Vec<_>>()
@@ -253,7 +275,7 @@ impl<F: Field> MPTConfig<F> {
         rlp_cm.add_columns(meta, &mut cb.base, lu(MptTableType::Fixed), 2, false, 4);
         rlp_cm.add_columns(meta, &mut cb.base, lu(MptTableType::Mult), 2, false, 2);
 
-        let mut state_cm = CellManager::new(50, 0);
+        let mut state_cm = CellManager::new(STATE_CELL_MANAGER_HEIGHT, 0);
         state_cm.add_columns(meta, &mut cb.base, MptCellType::StoragePhase1, 0, false, 20);
         state_cm.add_columns(meta, &mut cb.base, MptCellType::StoragePhase2, 1, false, 6);
         state_cm.add_columns(meta, &mut cb.base, MptCellType::StoragePhase3, 2, false, 5);
@@ -383,15 +405,41 @@ impl<F: Field> MPTConfig<F> {
             cb,
         }
     }
+}
 
-    /// Make the assignments to the MPTCircuit
+impl<F: Field> MPTConfig<F> {
+    /// Make the assignments to the MPTCircuit.  The nodes are followed by an
+    /// end node on the second to last row, and the rows in between are padded
+    /// with empty RLP items.  Returns the height of the circuit.
     pub fn assign(
         &self,
         layouter: &mut impl Layouter<F>,
         nodes: &[Node],
         challenges: &Challenges<Value<F>>,
+        n_rows: usize, // 0 means dynamically calculated from `nodes`.
     ) -> Result<usize, Error> {
-        let mut height = 0;
+        let end_node = Node::end();
+        let num_rows_end = end_node.values.len();
+        // The first row has to be the start of a node
+        let nodes = if nodes.is_empty() {
+            slice::from_ref(&end_node)
+        } else {
+            nodes
+        };
+        let num_rows_nodes: usize = nodes.iter().map(|node| node.values.len()).sum();
+
+        // Make sure the circuit is high enough for the mult table
+        let mut height = (num_rows_nodes + num_rows_end).max(2 * HASH_WIDTH + 1);
+        if n_rows > 0 {
+            assert!(
+                height <= n_rows,
+                "MPT circuit rows overflow: {} > {}",
+                height,
+                n_rows
+            );
+            height = n_rows;
+        }
+
         layouter.assign_region(
             || "MPT",
             |mut region| {
@@ -402,113 +450,47 @@ impl<F: Field> MPTConfig<F> {
 
                 let mut offset = 0;
                 for node in nodes.iter() {
-                    //println!("offset: {}", offset);
-                    let mut cached_region = CachedRegion::new(
+                    offset = self.assign_node(
                         &mut region,
+                        &mut memory,
                         keccak_r,
-                    );
-                    cached_region.annotate_columns(&self.cell_columns);
-
-                    let item_types = if node.start.is_some() {
-                        NODE_RLP_TYPES_START.to_vec()
-                    } else if node.extension_branch.is_some() {
-                        NODE_RLP_TYPES_BRANCH.to_vec()
-                    } else if node.account.is_some() {
-                        NODE_RLP_TYPES_ACCOUNT.to_vec()
-                    } else if node.storage.is_some() {
-                        NODE_RLP_TYPES_STORAGE.to_vec()
-                    } else {
-                        unreachable!()
-                    };
-
-                    // Assign bytes
-                    let mut rlp_values = Vec::new();
-                    // Decompose RLP
-                    for (idx, (bytes, item_type)) in node.values.iter().zip(item_types.iter()).enumerate() {
-                        cached_region.push_region(offset + idx, MPTRegion::RLP as usize);
-                        let rlp_value = self.rlp_item.assign(
-                            &mut cached_region,
-                            offset + idx,
-                            bytes,
-                            *item_type,
-                        )?;
-                        rlp_values.push(rlp_value);
-                        cached_region.pop_region();
-                    }
-
-                    // Assign nodes
-                    if node.start.is_some() {
-                        //println!("{}: start", offset);
-                        cached_region.push_region(offset, MPTRegion::Start as usize);
-                        assign!(cached_region, (self.state_machine.is_start, offset) => "is_start", true.scalar())?;
-                        self.state_machine.start_config.assign(
-                            &mut cached_region,
-                            self,
-                            &mut memory,
-                            offset,
-                            node,
-                            &rlp_values,
-                        )?;
-                        cached_region.pop_region();
-                    } else if node.extension_branch.is_some() {
-                        //println!("{}: branch", offset);
-                        cached_region.push_region(offset, MPTRegion::Branch as usize);
-                        assign!(cached_region, (self.state_machine.is_branch, offset) => "is_branch", true.scalar())?;
-                        self.state_machine.branch_config.assign(
-                            &mut cached_region,
-                            self,
-                            &mut memory,
-                            offset,
-                            node,
-                            &rlp_values,
-                        )?;
-                        cached_region.pop_region();
-                    } else if node.account.is_some() {
-                        //println!("{}: account", offset);
-                        cached_region.push_region(offset, MPTRegion::Account as usize);
-                        assign!(cached_region, (self.state_machine.is_account, offset) => "is_account", true.scalar())?;
-                        self.state_machine.account_config.assign(
-                            &mut cached_region,
-                            self,
-                            &mut memory,
-                            offset,
-                            node,
-                            &rlp_values,
-                        )?;
-                        cached_region.pop_region();
-                    } else if node.storage.is_some() {
-                        //println!("{}: storage", offset);
-                        cached_region.push_region(offset, MPTRegion::Storage as usize);
-                        assign!(cached_region, (self.state_machine.is_storage, offset) => "is_storage", true.scalar())?;
-                        self.state_machine.storage_config.assign(
-                            &mut cached_region,
-                            self,
-                            &mut memory,
-                            offset,
-                            node,
-                            &rlp_values,
-                        )?;
-                        cached_region.pop_region();
-                    }
-
-                    offset += node.values.len();
-
-                    memory.assign(&mut cached_region, offset)?;
-
-                    cached_region.assign_stored_expressions(&self.cb.base, challenges)?;
+                        offset,
+                        node,
+                        challenges,
+                    )?;
                 }
-                height = offset;
 
-                // Make sure the circuit is high enough for the mult table
-                while height < (2 * HASH_WIDTH + 1) {
-                    height += 1;
+                // Pad the rows up to the end node with an empty RLP item, the
+                // RLP decoding is done on all enabled rows.
+                let mut cached_region = CachedRegion::new(&mut region, keccak_r);
+                for offset in offset..height - num_rows_end {
+                    cached_region.push_region(offset, MPTRegion::RLP as usize);
+                    self.rlp_item.assign(
+                        &mut cached_region,
+                        offset,
+                        &end_node.values[StartRowType::RootS as usize],
+                        RlpItemType::Hash,
+                    )?;
+                    cached_region.pop_region();
                 }
+                cached_region.assign_stored_expressions(&self.cb.base, challenges)?;
+
+                // The last node has to start on the second to last row
+                self.assign_node(
+                    &mut region,
+                    &mut memory,
+                    keccak_r,
+                    height - num_rows_end,
+                    &end_node,
+                    challenges,
+                )?;
 
                 for offset in 0..height {
                     assignf!(region, (self.q_enable, offset) => true.scalar())?;
                     assignf!(region, (self.q_first, offset) => (offset == 0).scalar())?;
                     assignf!(region, (self.q_last, offset) => (offset == height - 2).scalar())?;
                 }
+                self.mpt_table.assign_q_enable(&mut region, height)?;
 
                 Ok(())
             },
@@ -517,6 +499,103 @@ impl<F: Field> MPTConfig<F> {
         Ok(height)
     }
 
+    /// Assign a node at `offset`, returns the offset of the next node
+    fn assign_node(
+        &self,
+        region: &mut Region<'_, F>,
+        memory: &mut MptMemory<F>,
+        keccak_r: F,
+        offset: usize,
+        node: &Node,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<usize, Error> {
+        let mut cached_region = CachedRegion::new(region, keccak_r);
+        cached_region.annotate_columns(&self.cell_columns);
+
+        let item_types = if node.start.is_some() {
+            NODE_RLP_TYPES_START.to_vec()
+        } else if node.extension_branch.is_some() {
+            NODE_RLP_TYPES_BRANCH.to_vec()
+        } else if node.account.is_some() {
+            NODE_RLP_TYPES_ACCOUNT.to_vec()
+        } else if node.storage.is_some() {
+            NODE_RLP_TYPES_STORAGE.to_vec()
+        } else {
+            unreachable!()
+        };
+
+        // Assign bytes
+        let mut rlp_values = Vec::new();
+        // Decompose RLP
+        for (idx, (bytes, item_type)) in node.values.iter().zip(item_types.iter()).enumerate() {
+            cached_region.push_region(offset + idx, MPTRegion::RLP as usize);
+            let rlp_value =
+                self.rlp_item
+                    .assign(&mut cached_region, offset + idx, bytes, *item_type)?;
+            rlp_values.push(rlp_value);
+            cached_region.pop_region();
+        }
+
+        // Assign nodes
+        if node.start.is_some() {
+            cached_region.push_region(offset, MPTRegion::Start as usize);
+            assign!(cached_region, (self.state_machine.is_start, offset) => "is_start", true.scalar())?;
+            self.state_machine.start_config.assign(
+                &mut cached_region,
+                self,
+                memory,
+                offset,
+                node,
+                &rlp_values,
+            )?;
+            cached_region.pop_region();
+        } else if node.extension_branch.is_some() {
+            cached_region.push_region(offset, MPTRegion::Branch as usize);
+            assign!(cached_region, (self.state_machine.is_branch, offset) => "is_branch", true.scalar())?;
+            self.state_machine.branch_config.assign(
+                &mut cached_region,
+                self,
+                memory,
+                offset,
+                node,
+                &rlp_values,
+            )?;
+            cached_region.pop_region();
+        } else if node.account.is_some() {
+            cached_region.push_region(offset, MPTRegion::Account as usize);
+            assign!(cached_region, (self.state_machine.is_account, offset) => "is_account", true.scalar())?;
+            self.state_machine.account_config.assign(
+                &mut cached_region,
+                self,
+                memory,
+                offset,
+                node,
+                &rlp_values,
+            )?;
+            cached_region.pop_region();
+        } else if node.storage.is_some() {
+            cached_region.push_region(offset, MPTRegion::Storage as usize);
+            assign!(cached_region, (self.state_machine.is_storage, offset) => "is_storage", true.scalar())?;
+            self.state_machine.storage_config.assign(
+                &mut cached_region,
+                self,
+                memory,
+                offset,
+                node,
+                &rlp_values,
+            )?;
+            cached_region.pop_region();
+        }
+
+        let offset = offset + node.values.len();
+
+        memory.assign(&mut cached_region, offset)?;
+
+        cached_region.assign_stored_expressions(&self.cb.base, challenges)?;
+
+        Ok(offset)
+    }
+
     /// Loads MPT fixed table
     pub fn load_fixed_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
         layouter.assign_region(
@@ -645,7 +724,7 @@ impl<F: Field> MPTConfig<F> {
 }
 
 /// MPT Circuit for proving the storage modification is valid.
-#[derive(Default)]
+#[derive(Clone, Default, Debug)]
 pub struct MPTCircuit<F: Field> {
     /// MPT nodes
     pub nodes: Vec<Node>,
@@ -656,6 +735,8 @@ pub struct MPTCircuit<F: Field> {
     /// Can be used to test artificially created tests with keys without known their known
     /// preimage. ONLY ENABLE FOR TESTS!
     pub disable_preimage_check: bool,
+    /// Pad the circuit to this number of rows, 0 means dynamically calculated
+    pub max_rows: usize,
     /// Marker
     pub _marker: PhantomData<F>,
 }
@@ -679,6 +760,83 @@ impl MPTCircuitParams {
     fn is_preimage_check_enabled(&self) -> bool {
         !self.disable_preimage_check
     }
+
+    /// Number of rows of the fixed table, following `MPTConfig::load_fixed_table`
+    fn fixed_table_len(&self) -> usize {
+        let mut len = 1 + 256 + 16;
+        for (range, out_of_range) in [(256, 1), (16, 16)] {
+            let get_range = |n: i32| if n <= 0 { out_of_range } else { range };
+            let max_length = RLP_UNIT_NUM_BYTES as i32;
+            for idx in -max_length..=max_length {
+                len += if self.is_two_byte_lookup_enabled() {
+                    get_range(idx) * get_range(idx - 1)
+                } else {
+                    // No 0 at index 1 when having to do the msb non-zero check
+                    2 * get_range(idx) - usize::from(idx == 1)
+                };
+            }
+        }
+        len + 1 + 16 + 255
+    }
+}
+
+impl<F: Field> SubCircuit<F> for MPTCircuit<F> {
+    type Config = MPTConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // The state machine cell columns are queried at
+        // `STATE_CELL_MANAGER_HEIGHT` distinct rotations, which is the most of
+        // any column.
+        (STATE_CELL_MANAGER_HEIGHT - 3) + 6
+    }
+
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        let keccak_data = block
+            .mpt_witness
+            .iter()
+            .flat_map(|node| node.keccak_data.iter().map(|data| data.to_vec()))
+            .collect();
+        Self {
+            nodes: block.mpt_witness.clone(),
+            keccak_data,
+            degree: log2_ceil(Self::min_num_rows_block(block).1) as usize,
+            disable_preimage_check: false,
+            max_rows: block.circuits_params.max_mpt_rows,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        let num_rows_end = StartRowType::Count as usize;
+        let num_rows_nodes = block
+            .mpt_witness
+            .iter()
+            .map(|node| node.values.len())
+            .sum::<usize>()
+            .max(num_rows_end);
+        // The mult table is loaded on one more row than the circuit height
+        let num_rows = (num_rows_nodes + num_rows_end).max(2 * HASH_WIDTH + 1) + 1;
+        let fixed_table_len = MPTCircuitParams::default().fixed_table_len();
+        (
+            num_rows.max(fixed_table_len),
+            (block.circuits_params.max_mpt_rows + 1)
+                .max(num_rows)
+                .max(fixed_table_len),
+        )
+    }
+
+    /// Make the assignments to the MPTCircuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let height = config.assign(layouter, &self.nodes, challenges, self.max_rows)?;
+        config.load_fixed_table(layouter)?;
+        config.load_mult_table(layouter, challenges, height)
+    }
 }
 
 impl<F: Field> Circuit<F> for MPTCircuit<F> {
@@ -701,8 +859,17 @@ impl<F: Field> Circuit<F> for MPTCircuit<F> {
         let challenges = Challenges::construct(meta);
         let challenges_expr = challenges.exprs(meta);
         let keccak_table = KeccakTable::construct(meta);
+        let mpt_table = MptTable::construct(meta);
         (
-            MPTConfig::new(meta, challenges_expr, keccak_table, params),
+            MPTConfig::new(
+                meta,
+                MPTCircuitConfigArgs {
+                    mpt_table,
+                    keccak_table,
+                    challenges: challenges_expr,
+                    params,
+                },
+            ),
             challenges,
         )
     }
@@ -717,9 +884,7 @@ impl<F: Field> Circuit<F> for MPTCircuit<F> {
         mut layouter: impl Layouter<F>,
     ) -> Result<(), Error> {
         let challenges = _challenges.values(&mut layouter);
-        let height = config.assign(&mut layouter, &self.nodes, &challenges)?;
-        config.load_fixed_table(&mut layouter)?;
-        config.load_mult_table(&mut layouter, &challenges, height)?;
+        self.synthesize_sub(&config, &challenges, &mut layouter)?;
         config
             .keccak_table
             .dev_load(&mut layouter, &self.keccak_data, &challenges)?;
@@ -755,9 +920,39 @@ pub fn load_proof(path: &str) -> Vec<Node> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::util::unusable_rows;
     use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
     use std::{fs, ops::Deref};
 
+    #[test]
+    fn mpt_circuit_unusable_rows() {
+        assert_eq!(
+            MPTCircuit::<Fr>::unusable_rows(),
+            unusable_rows::<Fr, MPTCircuit::<Fr>>(MPTCircuitParams::default()),
+        )
+    }
+
+    #[test]
+    fn test_mpt_padding() {
+        let nodes = load_proof("src/mpt_circuit/tests/AccountAfterFirstLevel.json");
+        let keccak_data = nodes
+            .iter()
+            .flat_map(|node| node.keccak_data.iter().map(|data| data.to_vec()))
+            .collect();
+        let degree = 15;
+        let circuit = MPTCircuit::<Fr> {
+            nodes,
+            keccak_data,
+            degree,
+            disable_preimage_check: false,
+            max_rows: 200,
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::<Fr>::run(degree as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
     #[test]
     fn test_mpt() {
         let path = "src/mpt_circuit/tests";
@@ -794,6 +989,7 @@ mod tests {
                     keccak_data,
                     degree,
                     disable_preimage_check,
+                    max_rows: 0,
                     _marker: PhantomData,
                 };
 
@@ -52,10 +52,12 @@ impl<F: Field> StartConfig<F> {
                     config.proof_type.expr(),
                     false.expr(),
                     0.expr(),
-                    root[true.idx()].lo().expr(),
-                    root[true.idx()].hi().expr(),
+                    // The new root is the root after the modification (C), the old root the
+                    // one before (S)
                     root[false.idx()].lo().expr(),
                     root[false.idx()].hi().expr(),
+                    root[true.idx()].lo().expr(),
+                    root[true.idx()].hi().expr(),
                 ],
             );
 
@@ -108,8 +110,8 @@ impl<F: Field> StartConfig<F> {
             start.proof_type as usize,
             false,
             0.scalar(),
-            root[true.idx()],
             root[false.idx()],
+            root[true.idx()],
         )?;
 
         for is_s in [true, false] {
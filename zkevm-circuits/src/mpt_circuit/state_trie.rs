@@ -0,0 +1,563 @@
+//! In-memory state trie, which generates the MPT witness of the updates of a
+//! block from the accounts of its pre-state, the same way the Go witness
+//! generator does from the state of a node.
+//!
+//! Only the updates of the nonce, balance and code hash of accounts which are
+//! in the trie are supported, so that the proofs before and after an update
+//! go through the same nodes.
+
+use super::{
+    param::{
+        ADDRESS_WIDTH, RLP_HASH_VALUE, RLP_LIST_LONG, RLP_LIST_SHORT, RLP_LONG, RLP_NIL, RLP_SHORT,
+        RLP_UNIT_NUM_BYTES,
+    },
+    witness_row::{
+        AccountNode, BranchNode, ExtensionBranchNode, ExtensionNode, Hex, Node, StartNode,
+    },
+};
+use crate::{table::MPTProofType, witness::MptUpdates};
+use eth_types::{
+    geth_types, keccak256,
+    trie::{empty_trie_root, hex_prefix, to_nibbles},
+    Address, ToBigEndian, Word, H256,
+};
+use ethers_core::utils::rlp::{self, Rlp, RlpStream};
+use std::collections::{BTreeMap, HashMap};
+
+/// Number of rows of an account node for a modified extension node
+const MOD_EXTENSION_ROWS: usize = 6;
+
+/// Leaf of a trie: the nibbles of its key and its value
+type Leaf = (Vec<u8>, Vec<u8>);
+
+/// Fields of an account in the state trie
+#[derive(Debug, Clone)]
+struct AccountState {
+    address: Address,
+    nonce: Word,
+    balance: Word,
+    storage_root: H256,
+    code_hash: H256,
+}
+
+impl AccountState {
+    fn rlp(&self) -> Vec<u8> {
+        let mut stream = RlpStream::new_list(4);
+        stream
+            .append(&self.nonce)
+            .append(&self.balance)
+            .append(&self.storage_root)
+            .append(&self.code_hash);
+        stream.out().to_vec()
+    }
+}
+
+/// State trie of accounts, indexed by the hash of their address
+#[derive(Debug, Clone, Default)]
+pub struct StateTrie {
+    accounts: BTreeMap<H256, AccountState>,
+}
+
+impl StateTrie {
+    /// Create the state trie holding `accounts`
+    pub fn new(accounts: &[geth_types::Account]) -> Self {
+        let accounts = accounts
+            .iter()
+            .map(|account| {
+                (
+                    H256(keccak256(account.address.as_bytes())),
+                    AccountState {
+                        address: account.address,
+                        nonce: account.nonce.as_u64().into(),
+                        balance: account.balance,
+                        storage_root: storage_root(&account.storage),
+                        code_hash: H256(keccak256(&account.code)),
+                    },
+                )
+            })
+            .collect();
+        Self { accounts }
+    }
+
+    /// Return the root of the trie
+    pub fn root(&self) -> H256 {
+        root(&self.leaves())
+    }
+
+    fn leaves(&self) -> Vec<Leaf> {
+        self.accounts
+            .iter()
+            .map(|(key, account)| (to_nibbles(key.as_bytes()), account.rlp()))
+            .collect()
+    }
+
+    /// Apply the `updates` in order and return the MPT witness proving them,
+    /// which starts from the current root of the trie.
+    pub fn prove_updates(&mut self, updates: &MptUpdates) -> Vec<Node> {
+        let mut nodes = Vec::new();
+        for update in updates.iter() {
+            let key = H256(keccak256(update.address().as_bytes()));
+            let key_nibbles = to_nibbles(key.as_bytes());
+            let root_s = self.root();
+            let (proof_s, ext_nibbles) = prove(&self.leaves(), &key_nibbles);
+
+            let account = self
+                .accounts
+                .get_mut(&key)
+                .unwrap_or_else(|| panic!("unsupported update of a missing account {update:?}"));
+            let proof_type = update.mpt_proof_type();
+            match proof_type {
+                MPTProofType::NonceChanged => account.nonce = update.new_value(),
+                MPTProofType::BalanceChanged => account.balance = update.new_value(),
+                MPTProofType::CodeHashChanged => {
+                    account.code_hash = H256(update.new_value().to_be_bytes())
+                }
+                _ => panic!("unsupported MPT update {update:?}"),
+            }
+            let root_c = self.root();
+            let (proof_c, _) = prove(&self.leaves(), &key_nibbles);
+
+            nodes.push(start_node(proof_type, root_s, root_c));
+            nodes.extend(account_proof_nodes(
+                update.address(),
+                key,
+                &proof_s,
+                &proof_c,
+                &ext_nibbles,
+            ));
+            nodes.push(Node::end());
+        }
+        nodes
+    }
+}
+
+/// Return the root of the storage trie of `storage`
+fn storage_root(storage: &HashMap<Word, Word>) -> H256 {
+    let mut leaves: Vec<Leaf> = storage
+        .iter()
+        .filter(|(_, value)| !value.is_zero())
+        .map(|(key, value)| {
+            (
+                to_nibbles(&keccak256(&key.to_be_bytes())),
+                rlp::encode(value).to_vec(),
+            )
+        })
+        .collect();
+    leaves.sort();
+    root(&leaves)
+}
+
+/// Return the root of the trie of `leaves`, which are sorted by key
+fn root(leaves: &[Leaf]) -> H256 {
+    if leaves.is_empty() {
+        empty_trie_root()
+    } else {
+        H256(keccak256(&encode_node(leaves, 0)))
+    }
+}
+
+/// Return the leaves below the child `nibble` of a branch at `depth`
+fn child_leaves(leaves: &[Leaf], depth: usize, nibble: u8) -> &[Leaf] {
+    let start = leaves.partition_point(|leaf| leaf.0[depth] < nibble);
+    let end = leaves.partition_point(|leaf| leaf.0[depth] <= nibble);
+    &leaves[start..end]
+}
+
+/// Return the number of nibbles shared by the keys of `leaves` from `depth`
+fn shared_nibbles(leaves: &[Leaf], depth: usize) -> usize {
+    (depth..leaves[0].0.len())
+        .take_while(|i| leaves.iter().all(|leaf| leaf.0[*i] == leaves[0].0[*i]))
+        .count()
+}
+
+/// Return the RLP encoding of the node holding `leaves` below `depth`
+fn encode_node(leaves: &[Leaf], depth: usize) -> Vec<u8> {
+    if let [(key, value)] = leaves {
+        let mut stream = RlpStream::new_list(2);
+        stream.append(&hex_prefix(&key[depth..], true).as_slice());
+        stream.append(&value.as_slice());
+        return stream.out().to_vec();
+    }
+
+    let shared = shared_nibbles(leaves, depth);
+    let stream = if shared > 0 {
+        let mut stream = RlpStream::new_list(2);
+        stream.append(&hex_prefix(&leaves[0].0[depth..depth + shared], false).as_slice());
+        append_child(&mut stream, encode_node(leaves, depth + shared));
+        stream
+    } else {
+        let mut stream = RlpStream::new_list(17);
+        for nibble in 0..16 {
+            let children = child_leaves(leaves, depth, nibble);
+            if children.is_empty() {
+                stream.append_empty_data();
+            } else {
+                append_child(&mut stream, encode_node(children, depth + 1));
+            }
+        }
+        stream.append_empty_data();
+        stream
+    };
+    stream.out().to_vec()
+}
+
+/// Append the reference to `child` to the RLP list of its parent: its hash
+/// when it's at least 32 bytes long, its RLP otherwise.
+fn append_child(stream: &mut RlpStream, child: Vec<u8>) {
+    if child.len() < 32 {
+        stream.append_raw(&child, 1);
+    } else {
+        stream.append(&H256(keccak256(&child)));
+    }
+}
+
+/// Return the proof of the leaf at `key`, which is the list of the nodes from
+/// the root to the leaf, embedded or not, and the nibbles of the extension
+/// nodes on the way.
+fn prove(leaves: &[Leaf], key: &[u8]) -> (Vec<Vec<u8>>, Vec<Vec<u8>>) {
+    let mut proof = Vec::new();
+    let mut ext_nibbles = Vec::new();
+    let mut leaves = leaves;
+    let mut depth = 0;
+    loop {
+        proof.push(encode_node(leaves, depth));
+        if leaves.len() == 1 {
+            assert_eq!(leaves[0].0, key, "the key is not in the trie");
+            return (proof, ext_nibbles);
+        }
+        let shared = shared_nibbles(leaves, depth);
+        if shared > 0 {
+            ext_nibbles.push(leaves[0].0[depth..depth + shared].to_vec());
+            depth += shared;
+        } else {
+            leaves = child_leaves(leaves, depth, key[depth]);
+            assert!(!leaves.is_empty(), "the key is not in the trie");
+            depth += 1;
+        }
+    }
+}
+
+/// Return `bytes` padded with zeros into a witness row
+fn row(bytes: &[u8]) -> Vec<u8> {
+    let mut row = vec![0; RLP_UNIT_NUM_BYTES];
+    row[..bytes.len()].copy_from_slice(bytes);
+    row
+}
+
+fn is_branch(node: &[u8]) -> bool {
+    Rlp::new(node)
+        .item_count()
+        .expect("a trie node is an RLP list")
+        == 17
+}
+
+/// Return the number of bytes of the RLP list prefix of a branch
+fn branch_rlp_offset(branch: &[u8]) -> usize {
+    match branch[0] {
+        248 => 2,
+        249 => 3,
+        _ => 1,
+    }
+}
+
+fn start_node(proof_type: MPTProofType, root_s: H256, root_c: H256) -> Node {
+    let root_row = |root: H256| {
+        [&[RLP_HASH_VALUE][..], root.as_bytes(), &[0]]
+            .concat()
+            .into()
+    };
+    Node {
+        start: Some(StartNode {
+            disable_preimage_check: false,
+            proof_type,
+        }),
+        values: vec![root_row(root_s), root_row(root_c)],
+        ..Default::default()
+    }
+}
+
+/// Return the nodes of the proofs of the account at `key` before and after
+/// an update, which go through the same nodes.
+fn account_proof_nodes(
+    address: Address,
+    key: H256,
+    proof_s: &[Vec<u8>],
+    proof_c: &[Vec<u8>],
+    ext_nibbles: &[Vec<u8>],
+) -> Vec<Node> {
+    assert_eq!(proof_s.len(), proof_c.len());
+    let key_nibbles = to_nibbles(key.as_bytes());
+
+    let mut nodes = Vec::new();
+    let mut key_index = 0;
+    let mut ext_index = 0;
+    let mut is_extension = false;
+    // The extension rows are carried to the branches after the extension.
+    let mut ext_list_rlp_bytes = row(&[]);
+    let mut ext_values = vec![row(&[]); 4];
+    for (i, (node_s, node_c)) in proof_s.iter().zip(proof_c.iter()).enumerate() {
+        if !is_branch(node_s) {
+            if i != proof_s.len() - 1 {
+                let (num_nibbles, list_rlp_bytes, values) =
+                    extension_rows(&ext_nibbles[ext_index], node_s, node_c);
+                ext_list_rlp_bytes = list_rlp_bytes;
+                ext_values = values;
+                is_extension = true;
+                key_index += num_nibbles;
+                ext_index += 1;
+                continue;
+            }
+            nodes.push(account_leaf_node(
+                address,
+                key,
+                node_s,
+                node_c,
+                &key_nibbles,
+            ));
+        } else {
+            let mut keccak_data = vec![node_s.clone(), node_c.clone()];
+            if is_extension {
+                keccak_data.extend([proof_s[i - 1].clone(), proof_c[i - 1].clone()]);
+            }
+            nodes.push(branch_node(
+                keccak_data,
+                is_extension,
+                &ext_list_rlp_bytes,
+                &ext_values,
+                key_nibbles[key_index] as usize,
+            ));
+            key_index += 1;
+            is_extension = false;
+        }
+    }
+    nodes
+}
+
+/// Return the rows of the 16 children of a branch, after an empty row for the
+/// modified child.
+fn branch_rows(branch: &[u8]) -> Vec<Vec<u8>> {
+    let mut offset = branch_rlp_offset(branch);
+    let mut rows = vec![row(&[])];
+    for _ in 0..16 {
+        let len = match branch[offset] {
+            RLP_NIL => 1,
+            RLP_HASH_VALUE => 33,
+            prefix => (prefix - RLP_LIST_SHORT) as usize + 1,
+        };
+        rows.push(row(&branch[offset..offset + len]));
+        offset += len;
+    }
+    rows
+}
+
+/// Return the node of a branch before and after an update, preceded by an
+/// extension when `keccak_data` holds the extension nodes after the
+/// branches.
+fn branch_node(
+    keccak_data: Vec<Vec<u8>>,
+    is_extension: bool,
+    ext_list_rlp_bytes: &[u8],
+    ext_values: &[Vec<u8>],
+    modified_index: usize,
+) -> Node {
+    let (branch_s, branch_c) = (&keccak_data[0], &keccak_data[1]);
+    let list_rlp_bytes =
+        [branch_s, branch_c].map(|branch| branch[..branch_rlp_offset(branch)].to_vec().into());
+
+    let mut values = branch_rows(branch_s);
+    values[0] = branch_rows(branch_c)[1 + modified_index].clone();
+    values.extend(ext_values.iter().cloned());
+
+    Node {
+        extension_branch: Some(ExtensionBranchNode {
+            is_extension,
+            is_mod_extension: [false, false],
+            is_placeholder: [false, false],
+            extension: ExtensionNode {
+                list_rlp_bytes: ext_list_rlp_bytes.to_vec().into(),
+            },
+            branch: BranchNode {
+                modified_index,
+                drifted_index: modified_index,
+                list_rlp_bytes,
+            },
+        }),
+        values: values.into_iter().map(Hex::from).collect(),
+        keccak_data: keccak_data.into_iter().map(Hex::from).collect(),
+        ..Default::default()
+    }
+}
+
+/// Return the number of bytes of the key of an extension node
+fn extension_key_len(node: &[u8]) -> usize {
+    if node[1] <= 32 {
+        1
+    } else if node[0] <= RLP_LIST_LONG {
+        (node[1] - 128) as usize
+    } else {
+        (node[2] - 128) as usize
+    }
+}
+
+/// Write the key and the child of an extension node into two rows, and return
+/// the RLP list prefix of the node.
+fn extension_row(key_row: &mut [u8], child_row: &mut [u8], node: &[u8], set_key: bool) -> Vec<u8> {
+    let (mut key_len, mut key_start) = if node[1] <= 32 {
+        (1, 1)
+    } else if node[0] <= RLP_LIST_LONG {
+        ((node[1] - 128) as usize, 2)
+    } else {
+        ((node[2] - 128) as usize, 3)
+    };
+    if key_len != 1 {
+        // The key row starts with the RLP prefix of the key.
+        key_start -= 1;
+        key_len += 1;
+    }
+
+    if set_key {
+        key_row[..key_len].copy_from_slice(&node[key_start..key_start + key_len]);
+    }
+    let child_start = key_start + key_len;
+    let child_len = match node[child_start] {
+        RLP_HASH_VALUE => 32,
+        prefix if prefix > RLP_LIST_SHORT => (prefix - RLP_LIST_SHORT) as usize,
+        _ => 0,
+    };
+    child_row[..child_len + 1].copy_from_slice(&node[child_start..child_start + child_len + 1]);
+    vec![node[0]]
+}
+
+/// Return the number of nibbles, the RLP list prefix and the 4 rows of an
+/// extension node before and after an update.
+fn extension_rows(nibbles: &[u8], node_s: &[u8], node_c: &[u8]) -> (usize, Vec<u8>, Vec<Vec<u8>>) {
+    let mut rows = vec![row(&[]); 4];
+    let (rows_s, rows_c) = rows.split_at_mut(2);
+    let (key_s, child_s) = rows_s.split_at_mut(1);
+    let (nibbles_c, child_c) = rows_c.split_at_mut(1);
+    let list_rlp_bytes = extension_row(&mut key_s[0], &mut child_s[0], node_s, true);
+    extension_row(&mut nibbles_c[0], &mut child_c[0], node_c, false);
+
+    let is_even = node_s[2] == 0;
+    let key_len = extension_key_len(node_s);
+    let num_nibbles = match key_len {
+        1 => 1,
+        _ if is_even => (key_len - 1) * 2,
+        _ => (key_len - 1) * 2 + 1,
+    };
+
+    // Every second nibble is enough to compute the key from its bytes.
+    let start = if key_len > 1 && is_even { 1 } else { 2 };
+    for (i, nibble) in nibbles.iter().skip(start).step_by(2).enumerate() {
+        rows[2][2 + i] = *nibble;
+    }
+
+    (num_nibbles, list_rlp_bytes, rows)
+}
+
+/// Return the rows of the nonce and the balance of an account leaf, and the
+/// position of its storage root.
+fn nonce_balance_rows(leaf: &[u8], key_len: usize) -> (Vec<u8>, Vec<u8>, usize) {
+    let value_len = |start: usize| match leaf[start] {
+        prefix if prefix <= RLP_NIL => 1,
+        prefix => (prefix - RLP_NIL) as usize + 1,
+    };
+    let nonce_start = 3 + key_len + 4;
+    let balance_start = nonce_start + value_len(nonce_start);
+    let storage_start = balance_start + value_len(balance_start);
+    (
+        row(&leaf[nonce_start..balance_start]),
+        row(&leaf[balance_start..storage_start]),
+        storage_start,
+    )
+}
+
+/// Return the node of an account leaf before and after an update.
+fn account_leaf_node(
+    address: Address,
+    key: H256,
+    leaf_s: &[u8],
+    leaf_c: &[u8],
+    key_nibbles: &[u8],
+) -> Node {
+    let key_len = |leaf: &[u8]| (leaf[2] - 128) as usize;
+    let (key_len_s, key_len_c) = (key_len(leaf_s), key_len(leaf_c));
+    for (leaf, key_len) in [(leaf_s, key_len_s), (leaf_c, key_len_c)] {
+        // The value is a string with a one byte length of a list with a one
+        // byte length.
+        assert_eq!(leaf[3 + key_len], RLP_LONG + 1);
+        assert_eq!(leaf[3 + key_len + 2], RLP_LIST_LONG + 1);
+        assert_eq!(leaf[3 + key_len + 1], leaf[3 + key_len + 3] + 2);
+    }
+
+    // The key of the leaf, with the nibbles of the key which are not in its
+    // path, for the proofs of non existing accounts.
+    let mut wrong = row(&[leaf_c[2]]);
+    let mut num_nibbles = (key_len_c - 1) * 2;
+    let offset = if leaf_c[3] != 32 {
+        num_nibbles += 1;
+        wrong[1] = key_nibbles[64 - num_nibbles] + 48;
+        1
+    } else {
+        wrong[1] = 32;
+        0
+    };
+    let remaining = &key_nibbles[64 - num_nibbles..];
+    for i in 0..key_len_c - 1 {
+        wrong[2 + i] = remaining[2 * i + offset] * 16 + remaining[2 * i + 1 + offset];
+    }
+
+    let (nonce_s, balance_s, storage_start_s) = nonce_balance_rows(leaf_s, key_len_s);
+    let (nonce_c, balance_c, storage_start_c) = nonce_balance_rows(leaf_c, key_len_c);
+    let hash_rows = |leaf: &[u8], start: usize| {
+        (
+            row(&leaf[start..start + 33]),
+            row(&leaf[start + 33..start + 66]),
+        )
+    };
+    let (storage_s, codehash_s) = hash_rows(leaf_s, storage_start_s);
+    let (storage_c, codehash_c) = hash_rows(leaf_c, storage_start_c);
+
+    let mut values = vec![
+        row(&leaf_s[2..3 + key_len_s]),
+        row(&leaf_c[2..3 + key_len_c]),
+        nonce_s,
+        balance_s,
+        storage_s,
+        codehash_s,
+        nonce_c,
+        balance_c,
+        storage_c,
+        codehash_c,
+        row(&[]),
+        wrong,
+    ];
+    values.extend(vec![row(&[]); MOD_EXTENSION_ROWS]);
+    values.push([&[RLP_SHORT + ADDRESS_WIDTH as u8][..], address.as_bytes()].concat());
+    values.push([&[RLP_HASH_VALUE][..], key.as_bytes()].concat());
+
+    let value_rlp_bytes = |offset: usize| {
+        [(leaf_s, key_len_s), (leaf_c, key_len_c)].map(|(leaf, key_len)| {
+            let start = 3 + key_len + offset;
+            leaf[start..start + 2].to_vec().into()
+        })
+    };
+    Node {
+        account: Some(AccountNode {
+            address: address.as_bytes().to_vec().into(),
+            key: key.as_bytes().to_vec().into(),
+            list_rlp_bytes: [leaf_s, leaf_c].map(|leaf| leaf[..2].to_vec().into()),
+            value_rlp_bytes: value_rlp_bytes(0),
+            value_list_rlp_bytes: value_rlp_bytes(2),
+            drifted_rlp_bytes: vec![0].into(),
+            wrong_rlp_bytes: leaf_c[..2].to_vec().into(),
+            is_mod_extension: [false, false],
+            mod_list_rlp_bytes: [row(&[]).into(), row(&[]).into()],
+        }),
+        values: values.into_iter().map(Hex::from).collect(),
+        keccak_data: [leaf_s, leaf_c, address.as_bytes()]
+            .map(|data| data.to_vec().into())
+            .to_vec(),
+        ..Default::default()
+    }
+}
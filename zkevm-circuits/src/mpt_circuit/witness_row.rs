@@ -4,7 +4,10 @@ use crate::table::MPTProofType;
 
 use serde::{Deserialize, Serialize};
 
-use super::RlpItemType;
+use super::{
+    param::{RLP_HASH_VALUE, RLP_UNIT_NUM_BYTES},
+    RlpItemType,
+};
 
 #[derive(Debug, Eq, PartialEq)]
 pub(crate) enum StorageRowType {
@@ -208,6 +211,23 @@ pub struct Node {
     pub keccak_data: Vec<Hex>,
 }
 
+impl Node {
+    /// The disabled start node that ends a list of proofs, with empty roots
+    pub fn end() -> Self {
+        let empty_root: Hex = [vec![RLP_HASH_VALUE], vec![0; RLP_UNIT_NUM_BYTES - 1]]
+            .concat()
+            .into();
+        Node {
+            start: Some(StartNode {
+                disable_preimage_check: false,
+                proof_type: MPTProofType::Disabled,
+            }),
+            values: vec![empty_root; StartRowType::Count as usize],
+            ..Default::default()
+        }
+    }
+}
+
 /// RLP types start
 pub const NODE_RLP_TYPES_START: [RlpItemType; StartRowType::Count as usize] =
     [RlpItemType::Hash, RlpItemType::Hash];
@@ -1,5 +1,7 @@
 //! Public Input Circuit implementation
+mod header;
 mod param;
+mod tx_trie;
 
 #[cfg(any(test, feature = "test-circuits"))]
 mod dev;
@@ -11,16 +13,18 @@ use std::{cmp::min, iter, marker::PhantomData};
 pub use PiCircuit as TestPiCircuit;
 
 use bus_mapping::circuit_input_builder::Withdrawal;
-use eth_types::{self, Field, ToLittleEndian};
+use eth_types::{self, evm_types::MAX_BLOBS_PER_BLOCK, Field, ToLittleEndian, H256};
 use halo2_proofs::plonk::{Expression, Instance, SecondPhase};
+use header::{HeaderConfig, HeaderPiCells};
 use itertools::Itertools;
 use param::*;
+use tx_trie::{receipt_trie_len, tx_trie_len, wd_trie_len, TrieLeaves, TxTrieConfig};
 
 use crate::{
     evm_circuit::{
         param::{
-            N_BYTES_BLOCK, N_BYTES_EXTRA_VALUE, N_BYTES_HALF_WORD, N_BYTES_TX, N_BYTES_U64,
-            N_BYTES_WITHDRAWAL, N_BYTES_WORD,
+            N_BYTES_BLOB_VERSIONED_HASH, N_BYTES_BLOCK, N_BYTES_EXTRA_VALUE, N_BYTES_HALF_WORD,
+            N_BYTES_TX, N_BYTES_U64, N_BYTES_WITHDRAWAL, N_BYTES_WORD,
         },
         util::{
             constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
@@ -28,10 +32,10 @@ use crate::{
         },
     },
     instance::{
-        public_data_convert, BlockValues, ExtraValues, PublicData, TxValues, NONZERO_BYTE_GAS_COST,
-        ZERO_BYTE_GAS_COST,
+        public_data_convert, BlockValues, ChunkPublicData, ExtraValues, PublicData, TxValues,
+        NONZERO_BYTE_GAS_COST, ZERO_BYTE_GAS_COST,
     },
-    table::{BlockTable, KeccakTable, LookupTable, TxFieldTag, TxTable, WdTable},
+    table::{BlockTable, KeccakTable, LookupTable, ReceiptTable, TxFieldTag, TxTable, WdTable},
     tx_circuit::TX_LEN,
     util::{word::Word, Challenges, SubCircuit, SubCircuitConfig},
     witness,
@@ -49,12 +53,16 @@ use halo2_proofs::{
 /// Config for PiCircuit
 #[derive(Clone, Debug)]
 pub struct PiCircuitConfig<F: Field> {
+    /// Max number of supported blocks in a chunk
+    max_blocks: usize,
     /// Max number of supported transactions
     max_txs: usize,
     /// Max number of supported withdrawals
     max_withdrawals: usize,
     /// Max number of supported calldata bytes
     max_calldata: usize,
+    /// Max number of supported bytes of RLP encoded logs
+    max_log_bytes: usize,
 
     // q_digest_last: will be 1 on last byte of keccak digest, others are 0
     q_digest_last: Selector,
@@ -103,6 +111,15 @@ pub struct PiCircuitConfig<F: Field> {
 
     pi_instance: Column<Instance>, // keccak_digest_hi, keccak_digest_lo
 
+    // header: verification of the block hash against the block header
+    header: HeaderConfig,
+    // tx_trie: verification of the transactions root against the tx table
+    tx_trie: TxTrieConfig,
+    // receipt_trie: verification of the receipts root against the receipt table
+    receipt_trie: TxTrieConfig,
+    // wd_trie: verification of the withdrawals root against the wd table
+    wd_trie: TxTrieConfig,
+
     _marker: PhantomData<F>,
     // External tables
     block_table: BlockTable,
@@ -113,12 +130,16 @@ pub struct PiCircuitConfig<F: Field> {
 
 /// Circuit configuration arguments
 pub struct PiCircuitConfigArgs<F: Field> {
+    /// Max number of supported blocks in a chunk
+    pub max_blocks: usize,
     /// Max number of supported transactions
     pub max_txs: usize,
     /// Max number of supported withdrawals
     pub max_withdrawals: usize,
     /// Max number of supported calldata bytes
     pub max_calldata: usize,
+    /// Max number of supported bytes of RLP encoded logs
+    pub max_log_bytes: usize,
     /// TxTable
     pub tx_table: TxTable,
     /// WdTable
@@ -127,6 +148,8 @@ pub struct PiCircuitConfigArgs<F: Field> {
     pub block_table: BlockTable,
     /// Keccak Table
     pub keccak_table: KeccakTable,
+    /// Receipt Table
+    pub receipt_table: ReceiptTable,
     /// Challenges
     pub challenges: Challenges<Expression<F>>,
 }
@@ -138,13 +161,16 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
     fn new(
         meta: &mut ConstraintSystem<F>,
         Self::ConfigArgs {
+            max_blocks,
             max_txs,
             max_withdrawals,
             max_calldata,
+            max_log_bytes,
             block_table,
             tx_table,
             wd_table,
             keccak_table,
+            receipt_table,
             challenges,
         }: Self::ConfigArgs,
     ) -> Self {
@@ -287,9 +313,13 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
         meta.lookup_any(
             "lookup rpi_bytes_keccak_rlc against rpi_digest_bytes_limbs",
             |meta| {
-                let circuit_len =
-                    PiCircuitConfig::<F>::circuit_len_all(max_txs, max_withdrawals, max_calldata)
-                        .expr();
+                let circuit_len = PiCircuitConfig::<F>::circuit_len_all(
+                    max_blocks,
+                    max_txs,
+                    max_withdrawals,
+                    max_calldata,
+                )
+                .expr();
                 let is_enabled = meta.query_advice(keccak_table.is_enabled, Rotation::cur());
                 let input_rlc = meta.query_advice(keccak_table.input_rlc, Rotation::cur());
                 let input_len = meta.query_advice(keccak_table.input_len, Rotation::cur());
@@ -318,6 +348,36 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
             },
         );
 
+        let header =
+            HeaderConfig::configure(meta, &keccak_table, &receipt_table, fixed_u16, &challenges);
+        let tx_trie = TxTrieConfig::configure(
+            meta,
+            &keccak_table,
+            &tx_table,
+            TrieLeaves::Txs(&block_table),
+            fixed_u16,
+            &challenges,
+            max_txs,
+        );
+        let receipt_trie = TxTrieConfig::configure(
+            meta,
+            &keccak_table,
+            &tx_table,
+            TrieLeaves::Receipts(&receipt_table),
+            fixed_u16,
+            &challenges,
+            max_txs,
+        );
+        let wd_trie = TxTrieConfig::configure(
+            meta,
+            &keccak_table,
+            &tx_table,
+            TrieLeaves::Withdrawals(&wd_table),
+            fixed_u16,
+            &challenges,
+            max_withdrawals,
+        );
+
         let tx_id_is_zero_config = IsZeroChip::configure(
             meta,
             |meta| meta.query_selector(q_tx_calldata),
@@ -497,9 +557,11 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
         });
 
         Self {
+            max_blocks,
             max_txs,
             max_withdrawals,
             max_calldata,
+            max_log_bytes,
             block_table,
             q_digest_last,
             q_bytes_last,
@@ -525,6 +587,10 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
             rpi_digest_bytes_limbs,
             q_rpi_byte_enable,
             pi_instance,
+            header,
+            tx_trie,
+            receipt_trie,
+            wd_trie,
             _marker: PhantomData,
         }
     }
@@ -534,22 +600,32 @@ impl<F: Field> PiCircuitConfig<F> {
     /// Return the number of rows in the circuit
     #[inline]
     fn circuit_len(&self) -> usize {
-        Self::circuit_len_all(self.max_txs, self.max_withdrawals, self.max_calldata)
+        Self::circuit_len_all(
+            self.max_blocks,
+            self.max_txs,
+            self.max_withdrawals,
+            self.max_calldata,
+        )
     }
 
-    /// Return the number of rows for txs and calldata
+    /// Return the number of rows for blocks, txs and calldata
     #[inline]
-    fn circuit_len_all(txs: usize, wds: usize, calldata: usize) -> usize {
+    fn circuit_len_all(blocks: usize, txs: usize, wds: usize, calldata: usize) -> usize {
         N_BYTES_ONE
-            + N_BYTES_BLOCK
-            + N_BYTES_EXTRA_VALUE
+            + Self::circuit_len_blocks(blocks)
             + Self::circuit_len_tx_id(txs)
             + Self::circuit_len_tx_index(txs)
             + Self::circuit_len_tx_values(txs)
+            + Self::circuit_len_blob_hashes()
             + calldata
             + Self::circuit_len_withdrawal(wds)
     }
 
+    #[inline]
+    fn circuit_len_blocks(blocks: usize) -> usize {
+        (N_BYTES_BLOCK + N_BYTES_EXTRA_VALUE) * blocks
+    }
+
     #[inline]
     fn circuit_len_tx_values(txs: usize) -> usize {
         N_BYTES_TX * (txs) + N_BYTES_ONE
@@ -565,6 +641,11 @@ impl<F: Field> PiCircuitConfig<F> {
         N_BYTES_U64 * TX_LEN * txs + N_BYTES_U64 // empty row
     }
 
+    #[inline]
+    fn circuit_len_blob_hashes() -> usize {
+        N_BYTES_BLOB_VERSIONED_HASH * MAX_BLOBS_PER_BLOCK
+    }
+
     #[inline]
     fn circuit_len_withdrawal(withdrawals: usize) -> usize {
         N_BYTES_WITHDRAWAL * withdrawals
@@ -884,6 +965,29 @@ impl<F: Field> PiCircuitConfig<F> {
         Ok(rpi_bytes_keccakrlc_cell)
     }
 
+    fn assign_empty_wdtable_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+    ) -> Result<(), Error> {
+        region.assign_fixed(
+            || "withdrawal_index",
+            self.wd_table.index,
+            offset,
+            || Value::known(F::ZERO),
+        )?;
+        for column in [
+            self.wd_table.id,
+            self.wd_table.validator_id,
+            self.wd_table.address.lo(),
+            self.wd_table.address.hi(),
+            self.wd_table.amount,
+        ] {
+            region.assign_advice(|| "wd_table", column, offset, || Value::known(F::ZERO))?;
+        }
+        Ok(())
+    }
+
     /// Assigns a wd_table row and stores the values in a vec for the raw_public_inputs column
     /// current_rpi_offset is used to record current rpi offset
     #[allow(clippy::too_many_arguments)]
@@ -898,6 +1002,12 @@ impl<F: Field> PiCircuitConfig<F> {
         rpi_bytes: &mut [u8],
         zero_cell: AssignedCell<F, F>,
     ) -> Result<(), Error> {
+        region.assign_fixed(
+            || "withdrawal_index",
+            self.wd_table.index,
+            offset,
+            || Value::known(F::from(offset as u64)),
+        )?;
         let id_assigned_cell = region.assign_advice(
             || "withdrawal_id",
             self.wd_table.id,
@@ -1107,7 +1217,8 @@ impl<F: Field> PiCircuitConfig<F> {
 
     /// Assigns the values for block table in the block_table column
     /// and rpi_bytes columns. Copy constraints will be enable
-    /// to assure block_table value cell equal with respective rpi_byte_rlc cell
+    /// to assure block_table value cell equal with respective rpi_byte_rlc cell.
+    /// Returns the block table value cells, in the order of the block table.
     #[allow(clippy::too_many_arguments)]
     fn assign_block_table(
         &self,
@@ -1119,7 +1230,7 @@ impl<F: Field> PiCircuitConfig<F> {
         current_rpi_offset: &mut usize,
         rpi_bytes: &mut [u8],
         zero_cell: AssignedCell<F, F>,
-    ) -> Result<(), Error> {
+    ) -> Result<Vec<Word<AssignedCell<F, F>>>, Error> {
         let mut block_copy_cells = vec![];
 
         // coinbase
@@ -1294,6 +1405,69 @@ impl<F: Field> PiCircuitConfig<F> {
         block_copy_cells.push((block_value, word));
         *block_table_offset += 1;
 
+        // blob_base_fee
+        let block_value = Word::from(block_values.blob_base_fee)
+            .into_value()
+            .assign_advice(
+                region,
+                || "blob_base_fee",
+                self.block_table.value,
+                *block_table_offset,
+            )?;
+        let (_, word) = self.assign_raw_bytes(
+            region,
+            &block_values.blob_base_fee.to_le_bytes(),
+            rpi_bytes_keccak_rlc,
+            rpi_bytes,
+            current_rpi_offset,
+            challenges,
+            zero_cell.clone(),
+        )?;
+        block_copy_cells.push((block_value, word));
+        *block_table_offset += 1;
+
+        // hardfork
+        let block_value = Word::from(block_values.hardfork)
+            .into_value()
+            .assign_advice(
+                region,
+                || "hardfork",
+                self.block_table.value,
+                *block_table_offset,
+            )?;
+        let (_, word) = self.assign_raw_bytes(
+            region,
+            &block_values.hardfork.to_le_bytes(),
+            rpi_bytes_keccak_rlc,
+            rpi_bytes,
+            current_rpi_offset,
+            challenges,
+            zero_cell.clone(),
+        )?;
+        block_copy_cells.push((block_value, word));
+        *block_table_offset += 1;
+
+        // withdrawal_offset
+        let block_value = Word::from(block_values.withdrawal_offset)
+            .into_value()
+            .assign_advice(
+                region,
+                || "withdrawal_offset",
+                self.block_table.value,
+                *block_table_offset,
+            )?;
+        let (_, word) = self.assign_raw_bytes(
+            region,
+            &block_values.withdrawal_offset.to_le_bytes(),
+            rpi_bytes_keccak_rlc,
+            rpi_bytes,
+            current_rpi_offset,
+            challenges,
+            zero_cell.clone(),
+        )?;
+        block_copy_cells.push((block_value, word));
+        *block_table_offset += 1;
+
         for prev_hash in block_values.history_hashes {
             let block_value = Word::from(prev_hash).into_value().assign_advice(
                 region,
@@ -1325,14 +1499,20 @@ impl<F: Field> PiCircuitConfig<F> {
             Ok::<(), Error>(())
         })?;
 
-        Ok(())
+        Ok(block_copy_cells
+            .into_iter()
+            .map(|(block_value, _)| block_value)
+            .collect())
     }
 
     /// Assigns the extra fields (not in block or tx tables):
     ///   - block hash
     ///   - state root
     ///   - previous block state root
-    /// to the rpi_byte column
+    ///   - excess blob gas
+    ///   - transactions root
+    ///   - receipts root
+    /// to the rpi_byte column, and returns their value cells in this order
     #[allow(clippy::too_many_arguments)]
     fn assign_extra_fields(
         &self,
@@ -1343,9 +1523,9 @@ impl<F: Field> PiCircuitConfig<F> {
         current_rpi_offset: &mut usize,
         rpi_bytes: &mut [u8],
         zero_cell: AssignedCell<F, F>,
-    ) -> Result<(), Error> {
+    ) -> Result<[Word<AssignedCell<F, F>>; 6], Error> {
         // block hash
-        self.assign_raw_bytes(
+        let (_, block_hash) = self.assign_raw_bytes(
             region,
             &extra
                 .block_hash
@@ -1362,7 +1542,7 @@ impl<F: Field> PiCircuitConfig<F> {
         )?;
 
         // block state root
-        self.assign_raw_bytes(
+        let (_, state_root) = self.assign_raw_bytes(
             region,
             &extra
                 .state_root
@@ -1379,7 +1559,7 @@ impl<F: Field> PiCircuitConfig<F> {
         )?;
 
         // previous block state root
-        self.assign_raw_bytes(
+        let (_, prev_state_root) = self.assign_raw_bytes(
             region,
             &extra
                 .prev_state_root
@@ -1392,10 +1572,62 @@ impl<F: Field> PiCircuitConfig<F> {
             rpi_bytes,
             current_rpi_offset,
             challenges,
+            zero_cell.clone(),
+        )?;
+
+        // excess blob gas
+        let (_, excess_blob_gas) = self.assign_raw_bytes(
+            region,
+            &extra.excess_blob_gas.to_le_bytes(),
+            rpi_bytes_keccak_rlc,
+            rpi_bytes,
+            current_rpi_offset,
+            challenges,
+            zero_cell.clone(),
+        )?;
+
+        // transactions root
+        let (_, transactions_root) = self.assign_raw_bytes(
+            region,
+            &extra
+                .transactions_root
+                .to_fixed_bytes()
+                .iter()
+                .copied()
+                .rev()
+                .collect_vec(),
+            rpi_bytes_keccak_rlc,
+            rpi_bytes,
+            current_rpi_offset,
+            challenges,
+            zero_cell.clone(),
+        )?;
+
+        // receipts root
+        let (_, receipts_root) = self.assign_raw_bytes(
+            region,
+            &extra
+                .receipts_root
+                .to_fixed_bytes()
+                .iter()
+                .copied()
+                .rev()
+                .collect_vec(),
+            rpi_bytes_keccak_rlc,
+            rpi_bytes,
+            current_rpi_offset,
+            challenges,
             zero_cell,
         )?;
 
-        Ok(())
+        Ok([
+            block_hash,
+            state_root,
+            prev_state_root,
+            excess_blob_gas,
+            transactions_root,
+            receipts_root,
+        ])
     }
 
     /// Assign digest word
@@ -1423,26 +1655,50 @@ impl<F: Field> PiCircuitConfig<F> {
 /// Public Inputs Circuit
 #[derive(Clone, Default, Debug)]
 pub struct PiCircuit<F: Field> {
+    max_blocks: usize,
     max_txs: usize,
     max_withdrawals: usize,
     max_calldata: usize,
+    max_log_bytes: usize,
     /// PublicInputs data known by the verifier
-    pub public_data: PublicData,
+    pub public_data: ChunkPublicData,
     _marker: PhantomData<F>,
 }
 
 impl<F: Field> PiCircuit<F> {
-    /// Creates a new PiCircuit
+    /// Creates a new PiCircuit for a single block
     pub fn new(
         max_txs: usize,
         max_withdrawals: usize,
         max_calldata: usize,
+        max_log_bytes: usize,
         public_data: PublicData,
+    ) -> Self {
+        Self::new_chunk(
+            1,
+            max_txs,
+            max_withdrawals,
+            max_calldata,
+            max_log_bytes,
+            public_data.into(),
+        )
+    }
+
+    /// Creates a new PiCircuit for a chunk of consecutive blocks
+    pub fn new_chunk(
+        max_blocks: usize,
+        max_txs: usize,
+        max_withdrawals: usize,
+        max_calldata: usize,
+        max_log_bytes: usize,
+        public_data: ChunkPublicData,
     ) -> Self {
         Self {
+            max_blocks,
             max_txs,
             max_withdrawals,
             max_calldata,
+            max_log_bytes,
             public_data,
             _marker: PhantomData,
         }
@@ -1460,10 +1716,12 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
 
     fn new_from_block(block: &witness::Block<F>) -> Self {
         let public_data = public_data_convert(block);
-        PiCircuit::new(
+        PiCircuit::new_chunk(
+            block.circuits_params.max_blocks,
             block.circuits_params.max_txs,
             block.circuits_params.max_withdrawals,
             block.circuits_params.max_calldata,
+            block.circuits_params.max_log_bytes,
             public_data,
         )
     }
@@ -1471,12 +1729,43 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
     /// Return the minimum number of rows required to prove the block
     fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
         let calldata_len = block.txs.iter().map(|tx| tx.call_data.len()).sum();
+        let log_bytes = block
+            .receipts
+            .iter()
+            .flat_map(|receipt| receipt.logs.iter())
+            .map(|log| log.rlp().len())
+            .sum();
         (
-            Self::Config::circuit_len_all(block.txs.len(), block.withdrawals().len(), calldata_len),
             Self::Config::circuit_len_all(
+                block.contexts.len(),
+                block.txs.len(),
+                block.withdrawals().len(),
+                calldata_len,
+            )
+            // the tries of the blocks are laid out one after the other
+            .max(
+                block.contexts.len()
+                    * (tx_trie_len(block.txs.len(), calldata_len)
+                        .max(receipt_trie_len(block.txs.len(), log_bytes))
+                        .max(wd_trie_len(block.withdrawals().len()))),
+            ),
+            Self::Config::circuit_len_all(
+                block.circuits_params.max_blocks,
                 block.circuits_params.max_txs,
                 block.circuits_params.max_withdrawals,
                 block.circuits_params.max_calldata,
+            )
+            .max(
+                block.circuits_params.max_blocks
+                    * (tx_trie_len(
+                        block.circuits_params.max_txs,
+                        block.circuits_params.max_calldata,
+                    )
+                    .max(receipt_trie_len(
+                        block.circuits_params.max_txs,
+                        block.circuits_params.max_log_bytes,
+                    ))
+                    .max(wd_trie_len(block.circuits_params.max_withdrawals))),
             ),
         )
     }
@@ -1484,6 +1773,7 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
     /// Compute the public inputs for this circuit.
     fn instance(&self) -> Vec<Vec<F>> {
         let rpi_digest_byte_field = self.public_data.get_rpi_digest_word(
+            self.max_blocks,
             self.max_txs,
             self.max_withdrawals,
             self.max_calldata,
@@ -1514,7 +1804,8 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                 Ok(())
             },
         )?;
-        let digest_word_assigned = layouter.assign_region(
+        config.tx_trie.load(layouter)?;
+        let (digest_word_assigned, blocks_pi_cells, zero_cell) = layouter.assign_region(
             || "region 0",
             |mut region| {
                 // Annotate columns
@@ -1557,8 +1848,6 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                 config.reset_rpi_bytes_row(&mut region, start_offset + 1)?;
                 config.reset_rpi_digest_row(&mut region, N_BYTES_WORD)?;
 
-                // Assign block table
-                let block_values = self.public_data.get_block_table_values();
                 let mut block_table_offset = 0;
 
                 // assign empty row in block table
@@ -1579,36 +1868,88 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                     zero_cell.clone(),
                 )?;
                 block_table_offset += 1;
-                config.assign_block_table(
-                    &mut region,
-                    &mut block_table_offset,
-                    block_values,
-                    &mut rpi_bytes_keccak_rlc,
-                    challenges,
-                    &mut current_rpi_offset,
-                    &mut rpi_bytes,
-                    zero_cell.clone(),
-                )?;
-                assert_eq!(
-                    start_offset - current_rpi_offset,
-                    N_BYTES_ONE + N_BYTES_BLOCK
-                );
 
-                // Assign extra fields
-                let extra_vals = self.public_data.get_extra_values();
-                config.assign_extra_fields(
-                    &mut region,
-                    extra_vals,
-                    &mut rpi_bytes_keccak_rlc,
-                    challenges,
-                    &mut current_rpi_offset,
-                    &mut rpi_bytes,
-                    zero_cell,
-                )?;
-                assert_eq!(
-                    start_offset - current_rpi_offset,
-                    N_BYTES_ONE + N_BYTES_BLOCK + N_BYTES_EXTRA_VALUE
-                );
+                // Assign block table and extra fields of every block of the chunk
+                let mut blocks_cells = vec![];
+                for (i, (block_values, extra_vals)) in self
+                    .public_data
+                    .get_block_values(config.max_blocks)
+                    .into_iter()
+                    .enumerate()
+                {
+                    let block_cells = config.assign_block_table(
+                        &mut region,
+                        &mut block_table_offset,
+                        block_values,
+                        &mut rpi_bytes_keccak_rlc,
+                        challenges,
+                        &mut current_rpi_offset,
+                        &mut rpi_bytes,
+                        zero_cell.clone(),
+                    )?;
+                    let extra_cells = config.assign_extra_fields(
+                        &mut region,
+                        extra_vals,
+                        &mut rpi_bytes_keccak_rlc,
+                        challenges,
+                        &mut current_rpi_offset,
+                        &mut rpi_bytes,
+                        zero_cell.clone(),
+                    )?;
+                    assert_eq!(
+                        start_offset - current_rpi_offset,
+                        N_BYTES_ONE + Self::Config::circuit_len_blocks(i + 1)
+                    );
+                    blocks_cells.push((block_cells, extra_cells));
+                }
+
+                // Chain every block to the previous one: its previous state
+                // root is the state root of the previous block, and its parent
+                // hash, the latest of its history hashes, is the hash of the
+                // previous block
+                for ((_, prev_extra_cells), (block_cells, extra_cells)) in
+                    blocks_cells.iter().tuple_windows()
+                {
+                    for (left, right) in [
+                        (&prev_extra_cells[1], &extra_cells[2]),
+                        (
+                            &prev_extra_cells[0],
+                            block_cells.last().expect("history hashes"),
+                        ),
+                    ] {
+                        region.constrain_equal(left.lo().cell(), right.lo().cell())?;
+                        region.constrain_equal(left.hi().cell(), right.hi().cell())?;
+                    }
+                }
+                // The header and the tries of every block of the chunk are
+                // verified against its block table cells, which follow the
+                // order of `assign_block_table`, and its extra fields, together
+                // with its withdrawal offset
+                let blocks_pi_cells = blocks_cells
+                    .into_iter()
+                    .take(self.public_data.blocks.len())
+                    .map(|(block_cells, extra_cells)| {
+                        let [block_hash, state_root, _, excess_blob_gas, transactions_root, receipts_root] =
+                            extra_cells;
+                        let header_pi_cells = HeaderPiCells {
+                            hardfork: block_cells[9].lo(),
+                            block_hash,
+                            parent_hash: block_cells.last().expect("history hashes").clone(),
+                            coinbase: block_cells[0].clone(),
+                            state_root,
+                            transactions_root,
+                            receipts_root,
+                            difficulty: block_cells[4].clone(),
+                            number: block_cells[2].clone(),
+                            gas_limit: block_cells[1].clone(),
+                            timestamp: block_cells[3].clone(),
+                            base_fee: block_cells[5].clone(),
+                            withdrawals_root: block_cells[7].clone(),
+                            excess_blob_gas,
+                        };
+                        (header_pi_cells, block_cells[10].lo())
+                    })
+                    .collect_vec();
 
                 let mut tx_table_offset = 0;
                 // Assign Tx table
@@ -1675,6 +2016,27 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                                 TxFieldTag::CallDataGasCost,
                                 tx.call_data_gas_cost.to_le_bytes().to_vec(),
                             ),
+                            (
+                                TxFieldTag::BlobVersionedHashesLen,
+                                tx.blob_versioned_hashes_len.to_le_bytes().to_vec(),
+                            ),
+                            (TxFieldTag::TxType, tx.tx_type.to_le_bytes().to_vec()),
+                            (
+                                TxFieldTag::AccessListAddressesLen,
+                                tx.access_list_addresses_len.to_le_bytes().to_vec(),
+                            ),
+                            (
+                                TxFieldTag::MaxFeePerGas,
+                                tx.max_fee_per_gas.to_le_bytes().to_vec(),
+                            ),
+                            (
+                                TxFieldTag::IsReplayProtected,
+                                tx.is_replay_protected.to_le_bytes().to_vec(),
+                            ),
+                            (
+                                TxFieldTag::BlockNumber,
+                                tx.block_number.to_le_bytes().to_vec(),
+                            ),
                             // TODO witness tx.tx_sign_hash
                             (TxFieldTag::TxSignHash, tx.tx_sign_hash.to_vec()),
                         ] {
@@ -1700,11 +2062,40 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                 assert_eq!(
                     start_offset - current_rpi_offset,
                     N_BYTES_ONE
-                        + N_BYTES_BLOCK
-                        + N_BYTES_EXTRA_VALUE
+                        + Self::Config::circuit_len_blocks(config.max_blocks)
+                        + Self::Config::circuit_len_tx_id(config.max_txs)
+                        + Self::Config::circuit_len_tx_index(config.max_txs)
+                        + Self::Config::circuit_len_tx_values(config.max_txs)
+                );
+
+                // Tx Table BlobVersionedHash
+                let blob_hashes = self.public_data.get_blob_versioned_hashes();
+                let blob_hash_padding =
+                    (0..MAX_BLOBS_PER_BLOCK - blob_hashes.len()).map(|_| (0, 0, H256::zero()));
+                for (tx_id, index, hash) in blob_hashes.into_iter().chain(blob_hash_padding) {
+                    config.assign_tx_row(
+                        &mut region,
+                        tx_table_offset,
+                        tx_id,
+                        TxFieldTag::BlobVersionedHash,
+                        index,
+                        &hash.to_fixed_bytes().iter().copied().rev().collect_vec(),
+                        &mut rpi_bytes_keccak_rlc,
+                        challenges,
+                        &mut current_rpi_offset,
+                        &mut rpi_bytes,
+                        zero_cell.clone(),
+                    )?;
+                    tx_table_offset += 1;
+                }
+                assert_eq!(
+                    start_offset - current_rpi_offset,
+                    N_BYTES_ONE
+                        + Self::Config::circuit_len_blocks(config.max_blocks)
                         + Self::Config::circuit_len_tx_id(config.max_txs)
                         + Self::Config::circuit_len_tx_index(config.max_txs)
                         + Self::Config::circuit_len_tx_values(config.max_txs)
+                        + Self::Config::circuit_len_blob_hashes()
                 );
 
                 // Tx Table CallData
@@ -1713,10 +2104,11 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                     .q_calldata_start
                     .enable(&mut region, tx_table_offset)?;
 
-                let mut call_data_offset = TX_LEN * self.max_txs + EMPTY_TX_ROW_COUNT;
+                let mut call_data_offset =
+                    TX_LEN * self.max_txs + EMPTY_TX_ROW_COUNT + MAX_BLOBS_PER_BLOCK;
 
-                let txs = self.public_data.transactions.clone();
-                for (i, tx) in self.public_data.transactions.iter().enumerate() {
+                let txs = self.public_data.transactions().collect_vec();
+                for (i, tx) in txs.iter().enumerate() {
                     let call_data_length = tx.call_data.0.len();
                     let mut gas_cost = F::ZERO;
                     for (index, byte) in tx.call_data.0.iter().enumerate() {
@@ -1782,11 +2174,11 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                 assert_eq!(
                     start_offset - current_rpi_offset,
                     N_BYTES_ONE
-                        + N_BYTES_BLOCK
-                        + N_BYTES_EXTRA_VALUE
+                        + Self::Config::circuit_len_blocks(config.max_blocks)
                         + Self::Config::circuit_len_tx_id(config.max_txs)
                         + Self::Config::circuit_len_tx_index(config.max_txs)
                         + Self::Config::circuit_len_tx_values(config.max_txs)
+                        + Self::Config::circuit_len_blob_hashes()
                         + config.max_calldata
                 );
 
@@ -1794,15 +2186,14 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                 // also assign empty to last of TxTable
                 config.assign_empty_txtable_row(&mut region, call_data_offset)?;
 
-                // assign withdrawal table and padding rows
-                let mut withdrawal_offset = 0;
+                // assign withdrawal table and padding rows, after its empty row
+                config.assign_empty_wdtable_row(&mut region, 0)?;
+                let mut withdrawal_offset = 1;
                 let wd_default = Withdrawal::default();
+                let withdrawals = self.public_data.withdrawals();
                 iter::empty()
-                    .chain(&self.public_data.withdrawals)
-                    .chain(
-                        (0..(config.max_withdrawals - self.public_data.withdrawals.len()))
-                            .map(|_| &wd_default),
-                    )
+                    .chain(&withdrawals)
+                    .chain((0..(config.max_withdrawals - withdrawals.len())).map(|_| &wd_default))
                     .enumerate()
                     .try_for_each(|(_, wd)| -> Result<(), Error> {
                         config.assign_wd_table_row(
@@ -1822,6 +2213,7 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
 
                 // assign keccak digest
                 let digest_word = self.public_data.get_rpi_digest_word::<F>(
+                    config.max_blocks,
                     config.max_txs,
                     config.max_withdrawals,
                     config.max_calldata,
@@ -1833,10 +2225,78 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                 // keccak lookup occur on offset 0
                 config.q_rpi_keccak_lookup.enable(&mut region, 0)?;
 
-                Ok(digest_word_assigned)
+                Ok((digest_word_assigned, blocks_pi_cells, zero_cell))
             },
         )?;
 
+        // Verify the block hash of every block of the chunk against its header,
+        // and its transactions, receipts and withdrawals roots against its txs,
+        // receipts and withdrawals, which follow the ones of the previous blocks
+        // in the tx, receipt and wd tables. The padding blocks have neither.
+        let mut tx_offset = zero_cell.clone();
+        let mut wd_offset = zero_cell;
+        for (i, ((header_pi_cells, withdrawal_offset), public_data)) in blocks_pi_cells
+            .iter()
+            .zip_eq(self.public_data.blocks.iter())
+            .enumerate()
+        {
+            let is_last = i == blocks_pi_cells.len() - 1;
+            // Verify the block hash against the block header
+            config.header.assign(
+                layouter,
+                &public_data.get_block_header(),
+                public_data.block_constants.hardfork,
+                header_pi_cells,
+                blocks_pi_cells.len() == 1,
+                challenges,
+            )?;
+
+            // Verify the transactions root against the tx table, and the receipts
+            // root against the receipt table
+            let callers = public_data
+                .transactions
+                .iter()
+                .map(|tx| tx.from)
+                .collect_vec();
+            let tx_end = config.tx_trie.assign(
+                layouter,
+                &public_data.get_tx_trie_nodes(),
+                &callers,
+                tx_trie_len(config.max_txs, config.max_calldata),
+                i,
+                is_last,
+                &[&tx_offset],
+                &header_pi_cells.transactions_root,
+                challenges,
+            )?;
+            config.receipt_trie.assign(
+                layouter,
+                &public_data.get_receipt_trie_nodes(),
+                &callers,
+                receipt_trie_len(config.max_txs, config.max_log_bytes),
+                i,
+                is_last,
+                &[&tx_offset],
+                &header_pi_cells.receipts_root,
+                challenges,
+            )?;
+            tx_offset = tx_end;
+
+            // Verify the withdrawals root against the wd table, from the withdrawal
+            // offset of the block
+            wd_offset = config.wd_trie.assign(
+                layouter,
+                &public_data.get_withdrawal_trie_nodes(),
+                &[],
+                wd_trie_len(config.max_withdrawals),
+                i,
+                is_last,
+                &[&wd_offset, withdrawal_offset],
+                &header_pi_cells.withdrawals_root,
+                challenges,
+            )?;
+        }
+
         // Constrain raw_public_input cells to public inputs
         layouter.constrain_instance(digest_word_assigned.lo().cell(), config.pi_instance, 0)?;
         layouter.constrain_instance(digest_word_assigned.hi().cell(), config.pi_instance, 1)?;
@@ -1,10 +1,12 @@
 use super::{PiCircuit, PiCircuitConfig, PiCircuitConfigArgs};
 
 use eth_types::{self, Field};
+use std::iter;
 
 use crate::{
-    table::{BlockTable, KeccakTable, TxTable, WdTable},
+    table::{BlockTable, KeccakTable, ReceiptTable, TxTable, WdTable},
     util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness::BlockContext,
 };
 use halo2_proofs::{
     circuit::{Layouter, SimpleFloorPlanner},
@@ -14,12 +16,16 @@ use halo2_proofs::{
 /// Public Input Circuit configuration parameters
 #[derive(Default)]
 pub struct PiCircuitParams {
+    /// Max Blocks
+    pub max_blocks: usize,
     /// Max Txs
     pub max_txs: usize,
     /// Max withdrawals
     pub max_withdrawals: usize,
     /// Max Calldata
     pub max_calldata: usize,
+    /// Max bytes of RLP encoded logs
+    pub max_log_bytes: usize,
 }
 
 impl<F: Field> Circuit<F> for PiCircuit<F> {
@@ -33,9 +39,11 @@ impl<F: Field> Circuit<F> for PiCircuit<F> {
 
     fn params(&self) -> Self::Params {
         PiCircuitParams {
+            max_blocks: self.max_blocks,
             max_txs: self.max_txs,
             max_withdrawals: self.max_withdrawals,
             max_calldata: self.max_calldata,
+            max_log_bytes: self.max_log_bytes,
         }
     }
 
@@ -44,19 +52,23 @@ impl<F: Field> Circuit<F> for PiCircuit<F> {
         let tx_table = TxTable::construct(meta);
         let wd_table = WdTable::construct(meta);
         let keccak_table = KeccakTable::construct(meta);
+        let receipt_table = ReceiptTable::construct(meta);
         let challenges = Challenges::construct(meta);
         let challenge_exprs = challenges.exprs(meta);
         (
             PiCircuitConfig::new(
                 meta,
                 PiCircuitConfigArgs {
+                    max_blocks: params.max_blocks,
                     max_txs: params.max_txs,
                     max_withdrawals: params.max_withdrawals,
                     max_calldata: params.max_calldata,
+                    max_log_bytes: params.max_log_bytes,
                     block_table,
                     tx_table,
                     wd_table,
                     keccak_table,
+                    receipt_table,
                     challenges: challenge_exprs,
                 },
             ),
@@ -76,13 +88,48 @@ impl<F: Field> Circuit<F> for PiCircuit<F> {
         let challenges = challenges.values(&mut layouter);
         // assign keccak table
         let rpi_bytes = self.public_data.get_pi_bytes(
+            config.max_blocks,
             config.max_txs,
             config.max_withdrawals,
             config.max_calldata,
         );
+        let block_inputs = self
+            .public_data
+            .blocks
+            .iter()
+            .flat_map(|public_data| {
+                iter::once(public_data.get_block_header_rlp().to_vec()).chain(
+                    public_data
+                        .get_tx_trie_nodes()
+                        .into_iter()
+                        .chain(public_data.get_receipt_trie_nodes())
+                        .chain(public_data.get_withdrawal_trie_nodes())
+                        .map(|node| node.rlp),
+                )
+            })
+            .collect::<Vec<_>>();
+        config.keccak_table.dev_load(
+            &mut layouter,
+            iter::once(&rpi_bytes).chain(block_inputs.iter()),
+            &challenges,
+        )?;
+        // assign receipt table
         config
-            .keccak_table
-            .dev_load(&mut layouter, vec![&rpi_bytes], &challenges)?;
+            .receipt_table
+            .dev_load(&mut layouter, &self.public_data.receipts(), &challenges)?;
+        // The envelopes of the txs bind their chain id to the block table
+        config.block_table.load(
+            &mut layouter,
+            &self
+                .public_data
+                .blocks
+                .iter()
+                .map(|block| BlockContext {
+                    chain_id: block.chain_id,
+                    ..Default::default()
+                })
+                .collect::<Vec<_>>(),
+        )?;
 
         self.synthesize_sub(&config, &challenges, &mut layouter)
     }
@@ -0,0 +1,1050 @@
+//! Verification of the block hash against the RLP encoding of the block header.
+//!
+//! The encoding is laid out one byte per row, each field taking as many rows
+//! as its longest encoding. The fields introduced after the hardfork of the
+//! block, the leading zeros of the integers and the prefix of the single byte
+//! strings are padding, which is skipped by the RLC of the encoding looked up
+//! in the keccak table. The header fields also found in the public inputs are
+//! bound to them, the logs bloom is looked up limb by limb in the receipt
+//! table, and the others are only committed to through the block hash. As the
+//! receipt table holds the logs bloom of all the receipts of a chunk, the logs
+//! bloom of the blocks of a chunk of several blocks is not looked up.
+
+use eth_types::{evm_types::Hardfork, geth_types::BlockHeader, Field, ToBigEndian, U256};
+use gadgets::util::{not, Expr};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, Selector},
+    poly::Rotation,
+};
+use itertools::Itertools;
+
+use crate::{
+    evm_circuit::{
+        param::{N_BYTES_HALF_WORD, N_BYTES_WORD},
+        util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+    },
+    table::{KeccakTable, LookupTable, ReceiptFieldTag, ReceiptTable, N_BYTES_BLOOM_LIMB},
+    util::{word::Word, Challenges},
+};
+
+use super::param::BYTE_POW_BASE;
+
+/// Prefix of the RLP list of the header, whose payload is always between 256
+/// and 65535 bytes long.
+const LIST_PREFIX: u8 = 0xf9;
+const N_ROWS_LIST_PREFIX: usize = 3;
+
+/// Encoding of a header field
+#[derive(Clone, Copy, Debug)]
+enum FieldKind {
+    /// String of the given number of bytes
+    Bytes(usize),
+    /// Integer of at most the given number of bytes, without leading zeros
+    Int(usize),
+    /// String of at most the given number of bytes
+    VarBytes(usize),
+}
+
+/// Fields of the block header, in the order of the RLP encoding
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum HeaderField {
+    ParentHash,
+    UnclesHash,
+    Beneficiary,
+    StateRoot,
+    TransactionsRoot,
+    ReceiptsRoot,
+    LogsBloom,
+    Difficulty,
+    Number,
+    GasLimit,
+    GasUsed,
+    Timestamp,
+    ExtraData,
+    MixHash,
+    Nonce,
+    BaseFee,
+    WithdrawalsRoot,
+    BlobGasUsed,
+    ExcessBlobGas,
+    ParentBeaconBlockRoot,
+}
+
+const HEADER_FIELDS: [HeaderField; 20] = [
+    HeaderField::ParentHash,
+    HeaderField::UnclesHash,
+    HeaderField::Beneficiary,
+    HeaderField::StateRoot,
+    HeaderField::TransactionsRoot,
+    HeaderField::ReceiptsRoot,
+    HeaderField::LogsBloom,
+    HeaderField::Difficulty,
+    HeaderField::Number,
+    HeaderField::GasLimit,
+    HeaderField::GasUsed,
+    HeaderField::Timestamp,
+    HeaderField::ExtraData,
+    HeaderField::MixHash,
+    HeaderField::Nonce,
+    HeaderField::BaseFee,
+    HeaderField::WithdrawalsRoot,
+    HeaderField::BlobGasUsed,
+    HeaderField::ExcessBlobGas,
+    HeaderField::ParentBeaconBlockRoot,
+];
+
+impl HeaderField {
+    fn kind(&self) -> FieldKind {
+        match self {
+            Self::ParentHash
+            | Self::UnclesHash
+            | Self::StateRoot
+            | Self::TransactionsRoot
+            | Self::ReceiptsRoot
+            | Self::MixHash
+            | Self::WithdrawalsRoot
+            | Self::ParentBeaconBlockRoot => FieldKind::Bytes(32),
+            Self::Beneficiary => FieldKind::Bytes(20),
+            Self::LogsBloom => FieldKind::Bytes(256),
+            Self::Nonce => FieldKind::Bytes(8),
+            Self::Difficulty | Self::BaseFee => FieldKind::Int(32),
+            Self::Number
+            | Self::GasLimit
+            | Self::GasUsed
+            | Self::Timestamp
+            | Self::BlobGasUsed
+            | Self::ExcessBlobGas => FieldKind::Int(8),
+            Self::ExtraData => FieldKind::VarBytes(32),
+        }
+    }
+
+    /// Hardfork that introduced the field
+    fn hardfork(&self) -> Hardfork {
+        match self {
+            Self::BaseFee => Hardfork::London,
+            Self::WithdrawalsRoot => Hardfork::Shanghai,
+            Self::BlobGasUsed | Self::ExcessBlobGas | Self::ParentBeaconBlockRoot => {
+                Hardfork::Cancun
+            }
+            _ => Hardfork::Berlin,
+        }
+    }
+
+    /// Maximum length of the content of the field
+    fn max_len(&self) -> usize {
+        match self.kind() {
+            FieldKind::Bytes(n) | FieldKind::Int(n) | FieldKind::VarBytes(n) => n,
+        }
+    }
+
+    /// Constant prefix of the fixed length strings, or `None` for the
+    /// variable length encodings, whose prefix takes a single row.
+    fn prefix(&self) -> Option<Vec<u8>> {
+        match self.kind() {
+            FieldKind::Bytes(n) if n < 56 => Some(vec![0x80 + n as u8]),
+            FieldKind::Bytes(n) => Some(vec![0xb9, (n >> 8) as u8, n as u8]),
+            FieldKind::Int(_) | FieldKind::VarBytes(_) => None,
+        }
+    }
+
+    /// Whether the field is bound to a public input
+    fn is_bound(&self) -> bool {
+        matches!(
+            self,
+            Self::ParentHash
+                | Self::Beneficiary
+                | Self::StateRoot
+                | Self::TransactionsRoot
+                | Self::ReceiptsRoot
+                | Self::Difficulty
+                | Self::Number
+                | Self::GasLimit
+                | Self::Timestamp
+                | Self::MixHash
+                | Self::BaseFee
+                | Self::WithdrawalsRoot
+                | Self::ExcessBlobGas
+        )
+    }
+
+    /// Content of the field in the header
+    fn content(&self, header: &BlockHeader) -> Vec<u8> {
+        let int_bytes = |value: U256| {
+            let bytes = value.to_be_bytes();
+            bytes[N_BYTES_WORD - (value.bits() + 7) / 8..].to_vec()
+        };
+        match self {
+            Self::ParentHash => header.parent_hash.as_bytes().to_vec(),
+            Self::UnclesHash => header.uncles_hash.as_bytes().to_vec(),
+            Self::Beneficiary => header.beneficiary.as_bytes().to_vec(),
+            Self::StateRoot => header.state_root.as_bytes().to_vec(),
+            Self::TransactionsRoot => header.transactions_root.as_bytes().to_vec(),
+            Self::ReceiptsRoot => header.receipts_root.as_bytes().to_vec(),
+            Self::LogsBloom => header.logs_bloom.as_bytes().to_vec(),
+            Self::Difficulty => int_bytes(header.difficulty),
+            Self::Number => int_bytes(header.number.as_u64().into()),
+            Self::GasLimit => int_bytes(header.gas_limit),
+            Self::GasUsed => int_bytes(header.gas_used),
+            Self::Timestamp => int_bytes(header.timestamp),
+            Self::ExtraData => header.extra_data.to_vec(),
+            Self::MixHash => header.mix_hash.as_bytes().to_vec(),
+            Self::Nonce => header.nonce.as_bytes().to_vec(),
+            Self::BaseFee => int_bytes(header.base_fee),
+            Self::WithdrawalsRoot => header.withdrawals_root.as_bytes().to_vec(),
+            Self::BlobGasUsed => int_bytes(header.blob_gas_used.as_u64().into()),
+            Self::ExcessBlobGas => int_bytes(header.excess_blob_gas.as_u64().into()),
+            Self::ParentBeaconBlockRoot => header.parent_beacon_block_root.as_bytes().to_vec(),
+        }
+    }
+}
+
+/// Public input cells the header is verified against
+#[derive(Clone, Debug)]
+pub(super) struct HeaderPiCells<F: Field> {
+    /// Hardfork of the block
+    pub(super) hardfork: AssignedCell<F, F>,
+    /// Hash of the block
+    pub(super) block_hash: Word<AssignedCell<F, F>>,
+    /// Latest of the history hashes
+    pub(super) parent_hash: Word<AssignedCell<F, F>>,
+    pub(super) coinbase: Word<AssignedCell<F, F>>,
+    pub(super) state_root: Word<AssignedCell<F, F>>,
+    /// Root of the transactions trie, verified against the tx table
+    pub(super) transactions_root: Word<AssignedCell<F, F>>,
+    /// Root of the receipts trie, verified against the receipt table
+    pub(super) receipts_root: Word<AssignedCell<F, F>>,
+    /// Difficulty before the merge, PREVRANDAO after
+    pub(super) difficulty: Word<AssignedCell<F, F>>,
+    pub(super) number: Word<AssignedCell<F, F>>,
+    pub(super) gas_limit: Word<AssignedCell<F, F>>,
+    pub(super) timestamp: Word<AssignedCell<F, F>>,
+    pub(super) base_fee: Word<AssignedCell<F, F>>,
+    pub(super) withdrawals_root: Word<AssignedCell<F, F>>,
+    pub(super) excess_blob_gas: Word<AssignedCell<F, F>>,
+}
+
+impl<F: Field> HeaderPiCells<F> {
+    /// Public input a bound field is bound to
+    fn field(&self, field: HeaderField) -> &Word<AssignedCell<F, F>> {
+        match field {
+            HeaderField::ParentHash => &self.parent_hash,
+            HeaderField::Beneficiary => &self.coinbase,
+            HeaderField::StateRoot => &self.state_root,
+            HeaderField::TransactionsRoot => &self.transactions_root,
+            HeaderField::ReceiptsRoot => &self.receipts_root,
+            HeaderField::Difficulty | HeaderField::MixHash => &self.difficulty,
+            HeaderField::Number => &self.number,
+            HeaderField::GasLimit => &self.gas_limit,
+            HeaderField::Timestamp => &self.timestamp,
+            HeaderField::BaseFee => &self.base_fee,
+            HeaderField::WithdrawalsRoot => &self.withdrawals_root,
+            HeaderField::ExcessBlobGas => &self.excess_blob_gas,
+            _ => unreachable!("{:?} is not bound to a public input", field),
+        }
+    }
+}
+
+/// Witness of a row of the header
+#[derive(Clone, Debug, Default)]
+struct HeaderRow {
+    byte: u8,
+    is_padding: bool,
+    is_present: bool,
+    is_short: bool,
+    is_field_start: bool,
+    is_field_end: bool,
+    is_var: bool,
+    constant: Option<u8>,
+    fork: u64,
+    is_value_start: bool,
+    /// Field and limb (0 for lo, 1 for hi) of the value ending at the row
+    value_end: Option<(HeaderField, usize)>,
+    /// Index of the logs bloom limb ending at the row
+    bloom_limb_end: Option<usize>,
+    is_difficulty: bool,
+}
+
+/// Lays out the RLP encoding of the header in the given hardfork
+fn header_rows(header: &BlockHeader, hardfork: Hardfork) -> Vec<HeaderRow> {
+    let rlp = header.rlp(hardfork);
+    assert_eq!(rlp[0], LIST_PREFIX, "header payload length out of range");
+
+    let mut rows = vec![
+        HeaderRow {
+            byte: LIST_PREFIX,
+            is_present: true,
+            is_field_start: true,
+            constant: Some(LIST_PREFIX),
+            is_value_start: true,
+            ..Default::default()
+        },
+        HeaderRow {
+            byte: rlp[1],
+            is_present: true,
+            ..Default::default()
+        },
+        HeaderRow {
+            byte: rlp[2],
+            is_present: true,
+            is_field_end: true,
+            ..Default::default()
+        },
+    ];
+
+    for field in HEADER_FIELDS {
+        let is_present = hardfork >= field.hardfork();
+        let content = field.content(header);
+        let max_len = field.max_len();
+        assert!(content.len() <= max_len);
+        let start = rows.len();
+
+        match field.prefix() {
+            Some(prefix) => {
+                assert_eq!(content.len(), max_len);
+                rows.extend(
+                    prefix
+                        .iter()
+                        .chain(content.iter())
+                        .enumerate()
+                        .map(|(i, byte)| HeaderRow {
+                            byte: if is_present { *byte } else { 0 },
+                            is_padding: !is_present,
+                            constant: (i < prefix.len()).then_some(*byte),
+                            ..Default::default()
+                        }),
+                );
+            }
+            None => {
+                let is_short = content.len() == 1 && content[0] < 0x80;
+                rows.push(HeaderRow {
+                    byte: if is_present && !is_short {
+                        0x80 + content.len() as u8
+                    } else {
+                        0
+                    },
+                    is_padding: !is_present || is_short,
+                    is_short: is_present && is_short,
+                    ..Default::default()
+                });
+                let padding = max_len - content.len();
+                rows.extend(
+                    std::iter::repeat(0)
+                        .take(padding)
+                        .chain(content.iter().copied())
+                        .enumerate()
+                        .map(|(i, byte)| HeaderRow {
+                            byte: if is_present { byte } else { 0 },
+                            is_padding: !is_present || i < padding,
+                            is_short: is_present && is_short,
+                            is_var: true,
+                            ..Default::default()
+                        }),
+                );
+                rows[start].is_var = true;
+            }
+        }
+
+        let end = rows.len() - 1;
+        let content_start = end + 1 - max_len;
+        for row in rows[start..].iter_mut() {
+            row.is_present = is_present;
+            row.fork = field.hardfork().as_u64();
+        }
+        rows[start].is_field_start = true;
+        rows[start].is_difficulty = field == HeaderField::Difficulty;
+        rows[end].is_field_end = true;
+        rows[content_start].is_value_start = true;
+        if max_len > N_BYTES_HALF_WORD {
+            rows[end + 1 - N_BYTES_HALF_WORD].is_value_start = true;
+        }
+        if field == HeaderField::LogsBloom {
+            for (index, limb_start) in (content_start..=end)
+                .step_by(N_BYTES_BLOOM_LIMB)
+                .enumerate()
+            {
+                rows[limb_start].is_value_start = true;
+                rows[limb_start + N_BYTES_BLOOM_LIMB - 1].bloom_limb_end = Some(index);
+            }
+        }
+        if field.is_bound() {
+            rows[end].value_end = Some((field, 0));
+            if max_len > N_BYTES_HALF_WORD {
+                rows[end - N_BYTES_HALF_WORD].value_end = Some((field, 1));
+            }
+        }
+    }
+
+    assert_eq!(
+        rows.iter()
+            .filter(|row| !row.is_padding)
+            .map(|row| row.byte)
+            .collect_vec(),
+        rlp.to_vec(),
+        "header layout must match its RLP encoding"
+    );
+
+    rows
+}
+
+/// Number of rows taken by the header, the keccak lookup excluded
+fn header_len() -> usize {
+    N_ROWS_LIST_PREFIX
+        + HEADER_FIELDS
+            .iter()
+            .map(|field| field.prefix().map_or(1, |prefix| prefix.len()) + field.max_len())
+            .sum::<usize>()
+}
+
+/// Config of the block header verification
+#[derive(Clone, Debug)]
+pub(super) struct HeaderConfig {
+    // q_header: 1 on the rows of the header
+    q_header: Column<Fixed>,
+    // q_header_first: 1 on the first row of the header
+    q_header_first: Column<Fixed>,
+    // q_field_start: 1 on the first row (the prefix) of every field
+    q_field_start: Column<Fixed>,
+    // q_field_end: 1 on the last row of every field
+    q_field_end: Column<Fixed>,
+    // q_var: 1 on the rows of the integers and variable length strings
+    q_var: Column<Fixed>,
+    // q_const: 1 on the constant prefix bytes, whose value is in header_const
+    q_const: Column<Fixed>,
+    header_const: Column<Fixed>,
+    // header_fork: hardfork that introduced the field, on its first row
+    header_fork: Column<Fixed>,
+    // q_value_start: 1 on the first row of every limb of a field value
+    q_value_start: Column<Fixed>,
+    // q_value_end: 1 on the last row of every limb of a field bound to a public input
+    q_value_end: Column<Fixed>,
+    // q_difficulty_end: same as q_value_end for the difficulty, bound before the merge
+    q_difficulty_end: Column<Fixed>,
+    // q_mix_hash_end: same as q_value_end for the mix hash, bound after the merge
+    q_mix_hash_end: Column<Fixed>,
+    // q_bloom_end: 1 on the last row of every limb of the logs bloom, whose
+    // index is in header_bloom_limb
+    q_bloom_end: Column<Fixed>,
+    header_bloom_limb: Column<Fixed>,
+    // q_difficulty: enabled on the first row of the difficulty
+    q_difficulty: Selector,
+    // q_header_hash: enabled on the row following the header, for the keccak lookup
+    q_header_hash: Selector,
+
+    // header_byte: RLP encoding of the header, and padding
+    header_byte: Column<Advice>,
+    header_is_padding: Column<Advice>,
+    // header_rlc: RLC of the non-padding bytes by the keccak challenge
+    header_rlc: Column<Advice>,
+    // header_len: number of non-padding bytes up to the row
+    header_len: Column<Advice>,
+    // header_field_len: number of non-padding bytes of the field content after the row
+    header_field_len: Column<Advice>,
+    // header_value: field value limbs, accumulated with base 256
+    header_value: Column<Advice>,
+    // header_pi_value: public input the value is bound to
+    header_pi_value: Column<Advice>,
+    // header_is_present: whether the field is part of the header in the hardfork
+    header_is_present: Column<Advice>,
+    // header_is_short: whether the field is a single byte encoded as itself
+    header_is_short: Column<Advice>,
+    header_hardfork: Column<Advice>,
+    // header_zero_difficulty: whether the difficulty is zero (after the merge)
+    header_zero_difficulty: Column<Advice>,
+    header_field_len_inv: Column<Advice>,
+}
+
+impl HeaderConfig {
+    /// Configure the block header verification
+    pub(super) fn configure<F: Field>(
+        meta: &mut ConstraintSystem<F>,
+        keccak_table: &KeccakTable,
+        receipt_table: &ReceiptTable,
+        fixed_u16: Column<Fixed>,
+        challenges: &Challenges<Expression<F>>,
+    ) -> Self {
+        let q_header = meta.fixed_column();
+        let q_header_first = meta.fixed_column();
+        let q_field_start = meta.fixed_column();
+        let q_field_end = meta.fixed_column();
+        let q_var = meta.fixed_column();
+        let q_const = meta.fixed_column();
+        let header_const = meta.fixed_column();
+        let header_fork = meta.fixed_column();
+        let q_value_start = meta.fixed_column();
+        let q_value_end = meta.fixed_column();
+        let q_difficulty_end = meta.fixed_column();
+        let q_mix_hash_end = meta.fixed_column();
+        let q_bloom_end = meta.fixed_column();
+        let header_bloom_limb = meta.fixed_column();
+        let q_difficulty = meta.selector();
+        let q_header_hash = meta.complex_selector();
+
+        let header_byte = meta.advice_column();
+        let header_is_padding = meta.advice_column();
+        let header_rlc = meta.advice_column_in(SecondPhase);
+        let header_len = meta.advice_column();
+        let header_field_len = meta.advice_column();
+        let header_value = meta.advice_column();
+        let header_pi_value = meta.advice_column();
+        let header_is_present = meta.advice_column();
+        let header_is_short = meta.advice_column();
+        let header_hardfork = meta.advice_column();
+        let header_zero_difficulty = meta.advice_column();
+        let header_field_len_inv = meta.advice_column();
+
+        meta.enable_equality(header_len);
+        meta.enable_equality(header_pi_value);
+        meta.enable_equality(header_hardfork);
+
+        meta.create_gate("header row", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let q_field_start = meta.query_fixed(q_field_start, Rotation::cur());
+            let q_field_end = meta.query_fixed(q_field_end, Rotation::cur());
+            let q_var = meta.query_fixed(q_var, Rotation::cur());
+            let q_const = meta.query_fixed(q_const, Rotation::cur());
+            let q_value_start = meta.query_fixed(q_value_start, Rotation::cur());
+            let byte = meta.query_advice(header_byte, Rotation::cur());
+            let is_padding = meta.query_advice(header_is_padding, Rotation::cur());
+            let is_present = meta.query_advice(header_is_present, Rotation::cur());
+
+            cb.require_boolean("is_padding is boolean", is_padding.expr());
+            cb.require_boolean("is_present is boolean", is_present.expr());
+            cb.require_boolean(
+                "is_short is boolean",
+                meta.query_advice(header_is_short, Rotation::cur()),
+            );
+            cb.require_zero("padding is zero", is_padding.expr() * byte.expr());
+            cb.require_zero(
+                "absent fields are padding",
+                not::expr(is_present.expr()) * not::expr(is_padding.expr()),
+            );
+            cb.require_zero(
+                "fixed length strings are padding iff absent",
+                not::expr(q_var.expr()) * (is_padding.expr() + is_present - 1.expr()),
+            );
+            cb.require_zero(
+                "constant bytes",
+                q_const
+                    * not::expr(is_padding.expr())
+                    * (byte.expr() - meta.query_fixed(header_const, Rotation::cur())),
+            );
+            cb.require_equal(
+                "value = value_prev * 256 + byte",
+                meta.query_advice(header_value, Rotation::cur()),
+                not::expr(q_value_start)
+                    * meta.query_advice(header_value, Rotation::prev())
+                    * BYTE_POW_BASE.expr()
+                    + byte,
+            );
+            cb.require_equal(
+                "field_len = field_len_next + is content and not padding",
+                meta.query_advice(header_field_len, Rotation::cur()),
+                not::expr(q_field_end) * meta.query_advice(header_field_len, Rotation::next())
+                    + not::expr(q_field_start) * not::expr(is_padding),
+            );
+
+            cb.gate(meta.query_fixed(q_header, Rotation::cur()))
+        });
+
+        meta.create_gate("header first row", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let byte = meta.query_advice(header_byte, Rotation::cur());
+            cb.require_equal(
+                "len = 1",
+                meta.query_advice(header_len, Rotation::cur()),
+                1.expr(),
+            );
+            cb.require_equal(
+                "rlc = byte",
+                meta.query_advice(header_rlc, Rotation::cur()),
+                byte,
+            );
+            // The total length of the header is copied to header_pi_value
+            cb.require_equal(
+                "list prefix is the payload length",
+                meta.query_advice(header_byte, Rotation(1)) * BYTE_POW_BASE.expr()
+                    + meta.query_advice(header_byte, Rotation(2))
+                    + N_ROWS_LIST_PREFIX.expr(),
+                meta.query_advice(header_pi_value, Rotation::cur()),
+            );
+
+            cb.gate(meta.query_fixed(q_header_first, Rotation::cur()))
+        });
+
+        meta.create_gate("header next rows", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let q_field_start = meta.query_fixed(q_field_start, Rotation::cur());
+            let byte = meta.query_advice(header_byte, Rotation::cur());
+            let is_padding = meta.query_advice(header_is_padding, Rotation::cur());
+            let rlc_prev = meta.query_advice(header_rlc, Rotation::prev());
+
+            cb.require_equal(
+                "len = len_prev + is not padding",
+                meta.query_advice(header_len, Rotation::cur()),
+                meta.query_advice(header_len, Rotation::prev()) + not::expr(is_padding.expr()),
+            );
+            cb.require_equal(
+                "rlc = rlc_prev * r + byte, unless padding",
+                meta.query_advice(header_rlc, Rotation::cur()),
+                is_padding.expr() * rlc_prev.expr()
+                    + not::expr(is_padding) * (rlc_prev * challenges.keccak_input() + byte),
+            );
+            for (name, column) in [
+                ("hardfork is constant", header_hardfork),
+                ("zero_difficulty is constant", header_zero_difficulty),
+            ] {
+                cb.require_equal(
+                    name,
+                    meta.query_advice(column, Rotation::cur()),
+                    meta.query_advice(column, Rotation::prev()),
+                );
+            }
+            for (name, column) in [
+                ("is_present is constant in the field", header_is_present),
+                ("is_short is constant in the field", header_is_short),
+            ] {
+                cb.require_zero(
+                    name,
+                    not::expr(q_field_start.expr())
+                        * (meta.query_advice(column, Rotation::cur())
+                            - meta.query_advice(column, Rotation::prev())),
+                );
+            }
+
+            cb.gate(
+                meta.query_fixed(q_header, Rotation::cur())
+                    * not::expr(meta.query_fixed(q_header_first, Rotation::cur())),
+            )
+        });
+
+        meta.create_gate("header field start", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let q_var = meta.query_fixed(q_var, Rotation::cur());
+            let byte = meta.query_advice(header_byte, Rotation::cur());
+            let is_padding = meta.query_advice(header_is_padding, Rotation::cur());
+            let is_present = meta.query_advice(header_is_present, Rotation::cur());
+            let is_short = meta.query_advice(header_is_short, Rotation::cur());
+            let field_len = meta.query_advice(header_field_len, Rotation::cur());
+            let fork_diff = meta.query_advice(header_hardfork, Rotation::cur())
+                - meta.query_fixed(header_fork, Rotation::cur());
+
+            // Hardforks go from 0 (Berlin) to 3 (Cancun)
+            cb.condition(is_present.expr(), |cb| {
+                cb.require_in_set(
+                    "present fields are introduced at or before the hardfork",
+                    fork_diff.expr(),
+                    (0..4).map(|diff| diff.expr()).collect(),
+                );
+            });
+            cb.condition(not::expr(is_present.expr()), |cb| {
+                cb.require_in_set(
+                    "absent fields are introduced after the hardfork",
+                    fork_diff,
+                    (1..4).map(|diff| 0.expr() - diff.expr()).collect(),
+                );
+            });
+            cb.require_equal(
+                "a present integer or string with a padded prefix is short",
+                is_short.expr(),
+                q_var.expr() * is_padding.expr() * is_present.expr(),
+            );
+            cb.require_zero(
+                "prefix is 0x80 + content length",
+                q_var
+                    * is_present
+                    * not::expr(is_padding)
+                    * (byte - 0x80.expr() - field_len.expr()),
+            );
+            cb.require_zero(
+                "short fields are a single byte",
+                is_short * (field_len - 1.expr()),
+            );
+
+            cb.gate(meta.query_fixed(q_field_start, Rotation::cur()))
+        });
+
+        meta.create_gate("header variable length content", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_zero(
+                "padding is leading",
+                not::expr(meta.query_advice(header_is_padding, Rotation::cur()))
+                    * meta.query_advice(header_is_padding, Rotation::next()),
+            );
+
+            cb.gate(
+                meta.query_fixed(q_var, Rotation::cur())
+                    * not::expr(meta.query_fixed(q_field_start, Rotation::cur()))
+                    * not::expr(meta.query_fixed(q_field_end, Rotation::cur())),
+            )
+        });
+
+        meta.create_gate("header values", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let value = meta.query_advice(header_value, Rotation::cur());
+            let pi_value = meta.query_advice(header_pi_value, Rotation::cur());
+            let zero_difficulty = meta.query_advice(header_zero_difficulty, Rotation::cur());
+
+            cb.require_zero(
+                "present fields are the public input",
+                meta.query_fixed(q_value_end, Rotation::cur())
+                    * meta.query_advice(header_is_present, Rotation::cur())
+                    * (value.expr() - pi_value.expr()),
+            );
+            cb.require_zero(
+                "non zero difficulty is the public input",
+                meta.query_fixed(q_difficulty_end, Rotation::cur())
+                    * not::expr(zero_difficulty.expr())
+                    * (value.expr() - pi_value.expr()),
+            );
+            cb.require_zero(
+                "mix hash is the public input when the difficulty is zero",
+                meta.query_fixed(q_mix_hash_end, Rotation::cur())
+                    * zero_difficulty
+                    * (value - pi_value),
+            );
+
+            cb.gate(1.expr())
+        });
+
+        meta.create_gate("header zero difficulty", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let field_len = meta.query_advice(header_field_len, Rotation::cur());
+            let zero_difficulty = meta.query_advice(header_zero_difficulty, Rotation::cur());
+            cb.require_equal(
+                "zero_difficulty = 1 - field_len * field_len_inv",
+                zero_difficulty.expr(),
+                1.expr()
+                    - field_len.expr() * meta.query_advice(header_field_len_inv, Rotation::cur()),
+            );
+            cb.require_zero(
+                "zero_difficulty * field_len = 0",
+                zero_difficulty * field_len,
+            );
+
+            cb.gate(meta.query_selector(q_difficulty))
+        });
+
+        // The bytes are pinned by the keccak lookup, so only the content of the
+        // short fields has to be range checked, against 0x7f.
+        meta.lookup_any("header short field content", |meta| {
+            let condition = meta.query_fixed(q_var, Rotation::cur())
+                * not::expr(meta.query_fixed(q_field_start, Rotation::cur()))
+                * meta.query_advice(header_is_short, Rotation::cur())
+                * not::expr(meta.query_advice(header_is_padding, Rotation::cur()));
+            let byte = meta.query_advice(header_byte, Rotation::cur());
+
+            vec![(
+                condition * (0x7f.expr() - byte),
+                meta.query_fixed(fixed_u16, Rotation::cur()),
+            )]
+        });
+
+        meta.lookup_any("header logs bloom limb", |meta| {
+            let q_bloom_end = meta.query_fixed(q_bloom_end, Rotation::cur());
+            let limb = meta.query_fixed(header_bloom_limb, Rotation::cur());
+            let value = meta.query_advice(header_value, Rotation::cur());
+
+            vec![
+                q_bloom_end.expr() * ReceiptFieldTag::LogsBloom.expr(),
+                q_bloom_end.expr() * limb,
+                q_bloom_end * value,
+                0.expr(),
+            ]
+            .into_iter()
+            .zip_eq(receipt_table.table_exprs(meta))
+            .collect()
+        });
+
+        meta.lookup_any("header keccak lookup", |meta| {
+            let q_header_hash = meta.query_selector(q_header_hash);
+            let is_enabled = meta.query_advice(keccak_table.is_enabled, Rotation::cur());
+            let input_rlc = meta.query_advice(keccak_table.input_rlc, Rotation::cur());
+            let input_len = meta.query_advice(keccak_table.input_len, Rotation::cur());
+            let output_lo = meta.query_advice(keccak_table.output.lo(), Rotation::cur());
+            let output_hi = meta.query_advice(keccak_table.output.hi(), Rotation::cur());
+
+            // block hash lo and hi limbs
+            let hash_lo = meta.query_advice(header_pi_value, Rotation::cur());
+            let hash_hi = meta.query_advice(header_pi_value, Rotation::next());
+
+            vec![
+                (q_header_hash.expr(), is_enabled),
+                (
+                    q_header_hash.expr() * meta.query_advice(header_rlc, Rotation::prev()),
+                    input_rlc,
+                ),
+                (
+                    q_header_hash.expr() * meta.query_advice(header_len, Rotation::prev()),
+                    input_len,
+                ),
+                (q_header_hash.expr() * hash_lo, output_lo),
+                (q_header_hash * hash_hi, output_hi),
+            ]
+        });
+
+        Self {
+            q_header,
+            q_header_first,
+            q_field_start,
+            q_field_end,
+            q_var,
+            q_const,
+            header_const,
+            header_fork,
+            q_value_start,
+            q_value_end,
+            q_difficulty_end,
+            q_mix_hash_end,
+            q_bloom_end,
+            header_bloom_limb,
+            q_difficulty,
+            q_header_hash,
+            header_byte,
+            header_is_padding,
+            header_rlc,
+            header_len,
+            header_field_len,
+            header_value,
+            header_pi_value,
+            header_is_present,
+            header_is_short,
+            header_hardfork,
+            header_zero_difficulty,
+            header_field_len_inv,
+        }
+    }
+
+    /// Assign the header of the block in its hardfork, and bind it to the
+    /// public inputs, and its logs bloom to the receipt table if `with_bloom`
+    pub(super) fn assign<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        header: &BlockHeader,
+        hardfork: Hardfork,
+        pi_cells: &HeaderPiCells<F>,
+        with_bloom: bool,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        let rows = header_rows(header, hardfork);
+        assert_eq!(rows.len(), header_len());
+
+        // field_len is accumulated backward, from the end of every field
+        let mut field_lens = vec![0u64; rows.len()];
+        for (offset, row) in rows.iter().enumerate().rev() {
+            let next = if row.is_field_end {
+                0
+            } else {
+                field_lens[offset + 1]
+            };
+            field_lens[offset] = next + (!row.is_field_start && !row.is_padding) as u64;
+        }
+        let zero_difficulty = header.difficulty.is_zero();
+
+        layouter.assign_region(
+            || "block header",
+            |mut region| {
+                let mut rlc = Value::known(F::ZERO);
+                let mut len = 0u64;
+                let mut value = F::ZERO;
+                let mut len_cell = None;
+
+                for (offset, (row, field_len)) in rows.iter().zip(field_lens.iter()).enumerate() {
+                    let value_end = row.value_end.map(|(field, _)| field);
+                    for (name, column, flag) in [
+                        ("q_header", self.q_header, true),
+                        ("q_header_first", self.q_header_first, offset == 0),
+                        ("q_field_start", self.q_field_start, row.is_field_start),
+                        ("q_field_end", self.q_field_end, row.is_field_end),
+                        ("q_var", self.q_var, row.is_var),
+                        ("q_const", self.q_const, row.constant.is_some()),
+                        ("q_value_start", self.q_value_start, row.is_value_start),
+                        (
+                            "q_value_end",
+                            self.q_value_end,
+                            value_end.is_some_and(|field| {
+                                !matches!(field, HeaderField::Difficulty | HeaderField::MixHash)
+                            }),
+                        ),
+                        (
+                            "q_difficulty_end",
+                            self.q_difficulty_end,
+                            value_end == Some(HeaderField::Difficulty),
+                        ),
+                        (
+                            "q_mix_hash_end",
+                            self.q_mix_hash_end,
+                            value_end == Some(HeaderField::MixHash),
+                        ),
+                        (
+                            "q_bloom_end",
+                            self.q_bloom_end,
+                            with_bloom && row.bloom_limb_end.is_some(),
+                        ),
+                    ] {
+                        region.assign_fixed(
+                            || name,
+                            column,
+                            offset,
+                            || Value::known(F::from(flag as u64)),
+                        )?;
+                    }
+                    region.assign_fixed(
+                        || "header_const",
+                        self.header_const,
+                        offset,
+                        || Value::known(F::from(row.constant.unwrap_or_default() as u64)),
+                    )?;
+                    region.assign_fixed(
+                        || "header_bloom_limb",
+                        self.header_bloom_limb,
+                        offset,
+                        || Value::known(F::from(row.bloom_limb_end.unwrap_or_default() as u64)),
+                    )?;
+                    region.assign_fixed(
+                        || "header_fork",
+                        self.header_fork,
+                        offset,
+                        || Value::known(F::from(row.fork)),
+                    )?;
+                    if row.is_difficulty {
+                        self.q_difficulty.enable(&mut region, offset)?;
+                    }
+
+                    let byte = F::from(row.byte as u64);
+                    if !row.is_padding {
+                        len += 1;
+                        rlc = rlc
+                            .zip(challenges.keccak_input())
+                            .map(|(rlc, r)| rlc * r + byte);
+                    }
+                    value = if row.is_value_start {
+                        byte
+                    } else {
+                        value * F::from(BYTE_POW_BASE) + byte
+                    };
+                    let field_len = F::from(*field_len);
+
+                    for (name, column, value) in [
+                        ("header_byte", self.header_byte, Value::known(byte)),
+                        (
+                            "header_is_padding",
+                            self.header_is_padding,
+                            Value::known(F::from(row.is_padding as u64)),
+                        ),
+                        ("header_rlc", self.header_rlc, rlc),
+                        (
+                            "header_field_len",
+                            self.header_field_len,
+                            Value::known(field_len),
+                        ),
+                        (
+                            "header_field_len_inv",
+                            self.header_field_len_inv,
+                            Value::known(field_len.invert().unwrap_or(F::ZERO)),
+                        ),
+                        ("header_value", self.header_value, Value::known(value)),
+                        (
+                            "header_is_present",
+                            self.header_is_present,
+                            Value::known(F::from(row.is_present as u64)),
+                        ),
+                        (
+                            "header_is_short",
+                            self.header_is_short,
+                            Value::known(F::from(row.is_short as u64)),
+                        ),
+                        (
+                            "header_zero_difficulty",
+                            self.header_zero_difficulty,
+                            Value::known(F::from(zero_difficulty as u64)),
+                        ),
+                    ] {
+                        region.assign_advice(|| name, column, offset, || value)?;
+                    }
+                    len_cell = Some(region.assign_advice(
+                        || "header_len",
+                        self.header_len,
+                        offset,
+                        || Value::known(F::from(len)),
+                    )?);
+
+                    if offset == 0 {
+                        pi_cells.hardfork.copy_advice(
+                            || "header_hardfork",
+                            &mut region,
+                            self.header_hardfork,
+                            offset,
+                        )?;
+                    } else {
+                        region.assign_advice(
+                            || "header_hardfork",
+                            self.header_hardfork,
+                            offset,
+                            || Value::known(F::from(hardfork.as_u64())),
+                        )?;
+                    }
+
+                    match row.value_end {
+                        Some((field, limb)) => {
+                            let word = pi_cells.field(field);
+                            let cell = if limb == 0 { word.lo() } else { word.hi() };
+                            cell.copy_advice(
+                                || "header_pi_value",
+                                &mut region,
+                                self.header_pi_value,
+                                offset,
+                            )?;
+                        }
+                        None if offset > 0 => {
+                            region.assign_advice(
+                                || "header_pi_value",
+                                self.header_pi_value,
+                                offset,
+                                || Value::known(F::ZERO),
+                            )?;
+                        }
+                        None => (),
+                    }
+                }
+
+                // The total length of the header, checked against the list prefix
+                let total_len = region.assign_advice(
+                    || "header_pi_value",
+                    self.header_pi_value,
+                    0,
+                    || Value::known(F::from(len)),
+                )?;
+                region.constrain_equal(
+                    total_len.cell(),
+                    len_cell.expect("header is not empty").cell(),
+                )?;
+
+                // The keccak lookup of the header against the block hash
+                let offset = rows.len();
+                self.q_header_hash.enable(&mut region, offset)?;
+                pi_cells.block_hash.lo().copy_advice(
+                    || "block hash lo",
+                    &mut region,
+                    self.header_pi_value,
+                    offset,
+                )?;
+                pi_cells.block_hash.hi().copy_advice(
+                    || "block hash hi",
+                    &mut region,
+                    self.header_pi_value,
+                    offset + 1,
+                )?;
+
+                Ok(())
+            },
+        )
+    }
+}
@@ -3,10 +3,17 @@ use std::collections::HashMap;
 use crate::{pi_circuit::dev::PiCircuitParams, util::unusable_rows, witness::block_convert};
 
 use super::*;
-use bus_mapping::{
-    circuit_input_builder::FixedCParams, mock::BlockData, state_db::EMPTY_CODE_HASH_LE,
+use bus_mapping::{circuit_input_builder::FixedCParams, mock::BlockData};
+use eth_types::{
+    bytecode,
+    evm_types::{blob_base_fee, Hardfork},
+    geth_types::{
+        GethData, Receipt, ReceiptLog, ACCESS_LIST_TX_TYPE, BLOB_TX_TYPE, DYNAMIC_FEE_TX_TYPE,
+        LEGACY_TX_TYPE,
+    },
+    AccessList, Address, Bytes, ToWord, Word, H160, H256,
 };
-use eth_types::{bytecode, geth_types::GethData, Address, Word, H160, H256};
+use ethers_core::types::transaction::eip2930::AccessListItem;
 use ethers_signers::{LocalWallet, Signer};
 use halo2_proofs::{
     dev::{MockProver, VerifyFailure},
@@ -21,13 +28,35 @@ fn pi_circuit_unusable_rows() {
     assert_eq!(
         PiCircuit::<Fr>::unusable_rows(),
         unusable_rows::<Fr, PiCircuit::<Fr>>(PiCircuitParams {
+            max_blocks: 1,
             max_txs: 2,
             max_withdrawals: 5,
             max_calldata: 8,
+            max_log_bytes: 64,
         }),
     )
 }
 
+const MAX_LOG_BYTES: usize = 256;
+
+/// Give a successful receipt without logs to the transactions which have none
+fn with_receipts(mut public_data: PublicData) -> PublicData {
+    for (i, tx) in public_data
+        .transactions
+        .iter()
+        .enumerate()
+        .skip(public_data.receipts.len())
+    {
+        public_data.receipts.push(Receipt {
+            tx_type: tx.transaction_type.as_u64(),
+            status: true,
+            cumulative_gas_used: 21000 * (i as u64 + 1),
+            logs: vec![],
+        });
+    }
+    public_data
+}
+
 fn run<F: Field>(
     k: u32,
     max_txs: usize,
@@ -35,10 +64,16 @@ fn run<F: Field>(
     max_calldata: usize,
     public_data: PublicData,
 ) -> Result<(), Vec<VerifyFailure>> {
-    let mut public_data = public_data;
+    let mut public_data = with_receipts(public_data);
     public_data.chain_id = *MOCK_CHAIN_ID;
 
-    let circuit = PiCircuit::<F>::new(max_txs, max_withdrawals, max_calldata, public_data);
+    let circuit = PiCircuit::<F>::new(
+        max_txs,
+        max_withdrawals,
+        max_calldata,
+        MAX_LOG_BYTES,
+        public_data,
+    );
 
     let public_inputs = circuit.instance();
 
@@ -86,6 +121,386 @@ fn test_simple_pi() {
     );
 }
 
+#[test]
+fn test_blob_tx_pi() {
+    let max_txs = 4;
+    let max_withdrawals = 2;
+    let max_calldata = 200;
+
+    let mut public_data = PublicData::default();
+    public_data.block_constants.excess_blob_gas = 0x60000u64.into();
+    public_data.block_constants.blob_base_fee = blob_base_fee(0x60000);
+
+    let mut blob_tx = CORRECT_MOCK_TXS[1].clone();
+    blob_tx
+        .transaction_type(BLOB_TX_TYPE)
+        .max_fee_per_blob_gas(Word::from(10))
+        .blob_versioned_hashes(vec![H256::repeat_byte(0x01), H256::repeat_byte(0x02)]);
+    public_data
+        .transactions
+        .push(CORRECT_MOCK_TXS[0].clone().into());
+    public_data.transactions.push(blob_tx.into());
+
+    let k = 17;
+    assert_eq!(
+        run::<Fr>(k, max_txs, max_withdrawals, max_calldata, public_data),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_hardfork_pi() {
+    let max_txs = 2;
+    let max_withdrawals = 2;
+    let max_calldata = 20;
+
+    let mut public_data = PublicData::default();
+    public_data.block_constants.hardfork = Hardfork::Shanghai;
+    public_data
+        .transactions
+        .push(CORRECT_MOCK_TXS[0].clone().into());
+
+    let k = 17;
+    assert_eq!(
+        run::<Fr>(k, max_txs, max_withdrawals, max_calldata, public_data),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_pre_merge_header_pi() {
+    let max_txs = 2;
+    let max_withdrawals = 2;
+    let max_calldata = 20;
+
+    let mut public_data = PublicData::default();
+    public_data.history_hashes = vec![Word::from(0xcafeu64)];
+    public_data.block_constants.hardfork = Hardfork::London;
+    public_data.block_constants.number = 0xd0b0f0u64.into();
+    public_data.block_constants.difficulty = Word::from(0x2c2a6f5fe9b3au64);
+    public_data.block_constants.base_fee = Word::from(0x3b9aca00u64);
+    public_data.header.difficulty = public_data.block_constants.difficulty;
+    public_data.header.mix_hash = H256::repeat_byte(0x42);
+    public_data.header.extra_data = Bytes::from(b"zkevm".to_vec());
+    // single byte integer encoded as itself
+    public_data.header.gas_used = Word::from(0x7fu64);
+
+    let k = 17;
+    assert_eq!(
+        run::<Fr>(k, max_txs, max_withdrawals, max_calldata, public_data),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_wrong_block_hash_pi() {
+    let max_txs = 2;
+    let max_withdrawals = 2;
+    let max_calldata = 20;
+
+    let mut public_data = PublicData::default();
+    public_data.block_hash = Some(H256::repeat_byte(0xab));
+
+    let k = 17;
+    assert!(run::<Fr>(k, max_txs, max_withdrawals, max_calldata, public_data).is_err());
+}
+
+#[test]
+fn test_wrong_header_difficulty_pi() {
+    let max_txs = 2;
+    let max_withdrawals = 2;
+    let max_calldata = 20;
+
+    let mut public_data = PublicData::default();
+    public_data.block_constants.difficulty = Word::from(2u64);
+    public_data.header.difficulty = Word::from(1u64);
+
+    let k = 17;
+    assert!(run::<Fr>(k, max_txs, max_withdrawals, max_calldata, public_data).is_err());
+}
+
+fn run_chunk<F: Field>(
+    k: u32,
+    max_blocks: usize,
+    max_txs: usize,
+    max_withdrawals: usize,
+    max_calldata: usize,
+    blocks: Vec<PublicData>,
+) -> Result<(), Vec<VerifyFailure>> {
+    let circuit = PiCircuit::<F>::new_chunk(
+        max_blocks,
+        max_txs,
+        max_withdrawals,
+        max_calldata,
+        MAX_LOG_BYTES,
+        ChunkPublicData { blocks },
+    );
+
+    let public_inputs = circuit.instance();
+
+    let prover = match MockProver::run(k, &circuit, public_inputs) {
+        Ok(prover) => prover,
+        Err(e) => panic!("{:#?}", e),
+    };
+    prover.verify()
+}
+
+/// Two consecutive blocks with a tx each, where the second one is chained to
+/// the first one
+fn chunk_public_data() -> Vec<PublicData> {
+    let mut first = PublicData {
+        chain_id: *MOCK_CHAIN_ID,
+        ..Default::default()
+    };
+    first.block_constants.number = 0xcafeu64.into();
+    first.state_root = H256::repeat_byte(0x01);
+    first.transactions.push(CORRECT_MOCK_TXS[0].clone().into());
+    let first = with_receipts(first);
+
+    let mut second = PublicData {
+        chain_id: *MOCK_CHAIN_ID,
+        ..Default::default()
+    };
+    second.block_constants.number = 0xcaffu64.into();
+    second.history_hashes = vec![first.get_extra_values().block_hash.to_word()];
+    second.prev_state_root = first.state_root;
+    second.state_root = H256::repeat_byte(0x02);
+    second.transactions.push(CORRECT_MOCK_TXS[1].clone().into());
+    let second = with_receipts(second);
+
+    vec![first, second]
+}
+
+#[test]
+fn test_chunk_pi() {
+    let max_blocks = 3;
+    let max_txs = 4;
+    let max_withdrawals = 2;
+    let max_calldata = 200;
+
+    let k = 17;
+    assert_eq!(
+        run_chunk::<Fr>(
+            k,
+            max_blocks,
+            max_txs,
+            max_withdrawals,
+            max_calldata,
+            chunk_public_data()
+        ),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_unchained_state_root_chunk_pi() {
+    let max_blocks = 2;
+    let max_txs = 4;
+    let max_withdrawals = 2;
+    let max_calldata = 200;
+
+    let mut blocks = chunk_public_data();
+    blocks[1].prev_state_root = H256::zero();
+
+    let k = 17;
+    assert!(run_chunk::<Fr>(
+        k,
+        max_blocks,
+        max_txs,
+        max_withdrawals,
+        max_calldata,
+        blocks
+    )
+    .is_err());
+}
+
+#[test]
+fn test_unchained_parent_hash_chunk_pi() {
+    let max_blocks = 2;
+    let max_txs = 4;
+    let max_withdrawals = 2;
+    let max_calldata = 200;
+
+    let mut blocks = chunk_public_data();
+    blocks[1].history_hashes = vec![Word::from(0xcafeu64)];
+
+    let k = 17;
+    assert!(run_chunk::<Fr>(
+        k,
+        max_blocks,
+        max_txs,
+        max_withdrawals,
+        max_calldata,
+        blocks
+    )
+    .is_err());
+}
+
+#[test]
+fn test_wrong_header_second_block_chunk_pi() {
+    let max_blocks = 2;
+    let max_txs = 4;
+    let max_withdrawals = 2;
+    let max_calldata = 200;
+
+    let mut blocks = chunk_public_data();
+    blocks[1].block_constants.difficulty = Word::from(2u64);
+    blocks[1].header.difficulty = Word::from(1u64);
+
+    let k = 17;
+    assert!(run_chunk::<Fr>(
+        k,
+        max_blocks,
+        max_txs,
+        max_withdrawals,
+        max_calldata,
+        blocks
+    )
+    .is_err());
+}
+
+#[test]
+fn test_tx_trie_pi() {
+    let max_txs = 8;
+    let max_withdrawals = 2;
+    let max_calldata = 200;
+
+    let mut public_data = PublicData::default();
+    public_data.block_constants.excess_blob_gas = 0x60000u64.into();
+    public_data.block_constants.blob_base_fee = blob_base_fee(0x60000);
+
+    // Leaves of every transaction type below two levels of branches
+    for (i, tx_type) in [
+        LEGACY_TX_TYPE,
+        ACCESS_LIST_TX_TYPE,
+        DYNAMIC_FEE_TX_TYPE,
+        BLOB_TX_TYPE,
+        DYNAMIC_FEE_TX_TYPE,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let mut tx = CORRECT_MOCK_TXS[i % CORRECT_MOCK_TXS.len()].clone();
+        tx.transaction_type(tx_type);
+        if tx_type == BLOB_TX_TYPE {
+            tx.max_fee_per_blob_gas(Word::from(10))
+                .blob_versioned_hashes(vec![H256::repeat_byte(0x01)]);
+        }
+        public_data.transactions.push(tx.into());
+    }
+
+    let k = 17;
+    assert_eq!(
+        run::<Fr>(k, max_txs, max_withdrawals, max_calldata, public_data),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_tx_trie_zero_caller_pi() {
+    let max_txs = 2;
+    let max_withdrawals = 2;
+    let max_calldata = 20;
+
+    // A transaction without caller is padding, which can't be in the trie
+    let mut tx = CORRECT_MOCK_TXS[0].clone();
+    tx.from(Address::zero());
+    let mut public_data = PublicData::default();
+    public_data.transactions.push(tx.into());
+
+    let k = 17;
+    assert!(run::<Fr>(k, max_txs, max_withdrawals, max_calldata, public_data).is_err());
+}
+
+#[test]
+fn test_tx_trie_envelope_overflow_pi() {
+    let max_txs = 2;
+    let max_withdrawals = 2;
+    let max_calldata = 20;
+
+    // An access list of 30 addresses takes more than the bytes supported for
+    // the envelope of a transaction, even if the trie fits the capacity
+    let mut tx = CORRECT_MOCK_TXS[0].clone();
+    tx.transaction_type(ACCESS_LIST_TX_TYPE)
+        .access_list(AccessList(
+            (0..30)
+                .map(|i| AccessListItem {
+                    address: Address::repeat_byte(i),
+                    storage_keys: vec![],
+                })
+                .collect(),
+        ));
+    let mut public_data = PublicData {
+        chain_id: *MOCK_CHAIN_ID,
+        ..Default::default()
+    };
+    public_data.transactions.push(tx.into());
+    let circuit = PiCircuit::<Fr>::new(
+        max_txs,
+        max_withdrawals,
+        max_calldata,
+        MAX_LOG_BYTES,
+        with_receipts(public_data),
+    );
+    let public_inputs = circuit.instance();
+
+    let k = 17;
+    assert!(MockProver::run(k, &circuit, public_inputs).is_err());
+}
+
+#[test]
+fn test_receipt_trie_pi() {
+    let max_txs = 4;
+    let max_withdrawals = 2;
+    let max_calldata = 200;
+
+    let mut public_data = PublicData::default();
+    for tx in CORRECT_MOCK_TXS[..3].iter() {
+        public_data.transactions.push(tx.clone().into());
+    }
+    public_data.receipts = vec![
+        Receipt {
+            tx_type: LEGACY_TX_TYPE,
+            status: true,
+            cumulative_gas_used: 21000,
+            logs: vec![ReceiptLog {
+                address: MOCK_ACCOUNTS[0],
+                topics: vec![H256::repeat_byte(0x11), H256::repeat_byte(0x22)],
+                data: Bytes::from(vec![0xaa; 64]),
+            }],
+        },
+        Receipt {
+            tx_type: LEGACY_TX_TYPE,
+            status: false,
+            cumulative_gas_used: 50000,
+            logs: vec![],
+        },
+        Receipt {
+            tx_type: LEGACY_TX_TYPE,
+            status: true,
+            cumulative_gas_used: 80000,
+            logs: vec![
+                ReceiptLog {
+                    address: MOCK_ACCOUNTS[1],
+                    topics: vec![],
+                    data: Bytes::from(vec![0x01]),
+                },
+                ReceiptLog {
+                    address: MOCK_ACCOUNTS[2],
+                    topics: vec![H256::repeat_byte(0x33)],
+                    data: Bytes::default(),
+                },
+            ],
+        },
+    ];
+
+    let k = 17;
+    assert_eq!(
+        run::<Fr>(k, max_txs, max_withdrawals, max_calldata, public_data),
+        Ok(())
+    );
+}
+
 #[test]
 fn test_1tx_1maxtx() {
     const MAX_TXS: usize = 1;
@@ -117,12 +532,7 @@ fn test_1tx_1maxtx() {
                 .input(calldata.into())
                 .gas((1e16 as u64).into());
         },
-        |block, _txs| {
-            block
-                .number(0xcafeu64)
-                .chain_id(*MOCK_CHAIN_ID)
-                .withdrawal_hash(Some(H256::from(*EMPTY_CODE_HASH_LE)))
-        },
+        |block, _txs| block.number(0xcafeu64).chain_id(*MOCK_CHAIN_ID),
     )
     .unwrap();
     let mut wallets = HashMap::new();
@@ -197,12 +607,7 @@ fn test_1wd_1wdmax() {
                 .address(Address::random())
                 .amount(100);
         },
-        |block, _txs| {
-            block
-                .number(0xcafeu64)
-                .chain_id(*MOCK_CHAIN_ID)
-                .withdrawal_hash(Some(H256::from_low_u64_le(0xabcd)))
-        },
+        |block, _txs| block.number(0xcafeu64).chain_id(*MOCK_CHAIN_ID),
     )
     .unwrap();
     let mut wallets = HashMap::new();
@@ -239,6 +644,62 @@ fn test_1wd_1wdmax() {
     assert_eq!(prover.verify(), Ok(()));
 }
 
+/// Withdrawals with small indices and amounts, whose leaves are shorter
+/// than 32 bytes
+fn small_withdrawals(n: u64) -> PublicData {
+    PublicData {
+        withdrawals: (0..n)
+            .map(|i| Withdrawal {
+                id: i,
+                validator_id: i,
+                address: Address::repeat_byte(0x01),
+                amount: 100,
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_small_wd_trie_pi() {
+    let max_txs = 2;
+    let max_withdrawals = 2;
+    let max_calldata = 8;
+
+    // A single short leaf is the root of the trie, which is always hashed
+    let k = 17;
+    assert_eq!(
+        run::<Fr>(
+            k,
+            max_txs,
+            max_withdrawals,
+            max_calldata,
+            small_withdrawals(1)
+        ),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_embedded_wd_trie_pi() {
+    let max_txs = 2;
+    let max_withdrawals = 2;
+    let max_calldata = 8;
+
+    // Short leaves below a branch are embedded in it, which is not supported
+    let circuit = PiCircuit::<Fr>::new(
+        max_txs,
+        max_withdrawals,
+        max_calldata,
+        MAX_LOG_BYTES,
+        small_withdrawals(2),
+    );
+    let public_inputs = circuit.instance();
+
+    let k = 17;
+    assert!(MockProver::run(k, &circuit, public_inputs).is_err());
+}
+
 fn run_size_check<F: Field>(
     max_txs: usize,
     max_withdrawals: usize,
@@ -249,7 +710,8 @@ fn run_size_check<F: Field>(
         max_txs,
         max_withdrawals,
         max_calldata,
-        public_data[0].clone(),
+        MAX_LOG_BYTES,
+        with_receipts(public_data[0].clone()),
     );
     let public_inputs = circuit.instance();
     let prover1 = MockProver::run(20, &circuit, public_inputs).unwrap();
@@ -258,7 +720,8 @@ fn run_size_check<F: Field>(
         max_txs,
         max_withdrawals,
         max_calldata,
-        public_data[1].clone(),
+        MAX_LOG_BYTES,
+        with_receipts(public_data[1].clone()),
     );
     let public_inputs = circuit2.instance();
     let prover2 = MockProver::run(20, &circuit, public_inputs).unwrap();
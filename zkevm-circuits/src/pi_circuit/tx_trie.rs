@@ -0,0 +1,2099 @@
+//! Verification of the transactions root against the tx table.
+//!
+//! The nodes of the transactions trie are laid out one byte per row, the root
+//! first, and parsed as RLP lists of strings: branches with 16 references and
+//! an empty value, extensions and leaves with a hex prefix encoded path. The
+//! hash of every node is looked up in the keccak table, every reference is
+//! looked up in the nodes together with the path of the child, and every node
+//! is looked up in the references, so that the nodes are exactly the trie
+//! below the transactions root. Nodes shorter than 32 bytes are embedded in
+//! their parent, which never happens in the transactions trie and is not
+//! supported.
+//!
+//! The leaves come in the order of their index, the path of each leaf being
+//! the RLP encoding of its index, and the value of a leaf is bound to the
+//! transaction of the tx table with the same index (its id minus one), offset
+//! by the transactions of the previous blocks of the chunk. Every block has
+//! its own trie, laid out after the trie of the previous block with the index
+//! of the block, in which the references are resolved. The id offset of the
+//! leaves is constant in a trie, zero for the first block and the id offset
+//! plus the number of leaves of the previous block for the others. The number
+//! of leaves of all the blocks is the number of transactions of the tx table,
+//! padding transactions having no caller. The value of every leaf is the signed
+//! envelope of the transaction, which is decoded by the [`TxRlpConfig`] of
+//! the tx circuit to bind all its fields to the tx table and its chain id to
+//! the block table.
+//!
+//! The same layout verifies the receipts root against the receipt table: the
+//! receipts trie has the same keys and number of leaves as the transactions
+//! trie, the value of every leaf being the receipt envelope of the
+//! transaction, which starts with the same type. The RLC and length of the
+//! value of every leaf are then looked up in the receipt table. The id offset
+//! of the receipts of a block is the one of its transactions.
+//!
+//! The same layout also verifies the withdrawals root against the wd table:
+//! the value of every leaf is the RLP list of the index, the validator index,
+//! the address and the amount of a withdrawal, which is decoded and looked up
+//! in the wd table with the index of the leaf. The withdrawal after the last
+//! leaf of the chunk is looked up with all its fields zero, as for the
+//! transactions, and the id offset of every block is also its withdrawal
+//! offset in the block table. Small withdrawals can make leaves shorter than
+//! 32 bytes, which doesn't happen with the validator indices and amounts of
+//! mainnet: a single one is the root, which is always hashed, but several of
+//! them are embedded in their parent and rejected, as the references must be
+//! hashes.
+
+use eth_types::{
+    evm_types::MAX_BLOBS_PER_BLOCK,
+    trie::{ordered_trie_key, to_nibbles, TrieNode, TrieNodeKind},
+    Address, Field, H256,
+};
+use gadgets::util::{not, Expr};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, VirtualCells,
+    },
+    poly::Rotation,
+};
+use log::error;
+use std::iter;
+
+use crate::{
+    evm_circuit::{
+        param::N_BYTES_WORD,
+        util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+    },
+    receipt_circuit::N_BYTES_RECEIPT,
+    table::{BlockTable, KeccakTable, ReceiptFieldTag, ReceiptTable, TxFieldTag, TxTable, WdTable},
+    tx_circuit::rlp::{tx_rlp_rows, TxRlpConfig, TxRlpRow},
+    util::{word::Word, Challenges},
+};
+
+use super::param::BYTE_POW_BASE;
+
+/// Maximum length of a transaction envelope besides its calldata and blob
+/// versioned hashes, which leaves room for a small access list
+const N_BYTES_TX_ENVELOPE: usize = 600;
+/// Maximum length of a branch: list prefix, 16 references and empty value
+const N_BYTES_BRANCH: usize = 3 + 16 * (1 + N_BYTES_WORD) + 1;
+/// Maximum length of an extension: list prefix, path of at most 6 nibbles
+/// and reference
+const N_BYTES_EXTENSION: usize = 1 + 5 + 1 + N_BYTES_WORD;
+/// Maximum length of a leaf besides its value: list prefix, path of at most
+/// 6 nibbles and value prefix
+const N_BYTES_LEAF: usize = 3 + 5 + 3;
+/// Maximum length of the RLP encoding of a withdrawal: list prefix, index,
+/// validator index, address and amount
+const N_BYTES_WD: usize = 1 + 9 + 9 + 21 + 9;
+/// Number of RLP items of a withdrawal
+const N_WD_FIELDS: usize = 4;
+
+/// Number of rows taken by the transactions trie. A trie has fewer inner
+/// nodes than leaves, so every transaction accounts for at most a branch, an
+/// extension and a leaf.
+pub(super) fn tx_trie_len(txs: usize, calldata: usize) -> usize {
+    1 + txs * (N_BYTES_LEAF + N_BYTES_TX_ENVELOPE + N_BYTES_BRANCH + N_BYTES_EXTENSION)
+        + calldata
+        + MAX_BLOBS_PER_BLOCK * (1 + N_BYTES_WORD)
+}
+
+/// Number of rows taken by the receipts trie, whose leaves hold the receipt
+/// envelopes instead of the transaction envelopes
+pub(super) fn receipt_trie_len(txs: usize, log_bytes: usize) -> usize {
+    1 + txs * (N_BYTES_LEAF + N_BYTES_RECEIPT + N_BYTES_BRANCH + N_BYTES_EXTENSION) + log_bytes
+}
+
+/// Number of rows taken by the withdrawals trie, with a row after the last
+/// leaf to look up the withdrawal that follows it
+pub(super) fn wd_trie_len(withdrawals: usize) -> usize {
+    2 + withdrawals * (N_BYTES_LEAF + N_BYTES_WD + N_BYTES_BRANCH + N_BYTES_EXTENSION)
+}
+
+/// Leaves of a trie verified by [`TxTrieConfig`]
+#[derive(Clone, Copy, Debug)]
+pub(super) enum TrieLeaves<'a> {
+    /// Transaction envelopes, bound to the tx table and the block table
+    Txs(&'a BlockTable),
+    /// Receipt envelopes, bound to the tx table and the receipt table
+    Receipts(&'a ReceiptTable),
+    /// Withdrawals, bound to the wd table
+    Withdrawals(&'a WdTable),
+}
+
+/// Tag of a row of the trie
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum RowTag {
+    /// Prefix of the RLP list of a node
+    NodeStart,
+    /// Length of the payload of a long list
+    ListLen,
+    /// Prefix of a string
+    ItemPrefix,
+    /// Length of a long string
+    ItemLen,
+    /// Content of a string, or a single byte encoded as itself
+    Content,
+    /// Row after the last node
+    #[default]
+    Padding,
+}
+
+/// Witness of a row of the trie
+#[derive(Clone, Debug, Default)]
+struct TrieRow {
+    byte: u8,
+    tag: RowTag,
+    is_single: bool,
+    is_long: bool,
+    is_node_end: bool,
+    kind: Option<TrieNodeKind>,
+    /// Accumulated nibbles and number of nibbles of the path of the node
+    node_path: (u64, u64),
+    /// Same as node_path, extended by the path of the node up to the row
+    path: (u64, u64),
+    /// Number of bytes of the node up to the row
+    len: u64,
+    len_rem: u64,
+    len_acc: u64,
+    item_rem: u64,
+    item_count: u64,
+    node_rem: u64,
+    is_hp: bool,
+    is_hp_first: bool,
+    is_odd: bool,
+    nib_lo: u8,
+    is_ref: bool,
+    is_lo: bool,
+    ref_acc: u128,
+    ref_hi: u128,
+    is_ref_end: bool,
+    child_path: (u64, u64),
+    is_value_first: bool,
+    is_typed: bool,
+    tx_type: u8,
+    hash: H256,
+    leaf_count: u64,
+    caller: Address,
+    /// Decoding of the transaction envelope, on the rows of the value
+    tx_rlp: TxRlpRow,
+}
+
+impl TrieRow {
+    /// Whether the row is a byte of the value of a leaf
+    fn is_value(&self) -> bool {
+        self.kind == Some(TrieNodeKind::Leaf) && self.tag == RowTag::Content && self.item_count == 2
+    }
+}
+
+/// Accumulate nibbles with base 16
+fn nibbles_acc(nibbles: &[u8]) -> u64 {
+    nibbles
+        .iter()
+        .fold(0, |acc, nibble| acc * 16 + *nibble as u64)
+}
+
+/// Push the row of the next byte of a node, carrying the node, the path and
+/// the item count of the previous row
+fn push_row(rows: &mut Vec<TrieRow>, byte: u8, tag: RowTag) -> &mut TrieRow {
+    let prev = rows.last().expect("node start is pushed first");
+    let row = TrieRow {
+        byte,
+        tag,
+        kind: prev.kind,
+        node_path: prev.node_path,
+        path: prev.path,
+        len: prev.len + 1,
+        item_count: prev.item_count + (tag == RowTag::ItemPrefix) as u64,
+        node_rem: if tag == RowTag::ListLen {
+            0
+        } else {
+            prev.node_rem - 1
+        },
+        ..Default::default()
+    };
+    rows.push(row);
+    rows.last_mut().expect("row is pushed")
+}
+
+/// Push the length bytes of a long list or string
+fn push_len_rows(rows: &mut Vec<TrieRow>, rlp: &[u8], offset: &mut usize, tag: RowTag) {
+    while rows.last().expect("prefix is pushed").len_rem > 0 {
+        let prev = rows.last().expect("prefix is pushed");
+        let (len_rem, len_acc) = (
+            prev.len_rem - 1,
+            prev.len_acc * BYTE_POW_BASE + rlp[*offset] as u64,
+        );
+        let row = push_row(rows, rlp[*offset], tag);
+        row.len_rem = len_rem;
+        row.len_acc = len_acc;
+        *offset += 1;
+    }
+}
+
+/// Decode the content byte of the last row as part of the path, a reference
+/// or the value of the node, depending on its kind and the item
+fn decode_content(rows: &mut [TrieRow]) {
+    let n = rows.len();
+    let prev = rows[n - 2].clone();
+    let row = &mut rows[n - 1];
+    let kind = row.kind.expect("content is part of a node");
+
+    match (kind, row.item_count) {
+        (TrieNodeKind::Extension | TrieNodeKind::Leaf, 1) => {
+            row.is_hp = true;
+            row.is_hp_first = row.is_single || prev.tag == RowTag::ItemPrefix;
+            if row.is_hp_first {
+                row.is_odd = (row.byte >> 4) & 1 == 1;
+                if row.is_odd {
+                    row.nib_lo = row.byte & 0xf;
+                    row.path = (row.path.0 * 16 + row.nib_lo as u64, row.path.1 + 1);
+                }
+            } else {
+                row.path = (row.path.0 * BYTE_POW_BASE + row.byte as u64, row.path.1 + 2);
+            }
+        }
+        (TrieNodeKind::Branch, _) | (TrieNodeKind::Extension, 2) => {
+            row.is_ref = true;
+            row.is_lo = row.item_rem < 16;
+            let reset = prev.tag == RowTag::ItemPrefix || (row.is_lo && !prev.is_lo);
+            row.ref_hi = match (row.is_lo, prev.is_lo) {
+                (true, true) => prev.ref_hi,
+                (true, false) => prev.ref_acc,
+                (false, _) => 0,
+            };
+            row.ref_acc = if reset {
+                row.byte as u128
+            } else {
+                prev.ref_acc * BYTE_POW_BASE as u128 + row.byte as u128
+            };
+            row.is_ref_end = row.item_rem == 0;
+            row.child_path = if kind == TrieNodeKind::Branch {
+                (row.path.0 * 16 + row.item_count - 1, row.path.1 + 1)
+            } else {
+                row.path
+            };
+        }
+        (TrieNodeKind::Leaf, 2) => {
+            row.is_value_first = prev.tag != RowTag::Content;
+            if row.is_value_first && row.byte < 0x80 {
+                row.is_typed = true;
+                row.tx_type = row.byte;
+            }
+        }
+        _ => unreachable!("{:?} has no item {}", kind, row.item_count),
+    }
+}
+
+/// Lays out the RLP encoding of a node, which must not embed a child
+fn node_rows(node: &TrieNode) -> Result<Vec<TrieRow>, Error> {
+    let rlp = &node.rlp;
+    let node_path = (nibbles_acc(&node.path), node.path.len() as u64);
+    let mut rows = vec![TrieRow {
+        byte: rlp[0],
+        tag: RowTag::NodeStart,
+        kind: Some(node.kind),
+        node_path,
+        path: node_path,
+        len: 1,
+        ..Default::default()
+    }];
+    if node.kind == TrieNodeKind::Empty {
+        rows[0].is_node_end = true;
+        return Ok(rows);
+    }
+
+    let mut offset = 1;
+    if rlp[0] > 0xf7 {
+        rows[0].is_long = true;
+        rows[0].len_rem = (rlp[0] - 0xf7) as u64;
+        push_len_rows(&mut rows, rlp, &mut offset, RowTag::ListLen);
+        let last = rows.last_mut().expect("length is pushed");
+        last.node_rem = last.len_acc;
+    } else {
+        rows[0].node_rem = (rlp[0] - 0xc0) as u64;
+    }
+
+    while offset < rlp.len() {
+        let byte = rlp[offset];
+        offset += 1;
+        if byte >= 0xc0 {
+            error!(
+                "trie node at path {:?} embeds a child shorter than 32 bytes",
+                node.path
+            );
+            return Err(Error::Synthesis);
+        }
+        if byte < 0x80 {
+            let row = push_row(&mut rows, byte, RowTag::Content);
+            row.is_single = true;
+            row.item_count += 1;
+            decode_content(&mut rows);
+            continue;
+        }
+
+        let row = push_row(&mut rows, byte, RowTag::ItemPrefix);
+        if byte > 0xb7 {
+            row.is_long = true;
+            row.len_rem = (byte - 0xb7) as u64;
+            push_len_rows(&mut rows, rlp, &mut offset, RowTag::ItemLen);
+            let last = rows.last_mut().expect("length is pushed");
+            last.item_rem = last.len_acc;
+        } else {
+            row.item_rem = (byte - 0x80) as u64;
+        }
+        while rows.last().expect("prefix is pushed").item_rem > 0 {
+            let item_rem = rows.last().expect("prefix is pushed").item_rem - 1;
+            let row = push_row(&mut rows, rlp[offset], RowTag::Content);
+            row.item_rem = item_rem;
+            offset += 1;
+            decode_content(&mut rows);
+        }
+    }
+
+    let last = rows.last_mut().expect("node is not empty");
+    assert_eq!(last.node_rem, 0, "list prefix must match the node length");
+    last.is_node_end = true;
+    Ok(rows)
+}
+
+/// Columns decoding the value of the leaves of the withdrawals trie
+#[derive(Clone, Debug)]
+struct WdValueConfig {
+    // is_field: whether the row is in the n-th item of the withdrawal
+    is_field: [Column<Advice>; N_WD_FIELDS],
+    // is_single: first byte of an item, encoded as itself
+    is_single: Column<Advice>,
+    // rem: number of bytes of the item after the row
+    rem: Column<Advice>,
+    rem_inv: Column<Advice>,
+    // acc: bytes of the item up to the row accumulated with base 256
+    acc: Column<Advice>,
+    // fields: items of the withdrawal decoded up to the row
+    fields: [Column<Advice>; N_WD_FIELDS],
+    // list_len: length of the payload of the withdrawal list
+    list_len: Column<Advice>,
+}
+
+/// Witness of the withdrawal decoding of a row of the trie
+#[derive(Clone, Debug, Default)]
+struct WdValueRow<F> {
+    field: Option<usize>,
+    is_single: bool,
+    rem: u64,
+    acc: F,
+    fields: [F; N_WD_FIELDS],
+    list_len: u64,
+}
+
+impl<F: Field> WdValueRow<F> {
+    /// Decode the next byte of the value of a leaf
+    fn next(&self, byte: u8, is_value_first: bool) -> Self {
+        if is_value_first {
+            return Self {
+                list_len: (byte - 0xc0) as u64,
+                ..Default::default()
+            };
+        }
+        let mut row = Self {
+            field: self.field,
+            fields: self.fields,
+            list_len: self.list_len,
+            ..Default::default()
+        };
+        if self.rem == 0 {
+            row.field = Some(self.field.map_or(0, |field| field + 1));
+            if byte < 0x80 {
+                row.is_single = true;
+                row.acc = F::from(byte as u64);
+            } else {
+                row.rem = (byte - 0x80) as u64;
+            }
+        } else {
+            row.rem = self.rem - 1;
+            row.acc = self.acc * F::from(BYTE_POW_BASE) + F::from(byte as u64);
+        }
+        row.fields[row.field.expect("byte is part of an item")] = row.acc;
+        row
+    }
+}
+
+/// Config of the transactions root verification
+#[derive(Clone, Debug)]
+pub(super) struct TxTrieConfig {
+    max_leaves: usize,
+
+    // q_trie: 1 on the rows of the trie
+    q_trie: Column<Fixed>,
+    // q_trie_first: 1 on the first row of the trie
+    q_trie_first: Column<Fixed>,
+    // q_trie_last: 1 on the last row of the trie
+    q_trie_last: Column<Fixed>,
+    // q_chunk_last: 1 on the last row of the trie of the last block of the chunk
+    q_chunk_last: Column<Fixed>,
+    // block_index: index of the block of the trie in the chunk
+    block_index: Column<Fixed>,
+    // q_key: 1 on the rows of the key table, which holds the path of the leaf
+    // of every index, accumulated with base 16, and its number of nibbles
+    q_key: Column<Fixed>,
+    key_index: Column<Fixed>,
+    key_path_acc: Column<Fixed>,
+    key_path_len: Column<Fixed>,
+
+    // byte: RLP encoding of the nodes, and padding
+    byte: Column<Advice>,
+    // Tags of the row, one of node start, list length, item prefix, item
+    // length, content or padding
+    is_node_start: Column<Advice>,
+    is_list_len: Column<Advice>,
+    is_item_prefix: Column<Advice>,
+    is_item_len: Column<Advice>,
+    is_content: Column<Advice>,
+    is_padding: Column<Advice>,
+    // is_single: content which is a single byte encoded as itself
+    is_single: Column<Advice>,
+    // is_long: list or item prefix followed by the length bytes
+    is_long: Column<Advice>,
+    // is_node_end: last byte of a node
+    is_node_end: Column<Advice>,
+    // Kind of the node, constant in the node
+    is_branch: Column<Advice>,
+    is_ext: Column<Advice>,
+    is_leaf: Column<Advice>,
+    is_empty: Column<Advice>,
+    // node_path: path of the node accumulated with base 16, and its number of nibbles
+    node_path_acc: Column<Advice>,
+    node_path_len: Column<Advice>,
+    // path: node_path extended by the hex prefix encoded path of the node up to the row
+    path_acc: Column<Advice>,
+    path_len: Column<Advice>,
+    // len_rem: number of length bytes after the row
+    len_rem: Column<Advice>,
+    // len_acc: length bytes accumulated with base 256
+    len_acc: Column<Advice>,
+    // item_rem: number of content bytes of the item after the row
+    item_rem: Column<Advice>,
+    item_rem_inv: Column<Advice>,
+    // item_count: number of items of the node up to the row
+    item_count: Column<Advice>,
+    // node_rem: number of bytes of the node after the row, once the list length is known
+    node_rem: Column<Advice>,
+    // is_hp: content of the hex prefix encoded path of an extension or a leaf
+    is_hp: Column<Advice>,
+    // is_hp_first: first byte of the path, made of the flags and nib_lo
+    is_hp_first: Column<Advice>,
+    is_odd: Column<Advice>,
+    nib_lo: Column<Advice>,
+    // is_ref: content of a reference to a child
+    is_ref: Column<Advice>,
+    // is_lo: last 16 bytes of a reference
+    is_lo: Column<Advice>,
+    // ref_acc: bytes of the current limb of the reference accumulated with base 256
+    ref_acc: Column<Advice>,
+    // ref_hi: hi limb of the reference, on the rows of the lo limb
+    ref_hi: Column<Advice>,
+    // is_ref_end: last byte of a reference, or the first row for the root
+    is_ref_end: Column<Advice>,
+    // child_path: path of the referenced child
+    child_path_acc: Column<Advice>,
+    child_path_len: Column<Advice>,
+    // len: number of bytes of the node up to the row
+    len: Column<Advice>,
+    // rlc: RLC of the bytes of the node up to the row by the keccak challenge
+    rlc: Column<Advice>,
+    // hash: hash of the node
+    hash: Word<Column<Advice>>,
+    // leaf_count: number of leaves up to the row
+    leaf_count: Column<Advice>,
+    // id_offset: number of leaves of the tries of the previous blocks
+    id_offset: Column<Advice>,
+    // id_end: id_offset + leaf_count, the id offset of the next block on the last row
+    id_end: Column<Advice>,
+    // is_value: content of the value of a leaf
+    is_value: Column<Advice>,
+    // is_value_first: first byte of the value of a leaf
+    is_value_first: Column<Advice>,
+    // is_typed: whether the value is a typed transaction envelope
+    is_typed: Column<Advice>,
+    tx_type: Column<Advice>,
+    // caller: caller of the transaction of the leaf, on the first byte of the value
+    caller: Word<Column<Advice>>,
+    // caller_inv: inverse of the caller, or of the number of padding
+    // transactions on the last row of the chunk
+    caller_inv: Column<Advice>,
+    // value_rlc: RLC of the value of a leaf up to the row by the keccak challenge
+    value_rlc: Column<Advice>,
+    // value_len: number of bytes of the value of a leaf up to the row
+    value_len: Column<Advice>,
+    // wd: decoding of the withdrawals, for the withdrawals trie
+    wd: Option<WdValueConfig>,
+    // rlp: decoding of the transaction envelopes, for the transactions trie
+    rlp: Option<TxRlpConfig>,
+}
+
+impl TxTrieConfig {
+    /// Configure the verification of the root of a trie of transactions,
+    /// receipts or withdrawals
+    pub(super) fn configure<F: Field>(
+        meta: &mut ConstraintSystem<F>,
+        keccak_table: &KeccakTable,
+        tx_table: &TxTable,
+        leaves: TrieLeaves<'_>,
+        fixed_u16: Column<Fixed>,
+        challenges: &Challenges<Expression<F>>,
+        max_leaves: usize,
+    ) -> Self {
+        let q_trie = meta.fixed_column();
+        let q_trie_first = meta.fixed_column();
+        let q_trie_last = meta.fixed_column();
+        let q_chunk_last = meta.fixed_column();
+        let block_index = meta.fixed_column();
+        let q_key = meta.fixed_column();
+        let key_index = meta.fixed_column();
+        let key_path_acc = meta.fixed_column();
+        let key_path_len = meta.fixed_column();
+
+        let byte = meta.advice_column();
+        let is_node_start = meta.advice_column();
+        let is_list_len = meta.advice_column();
+        let is_item_prefix = meta.advice_column();
+        let is_item_len = meta.advice_column();
+        let is_content = meta.advice_column();
+        let is_padding = meta.advice_column();
+        let is_single = meta.advice_column();
+        let is_long = meta.advice_column();
+        let is_node_end = meta.advice_column();
+        let is_branch = meta.advice_column();
+        let is_ext = meta.advice_column();
+        let is_leaf = meta.advice_column();
+        let is_empty = meta.advice_column();
+        let node_path_acc = meta.advice_column();
+        let node_path_len = meta.advice_column();
+        let path_acc = meta.advice_column();
+        let path_len = meta.advice_column();
+        let len_rem = meta.advice_column();
+        let len_acc = meta.advice_column();
+        let item_rem = meta.advice_column();
+        let item_rem_inv = meta.advice_column();
+        let item_count = meta.advice_column();
+        let node_rem = meta.advice_column();
+        let is_hp = meta.advice_column();
+        let is_hp_first = meta.advice_column();
+        let is_odd = meta.advice_column();
+        let nib_lo = meta.advice_column();
+        let is_ref = meta.advice_column();
+        let is_lo = meta.advice_column();
+        let ref_acc = meta.advice_column();
+        let ref_hi = meta.advice_column();
+        let is_ref_end = meta.advice_column();
+        let child_path_acc = meta.advice_column();
+        let child_path_len = meta.advice_column();
+        let len = meta.advice_column();
+        let rlc = meta.advice_column_in(SecondPhase);
+        let hash = Word::new([meta.advice_column(), meta.advice_column()]);
+        let leaf_count = meta.advice_column();
+        let id_offset = meta.advice_column();
+        let id_end = meta.advice_column();
+        let is_value = meta.advice_column();
+        let is_value_first = meta.advice_column();
+        let is_typed = meta.advice_column();
+        let tx_type = meta.advice_column();
+        let caller = Word::new([meta.advice_column(), meta.advice_column()]);
+        let caller_inv = meta.advice_column();
+        let value_rlc = meta.advice_column_in(SecondPhase);
+        let value_len = meta.advice_column();
+        let is_wd = matches!(leaves, TrieLeaves::Withdrawals(_));
+
+        // The root is copied to the reference of the first row
+        meta.enable_equality(ref_acc);
+        meta.enable_equality(ref_hi);
+        // The id offset is chained from the previous block to the next one
+        meta.enable_equality(id_offset);
+        meta.enable_equality(id_end);
+
+        let inv_2 = Expression::Constant(F::from(2).invert().unwrap());
+        // Whether length bytes follow the row, as len_rem is at most 2
+        let has_len = |len_rem: Expression<F>| len_rem.expr() * (3.expr() - len_rem) * inv_2.expr();
+        let pow_2_128 = Expression::Constant(F::from_u128(u128::MAX) + F::ONE);
+        // Id of the transaction or withdrawal of the last leaf up to the row
+        let leaf_id = |meta: &mut VirtualCells<'_, F>| {
+            meta.query_advice(id_offset, Rotation::cur())
+                + meta.query_advice(leaf_count, Rotation::cur())
+        };
+
+        meta.create_gate("tx trie row", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let item_prefix_prev = meta.query_advice(is_item_prefix, Rotation::prev());
+            let content_prev = meta.query_advice(is_content, Rotation::prev());
+            let is_lo_prev = meta.query_advice(is_lo, Rotation::prev());
+            let len_rem_prev = meta.query_advice(len_rem, Rotation::prev());
+            let len_acc_prev = meta.query_advice(len_acc, Rotation::prev());
+            let item_rem_prev = meta.query_advice(item_rem, Rotation::prev());
+            let item_count_prev = meta.query_advice(item_count, Rotation::prev());
+            let node_rem_prev = meta.query_advice(node_rem, Rotation::prev());
+            let path_acc_prev = meta.query_advice(path_acc, Rotation::prev());
+            let path_len_prev = meta.query_advice(path_len, Rotation::prev());
+            let ref_acc_prev = meta.query_advice(ref_acc, Rotation::prev());
+            let ref_hi_prev = meta.query_advice(ref_hi, Rotation::prev());
+            let len_prev = meta.query_advice(len, Rotation::prev());
+            let rlc_prev = meta.query_advice(rlc, Rotation::prev());
+
+            let byte = meta.query_advice(byte, Rotation::cur());
+            let start = meta.query_advice(is_node_start, Rotation::cur());
+            let end = meta.query_advice(is_node_end, Rotation::cur());
+            let list_len = meta.query_advice(is_list_len, Rotation::cur());
+            let item_prefix = meta.query_advice(is_item_prefix, Rotation::cur());
+            let item_len = meta.query_advice(is_item_len, Rotation::cur());
+            let content = meta.query_advice(is_content, Rotation::cur());
+            let padding = meta.query_advice(is_padding, Rotation::cur());
+            let single = meta.query_advice(is_single, Rotation::cur());
+            let long = meta.query_advice(is_long, Rotation::cur());
+            let branch = meta.query_advice(is_branch, Rotation::cur());
+            let ext = meta.query_advice(is_ext, Rotation::cur());
+            let leaf = meta.query_advice(is_leaf, Rotation::cur());
+            let empty = meta.query_advice(is_empty, Rotation::cur());
+            let hp = meta.query_advice(is_hp, Rotation::cur());
+            let hp_first = meta.query_advice(is_hp_first, Rotation::cur());
+            let odd = meta.query_advice(is_odd, Rotation::cur());
+            let nib_lo = meta.query_advice(nib_lo, Rotation::cur());
+            let reference = meta.query_advice(is_ref, Rotation::cur());
+            let lo = meta.query_advice(is_lo, Rotation::cur());
+            let value = meta.query_advice(is_value, Rotation::cur());
+            let value_first = meta.query_advice(is_value_first, Rotation::cur());
+            let typed = meta.query_advice(is_typed, Rotation::cur());
+            let len_rem = meta.query_advice(len_rem, Rotation::cur());
+            let len_acc = meta.query_advice(len_acc, Rotation::cur());
+            let item_rem = meta.query_advice(item_rem, Rotation::cur());
+            let item_count = meta.query_advice(item_count, Rotation::cur());
+            let iz = 1.expr() - item_rem.expr() * meta.query_advice(item_rem_inv, Rotation::cur());
+
+            for (name, flag) in [
+                ("is_node_start is boolean", start.expr()),
+                ("is_node_end is boolean", end.expr()),
+                ("is_list_len is boolean", list_len.expr()),
+                ("is_item_prefix is boolean", item_prefix.expr()),
+                ("is_item_len is boolean", item_len.expr()),
+                ("is_content is boolean", content.expr()),
+                ("is_padding is boolean", padding.expr()),
+                ("is_single is boolean", single.expr()),
+                ("is_long is boolean", long.expr()),
+                ("is_branch is boolean", branch.expr()),
+                ("is_ext is boolean", ext.expr()),
+                ("is_leaf is boolean", leaf.expr()),
+                ("is_empty is boolean", empty.expr()),
+                ("is_odd is boolean", odd.expr()),
+                ("is_lo is boolean", lo.expr()),
+                ("is_typed is boolean", typed.expr()),
+            ] {
+                cb.require_boolean(name, flag);
+            }
+
+            cb.require_equal(
+                "a row has a single tag",
+                start.expr()
+                    + list_len.expr()
+                    + item_prefix.expr()
+                    + item_len.expr()
+                    + content.expr()
+                    + padding.expr(),
+                1.expr(),
+            );
+            cb.require_zero(
+                "single bytes are content",
+                single.expr() * not::expr(content.expr()),
+            );
+            cb.require_zero(
+                "only prefixes are long",
+                long.expr() * not::expr(start.expr() + item_prefix.expr()),
+            );
+            cb.require_zero("padding is not a node end", padding.expr() * end.expr());
+            cb.require_equal(
+                "a node has a single kind",
+                branch.expr() + ext.expr() + leaf.expr() + empty.expr(),
+                not::expr(padding.expr()),
+            );
+            cb.condition(empty.expr(), |cb| {
+                cb.require_equal("empty node starts at the row", start.expr(), 1.expr());
+                cb.require_equal("empty node ends at the row", end.expr(), 1.expr());
+                cb.require_equal("empty node is the empty string", byte.expr(), 0x80.expr());
+                cb.require_zero("empty node is not long", long.expr());
+            });
+
+            // RLP lengths
+            let is_len = list_len.expr() + item_len.expr();
+            let is_len_last = not::expr(has_len(len_rem.expr()));
+            cb.require_equal(
+                "len_rem = number of length bytes, decremented on every length byte",
+                len_rem.expr(),
+                long.expr()
+                    * (start.expr() * (byte.expr() - 0xf7.expr())
+                        + item_prefix.expr() * (byte.expr() - 0xb7.expr()))
+                    + is_len.expr() * (len_rem_prev - 1.expr()),
+            );
+            cb.require_equal(
+                "len_acc = len_acc_prev * 256 + byte on length bytes",
+                len_acc.expr(),
+                is_len * (len_acc_prev * BYTE_POW_BASE.expr() + byte.expr()),
+            );
+            cb.require_equal(
+                "item_rem = item length, decremented on every content byte",
+                item_rem.expr(),
+                item_prefix.expr() * not::expr(long.expr()) * (byte.expr() - 0x80.expr())
+                    + item_len.expr() * is_len_last.expr() * len_acc.expr()
+                    + content.expr() * not::expr(single.expr()) * (item_rem_prev - 1.expr()),
+            );
+            cb.require_equal(
+                "node_rem = list length, decremented on every item byte",
+                meta.query_advice(node_rem, Rotation::cur()),
+                start.expr()
+                    * not::expr(long.expr())
+                    * not::expr(empty.expr())
+                    * (byte.expr() - 0xc0.expr())
+                    + list_len.expr() * is_len_last * len_acc
+                    + (item_prefix.expr() + item_len.expr() + content.expr())
+                        * (node_rem_prev - 1.expr()),
+            );
+            cb.require_zero("iz = item_rem == 0", item_rem.expr() * iz.expr());
+            cb.require_equal(
+                "item_count = item_count_prev + is item start",
+                item_count.expr(),
+                not::expr(start.expr()) * item_count_prev + item_prefix.expr() + single.expr(),
+            );
+
+            // RLC and length of the node, looked up in the keccak table
+            cb.condition(not::expr(padding.expr()), |cb| {
+                cb.require_equal(
+                    "len = len_prev + 1 in the node",
+                    meta.query_advice(len, Rotation::cur()),
+                    start.expr() + not::expr(start.expr()) * (len_prev + 1.expr()),
+                );
+                cb.require_equal(
+                    "rlc = rlc_prev * r + byte in the node",
+                    meta.query_advice(rlc, Rotation::cur()),
+                    start.expr() * byte.expr()
+                        + not::expr(start.expr())
+                            * (rlc_prev * challenges.keccak_input() + byte.expr()),
+                );
+            });
+
+            // Path, extended by the hex prefix encoding of extensions and leaves
+            cb.require_equal(
+                "path_acc = node path extended by the hex prefix path",
+                meta.query_advice(path_acc, Rotation::cur()),
+                start.expr() * meta.query_advice(node_path_acc, Rotation::cur())
+                    + not::expr(start.expr())
+                        * (hp_first.expr()
+                            * (path_acc_prev.expr() * (1.expr() + 15.expr() * odd.expr())
+                                + odd.expr() * nib_lo.expr())
+                            + (hp.expr() - hp_first.expr())
+                                * (path_acc_prev.expr() * BYTE_POW_BASE.expr() + byte.expr())
+                            + not::expr(hp.expr()) * path_acc_prev),
+            );
+            cb.require_equal(
+                "path_len = node path length extended by the hex prefix path",
+                meta.query_advice(path_len, Rotation::cur()),
+                start.expr() * meta.query_advice(node_path_len, Rotation::cur())
+                    + not::expr(start.expr())
+                        * (path_len_prev
+                            + hp_first.expr() * odd.expr()
+                            + (hp.expr() - hp_first.expr()) * 2.expr()),
+            );
+
+            // Meaning of the content bytes
+            cb.require_equal(
+                "is_hp = content of the first item of an extension or a leaf",
+                hp.expr(),
+                (ext.expr() + leaf.expr()) * content.expr() * (2.expr() - item_count.expr()),
+            );
+            cb.require_equal(
+                "is_hp_first = first byte of the path",
+                hp_first.expr(),
+                hp.expr() * (single.expr() + item_prefix_prev.expr()),
+            );
+            cb.require_equal(
+                "is_ref = content of a branch or of the second item of an extension",
+                reference.expr(),
+                content.expr() * (branch.expr() + ext.expr() * (item_count.expr() - 1.expr())),
+            );
+            cb.require_equal(
+                "is_value = content of the second item of a leaf",
+                value.expr(),
+                leaf.expr() * content.expr() * (item_count.expr() - 1.expr()),
+            );
+            cb.require_equal(
+                "is_value_first = first content byte of the second item of a leaf",
+                value_first.expr(),
+                value.expr() * not::expr(content_prev),
+            );
+            cb.require_zero(
+                "only references have a lo limb",
+                lo.expr() * not::expr(reference.expr()),
+            );
+            cb.condition(hp_first, |cb| {
+                cb.require_equal(
+                    "first byte of the path is the flags and nib_lo",
+                    byte.expr(),
+                    16.expr() * (2.expr() * leaf.expr() + odd.expr()) + nib_lo.expr(),
+                );
+                cb.require_zero("nib_lo is zero for even paths", not::expr(odd) * nib_lo);
+            });
+            cb.condition(reference, |cb| {
+                let reset = item_prefix_prev.expr() + lo.expr() * not::expr(is_lo_prev.expr());
+                cb.require_equal(
+                    "ref_acc = ref_acc_prev * 256 + byte in the limb",
+                    meta.query_advice(ref_acc, Rotation::cur()),
+                    not::expr(reset) * ref_acc_prev.expr() * BYTE_POW_BASE.expr() + byte.expr(),
+                );
+                cb.require_equal(
+                    "ref_hi = hi limb of the reference on the rows of the lo limb",
+                    meta.query_advice(ref_hi, Rotation::cur()),
+                    lo.expr()
+                        * (is_lo_prev.expr() * ref_hi_prev + not::expr(is_lo_prev) * ref_acc_prev),
+                );
+            });
+
+            // RLC and length of the value of the leaves
+            cb.require_equal(
+                "value_rlc = value_rlc_prev * r + byte in the value",
+                meta.query_advice(value_rlc, Rotation::cur()),
+                value.expr()
+                    * (not::expr(value_first.expr())
+                        * meta.query_advice(value_rlc, Rotation::prev())
+                        * challenges.keccak_input()
+                        + byte.expr()),
+            );
+            cb.require_equal(
+                "value_len = value_len_prev + 1 in the value",
+                meta.query_advice(value_len, Rotation::cur()),
+                value
+                    * (not::expr(value_first.expr())
+                        * meta.query_advice(value_len, Rotation::prev())
+                        + 1.expr()),
+            );
+
+            // Structure of the nodes
+            cb.require_zero(
+                "branches have no single byte",
+                branch.expr() * single.expr(),
+            );
+            cb.require_zero(
+                "single bytes are the path",
+                single.expr() * (item_count.expr() - 1.expr()),
+            );
+            cb.require_zero(
+                "branch items are empty or a reference",
+                branch.expr()
+                    * item_prefix.expr()
+                    * (byte.expr() - 0x80.expr())
+                    * (byte.expr() - 0xa0.expr()),
+            );
+            cb.require_zero(
+                "the second item of an extension is a reference",
+                ext.expr()
+                    * item_prefix.expr()
+                    * (item_count.expr() - 1.expr())
+                    * (byte.expr() - 0xa0.expr()),
+            );
+            if !is_wd {
+                cb.require_zero(
+                    "the value of a leaf is long",
+                    leaf.expr()
+                        * item_prefix.expr()
+                        * (item_count.expr() - 1.expr())
+                        * not::expr(long.expr()),
+                );
+            }
+            let path_prefix =
+                (ext.expr() + leaf.expr()) * item_prefix.expr() * (2.expr() - item_count.expr());
+            cb.require_zero("the path is short", path_prefix.expr() * long.expr());
+            cb.require_zero("the path is not empty", path_prefix * iz.expr());
+
+            cb.condition(end.expr(), |cb| {
+                cb.require_zero("the node ends with its last length", len_rem);
+                cb.require_zero("the node ends with its last item", item_rem);
+                cb.require_zero(
+                    "the node ends with its list",
+                    meta.query_advice(node_rem, Rotation::cur()),
+                );
+                cb.require_zero(
+                    "branches have 17 items",
+                    branch.expr() * (item_count.expr() - 17.expr()),
+                );
+                cb.require_zero(
+                    "branches end with their value",
+                    branch.expr() * not::expr(item_prefix),
+                );
+                cb.require_zero(
+                    "the value of branches is empty",
+                    branch.expr() * (byte.expr() - 0x80.expr()),
+                );
+                cb.require_zero(
+                    "extensions and leaves have 2 items",
+                    (ext + leaf) * (item_count - 2.expr()),
+                );
+            });
+
+            // The value of a leaf starts with the type of a typed transaction,
+            // or the list prefix of a legacy one or of a withdrawal
+            cb.condition(value_first, |cb| {
+                if is_wd {
+                    cb.require_zero("withdrawals are not typed", typed);
+                } else {
+                    let tx_type = meta.query_advice(tx_type, Rotation::cur());
+                    cb.require_zero(
+                        "tx_type is the first byte of typed transactions",
+                        typed.expr() * (byte - tx_type.expr()),
+                    );
+                    cb.require_zero(
+                        "tx_type is 0 for legacy transactions",
+                        not::expr(typed) * tx_type,
+                    );
+                    cb.require_equal(
+                        "the caller is not zero",
+                        (meta.query_advice(caller.lo(), Rotation::cur())
+                            + meta.query_advice(caller.hi(), Rotation::cur()) * pow_2_128.expr())
+                            * meta.query_advice(caller_inv, Rotation::cur()),
+                        1.expr(),
+                    );
+                }
+            });
+
+            cb.gate(meta.query_fixed(q_trie, Rotation::cur()))
+        });
+
+        meta.create_gate("tx trie first row", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_equal(
+                "the root starts at the first row",
+                meta.query_advice(is_node_start, Rotation::cur()),
+                1.expr(),
+            );
+            cb.require_equal(
+                "leaf_count = is_leaf",
+                meta.query_advice(leaf_count, Rotation::cur()),
+                meta.query_advice(is_leaf, Rotation::cur()),
+            );
+            // The root is referenced from the first row, with an empty path
+            cb.require_equal(
+                "the root is referenced",
+                meta.query_advice(is_ref_end, Rotation::cur()),
+                1.expr(),
+            );
+            for (name, column) in [
+                ("child path of the root is empty", child_path_acc),
+                ("child path length of the root is zero", child_path_len),
+                ("path of the root is empty", node_path_acc),
+                ("path length of the root is zero", node_path_len),
+            ] {
+                cb.require_zero(name, meta.query_advice(column, Rotation::cur()));
+            }
+
+            cb.gate(meta.query_fixed(q_trie_first, Rotation::cur()))
+        });
+
+        meta.create_gate("tx trie next rows", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let start_prev = meta.query_advice(is_node_start, Rotation::prev());
+            let end_prev = meta.query_advice(is_node_end, Rotation::prev());
+            let padding_prev = meta.query_advice(is_padding, Rotation::prev());
+            let item_prefix_prev = meta.query_advice(is_item_prefix, Rotation::prev());
+            let list_len_prev = meta.query_advice(is_list_len, Rotation::prev());
+            let item_len_prev = meta.query_advice(is_item_len, Rotation::prev());
+            let item_rem_prev = meta.query_advice(item_rem, Rotation::prev());
+            let has_len_prev = has_len(meta.query_advice(len_rem, Rotation::prev()));
+            let iz_prev =
+                1.expr() - item_rem_prev.expr() * meta.query_advice(item_rem_inv, Rotation::prev());
+
+            let start = meta.query_advice(is_node_start, Rotation::cur());
+            let padding = meta.query_advice(is_padding, Rotation::cur());
+            let list_len = meta.query_advice(is_list_len, Rotation::cur());
+            let item_len = meta.query_advice(is_item_len, Rotation::cur());
+            let content = meta.query_advice(is_content, Rotation::cur());
+            let single = meta.query_advice(is_single, Rotation::cur());
+            let branch = meta.query_advice(is_branch, Rotation::cur());
+            let item_rem = meta.query_advice(item_rem, Rotation::cur());
+            let iz = 1.expr() - item_rem * meta.query_advice(item_rem_inv, Rotation::cur());
+
+            // Transitions between the tags
+            cb.require_zero(
+                "a node end is followed by a node start or padding",
+                end_prev.expr() * not::expr(start.expr() + padding.expr()),
+            );
+            cb.require_zero(
+                "padding is followed by padding",
+                padding_prev.expr() * not::expr(padding.expr()),
+            );
+            cb.condition(not::expr(end_prev + padding_prev), |cb| {
+                cb.require_zero("nodes go on until their end", start.expr() + padding.expr());
+                cb.require_equal(
+                    "length bytes follow the long prefixes",
+                    list_len.expr() + item_len.expr(),
+                    has_len_prev.expr(),
+                );
+                cb.require_equal(
+                    "content follows the prefix until the end of the item",
+                    content.expr() * not::expr(single.expr()),
+                    not::expr(has_len_prev.expr()) * not::expr(iz_prev.expr()),
+                );
+                cb.require_equal(
+                    "a new item follows the end of the previous item",
+                    meta.query_advice(is_item_prefix, Rotation::cur()) + single,
+                    not::expr(has_len_prev) * iz_prev,
+                );
+            });
+            cb.require_zero(
+                "list length bytes follow the list prefix",
+                list_len * not::expr(start_prev.expr() + list_len_prev),
+            );
+            cb.require_zero(
+                "item length bytes follow the item prefix",
+                item_len * not::expr(item_prefix_prev + item_len_prev),
+            );
+            cb.require_zero(
+                "only the root can be empty",
+                meta.query_advice(is_empty, Rotation::cur()),
+            );
+
+            // Node constants
+            for (name, column) in [
+                ("is_branch is constant in the node", is_branch),
+                ("is_ext is constant in the node", is_ext),
+                ("is_leaf is constant in the node", is_leaf),
+                ("node_path_acc is constant in the node", node_path_acc),
+                ("node_path_len is constant in the node", node_path_len),
+            ] {
+                cb.require_zero(
+                    name,
+                    not::expr(start.expr())
+                        * not::expr(padding.expr())
+                        * (meta.query_advice(column, Rotation::cur())
+                            - meta.query_advice(column, Rotation::prev())),
+                );
+            }
+            cb.require_equal(
+                "leaf_count = leaf_count_prev + is leaf start",
+                meta.query_advice(leaf_count, Rotation::cur()),
+                meta.query_advice(leaf_count, Rotation::prev())
+                    + start * meta.query_advice(is_leaf, Rotation::cur()),
+            );
+            cb.require_equal(
+                "id_offset is constant in the trie",
+                meta.query_advice(id_offset, Rotation::cur()),
+                meta.query_advice(id_offset, Rotation::prev()),
+            );
+
+            // References and the path of their child
+            let ref_end = meta.query_advice(is_ref_end, Rotation::cur());
+            cb.require_equal(
+                "is_ref_end = last byte of a reference",
+                ref_end.expr(),
+                meta.query_advice(is_ref, Rotation::cur()) * iz,
+            );
+            cb.condition(ref_end, |cb| {
+                let path_acc = meta.query_advice(path_acc, Rotation::cur());
+                let path_len = meta.query_advice(path_len, Rotation::cur());
+                cb.require_equal(
+                    "children of branches extend the path by their index",
+                    meta.query_advice(child_path_acc, Rotation::cur()),
+                    path_acc * (1.expr() + 15.expr() * branch.expr())
+                        + branch.expr()
+                            * (meta.query_advice(item_count, Rotation::cur()) - 1.expr()),
+                );
+                cb.require_equal(
+                    "children of branches extend the path by a nibble",
+                    meta.query_advice(child_path_len, Rotation::cur()),
+                    path_len + branch,
+                );
+            });
+
+            cb.gate(
+                meta.query_fixed(q_trie, Rotation::cur())
+                    * not::expr(meta.query_fixed(q_trie_first, Rotation::cur())),
+            )
+        });
+
+        meta.create_gate("tx trie last row", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let id_end = meta.query_advice(id_end, Rotation::cur());
+            let open = max_leaves.expr() - id_end.expr();
+            let is_full = 1.expr() - open.expr() * meta.query_advice(caller_inv, Rotation::cur());
+
+            cb.require_zero(
+                "the last node ends",
+                not::expr(meta.query_advice(is_padding, Rotation::cur()))
+                    * not::expr(meta.query_advice(is_node_end, Rotation::cur())),
+            );
+            cb.require_equal("id_end = id_offset + leaf_count", id_end, leaf_id(meta));
+            // The leaves of the chunk end at the last block
+            cb.require_zero(
+                "is_full = id_end == max_leaves",
+                meta.query_fixed(q_chunk_last, Rotation::cur()) * open * is_full,
+            );
+            // The transaction after the last leaf is looked up with a zero caller
+            for (name, column) in [
+                ("caller lo is zero", caller.lo()),
+                ("caller hi is zero", caller.hi()),
+                ("the last row is not a value", is_value_first),
+            ] {
+                cb.require_zero(name, meta.query_advice(column, Rotation::cur()));
+            }
+            if is_wd {
+                // The withdrawal after the last leaf is looked up at the last row
+                cb.require_zero(
+                    "the last row is not a leaf end",
+                    meta.query_advice(is_node_end, Rotation::cur())
+                        * meta.query_advice(is_leaf, Rotation::cur()),
+                );
+            }
+
+            cb.gate(meta.query_fixed(q_trie_last, Rotation::cur()))
+        });
+
+        meta.lookup_any("tx trie node hash", |meta| {
+            let end = meta.query_advice(is_node_end, Rotation::cur());
+
+            vec![
+                (
+                    end.expr(),
+                    meta.query_advice(keccak_table.is_enabled, Rotation::cur()),
+                ),
+                (
+                    end.expr() * meta.query_advice(rlc, Rotation::cur()),
+                    meta.query_advice(keccak_table.input_rlc, Rotation::cur()),
+                ),
+                (
+                    end.expr() * meta.query_advice(len, Rotation::cur()),
+                    meta.query_advice(keccak_table.input_len, Rotation::cur()),
+                ),
+                (
+                    end.expr() * meta.query_advice(hash.lo(), Rotation::cur()),
+                    meta.query_advice(keccak_table.output.lo(), Rotation::cur()),
+                ),
+                (
+                    end * meta.query_advice(hash.hi(), Rotation::cur()),
+                    meta.query_advice(keccak_table.output.hi(), Rotation::cur()),
+                ),
+            ]
+        });
+
+        // Every reference is the hash of a node with the path of the child, and
+        // every node is referenced with its path, the root from the first row,
+        // both in the trie of the same block.
+        let references = |meta: &mut VirtualCells<'_, F>| {
+            [
+                meta.query_advice(is_ref_end, Rotation::cur()),
+                meta.query_fixed(block_index, Rotation::cur()),
+                meta.query_advice(ref_acc, Rotation::cur()),
+                meta.query_advice(ref_hi, Rotation::cur()),
+                meta.query_advice(child_path_acc, Rotation::cur()),
+                meta.query_advice(child_path_len, Rotation::cur()),
+            ]
+        };
+        let nodes = |meta: &mut VirtualCells<'_, F>| {
+            [
+                meta.query_advice(is_node_end, Rotation::cur()),
+                meta.query_fixed(block_index, Rotation::cur()),
+                meta.query_advice(hash.lo(), Rotation::cur()),
+                meta.query_advice(hash.hi(), Rotation::cur()),
+                meta.query_advice(node_path_acc, Rotation::cur()),
+                meta.query_advice(node_path_len, Rotation::cur()),
+            ]
+        };
+        let lookup =
+            |input: [Expression<F>; 6],
+             table: [Expression<F>; 6],
+             q_trie: Expression<F>|
+             -> Vec<(Expression<F>, Expression<F>)> {
+                let [cond_input, input @ ..] = input;
+                let [cond_table, table @ ..] = table;
+                let cond_table = q_trie * cond_table;
+                iter::once((cond_input.expr(), cond_table.expr()))
+                    .chain(input.into_iter().zip(table).map(|(input, table)| {
+                        (cond_input.expr() * input, cond_table.expr() * table)
+                    }))
+                    .collect()
+            };
+        meta.lookup_any("tx trie child reference", |meta| {
+            let q_trie = meta.query_fixed(q_trie, Rotation::cur());
+            lookup(references(meta), nodes(meta), q_trie)
+        });
+        meta.lookup_any("tx trie node reachability", |meta| {
+            let q_trie = meta.query_fixed(q_trie, Rotation::cur());
+            lookup(nodes(meta), references(meta), q_trie)
+        });
+
+        meta.lookup_any("tx trie leaf key", |meta| {
+            let cond = meta.query_advice(is_node_end, Rotation::cur())
+                * meta.query_advice(is_leaf, Rotation::cur());
+
+            vec![
+                (cond.expr(), meta.query_fixed(q_key, Rotation::cur())),
+                (
+                    cond.expr() * (meta.query_advice(leaf_count, Rotation::cur()) - 1.expr()),
+                    meta.query_fixed(key_index, Rotation::cur()),
+                ),
+                (
+                    cond.expr() * meta.query_advice(path_acc, Rotation::cur()),
+                    meta.query_fixed(key_path_acc, Rotation::cur()),
+                ),
+                (
+                    cond * meta.query_advice(path_len, Rotation::cur()),
+                    meta.query_fixed(key_path_len, Rotation::cur()),
+                ),
+            ]
+        });
+
+        // Range checks of the prefixes against their short or long range, of the
+        // path nibble, of the limbs of the references and of the first byte of
+        // the values, split into a lower and an upper bound.
+        meta.lookup_any("tx trie lower bounds", |meta| {
+            let byte = meta.query_advice(byte, Rotation::cur());
+            let start = meta.query_advice(is_node_start, Rotation::cur());
+            let long = meta.query_advice(is_long, Rotation::cur());
+            let lo = meta.query_advice(is_lo, Rotation::cur());
+            let item_rem = meta.query_advice(item_rem, Rotation::cur());
+
+            let value = start
+                * not::expr(meta.query_advice(is_empty, Rotation::cur()))
+                * (byte.expr() - 0xc0.expr() - 0x38.expr() * long.expr())
+                + meta.query_advice(is_item_prefix, Rotation::cur())
+                    * (byte.expr() - 0x80.expr() - 0x38.expr() * long)
+                + meta.query_advice(is_hp_first, Rotation::cur())
+                    * meta.query_advice(nib_lo, Rotation::cur())
+                + meta.query_advice(is_ref, Rotation::cur())
+                    * (lo.expr() * (15.expr() - item_rem.expr())
+                        + not::expr(lo) * (item_rem - 16.expr()))
+                + meta.query_advice(is_value_first, Rotation::cur())
+                    * not::expr(meta.query_advice(is_typed, Rotation::cur()))
+                    * (byte - 0xc0.expr());
+
+            vec![(value, meta.query_fixed(fixed_u16, Rotation::cur()))]
+        });
+        meta.lookup_any("tx trie upper bounds", |meta| {
+            let byte = meta.query_advice(byte, Rotation::cur());
+            let long = meta.query_advice(is_long, Rotation::cur());
+
+            let value = meta.query_advice(is_node_start, Rotation::cur())
+                * not::expr(meta.query_advice(is_empty, Rotation::cur()))
+                * (0xf7.expr() + 2.expr() * long.expr() - byte.expr())
+                + meta.query_advice(is_item_prefix, Rotation::cur())
+                    * (0xb7.expr() + 2.expr() * long - byte.expr())
+                + meta.query_advice(is_hp_first, Rotation::cur())
+                    * (15.expr() - meta.query_advice(nib_lo, Rotation::cur()))
+                + meta.query_advice(is_value_first, Rotation::cur())
+                    * meta.query_advice(is_typed, Rotation::cur())
+                    * (byte - 1.expr());
+
+            vec![(value, meta.query_fixed(fixed_u16, Rotation::cur()))]
+        });
+
+        // The caller of every leaf, and a zero caller for the transaction after
+        // the last leaf of the chunk unless the tx table is full
+        if !is_wd {
+            meta.lookup_any("tx trie caller", |meta| {
+                let value_first = meta.query_advice(is_value_first, Rotation::cur());
+                let id_end = meta.query_advice(id_end, Rotation::cur());
+                let open = max_leaves.expr() - id_end.expr();
+                let is_padding_tx = meta.query_fixed(q_chunk_last, Rotation::cur())
+                    * open
+                    * meta.query_advice(caller_inv, Rotation::cur());
+                let cond = value_first.expr() + is_padding_tx.expr();
+
+                vec![
+                    (
+                        value_first * leaf_id(meta) + is_padding_tx * (id_end + 1.expr()),
+                        meta.query_advice(tx_table.tx_id, Rotation::cur()),
+                    ),
+                    (
+                        cond.expr() * (TxFieldTag::CallerAddress as u64).expr(),
+                        meta.query_fixed(tx_table.tag, Rotation::cur()),
+                    ),
+                    (
+                        cond.expr() * meta.query_advice(caller.lo(), Rotation::cur()),
+                        meta.query_advice(tx_table.value.lo(), Rotation::cur()),
+                    ),
+                    (
+                        cond * meta.query_advice(caller.hi(), Rotation::cur()),
+                        meta.query_advice(tx_table.value.hi(), Rotation::cur()),
+                    ),
+                ]
+            });
+            meta.lookup_any("tx trie tx type", |meta| {
+                let value_first = meta.query_advice(is_value_first, Rotation::cur());
+
+                vec![
+                    (
+                        value_first.expr() * leaf_id(meta),
+                        meta.query_advice(tx_table.tx_id, Rotation::cur()),
+                    ),
+                    (
+                        value_first.expr() * (TxFieldTag::TxType as u64).expr(),
+                        meta.query_fixed(tx_table.tag, Rotation::cur()),
+                    ),
+                    (
+                        value_first.expr() * meta.query_advice(tx_type, Rotation::cur()),
+                        meta.query_advice(tx_table.value.lo(), Rotation::cur()),
+                    ),
+                    (
+                        0.expr(),
+                        meta.query_advice(tx_table.value.hi(), Rotation::cur()),
+                    ),
+                ]
+            });
+        }
+
+        if let TrieLeaves::Receipts(receipt_table) = leaves {
+            meta.lookup_any("receipt trie leaf value", |meta| {
+                let cond = meta.query_advice(is_node_end, Rotation::cur())
+                    * meta.query_advice(is_leaf, Rotation::cur());
+
+                vec![
+                    (
+                        cond.expr() * ReceiptFieldTag::Envelope.expr(),
+                        meta.query_advice(receipt_table.tag, Rotation::cur()),
+                    ),
+                    (
+                        cond.expr() * leaf_id(meta),
+                        meta.query_advice(receipt_table.index, Rotation::cur()),
+                    ),
+                    (
+                        cond.expr() * meta.query_advice(value_rlc, Rotation::cur()),
+                        meta.query_advice(receipt_table.value, Rotation::cur()),
+                    ),
+                    (
+                        cond * meta.query_advice(value_len, Rotation::cur()),
+                        meta.query_advice(receipt_table.len, Rotation::cur()),
+                    ),
+                ]
+            });
+        }
+
+        let wd = if let TrieLeaves::Withdrawals(wd_table) = leaves {
+            let wd = WdValueConfig {
+                is_field: [(); N_WD_FIELDS].map(|_| meta.advice_column()),
+                is_single: meta.advice_column(),
+                rem: meta.advice_column(),
+                rem_inv: meta.advice_column(),
+                acc: meta.advice_column(),
+                fields: [(); N_WD_FIELDS].map(|_| meta.advice_column()),
+                list_len: meta.advice_column(),
+            };
+            // The first byte of an item of the withdrawal
+            let is_item_start = |meta: &mut VirtualCells<'_, F>| {
+                let rem_prev = meta.query_advice(wd.rem, Rotation::prev());
+                meta.query_advice(is_value, Rotation::cur())
+                    * not::expr(meta.query_advice(is_value_first, Rotation::cur()))
+                    * (1.expr() - rem_prev * meta.query_advice(wd.rem_inv, Rotation::prev()))
+            };
+
+            meta.create_gate("wd trie leaf value", |meta| {
+                let mut cb = BaseConstraintBuilder::default();
+
+                let byte = meta.query_advice(byte, Rotation::cur());
+                let value = meta.query_advice(is_value, Rotation::cur());
+                let value_first = meta.query_advice(is_value_first, Rotation::cur());
+                let item_start = is_item_start(meta);
+                let single = meta.query_advice(wd.is_single, Rotation::cur());
+                let rem = meta.query_advice(wd.rem, Rotation::cur());
+                let acc = meta.query_advice(wd.acc, Rotation::cur());
+                let list_len = meta.query_advice(wd.list_len, Rotation::cur());
+                let is_field = wd
+                    .is_field
+                    .map(|column| meta.query_advice(column, Rotation::cur()));
+
+                cb.require_boolean("is_single is boolean", single.expr());
+                for is_field in is_field.iter() {
+                    cb.require_boolean("is_field is boolean", is_field.expr());
+                }
+                cb.require_zero(
+                    "rem_inv is the inverse of rem",
+                    rem.expr()
+                        * (1.expr() - rem.expr() * meta.query_advice(wd.rem_inv, Rotation::cur())),
+                );
+
+                // The value starts with the list prefix of the withdrawal
+                cb.condition(value_first.expr(), |cb| {
+                    cb.require_equal(
+                        "list_len = byte - 0xc0",
+                        list_len.expr(),
+                        byte.expr() - 0xc0.expr(),
+                    );
+                    cb.require_zero("the list prefix is not an item", rem.expr());
+                    for (is_field, field) in is_field.iter().zip(wd.fields) {
+                        cb.require_zero("the list prefix is not an item", is_field.expr());
+                        cb.require_zero(
+                            "no item is decoded at the list prefix",
+                            meta.query_advice(field, Rotation::cur()),
+                        );
+                    }
+                });
+                // Followed by the items, each of them an integer encoded as a single
+                // byte or a string
+                cb.condition(value * not::expr(value_first), |cb| {
+                    cb.require_equal(
+                        "list_len is constant in the value",
+                        list_len.expr(),
+                        meta.query_advice(wd.list_len, Rotation::prev()),
+                    );
+                    for (i, is_field) in is_field.iter().enumerate() {
+                        let is_field_before = if i == 0 {
+                            meta.query_advice(is_value_first, Rotation::prev())
+                        } else {
+                            meta.query_advice(wd.is_field[i - 1], Rotation::prev())
+                        };
+                        cb.require_equal(
+                            "is_field moves to the next item at the start of an item",
+                            is_field.expr(),
+                            item_start.expr() * is_field_before
+                                + not::expr(item_start.expr())
+                                    * meta.query_advice(wd.is_field[i], Rotation::prev()),
+                        );
+                    }
+                    cb.require_equal(
+                        "the withdrawal has 4 items",
+                        is_field
+                            .iter()
+                            .fold(0.expr(), |acc, is_field| acc + is_field.expr()),
+                        1.expr(),
+                    );
+                    cb.require_zero(
+                        "only the first byte of an item is single",
+                        single.expr() * not::expr(item_start.expr()),
+                    );
+                    cb.require_equal(
+                        "rem = item length, decremented on every byte",
+                        rem.expr(),
+                        item_start.expr() * not::expr(single.expr()) * (byte.expr() - 0x80.expr())
+                            + not::expr(item_start.expr())
+                                * (meta.query_advice(wd.rem, Rotation::prev()) - 1.expr()),
+                    );
+                    cb.require_equal(
+                        "acc = acc_prev * 256 + byte in the item",
+                        acc.expr(),
+                        item_start.expr() * single * byte.expr()
+                            + not::expr(item_start.expr())
+                                * (meta.query_advice(wd.acc, Rotation::prev())
+                                    * BYTE_POW_BASE.expr()
+                                    + byte.expr()),
+                    );
+                    cb.require_zero(
+                        "the address is a string of 20 bytes",
+                        item_start * is_field[2].expr() * (byte - 0x94.expr()),
+                    );
+                    for (is_field, field) in is_field.iter().zip(wd.fields) {
+                        cb.require_equal(
+                            "fields = acc in the item",
+                            meta.query_advice(field, Rotation::cur()),
+                            is_field.expr() * acc.expr()
+                                + not::expr(is_field.expr())
+                                    * meta.query_advice(field, Rotation::prev()),
+                        );
+                    }
+                });
+
+                let leaf_end = meta.query_advice(is_node_end, Rotation::cur())
+                    * meta.query_advice(is_leaf, Rotation::cur());
+                cb.condition(leaf_end, |cb| {
+                    cb.require_equal(
+                        "the value of a leaf is not empty",
+                        meta.query_advice(is_value, Rotation::cur()),
+                        1.expr(),
+                    );
+                    cb.require_equal("the last item is the amount", is_field[3].expr(), 1.expr());
+                    cb.require_zero("the value ends with its last item", rem);
+                    cb.require_equal(
+                        "the list prefix matches the value length",
+                        meta.query_advice(value_len, Rotation::cur()),
+                        list_len + 1.expr(),
+                    );
+                });
+
+                cb.gate(meta.query_fixed(q_trie, Rotation::cur()))
+            });
+
+            // Range checks of the list prefix and of the first byte of the items,
+            // which are single bytes, or string prefixes of at most 20 bytes
+            meta.lookup_any("wd trie lower bounds", |meta| {
+                let byte = meta.query_advice(byte, Rotation::cur());
+                let single = meta.query_advice(wd.is_single, Rotation::cur());
+
+                let value = is_item_start(meta)
+                    * (single.expr() * (0x7f.expr() - byte.expr())
+                        + not::expr(single) * (byte - 0x80.expr()));
+
+                vec![(value, meta.query_fixed(fixed_u16, Rotation::cur()))]
+            });
+            meta.lookup_any("wd trie upper bounds", |meta| {
+                let byte = meta.query_advice(byte, Rotation::cur());
+
+                let value = is_item_start(meta)
+                    * not::expr(meta.query_advice(wd.is_single, Rotation::cur()))
+                    * (0x94.expr() - byte.expr())
+                    + meta.query_advice(is_value_first, Rotation::cur()) * (0xf7.expr() - byte);
+
+                vec![(value, meta.query_fixed(fixed_u16, Rotation::cur()))]
+            });
+
+            // The withdrawal of every leaf, and a zero withdrawal after the last
+            // leaf of the chunk unless the wd table is full
+            meta.lookup_any("wd trie withdrawal", |meta| {
+                let leaf_end = meta.query_advice(is_node_end, Rotation::cur())
+                    * meta.query_advice(is_leaf, Rotation::cur());
+                let id_end = meta.query_advice(id_end, Rotation::cur());
+                let open = max_leaves.expr() - id_end.expr();
+                let is_padding_wd = meta.query_fixed(q_chunk_last, Rotation::cur())
+                    * open
+                    * meta.query_advice(caller_inv, Rotation::cur());
+                let [id, validator_id, address, amount] = wd
+                    .fields
+                    .map(|column| leaf_end.expr() * meta.query_advice(column, Rotation::cur()));
+
+                vec![
+                    (
+                        leaf_end * leaf_id(meta) + is_padding_wd * (id_end + 1.expr()),
+                        meta.query_fixed(wd_table.index, Rotation::cur()),
+                    ),
+                    (id, meta.query_advice(wd_table.id, Rotation::cur())),
+                    (
+                        validator_id,
+                        meta.query_advice(wd_table.validator_id, Rotation::cur()),
+                    ),
+                    (
+                        address,
+                        meta.query_advice(wd_table.address.lo(), Rotation::cur())
+                            + meta.query_advice(wd_table.address.hi(), Rotation::cur())
+                                * pow_2_128.expr(),
+                    ),
+                    (amount, meta.query_advice(wd_table.amount, Rotation::cur())),
+                ]
+            });
+
+            Some(wd)
+        } else {
+            None
+        };
+
+        // The value of every leaf of the transactions trie is decoded as the
+        // envelope of the transaction with the id of the leaf, which ends with
+        // the leaf
+        let rlp = if let TrieLeaves::Txs(block_table) = leaves {
+            let rlp = TxRlpConfig::configure(
+                meta,
+                |meta| {
+                    meta.query_fixed(q_trie, Rotation::cur())
+                        * meta.query_advice(is_value, Rotation::cur())
+                },
+                |meta| meta.query_advice(is_value_first, Rotation::cur()),
+                leaf_id,
+                byte,
+                true,
+                tx_table,
+                block_table,
+                &[fixed_u16],
+            );
+
+            meta.create_gate("tx trie leaf envelope", |meta| {
+                let mut cb = BaseConstraintBuilder::default();
+
+                cb.require_equal(
+                    "the envelope ends with the leaf",
+                    rlp.is_end(meta, Rotation::cur()),
+                    meta.query_advice(is_node_end, Rotation::cur()),
+                );
+
+                cb.gate(
+                    meta.query_fixed(q_trie, Rotation::cur())
+                        * meta.query_advice(is_value, Rotation::cur()),
+                )
+            });
+
+            Some(rlp)
+        } else {
+            None
+        };
+
+        Self {
+            max_leaves,
+            q_trie,
+            q_trie_first,
+            q_trie_last,
+            q_chunk_last,
+            block_index,
+            q_key,
+            key_index,
+            key_path_acc,
+            key_path_len,
+            byte,
+            is_node_start,
+            is_list_len,
+            is_item_prefix,
+            is_item_len,
+            is_content,
+            is_padding,
+            is_single,
+            is_long,
+            is_node_end,
+            is_branch,
+            is_ext,
+            is_leaf,
+            is_empty,
+            node_path_acc,
+            node_path_len,
+            path_acc,
+            path_len,
+            len_rem,
+            len_acc,
+            item_rem,
+            item_rem_inv,
+            item_count,
+            node_rem,
+            is_hp,
+            is_hp_first,
+            is_odd,
+            nib_lo,
+            is_ref,
+            is_lo,
+            ref_acc,
+            ref_hi,
+            is_ref_end,
+            child_path_acc,
+            child_path_len,
+            len,
+            rlc,
+            hash,
+            leaf_count,
+            id_offset,
+            id_end,
+            is_value,
+            is_value_first,
+            is_typed,
+            tx_type,
+            caller,
+            caller_inv,
+            value_rlc,
+            value_len,
+            wd,
+            rlp,
+        }
+    }
+
+    /// Load the fixed table of the fields of the transaction envelopes, for
+    /// the transactions trie
+    pub(super) fn load<F: Field>(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        match &self.rlp {
+            Some(rlp) => rlp.load(layouter),
+            None => Ok(()),
+        }
+    }
+
+    /// Assign the nodes of the trie of the block of the given index in the
+    /// chunk, root first and leaves in the order of their index, and bind the
+    /// root to the public input. The ids of the leaves are offset by the
+    /// value of the `id_offsets` cells, which are all bound to it, and the
+    /// id offset of the next block is returned. The callers of the
+    /// transactions are empty for the withdrawals trie.
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn assign<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        nodes: &[TrieNode],
+        callers: &[Address],
+        capacity: usize,
+        block_index: usize,
+        is_last: bool,
+        id_offsets: &[&AssignedCell<F, F>],
+        root: &Word<AssignedCell<F, F>>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        assert!(
+            capacity >= self.max_leaves,
+            "tx trie must hold the key table"
+        );
+
+        let mut leaf_count = 0;
+        let mut rows = Vec::with_capacity(capacity);
+        for node in nodes {
+            if node.kind == TrieNodeKind::Leaf {
+                assert_eq!(node.index, Some(leaf_count), "leaves are sorted by index");
+                leaf_count += 1;
+            }
+            let hash = node.hash();
+            let mut new_rows = node_rows(node)?;
+            if self.rlp.is_some() && node.kind == TrieNodeKind::Leaf {
+                let envelope = new_rows
+                    .iter()
+                    .filter(|row| row.is_value())
+                    .map(|row| row.byte)
+                    .collect::<Vec<_>>();
+                let tx_rlp = tx_rlp_rows(&envelope, true);
+                let n_bytes = tx_rlp.iter().filter(|row| !row.is_unbounded()).count();
+                if n_bytes > N_BYTES_TX_ENVELOPE {
+                    error!(
+                        "envelope of tx {} takes {} bytes besides its call data and blobs, over {}",
+                        leaf_count, n_bytes, N_BYTES_TX_ENVELOPE
+                    );
+                    return Err(Error::Synthesis);
+                }
+                for (row, tx_rlp) in new_rows.iter_mut().filter(|row| row.is_value()).zip(tx_rlp) {
+                    row.tx_rlp = tx_rlp;
+                }
+            }
+            rows.extend(new_rows.into_iter().map(|row| TrieRow {
+                caller: if row.is_value_first && self.wd.is_none() {
+                    callers[leaf_count - 1]
+                } else {
+                    Address::zero()
+                },
+                hash,
+                leaf_count: leaf_count as u64,
+                ..row
+            }));
+        }
+        if rows.len() > capacity {
+            error!(
+                "trie of {} rows exceeds the capacity of {} rows",
+                rows.len(),
+                capacity
+            );
+            return Err(Error::Synthesis);
+        }
+        // Padding carries the counters of the last node
+        let last = rows.last().expect("trie has a root").clone();
+        rows.resize(
+            capacity,
+            TrieRow {
+                path: last.path,
+                item_count: last.item_count,
+                leaf_count: last.leaf_count,
+                ..Default::default()
+            },
+        );
+        let id_offset = id_offsets[0].value().copied();
+        let open = id_offset.map(|id_offset| {
+            F::from(self.max_leaves as u64) - id_offset - F::from(leaf_count as u64)
+        });
+
+        layouter.assign_region(
+            || "tx trie",
+            |mut region| {
+                let mut id_end = None;
+                let mut rlc = Value::known(F::ZERO);
+                let mut value_rlc = Value::known(F::ZERO);
+                let mut value_len = 0;
+                let mut wd_row = WdValueRow::<F>::default();
+
+                for (offset, row) in rows.iter().enumerate() {
+                    let key =
+                        (offset < self.max_leaves).then(|| to_nibbles(&ordered_trie_key(offset)));
+                    for (name, column, value) in [
+                        ("q_trie", self.q_trie, 1),
+                        ("q_trie_first", self.q_trie_first, (offset == 0) as u64),
+                        (
+                            "q_trie_last",
+                            self.q_trie_last,
+                            (offset == capacity - 1) as u64,
+                        ),
+                        (
+                            "q_chunk_last",
+                            self.q_chunk_last,
+                            (is_last && offset == capacity - 1) as u64,
+                        ),
+                        ("block_index", self.block_index, block_index as u64),
+                        ("q_key", self.q_key, key.is_some() as u64),
+                        (
+                            "key_index",
+                            self.key_index,
+                            key.as_ref().map_or(0, |_| offset as u64),
+                        ),
+                        (
+                            "key_path_acc",
+                            self.key_path_acc,
+                            key.as_ref().map_or(0, |key| nibbles_acc(key)),
+                        ),
+                        (
+                            "key_path_len",
+                            self.key_path_len,
+                            key.as_ref().map_or(0, |key| key.len() as u64),
+                        ),
+                    ] {
+                        region.assign_fixed(
+                            || name,
+                            column,
+                            offset,
+                            || Value::known(F::from(value)),
+                        )?;
+                    }
+
+                    let byte = F::from(row.byte as u64);
+                    match row.tag {
+                        RowTag::NodeStart => rlc = Value::known(byte),
+                        RowTag::Padding => (),
+                        _ => {
+                            rlc = rlc
+                                .zip(challenges.keccak_input())
+                                .map(|(rlc, r)| rlc * r + byte)
+                        }
+                    }
+                    let is_value = row.is_value();
+                    (value_rlc, value_len) = match (is_value, row.is_value_first) {
+                        (false, _) => (Value::known(F::ZERO), 0),
+                        (true, true) => (Value::known(byte), 1),
+                        (true, false) => (
+                            value_rlc
+                                .zip(challenges.keccak_input())
+                                .map(|(rlc, r)| rlc * r + byte),
+                            value_len + 1,
+                        ),
+                    };
+                    wd_row = if is_value && self.wd.is_some() {
+                        wd_row.next(row.byte, row.is_value_first)
+                    } else {
+                        WdValueRow::default()
+                    };
+                    let item_rem = F::from(row.item_rem);
+                    let caller = Word::<F>::from(row.caller);
+                    let caller_inv = match (offset == capacity - 1, is_last) {
+                        (true, true) => open.map(|open| open.invert().unwrap_or(F::ZERO)),
+                        (true, false) => Value::known(F::ZERO),
+                        (false, _) => Value::known(
+                            (caller.lo() + caller.hi() * (F::from_u128(u128::MAX) + F::ONE))
+                                .invert()
+                                .unwrap_or(F::ZERO),
+                        ),
+                    };
+
+                    for (name, column, value) in [
+                        ("byte", self.byte, byte),
+                        (
+                            "is_node_start",
+                            self.is_node_start,
+                            F::from((row.tag == RowTag::NodeStart) as u64),
+                        ),
+                        (
+                            "is_list_len",
+                            self.is_list_len,
+                            F::from((row.tag == RowTag::ListLen) as u64),
+                        ),
+                        (
+                            "is_item_prefix",
+                            self.is_item_prefix,
+                            F::from((row.tag == RowTag::ItemPrefix) as u64),
+                        ),
+                        (
+                            "is_item_len",
+                            self.is_item_len,
+                            F::from((row.tag == RowTag::ItemLen) as u64),
+                        ),
+                        (
+                            "is_content",
+                            self.is_content,
+                            F::from((row.tag == RowTag::Content) as u64),
+                        ),
+                        (
+                            "is_padding",
+                            self.is_padding,
+                            F::from((row.tag == RowTag::Padding) as u64),
+                        ),
+                        ("is_single", self.is_single, F::from(row.is_single as u64)),
+                        ("is_long", self.is_long, F::from(row.is_long as u64)),
+                        (
+                            "is_node_end",
+                            self.is_node_end,
+                            F::from(row.is_node_end as u64),
+                        ),
+                        (
+                            "is_branch",
+                            self.is_branch,
+                            F::from((row.kind == Some(TrieNodeKind::Branch)) as u64),
+                        ),
+                        (
+                            "is_ext",
+                            self.is_ext,
+                            F::from((row.kind == Some(TrieNodeKind::Extension)) as u64),
+                        ),
+                        (
+                            "is_leaf",
+                            self.is_leaf,
+                            F::from((row.kind == Some(TrieNodeKind::Leaf)) as u64),
+                        ),
+                        (
+                            "is_empty",
+                            self.is_empty,
+                            F::from((row.kind == Some(TrieNodeKind::Empty)) as u64),
+                        ),
+                        (
+                            "node_path_acc",
+                            self.node_path_acc,
+                            F::from(row.node_path.0),
+                        ),
+                        (
+                            "node_path_len",
+                            self.node_path_len,
+                            F::from(row.node_path.1),
+                        ),
+                        ("path_acc", self.path_acc, F::from(row.path.0)),
+                        ("path_len", self.path_len, F::from(row.path.1)),
+                        ("len_rem", self.len_rem, F::from(row.len_rem)),
+                        ("len_acc", self.len_acc, F::from(row.len_acc)),
+                        ("item_rem", self.item_rem, item_rem),
+                        (
+                            "item_rem_inv",
+                            self.item_rem_inv,
+                            item_rem.invert().unwrap_or(F::ZERO),
+                        ),
+                        ("item_count", self.item_count, F::from(row.item_count)),
+                        ("node_rem", self.node_rem, F::from(row.node_rem)),
+                        ("is_hp", self.is_hp, F::from(row.is_hp as u64)),
+                        (
+                            "is_hp_first",
+                            self.is_hp_first,
+                            F::from(row.is_hp_first as u64),
+                        ),
+                        ("is_odd", self.is_odd, F::from(row.is_odd as u64)),
+                        ("nib_lo", self.nib_lo, F::from(row.nib_lo as u64)),
+                        ("is_ref", self.is_ref, F::from(row.is_ref as u64)),
+                        ("is_lo", self.is_lo, F::from(row.is_lo as u64)),
+                        (
+                            "is_ref_end",
+                            self.is_ref_end,
+                            F::from((offset == 0 || row.is_ref_end) as u64),
+                        ),
+                        (
+                            "child_path_acc",
+                            self.child_path_acc,
+                            F::from(row.child_path.0),
+                        ),
+                        (
+                            "child_path_len",
+                            self.child_path_len,
+                            F::from(row.child_path.1),
+                        ),
+                        ("len", self.len, F::from(row.len)),
+                        ("leaf_count", self.leaf_count, F::from(row.leaf_count)),
+                        ("is_value", self.is_value, F::from(is_value as u64)),
+                        (
+                            "is_value_first",
+                            self.is_value_first,
+                            F::from(row.is_value_first as u64),
+                        ),
+                        ("is_typed", self.is_typed, F::from(row.is_typed as u64)),
+                        ("tx_type", self.tx_type, F::from(row.tx_type as u64)),
+                        ("value_len", self.value_len, F::from(value_len)),
+                    ] {
+                        region.assign_advice(|| name, column, offset, || Value::known(value))?;
+                    }
+                    region.assign_advice(
+                        || "caller_inv",
+                        self.caller_inv,
+                        offset,
+                        || caller_inv,
+                    )?;
+                    region.assign_advice(|| "rlc", self.rlc, offset, || rlc)?;
+                    region.assign_advice(|| "value_rlc", self.value_rlc, offset, || value_rlc)?;
+                    Word::<F>::from(row.hash).into_value().assign_advice(
+                        &mut region,
+                        || "hash",
+                        self.hash,
+                        offset,
+                    )?;
+                    caller.into_value().assign_advice(
+                        &mut region,
+                        || "caller",
+                        self.caller,
+                        offset,
+                    )?;
+                    if let Some(wd) = &self.wd {
+                        let rem = F::from(wd_row.rem);
+                        for (name, column, value) in iter::empty()
+                            .chain(wd.is_field.iter().enumerate().map(|(i, column)| {
+                                (
+                                    "wd is_field",
+                                    *column,
+                                    F::from((wd_row.field == Some(i)) as u64),
+                                )
+                            }))
+                            .chain(
+                                wd.fields
+                                    .iter()
+                                    .zip(wd_row.fields)
+                                    .map(|(column, field)| ("wd fields", *column, field)),
+                            )
+                            .chain([
+                                (
+                                    "wd is_single",
+                                    wd.is_single,
+                                    F::from(wd_row.is_single as u64),
+                                ),
+                                ("wd rem", wd.rem, rem),
+                                ("wd rem_inv", wd.rem_inv, rem.invert().unwrap_or(F::ZERO)),
+                                ("wd acc", wd.acc, wd_row.acc),
+                                ("wd list_len", wd.list_len, F::from(wd_row.list_len)),
+                            ])
+                        {
+                            region.assign_advice(
+                                || name,
+                                column,
+                                offset,
+                                || Value::known(value),
+                            )?;
+                        }
+                    }
+
+                    if let Some(rlp) = &self.rlp {
+                        rlp.assign_row(&mut region, offset, &row.tx_rlp)?;
+                    }
+
+                    let id_end_cell = region.assign_advice(
+                        || "id_end",
+                        self.id_end,
+                        offset,
+                        || id_offset + Value::known(F::from(row.leaf_count)),
+                    )?;
+                    if offset == capacity - 1 {
+                        id_end = Some(id_end_cell);
+                    }
+
+                    if offset == 0 {
+                        let id_offset_cell = id_offsets[0].copy_advice(
+                            || "id_offset",
+                            &mut region,
+                            self.id_offset,
+                            offset,
+                        )?;
+                        for other in &id_offsets[1..] {
+                            region.constrain_equal(other.cell(), id_offset_cell.cell())?;
+                        }
+                        root.lo()
+                            .copy_advice(|| "ref_acc", &mut region, self.ref_acc, offset)?;
+                        root.hi()
+                            .copy_advice(|| "ref_hi", &mut region, self.ref_hi, offset)?;
+                    } else {
+                        region.assign_advice(
+                            || "id_offset",
+                            self.id_offset,
+                            offset,
+                            || id_offset,
+                        )?;
+                        for (name, column, value) in [
+                            ("ref_acc", self.ref_acc, row.ref_acc),
+                            ("ref_hi", self.ref_hi, row.ref_hi),
+                        ] {
+                            region.assign_advice(
+                                || name,
+                                column,
+                                offset,
+                                || Value::known(F::from_u128(value)),
+                            )?;
+                        }
+                    }
+                }
+
+                Ok(id_end.expect("trie has a last row"))
+            },
+        )
+    }
+}
@@ -0,0 +1,2028 @@
+//! The receipt circuit implementation.
+//!
+//! The circuit verifies the receipts of the block transactions against the RW
+//! table, and the logs bloom of the block against the logs of the receipts.
+//! The logs bloom of the block takes the first [`N_BLOOM_ROWS`] rows, one
+//! byte per row, followed by the EIP-2718 envelope of every receipt, one byte
+//! per row, parsed as the RLP list of the status, the cumulative gas used,
+//! the logs bloom of the receipt and the logs. The fields of the receipts are
+//! bound to the RW table:
+//! - the status, the cumulative gas used and the number of logs to the `TxReceipt` rows of the
+//!   transaction,
+//! - the address, the topics, the data bytes, the number of topics and the number of data bytes of
+//!   every log to the `TxLog` rows of the transaction.
+//!
+//! The keccak hash of every address and topic is looked up in the keccak
+//! table, and the three bits it accrues to a bloom are set in the bloom of its
+//! receipt and in the bloom of the block. Conversely, every bit set in the
+//! bloom of a receipt is accrued by one of its addresses or topics, and every
+//! bit set in the bloom of the block by an address or topic of any receipt.
+//!
+//! The envelopes and the logs bloom of the block are exposed in the
+//! [`ReceiptTable`], where the PI circuit looks them up from the leaves of
+//! the receipts trie and from the block header. As the receipt of every
+//! transaction id is bound to its `TxReceipt` rows, there is no receipt for
+//! a transaction which is not part of the block.
+#[cfg(any(test, feature = "test-circuits"))]
+mod dev;
+#[cfg(test)]
+mod test;
+#[cfg(feature = "test-circuits")]
+pub use dev::ReceiptCircuit as TestReceiptCircuit;
+
+use std::marker::PhantomData;
+
+use crate::{
+    evm_circuit::util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+    table::{
+        KeccakTable, LookupTable, ReceiptFieldTag, ReceiptTable, RwTable, TxLogFieldTag,
+        TxReceiptFieldTag, UXTable, N_BYTES_BLOOM_LIMB,
+    },
+    util::{build_tx_log_expression, Challenges, SubCircuit, SubCircuitConfig},
+    witness::{self, RwMap},
+};
+use bus_mapping::operation::Target;
+use eth_types::{
+    geth_types::{logs_bloom, Receipt},
+    keccak256, Field,
+};
+use gadgets::util::{not, Expr};
+use halo2_proofs::{
+    circuit::{Layouter, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, VirtualCells,
+    },
+    poly::Rotation,
+};
+use itertools::Itertools;
+use strum::{EnumCount, IntoEnumIterator};
+use strum_macros::{EnumCount, EnumIter};
+
+/// Number of rows of the logs bloom of the block.
+pub const N_BLOOM_ROWS: usize = 256;
+/// Maximum length of a receipt envelope besides its logs: type, list prefix,
+/// status, cumulative gas used, logs bloom and logs list prefix.
+pub const N_BYTES_RECEIPT: usize = 1 + 3 + 1 + 9 + 259 + 3;
+/// Number of rows of the fixed tables of the circuit, the largest being the
+/// bits of every byte.
+const N_FIXED_ROWS: usize = 256 * 8;
+/// Number of bytes of the keccak hash of an address or topic which are
+/// accumulated in the hi limb looked up in the keccak table.
+const N_BYTES_HASH_HI: usize = 16;
+
+/// Tag of a row of the circuit
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, EnumCount, EnumIter)]
+enum RowTag {
+    /// Byte of the logs bloom of the block
+    BlockBloom,
+    /// EIP-2718 type of a typed receipt
+    TxType,
+    /// Prefix and length bytes of the list of the receipt
+    ReceiptPrefix,
+    ReceiptLen,
+    /// Status, encoded as 0x01 or the empty string
+    Status,
+    /// Prefix and content of the cumulative gas used
+    GasPrefix,
+    Gas,
+    /// Prefix, length bytes and content of the logs bloom of the receipt
+    BloomPrefix,
+    BloomLen,
+    Bloom,
+    /// Prefix and length bytes of the list of the logs
+    LogsPrefix,
+    LogsLen,
+    /// Prefix and length bytes of the list of a log
+    LogPrefix,
+    LogLen,
+    /// Prefix and content of the address of a log
+    AddressPrefix,
+    Address,
+    /// Prefix and length bytes of the list of the topics of a log
+    TopicsPrefix,
+    TopicsLen,
+    /// Prefix and content of a topic
+    TopicPrefix,
+    Topic,
+    /// Prefix, length bytes and content of the data of a log, or a single
+    /// byte encoded as itself
+    DataPrefix,
+    DataLen,
+    Data,
+    /// Row after the last receipt
+    #[default]
+    Padding,
+}
+
+impl RowTag {
+    const LIST_PREFIXES: [Self; 4] = [
+        Self::ReceiptPrefix,
+        Self::LogsPrefix,
+        Self::LogPrefix,
+        Self::TopicsPrefix,
+    ];
+    const STR_PREFIXES: [Self; 5] = [
+        Self::GasPrefix,
+        Self::BloomPrefix,
+        Self::AddressPrefix,
+        Self::TopicPrefix,
+        Self::DataPrefix,
+    ];
+    /// Prefixes which can be long, with their length bytes
+    const HEADERS: [(Self, Self); 6] = [
+        (Self::ReceiptPrefix, Self::ReceiptLen),
+        (Self::BloomPrefix, Self::BloomLen),
+        (Self::LogsPrefix, Self::LogsLen),
+        (Self::LogPrefix, Self::LogLen),
+        (Self::TopicsPrefix, Self::TopicsLen),
+        (Self::DataPrefix, Self::DataLen),
+    ];
+    /// Prefix, length bytes if any and content of the strings
+    const STRINGS: [(Self, Option<Self>, Self); 5] = [
+        (Self::GasPrefix, None, Self::Gas),
+        (Self::BloomPrefix, Some(Self::BloomLen), Self::Bloom),
+        (Self::AddressPrefix, None, Self::Address),
+        (Self::TopicPrefix, None, Self::Topic),
+        (Self::DataPrefix, Some(Self::DataLen), Self::Data),
+    ];
+    const LENS: [Self; 6] = [
+        Self::ReceiptLen,
+        Self::BloomLen,
+        Self::LogsLen,
+        Self::LogLen,
+        Self::TopicsLen,
+        Self::DataLen,
+    ];
+    const CONTENTS: [Self; 5] = [
+        Self::Gas,
+        Self::Bloom,
+        Self::Address,
+        Self::Topic,
+        Self::Data,
+    ];
+    /// Rows of the payload of the list of the receipt
+    const PAYLOAD: [Self; 19] = [
+        Self::Status,
+        Self::GasPrefix,
+        Self::Gas,
+        Self::BloomPrefix,
+        Self::BloomLen,
+        Self::Bloom,
+        Self::LogsPrefix,
+        Self::LogsLen,
+        Self::LogPrefix,
+        Self::LogLen,
+        Self::AddressPrefix,
+        Self::Address,
+        Self::TopicsPrefix,
+        Self::TopicsLen,
+        Self::TopicPrefix,
+        Self::Topic,
+        Self::DataPrefix,
+        Self::DataLen,
+        Self::Data,
+    ];
+    /// Rows of the payload of the list of a log
+    const LOG: [Self; 9] = [
+        Self::AddressPrefix,
+        Self::Address,
+        Self::TopicsPrefix,
+        Self::TopicsLen,
+        Self::TopicPrefix,
+        Self::Topic,
+        Self::DataPrefix,
+        Self::DataLen,
+        Self::Data,
+    ];
+    /// Rows of the payload of the list of the topics
+    const TOPICS: [Self; 2] = [Self::TopicPrefix, Self::Topic];
+    /// Content of the strings accrued to the blooms
+    const ITEMS: [Self; 2] = [Self::Address, Self::Topic];
+
+    fn is_in(self, tags: &[Self]) -> bool {
+        tags.contains(&self)
+    }
+}
+
+/// Witness of a row of the circuit
+#[derive(Clone, Debug, Default)]
+struct ReceiptRow {
+    byte: u8,
+    tag: RowTag,
+    is_single: bool,
+    is_long: bool,
+    len_rem: u64,
+    len_acc: u64,
+    is_hdr_end: bool,
+    hdr_len: u64,
+    rem_receipt: u64,
+    is_end: bool,
+    rem_log: u64,
+    rem_topics: u64,
+    is_topics_end: bool,
+    rem_str: u64,
+    is_str_end: bool,
+    str_len: u64,
+    tx_id: u64,
+    log_id: u64,
+    topic_count: u64,
+    acc: u128,
+    acc_hi: u128,
+    is_lo: bool,
+    is_hash: bool,
+    is_bit: bool,
+    hash_byte: u8,
+    hash_acc: u128,
+    hash_lo: u128,
+    bit_index: u64,
+    bloom_byte: u8,
+    block_byte: u8,
+    bit_tx: [u64; 8],
+    len: u64,
+}
+
+/// Bytes of an envelope laid out with their tag
+struct EnvelopeLayout {
+    rlp: Vec<u8>,
+    offset: usize,
+    rows: Vec<ReceiptRow>,
+}
+
+impl EnvelopeLayout {
+    /// Push the row of the next byte of the envelope
+    fn push(&mut self, tag: RowTag) -> u8 {
+        let byte = self.rlp[self.offset];
+        self.offset += 1;
+        self.rows.push(ReceiptRow {
+            byte,
+            tag,
+            ..Default::default()
+        });
+        byte
+    }
+
+    /// Push the prefix and length bytes of a list or a string, and return the
+    /// length of its payload
+    fn header(&mut self, prefix: RowTag, len: Option<RowTag>) -> usize {
+        let byte = self.push(prefix);
+        let (base, long_base) = if byte >= 0xc0 {
+            (0xc0, 0xf7)
+        } else {
+            (0x80, 0xb7)
+        };
+        if byte > long_base {
+            let len = len.expect("only lists and strings with a length tag are long");
+            (0..byte - long_base).fold(0, |acc, _| acc * 256 + self.push(len) as usize)
+        } else {
+            (byte - base) as usize
+        }
+    }
+
+    /// Push the prefix, length bytes and content of a string
+    fn string(&mut self, prefix: RowTag, len: Option<RowTag>, content: RowTag) {
+        for _ in 0..self.header(prefix, len) {
+            self.push(content);
+        }
+    }
+}
+
+/// Lay out the bytes of the envelope of a receipt, whose cumulative gas used
+/// is never encoded as a single byte, being at least the intrinsic gas of a
+/// transaction.
+fn envelope_rows(receipt: &Receipt) -> Vec<ReceiptRow> {
+    let mut layout = EnvelopeLayout {
+        rlp: receipt.rlp(),
+        offset: 0,
+        rows: vec![],
+    };
+    if receipt.tx_type != 0 {
+        layout.push(RowTag::TxType);
+    }
+    layout.header(RowTag::ReceiptPrefix, Some(RowTag::ReceiptLen));
+    layout.push(RowTag::Status);
+    layout.string(RowTag::GasPrefix, None, RowTag::Gas);
+    layout.string(RowTag::BloomPrefix, Some(RowTag::BloomLen), RowTag::Bloom);
+    layout.header(RowTag::LogsPrefix, Some(RowTag::LogsLen));
+    for log in receipt.logs.iter() {
+        layout.header(RowTag::LogPrefix, Some(RowTag::LogLen));
+        layout.string(RowTag::AddressPrefix, None, RowTag::Address);
+        layout.header(RowTag::TopicsPrefix, Some(RowTag::TopicsLen));
+        for _ in log.topics.iter() {
+            layout.string(RowTag::TopicPrefix, None, RowTag::Topic);
+        }
+        if layout.rlp[layout.offset] < 0x80 {
+            layout.push(RowTag::Data);
+            layout.rows.last_mut().expect("data is pushed").is_single = true;
+        } else {
+            layout.string(RowTag::DataPrefix, Some(RowTag::DataLen), RowTag::Data);
+        }
+    }
+    assert_eq!(
+        layout.offset,
+        layout.rlp.len(),
+        "envelope is fully laid out"
+    );
+    layout.rows
+}
+
+/// Derive the witness of an envelope row from its byte, its tag and the
+/// previous row
+fn derive_row(prev: &ReceiptRow, row: &mut ReceiptRow, item_hash: &[u8; 32]) {
+    let tag = row.tag;
+    let byte = row.byte as u64;
+    let prev_end = prev.is_end || prev.tag == RowTag::BlockBloom;
+    let is_list_prefix = tag.is_in(&RowTag::LIST_PREFIXES);
+    let is_str_prefix = tag.is_in(&RowTag::STR_PREFIXES);
+    let is_len = tag.is_in(&RowTag::LENS);
+    let is_content = tag.is_in(&RowTag::CONTENTS);
+
+    // RLP headers
+    row.is_long = (is_list_prefix && byte > 0xf7) || (is_str_prefix && byte > 0xb7 && byte < 0xc0);
+    row.len_rem = match (row.is_long, is_list_prefix, is_len) {
+        (true, true, _) => byte - 0xf7,
+        (true, false, _) => byte - 0xb7,
+        (false, _, true) => prev.len_rem - 1,
+        _ => 0,
+    };
+    row.len_acc = if is_len { prev.len_acc * 256 + byte } else { 0 };
+    row.is_hdr_end =
+        ((is_list_prefix || is_str_prefix) && !row.is_long) || (is_len && row.len_rem == 0);
+    row.hdr_len = match (row.is_hdr_end, is_len, is_list_prefix) {
+        (false, _, _) => 0,
+        (true, true, _) => row.len_acc,
+        (true, false, true) => byte - 0xc0,
+        (true, false, false) => byte - 0x80,
+    };
+
+    // Remaining bytes of the receipt, log, topics and string
+    row.rem_receipt = if tag.is_in(&RowTag::PAYLOAD) {
+        prev.rem_receipt - 1
+    } else if tag == RowTag::ReceiptLen && row.is_hdr_end {
+        row.hdr_len
+    } else {
+        0
+    };
+    row.is_end = tag.is_in(&RowTag::PAYLOAD) && row.rem_receipt == 0;
+    row.rem_log = if tag.is_in(&RowTag::LOG) {
+        prev.rem_log - 1
+    } else if tag.is_in(&[RowTag::LogPrefix, RowTag::LogLen]) && row.is_hdr_end {
+        row.hdr_len
+    } else {
+        0
+    };
+    let topics_hdr_end = tag.is_in(&[RowTag::TopicsPrefix, RowTag::TopicsLen]) && row.is_hdr_end;
+    row.rem_topics = if tag.is_in(&RowTag::TOPICS) {
+        prev.rem_topics - 1
+    } else if topics_hdr_end {
+        row.hdr_len
+    } else {
+        0
+    };
+    let str_hdr_end =
+        row.is_hdr_end && (is_str_prefix || tag.is_in(&[RowTag::BloomLen, RowTag::DataLen]));
+    row.rem_str = if str_hdr_end {
+        row.hdr_len
+    } else if is_content && !row.is_single {
+        prev.rem_str - 1
+    } else {
+        0
+    };
+    row.is_str_end = (str_hdr_end || is_content) && row.rem_str == 0;
+    row.is_topics_end =
+        (topics_hdr_end || (tag == RowTag::Topic && row.is_str_end)) && row.rem_topics == 0;
+    row.str_len = if str_hdr_end {
+        row.hdr_len
+    } else if row.is_single {
+        1
+    } else if is_content {
+        prev.str_len
+    } else {
+        0
+    };
+
+    // Identifiers of the transaction and the log
+    row.tx_id = prev.tx_id + (prev_end && tag != RowTag::Padding) as u64;
+    row.log_id = if prev_end { 0 } else { prev.log_id } + (tag == RowTag::LogPrefix) as u64;
+    row.topic_count = if tag == RowTag::LogPrefix {
+        0
+    } else {
+        prev.topic_count
+    } + (tag == RowTag::TopicPrefix) as u64;
+    row.len = match (tag, prev_end) {
+        (RowTag::Padding, _) => 0,
+        (_, true) => 1,
+        (_, false) => prev.len + 1,
+    };
+
+    // Values of the gas, addresses and topics, in 16-byte limbs
+    if tag.is_in(&[RowTag::Gas, RowTag::Address, RowTag::Topic]) {
+        row.is_lo = tag != RowTag::Gas && row.rem_str < 16;
+        let reset = prev.tag.is_in(&[
+            RowTag::GasPrefix,
+            RowTag::AddressPrefix,
+            RowTag::TopicPrefix,
+        ]) || (row.is_lo && !prev.is_lo);
+        row.acc = if reset {
+            byte as u128
+        } else {
+            prev.acc * 256 + byte as u128
+        };
+        row.acc_hi = match (row.is_lo, prev.is_lo) {
+            (true, true) => prev.acc_hi,
+            (true, false) => prev.acc,
+            (false, _) => 0,
+        };
+    }
+
+    // Hash of the addresses and topics, and the bits they accrue to the blooms
+    if tag.is_in(&RowTag::ITEMS) {
+        let k = (row.str_len - 1 - row.rem_str) as usize;
+        let is_first = prev
+            .tag
+            .is_in(&[RowTag::AddressPrefix, RowTag::TopicPrefix]);
+        row.is_hash = k < N_BYTES_HASH_HI;
+        row.is_bit = k == 1 || k == 3 || k == 5;
+        row.hash_byte = if row.is_hash { item_hash[k] } else { 0 };
+        row.hash_acc = match (row.is_hash, is_first) {
+            (true, true) => row.hash_byte as u128,
+            (true, false) => prev.hash_acc * 256 + row.hash_byte as u128,
+            (false, _) => prev.hash_acc,
+        };
+        if row.is_str_end {
+            row.hash_lo = item_hash[N_BYTES_HASH_HI..]
+                .iter()
+                .fold(0, |acc, byte| acc * 256 + *byte as u128);
+        }
+        if row.is_bit {
+            row.bit_index = 255 - ((prev.hash_byte & 7) as u64 * 32 + (row.hash_byte >> 3) as u64);
+        }
+    }
+}
+
+/// Lay out the logs bloom of the block and the envelopes of the receipts,
+/// padded up to the capacity
+fn circuit_rows(receipts: &[Receipt], capacity: usize) -> Vec<ReceiptRow> {
+    let blooms = receipts.iter().map(|r| r.logs_bloom()).collect_vec();
+    let block_bloom = logs_bloom(receipts);
+
+    let mut rows = block_bloom
+        .as_bytes()
+        .iter()
+        .enumerate()
+        .map(|(index, byte)| {
+            let bit_tx = std::array::from_fn(|j| {
+                blooms
+                    .iter()
+                    .position(|bloom| (bloom.as_bytes()[index] >> j) & 1 == 1)
+                    .map_or(0, |i| i as u64 + 1)
+            });
+            ReceiptRow {
+                byte: *byte,
+                tag: RowTag::BlockBloom,
+                bit_tx,
+                ..Default::default()
+            }
+        })
+        .collect_vec();
+    for (i, row) in rows.iter_mut().enumerate() {
+        row.acc = block_bloom.as_bytes()[i - i % N_BYTES_BLOOM_LIMB..=i]
+            .iter()
+            .fold(0, |acc, byte| acc * 256 + *byte as u128);
+    }
+
+    for receipt in receipts {
+        rows.extend(envelope_rows(receipt));
+    }
+    assert!(rows.len() < capacity, "receipts exceed the capacity");
+    rows.resize(capacity, ReceiptRow::default());
+
+    let mut item_hash = [0; 32];
+    for i in N_BLOOM_ROWS..capacity {
+        if rows[i - 1]
+            .tag
+            .is_in(&[RowTag::AddressPrefix, RowTag::TopicPrefix])
+        {
+            let len = (rows[i - 1].byte - 0x80) as usize;
+            item_hash = keccak256(&rows[i..i + len].iter().map(|row| row.byte).collect_vec());
+        }
+        let (done, rest) = rows.split_at_mut(i);
+        let row = &mut rest[0];
+        derive_row(&done[i - 1], row, &item_hash);
+        if row.is_bit {
+            row.bloom_byte = blooms[row.tx_id as usize - 1].as_bytes()[row.bit_index as usize];
+            row.block_byte = block_bloom.as_bytes()[row.bit_index as usize];
+        }
+    }
+    rows
+}
+
+/// ReceiptCircuitConfig
+#[derive(Clone, Debug)]
+pub struct ReceiptCircuitConfig<F> {
+    // q_block_bloom: 1 on the rows of the logs bloom of the block
+    q_block_bloom: Column<Fixed>,
+    // q_receipt: 1 on the rows of the envelopes and padding
+    q_receipt: Column<Fixed>,
+    // q_last: 1 on the last row, which is padding
+    q_last: Column<Fixed>,
+    // First and last byte of every limb of the logs bloom of the block
+    q_limb_start: Column<Fixed>,
+    q_limb_end: Column<Fixed>,
+    limb_index: Column<Fixed>,
+    // block_index: index of the byte of the logs bloom of the block
+    block_index: Column<Fixed>,
+    // Fixed tables: the 5 hi bits and 3 lo bits of every byte, every bit of
+    // every byte, and whether the byte of the given position (plus one) of a
+    // hash is part of the hi limb, and is the lo byte of a bloom bit index
+    split_byte: Column<Fixed>,
+    split_hi: Column<Fixed>,
+    split_lo: Column<Fixed>,
+    bit_byte: Column<Fixed>,
+    bit_pos: Column<Fixed>,
+    bit_value: Column<Fixed>,
+    pos_k: Column<Fixed>,
+    pos_is_hash: Column<Fixed>,
+    pos_is_bit: Column<Fixed>,
+
+    // byte: logs bloom of the block, envelopes of the receipts and padding
+    byte: Column<Advice>,
+    // One-hot tag of the row
+    tags: [Column<Advice>; RowTag::COUNT],
+    // is_single: data which is a single byte encoded as itself
+    is_single: Column<Advice>,
+    // is_long: prefix followed by length bytes
+    is_long: Column<Advice>,
+    // len_rem: number of length bytes after the row
+    len_rem: Column<Advice>,
+    // len_acc: length bytes accumulated with base 256
+    len_acc: Column<Advice>,
+    // is_hdr_end: last byte of the prefix and length of a list or string
+    is_hdr_end: Column<Advice>,
+    // hdr_len: length of the payload of the list or string, on its header end
+    hdr_len: Column<Advice>,
+    // rem_receipt: number of bytes of the receipt list after the row
+    rem_receipt: Column<Advice>,
+    rem_receipt_inv: Column<Advice>,
+    // is_end: last byte of a receipt
+    is_end: Column<Advice>,
+    // rem_log: number of bytes of the log list after the row
+    rem_log: Column<Advice>,
+    // rem_topics: number of bytes of the topics list after the row
+    rem_topics: Column<Advice>,
+    rem_topics_inv: Column<Advice>,
+    // is_topics_end: last byte of the topics list
+    is_topics_end: Column<Advice>,
+    // rem_str: number of content bytes of the string after the row
+    rem_str: Column<Advice>,
+    rem_str_inv: Column<Advice>,
+    // is_str_end: last byte of a string
+    is_str_end: Column<Advice>,
+    // str_len: length of the string
+    str_len: Column<Advice>,
+    // tx_id: id of the transaction of the receipt
+    tx_id: Column<Advice>,
+    // log_id: number of logs of the receipt up to the row
+    log_id: Column<Advice>,
+    // topic_count: number of topics of the log up to the row
+    topic_count: Column<Advice>,
+    // acc: bytes of the current limb of a value accumulated with base 256
+    acc: Column<Advice>,
+    // acc_hi: hi limb of the value, on the rows of the lo limb
+    acc_hi: Column<Advice>,
+    // is_lo: last 16 bytes of an address or topic
+    is_lo: Column<Advice>,
+    // is_hash: byte of an address or topic whose hash byte is in the hi limb
+    is_hash: Column<Advice>,
+    // is_bit: byte of an address or topic whose hash byte is the lo byte of a
+    // bloom bit index
+    is_bit: Column<Advice>,
+    // hash_byte: byte of the hash of the address or topic, and its 5 hi and 3 lo bits
+    hash_byte: Column<Advice>,
+    hash_hi: Column<Advice>,
+    hash_lo3: Column<Advice>,
+    // hash_acc: hi limb of the hash accumulated with base 256
+    hash_acc: Column<Advice>,
+    // hash_lo: lo limb of the hash, on the last byte of the address or topic
+    hash_lo: Column<Advice>,
+    // item_rlc: RLC of the address or topic by the keccak challenge
+    item_rlc: Column<Advice>,
+    // bit_index: index of the bloom byte of the bit accrued by the hash
+    bit_index: Column<Advice>,
+    // Bytes of the bloom of the receipt and of the block at bit_index
+    bloom_byte: Column<Advice>,
+    block_byte: Column<Advice>,
+    // bits: bits of the bloom bytes
+    bits: [Column<Advice>; 8],
+    // bit_tx: transaction accruing every bit of the logs bloom of the block
+    bit_tx: [Column<Advice>; 8],
+    // len: number of bytes of the envelope up to the row
+    len: Column<Advice>,
+    // rlc: RLC of the envelope up to the row by the keccak challenge
+    rlc: Column<Advice>,
+    /// The columns for other circuits to lookup the receipts and logs bloom
+    pub receipt_table: ReceiptTable,
+    _marker: PhantomData<F>,
+}
+
+/// Circuit configuration arguments
+pub struct ReceiptCircuitConfigArgs<F: Field> {
+    /// RwTable
+    pub rw_table: RwTable,
+    /// KeccakTable
+    pub keccak_table: KeccakTable,
+    /// ReceiptTable
+    pub receipt_table: ReceiptTable,
+    /// U8Table
+    pub u8_table: UXTable<8>,
+    /// U16Table
+    pub u16_table: UXTable<16>,
+    /// Challenges
+    pub challenges: Challenges<Expression<F>>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for ReceiptCircuitConfig<F> {
+    type ConfigArgs = ReceiptCircuitConfigArgs<F>;
+
+    /// Return a new ReceiptCircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            rw_table,
+            keccak_table,
+            receipt_table,
+            u8_table,
+            u16_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let q_block_bloom = meta.fixed_column();
+        let q_receipt = meta.fixed_column();
+        let q_last = meta.fixed_column();
+        let q_limb_start = meta.fixed_column();
+        let q_limb_end = meta.fixed_column();
+        let limb_index = meta.fixed_column();
+        let block_index = meta.fixed_column();
+        let split_byte = meta.fixed_column();
+        let split_hi = meta.fixed_column();
+        let split_lo = meta.fixed_column();
+        let bit_byte = meta.fixed_column();
+        let bit_pos = meta.fixed_column();
+        let bit_value = meta.fixed_column();
+        let pos_k = meta.fixed_column();
+        let pos_is_hash = meta.fixed_column();
+        let pos_is_bit = meta.fixed_column();
+
+        let byte = meta.advice_column();
+        let tags = std::array::from_fn(|_| meta.advice_column());
+        let is_single = meta.advice_column();
+        let is_long = meta.advice_column();
+        let len_rem = meta.advice_column();
+        let len_acc = meta.advice_column();
+        let is_hdr_end = meta.advice_column();
+        let hdr_len = meta.advice_column();
+        let rem_receipt = meta.advice_column();
+        let rem_receipt_inv = meta.advice_column();
+        let is_end = meta.advice_column();
+        let rem_log = meta.advice_column();
+        let rem_topics = meta.advice_column();
+        let rem_topics_inv = meta.advice_column();
+        let is_topics_end = meta.advice_column();
+        let rem_str = meta.advice_column();
+        let rem_str_inv = meta.advice_column();
+        let is_str_end = meta.advice_column();
+        let str_len = meta.advice_column();
+        let tx_id = meta.advice_column();
+        let log_id = meta.advice_column();
+        let topic_count = meta.advice_column();
+        let acc = meta.advice_column();
+        let acc_hi = meta.advice_column();
+        let is_lo = meta.advice_column();
+        let is_hash = meta.advice_column();
+        let is_bit = meta.advice_column();
+        let hash_byte = meta.advice_column();
+        let hash_hi = meta.advice_column();
+        let hash_lo3 = meta.advice_column();
+        let hash_acc = meta.advice_column();
+        let hash_lo = meta.advice_column();
+        let item_rlc = meta.advice_column_in(SecondPhase);
+        let bit_index = meta.advice_column();
+        let bloom_byte = meta.advice_column();
+        let block_byte = meta.advice_column();
+        let bits = std::array::from_fn(|_| meta.advice_column());
+        let bit_tx = std::array::from_fn(|_| meta.advice_column());
+        let len = meta.advice_column();
+        let rlc = meta.advice_column_in(SecondPhase);
+
+        let tag = |meta: &mut VirtualCells<'_, F>, tag: RowTag, rotation: Rotation| {
+            meta.query_advice(tags[tag as usize], rotation)
+        };
+        let tag_sum = |meta: &mut VirtualCells<'_, F>, list: &[RowTag], rotation: Rotation| {
+            list.iter()
+                .fold(0.expr(), |acc, t| acc + tag(meta, *t, rotation))
+        };
+        let inv_2 = Expression::Constant(F::from(2).invert().unwrap());
+        // Whether length bytes follow the row, as len_rem is at most 2
+        let has_len = |len_rem: Expression<F>| len_rem.expr() * (3.expr() - len_rem) * inv_2.expr();
+        let compose_bits = |meta: &mut VirtualCells<'_, F>| {
+            bits.iter().rev().fold(0.expr(), |acc, bit| {
+                acc * 2.expr() + meta.query_advice(*bit, Rotation::cur())
+            })
+        };
+
+        meta.create_gate("receipt circuit row", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            for column in tags {
+                cb.require_boolean(
+                    "tags are boolean",
+                    meta.query_advice(column, Rotation::cur()),
+                );
+            }
+            cb.require_equal(
+                "a row has a single tag",
+                tag_sum(meta, &RowTag::iter().collect_vec(), Rotation::cur()),
+                1.expr(),
+            );
+            cb.require_equal(
+                "the logs bloom of the block takes its rows",
+                tag(meta, RowTag::BlockBloom, Rotation::cur()),
+                meta.query_fixed(q_block_bloom, Rotation::cur()),
+            );
+            for bit in bits {
+                cb.require_boolean("bits are boolean", meta.query_advice(bit, Rotation::cur()));
+            }
+            cb.require_zero(
+                "bits are the bits of the bloom bytes",
+                tag_sum(meta, &[RowTag::Bloom, RowTag::BlockBloom], Rotation::cur())
+                    * (compose_bits(meta) - meta.query_advice(byte, Rotation::cur())),
+            );
+
+            cb.gate(
+                meta.query_fixed(q_block_bloom, Rotation::cur())
+                    + meta.query_fixed(q_receipt, Rotation::cur()),
+            )
+        });
+
+        meta.create_gate("receipt circuit block bloom", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let q_limb_start = meta.query_fixed(q_limb_start, Rotation::cur());
+            let q_limb_end = meta.query_fixed(q_limb_end, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+
+            for (name, column) in [
+                ("no receipt ends in the logs bloom of the block", is_end),
+                (
+                    "no topics end in the logs bloom of the block",
+                    is_topics_end,
+                ),
+                ("tx_id is zero before the first receipt", tx_id),
+            ] {
+                cb.require_zero(name, meta.query_advice(column, Rotation::cur()));
+            }
+            cb.require_equal(
+                "acc = acc_prev * 256 + byte in the limb",
+                acc.expr(),
+                not::expr(q_limb_start) * meta.query_advice(acc, Rotation::prev()) * 256.expr()
+                    + meta.query_advice(byte, Rotation::cur()),
+            );
+
+            // The limbs of the logs bloom of the block in the receipt table
+            for (name, column, value) in [
+                (
+                    "receipt table tag",
+                    receipt_table.tag,
+                    q_limb_end.expr() * ReceiptFieldTag::LogsBloom.expr(),
+                ),
+                (
+                    "receipt table index",
+                    receipt_table.index,
+                    q_limb_end.expr() * meta.query_fixed(limb_index, Rotation::cur()),
+                ),
+                ("receipt table value", receipt_table.value, q_limb_end * acc),
+                ("receipt table len", receipt_table.len, 0.expr()),
+            ] {
+                cb.require_equal(name, meta.query_advice(column, Rotation::cur()), value);
+            }
+
+            cb.gate(meta.query_fixed(q_block_bloom, Rotation::cur()))
+        });
+
+        meta.create_gate("receipt circuit envelope", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let cur = Rotation::cur();
+            let prev = Rotation::prev();
+            let byte = meta.query_advice(byte, cur);
+            let single = meta.query_advice(is_single, cur);
+            let long = meta.query_advice(is_long, cur);
+            let hdr_end = meta.query_advice(is_hdr_end, cur);
+            let hdr_len = meta.query_advice(hdr_len, cur);
+            let end = meta.query_advice(is_end, cur);
+            let topics_end = meta.query_advice(is_topics_end, cur);
+            let str_end = meta.query_advice(is_str_end, cur);
+            let str_len = meta.query_advice(str_len, cur);
+            let lo = meta.query_advice(is_lo, cur);
+            let hash = meta.query_advice(is_hash, cur);
+            let bit = meta.query_advice(is_bit, cur);
+            let len_rem = meta.query_advice(len_rem, cur);
+            let rem_receipt = meta.query_advice(rem_receipt, cur);
+            let rem_topics = meta.query_advice(rem_topics, cur);
+            let rem_str = meta.query_advice(rem_str, cur);
+            let iz_receipt =
+                1.expr() - rem_receipt.expr() * meta.query_advice(rem_receipt_inv, cur);
+            let iz_topics = 1.expr() - rem_topics.expr() * meta.query_advice(rem_topics_inv, cur);
+            let iz_str = 1.expr() - rem_str.expr() * meta.query_advice(rem_str_inv, cur);
+
+            let padding = tag(meta, RowTag::Padding, cur);
+            let list_prefix = tag_sum(meta, &RowTag::LIST_PREFIXES, cur);
+            let str_prefix = tag_sum(meta, &RowTag::STR_PREFIXES, cur);
+            let is_len = tag_sum(meta, &RowTag::LENS, cur);
+            let content = tag_sum(meta, &RowTag::CONTENTS, cur);
+            let item = tag_sum(meta, &RowTag::ITEMS, cur);
+            let value_content = tag_sum(meta, &[RowTag::Gas, RowTag::Address, RowTag::Topic], cur);
+            let data_end = tag_sum(
+                meta,
+                &[RowTag::DataPrefix, RowTag::DataLen, RowTag::Data],
+                cur,
+            ) * str_end.expr();
+
+            let end_prev = meta.query_advice(is_end, prev) + tag(meta, RowTag::BlockBloom, prev);
+            let hdr_end_prev = meta.query_advice(is_hdr_end, prev);
+            let str_end_prev = meta.query_advice(is_str_end, prev);
+            let topics_end_prev = meta.query_advice(is_topics_end, prev);
+            let is_lo_prev = meta.query_advice(is_lo, prev);
+            let has_len_prev = has_len(meta.query_advice(len_rem, prev));
+
+            for (name, flag) in [
+                ("is_single is boolean", single.expr()),
+                ("is_long is boolean", long.expr()),
+                ("is_hdr_end is boolean", hdr_end.expr()),
+                ("is_end is boolean", end.expr()),
+                ("is_topics_end is boolean", topics_end.expr()),
+                ("is_str_end is boolean", str_end.expr()),
+                ("is_lo is boolean", lo.expr()),
+                ("is_hash is boolean", hash.expr()),
+                ("is_bit is boolean", bit.expr()),
+            ] {
+                cb.require_boolean(name, flag);
+            }
+            cb.require_zero(
+                "single bytes are data",
+                single.expr() * not::expr(tag(meta, RowTag::Data, cur)),
+            );
+            cb.require_zero(
+                "only prefixes are long",
+                long.expr() * not::expr(list_prefix.expr() + str_prefix.expr()),
+            );
+            for bit_tx in bit_tx {
+                cb.require_zero(
+                    "bit_tx is only set in the logs bloom of the block",
+                    meta.query_advice(bit_tx, cur),
+                );
+            }
+
+            // RLP headers
+            cb.require_equal(
+                "len_rem = number of length bytes, decremented on every length byte",
+                len_rem.expr(),
+                long.expr()
+                    * (list_prefix.expr() * (byte.expr() - 0xf7.expr())
+                        + str_prefix.expr() * (byte.expr() - 0xb7.expr()))
+                    + is_len.expr() * (meta.query_advice(len_rem, prev) - 1.expr()),
+            );
+            let len_acc = meta.query_advice(len_acc, cur);
+            cb.require_equal(
+                "len_acc = len_acc_prev * 256 + byte on length bytes",
+                len_acc.expr(),
+                is_len.expr() * (meta.query_advice(len_acc, prev) * 256.expr() + byte.expr()),
+            );
+            cb.require_equal(
+                "is_hdr_end = short prefix or last length byte",
+                hdr_end.expr(),
+                (list_prefix.expr() + str_prefix.expr()) * not::expr(long.expr())
+                    + is_len.expr() * not::expr(has_len(len_rem)),
+            );
+            cb.require_zero(
+                "hdr_len = length of the payload at the header end",
+                hdr_end.expr()
+                    * (hdr_len.expr()
+                        - list_prefix.expr() * (byte.expr() - 0xc0.expr())
+                        - str_prefix.expr() * (byte.expr() - 0x80.expr())
+                        - is_len.expr() * len_acc),
+            );
+
+            // Remaining bytes of the receipt, the log, the topics and the string
+            let str_hdr_end = (str_prefix.expr()
+                + tag_sum(meta, &[RowTag::BloomLen, RowTag::DataLen], cur))
+                * hdr_end.expr();
+            let topics_hdr_end =
+                tag_sum(meta, &[RowTag::TopicsPrefix, RowTag::TopicsLen], cur) * hdr_end.expr();
+            let in_payload = tag_sum(meta, &RowTag::PAYLOAD, cur);
+            cb.require_equal(
+                "rem_receipt = receipt length, decremented on every payload byte",
+                rem_receipt.expr(),
+                in_payload.expr() * (meta.query_advice(rem_receipt, prev) - 1.expr())
+                    + tag(meta, RowTag::ReceiptLen, cur) * hdr_end.expr() * hdr_len.expr(),
+            );
+            cb.require_zero(
+                "iz_receipt = rem_receipt == 0",
+                rem_receipt * iz_receipt.expr(),
+            );
+            cb.require_equal(
+                "is_end = last byte of the receipt payload",
+                end.expr(),
+                in_payload * iz_receipt,
+            );
+            let rem_log = meta.query_advice(rem_log, cur);
+            cb.require_equal(
+                "rem_log = log length, decremented on every log byte",
+                rem_log.expr(),
+                tag_sum(meta, &RowTag::LOG, cur) * (meta.query_advice(rem_log, prev) - 1.expr())
+                    + tag_sum(meta, &[RowTag::LogPrefix, RowTag::LogLen], cur)
+                        * hdr_end.expr()
+                        * hdr_len.expr(),
+            );
+            cb.require_equal(
+                "rem_topics = topics length, decremented on every topic byte",
+                rem_topics.expr(),
+                tag_sum(meta, &RowTag::TOPICS, cur)
+                    * (meta.query_advice(rem_topics, prev) - 1.expr())
+                    + topics_hdr_end.expr() * hdr_len.expr(),
+            );
+            cb.require_zero("iz_topics = rem_topics == 0", rem_topics * iz_topics.expr());
+            cb.require_equal(
+                "is_topics_end = last byte of the topics",
+                topics_end.expr(),
+                (topics_hdr_end + tag(meta, RowTag::Topic, cur) * str_end.expr()) * iz_topics,
+            );
+            cb.require_equal(
+                "rem_str = string length, decremented on every content byte",
+                rem_str.expr(),
+                str_hdr_end.expr() * hdr_len.expr()
+                    + (content.expr() - single.expr())
+                        * (meta.query_advice(rem_str, prev) - 1.expr()),
+            );
+            cb.require_zero("iz_str = rem_str == 0", rem_str.expr() * iz_str.expr());
+            cb.require_equal(
+                "is_str_end = last byte of the string",
+                str_end.expr(),
+                (str_hdr_end.expr() + content.expr()) * iz_str,
+            );
+            cb.require_equal(
+                "str_len = string length, carried in the string",
+                str_len.expr(),
+                str_hdr_end * hdr_len.expr()
+                    + (content.expr() - single.expr()) * meta.query_advice(str_len, prev)
+                    + single.expr(),
+            );
+
+            // Fixed parts of the receipts
+            cb.require_zero(
+                "the status is 0x01 or the empty string",
+                tag(meta, RowTag::Status, cur)
+                    * (byte.expr() - 0x01.expr())
+                    * (byte.expr() - 0x80.expr()),
+            );
+            cb.require_zero(
+                "the receipt list is long",
+                tag(meta, RowTag::ReceiptPrefix, cur) * not::expr(long.expr()),
+            );
+            cb.require_zero(
+                "the cumulative gas used is short",
+                tag(meta, RowTag::GasPrefix, cur) * long.expr(),
+            );
+            cb.require_zero(
+                "the cumulative gas used starts from zero",
+                tag(meta, RowTag::GasPrefix, cur) * meta.query_advice(acc, cur),
+            );
+            for (name, row_tag, value) in [
+                ("the logs bloom is a long string", RowTag::BloomPrefix, 0xb9),
+                ("addresses are 20 bytes long", RowTag::AddressPrefix, 0x94),
+                ("topics are 32 bytes long", RowTag::TopicPrefix, 0xa0),
+            ] {
+                cb.require_zero(name, tag(meta, row_tag, cur) * (byte.expr() - value.expr()));
+            }
+            cb.require_zero(
+                "the logs bloom is 256 bytes long",
+                tag(meta, RowTag::BloomLen, cur) * hdr_end.expr() * (hdr_len.expr() - 256.expr()),
+            );
+            cb.require_zero(
+                "the logs take the rest of the receipt",
+                tag_sum(meta, &[RowTag::LogsPrefix, RowTag::LogsLen], cur)
+                    * hdr_end.expr()
+                    * (meta.query_advice(rem_receipt, cur) - hdr_len.expr()),
+            );
+            cb.require_zero("a log ends with its data", data_end.expr() * rem_log.expr());
+            cb.require_zero(
+                "a receipt ends with its logs",
+                end.expr()
+                    * not::expr(
+                        tag_sum(meta, &[RowTag::LogsPrefix, RowTag::LogsLen], cur) * hdr_end.expr()
+                            + data_end.expr(),
+                    ),
+            );
+
+            // Transitions between the tags
+            cb.require_zero(
+                "a receipt starts after the end of the previous one, or padding",
+                end_prev.expr()
+                    * not::expr(tag_sum(
+                        meta,
+                        &[RowTag::TxType, RowTag::ReceiptPrefix, RowTag::Padding],
+                        cur,
+                    )),
+            );
+            cb.require_zero(
+                "padding is followed by padding",
+                tag(meta, RowTag::Padding, prev) * not::expr(padding.expr()),
+            );
+            cb.require_zero(
+                "the type is followed by the receipt list",
+                tag(meta, RowTag::TxType, prev) * not::expr(tag(meta, RowTag::ReceiptPrefix, cur)),
+            );
+            for (prefix, len) in RowTag::HEADERS {
+                cb.require_zero(
+                    "length bytes follow the long prefixes",
+                    has_len_prev.expr()
+                        * (tag(meta, prefix, prev) + tag(meta, len, prev))
+                        * not::expr(tag(meta, len, cur)),
+                );
+            }
+            cb.require_zero(
+                "the status follows the receipt header",
+                tag(meta, RowTag::ReceiptLen, prev)
+                    * hdr_end_prev.expr()
+                    * not::expr(tag(meta, RowTag::Status, cur)),
+            );
+            cb.require_zero(
+                "the cumulative gas used follows the status",
+                tag(meta, RowTag::Status, prev) * not::expr(tag(meta, RowTag::GasPrefix, cur)),
+            );
+            for (prefix, len, content) in RowTag::STRINGS {
+                let in_string = ((tag(meta, prefix, prev)
+                    + len.map_or(0.expr(), |len| tag(meta, len, prev)))
+                    * hdr_end_prev.expr()
+                    + tag(meta, content, prev))
+                    * not::expr(str_end_prev.expr());
+                cb.require_zero(
+                    "content follows the header until the end of the string",
+                    in_string.expr() * not::expr(tag(meta, content, cur)),
+                );
+                cb.require_zero(
+                    "strings of more than a byte are not a single byte",
+                    in_string * single.expr(),
+                );
+            }
+            cb.require_zero(
+                "the logs bloom follows the cumulative gas used",
+                tag_sum(meta, &[RowTag::GasPrefix, RowTag::Gas], prev)
+                    * str_end_prev.expr()
+                    * not::expr(tag(meta, RowTag::BloomPrefix, cur)),
+            );
+            cb.require_zero(
+                "the logs follow the logs bloom",
+                tag(meta, RowTag::Bloom, prev)
+                    * str_end_prev.expr()
+                    * not::expr(tag(meta, RowTag::LogsPrefix, cur)),
+            );
+            let log_start = tag(meta, RowTag::LogPrefix, cur);
+            cb.require_zero(
+                "a log follows the logs header unless the receipt ends",
+                tag_sum(meta, &[RowTag::LogsPrefix, RowTag::LogsLen], prev)
+                    * hdr_end_prev.expr()
+                    * not::expr(meta.query_advice(is_end, prev))
+                    * not::expr(log_start.expr()),
+            );
+            cb.require_zero(
+                "a log follows a log unless the receipt ends",
+                tag_sum(
+                    meta,
+                    &[RowTag::DataPrefix, RowTag::DataLen, RowTag::Data],
+                    prev,
+                ) * str_end_prev.expr()
+                    * not::expr(meta.query_advice(is_end, prev))
+                    * not::expr(log_start.expr()),
+            );
+            cb.require_zero(
+                "the address follows the log header",
+                tag_sum(meta, &[RowTag::LogPrefix, RowTag::LogLen], prev)
+                    * hdr_end_prev.expr()
+                    * not::expr(tag(meta, RowTag::AddressPrefix, cur)),
+            );
+            cb.require_zero(
+                "the topics follow the address",
+                tag(meta, RowTag::Address, prev)
+                    * str_end_prev.expr()
+                    * not::expr(tag(meta, RowTag::TopicsPrefix, cur)),
+            );
+            cb.require_zero(
+                "a topic follows the topics header or a topic until the topics end",
+                (tag_sum(meta, &[RowTag::TopicsPrefix, RowTag::TopicsLen], prev)
+                    * hdr_end_prev.expr()
+                    + tag(meta, RowTag::Topic, prev) * str_end_prev.expr())
+                    * not::expr(topics_end_prev.expr())
+                    * not::expr(tag(meta, RowTag::TopicPrefix, cur)),
+            );
+            cb.require_zero(
+                "the data follows the topics",
+                topics_end_prev * not::expr(tag(meta, RowTag::DataPrefix, cur) + single.expr()),
+            );
+
+            // Transaction and log identifiers
+            let tx_id = meta.query_advice(tx_id, cur);
+            cb.require_equal(
+                "tx_id = tx_id_prev + 1 on the first byte of a receipt",
+                tx_id.expr(),
+                meta.query_advice(tx_id, prev) + end_prev.expr() * not::expr(padding.expr()),
+            );
+            let log_id = meta.query_advice(log_id, cur);
+            cb.require_equal(
+                "log_id = number of logs of the receipt",
+                log_id.expr(),
+                not::expr(end_prev.expr()) * meta.query_advice(log_id, prev) + log_start.expr(),
+            );
+            cb.require_equal(
+                "topic_count = number of topics of the log",
+                meta.query_advice(topic_count, cur),
+                not::expr(log_start) * meta.query_advice(topic_count, prev)
+                    + tag(meta, RowTag::TopicPrefix, cur),
+            );
+
+            // RLC and length of the envelope, exposed in the receipt table
+            let len = meta.query_advice(len, cur);
+            let rlc = meta.query_advice(rlc, cur);
+            cb.require_equal(
+                "len = len_prev + 1 in the envelope",
+                len.expr(),
+                not::expr(padding.expr())
+                    * (not::expr(end_prev.expr()) * meta.query_advice(len, prev) + 1.expr()),
+            );
+            cb.require_equal(
+                "rlc = rlc_prev * r + byte in the envelope",
+                rlc.expr(),
+                not::expr(padding.expr())
+                    * (not::expr(end_prev.expr())
+                        * meta.query_advice(rlc, prev)
+                        * challenges.keccak_input()
+                        + byte.expr()),
+            );
+            for (name, column, value) in [
+                (
+                    "receipt table tag",
+                    receipt_table.tag,
+                    end.expr() * ReceiptFieldTag::Envelope.expr(),
+                ),
+                (
+                    "receipt table index",
+                    receipt_table.index,
+                    end.expr() * tx_id,
+                ),
+                ("receipt table value", receipt_table.value, end.expr() * rlc),
+                ("receipt table len", receipt_table.len, end.expr() * len),
+            ] {
+                cb.require_equal(name, meta.query_advice(column, cur), value);
+            }
+
+            // Values of the gas, the addresses and the topics in 16-byte limbs
+            let acc = meta.query_advice(acc, cur);
+            let acc_prev = meta.query_advice(acc, prev);
+            let reset = tag_sum(
+                meta,
+                &[
+                    RowTag::GasPrefix,
+                    RowTag::AddressPrefix,
+                    RowTag::TopicPrefix,
+                ],
+                prev,
+            ) + lo.expr() * not::expr(is_lo_prev.expr());
+            cb.require_zero(
+                "only addresses and topics have a lo limb",
+                lo.expr() * not::expr(item.expr()),
+            );
+            cb.require_zero(
+                "acc = acc_prev * 256 + byte in the limb",
+                value_content
+                    * (acc.expr() - not::expr(reset) * acc_prev.expr() * 256.expr() - byte.expr()),
+            );
+            cb.require_zero(
+                "acc_hi = hi limb of the value on the rows of the lo limb",
+                item.expr()
+                    * (meta.query_advice(acc_hi, cur)
+                        - lo.expr()
+                            * (is_lo_prev.expr() * meta.query_advice(acc_hi, prev)
+                                + not::expr(is_lo_prev) * acc_prev)),
+            );
+
+            // Hash of the addresses and topics, and the bits they accrue
+            let first = tag_sum(meta, &[RowTag::AddressPrefix, RowTag::TopicPrefix], prev);
+            let hash_acc_prev = meta.query_advice(hash_acc, prev);
+            cb.require_zero(
+                "only addresses and topics are hashed",
+                (hash.expr() + bit.expr()) * not::expr(item.expr()),
+            );
+            cb.require_zero(
+                "hash_acc = hash_acc_prev * 256 + hash_byte in the hi limb",
+                item.expr()
+                    * (meta.query_advice(hash_acc, cur)
+                        - hash.expr()
+                            * (not::expr(first.expr()) * hash_acc_prev.expr() * 256.expr()
+                                + meta.query_advice(hash_byte, cur))
+                        - not::expr(hash) * hash_acc_prev),
+            );
+            cb.require_zero(
+                "item_rlc = item_rlc_prev * r + byte in the item",
+                item.expr()
+                    * (meta.query_advice(item_rlc, cur)
+                        - not::expr(first)
+                            * meta.query_advice(item_rlc, prev)
+                            * challenges.keccak_input()
+                        - byte.expr()),
+            );
+            cb.require_zero(
+                "bit_index = 255 - (lo 3 bits of the previous hash byte || hi 5 bits)",
+                bit.expr()
+                    * (meta.query_advice(bit_index, cur) - 255.expr()
+                        + meta.query_advice(hash_lo3, prev) * 32.expr()
+                        + meta.query_advice(hash_hi, cur)),
+            );
+
+            cb.gate(meta.query_fixed(q_receipt, Rotation::cur()))
+        });
+
+        meta.create_gate("receipt circuit last row", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_equal(
+                "the last row is padding",
+                tag(meta, RowTag::Padding, Rotation::cur()),
+                1.expr(),
+            );
+
+            cb.gate(meta.query_fixed(q_last, Rotation::cur()))
+        });
+
+        meta.lookup_any("receipt circuit byte range", |meta| {
+            let q_receipt = meta.query_fixed(q_receipt, Rotation::cur());
+            vec![(
+                q_receipt * meta.query_advice(byte, Rotation::cur()),
+                u8_table.table_exprs(meta)[0].expr(),
+            )]
+        });
+
+        // Range checks of the prefixes against their short or long range, of the
+        // types and single data bytes, and of the limbs of addresses and topics,
+        // split into a lower and an upper bound.
+        meta.lookup_any("receipt circuit lower bounds", |meta| {
+            let byte = meta.query_advice(byte, Rotation::cur());
+            let long = meta.query_advice(is_long, Rotation::cur());
+            let lo = meta.query_advice(is_lo, Rotation::cur());
+            let rem_str = meta.query_advice(rem_str, Rotation::cur());
+
+            let value = tag_sum(meta, &RowTag::LIST_PREFIXES, Rotation::cur())
+                * (byte.expr() - 0xc0.expr() - 0x38.expr() * long.expr())
+                + tag_sum(meta, &RowTag::STR_PREFIXES, Rotation::cur())
+                    * (byte.expr() - 0x80.expr() - 0x38.expr() * long)
+                + tag(meta, RowTag::TxType, Rotation::cur()) * (byte - 1.expr())
+                + tag_sum(meta, &RowTag::ITEMS, Rotation::cur())
+                    * (lo.expr() * (15.expr() - rem_str.expr())
+                        + not::expr(lo) * (rem_str - 16.expr()));
+
+            vec![(value, u16_table.table_exprs(meta)[0].expr())]
+        });
+        meta.lookup_any("receipt circuit upper bounds", |meta| {
+            let byte = meta.query_advice(byte, Rotation::cur());
+            let long = meta.query_advice(is_long, Rotation::cur());
+            let gas_prefix = tag(meta, RowTag::GasPrefix, Rotation::cur());
+
+            // The cumulative gas used takes at most 8 bytes
+            let value = tag_sum(meta, &RowTag::LIST_PREFIXES, Rotation::cur())
+                * (0xf7.expr() + 2.expr() * long.expr() - byte.expr())
+                + (tag_sum(meta, &RowTag::STR_PREFIXES, Rotation::cur()) - gas_prefix.expr())
+                    * (0xb7.expr() + 2.expr() * long - byte.expr())
+                + gas_prefix * (0x88.expr() - byte.expr())
+                + tag(meta, RowTag::TxType, Rotation::cur()) * (0x7f.expr() - byte.expr())
+                + meta.query_advice(is_single, Rotation::cur()) * (0x7f.expr() - byte);
+
+            vec![(value, u16_table.table_exprs(meta)[0].expr())]
+        });
+
+        // Bytes of the keccak hash of the addresses and topics
+        meta.lookup_any("receipt circuit hash position", |meta| {
+            let item = tag_sum(meta, &RowTag::ITEMS, Rotation::cur());
+            let k = meta.query_advice(str_len, Rotation::cur())
+                - meta.query_advice(rem_str, Rotation::cur());
+
+            vec![
+                (item.expr() * k, meta.query_fixed(pos_k, Rotation::cur())),
+                (
+                    item.expr() * meta.query_advice(is_hash, Rotation::cur()),
+                    meta.query_fixed(pos_is_hash, Rotation::cur()),
+                ),
+                (
+                    item * meta.query_advice(is_bit, Rotation::cur()),
+                    meta.query_fixed(pos_is_bit, Rotation::cur()),
+                ),
+            ]
+        });
+        meta.lookup_any("receipt circuit hash byte split", |meta| {
+            let hash = meta.query_advice(is_hash, Rotation::cur());
+
+            vec![
+                (
+                    hash.expr() * meta.query_advice(hash_byte, Rotation::cur()),
+                    meta.query_fixed(split_byte, Rotation::cur()),
+                ),
+                (
+                    hash.expr() * meta.query_advice(hash_hi, Rotation::cur()),
+                    meta.query_fixed(split_hi, Rotation::cur()),
+                ),
+                (
+                    hash * meta.query_advice(hash_lo3, Rotation::cur()),
+                    meta.query_fixed(split_lo, Rotation::cur()),
+                ),
+            ]
+        });
+        meta.lookup_any("receipt circuit item hash", |meta| {
+            let cond = tag_sum(meta, &RowTag::ITEMS, Rotation::cur())
+                * meta.query_advice(is_str_end, Rotation::cur());
+
+            vec![
+                (
+                    cond.expr(),
+                    meta.query_advice(keccak_table.is_enabled, Rotation::cur()),
+                ),
+                (
+                    cond.expr() * meta.query_advice(item_rlc, Rotation::cur()),
+                    meta.query_advice(keccak_table.input_rlc, Rotation::cur()),
+                ),
+                (
+                    cond.expr() * meta.query_advice(str_len, Rotation::cur()),
+                    meta.query_advice(keccak_table.input_len, Rotation::cur()),
+                ),
+                (
+                    cond.expr() * meta.query_advice(hash_lo, Rotation::cur()),
+                    meta.query_advice(keccak_table.output.lo(), Rotation::cur()),
+                ),
+                (
+                    cond * meta.query_advice(hash_acc, Rotation::cur()),
+                    meta.query_advice(keccak_table.output.hi(), Rotation::cur()),
+                ),
+            ]
+        });
+
+        // The bits accrued by the addresses and topics are set in the bloom of
+        // their receipt and of the block
+        meta.lookup_any("receipt circuit accrued bit in the receipt bloom", |meta| {
+            let bit = meta.query_advice(is_bit, Rotation::cur());
+            let bloom = tag(meta, RowTag::Bloom, Rotation::cur());
+
+            vec![
+                (bit.expr(), bloom.expr()),
+                (
+                    bit.expr() * meta.query_advice(tx_id, Rotation::cur()),
+                    bloom.expr() * meta.query_advice(tx_id, Rotation::cur()),
+                ),
+                (
+                    bit.expr() * meta.query_advice(bit_index, Rotation::cur()),
+                    bloom.expr() * (255.expr() - meta.query_advice(rem_str, Rotation::cur())),
+                ),
+                (
+                    bit * meta.query_advice(bloom_byte, Rotation::cur()),
+                    bloom * meta.query_advice(byte, Rotation::cur()),
+                ),
+            ]
+        });
+        meta.lookup_any("receipt circuit accrued bit in the block bloom", |meta| {
+            let bit = meta.query_advice(is_bit, Rotation::cur());
+            let block_bloom = tag(meta, RowTag::BlockBloom, Rotation::cur());
+
+            vec![
+                (bit.expr(), block_bloom.expr()),
+                (
+                    bit.expr() * meta.query_advice(bit_index, Rotation::cur()),
+                    block_bloom.expr() * meta.query_fixed(block_index, Rotation::cur()),
+                ),
+                (
+                    bit * meta.query_advice(block_byte, Rotation::cur()),
+                    block_bloom * meta.query_advice(byte, Rotation::cur()),
+                ),
+            ]
+        });
+        for (name, column) in [
+            (
+                "receipt circuit accrued bit is set in the receipt bloom byte",
+                bloom_byte,
+            ),
+            (
+                "receipt circuit accrued bit is set in the block bloom byte",
+                block_byte,
+            ),
+        ] {
+            meta.lookup_any(name, |meta| {
+                let bit = meta.query_advice(is_bit, Rotation::cur());
+
+                vec![
+                    (
+                        bit.expr() * meta.query_advice(column, Rotation::cur()),
+                        meta.query_fixed(bit_byte, Rotation::cur()),
+                    ),
+                    (
+                        bit.expr() * meta.query_advice(hash_lo3, Rotation::cur()),
+                        meta.query_fixed(bit_pos, Rotation::cur()),
+                    ),
+                    (bit, meta.query_fixed(bit_value, Rotation::cur())),
+                ]
+            });
+        }
+
+        // Every bit set in the bloom of a receipt is accrued by one of its
+        // addresses or topics, and every bit set in the bloom of the block by an
+        // address or topic of any receipt
+        for (j, bit) in bits.into_iter().enumerate() {
+            meta.lookup_any("receipt circuit bloom bit is accrued", |meta| {
+                let bloom = tag(meta, RowTag::Bloom, Rotation::cur());
+                let cond = meta.query_advice(bit, Rotation::cur())
+                    * (bloom.expr() + tag(meta, RowTag::BlockBloom, Rotation::cur()));
+                let is_bit = meta.query_advice(is_bit, Rotation::cur());
+
+                vec![
+                    (cond.expr(), is_bit.expr()),
+                    (
+                        cond.expr()
+                            * (meta.query_advice(tx_id, Rotation::cur())
+                                + meta.query_advice(bit_tx[j], Rotation::cur())),
+                        is_bit.expr() * meta.query_advice(tx_id, Rotation::cur()),
+                    ),
+                    (
+                        cond.expr()
+                            * (bloom * (255.expr() - meta.query_advice(rem_str, Rotation::cur()))
+                                + meta.query_fixed(block_index, Rotation::cur())),
+                        is_bit.expr() * meta.query_advice(bit_index, Rotation::cur()),
+                    ),
+                    (
+                        cond * j.expr(),
+                        is_bit * meta.query_advice(hash_lo3, Rotation::cur()),
+                    ),
+                ]
+            });
+        }
+
+        // The fields of the receipts and their logs in the RW table
+        let rw_lookup = |meta: &mut VirtualCells<'_, F>,
+                         cond: Expression<F>,
+                         values: [Expression<F>; 6]|
+         -> Vec<(Expression<F>, Expression<F>)> {
+            let table = [
+                rw_table.tag,
+                rw_table.id,
+                rw_table.address,
+                rw_table.field_tag,
+                rw_table.value.lo(),
+                rw_table.value.hi(),
+            ];
+            values
+                .into_iter()
+                .zip(table)
+                .map(|(value, column)| {
+                    (
+                        cond.expr() * value,
+                        meta.query_advice(column, Rotation::cur()),
+                    )
+                })
+                .collect()
+        };
+        let receipt_field =
+            |meta: &mut VirtualCells<'_, F>, field_tag: TxReceiptFieldTag, value: Expression<F>| {
+                [
+                    Target::TxReceipt.expr(),
+                    meta.query_advice(tx_id, Rotation::cur()),
+                    0.expr(),
+                    field_tag.expr(),
+                    value,
+                    0.expr(),
+                ]
+            };
+        let log_field = |meta: &mut VirtualCells<'_, F>,
+                         index: Expression<F>,
+                         field_tag: TxLogFieldTag,
+                         [lo, hi]: [Expression<F>; 2]| {
+            [
+                Target::TxLog.expr(),
+                meta.query_advice(tx_id, Rotation::cur()),
+                build_tx_log_expression(
+                    index,
+                    field_tag.expr(),
+                    meta.query_advice(log_id, Rotation::cur()),
+                ),
+                0.expr(),
+                lo,
+                hi,
+            ]
+        };
+        meta.lookup_any("receipt circuit status", |meta| {
+            let cond = tag(meta, RowTag::Status, Rotation::cur());
+            // The status is encoded as 0x01 or 0x80
+            let status = (0x80.expr() - meta.query_advice(byte, Rotation::cur()))
+                * Expression::Constant(F::from(0x7f).invert().unwrap());
+            let values = receipt_field(meta, TxReceiptFieldTag::PostStateOrStatus, status);
+            rw_lookup(meta, cond, values)
+        });
+        meta.lookup_any("receipt circuit cumulative gas used", |meta| {
+            let cond = tag_sum(meta, &[RowTag::GasPrefix, RowTag::Gas], Rotation::cur())
+                * meta.query_advice(is_str_end, Rotation::cur());
+            let gas = meta.query_advice(acc, Rotation::cur());
+            let values = receipt_field(meta, TxReceiptFieldTag::CumulativeGasUsed, gas);
+            rw_lookup(meta, cond, values)
+        });
+        meta.lookup_any("receipt circuit log length", |meta| {
+            let cond = meta.query_advice(is_end, Rotation::cur());
+            let log_length = meta.query_advice(log_id, Rotation::cur());
+            let values = receipt_field(meta, TxReceiptFieldTag::LogLength, log_length);
+            rw_lookup(meta, cond, values)
+        });
+        meta.lookup_any("receipt circuit log address", |meta| {
+            let cond = tag(meta, RowTag::Address, Rotation::cur())
+                * meta.query_advice(is_str_end, Rotation::cur());
+            let address = [
+                meta.query_advice(acc, Rotation::cur()),
+                meta.query_advice(acc_hi, Rotation::cur()),
+            ];
+            let values = log_field(meta, 0.expr(), TxLogFieldTag::Address, address);
+            rw_lookup(meta, cond, values)
+        });
+        meta.lookup_any("receipt circuit log topic", |meta| {
+            let cond = tag(meta, RowTag::Topic, Rotation::cur())
+                * meta.query_advice(is_str_end, Rotation::cur());
+            let index = meta.query_advice(topic_count, Rotation::cur()) - 1.expr();
+            let topic = [
+                meta.query_advice(acc, Rotation::cur()),
+                meta.query_advice(acc_hi, Rotation::cur()),
+            ];
+            let values = log_field(meta, index, TxLogFieldTag::Topic, topic);
+            rw_lookup(meta, cond, values)
+        });
+        meta.lookup_any("receipt circuit log topic length", |meta| {
+            let cond = meta.query_advice(is_topics_end, Rotation::cur());
+            let topic_length = [meta.query_advice(topic_count, Rotation::cur()), 0.expr()];
+            let values = log_field(meta, 0.expr(), TxLogFieldTag::TopicLength, topic_length);
+            rw_lookup(meta, cond, values)
+        });
+        meta.lookup_any("receipt circuit log data", |meta| {
+            let cond = tag(meta, RowTag::Data, Rotation::cur());
+            let index = meta.query_advice(str_len, Rotation::cur())
+                - meta.query_advice(rem_str, Rotation::cur())
+                - 1.expr();
+            let data = [meta.query_advice(byte, Rotation::cur()), 0.expr()];
+            let values = log_field(meta, index, TxLogFieldTag::Data, data);
+            rw_lookup(meta, cond, values)
+        });
+        meta.lookup_any("receipt circuit log data length", |meta| {
+            let cond = tag_sum(
+                meta,
+                &[RowTag::DataPrefix, RowTag::DataLen, RowTag::Data],
+                Rotation::cur(),
+            ) * meta.query_advice(is_str_end, Rotation::cur());
+            let data_length = [meta.query_advice(str_len, Rotation::cur()), 0.expr()];
+            let values = log_field(meta, 0.expr(), TxLogFieldTag::DataLength, data_length);
+            rw_lookup(meta, cond, values)
+        });
+
+        Self {
+            q_block_bloom,
+            q_receipt,
+            q_last,
+            q_limb_start,
+            q_limb_end,
+            limb_index,
+            block_index,
+            split_byte,
+            split_hi,
+            split_lo,
+            bit_byte,
+            bit_pos,
+            bit_value,
+            pos_k,
+            pos_is_hash,
+            pos_is_bit,
+            byte,
+            tags,
+            is_single,
+            is_long,
+            len_rem,
+            len_acc,
+            is_hdr_end,
+            hdr_len,
+            rem_receipt,
+            rem_receipt_inv,
+            is_end,
+            rem_log,
+            rem_topics,
+            rem_topics_inv,
+            is_topics_end,
+            rem_str,
+            rem_str_inv,
+            is_str_end,
+            str_len,
+            tx_id,
+            log_id,
+            topic_count,
+            acc,
+            acc_hi,
+            is_lo,
+            is_hash,
+            is_bit,
+            hash_byte,
+            hash_hi,
+            hash_lo3,
+            hash_acc,
+            hash_lo,
+            item_rlc,
+            bit_index,
+            bloom_byte,
+            block_byte,
+            bits,
+            bit_tx,
+            len,
+            rlc,
+            receipt_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> ReceiptCircuitConfig<F> {
+    /// Assign the fixed tables of the circuit
+    fn load_fixed_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_region(
+            || "receipt circuit fixed tables",
+            |mut region| {
+                for offset in 0..N_FIXED_ROWS {
+                    let split = offset % 256;
+                    let (byte, pos) = (offset / 8, offset % 8);
+                    let k = (offset < 32).then_some(offset);
+                    for (name, column, value) in [
+                        ("split_byte", self.split_byte, split),
+                        ("split_hi", self.split_hi, split >> 3),
+                        ("split_lo", self.split_lo, split & 7),
+                        ("bit_byte", self.bit_byte, byte),
+                        ("bit_pos", self.bit_pos, pos),
+                        ("bit_value", self.bit_value, (byte >> pos) & 1),
+                        ("pos_k", self.pos_k, k.map_or(0, |k| k + 1)),
+                        (
+                            "pos_is_hash",
+                            self.pos_is_hash,
+                            k.map_or(0, |k| (k < N_BYTES_HASH_HI) as usize),
+                        ),
+                        (
+                            "pos_is_bit",
+                            self.pos_is_bit,
+                            k.map_or(0, |k| [1, 3, 5].contains(&k) as usize),
+                        ),
+                    ] {
+                        region.assign_fixed(
+                            || name,
+                            column,
+                            offset,
+                            || Value::known(F::from(value as u64)),
+                        )?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Assign the logs bloom of the block and the receipts
+    fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        receipts: &[Receipt],
+        capacity: usize,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        self.load_fixed_tables(layouter)?;
+        let rows = circuit_rows(receipts, capacity);
+
+        layouter.assign_region(
+            || "receipt circuit",
+            |mut region| {
+                let mut rlc = Value::known(F::ZERO);
+                let mut item_rlc = Value::known(F::ZERO);
+                let mut prev_end = true;
+
+                for (offset, row) in rows.iter().enumerate() {
+                    let is_block_bloom = offset < N_BLOOM_ROWS;
+                    for (name, column, value) in [
+                        ("q_block_bloom", self.q_block_bloom, is_block_bloom as u64),
+                        ("q_receipt", self.q_receipt, !is_block_bloom as u64),
+                        ("q_last", self.q_last, (offset == capacity - 1) as u64),
+                        (
+                            "q_limb_start",
+                            self.q_limb_start,
+                            (is_block_bloom && offset % N_BYTES_BLOOM_LIMB == 0) as u64,
+                        ),
+                        (
+                            "q_limb_end",
+                            self.q_limb_end,
+                            (is_block_bloom
+                                && offset % N_BYTES_BLOOM_LIMB == N_BYTES_BLOOM_LIMB - 1)
+                                as u64,
+                        ),
+                        (
+                            "limb_index",
+                            self.limb_index,
+                            if is_block_bloom {
+                                (offset / N_BYTES_BLOOM_LIMB) as u64
+                            } else {
+                                0
+                            },
+                        ),
+                        (
+                            "block_index",
+                            self.block_index,
+                            if is_block_bloom { offset as u64 } else { 0 },
+                        ),
+                    ] {
+                        region.assign_fixed(
+                            || name,
+                            column,
+                            offset,
+                            || Value::known(F::from(value)),
+                        )?;
+                    }
+
+                    let byte = F::from(row.byte as u64);
+                    if !is_block_bloom {
+                        rlc = match (row.tag, prev_end) {
+                            (RowTag::Padding, _) => Value::known(F::ZERO),
+                            (_, true) => Value::known(byte),
+                            (_, false) => rlc
+                                .zip(challenges.keccak_input())
+                                .map(|(rlc, r)| rlc * r + byte),
+                        };
+                        prev_end = row.is_end;
+                    }
+                    if row.tag.is_in(&RowTag::ITEMS) {
+                        let first = rows[offset - 1]
+                            .tag
+                            .is_in(&[RowTag::AddressPrefix, RowTag::TopicPrefix]);
+                        item_rlc = if first {
+                            Value::known(byte)
+                        } else {
+                            item_rlc
+                                .zip(challenges.keccak_input())
+                                .map(|(rlc, r)| rlc * r + byte)
+                        };
+                    } else {
+                        item_rlc = Value::known(F::ZERO);
+                    }
+                    let is_bloom = row.tag == RowTag::Bloom || row.tag == RowTag::BlockBloom;
+                    let is_limb_end =
+                        is_block_bloom && offset % N_BYTES_BLOOM_LIMB == N_BYTES_BLOOM_LIMB - 1;
+
+                    for (column, tag) in self.tags.iter().zip(RowTag::iter()) {
+                        region.assign_advice(
+                            || "tag",
+                            *column,
+                            offset,
+                            || Value::known(F::from((row.tag == tag) as u64)),
+                        )?;
+                    }
+                    for (j, (bit, bit_tx)) in self.bits.iter().zip(self.bit_tx.iter()).enumerate() {
+                        region.assign_advice(
+                            || "bits",
+                            *bit,
+                            offset,
+                            || {
+                                Value::known(F::from(
+                                    is_bloom as u64 * ((row.byte >> j) & 1) as u64,
+                                ))
+                            },
+                        )?;
+                        region.assign_advice(
+                            || "bit_tx",
+                            *bit_tx,
+                            offset,
+                            || Value::known(F::from(row.bit_tx[j])),
+                        )?;
+                    }
+
+                    let inv = |value: u64| F::from(value).invert().unwrap_or(F::ZERO);
+                    let (table_tag, table_index, table_len) = match (row.is_end, is_limb_end) {
+                        (true, _) => (ReceiptFieldTag::Envelope as u64, row.tx_id, row.len),
+                        (_, true) => (
+                            ReceiptFieldTag::LogsBloom as u64,
+                            (offset / N_BYTES_BLOOM_LIMB) as u64,
+                            0,
+                        ),
+                        _ => (0, 0, 0),
+                    };
+                    let table_value = if row.is_end {
+                        rlc
+                    } else if is_limb_end {
+                        Value::known(F::from_u128(row.acc))
+                    } else {
+                        Value::known(F::ZERO)
+                    };
+                    for (name, column, value) in [
+                        ("byte", self.byte, byte),
+                        ("is_single", self.is_single, F::from(row.is_single as u64)),
+                        ("is_long", self.is_long, F::from(row.is_long as u64)),
+                        ("len_rem", self.len_rem, F::from(row.len_rem)),
+                        ("len_acc", self.len_acc, F::from(row.len_acc)),
+                        (
+                            "is_hdr_end",
+                            self.is_hdr_end,
+                            F::from(row.is_hdr_end as u64),
+                        ),
+                        ("hdr_len", self.hdr_len, F::from(row.hdr_len)),
+                        ("rem_receipt", self.rem_receipt, F::from(row.rem_receipt)),
+                        (
+                            "rem_receipt_inv",
+                            self.rem_receipt_inv,
+                            inv(row.rem_receipt),
+                        ),
+                        ("is_end", self.is_end, F::from(row.is_end as u64)),
+                        ("rem_log", self.rem_log, F::from(row.rem_log)),
+                        ("rem_topics", self.rem_topics, F::from(row.rem_topics)),
+                        ("rem_topics_inv", self.rem_topics_inv, inv(row.rem_topics)),
+                        (
+                            "is_topics_end",
+                            self.is_topics_end,
+                            F::from(row.is_topics_end as u64),
+                        ),
+                        ("rem_str", self.rem_str, F::from(row.rem_str)),
+                        ("rem_str_inv", self.rem_str_inv, inv(row.rem_str)),
+                        (
+                            "is_str_end",
+                            self.is_str_end,
+                            F::from(row.is_str_end as u64),
+                        ),
+                        ("str_len", self.str_len, F::from(row.str_len)),
+                        ("tx_id", self.tx_id, F::from(row.tx_id)),
+                        ("log_id", self.log_id, F::from(row.log_id)),
+                        ("topic_count", self.topic_count, F::from(row.topic_count)),
+                        ("acc", self.acc, F::from_u128(row.acc)),
+                        ("acc_hi", self.acc_hi, F::from_u128(row.acc_hi)),
+                        ("is_lo", self.is_lo, F::from(row.is_lo as u64)),
+                        ("is_hash", self.is_hash, F::from(row.is_hash as u64)),
+                        ("is_bit", self.is_bit, F::from(row.is_bit as u64)),
+                        ("hash_byte", self.hash_byte, F::from(row.hash_byte as u64)),
+                        (
+                            "hash_hi",
+                            self.hash_hi,
+                            F::from((row.hash_byte >> 3) as u64),
+                        ),
+                        (
+                            "hash_lo3",
+                            self.hash_lo3,
+                            F::from((row.hash_byte & 7) as u64),
+                        ),
+                        ("hash_acc", self.hash_acc, F::from_u128(row.hash_acc)),
+                        ("hash_lo", self.hash_lo, F::from_u128(row.hash_lo)),
+                        ("bit_index", self.bit_index, F::from(row.bit_index)),
+                        (
+                            "bloom_byte",
+                            self.bloom_byte,
+                            F::from(row.bloom_byte as u64),
+                        ),
+                        (
+                            "block_byte",
+                            self.block_byte,
+                            F::from(row.block_byte as u64),
+                        ),
+                        ("len", self.len, F::from(row.len)),
+                        (
+                            "receipt table tag",
+                            self.receipt_table.tag,
+                            F::from(table_tag),
+                        ),
+                        (
+                            "receipt table index",
+                            self.receipt_table.index,
+                            F::from(table_index),
+                        ),
+                        (
+                            "receipt table len",
+                            self.receipt_table.len,
+                            F::from(table_len),
+                        ),
+                    ] {
+                        region.assign_advice(|| name, column, offset, || Value::known(value))?;
+                    }
+                    for (name, column, value) in [
+                        ("rlc", self.rlc, rlc),
+                        ("item_rlc", self.item_rlc, item_rlc),
+                        ("receipt table value", self.receipt_table.value, table_value),
+                    ] {
+                        region.assign_advice(|| name, column, offset, || value)?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+/// Number of rows of the receipt circuit for the given number of
+/// transactions and bytes of the RLP encoded logs
+fn receipt_circuit_len(max_txs: usize, max_log_bytes: usize) -> usize {
+    N_BLOOM_ROWS + max_txs * N_BYTES_RECEIPT + max_log_bytes + 1
+}
+
+/// Data of the RW table, assigned by the dev circuit
+#[derive(Clone, Default, Debug)]
+pub struct ExternalData {
+    /// StateCircuit -> max_rws
+    pub max_rws: usize,
+    /// StateCircuit -> rws
+    pub rws: RwMap,
+}
+
+/// ReceiptCircuit
+#[derive(Clone, Default, Debug)]
+pub struct ReceiptCircuit<F: Field> {
+    /// Receipts of the block transactions
+    pub receipts: Vec<Receipt>,
+    /// Maximum number of transactions
+    pub max_txs: usize,
+    /// Maximum number of bytes of the RLP encoded logs
+    pub max_log_bytes: usize,
+    /// Data for external lookup tables
+    pub external_data: ExternalData,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> ReceiptCircuit<F> {
+    /// Creates a new circuit instance
+    pub fn new(
+        receipts: Vec<Receipt>,
+        max_txs: usize,
+        max_log_bytes: usize,
+        external_data: ExternalData,
+    ) -> Self {
+        Self {
+            receipts,
+            max_txs,
+            max_log_bytes,
+            external_data,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> SubCircuit<F> for ReceiptCircuit<F> {
+    type Config = ReceiptCircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // No column is queried at more than 3 distinct rotations, so returns 6
+        // unusable rows.
+        6
+    }
+
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        Self::new(
+            block.receipts.clone(),
+            block.circuits_params.max_txs,
+            block.circuits_params.max_log_bytes,
+            ExternalData {
+                max_rws: block.circuits_params.max_rws,
+                rws: block.rws.clone(),
+            },
+        )
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        let log_bytes = block
+            .receipts
+            .iter()
+            .flat_map(|receipt| receipt.logs.iter())
+            .map(|log| log.rlp().len())
+            .sum();
+        (
+            receipt_circuit_len(block.receipts.len(), log_bytes).max(N_FIXED_ROWS),
+            receipt_circuit_len(
+                block.circuits_params.max_txs,
+                block.circuits_params.max_log_bytes,
+            )
+            .max(N_FIXED_ROWS),
+        )
+    }
+
+    /// Make the assignments to the ReceiptCircuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.assign(
+            layouter,
+            &self.receipts,
+            receipt_circuit_len(self.max_txs, self.max_log_bytes),
+            challenges,
+        )
+    }
+}
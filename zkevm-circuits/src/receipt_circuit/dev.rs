@@ -0,0 +1,86 @@
+pub use super::ReceiptCircuit;
+
+use crate::{
+    receipt_circuit::{ReceiptCircuitConfig, ReceiptCircuitConfigArgs},
+    table::{KeccakTable, ReceiptTable, RwTable, UXTable},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+};
+use eth_types::Field;
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Challenge, Circuit, ConstraintSystem, Error},
+};
+
+impl<F: Field> Circuit<F> for ReceiptCircuit<F> {
+    type Config = (
+        ReceiptCircuitConfig<F>,
+        RwTable,
+        KeccakTable,
+        UXTable<8>,
+        UXTable<16>,
+        Challenges<Challenge>,
+    );
+    type FloorPlanner = SimpleFloorPlanner;
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let rw_table = RwTable::construct(meta);
+        let keccak_table = KeccakTable::construct(meta);
+        let receipt_table = ReceiptTable::construct(meta);
+        let u8_table = UXTable::construct(meta);
+        let u16_table = UXTable::construct(meta);
+        let challenges = Challenges::construct(meta);
+        let challenge_exprs = challenges.exprs(meta);
+
+        let config = ReceiptCircuitConfig::new(
+            meta,
+            ReceiptCircuitConfigArgs {
+                rw_table,
+                keccak_table,
+                receipt_table,
+                u8_table,
+                u16_table,
+                challenges: challenge_exprs,
+            },
+        );
+        (
+            config,
+            rw_table,
+            keccak_table,
+            u8_table,
+            u16_table,
+            challenges,
+        )
+    }
+
+    fn synthesize(
+        &self,
+        (config, rw_table, keccak_table, u8_table, u16_table, challenges): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let challenges = challenges.values(&mut layouter);
+        u8_table.load(&mut layouter)?;
+        u16_table.load(&mut layouter)?;
+        rw_table.load(
+            &mut layouter,
+            &self.external_data.rws.table_assignments(),
+            self.external_data.max_rws,
+        )?;
+        // The addresses and topics accrued to the blooms
+        let items: Vec<Vec<u8>> = self
+            .receipts
+            .iter()
+            .flat_map(|receipt| receipt.logs.iter())
+            .flat_map(|log| {
+                std::iter::once(log.address.as_bytes().to_vec())
+                    .chain(log.topics.iter().map(|topic| topic.as_bytes().to_vec()))
+            })
+            .collect();
+        keccak_table.dev_load(&mut layouter, &items, &challenges)?;
+        self.synthesize_sub(&config, &challenges, &mut layouter)
+    }
+}
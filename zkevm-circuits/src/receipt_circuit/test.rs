@@ -0,0 +1,126 @@
+use super::*;
+use crate::{util::unusable_rows, witness::block_convert};
+use bus_mapping::{circuit_input_builder::FixedCParams, mock::BlockData};
+use eth_types::{bytecode, geth_types::GethData, Bytecode, Word};
+use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
+use mock::{test_ctx::helpers::account_0_code_account_1_no_code, TestContext};
+
+#[test]
+fn receipt_circuit_unusable_rows() {
+    assert_eq!(
+        ReceiptCircuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, ReceiptCircuit::<Fr>>(()),
+    )
+}
+
+/// Build the witness of a block of two transactions calling the code
+fn gen_block(code: Bytecode) -> witness::Block<Fr> {
+    let test_ctx = TestContext::<2, 2>::new(
+        None,
+        account_0_code_account_1_no_code(code),
+        |mut txs, accs| {
+            txs[0].from(accs[1].address).to(accs[0].address);
+            txs[1].from(accs[1].address).to(accs[0].address);
+        },
+        |block, _tx| block.number(0xcafeu64),
+    )
+    .unwrap();
+    let block: GethData = test_ctx.into();
+    let mut builder =
+        BlockData::new_from_geth_data_with_params(block.clone(), FixedCParams::default())
+            .new_circuit_input_builder();
+    builder
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap();
+    block_convert(&builder).unwrap()
+}
+
+fn gen_logs_block() -> witness::Block<Fr> {
+    gen_block(bytecode! {
+        PUSH32(Word::MAX)
+        PUSH1(0)
+        MSTORE
+        // LOG0 of a single byte lower than 0x80
+        PUSH1(1)
+        PUSH1(31)
+        LOG0
+        // LOG2 of 64 bytes
+        PUSH32(Word::from(0x1234))
+        PUSH32(Word::MAX)
+        PUSH1(64)
+        PUSH1(0)
+        LOG2
+        // LOG1 of no data
+        PUSH1(0xff)
+        PUSH1(0)
+        PUSH1(0)
+        LOG1
+        STOP
+    })
+}
+
+fn verify(block: witness::Block<Fr>, success: bool) {
+    // The u16 table takes 2^16 rows.
+    let k = 17;
+    let circuit = ReceiptCircuit::<Fr>::new_from_block(&block);
+    let prover = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
+    let result = prover.verify_par();
+    if let Err(failures) = &result {
+        for failure in failures.iter().take(10) {
+            log::error!("{}", failure);
+        }
+    }
+    assert_eq!(result.is_ok(), success);
+}
+
+#[test]
+fn receipt_circuit_logs() {
+    verify(gen_logs_block(), true);
+}
+
+#[test]
+fn receipt_circuit_no_logs() {
+    verify(gen_block(bytecode! { STOP }), true);
+}
+
+#[test]
+fn receipt_circuit_no_receipts() {
+    let mut block = gen_block(bytecode! { STOP });
+    block.receipts.clear();
+    verify(block, true);
+}
+
+#[test]
+fn receipt_circuit_invalid_status() {
+    let mut block = gen_logs_block();
+    block.receipts[0].status = false;
+    verify(block, false);
+}
+
+#[test]
+fn receipt_circuit_invalid_cumulative_gas_used() {
+    let mut block = gen_logs_block();
+    block.receipts[1].cumulative_gas_used += 1;
+    verify(block, false);
+}
+
+#[test]
+fn receipt_circuit_invalid_topic() {
+    let mut block = gen_logs_block();
+    block.receipts[0].logs[1].topics[0].0[31] ^= 1;
+    verify(block, false);
+}
+
+#[test]
+fn receipt_circuit_invalid_data() {
+    let mut block = gen_logs_block();
+    block.receipts[1].logs[0].data = vec![0x7e].into();
+    verify(block, false);
+}
+
+#[test]
+fn receipt_circuit_missing_log() {
+    let mut block = gen_logs_block();
+    block.receipts[0].logs.pop();
+    verify(block, false);
+}
@@ -0,0 +1,933 @@
+//! The ripemd160 circuit implementation.
+//!
+//! Every 64-byte block of a message takes [`NUM_ROWS_PER_BLOCK`] rows: the
+//! state words are loaded on the first 5 rows, each of the 80 steps computes
+//! the new word of both the left and the right lines on its own row, and the
+//! last 5 rows combine the words of both lines with the state of the previous
+//! block.  As in the sha256 circuit, the words are decomposed in bits so that
+//! the boolean functions and the rotations are computed with expressions only.
+//!
+//! A step only computes one new word `t = ROL^s(A + f(B, C, D) + x + K) + E`,
+//! the other words being the ones computed by the previous steps: `B` and `C`
+//! are the words of the 2 previous steps, `D`, `E` and `A` the words of the 3,
+//! 4 and 5 previous steps rotated left by [`STATE_ROTATION`].
+mod param;
+/// Ripemd160 multi
+pub(crate) mod ripemd160_multi;
+/// Util
+mod util;
+
+#[cfg(any(test, feature = "test-circuits"))]
+mod dev;
+#[cfg(test)]
+mod test;
+#[cfg(feature = "test-circuits")]
+pub use dev::Ripemd160Circuit as TestRipemd160Circuit;
+
+use std::{array, marker::PhantomData};
+pub use Ripemd160CircuitConfig as Ripemd160Config;
+
+use self::{
+    param::*,
+    ripemd160_multi::{
+        get_num_ripemd160_blocks, multi_ripemd160, Ripemd160Row, Ripemd160Selectors,
+    },
+    util::*,
+};
+use crate::{
+    evm_circuit::util::{
+        constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+        rlc,
+    },
+    table::{LookupTable, Ripemd160Table},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use eth_types::Field;
+use gadgets::util::{not, select, sum, Expr};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Region, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, VirtualCells,
+    },
+    poly::Rotation,
+};
+
+/// Ripemd160Config
+#[derive(Clone, Debug)]
+pub struct Ripemd160CircuitConfig<F> {
+    q_enable: Column<Fixed>,
+    q_first: Column<Fixed>,
+    q_block_start: Column<Fixed>,
+    q_start: Column<Fixed>,
+    q_start_last: Column<Fixed>,
+    q_step: Column<Fixed>,
+    q_input: Column<Fixed>,
+    q_input_first: Column<Fixed>,
+    q_input_last: Column<Fixed>,
+    q_length: Column<Fixed>,
+    q_end: Column<Fixed>,
+    q_block_end: Column<Fixed>,
+    q_round: [Column<Fixed>; NUM_ROUNDS],
+    q_rotation_left: [Column<Fixed>; NUM_STEP_ROTATIONS],
+    q_rotation_right: [Column<Fixed>; NUM_STEP_ROTATIONS],
+    k_left: Column<Fixed>,
+    k_right: Column<Fixed>,
+    iv: Column<Fixed>,
+    t_left: [Column<Advice>; NUM_BITS_PER_WORD],
+    u_left: [Column<Advice>; NUM_BITS_PER_WORD],
+    t_right: [Column<Advice>; NUM_BITS_PER_WORD],
+    u_right: [Column<Advice>; NUM_BITS_PER_WORD],
+    x: [Column<Advice>; NUM_BITS_PER_WORD],
+    x_left: Column<Advice>,
+    x_right: Column<Advice>,
+    carry_u_left: [Column<Advice>; NUM_CARRY_BITS_U],
+    carry_t_left: [Column<Advice>; NUM_CARRY_BITS_T],
+    carry_u_right: [Column<Advice>; NUM_CARRY_BITS_U],
+    carry_t_right: [Column<Advice>; NUM_CARRY_BITS_T],
+    h: [Column<Advice>; NUM_STATE_WORDS],
+    is_final: Column<Advice>,
+    is_first_block: Column<Advice>,
+    is_paddings: [Column<Advice>; NUM_BYTES_PER_WORD],
+    data_rlcs: [Column<Advice>; NUM_BYTES_PER_WORD],
+    /// The columns for other circuits to lookup Ripemd160 hash results
+    pub ripemd160_table: Ripemd160Table,
+    _marker: PhantomData<F>,
+}
+
+/// Circuit configuration arguments
+pub struct Ripemd160CircuitConfigArgs<F: Field> {
+    /// Ripemd160Table
+    pub ripemd160_table: Ripemd160Table,
+    /// Challenges randomness
+    pub challenges: Challenges<Expression<F>>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for Ripemd160CircuitConfig<F> {
+    type ConfigArgs = Ripemd160CircuitConfigArgs<F>;
+
+    /// Return a new Ripemd160CircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            ripemd160_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let q_enable = meta.fixed_column();
+        let q_first = meta.fixed_column();
+        let q_block_start = meta.fixed_column();
+        let q_start = meta.fixed_column();
+        let q_start_last = meta.fixed_column();
+        let q_step = meta.fixed_column();
+        let q_input = meta.fixed_column();
+        let q_input_first = meta.fixed_column();
+        let q_input_last = meta.fixed_column();
+        let q_length = meta.fixed_column();
+        let q_end = meta.fixed_column();
+        let q_block_end = meta.fixed_column();
+        let q_round = array::from_fn(|_| meta.fixed_column());
+        let q_rotation_left = array::from_fn(|_| meta.fixed_column());
+        let q_rotation_right = array::from_fn(|_| meta.fixed_column());
+        let k_left = meta.fixed_column();
+        let k_right = meta.fixed_column();
+        let iv = meta.fixed_column();
+
+        let t_left = array::from_fn(|_| meta.advice_column());
+        let u_left = array::from_fn(|_| meta.advice_column());
+        let t_right = array::from_fn(|_| meta.advice_column());
+        let u_right = array::from_fn(|_| meta.advice_column());
+        let x = array::from_fn(|_| meta.advice_column());
+        // The message words used by the steps are copied from the input words.
+        let x_left = meta.advice_column();
+        let x_right = meta.advice_column();
+        meta.enable_equality(x_left);
+        meta.enable_equality(x_right);
+        let carry_u_left = array::from_fn(|_| meta.advice_column());
+        let carry_t_left = array::from_fn(|_| meta.advice_column());
+        let carry_u_right = array::from_fn(|_| meta.advice_column());
+        let carry_t_right = array::from_fn(|_| meta.advice_column());
+        let h = array::from_fn(|_| meta.advice_column());
+        let is_final = meta.advice_column();
+        let is_first_block = meta.advice_column();
+        let is_paddings = array::from_fn(|_| meta.advice_column());
+        // The data rlc after the last byte of a row is the one exposed in the table.
+        let data_rlcs = array::from_fn(|idx| {
+            if idx == NUM_BYTES_PER_WORD - 1 {
+                ripemd160_table.input_rlc
+            } else {
+                meta.advice_column_in(SecondPhase)
+            }
+        });
+        let length = ripemd160_table.input_len;
+        let is_enabled = ripemd160_table.is_enabled;
+        let output_rlc = ripemd160_table.output_rlc;
+        let r = challenges.keccak_input();
+
+        let query_bits = |meta: &mut VirtualCells<F>,
+                          columns: &[Column<Advice>; NUM_BITS_PER_WORD],
+                          rot: i32|
+         -> Bits<F> {
+            array::from_fn(|i| meta.query_advice(columns[i], Rotation(rot)))
+        };
+        let query_carry = |meta: &mut VirtualCells<F>, columns: &[Column<Advice>], rot: i32| {
+            let bits = columns
+                .iter()
+                .map(|column| meta.query_advice(*column, Rotation(rot)))
+                .collect::<Vec<_>>();
+            decode::expr(&bits) * (1u64 << NUM_BITS_PER_WORD).expr()
+        };
+        // The bits of the state `h0, h1, h2, h3, h4` from the words stored in the
+        // order of `to_state_words`, the last one being at rotation `rot`.
+        let query_state = |meta: &mut VirtualCells<F>, rot: i32| -> [Bits<F>; NUM_STATE_WORDS] {
+            let [w0, w1, w2, w3, w4] = array::from_fn(|i| {
+                query_bits(meta, &t_left, rot - (NUM_STATE_WORDS - 1 - i) as i32)
+            });
+            [
+                rotate_left(&w0, STATE_ROTATION),
+                w4,
+                w3,
+                rotate_left(&w2, STATE_ROTATION),
+                rotate_left(&w1, STATE_ROTATION),
+            ]
+        };
+
+        meta.create_gate("boolean checks", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for column in t_left
+                .iter()
+                .chain(u_left.iter())
+                .chain(t_right.iter())
+                .chain(u_right.iter())
+                .chain(x.iter())
+                .chain(carry_u_left.iter())
+                .chain(carry_t_left.iter())
+                .chain(carry_u_right.iter())
+                .chain(carry_t_right.iter())
+                .chain(is_paddings.iter())
+                .chain([is_final, is_first_block].iter())
+            {
+                cb.require_boolean("boolean", meta.query_advice(*column, Rotation::cur()));
+            }
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("block", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_first = meta.query_fixed(q_first, Rotation::cur());
+            let q_block_start = meta.query_fixed(q_block_start, Rotation::cur());
+            let [(is_final, is_final_prev), (is_first_block, is_first_block_prev)] =
+                [is_final, is_first_block].map(|column| {
+                    (
+                        meta.query_advice(column, Rotation::cur()),
+                        meta.query_advice(column, Rotation::prev()),
+                    )
+                });
+            cb.condition(not::expr(q_block_start.clone()), |cb| {
+                cb.require_equal(
+                    "is_final is the same on all rows of a block",
+                    is_final,
+                    is_final_prev.clone(),
+                );
+                cb.require_equal(
+                    "is_first_block is the same on all rows of a block",
+                    is_first_block.clone(),
+                    is_first_block_prev,
+                );
+            });
+            cb.condition(q_block_start, |cb| {
+                cb.require_equal(
+                    "a new hash starts on the first row or after a final block",
+                    is_first_block,
+                    select::expr(q_first, 1.expr(), is_final_prev),
+                );
+            });
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        // Load the state, the initial value for the first block of a hash and the
+        // state after the previous block otherwise.  Both lines start from the same
+        // words.
+        meta.create_gate("start", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let is_first_block = meta.query_advice(is_first_block, Rotation::cur());
+            let word = decode::expr(&query_bits(meta, &t_left, 0));
+            let word_prev_block = decode::expr(&query_bits(meta, &t_left, -(NUM_END_ROWS as i32)));
+            let iv = meta.query_fixed(iv, Rotation::cur());
+            cb.require_equal(
+                "left word",
+                word.clone(),
+                select::expr(is_first_block, iv, word_prev_block),
+            );
+            cb.require_equal(
+                "right word",
+                decode::expr(&query_bits(meta, &t_right, 0)),
+                word,
+            );
+            cb.gate(meta.query_fixed(q_start, Rotation::cur()))
+        });
+
+        // Keep the state of the block, which is added to the words of both lines at
+        // the end.
+        meta.create_gate("state", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let state = query_state(meta, 0);
+            for (column, bits) in h.iter().zip(state.iter()) {
+                cb.require_equal(
+                    "state word",
+                    meta.query_advice(*column, Rotation::cur()),
+                    decode::expr(bits),
+                );
+            }
+            cb.gate(meta.query_fixed(q_start_last, Rotation::cur()))
+        });
+
+        meta.create_gate("carry over state", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for column in h {
+                cb.require_equal(
+                    "state is carried over",
+                    meta.query_advice(column, Rotation::cur()),
+                    meta.query_advice(column, Rotation::prev()),
+                );
+            }
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    - meta.query_fixed(q_start, Rotation::cur()),
+            )
+        });
+
+        // A step computes the new word of each line.  The right line uses the
+        // boolean functions in the reverse order of the rounds.
+        meta.create_gate("step", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_round = q_round.map(|column| meta.query_fixed(column, Rotation::cur()));
+            for (name, t, u, carry_u, carry_t, x, k, q_rotation, is_left) in [
+                (
+                    "left",
+                    t_left,
+                    u_left,
+                    &carry_u_left[..],
+                    &carry_t_left[..],
+                    x_left,
+                    k_left,
+                    q_rotation_left,
+                    true,
+                ),
+                (
+                    "right",
+                    t_right,
+                    u_right,
+                    &carry_u_right[..],
+                    &carry_t_right[..],
+                    x_right,
+                    k_right,
+                    q_rotation_right,
+                    false,
+                ),
+            ] {
+                let [b, c, d, e, a] = [-1, -2, -3, -4, -5].map(|rot| query_bits(meta, &t, rot));
+                let [d, e, a] = [d, e, a].map(|bits| rotate_left(&bits, STATE_ROTATION));
+                let f = sum::expr(q_round.iter().enumerate().map(|(round, q_round)| {
+                    let round = if is_left {
+                        round
+                    } else {
+                        NUM_ROUNDS - 1 - round
+                    };
+                    q_round.clone() * f::expr(round, &b, &c, &d)
+                }));
+                let u_bits = query_bits(meta, &u, 0);
+                cb.require_equal(
+                    name,
+                    decode::expr(&u_bits) + query_carry(meta, carry_u, 0),
+                    decode::expr(&a)
+                        + f
+                        + meta.query_advice(x, Rotation::cur())
+                        + meta.query_fixed(k, Rotation::cur()),
+                );
+                let rotated = sum::expr(q_rotation.iter().enumerate().map(|(idx, q_rotation)| {
+                    meta.query_fixed(*q_rotation, Rotation::cur())
+                        * decode::expr(&rotate_left(&u_bits, MIN_STEP_ROTATION + idx))
+                }));
+                cb.require_equal(
+                    name,
+                    decode::expr(&query_bits(meta, &t, 0)) + query_carry(meta, carry_t, 0),
+                    rotated + decode::expr(&e),
+                );
+            }
+            cb.gate(meta.query_fixed(q_step, Rotation::cur()))
+        });
+
+        // Combine the last words `A, B, C, D, E` of both lines with the state, the
+        // new state is stored on the 5 rows from this one, in the order it is loaded.
+        meta.create_gate("end", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let [[a_left, b_left, c_left, d_left, e_left], [a_right, b_right, c_right, d_right, e_right]] =
+                [t_left, t_right].map(|t| {
+                    let [b, c, d, e, a] = [-1, -2, -3, -4, -5].map(|rot| query_bits(meta, &t, rot));
+                    [
+                        decode::expr(&rotate_left(&a, STATE_ROTATION)),
+                        decode::expr(&b),
+                        decode::expr(&c),
+                        decode::expr(&rotate_left(&d, STATE_ROTATION)),
+                        decode::expr(&rotate_left(&e, STATE_ROTATION)),
+                    ]
+                });
+            let [h0, h1, h2, h3, h4] = h.map(|column| meta.query_advice(column, Rotation::cur()));
+            let new_state = [
+                h1 + c_left + d_right,
+                h2 + d_left + e_right,
+                h3 + e_left + a_right,
+                h4 + a_left + b_right,
+                h0 + b_left + c_right,
+            ];
+            for (rot, idx) in [0, 4, 3, 2, 1].into_iter().enumerate() {
+                let bits = query_bits(meta, &t_left, rot as i32);
+                let word = if rot < 3 {
+                    decode::expr(&rotate_left(&bits, STATE_ROTATION))
+                } else {
+                    decode::expr(&bits)
+                };
+                cb.require_equal(
+                    "new state word",
+                    word + query_carry(meta, &carry_u_left, rot as i32),
+                    new_state[idx].clone(),
+                );
+            }
+            cb.gate(meta.query_fixed(q_end, Rotation::cur()))
+        });
+
+        // Process the input words, the bytes of a word are little-endian.
+        meta.create_gate("input", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let x_bits = query_bits(meta, &x, 0);
+            cb.require_equal(
+                "the left line uses the input words in order",
+                meta.query_advice(x_left, Rotation::cur()),
+                decode::expr(&x_bits),
+            );
+            let bytes: [Expression<F>; NUM_BYTES_PER_WORD] = array::from_fn(|idx| {
+                let start = idx * NUM_BITS_PER_BYTE;
+                decode::expr(&x_bits[start..start + NUM_BITS_PER_BYTE])
+            });
+            let is_paddings_cur =
+                is_paddings.map(|column| meta.query_advice(column, Rotation::cur()));
+            let data_rlcs_cur = data_rlcs.map(|column| meta.query_advice(column, Rotation::cur()));
+            let is_padding_prev =
+                meta.query_advice(is_paddings[NUM_BYTES_PER_WORD - 1], Rotation::prev());
+            let data_rlc_prev =
+                meta.query_advice(data_rlcs[NUM_BYTES_PER_WORD - 1], Rotation::prev());
+            let is_final = meta.query_advice(is_final, Rotation::cur());
+            let q_length = meta.query_fixed(q_length, Rotation::cur());
+
+            for idx in 0..NUM_BYTES_PER_WORD {
+                let (is_padding_before, data_rlc_before) = if idx == 0 {
+                    (is_padding_prev.clone(), data_rlc_prev.clone())
+                } else {
+                    (
+                        is_paddings_cur[idx - 1].clone(),
+                        data_rlcs_cur[idx - 1].clone(),
+                    )
+                };
+                let is_first_padding = is_paddings_cur[idx].clone() - is_padding_before.clone();
+                cb.require_boolean("padding is monotonic", is_first_padding.clone());
+                cb.condition(is_first_padding, |cb| {
+                    cb.require_equal(
+                        "first padding byte is 0x80",
+                        bytes[idx].clone(),
+                        0x80.expr(),
+                    );
+                });
+                // The length words of the final block are checked below.
+                cb.condition(
+                    is_padding_before * not::expr(q_length.clone() * is_final.clone()),
+                    |cb| {
+                        cb.require_zero("other padding bytes are zero", bytes[idx].clone());
+                    },
+                );
+                cb.require_equal(
+                    "data rlc only accumulates the message bytes",
+                    data_rlcs_cur[idx].clone(),
+                    select::expr(
+                        is_paddings_cur[idx].clone(),
+                        data_rlc_before.clone(),
+                        data_rlc_before * r.clone() + bytes[idx].clone(),
+                    ),
+                );
+            }
+            let length_cur = meta.query_advice(length, Rotation::cur());
+            cb.require_equal(
+                "length only counts the message bytes",
+                length_cur.clone(),
+                meta.query_advice(length, Rotation::prev())
+                    + sum::expr(
+                        is_paddings_cur
+                            .iter()
+                            .map(|is_padding| not::expr(is_padding.clone())),
+                    ),
+            );
+
+            let q_input_last = meta.query_fixed(q_input_last, Rotation::cur());
+            let is_padding_length_start = meta.query_advice(is_paddings[0], Rotation::prev());
+            let x_length_lo = decode::expr(&query_bits(meta, &x, -1));
+            cb.condition(q_input_last * is_final.clone(), |cb| {
+                cb.require_equal(
+                    "the length words of the final block are padding",
+                    is_padding_length_start,
+                    1.expr(),
+                );
+                cb.require_equal(
+                    "the length words hold the message length in bits",
+                    decode::expr(&x_bits) * (1u64 << NUM_BITS_PER_WORD).expr() + x_length_lo,
+                    length_cur * NUM_BITS_PER_BYTE.expr(),
+                );
+            });
+
+            // A final block only made of padding bytes is only allowed when the
+            // length words didn't fit in the previous block, i.e. when the byte
+            // before the length words is not padding.
+            let q_input_first = meta.query_fixed(q_input_first, Rotation::cur());
+            let is_padding_before_length = meta.query_advice(
+                is_paddings[NUM_BYTES_PER_WORD - 1],
+                Rotation(
+                    -((NUM_ROWS_PER_BLOCK
+                        - (NUM_WORDS_TO_ABSORB - NUM_BYTES_LENGTH / NUM_BYTES_PER_WORD - 1))
+                        as i32),
+                ),
+            );
+            cb.condition(q_input_first * is_final, |cb| {
+                cb.require_zero(
+                    "no extra padding block",
+                    is_padding_prev * is_padding_before_length,
+                );
+            });
+            cb.gate(meta.query_fixed(q_input, Rotation::cur()))
+        });
+
+        // The message data is carried over on the other rows, and reset when a new
+        // hash starts.
+        meta.create_gate("carry over data", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let start_new_hash = meta.query_fixed(q_block_start, Rotation::cur())
+                * meta.query_advice(is_first_block, Rotation::cur());
+            for column in [
+                is_paddings[NUM_BYTES_PER_WORD - 1],
+                data_rlcs[NUM_BYTES_PER_WORD - 1],
+                length,
+            ] {
+                cb.require_equal(
+                    "data is carried over",
+                    meta.query_advice(column, Rotation::cur()),
+                    not::expr(start_new_hash.clone()) * meta.query_advice(column, Rotation::prev()),
+                );
+            }
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    - meta.query_fixed(q_input, Rotation::cur()),
+            )
+        });
+
+        // The digest is the state after the final block of the hash, the words are
+        // little-endian.
+        meta.create_gate("output", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let is_enabled = meta.query_advice(is_enabled, Rotation::cur());
+            cb.require_equal(
+                "the table is enabled on the last row of a final block",
+                is_enabled.clone(),
+                meta.query_advice(is_final, Rotation::cur()),
+            );
+            let mut digest_bytes = Vec::new();
+            for bits in query_state(meta, 0) {
+                for idx in 0..NUM_BYTES_PER_WORD {
+                    let start = idx * NUM_BITS_PER_BYTE;
+                    digest_bytes.push(decode::expr(&bits[start..start + NUM_BITS_PER_BYTE]));
+                }
+            }
+            digest_bytes.reverse();
+            let output_rlc = meta.query_advice(output_rlc, Rotation::cur());
+            cb.condition(is_enabled, |cb| {
+                cb.require_equal(
+                    "output rlc",
+                    output_rlc,
+                    rlc::expr(&digest_bytes, r.clone()),
+                );
+            });
+            cb.gate(meta.query_fixed(q_block_end, Rotation::cur()))
+        });
+
+        meta.create_gate("table", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            cb.require_zero(
+                "the table is only enabled on the last row of a block",
+                meta.query_advice(is_enabled, Rotation::cur())
+                    * not::expr(meta.query_fixed(q_block_end, Rotation::cur())),
+            );
+            cb.gate(1.expr())
+        });
+
+        Ripemd160CircuitConfig {
+            q_enable,
+            q_first,
+            q_block_start,
+            q_start,
+            q_start_last,
+            q_step,
+            q_input,
+            q_input_first,
+            q_input_last,
+            q_length,
+            q_end,
+            q_block_end,
+            q_round,
+            q_rotation_left,
+            q_rotation_right,
+            k_left,
+            k_right,
+            iv,
+            t_left,
+            u_left,
+            t_right,
+            u_right,
+            x,
+            x_left,
+            x_right,
+            carry_u_left,
+            carry_t_left,
+            carry_u_right,
+            carry_t_right,
+            h,
+            is_final,
+            is_first_block,
+            is_paddings,
+            data_rlcs,
+            ripemd160_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> Ripemd160CircuitConfig<F> {
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        witness: &[Ripemd160Row<F>],
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "assign ripemd160 rows",
+            |mut region| {
+                let mut words = Vec::with_capacity(witness.len());
+                for (offset, ripemd160_row) in witness.iter().enumerate() {
+                    words.push(self.set_row(&mut region, offset, ripemd160_row)?);
+                }
+                // The steps use the input words of their block in the order of
+                // `R_LEFT` and `R_RIGHT`, the left line uses them in order on the
+                // first round.
+                for block in words.chunks(NUM_ROWS_PER_BLOCK) {
+                    let steps = &block[NUM_START_ROWS..NUM_START_ROWS + NUM_STEPS];
+                    for (step, (x_left, x_right)) in steps.iter().enumerate() {
+                        let (round, idx) = (step / NUM_STEPS_PER_ROUND, step % NUM_STEPS_PER_ROUND);
+                        if step >= NUM_WORDS_TO_ABSORB {
+                            region.constrain_equal(
+                                x_left.cell(),
+                                steps[R_LEFT[round][idx]].0.cell(),
+                            )?;
+                        }
+                        region
+                            .constrain_equal(x_right.cell(), steps[R_RIGHT[round][idx]].0.cell())?;
+                    }
+                }
+                self.ripemd160_table.annotate_columns_in_region(&mut region);
+                self.annotate_circuit(&mut region);
+                Ok(())
+            },
+        )
+    }
+
+    /// Assigns a row, returns the cells of the message words used by the step.
+    fn set_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        row: &Ripemd160Row<F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let selectors = Ripemd160Selectors::new(offset);
+
+        // Fixed selectors
+        for (name, column, value) in [
+            ("q_enable", self.q_enable, F::ONE),
+            ("q_first", self.q_first, F::from((offset == 0) as u64)),
+            (
+                "q_block_start",
+                self.q_block_start,
+                F::from(selectors.q_block_start as u64),
+            ),
+            ("q_start", self.q_start, F::from(selectors.q_start as u64)),
+            (
+                "q_start_last",
+                self.q_start_last,
+                F::from(selectors.q_start_last as u64),
+            ),
+            ("q_step", self.q_step, F::from(selectors.q_step as u64)),
+            ("q_input", self.q_input, F::from(selectors.q_input as u64)),
+            (
+                "q_input_first",
+                self.q_input_first,
+                F::from(selectors.q_input_first as u64),
+            ),
+            (
+                "q_input_last",
+                self.q_input_last,
+                F::from(selectors.q_input_last as u64),
+            ),
+            (
+                "q_length",
+                self.q_length,
+                F::from(selectors.q_length as u64),
+            ),
+            ("q_end", self.q_end, F::from(selectors.q_end as u64)),
+            (
+                "q_block_end",
+                self.q_block_end,
+                F::from(selectors.q_block_end as u64),
+            ),
+            ("k_left", self.k_left, F::from(selectors.k_left as u64)),
+            ("k_right", self.k_right, F::from(selectors.k_right as u64)),
+            ("iv", self.iv, F::from(selectors.iv as u64)),
+        ]
+        .into_iter()
+        .chain(
+            self.q_round
+                .iter()
+                .zip(selectors.q_round.iter())
+                .chain(
+                    self.q_rotation_left
+                        .iter()
+                        .zip(selectors.q_rotation_left.iter()),
+                )
+                .chain(
+                    self.q_rotation_right
+                        .iter()
+                        .zip(selectors.q_rotation_right.iter()),
+                )
+                .map(|(column, value)| ("q_round_rotation", *column, F::from(*value as u64))),
+        ) {
+            region.assign_fixed(
+                || format!("assign {} {}", name, offset),
+                column,
+                offset,
+                || Value::known(value),
+            )?;
+        }
+
+        self.ripemd160_table.assign_row(
+            region,
+            offset,
+            [
+                Value::known(F::from(row.is_enabled as u64)),
+                row.data_rlcs[NUM_BYTES_PER_WORD - 1],
+                Value::known(F::from(row.length as u64)),
+                row.output_rlc,
+            ],
+        )?;
+
+        // Words
+        for (name, columns, bits) in [
+            ("t_left", &self.t_left, &row.t_left),
+            ("u_left", &self.u_left, &row.u_left),
+            ("t_right", &self.t_right, &row.t_right),
+            ("u_right", &self.u_right, &row.u_right),
+            ("x", &self.x, &row.x),
+        ] {
+            for (idx, (column, bit)) in columns.iter().zip(bits.iter()).enumerate() {
+                region.assign_advice(
+                    || format!("assign {} bit {} {}", name, idx, offset),
+                    *column,
+                    offset,
+                    || Value::known(F::from(*bit as u64)),
+                )?;
+            }
+        }
+        let [x_left, x_right] = [
+            ("x_left", self.x_left, row.x_left),
+            ("x_right", self.x_right, row.x_right),
+        ]
+        .map(|(name, column, value)| {
+            region.assign_advice(
+                || format!("assign {} {}", name, offset),
+                column,
+                offset,
+                || Value::known(F::from(value as u64)),
+            )
+        });
+
+        // Carries
+        for (name, columns, carry) in [
+            ("carry_u_left", &self.carry_u_left[..], row.carry_u_left),
+            ("carry_t_left", &self.carry_t_left[..], row.carry_t_left),
+            ("carry_u_right", &self.carry_u_right[..], row.carry_u_right),
+            ("carry_t_right", &self.carry_t_right[..], row.carry_t_right),
+        ] {
+            for (idx, column) in columns.iter().enumerate() {
+                region.assign_advice(
+                    || format!("assign {} bit {} {}", name, idx, offset),
+                    *column,
+                    offset,
+                    || Value::known(F::from((carry >> idx) & 1)),
+                )?;
+            }
+        }
+
+        // State
+        for (idx, (column, value)) in self.h.iter().zip(row.h.iter()).enumerate() {
+            region.assign_advice(
+                || format!("assign h{} {}", idx, offset),
+                *column,
+                offset,
+                || Value::known(F::from(*value as u64)),
+            )?;
+        }
+
+        // Message data
+        for (name, column, value) in [
+            ("is_final", self.is_final, row.is_final),
+            ("is_first_block", self.is_first_block, row.is_first_block),
+        ]
+        .into_iter()
+        .chain(
+            self.is_paddings
+                .iter()
+                .zip(row.is_paddings.iter())
+                .map(|(column, value)| ("is_padding", *column, *value)),
+        ) {
+            region.assign_advice(
+                || format!("assign {} {}", name, offset),
+                column,
+                offset,
+                || Value::known(F::from(value as u64)),
+            )?;
+        }
+        for (column, value) in self
+            .data_rlcs
+            .iter()
+            .zip(row.data_rlcs.iter())
+            .take(NUM_BYTES_PER_WORD - 1)
+        {
+            region.assign_advice(
+                || format!("assign data_rlc {}", offset),
+                *column,
+                offset,
+                || *value,
+            )?;
+        }
+
+        Ok((x_left?, x_right?))
+    }
+
+    fn annotate_circuit(&self, region: &mut Region<F>) {
+        region.name_column(|| "RIPEMD160_q_enable", self.q_enable);
+        region.name_column(|| "RIPEMD160_q_first", self.q_first);
+        region.name_column(|| "RIPEMD160_q_block_start", self.q_block_start);
+        region.name_column(|| "RIPEMD160_q_start", self.q_start);
+        region.name_column(|| "RIPEMD160_q_start_last", self.q_start_last);
+        region.name_column(|| "RIPEMD160_q_step", self.q_step);
+        region.name_column(|| "RIPEMD160_q_input", self.q_input);
+        region.name_column(|| "RIPEMD160_q_input_first", self.q_input_first);
+        region.name_column(|| "RIPEMD160_q_input_last", self.q_input_last);
+        region.name_column(|| "RIPEMD160_q_length", self.q_length);
+        region.name_column(|| "RIPEMD160_q_end", self.q_end);
+        region.name_column(|| "RIPEMD160_q_block_end", self.q_block_end);
+        region.name_column(|| "RIPEMD160_x_left", self.x_left);
+        region.name_column(|| "RIPEMD160_x_right", self.x_right);
+        region.name_column(|| "RIPEMD160_is_final", self.is_final);
+        region.name_column(|| "RIPEMD160_is_first_block", self.is_first_block);
+    }
+}
+
+/// Ripemd160Circuit
+#[derive(Default, Clone, Debug)]
+pub struct Ripemd160Circuit<F: Field> {
+    inputs: Vec<Vec<u8>>,
+    num_rows: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> SubCircuit<F> for Ripemd160Circuit<F> {
+    type Config = Ripemd160CircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // Columns of the words `t_left` are queried at 10 distinct rotations
+        // - Rotation(0) to Rotation(4) by the end of a block
+        // - Rotation(-1) to Rotation(-5) by the steps
+        // so returns 13 unusable rows.
+        13
+    }
+
+    /// The `block.circuits_params.max_ripemd160_rows` parameter, when set,
+    /// sets up the circuit to support a fixed number of blocks, independently
+    /// of the blocks required by the ripemd160 precompile calls of the block.
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        Self::new(
+            block.circuits_params.max_ripemd160_rows,
+            block
+                .precompile_events
+                .get_ripemd160_events()
+                .into_iter()
+                .map(|event| event.input)
+                .collect(),
+        )
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        (
+            block
+                .precompile_events
+                .get_ripemd160_events()
+                .iter()
+                .map(|event| get_num_ripemd160_blocks(event.input.len()) * NUM_ROWS_PER_BLOCK)
+                .sum(),
+            block.circuits_params.max_ripemd160_rows,
+        )
+    }
+
+    /// Make the assignments to the Ripemd160Circuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let witness = self.generate_witness(*challenges);
+        config.assign(layouter, witness.as_slice())
+    }
+}
+
+impl<F: Field> Ripemd160Circuit<F> {
+    /// Creates a new circuit instance
+    pub fn new(num_rows: usize, inputs: Vec<Vec<u8>>) -> Self {
+        Ripemd160Circuit {
+            inputs,
+            num_rows,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The number of ripemd160 blocks that can be compressed in this circuit
+    pub fn capacity(&self) -> Option<usize> {
+        if self.num_rows > 0 {
+            Some((self.num_rows - Self::unusable_rows()) / NUM_ROWS_PER_BLOCK)
+        } else {
+            None
+        }
+    }
+
+    /// Sets the witness using the data to be hashed
+    pub(crate) fn generate_witness(
+        &self,
+        challenges: Challenges<Value<F>>,
+    ) -> Vec<Ripemd160Row<F>> {
+        multi_ripemd160(self.inputs.as_slice(), challenges, self.capacity())
+            .expect("Too many inputs for given capacity")
+    }
+}
@@ -0,0 +1,352 @@
+use super::{param::*, util::*};
+use crate::{evm_circuit::util::rlc, util::Challenges};
+use eth_types::Field;
+use halo2_proofs::{circuit::Value, plonk::Error};
+use log::debug;
+
+/// Ripemd160Row
+#[derive(Clone, Debug)]
+pub(crate) struct Ripemd160Row<F: Field> {
+    pub(crate) t_left: [bool; NUM_BITS_PER_WORD],
+    pub(crate) u_left: [bool; NUM_BITS_PER_WORD],
+    pub(crate) t_right: [bool; NUM_BITS_PER_WORD],
+    pub(crate) u_right: [bool; NUM_BITS_PER_WORD],
+    pub(crate) x: [bool; NUM_BITS_PER_WORD],
+    pub(crate) x_left: u32,
+    pub(crate) x_right: u32,
+    pub(crate) carry_u_left: u64,
+    pub(crate) carry_t_left: u64,
+    pub(crate) carry_u_right: u64,
+    pub(crate) carry_t_right: u64,
+    pub(crate) h: [u32; NUM_STATE_WORDS],
+    pub(crate) is_final: bool,
+    pub(crate) is_first_block: bool,
+    pub(crate) is_paddings: [bool; NUM_BYTES_PER_WORD],
+    pub(crate) data_rlcs: [Value<F>; NUM_BYTES_PER_WORD],
+    pub(crate) length: usize,
+    pub(crate) is_enabled: bool,
+    pub(crate) output_rlc: Value<F>,
+}
+
+/// The selectors of a row, which only depend on the position of the row in
+/// its block.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Ripemd160Selectors {
+    pub(crate) q_block_start: bool,
+    pub(crate) q_start: bool,
+    pub(crate) q_start_last: bool,
+    pub(crate) q_step: bool,
+    pub(crate) q_input: bool,
+    pub(crate) q_input_first: bool,
+    pub(crate) q_input_last: bool,
+    pub(crate) q_length: bool,
+    pub(crate) q_end: bool,
+    pub(crate) q_block_end: bool,
+    pub(crate) q_round: [bool; NUM_ROUNDS],
+    pub(crate) q_rotation_left: [bool; NUM_STEP_ROTATIONS],
+    pub(crate) q_rotation_right: [bool; NUM_STEP_ROTATIONS],
+    pub(crate) k_left: u32,
+    pub(crate) k_right: u32,
+    pub(crate) iv: u32,
+}
+
+impl Ripemd160Selectors {
+    /// Returns the selectors of the row at `offset` in the circuit.
+    pub(crate) fn new(offset: usize) -> Self {
+        let idx = offset % NUM_ROWS_PER_BLOCK;
+        if idx < NUM_START_ROWS {
+            Self {
+                q_block_start: idx == 0,
+                q_start: true,
+                q_start_last: idx == NUM_START_ROWS - 1,
+                iv: to_state_words(IV)[idx],
+                ..Default::default()
+            }
+        } else if idx < NUM_START_ROWS + NUM_STEPS {
+            let step = idx - NUM_START_ROWS;
+            let (round, idx_in_round) = (step / NUM_STEPS_PER_ROUND, step % NUM_STEPS_PER_ROUND);
+            let mut selectors = Self {
+                q_step: true,
+                q_input: step < NUM_WORDS_TO_ABSORB,
+                q_input_first: step == 0,
+                q_input_last: step == NUM_WORDS_TO_ABSORB - 1,
+                q_length: (NUM_WORDS_TO_ABSORB - NUM_BYTES_LENGTH / NUM_BYTES_PER_WORD
+                    ..NUM_WORDS_TO_ABSORB)
+                    .contains(&step),
+                k_left: K_LEFT[round],
+                k_right: K_RIGHT[round],
+                ..Default::default()
+            };
+            selectors.q_round[round] = true;
+            selectors.q_rotation_left[S_LEFT[round][idx_in_round] - MIN_STEP_ROTATION] = true;
+            selectors.q_rotation_right[S_RIGHT[round][idx_in_round] - MIN_STEP_ROTATION] = true;
+            selectors
+        } else {
+            Self {
+                q_end: idx == NUM_START_ROWS + NUM_STEPS,
+                q_block_end: idx == NUM_ROWS_PER_BLOCK - 1,
+                ..Default::default()
+            }
+        }
+    }
+}
+
+/// Returns the words of the state in the order they are loaded in the circuit,
+/// i.e. the words computed by the 5 previous steps: `h0`, `h4` and `h3` rotated
+/// right by [`STATE_ROTATION`], then `h2` and `h1`.
+pub(crate) fn to_state_words(hs: [u32; NUM_STATE_WORDS]) -> [u32; NUM_STATE_WORDS] {
+    let rotation = STATE_ROTATION as u32;
+    [
+        hs[0].rotate_right(rotation),
+        hs[4].rotate_right(rotation),
+        hs[3].rotate_right(rotation),
+        hs[2],
+        hs[1],
+    ]
+}
+
+/// Returns the bytes of the message padded to a multiple of the block size.
+fn pad(bytes: &[u8]) -> Vec<u8> {
+    let mut padded = bytes.to_vec();
+    padded.push(0x80);
+    while padded.len() % RATE != RATE - NUM_BYTES_LENGTH {
+        padded.push(0);
+    }
+    padded.extend_from_slice(&(bytes.len() as u64 * 8).to_le_bytes());
+    padded
+}
+
+/// A step of a line, from the words computed by the 5 previous steps.  Returns
+/// the intermediate word `u = A + f(B, C, D) + x + K` and the new word
+/// `t = ROL^s(u) + E` with their carries.
+fn step(ts: &[u32], round: usize, x: u32, k: u32, rotation: usize) -> ((u32, u64), (u32, u64)) {
+    let rotation_state = |t: u32| t.rotate_left(STATE_ROTATION as u32);
+    let n = ts.len();
+    let (b, c, d, e, a) = (
+        ts[n - 1],
+        ts[n - 2],
+        rotation_state(ts[n - 3]),
+        rotation_state(ts[n - 4]),
+        rotation_state(ts[n - 5]),
+    );
+    let u = a as u64 + f::value(round, b, c, d) as u64 + x as u64 + k as u64;
+    let t = (u as u32).rotate_left(rotation as u32) as u64 + e as u64;
+    (
+        (u as u32, u >> NUM_BITS_PER_WORD),
+        (t as u32, t >> NUM_BITS_PER_WORD),
+    )
+}
+
+fn ripemd160<F: Field>(
+    rows: &mut Vec<Ripemd160Row<F>>,
+    bytes: &[u8],
+    challenges: Challenges<Value<F>>,
+) {
+    let mut hs = IV;
+    let mut length = 0;
+    let mut data_rlc = Value::known(F::ZERO);
+
+    let padded = pad(bytes);
+    let num_blocks = padded.len() / RATE;
+    for (idx, block) in padded.chunks(RATE).enumerate() {
+        let is_first_block = idx == 0;
+        let is_final = idx == num_blocks - 1;
+        let mut is_padding = idx * RATE > bytes.len();
+        let new_row = |is_paddings: [bool; NUM_BYTES_PER_WORD],
+                       data_rlcs: [Value<F>; NUM_BYTES_PER_WORD],
+                       length: usize| Ripemd160Row {
+            t_left: to_bits(0),
+            u_left: to_bits(0),
+            t_right: to_bits(0),
+            u_right: to_bits(0),
+            x: to_bits(0),
+            x_left: 0,
+            x_right: 0,
+            carry_u_left: 0,
+            carry_t_left: 0,
+            carry_u_right: 0,
+            carry_t_right: 0,
+            h: hs,
+            is_final,
+            is_first_block,
+            is_paddings,
+            data_rlcs,
+            length,
+            is_enabled: false,
+            output_rlc: Value::known(F::ZERO),
+        };
+
+        // Load the state as the words computed by the 5 steps before the first one.
+        let state_words = to_state_words(hs);
+        for word in state_words {
+            rows.push(Ripemd160Row {
+                t_left: to_bits(word),
+                t_right: to_bits(word),
+                ..new_row(
+                    [is_padding; NUM_BYTES_PER_WORD],
+                    [data_rlc; NUM_BYTES_PER_WORD],
+                    length,
+                )
+            });
+        }
+
+        // Steps, the left and right lines are computed in parallel.
+        let xs: [u32; NUM_WORDS_TO_ABSORB] = std::array::from_fn(|i| {
+            u32::from_le_bytes(
+                block[i * NUM_BYTES_PER_WORD..(i + 1) * NUM_BYTES_PER_WORD]
+                    .try_into()
+                    .unwrap(),
+            )
+        });
+        let mut ts_left = state_words.to_vec();
+        let mut ts_right = state_words.to_vec();
+        for step_idx in 0..NUM_STEPS {
+            let (round, idx_in_round) = (
+                step_idx / NUM_STEPS_PER_ROUND,
+                step_idx % NUM_STEPS_PER_ROUND,
+            );
+            let mut is_paddings = [is_padding; NUM_BYTES_PER_WORD];
+            let mut data_rlcs = [data_rlc; NUM_BYTES_PER_WORD];
+            let x = if step_idx < NUM_WORDS_TO_ABSORB {
+                let word =
+                    &block[step_idx * NUM_BYTES_PER_WORD..(step_idx + 1) * NUM_BYTES_PER_WORD];
+                for (i, byte) in word.iter().enumerate() {
+                    is_padding = idx * RATE + step_idx * NUM_BYTES_PER_WORD + i >= bytes.len();
+                    if !is_padding {
+                        length += 1;
+                        data_rlc = data_rlc
+                            .zip(challenges.keccak_input())
+                            .map(|(data_rlc, r)| data_rlc * r + F::from(*byte as u64));
+                    }
+                    is_paddings[i] = is_padding;
+                    data_rlcs[i] = data_rlc;
+                }
+                xs[step_idx]
+            } else {
+                0
+            };
+
+            let x_left = xs[R_LEFT[round][idx_in_round]];
+            let x_right = xs[R_RIGHT[round][idx_in_round]];
+            let ((u_left, carry_u_left), (t_left, carry_t_left)) = step(
+                &ts_left,
+                round,
+                x_left,
+                K_LEFT[round],
+                S_LEFT[round][idx_in_round],
+            );
+            let ((u_right, carry_u_right), (t_right, carry_t_right)) = step(
+                &ts_right,
+                NUM_ROUNDS - 1 - round,
+                x_right,
+                K_RIGHT[round],
+                S_RIGHT[round][idx_in_round],
+            );
+            rows.push(Ripemd160Row {
+                t_left: to_bits(t_left),
+                u_left: to_bits(u_left),
+                t_right: to_bits(t_right),
+                u_right: to_bits(u_right),
+                x: to_bits(x),
+                x_left,
+                x_right,
+                carry_u_left,
+                carry_t_left,
+                carry_u_right,
+                carry_t_right,
+                ..new_row(is_paddings, data_rlcs, length)
+            });
+            ts_left.push(t_left);
+            ts_right.push(t_right);
+        }
+
+        // Combine the words of both lines with the state of the previous block,
+        // the new state is stored in the same order as it is loaded.
+        let final_words = |ts: &[u32]| {
+            let rotation_state = |t: u32| t.rotate_left(STATE_ROTATION as u32);
+            let n = ts.len();
+            // A, B, C, D, E
+            [
+                rotation_state(ts[n - 5]),
+                ts[n - 1],
+                ts[n - 2],
+                rotation_state(ts[n - 3]),
+                rotation_state(ts[n - 4]),
+            ]
+        };
+        let [a_left, b_left, c_left, d_left, e_left] = final_words(&ts_left);
+        let [a_right, b_right, c_right, d_right, e_right] = final_words(&ts_right);
+        let sums = [
+            hs[1] as u64 + c_left as u64 + d_right as u64,
+            hs[2] as u64 + d_left as u64 + e_right as u64,
+            hs[3] as u64 + e_left as u64 + a_right as u64,
+            hs[4] as u64 + a_left as u64 + b_right as u64,
+            hs[0] as u64 + b_left as u64 + c_right as u64,
+        ];
+        let new_hs = sums.map(|sum| sum as u32);
+        let new_state_words = to_state_words(new_hs);
+        for (i, word) in new_state_words.into_iter().enumerate() {
+            // The carry of the sum of the i-th loaded word
+            let carry = sums[[0, 4, 3, 2, 1][i]] >> NUM_BITS_PER_WORD;
+            rows.push(Ripemd160Row {
+                t_left: to_bits(word),
+                carry_u_left: carry,
+                ..new_row(
+                    [is_padding; NUM_BYTES_PER_WORD],
+                    [data_rlc; NUM_BYTES_PER_WORD],
+                    length,
+                )
+            });
+        }
+        hs = new_hs;
+
+        if is_final {
+            let digest = hs.iter().flat_map(|h| h.to_le_bytes()).collect::<Vec<_>>();
+            let last_row = rows.last_mut().unwrap();
+            last_row.is_enabled = true;
+            last_row.output_rlc = challenges
+                .keccak_input()
+                .map(|r| rlc::value(digest.iter().rev(), r));
+            debug!("ripemd160 digest: {}", hex::encode(digest));
+        }
+    }
+}
+
+/// Witness generation for multiple ripemd160 hashes.  The rows of `capacity`
+/// blocks are returned when it's set, padding with the hashes of empty inputs.
+pub(crate) fn multi_ripemd160<F: Field>(
+    bytes: &[Vec<u8>],
+    challenges: Challenges<Value<F>>,
+    capacity: Option<usize>,
+) -> Result<Vec<Ripemd160Row<F>>, Error> {
+    let mut rows: Vec<Ripemd160Row<F>> = Vec::new();
+    for bytes in bytes {
+        ripemd160(&mut rows, bytes, challenges);
+    }
+    if let Some(capacity) = capacity {
+        let padding_rows = {
+            let mut rows = Vec::new();
+            ripemd160(&mut rows, &[], challenges);
+            rows
+        };
+        // Pad with no data hashes to the expected capacity
+        while rows.len() < capacity * NUM_ROWS_PER_BLOCK {
+            rows.extend(padding_rows.clone());
+        }
+        // Check that we are not over capacity
+        if rows.len() > capacity * NUM_ROWS_PER_BLOCK {
+            log::error!(
+                "Ripemd160 inputs exceed capacity.  needed_rows = {}, available_rows = {}",
+                rows.len(),
+                capacity * NUM_ROWS_PER_BLOCK
+            );
+            return Err(Error::BoundsFailure);
+        }
+    }
+    Ok(rows)
+}
+
+/// Returns the number of blocks needed to hash the input.
+pub(crate) fn get_num_ripemd160_blocks(input_len: usize) -> usize {
+    (input_len + 1 + NUM_BYTES_LENGTH + RATE - 1) / RATE
+}
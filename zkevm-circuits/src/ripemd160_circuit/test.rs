@@ -0,0 +1,131 @@
+use super::*;
+use crate::util::unusable_rows;
+use halo2_proofs::{
+    dev::{CellValue, MockProver},
+    halo2curves::bn256::Fr,
+    plonk::Circuit,
+};
+use itertools::izip;
+use log::error;
+
+#[test]
+fn ripemd160_circuit_unusable_rows() {
+    assert_eq!(
+        Ripemd160Circuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, Ripemd160Circuit::<Fr>>(()),
+    )
+}
+
+const EMPTY_DIGEST: &str = "9c1185a5c5e9fc54612808977ee8f548b2258d31";
+
+fn verify<F: Field>(k: u32, inputs: Vec<Vec<u8>>, digests: Vec<&str>, success: bool) {
+    let circuit = Ripemd160Circuit::new(2usize.pow(k), inputs.clone());
+    let prover = MockProver::<F>::run(k, &circuit, vec![]).unwrap();
+    let (config, challenges) = Ripemd160Circuit::configure(&mut ConstraintSystem::<F>::default());
+    let input_challenge = prover.get_challenge(challenges.keccak_input());
+
+    // Check constraints.
+    let verify_result = prover.verify();
+    if verify_result.is_ok() != success {
+        if let Some(errors) = verify_result.err() {
+            for error in errors.iter() {
+                error!("{}", error);
+            }
+        }
+        panic!();
+    }
+
+    // Extract the content of the lookup table.
+    let hash_lookup_table = {
+        let is_enabled = prover.advice_values(config.ripemd160_table.is_enabled);
+        let input_rlc = prover.advice_values(config.ripemd160_table.input_rlc);
+        let input_len = prover.advice_values(config.ripemd160_table.input_len);
+        let output_rlc = prover.advice_values(config.ripemd160_table.output_rlc);
+
+        // Keep the rows that are supposed to contain hash results.
+        izip!(is_enabled, input_rlc, input_len, output_rlc)
+            .filter_map(|(enabled, input_rlc, input_len, output_rlc)| {
+                assigned_non_zero(enabled)
+                    .then(|| (unwrap(input_rlc), unwrap(input_len), unwrap(output_rlc)))
+            })
+            .collect::<Vec<(F, F, F)>>()
+    };
+
+    let rlc = |bytes: &[u8]| rlc::value(bytes.iter().rev(), input_challenge);
+
+    // Check that all the digests are there.
+    assert!(hash_lookup_table.len() >= inputs.len());
+    assert_eq!(inputs.len(), digests.len());
+    for (input, digest, hash) in izip!(&inputs, &digests, &hash_lookup_table) {
+        let expected = (
+            rlc(input),
+            F::from(input.len() as u64),
+            rlc(&hex::decode(digest).unwrap()),
+        );
+        assert_eq!(*hash, expected);
+    }
+
+    // Check that other digests are the digest of the empty message.
+    let empty_hash = (F::ZERO, F::ZERO, rlc(&hex::decode(EMPTY_DIGEST).unwrap()));
+    for hash in hash_lookup_table.iter().skip(inputs.len()) {
+        assert_eq!(*hash, empty_hash);
+    }
+}
+
+fn assigned_non_zero<F: Field>(cv: &CellValue<F>) -> bool {
+    match *cv {
+        CellValue::Assigned(v) => !v.is_zero_vartime(),
+        _ => false,
+    }
+}
+
+fn unwrap<F: Field>(cv: &CellValue<F>) -> F {
+    match *cv {
+        CellValue::Assigned(f) => f,
+        _ => panic!("the cell should be assigned"),
+    }
+}
+
+#[test]
+fn multi_ripemd160_simple() {
+    let k = 12;
+    let inputs = vec![
+        vec![],
+        b"abc".to_vec(),
+        (0u8..55).collect::<Vec<_>>(),
+        (0u8..56).collect::<Vec<_>>(),
+        (0u8..64).collect::<Vec<_>>(),
+        b"1234567890".repeat(8),
+    ];
+    let digests = vec![
+        EMPTY_DIGEST,
+        "8eb208f7e05d987a9b044a8e98c6b087f15a0bfc",
+        "3c86963b3ff646a65ae42996e9664c747cc7e5e6",
+        "ebdd79cfd4fd9949ef8089673d2620427f487cfb",
+        "2581f5e9f957b44b0fa24d31996de47409dd1e0f",
+        "9b752e45573d4b39f4dbd3323cab82bf63326bfb",
+    ];
+    verify::<Fr>(k, inputs, digests, true);
+}
+#[test]
+fn variadic_size_check() {
+    let k = 12;
+    let num_rows = 2usize.pow(k);
+    // Empty
+    let inputs = vec![];
+    let circuit = Ripemd160Circuit::new(num_rows, inputs);
+    let prover1 = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
+
+    // Non-empty
+    let inputs = vec![
+        vec![],
+        (0u8..1).collect::<Vec<_>>(),
+        (0u8..55).collect::<Vec<_>>(),
+        (0u8..200).collect::<Vec<_>>(),
+    ];
+    let circuit = Ripemd160Circuit::new(num_rows, inputs);
+    let prover2 = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
+
+    assert_eq!(prover1.fixed(), prover2.fixed());
+    assert_eq!(prover1.permutation(), prover2.permutation());
+}
@@ -0,0 +1,80 @@
+use super::param::*;
+use eth_types::Field;
+use gadgets::util::Expr;
+use halo2_proofs::plonk::Expression;
+use std::array;
+
+/// A 32-bit word decomposed in bits, least significant bit first.
+pub(crate) type Bits<F> = [Expression<F>; NUM_BITS_PER_WORD];
+
+/// Decomposes a word in bits, least significant bit first.
+pub(crate) fn to_bits(value: u32) -> [bool; NUM_BITS_PER_WORD] {
+    array::from_fn(|i| (value >> i) & 1 == 1)
+}
+
+/// Rotates the bits of a word to the left.
+pub(crate) fn rotate_left<F: Field>(bits: &Bits<F>, n: usize) -> Bits<F> {
+    array::from_fn(|i| bits[(i + NUM_BITS_PER_WORD - n) % NUM_BITS_PER_WORD].clone())
+}
+
+/// Recombines bits into the word value
+pub(crate) mod decode {
+    use super::*;
+
+    pub(crate) fn expr<F: Field>(bits: &[Expression<F>]) -> Expression<F> {
+        bits.iter()
+            .rev()
+            .fold(0.expr(), |acc, bit| acc * 2.expr() + bit.clone())
+    }
+}
+
+/// The boolean functions of the rounds, the left line uses them in order and
+/// the right line in reverse order:
+/// - `f1(x, y, z) = x ^ y ^ z`
+/// - `f2(x, y, z) = (x & y) | (!x & z)`
+/// - `f3(x, y, z) = (x | !y) ^ z`
+/// - `f4(x, y, z) = (x & z) | (y & !z)`
+/// - `f5(x, y, z) = x ^ (y | !z)`
+pub(crate) mod f {
+    use super::*;
+
+    fn xor<F: Field>(x: Expression<F>, y: Expression<F>) -> Expression<F> {
+        x.clone() + y.clone() - 2.expr() * x * y
+    }
+
+    fn or_not<F: Field>(x: Expression<F>, y: Expression<F>) -> Expression<F> {
+        let not_y = 1.expr() - y;
+        x.clone() + not_y.clone() - x * not_y
+    }
+
+    pub(crate) fn expr<F: Field>(
+        round: usize,
+        x: &Bits<F>,
+        y: &Bits<F>,
+        z: &Bits<F>,
+    ) -> Expression<F> {
+        let bits: Bits<F> = array::from_fn(|i| {
+            let (x, y, z) = (x[i].clone(), y[i].clone(), z[i].clone());
+            match round {
+                0 => xor(xor(x, y), z),
+                1 => x.clone() * y + (1.expr() - x) * z,
+                2 => xor(or_not(x, y), z),
+                3 => x * z.clone() + y * (1.expr() - z),
+                4 => xor(x, or_not(y, z)),
+                _ => unreachable!("ripemd160 has {} rounds", NUM_ROUNDS),
+            }
+        });
+        decode::expr(&bits)
+    }
+
+    pub(crate) fn value(round: usize, x: u32, y: u32, z: u32) -> u32 {
+        match round {
+            0 => x ^ y ^ z,
+            1 => (x & y) | (!x & z),
+            2 => (x | !y) ^ z,
+            3 => (x & z) | (y & !z),
+            4 => x ^ (y | !z),
+            _ => unreachable!("ripemd160 has {} rounds", NUM_ROUNDS),
+        }
+    }
+}
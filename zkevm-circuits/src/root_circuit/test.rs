@@ -2,7 +2,7 @@ use crate::{
     root_circuit::{compile, Config, Gwc, PoseidonTranscript, RootCircuit},
     super_circuit::{test::block_1tx, SuperCircuit},
 };
-use bus_mapping::circuit_input_builder::FixedCParams;
+use bus_mapping::{circuit_input_builder::FixedCParams, precompile::PrecompileEcParams};
 use halo2_proofs::{
     circuit::Value,
     dev::MockProver,
@@ -23,6 +23,7 @@ fn test_root_circuit() {
         // Preprocess
         const TEST_MOCK_RANDOMNESS: u64 = 0x100;
         let circuits_params = FixedCParams {
+            max_blocks: 1,
             max_txs: 1,
             max_withdrawals: 5,
             max_calldata: 32,
@@ -32,6 +33,15 @@ fn test_root_circuit() {
             max_bytecode: 512,
             max_evm_rows: 0,
             max_keccak_rows: 0,
+            max_ecrecover: 0,
+            max_sha256_rows: 0,
+            max_ripemd160_rows: 0,
+            max_modexp: 0,
+            max_ec_ops: PrecompileEcParams::default(),
+            max_blake2f_rows: 0,
+            max_point_evaluations: 0,
+            max_log_bytes: 512,
+            max_mpt_rows: 0,
         };
         let (k, circuit, instance, _) =
             SuperCircuit::<_>::build(block_1tx(), circuits_params, TEST_MOCK_RANDOMNESS.into())
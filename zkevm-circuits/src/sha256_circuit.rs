@@ -0,0 +1,748 @@
+//! The sha256 circuit implementation.
+//!
+//! Every 64-byte block of a message takes [`NUM_ROWS_PER_BLOCK`] rows: the
+//! state words are loaded on the first 4 rows, each of the 64 rounds computes
+//! the new `a` and `e` words on its own row, and the last 4 rows add the
+//! compressed state to the state of the previous block.  The words are
+//! decomposed in bits so that the boolean functions are computed with
+//! expressions only.
+mod param;
+/// Sha256 packed multi
+pub(crate) mod sha256_packed_multi;
+/// Util
+mod util;
+
+#[cfg(any(test, feature = "test-circuits"))]
+mod dev;
+#[cfg(test)]
+mod test;
+#[cfg(feature = "test-circuits")]
+pub use dev::Sha256Circuit as TestSha256Circuit;
+
+use std::{array, marker::PhantomData};
+pub use Sha256CircuitConfig as Sha256Config;
+
+use self::{
+    param::*,
+    sha256_packed_multi::{get_num_sha256_blocks, multi_sha256, Sha256Row, Sha256Selectors},
+    util::*,
+};
+use crate::{
+    evm_circuit::util::{
+        constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+        rlc,
+    },
+    table::{LookupTable, Sha256Table},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use eth_types::Field;
+use gadgets::util::{not, select, sum, Expr};
+use halo2_proofs::{
+    circuit::{Layouter, Region, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, VirtualCells,
+    },
+    poly::Rotation,
+};
+
+/// Sha256Config
+#[derive(Clone, Debug)]
+pub struct Sha256CircuitConfig<F> {
+    q_enable: Column<Fixed>,
+    q_first: Column<Fixed>,
+    q_block_start: Column<Fixed>,
+    q_start: Column<Fixed>,
+    q_round: Column<Fixed>,
+    q_input: Column<Fixed>,
+    q_input_first: Column<Fixed>,
+    q_input_last: Column<Fixed>,
+    q_length: Column<Fixed>,
+    q_extend: Column<Fixed>,
+    q_end: Column<Fixed>,
+    q_block_end: Column<Fixed>,
+    round_cst: Column<Fixed>,
+    iv_a: Column<Fixed>,
+    iv_e: Column<Fixed>,
+    w: [Column<Advice>; NUM_BITS_PER_WORD],
+    a: [Column<Advice>; NUM_BITS_PER_WORD],
+    e: [Column<Advice>; NUM_BITS_PER_WORD],
+    carry_w: [Column<Advice>; NUM_CARRY_BITS],
+    carry_a: [Column<Advice>; NUM_CARRY_BITS],
+    carry_e: [Column<Advice>; NUM_CARRY_BITS],
+    is_final: Column<Advice>,
+    is_first_block: Column<Advice>,
+    is_paddings: [Column<Advice>; NUM_BYTES_PER_WORD],
+    data_rlcs: [Column<Advice>; NUM_BYTES_PER_WORD],
+    /// The columns for other circuits to lookup Sha256 hash results
+    pub sha256_table: Sha256Table,
+    _marker: PhantomData<F>,
+}
+
+/// Circuit configuration arguments
+pub struct Sha256CircuitConfigArgs<F: Field> {
+    /// Sha256Table
+    pub sha256_table: Sha256Table,
+    /// Challenges randomness
+    pub challenges: Challenges<Expression<F>>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for Sha256CircuitConfig<F> {
+    type ConfigArgs = Sha256CircuitConfigArgs<F>;
+
+    /// Return a new Sha256CircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            sha256_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let q_enable = meta.fixed_column();
+        let q_first = meta.fixed_column();
+        let q_block_start = meta.fixed_column();
+        let q_start = meta.fixed_column();
+        let q_round = meta.fixed_column();
+        let q_input = meta.fixed_column();
+        let q_input_first = meta.fixed_column();
+        let q_input_last = meta.fixed_column();
+        let q_length = meta.fixed_column();
+        let q_extend = meta.fixed_column();
+        let q_end = meta.fixed_column();
+        let q_block_end = meta.fixed_column();
+        let round_cst = meta.fixed_column();
+        let iv_a = meta.fixed_column();
+        let iv_e = meta.fixed_column();
+
+        let w = array::from_fn(|_| meta.advice_column());
+        let a = array::from_fn(|_| meta.advice_column());
+        let e = array::from_fn(|_| meta.advice_column());
+        let carry_w = array::from_fn(|_| meta.advice_column());
+        let carry_a = array::from_fn(|_| meta.advice_column());
+        let carry_e = array::from_fn(|_| meta.advice_column());
+        let is_final = meta.advice_column();
+        let is_first_block = meta.advice_column();
+        let is_paddings = array::from_fn(|_| meta.advice_column());
+        // The data rlc after the last byte of a row is the one exposed in the table.
+        let data_rlcs = array::from_fn(|idx| {
+            if idx == NUM_BYTES_PER_WORD - 1 {
+                sha256_table.input_rlc
+            } else {
+                meta.advice_column_in(SecondPhase)
+            }
+        });
+        let length = sha256_table.input_len;
+        let is_enabled = sha256_table.is_enabled;
+        let output_rlc = sha256_table.output_rlc;
+        let r = challenges.keccak_input();
+
+        let query_bits = |meta: &mut VirtualCells<F>,
+                          columns: &[Column<Advice>; NUM_BITS_PER_WORD],
+                          rot: i32|
+         -> Bits<F> {
+            array::from_fn(|i| meta.query_advice(columns[i], Rotation(rot)))
+        };
+        let query_carry = |meta: &mut VirtualCells<F>,
+                           columns: &[Column<Advice>; NUM_CARRY_BITS]| {
+            let bits = columns.map(|column| meta.query_advice(column, Rotation::cur()));
+            decode::expr(&bits) * (1u64 << NUM_BITS_PER_WORD).expr()
+        };
+
+        meta.create_gate("boolean checks", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for column in w
+                .iter()
+                .chain(a.iter())
+                .chain(e.iter())
+                .chain(carry_w.iter())
+                .chain(carry_a.iter())
+                .chain(carry_e.iter())
+                .chain(is_paddings.iter())
+                .chain([is_final, is_first_block].iter())
+            {
+                cb.require_boolean("boolean", meta.query_advice(*column, Rotation::cur()));
+            }
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("block", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_first = meta.query_fixed(q_first, Rotation::cur());
+            let q_block_start = meta.query_fixed(q_block_start, Rotation::cur());
+            let [(is_final, is_final_prev), (is_first_block, is_first_block_prev)] =
+                [is_final, is_first_block].map(|column| {
+                    (
+                        meta.query_advice(column, Rotation::cur()),
+                        meta.query_advice(column, Rotation::prev()),
+                    )
+                });
+            cb.condition(not::expr(q_block_start.clone()), |cb| {
+                cb.require_equal(
+                    "is_final is the same on all rows of a block",
+                    is_final,
+                    is_final_prev.clone(),
+                );
+                cb.require_equal(
+                    "is_first_block is the same on all rows of a block",
+                    is_first_block.clone(),
+                    is_first_block_prev,
+                );
+            });
+            cb.condition(q_block_start, |cb| {
+                cb.require_equal(
+                    "a new hash starts on the first row or after a final block",
+                    is_first_block,
+                    select::expr(q_first, 1.expr(), is_final_prev),
+                );
+            });
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        // Load the state, the initial value for the first block of a hash and the
+        // state after the previous block otherwise.
+        meta.create_gate("start", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let is_first_block = meta.query_advice(is_first_block, Rotation::cur());
+            for (name, columns, iv) in [("a", a, iv_a), ("e", e, iv_e)] {
+                let word = decode::expr(&query_bits(meta, &columns, 0));
+                let word_prev_block =
+                    decode::expr(&query_bits(meta, &columns, -(NUM_END_ROWS as i32)));
+                let iv = meta.query_fixed(iv, Rotation::cur());
+                cb.require_equal(
+                    name,
+                    word,
+                    select::expr(is_first_block.clone(), iv, word_prev_block),
+                );
+            }
+            cb.gate(meta.query_fixed(q_start, Rotation::cur()))
+        });
+
+        // A round computes the new `a` and `e`, the other words of the state are
+        // the ones of the previous rows:
+        // - `a, b, c, d` are `a` at rotations -1, -2, -3, -4
+        // - `e, f, g, h` are `e` at rotations -1, -2, -3, -4
+        meta.create_gate("round", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let [a1, a2, a3, a4] = [-1, -2, -3, -4].map(|rot| query_bits(meta, &a, rot));
+            let [e1, e2, e3, e4] = [-1, -2, -3, -4].map(|rot| query_bits(meta, &e, rot));
+            let new_a = decode::expr(&query_bits(meta, &a, 0));
+            let new_e = decode::expr(&query_bits(meta, &e, 0));
+            let w_cur = decode::expr(&query_bits(meta, &w, 0));
+            let round_cst = meta.query_fixed(round_cst, Rotation::cur());
+
+            let t1 = decode::expr(&e4)
+                + big_sigma1::expr(&e1)
+                + ch::expr(&e1, &e2, &e3)
+                + round_cst
+                + w_cur;
+            let t2 = big_sigma0::expr(&a1) + maj::expr(&a1, &a2, &a3);
+            cb.require_equal(
+                "e = d + t1",
+                new_e + query_carry(meta, &carry_e),
+                decode::expr(&a4) + t1.clone(),
+            );
+            cb.require_equal("a = t1 + t2", new_a + query_carry(meta, &carry_a), t1 + t2);
+            cb.gate(meta.query_fixed(q_round, Rotation::cur()))
+        });
+
+        // The message schedule for the rounds after the input words.
+        meta.create_gate("message schedule", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let w_cur = decode::expr(&query_bits(meta, &w, 0));
+            let w_2 = query_bits(meta, &w, -2);
+            let w_7 = query_bits(meta, &w, -7);
+            let w_15 = query_bits(meta, &w, -15);
+            let w_16 = query_bits(meta, &w, -16);
+            cb.require_equal(
+                "w = σ1(w[t-2]) + w[t-7] + σ0(w[t-15]) + w[t-16]",
+                w_cur + query_carry(meta, &carry_w),
+                small_sigma1::expr(&w_2)
+                    + decode::expr(&w_7)
+                    + small_sigma0::expr(&w_15)
+                    + decode::expr(&w_16),
+            );
+            cb.gate(meta.query_fixed(q_extend, Rotation::cur()))
+        });
+
+        // Add the compressed state to the state loaded at the start of the block.
+        meta.create_gate("end", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for (name, columns, carry) in [("a", a, carry_a), ("e", e, carry_e)] {
+                let word = decode::expr(&query_bits(meta, &columns, 0));
+                let compressed = decode::expr(&query_bits(meta, &columns, -(NUM_END_ROWS as i32)));
+                let word_start = decode::expr(&query_bits(
+                    meta,
+                    &columns,
+                    -((NUM_ROWS_PER_BLOCK - NUM_END_ROWS) as i32),
+                ));
+                cb.require_equal(
+                    name,
+                    word + query_carry(meta, &carry),
+                    compressed + word_start,
+                );
+            }
+            cb.gate(meta.query_fixed(q_end, Rotation::cur()))
+        });
+
+        // Process the input words, the bytes of a word are big-endian.
+        meta.create_gate("input", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let w_bits = query_bits(meta, &w, 0);
+            let bytes: [Expression<F>; NUM_BYTES_PER_WORD] = array::from_fn(|idx| {
+                let start = (NUM_BYTES_PER_WORD - 1 - idx) * NUM_BITS_PER_BYTE;
+                decode::expr(&w_bits[start..start + NUM_BITS_PER_BYTE])
+            });
+            let is_paddings_cur =
+                is_paddings.map(|column| meta.query_advice(column, Rotation::cur()));
+            let data_rlcs_cur = data_rlcs.map(|column| meta.query_advice(column, Rotation::cur()));
+            let is_padding_prev =
+                meta.query_advice(is_paddings[NUM_BYTES_PER_WORD - 1], Rotation::prev());
+            let data_rlc_prev =
+                meta.query_advice(data_rlcs[NUM_BYTES_PER_WORD - 1], Rotation::prev());
+            let is_final = meta.query_advice(is_final, Rotation::cur());
+            let q_length = meta.query_fixed(q_length, Rotation::cur());
+
+            for idx in 0..NUM_BYTES_PER_WORD {
+                let (is_padding_before, data_rlc_before) = if idx == 0 {
+                    (is_padding_prev.clone(), data_rlc_prev.clone())
+                } else {
+                    (
+                        is_paddings_cur[idx - 1].clone(),
+                        data_rlcs_cur[idx - 1].clone(),
+                    )
+                };
+                let is_first_padding = is_paddings_cur[idx].clone() - is_padding_before.clone();
+                cb.require_boolean("padding is monotonic", is_first_padding.clone());
+                cb.condition(is_first_padding, |cb| {
+                    cb.require_equal(
+                        "first padding byte is 0x80",
+                        bytes[idx].clone(),
+                        0x80.expr(),
+                    );
+                });
+                // The length words of the final block are checked below.
+                cb.condition(
+                    is_padding_before * not::expr(q_length.clone() * is_final.clone()),
+                    |cb| {
+                        cb.require_zero("other padding bytes are zero", bytes[idx].clone());
+                    },
+                );
+                cb.require_equal(
+                    "data rlc only accumulates the message bytes",
+                    data_rlcs_cur[idx].clone(),
+                    select::expr(
+                        is_paddings_cur[idx].clone(),
+                        data_rlc_before.clone(),
+                        data_rlc_before * r.clone() + bytes[idx].clone(),
+                    ),
+                );
+            }
+            let length_cur = meta.query_advice(length, Rotation::cur());
+            cb.require_equal(
+                "length only counts the message bytes",
+                length_cur.clone(),
+                meta.query_advice(length, Rotation::prev())
+                    + sum::expr(
+                        is_paddings_cur
+                            .iter()
+                            .map(|is_padding| not::expr(is_padding.clone())),
+                    ),
+            );
+
+            let q_input_last = meta.query_fixed(q_input_last, Rotation::cur());
+            let is_padding_length_start = meta.query_advice(is_paddings[0], Rotation::prev());
+            let w_length_hi = decode::expr(&query_bits(meta, &w, -1));
+            cb.condition(q_input_last * is_final.clone(), |cb| {
+                cb.require_equal(
+                    "the length words of the final block are padding",
+                    is_padding_length_start,
+                    1.expr(),
+                );
+                cb.require_equal(
+                    "the length words hold the message length in bits",
+                    w_length_hi * (1u64 << NUM_BITS_PER_WORD).expr() + decode::expr(&w_bits),
+                    length_cur * NUM_BITS_PER_BYTE.expr(),
+                );
+            });
+
+            // A final block only made of padding bytes is only allowed when the
+            // length words didn't fit in the previous block, i.e. when the byte
+            // before the length words is not padding.
+            let q_input_first = meta.query_fixed(q_input_first, Rotation::cur());
+            let is_padding_before_length = meta.query_advice(
+                is_paddings[NUM_BYTES_PER_WORD - 1],
+                Rotation(
+                    -((NUM_ROWS_PER_BLOCK
+                        - (NUM_WORDS_TO_ABSORB - NUM_BYTES_LENGTH / NUM_BYTES_PER_WORD - 1))
+                        as i32),
+                ),
+            );
+            cb.condition(q_input_first * is_final, |cb| {
+                cb.require_zero(
+                    "no extra padding block",
+                    is_padding_prev * is_padding_before_length,
+                );
+            });
+            cb.gate(meta.query_fixed(q_input, Rotation::cur()))
+        });
+
+        // The message data is carried over on the other rows, and reset when a new
+        // hash starts.
+        meta.create_gate("carry over data", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let start_new_hash = meta.query_fixed(q_block_start, Rotation::cur())
+                * meta.query_advice(is_first_block, Rotation::cur());
+            for column in [
+                is_paddings[NUM_BYTES_PER_WORD - 1],
+                data_rlcs[NUM_BYTES_PER_WORD - 1],
+                length,
+            ] {
+                cb.require_equal(
+                    "data is carried over",
+                    meta.query_advice(column, Rotation::cur()),
+                    not::expr(start_new_hash.clone()) * meta.query_advice(column, Rotation::prev()),
+                );
+            }
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    - meta.query_fixed(q_input, Rotation::cur()),
+            )
+        });
+
+        // The digest is the state after the final block of the hash, `a` and `e`
+        // hold H0..H3 and H4..H7 on the last 4 rows.
+        meta.create_gate("output", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let is_enabled = meta.query_advice(is_enabled, Rotation::cur());
+            cb.require_equal(
+                "the table is enabled on the last row of a final block",
+                is_enabled.clone(),
+                meta.query_advice(is_final, Rotation::cur()),
+            );
+            let mut digest_bytes = Vec::new();
+            for columns in [a, e] {
+                for rot in 0..NUM_END_ROWS {
+                    let bits = query_bits(meta, &columns, -(rot as i32));
+                    for idx in 0..NUM_BYTES_PER_WORD {
+                        let start = (NUM_BYTES_PER_WORD - 1 - idx) * NUM_BITS_PER_BYTE;
+                        digest_bytes.push(decode::expr(&bits[start..start + NUM_BITS_PER_BYTE]));
+                    }
+                }
+            }
+            digest_bytes.reverse();
+            let output_rlc = meta.query_advice(output_rlc, Rotation::cur());
+            cb.condition(is_enabled, |cb| {
+                cb.require_equal(
+                    "output rlc",
+                    output_rlc,
+                    rlc::expr(&digest_bytes, r.clone()),
+                );
+            });
+            cb.gate(meta.query_fixed(q_block_end, Rotation::cur()))
+        });
+
+        meta.create_gate("table", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            cb.require_zero(
+                "the table is only enabled on the last row of a block",
+                meta.query_advice(is_enabled, Rotation::cur())
+                    * not::expr(meta.query_fixed(q_block_end, Rotation::cur())),
+            );
+            cb.gate(1.expr())
+        });
+
+        Sha256CircuitConfig {
+            q_enable,
+            q_first,
+            q_block_start,
+            q_start,
+            q_round,
+            q_input,
+            q_input_first,
+            q_input_last,
+            q_length,
+            q_extend,
+            q_end,
+            q_block_end,
+            round_cst,
+            iv_a,
+            iv_e,
+            w,
+            a,
+            e,
+            carry_w,
+            carry_a,
+            carry_e,
+            is_final,
+            is_first_block,
+            is_paddings,
+            data_rlcs,
+            sha256_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> Sha256CircuitConfig<F> {
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        witness: &[Sha256Row<F>],
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "assign sha256 rows",
+            |mut region| {
+                for (offset, sha256_row) in witness.iter().enumerate() {
+                    self.set_row(&mut region, offset, sha256_row)?;
+                }
+                self.sha256_table.annotate_columns_in_region(&mut region);
+                self.annotate_circuit(&mut region);
+                Ok(())
+            },
+        )
+    }
+
+    fn set_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        row: &Sha256Row<F>,
+    ) -> Result<(), Error> {
+        let selectors = Sha256Selectors::new(offset);
+
+        // Fixed selectors
+        for (name, column, value) in [
+            ("q_enable", self.q_enable, F::ONE),
+            ("q_first", self.q_first, F::from((offset == 0) as u64)),
+            (
+                "q_block_start",
+                self.q_block_start,
+                F::from(selectors.q_block_start as u64),
+            ),
+            ("q_start", self.q_start, F::from(selectors.q_start as u64)),
+            ("q_round", self.q_round, F::from(selectors.q_round as u64)),
+            ("q_input", self.q_input, F::from(selectors.q_input as u64)),
+            (
+                "q_input_first",
+                self.q_input_first,
+                F::from(selectors.q_input_first as u64),
+            ),
+            (
+                "q_input_last",
+                self.q_input_last,
+                F::from(selectors.q_input_last as u64),
+            ),
+            (
+                "q_length",
+                self.q_length,
+                F::from(selectors.q_length as u64),
+            ),
+            (
+                "q_extend",
+                self.q_extend,
+                F::from(selectors.q_extend as u64),
+            ),
+            ("q_end", self.q_end, F::from(selectors.q_end as u64)),
+            (
+                "q_block_end",
+                self.q_block_end,
+                F::from(selectors.q_block_end as u64),
+            ),
+            (
+                "round_cst",
+                self.round_cst,
+                F::from(selectors.round_cst as u64),
+            ),
+            ("iv_a", self.iv_a, F::from(selectors.iv_a as u64)),
+            ("iv_e", self.iv_e, F::from(selectors.iv_e as u64)),
+        ] {
+            region.assign_fixed(
+                || format!("assign {} {}", name, offset),
+                column,
+                offset,
+                || Value::known(value),
+            )?;
+        }
+
+        self.sha256_table.assign_row(
+            region,
+            offset,
+            [
+                Value::known(F::from(row.is_enabled as u64)),
+                row.data_rlcs[NUM_BYTES_PER_WORD - 1],
+                Value::known(F::from(row.length as u64)),
+                row.output_rlc,
+            ],
+        )?;
+
+        // Words
+        for (name, columns, bits) in [
+            ("w", &self.w, &row.w),
+            ("a", &self.a, &row.a),
+            ("e", &self.e, &row.e),
+        ] {
+            for (idx, (column, bit)) in columns.iter().zip(bits.iter()).enumerate() {
+                region.assign_advice(
+                    || format!("assign {} bit {} {}", name, idx, offset),
+                    *column,
+                    offset,
+                    || Value::known(F::from(*bit as u64)),
+                )?;
+            }
+        }
+
+        // Carries
+        for (name, columns, carry) in [
+            ("carry_w", &self.carry_w, row.carry_w),
+            ("carry_a", &self.carry_a, row.carry_a),
+            ("carry_e", &self.carry_e, row.carry_e),
+        ] {
+            for (idx, column) in columns.iter().enumerate() {
+                region.assign_advice(
+                    || format!("assign {} bit {} {}", name, idx, offset),
+                    *column,
+                    offset,
+                    || Value::known(F::from((carry >> idx) & 1)),
+                )?;
+            }
+        }
+
+        // Message data
+        for (name, column, value) in [
+            ("is_final", self.is_final, row.is_final),
+            ("is_first_block", self.is_first_block, row.is_first_block),
+        ]
+        .into_iter()
+        .chain(
+            self.is_paddings
+                .iter()
+                .zip(row.is_paddings.iter())
+                .map(|(column, value)| ("is_padding", *column, *value)),
+        ) {
+            region.assign_advice(
+                || format!("assign {} {}", name, offset),
+                column,
+                offset,
+                || Value::known(F::from(value as u64)),
+            )?;
+        }
+        for (column, value) in self
+            .data_rlcs
+            .iter()
+            .zip(row.data_rlcs.iter())
+            .take(NUM_BYTES_PER_WORD - 1)
+        {
+            region.assign_advice(
+                || format!("assign data_rlc {}", offset),
+                *column,
+                offset,
+                || *value,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn annotate_circuit(&self, region: &mut Region<F>) {
+        region.name_column(|| "SHA256_q_enable", self.q_enable);
+        region.name_column(|| "SHA256_q_first", self.q_first);
+        region.name_column(|| "SHA256_q_block_start", self.q_block_start);
+        region.name_column(|| "SHA256_q_start", self.q_start);
+        region.name_column(|| "SHA256_q_round", self.q_round);
+        region.name_column(|| "SHA256_q_input", self.q_input);
+        region.name_column(|| "SHA256_q_input_first", self.q_input_first);
+        region.name_column(|| "SHA256_q_input_last", self.q_input_last);
+        region.name_column(|| "SHA256_q_length", self.q_length);
+        region.name_column(|| "SHA256_q_extend", self.q_extend);
+        region.name_column(|| "SHA256_q_end", self.q_end);
+        region.name_column(|| "SHA256_q_block_end", self.q_block_end);
+        region.name_column(|| "SHA256_is_final", self.is_final);
+        region.name_column(|| "SHA256_is_first_block", self.is_first_block);
+    }
+}
+
+/// Sha256Circuit
+#[derive(Default, Clone, Debug)]
+pub struct Sha256Circuit<F: Field> {
+    inputs: Vec<Vec<u8>>,
+    num_rows: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> SubCircuit<F> for Sha256Circuit<F> {
+    type Config = Sha256CircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // Columns of the words `w` and `a` are queried at 6 distinct rotations
+        // - `w`: Rotation(0), Rotation(-1), Rotation(-2), Rotation(-7), Rotation(-15),
+        //   Rotation(-16)
+        // - `a`: Rotation(0), Rotation(-1), Rotation(-2), Rotation(-3), Rotation(-4), Rotation(-68)
+        // so returns 9 unusable rows.
+        9
+    }
+
+    /// The `block.circuits_params.max_sha256_rows` parameter, when set, sets
+    /// up the circuit to support a fixed number of blocks, independently of
+    /// the blocks required by the sha256 precompile calls of the block.
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        Self::new(
+            block.circuits_params.max_sha256_rows,
+            block
+                .precompile_events
+                .get_sha256_events()
+                .into_iter()
+                .map(|event| event.input)
+                .collect(),
+        )
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        (
+            block
+                .precompile_events
+                .get_sha256_events()
+                .iter()
+                .map(|event| get_num_sha256_blocks(event.input.len()) * NUM_ROWS_PER_BLOCK)
+                .sum(),
+            block.circuits_params.max_sha256_rows,
+        )
+    }
+
+    /// Make the assignments to the Sha256Circuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let witness = self.generate_witness(*challenges);
+        config.assign(layouter, witness.as_slice())
+    }
+}
+
+impl<F: Field> Sha256Circuit<F> {
+    /// Creates a new circuit instance
+    pub fn new(num_rows: usize, inputs: Vec<Vec<u8>>) -> Self {
+        Sha256Circuit {
+            inputs,
+            num_rows,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The number of sha256 blocks that can be compressed in this circuit
+    pub fn capacity(&self) -> Option<usize> {
+        if self.num_rows > 0 {
+            Some((self.num_rows - Self::unusable_rows()) / NUM_ROWS_PER_BLOCK)
+        } else {
+            None
+        }
+    }
+
+    /// Sets the witness using the data to be hashed
+    pub(crate) fn generate_witness(&self, challenges: Challenges<Value<F>>) -> Vec<Sha256Row<F>> {
+        multi_sha256(self.inputs.as_slice(), challenges, self.capacity())
+            .expect("Too many inputs for given capacity")
+    }
+}
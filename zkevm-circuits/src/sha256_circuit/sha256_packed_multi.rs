@@ -0,0 +1,263 @@
+use super::{param::*, util::*};
+use crate::{evm_circuit::util::rlc, util::Challenges};
+use eth_types::Field;
+use halo2_proofs::{circuit::Value, plonk::Error};
+use log::debug;
+
+/// Sha256Row
+#[derive(Clone, Debug)]
+pub(crate) struct Sha256Row<F: Field> {
+    pub(crate) w: [bool; NUM_BITS_PER_WORD],
+    pub(crate) a: [bool; NUM_BITS_PER_WORD],
+    pub(crate) e: [bool; NUM_BITS_PER_WORD],
+    pub(crate) carry_w: u64,
+    pub(crate) carry_a: u64,
+    pub(crate) carry_e: u64,
+    pub(crate) is_final: bool,
+    pub(crate) is_first_block: bool,
+    pub(crate) is_paddings: [bool; NUM_BYTES_PER_WORD],
+    pub(crate) data_rlcs: [Value<F>; NUM_BYTES_PER_WORD],
+    pub(crate) length: usize,
+    pub(crate) is_enabled: bool,
+    pub(crate) output_rlc: Value<F>,
+}
+
+/// The selectors of a row, which only depend on the position of the row in
+/// its block.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Sha256Selectors {
+    pub(crate) q_block_start: bool,
+    pub(crate) q_start: bool,
+    pub(crate) q_round: bool,
+    pub(crate) q_input: bool,
+    pub(crate) q_input_first: bool,
+    pub(crate) q_input_last: bool,
+    pub(crate) q_length: bool,
+    pub(crate) q_extend: bool,
+    pub(crate) q_end: bool,
+    pub(crate) q_block_end: bool,
+    pub(crate) round_cst: u32,
+    pub(crate) iv_a: u32,
+    pub(crate) iv_e: u32,
+}
+
+impl Sha256Selectors {
+    /// Returns the selectors of the row at `offset` in the circuit.
+    pub(crate) fn new(offset: usize) -> Self {
+        let idx = offset % NUM_ROWS_PER_BLOCK;
+        if idx < NUM_START_ROWS {
+            Self {
+                q_block_start: idx == 0,
+                q_start: true,
+                iv_a: IV[NUM_START_ROWS - 1 - idx],
+                iv_e: IV[2 * NUM_START_ROWS - 1 - idx],
+                ..Default::default()
+            }
+        } else if idx < NUM_START_ROWS + NUM_ROUNDS {
+            let round = idx - NUM_START_ROWS;
+            Self {
+                q_round: true,
+                q_input: round < NUM_WORDS_TO_ABSORB,
+                q_input_first: round == 0,
+                q_input_last: round == NUM_WORDS_TO_ABSORB - 1,
+                q_length: (NUM_WORDS_TO_ABSORB - NUM_BYTES_LENGTH / NUM_BYTES_PER_WORD
+                    ..NUM_WORDS_TO_ABSORB)
+                    .contains(&round),
+                q_extend: round >= NUM_WORDS_TO_ABSORB,
+                round_cst: ROUND_CST[round],
+                ..Default::default()
+            }
+        } else {
+            Self {
+                q_end: true,
+                q_block_end: idx == NUM_ROWS_PER_BLOCK - 1,
+                ..Default::default()
+            }
+        }
+    }
+}
+
+/// Returns the bytes of the message padded to a multiple of the block size.
+fn pad(bytes: &[u8]) -> Vec<u8> {
+    let mut padded = bytes.to_vec();
+    padded.push(0x80);
+    while padded.len() % RATE != RATE - NUM_BYTES_LENGTH {
+        padded.push(0);
+    }
+    padded.extend_from_slice(&(bytes.len() as u64 * 8).to_be_bytes());
+    padded
+}
+
+fn sha256<F: Field>(rows: &mut Vec<Sha256Row<F>>, bytes: &[u8], challenges: Challenges<Value<F>>) {
+    let mut hs = IV;
+    let mut length = 0;
+    let mut data_rlc = Value::known(F::ZERO);
+
+    let padded = pad(bytes);
+    let num_blocks = padded.len() / RATE;
+    for (idx, block) in padded.chunks(RATE).enumerate() {
+        let is_first_block = idx == 0;
+        let is_final = idx == num_blocks - 1;
+        let mut is_padding = idx * RATE > bytes.len();
+        let new_row = |w: u32,
+                       a: u32,
+                       e: u32,
+                       carries: [u64; 3],
+                       is_paddings: [bool; NUM_BYTES_PER_WORD],
+                       data_rlcs: [Value<F>; NUM_BYTES_PER_WORD],
+                       length: usize| Sha256Row {
+            w: to_bits(w),
+            a: to_bits(a),
+            e: to_bits(e),
+            carry_w: carries[0],
+            carry_a: carries[1],
+            carry_e: carries[2],
+            is_final,
+            is_first_block,
+            is_paddings,
+            data_rlcs,
+            length,
+            is_enabled: false,
+            output_rlc: Value::known(F::ZERO),
+        };
+
+        // Load the state, the last word first so that the rounds can find
+        // `a, b, c, d` and `e, f, g, h` on the previous rows.
+        for i in 0..NUM_START_ROWS {
+            rows.push(new_row(
+                0,
+                hs[NUM_START_ROWS - 1 - i],
+                hs[2 * NUM_START_ROWS - 1 - i],
+                [0; 3],
+                [is_padding; NUM_BYTES_PER_WORD],
+                [data_rlc; NUM_BYTES_PER_WORD],
+                length,
+            ));
+        }
+
+        // Rounds
+        let mut ws = [0u32; NUM_ROUNDS];
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = hs;
+        for round in 0..NUM_ROUNDS {
+            let mut is_paddings = [is_padding; NUM_BYTES_PER_WORD];
+            let mut data_rlcs = [data_rlc; NUM_BYTES_PER_WORD];
+            let carry_w = if round < NUM_WORDS_TO_ABSORB {
+                let word = &block[round * NUM_BYTES_PER_WORD..(round + 1) * NUM_BYTES_PER_WORD];
+                ws[round] = u32::from_be_bytes(word.try_into().unwrap());
+                for (i, byte) in word.iter().enumerate() {
+                    is_padding = idx * RATE + round * NUM_BYTES_PER_WORD + i >= bytes.len();
+                    if !is_padding {
+                        length += 1;
+                        data_rlc = data_rlc
+                            .zip(challenges.keccak_input())
+                            .map(|(data_rlc, r)| data_rlc * r + F::from(*byte as u64));
+                    }
+                    is_paddings[i] = is_padding;
+                    data_rlcs[i] = data_rlc;
+                }
+                0
+            } else {
+                let w = small_sigma1::value(ws[round - 2]) as u64
+                    + ws[round - 7] as u64
+                    + small_sigma0::value(ws[round - 15]) as u64
+                    + ws[round - 16] as u64;
+                ws[round] = w as u32;
+                w >> NUM_BITS_PER_WORD
+            };
+
+            let t1 = h as u64
+                + big_sigma1::value(e) as u64
+                + ch::value(e, f, g) as u64
+                + ROUND_CST[round] as u64
+                + ws[round] as u64;
+            let t2 = big_sigma0::value(a) as u64 + maj::value(a, b, c) as u64;
+            let new_e = d as u64 + t1;
+            let new_a = t1 + t2;
+            rows.push(new_row(
+                ws[round],
+                new_a as u32,
+                new_e as u32,
+                [
+                    carry_w,
+                    new_a >> NUM_BITS_PER_WORD,
+                    new_e >> NUM_BITS_PER_WORD,
+                ],
+                is_paddings,
+                data_rlcs,
+                length,
+            ));
+
+            (h, g, f, e) = (g, f, e, new_e as u32);
+            (d, c, b, a) = (c, b, a, new_a as u32);
+        }
+
+        // Add the compressed state to the state of the previous block
+        let compressed = [a, b, c, d, e, f, g, h];
+        for i in 0..NUM_END_ROWS {
+            let [idx_a, idx_e] = [NUM_END_ROWS - 1 - i, 2 * NUM_END_ROWS - 1 - i];
+            let new_a = hs[idx_a] as u64 + compressed[idx_a] as u64;
+            let new_e = hs[idx_e] as u64 + compressed[idx_e] as u64;
+            rows.push(new_row(
+                0,
+                new_a as u32,
+                new_e as u32,
+                [0, new_a >> NUM_BITS_PER_WORD, new_e >> NUM_BITS_PER_WORD],
+                [is_padding; NUM_BYTES_PER_WORD],
+                [data_rlc; NUM_BYTES_PER_WORD],
+                length,
+            ));
+        }
+        for (h, compressed) in hs.iter_mut().zip(compressed) {
+            *h = h.wrapping_add(compressed);
+        }
+
+        if is_final {
+            let digest = hs.iter().flat_map(|h| h.to_be_bytes()).collect::<Vec<_>>();
+            let last_row = rows.last_mut().unwrap();
+            last_row.is_enabled = true;
+            last_row.output_rlc = challenges
+                .keccak_input()
+                .map(|r| rlc::value(digest.iter().rev(), r));
+            debug!("sha256 digest: {}", hex::encode(digest));
+        }
+    }
+}
+
+/// Witness generation for multiple sha256 hashes.  The rows of `capacity`
+/// blocks are returned when it's set, padding with the hashes of empty inputs.
+pub(crate) fn multi_sha256<F: Field>(
+    bytes: &[Vec<u8>],
+    challenges: Challenges<Value<F>>,
+    capacity: Option<usize>,
+) -> Result<Vec<Sha256Row<F>>, Error> {
+    let mut rows: Vec<Sha256Row<F>> = Vec::new();
+    for bytes in bytes {
+        sha256(&mut rows, bytes, challenges);
+    }
+    if let Some(capacity) = capacity {
+        let padding_rows = {
+            let mut rows = Vec::new();
+            sha256(&mut rows, &[], challenges);
+            rows
+        };
+        // Pad with no data hashes to the expected capacity
+        while rows.len() < capacity * NUM_ROWS_PER_BLOCK {
+            rows.extend(padding_rows.clone());
+        }
+        // Check that we are not over capacity
+        if rows.len() > capacity * NUM_ROWS_PER_BLOCK {
+            log::error!(
+                "Sha256 inputs exceed capacity.  needed_rows = {}, available_rows = {}",
+                rows.len(),
+                capacity * NUM_ROWS_PER_BLOCK
+            );
+            return Err(Error::BoundsFailure);
+        }
+    }
+    Ok(rows)
+}
+
+/// Returns the number of blocks needed to hash the input.
+pub(crate) fn get_num_sha256_blocks(input_len: usize) -> usize {
+    (input_len + 1 + NUM_BYTES_LENGTH + RATE - 1) / RATE
+}
@@ -0,0 +1,132 @@
+use super::*;
+use crate::util::unusable_rows;
+use halo2_proofs::{
+    dev::{CellValue, MockProver},
+    halo2curves::bn256::Fr,
+    plonk::Circuit,
+};
+use itertools::izip;
+use log::error;
+
+#[test]
+fn sha256_circuit_unusable_rows() {
+    assert_eq!(
+        Sha256Circuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, Sha256Circuit::<Fr>>(()),
+    )
+}
+
+const EMPTY_DIGEST: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+fn verify<F: Field>(k: u32, inputs: Vec<Vec<u8>>, digests: Vec<&str>, success: bool) {
+    let circuit = Sha256Circuit::new(2usize.pow(k), inputs.clone());
+    let prover = MockProver::<F>::run(k, &circuit, vec![]).unwrap();
+    let (config, challenges) = Sha256Circuit::configure(&mut ConstraintSystem::<F>::default());
+    let input_challenge = prover.get_challenge(challenges.keccak_input());
+
+    // Check constraints.
+    let verify_result = prover.verify();
+    if verify_result.is_ok() != success {
+        if let Some(errors) = verify_result.err() {
+            for error in errors.iter() {
+                error!("{}", error);
+            }
+        }
+        panic!();
+    }
+
+    // Extract the content of the lookup table.
+    let hash_lookup_table = {
+        let is_enabled = prover.advice_values(config.sha256_table.is_enabled);
+        let input_rlc = prover.advice_values(config.sha256_table.input_rlc);
+        let input_len = prover.advice_values(config.sha256_table.input_len);
+        let output_rlc = prover.advice_values(config.sha256_table.output_rlc);
+
+        // Keep the rows that are supposed to contain hash results.
+        izip!(is_enabled, input_rlc, input_len, output_rlc)
+            .filter_map(|(enabled, input_rlc, input_len, output_rlc)| {
+                assigned_non_zero(enabled)
+                    .then(|| (unwrap(input_rlc), unwrap(input_len), unwrap(output_rlc)))
+            })
+            .collect::<Vec<(F, F, F)>>()
+    };
+
+    let rlc = |bytes: &[u8]| rlc::value(bytes.iter().rev(), input_challenge);
+
+    // Check that all the digests are there.
+    assert!(hash_lookup_table.len() >= inputs.len());
+    assert_eq!(inputs.len(), digests.len());
+    for (input, digest, hash) in izip!(&inputs, &digests, &hash_lookup_table) {
+        let expected = (
+            rlc(input),
+            F::from(input.len() as u64),
+            rlc(&hex::decode(digest).unwrap()),
+        );
+        assert_eq!(*hash, expected);
+    }
+
+    // Check that other digests are the digest of the empty message.
+    let empty_hash = (F::ZERO, F::ZERO, rlc(&hex::decode(EMPTY_DIGEST).unwrap()));
+    for hash in hash_lookup_table.iter().skip(inputs.len()) {
+        assert_eq!(*hash, empty_hash);
+    }
+}
+
+fn assigned_non_zero<F: Field>(cv: &CellValue<F>) -> bool {
+    match *cv {
+        CellValue::Assigned(v) => !v.is_zero_vartime(),
+        _ => false,
+    }
+}
+
+fn unwrap<F: Field>(cv: &CellValue<F>) -> F {
+    match *cv {
+        CellValue::Assigned(f) => f,
+        _ => panic!("the cell should be assigned"),
+    }
+}
+
+#[test]
+fn packed_multi_sha256_simple() {
+    let k = 12;
+    let inputs = vec![
+        vec![],
+        b"abc".to_vec(),
+        (0u8..55).collect::<Vec<_>>(),
+        (0u8..56).collect::<Vec<_>>(),
+        (0u8..64).collect::<Vec<_>>(),
+        b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq".to_vec(),
+    ];
+    let digests = vec![
+        EMPTY_DIGEST,
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+        "463eb28e72f82e0a96c0a4cc53690c571281131f672aa229e0d45ae59b598b59",
+        "da2ae4d6b36748f2a318f23e7ab1dfdf45acdc9d049bd80e59de82a60895f562",
+        "fdeab9acf3710362bd2658cdc9a29e8f9c757fcf9811603a8c447cd1d9151108",
+        "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+    ];
+    verify::<Fr>(k, inputs, digests, true);
+}
+
+#[test]
+fn variadic_size_check() {
+    let k = 12;
+    let num_rows = 2usize.pow(k);
+    // Empty
+    let inputs = vec![];
+    let circuit = Sha256Circuit::new(num_rows, inputs);
+    let prover1 = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
+
+    // Non-empty
+    let inputs = vec![
+        vec![],
+        (0u8..1).collect::<Vec<_>>(),
+        (0u8..55).collect::<Vec<_>>(),
+        (0u8..200).collect::<Vec<_>>(),
+    ];
+    let circuit = Sha256Circuit::new(num_rows, inputs);
+    let prover2 = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
+
+    assert_eq!(prover1.fixed(), prover2.fixed());
+    assert_eq!(prover1.permutation(), prover2.permutation());
+}
@@ -0,0 +1,152 @@
+use super::param::*;
+use eth_types::Field;
+use gadgets::util::Expr;
+use halo2_proofs::plonk::Expression;
+use std::array;
+
+/// A 32-bit word decomposed in bits, least significant bit first.
+pub(crate) type Bits<F> = [Expression<F>; NUM_BITS_PER_WORD];
+
+/// Decomposes a word in bits, least significant bit first.
+pub(crate) fn to_bits(value: u32) -> [bool; NUM_BITS_PER_WORD] {
+    array::from_fn(|i| (value >> i) & 1 == 1)
+}
+
+/// Rotates the bits of a word to the right.
+pub(crate) fn rotate_right<F: Field>(bits: &Bits<F>, n: usize) -> Bits<F> {
+    array::from_fn(|i| bits[(i + n) % NUM_BITS_PER_WORD].clone())
+}
+
+/// Shifts the bits of a word to the right.
+pub(crate) fn shift_right<F: Field>(bits: &Bits<F>, n: usize) -> Bits<F> {
+    array::from_fn(|i| {
+        if i + n < NUM_BITS_PER_WORD {
+            bits[i + n].clone()
+        } else {
+            0.expr()
+        }
+    })
+}
+
+/// Bitwise xor of three words.
+pub(crate) fn xor3<F: Field>(x: &Bits<F>, y: &Bits<F>, z: &Bits<F>) -> Bits<F> {
+    array::from_fn(|i| {
+        let (x, y, z) = (x[i].clone(), y[i].clone(), z[i].clone());
+        x.clone() + y.clone() + z.clone()
+            - 2.expr() * (x.clone() * y.clone() + x.clone() * z.clone() + y.clone() * z.clone())
+            + 4.expr() * x * y * z
+    })
+}
+
+/// Recombines bits into the word value
+pub(crate) mod decode {
+    use super::*;
+
+    pub(crate) fn expr<F: Field>(bits: &[Expression<F>]) -> Expression<F> {
+        bits.iter()
+            .rev()
+            .fold(0.expr(), |acc, bit| acc * 2.expr() + bit.clone())
+    }
+}
+
+/// `Σ0(a) = ROTR^2(a) ^ ROTR^13(a) ^ ROTR^22(a)`
+pub(crate) mod big_sigma0 {
+    use super::*;
+
+    pub(crate) fn expr<F: Field>(a: &Bits<F>) -> Expression<F> {
+        let [r0, r1, r2] = SIGMA0_A.map(|n| rotate_right(a, n));
+        decode::expr(&xor3(&r0, &r1, &r2))
+    }
+
+    pub(crate) fn value(a: u32) -> u32 {
+        let [r0, r1, r2] = SIGMA0_A;
+        a.rotate_right(r0 as u32) ^ a.rotate_right(r1 as u32) ^ a.rotate_right(r2 as u32)
+    }
+}
+
+/// `Σ1(e) = ROTR^6(e) ^ ROTR^11(e) ^ ROTR^25(e)`
+pub(crate) mod big_sigma1 {
+    use super::*;
+
+    pub(crate) fn expr<F: Field>(e: &Bits<F>) -> Expression<F> {
+        let [r0, r1, r2] = SIGMA1_E.map(|n| rotate_right(e, n));
+        decode::expr(&xor3(&r0, &r1, &r2))
+    }
+
+    pub(crate) fn value(e: u32) -> u32 {
+        let [r0, r1, r2] = SIGMA1_E;
+        e.rotate_right(r0 as u32) ^ e.rotate_right(r1 as u32) ^ e.rotate_right(r2 as u32)
+    }
+}
+
+/// `σ0(w) = ROTR^7(w) ^ ROTR^18(w) ^ SHR^3(w)`
+pub(crate) mod small_sigma0 {
+    use super::*;
+
+    pub(crate) fn expr<F: Field>(w: &Bits<F>) -> Expression<F> {
+        let [r0, r1, s] = SIGMA0_W;
+        decode::expr(&xor3(
+            &rotate_right(w, r0),
+            &rotate_right(w, r1),
+            &shift_right(w, s),
+        ))
+    }
+
+    pub(crate) fn value(w: u32) -> u32 {
+        let [r0, r1, s] = SIGMA0_W;
+        w.rotate_right(r0 as u32) ^ w.rotate_right(r1 as u32) ^ (w >> s)
+    }
+}
+
+/// `σ1(w) = ROTR^17(w) ^ ROTR^19(w) ^ SHR^10(w)`
+pub(crate) mod small_sigma1 {
+    use super::*;
+
+    pub(crate) fn expr<F: Field>(w: &Bits<F>) -> Expression<F> {
+        let [r0, r1, s] = SIGMA1_W;
+        decode::expr(&xor3(
+            &rotate_right(w, r0),
+            &rotate_right(w, r1),
+            &shift_right(w, s),
+        ))
+    }
+
+    pub(crate) fn value(w: u32) -> u32 {
+        let [r0, r1, s] = SIGMA1_W;
+        w.rotate_right(r0 as u32) ^ w.rotate_right(r1 as u32) ^ (w >> s)
+    }
+}
+
+/// `Ch(e, f, g) = (e & f) ^ (!e & g)`
+pub(crate) mod ch {
+    use super::*;
+
+    pub(crate) fn expr<F: Field>(e: &Bits<F>, f: &Bits<F>, g: &Bits<F>) -> Expression<F> {
+        let bits: Bits<F> = array::from_fn(|i| {
+            e[i].clone() * f[i].clone() + (1.expr() - e[i].clone()) * g[i].clone()
+        });
+        decode::expr(&bits)
+    }
+
+    pub(crate) fn value(e: u32, f: u32, g: u32) -> u32 {
+        (e & f) ^ (!e & g)
+    }
+}
+
+/// `Maj(a, b, c) = (a & b) ^ (a & c) ^ (b & c)`
+pub(crate) mod maj {
+    use super::*;
+
+    pub(crate) fn expr<F: Field>(a: &Bits<F>, b: &Bits<F>, c: &Bits<F>) -> Expression<F> {
+        let bits: Bits<F> = array::from_fn(|i| {
+            let (a, b, c) = (a[i].clone(), b[i].clone(), c[i].clone());
+            a.clone() * b.clone() + a.clone() * c.clone() + b.clone() * c.clone()
+                - 2.expr() * a * b * c
+        });
+        decode::expr(&bits)
+    }
+
+    pub(crate) fn value(a: u32, b: u32, c: u32) -> u32 {
+        (a & b) ^ (a & c) ^ (b & c)
+    }
+}
@@ -29,10 +29,10 @@ use gadgets::{
     binary_number::{BinaryNumberChip, BinaryNumberConfig},
 };
 use halo2_proofs::{
-    circuit::{Layouter, Region, Value},
+    circuit::{AssignedCell, Layouter, Region, Value},
     plonk::{
-        Advice, Column, ConstraintSystem, Error, Expression, FirstPhase, Fixed, SecondPhase,
-        VirtualCells,
+        Advice, Column, ConstraintSystem, Error, Expression, FirstPhase, Fixed, Instance,
+        SecondPhase, VirtualCells,
     },
     poly::Rotation,
 };
@@ -64,6 +64,8 @@ pub struct StateCircuitConfig<F> {
     // Intermediary witness used to reduce mpt lookup expression degree
     mpt_proof_type: Column<Advice>,
     state_root: word::Word<Column<Advice>>,
+    // The state roots before and after the block, proven by the MPT lookups
+    state_root_instance: Column<Instance>,
     lexicographic_ordering: LexicographicOrderingConfig,
     not_first_access: Column<Advice>,
     lookups: LookupsConfig,
@@ -135,6 +137,10 @@ impl<F: Field> SubCircuitConfig<F> for StateCircuitConfig<F> {
         );
         let mpt_proof_type = meta.advice_column_in(SecondPhase);
         let state_root = word::Word::new([meta.advice_column(), meta.advice_column()]);
+        let state_root_instance = meta.instance_column();
+        meta.enable_equality(state_root.lo());
+        meta.enable_equality(state_root.hi());
+        meta.enable_equality(state_root_instance);
 
         let sort_keys = SortKeysConfig {
             tag,
@@ -163,6 +169,7 @@ impl<F: Field> SubCircuitConfig<F> for StateCircuitConfig<F> {
             is_non_exist,
             mpt_proof_type,
             state_root,
+            state_root_instance,
             lexicographic_ordering,
             not_first_access: meta.advice_column(),
             lookups,
@@ -201,23 +208,29 @@ impl<F: Field> StateCircuitConfig<F> {
         let updates = MptUpdates::mock_from(rows);
         layouter.assign_region(
             || "state circuit",
-            |mut region| self.assign_with_region(&mut region, rows, &updates, n_rows),
+            |mut region| {
+                self.assign_with_region(&mut region, rows, &updates, n_rows)
+                    .map(|_| ())
+            },
         )
     }
 
+    /// Returns the state root cells of the first and last rows
     fn assign_with_region(
         &self,
         region: &mut Region<'_, F>,
         rows: &[Rw],
         updates: &MptUpdates,
         n_rows: usize, // 0 means dynamically calculated from `rows`.
-    ) -> Result<(), Error> {
+    ) -> Result<[word::Word<AssignedCell<F, F>>; 2], Error> {
         let tag_chip = BinaryNumberChip::construct(self.sort_keys.tag);
 
         let (rows, padding_length) = RwMap::table_assignments_prepad(rows, n_rows);
         let rows_len = rows.len();
 
         let mut state_root = updates.old_root();
+        let mut first_state_root = None;
+        let mut last_state_root = None;
 
         // annotate columns
         self.annotate_circuit_in_region(region);
@@ -353,9 +366,12 @@ impl<F: Field> StateCircuitConfig<F> {
             // State root assignment is at previous row (offset - 1) because the state root
             // changes on the last access row.
             if offset != 0 {
-                word::Word::<F>::from(state_root)
+                let state_root = word::Word::<F>::from(state_root)
                     .into_value()
                     .assign_advice(region, || "state root", self.state_root, offset - 1)?;
+                if offset == 1 {
+                    first_state_root = Some(state_root);
+                }
             }
 
             if offset == rows_len - 1 {
@@ -368,13 +384,17 @@ impl<F: Field> StateCircuitConfig<F> {
                         new_root
                     };
                 }
-                word::Word::<F>::from(state_root)
+                let state_root = word::Word::<F>::from(state_root)
                     .into_value()
                     .assign_advice(region, || "last row state_root", self.state_root, offset)?;
+                if offset == 0 {
+                    first_state_root = Some(state_root.clone());
+                }
+                last_state_root = Some(state_root);
             }
         }
 
-        Ok(())
+        Ok([first_state_root.unwrap(), last_state_root.unwrap()])
     }
 
     fn annotate_circuit_in_region(&self, region: &mut Region<F>) {
@@ -450,7 +470,14 @@ impl<F: Field> SubCircuit<F> for StateCircuit<F> {
     type Config = StateCircuitConfig<F>;
 
     fn new_from_block(block: &witness::Block<F>) -> Self {
-        Self::new(block.rws.clone(), block.circuits_params.max_rws)
+        Self {
+            rows: block.rws.table_assignments(),
+            updates: block.mpt_updates.clone(),
+            n_rows: block.circuits_params.max_rws,
+            #[cfg(test)]
+            overrides: HashMap::new(),
+            _marker: PhantomData::default(),
+        }
     }
 
     fn unusable_rows() -> usize {
@@ -479,14 +506,19 @@ impl<F: Field> SubCircuit<F> for StateCircuit<F> {
         // Assigning to same columns in different regions should be avoided.
         // Here we use one single region to assign `overrides` to both rw table and
         // other parts.
-        layouter.assign_region(
+        let state_roots = layouter.assign_region(
             || "state circuit",
             |mut region| {
                 config
                     .rw_table
                     .load_with_region(&mut region, &self.rows, self.n_rows)?;
 
-                config.assign_with_region(&mut region, &self.rows, &self.updates, self.n_rows)?;
+                let state_roots = config.assign_with_region(
+                    &mut region,
+                    &self.rows,
+                    &self.updates,
+                    self.n_rows,
+                )?;
                 #[cfg(test)]
                 {
                     let first_non_padding_index = if self.rows.len() < self.n_rows {
@@ -510,14 +542,31 @@ impl<F: Field> SubCircuit<F> for StateCircuit<F> {
                     }
                 }
 
-                Ok(())
+                Ok(state_roots)
             },
-        )
+        )?;
+
+        // Expose the state roots before and after the block
+        for (offset, cell) in state_roots
+            .iter()
+            .flat_map(|state_root| [state_root.lo(), state_root.hi()])
+            .enumerate()
+        {
+            layouter.constrain_instance(cell.cell(), config.state_root_instance, offset)?;
+        }
+        Ok(())
     }
 
-    /// powers of randomness for instance columns
+    /// The previous and the new state root, as lo/hi words
     fn instance(&self) -> Vec<Vec<F>> {
-        vec![]
+        let prev_state_root = word::Word::<F>::from(self.updates.old_root());
+        let state_root = word::Word::<F>::from(self.updates.new_root());
+        vec![vec![
+            prev_state_root.lo(),
+            prev_state_root.hi(),
+            state_root.lo(),
+            state_root.hi(),
+        ]]
     }
 }
 
@@ -526,7 +575,7 @@ fn queries<F: Field>(meta: &mut VirtualCells<'_, F>, c: &StateCircuitConfig<F>)
     let final_bits_sum = meta.query_advice(first_different_limb.bits[3], Rotation::cur())
         + meta.query_advice(first_different_limb.bits[4], Rotation::cur());
     let mpt_update_table_expressions = c.mpt_table.table_exprs(meta);
-    assert_eq!(mpt_update_table_expressions.len(), 12);
+    assert_eq!(mpt_update_table_expressions.len(), 13);
 
     let meta_query_word =
         |metap: &mut VirtualCells<'_, F>, word_column: word::Word<Column<Advice>>, at: Rotation| {
@@ -556,27 +605,28 @@ fn queries<F: Field>(meta: &mut VirtualCells<'_, F>, c: &StateCircuitConfig<F>)
         },
         // TODO: clean this up
         mpt_update_table: MptUpdateTableQueries {
-            address: mpt_update_table_expressions[0].clone(),
+            q_enable: mpt_update_table_expressions[0].clone(),
+            address: mpt_update_table_expressions[1].clone(),
             storage_key: word::Word::new([
-                mpt_update_table_expressions[1].clone(),
                 mpt_update_table_expressions[2].clone(),
+                mpt_update_table_expressions[3].clone(),
             ]),
-            proof_type: mpt_update_table_expressions[3].clone(),
+            proof_type: mpt_update_table_expressions[4].clone(),
             new_root: word::Word::new([
-                mpt_update_table_expressions[4].clone(),
                 mpt_update_table_expressions[5].clone(),
+                mpt_update_table_expressions[6].clone(),
             ]),
             old_root: word::Word::new([
-                mpt_update_table_expressions[6].clone(),
                 mpt_update_table_expressions[7].clone(),
+                mpt_update_table_expressions[8].clone(),
             ]),
             new_value: word::Word::new([
-                mpt_update_table_expressions[8].clone(),
                 mpt_update_table_expressions[9].clone(),
+                mpt_update_table_expressions[10].clone(),
             ]),
             old_value: word::Word::new([
-                mpt_update_table_expressions[10].clone(),
                 mpt_update_table_expressions[11].clone(),
+                mpt_update_table_expressions[12].clone(),
             ]),
         },
         lexicographic_ordering_selector: meta
@@ -31,6 +31,7 @@ pub struct RwTableQueries<F: Field> {
 
 #[derive(Clone)]
 pub struct MptUpdateTableQueries<F: Field> {
+    pub q_enable: Expression<F>,
     pub address: Expression<F>,
     pub storage_key: word::Word<Expression<F>>,
     pub proof_type: Expression<F>,
@@ -145,6 +146,12 @@ impl<F: Field> ConstraintBuilder<F> {
         self.condition(q.tag_matches(Target::TxLog), |cb| {
             cb.build_tx_log_constraints(q)
         });
+        self.condition(q.tag_matches(Target::TxCreatedAccount), |cb| {
+            cb.build_tx_created_account_constraints(q)
+        });
+        self.condition(q.tag_matches(Target::TransientStorage), |cb| {
+            cb.build_transient_storage_constraints(q)
+        });
     }
 
     fn build_general_constraints(&mut self, q: &Queries<F>) {
@@ -324,6 +331,7 @@ impl<F: Field> ConstraintBuilder<F> {
             cb.add_lookup(
                 "mpt_update exists in mpt circuit for AccountStorage last access",
                 LookupBuilder::new()
+                    .add(&1.expr(), &q.mpt_update_table.q_enable)
                     .add(&q.rw_table.address, &q.mpt_update_table.address)
                     .add_word(&q.rw_table.storage_key, &q.mpt_update_table.storage_key)
                     .add(&q.mpt_proof_type(), &q.mpt_update_table.proof_type)
@@ -371,6 +379,51 @@ impl<F: Field> ConstraintBuilder<F> {
         });
     }
 
+    fn build_tx_created_account_constraints(&mut self, q: &Queries<F>) {
+        self.require_zero("field_tag is 0 for TxCreatedAccount", q.field_tag());
+        self.require_word_zero(
+            "storage_key is 0 for TxCreatedAccount",
+            q.rw_table.storage_key.clone(),
+        );
+        self.require_word_boolean("TxCreatedAccount value is boolean", q.value());
+        self.require_word_zero("initial TxCreatedAccount value is false", q.initial_value());
+
+        self.require_word_equal(
+            "state_root is unchanged for TxCreatedAccount",
+            q.state_root(),
+            q.state_root_prev(),
+        );
+
+        self.condition(q.not_first_access.clone(), |cb| {
+            cb.require_word_equal(
+                "value column at Rotation::prev() equals value_prev at Rotation::cur()",
+                q.rw_table.value_prev.clone(),
+                q.value_prev_column(),
+            );
+        });
+    }
+
+    fn build_transient_storage_constraints(&mut self, q: &Queries<F>) {
+        self.require_zero("field_tag is 0 for TransientStorage", q.field_tag());
+        // Transient storage is discarded at the end of every transaction, so
+        // it's always 0 at the first access in a tx.
+        self.require_word_zero("initial TransientStorage value is 0", q.initial_value());
+
+        self.require_word_equal(
+            "state_root is unchanged for TransientStorage",
+            q.state_root(),
+            q.state_root_prev(),
+        );
+
+        self.condition(q.not_first_access.clone(), |cb| {
+            cb.require_word_equal(
+                "value column at Rotation::prev() equals value_prev at Rotation::cur()",
+                q.rw_table.value_prev.clone(),
+                q.value_prev_column(),
+            );
+        });
+    }
+
     fn build_tx_access_list_account_storage_constraints(&mut self, q: &Queries<F>) {
         self.require_zero(
             "field_tag is 0 for TxAccessListAccountStorage",
@@ -466,6 +519,7 @@ impl<F: Field> ConstraintBuilder<F> {
             cb.add_lookup(
                 "mpt_update exists in mpt circuit for Account last access",
                 LookupBuilder::new()
+                    .add(&1.expr(), &q.mpt_update_table.q_enable)
                     .add(&q.rw_table.address, &q.mpt_update_table.address)
                     .add_word(&q.rw_table.storage_key, &q.mpt_update_table.storage_key)
                     .add(&q.mpt_proof_type(), &q.mpt_update_table.proof_type)
@@ -90,6 +90,38 @@ fn verifying_key_independent_of_rw_length() {
     );
 }
 
+#[test]
+fn wrong_state_root_instance() {
+    let storage_op = Operation::new(
+        RWCounter::from(1),
+        RW::WRITE,
+        StorageOp::new(
+            U256::from(100).to_address(),
+            Word::from(0x40),
+            Word::from(32),
+            Word::zero(),
+            1usize,
+            Word::zero(),
+        ),
+    );
+    let circuit = StateCircuit::<Fr>::new(
+        RwMap::from(&OperationContainer {
+            storage: vec![storage_op],
+            ..Default::default()
+        }),
+        N_ROWS,
+    );
+
+    let mut instance = circuit.instance();
+    let prover = MockProver::<Fr>::run(17, &circuit, instance.clone()).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+
+    // The new state root lo
+    instance[0][2] += Fr::ONE;
+    let prover = MockProver::<Fr>::run(17, &circuit, instance).unwrap();
+    assert!(prover.verify().is_err());
+}
+
 #[test]
 fn state_circuit_simple_2() {
     let memory_op_0 = Operation::new(
@@ -879,6 +911,31 @@ fn bad_initial_tx_refund_value() {
     );
 }
 
+#[test]
+fn bad_initial_transient_storage_value() {
+    let rows = vec![Rw::TransientStorage {
+        rw_counter: 1,
+        is_write: true,
+        tx_id: 1,
+        account_address: address!("0x0000000000000000000000000000000004356002"),
+        storage_key: U256::from(5),
+        value: U256::from(300),
+        value_prev: U256::zero(),
+    }];
+
+    let overrides = HashMap::from([
+        ((AdviceColumn::InitialValueHi, 0), Fr::ZERO),
+        ((AdviceColumn::InitialValueLo, 0), Fr::from(10)),
+        ((AdviceColumn::ValuePrevHi, 0), Fr::ZERO),
+        ((AdviceColumn::ValuePrevLo, 0), Fr::from(10)),
+    ]);
+
+    assert_error_matches(
+        verify_with_overrides(rows, overrides),
+        "initial TransientStorage value is 0",
+    );
+}
+
 #[test]
 fn bad_initial_tx_log_value() {
     let rows = vec![Rw::TxLog {
@@ -14,7 +14,14 @@
 //! - [x] Copy Circuit
 //! - [x] Exponentiation Circuit
 //! - [ ] Keccak Circuit
-//! - [ ] MPT Circuit
+//! - [x] Sha256 Circuit
+//! - [x] ModExp Circuit
+//! - [x] Ecc Circuit
+//! - [x] Ripemd160 Circuit
+//! - [x] Blake2f Circuit
+//! - [x] Kzg Circuit
+//! - [x] MPT Circuit
+//! - [x] Receipt Circuit
 //! - [x] PublicInputs Circuit
 //!
 //! And the following shared tables, with the circuits that use them:
@@ -40,40 +47,81 @@
 //! - [ ] Block Table
 //!   - [ ] EVM Circuit
 //!   - [x] PublicInputs Circuit
-//! - [ ] MPT Table
-//!   - [ ] MPT Circuit
-//!   - [ ] State Circuit
+//! - [x] MPT Table
+//!   - [x] MPT Circuit
+//!   - [x] State Circuit
 //! - [x] Keccak Table
 //!   - [ ] Keccak Circuit
 //!   - [ ] EVM Circuit
 //!   - [x] Bytecode Circuit
 //!   - [x] Tx Circuit
-//!   - [ ] MPT Circuit
+//!   - [x] MPT Circuit
+//! - [x] Sha256 Table
+//!   - [x] Sha256 Circuit
+//!   - [x] EVM Circuit
+//! - [x] ModExp Table
+//!   - [x] ModExp Circuit
+//!   - [x] EVM Circuit
+//! - [x] Ecc Table
+//!   - [x] Ecc Circuit
+//!   - [x] EVM Circuit
+//! - [x] Ripemd160 Table
+//!   - [x] Ripemd160 Circuit
+//!   - [x] EVM Circuit
+//! - [x] Blake2f Table
+//!   - [x] Blake2f Circuit
+//!   - [x] EVM Circuit
+//! - [x] Kzg Table
+//!   - [x] Kzg Circuit
+//!   - [x] EVM Circuit
+//! - [x] Receipt Table
+//!   - [x] Receipt Circuit
+//!   - [x] PublicInputs Circuit
+//!
+//! The MPT Table is proven by the MPT Circuit from the MPT witness of the
+//! block, see [`Block::set_mpt_witness`].  When building from [`GethData`], the
+//! MPT witness is generated from the accounts of the pre-state with a
+//! [`StateTrie`].
 
 #[cfg(test)]
 pub(crate) mod test;
 
 use crate::{
+    blake2f_circuit::{Blake2fCircuit, Blake2fCircuitConfig, Blake2fCircuitConfigArgs},
     bytecode_circuit::{BytecodeCircuit, BytecodeCircuitConfig, BytecodeCircuitConfigArgs},
     copy_circuit::{CopyCircuit, CopyCircuitConfig, CopyCircuitConfigArgs},
+    ecc_circuit::{EccCircuit, EccCircuitConfig, EccCircuitConfigArgs},
     evm_circuit::{EvmCircuit, EvmCircuitConfig, EvmCircuitConfigArgs},
     exp_circuit::{ExpCircuit, ExpCircuitConfig},
     keccak_circuit::{KeccakCircuit, KeccakCircuitConfig, KeccakCircuitConfigArgs},
+    kzg_circuit::{KzgCircuit, KzgCircuitConfig, KzgCircuitConfigArgs},
+    modexp_circuit::{ModExpCircuit, ModExpCircuitConfig, ModExpCircuitConfigArgs},
+    mpt_circuit::{
+        state_trie::StateTrie, witness_row::Node, MPTCircuit, MPTCircuitConfigArgs,
+        MPTCircuitParams, MPTConfig,
+    },
     pi_circuit::{PiCircuit, PiCircuitConfig, PiCircuitConfigArgs},
+    receipt_circuit::{ReceiptCircuit, ReceiptCircuitConfig, ReceiptCircuitConfigArgs},
+    ripemd160_circuit::{Ripemd160Circuit, Ripemd160CircuitConfig, Ripemd160CircuitConfigArgs},
+    sha256_circuit::{Sha256Circuit, Sha256CircuitConfig, Sha256CircuitConfigArgs},
     state_circuit::{StateCircuit, StateCircuitConfig, StateCircuitConfigArgs},
     table::{
-        BlockTable, BytecodeTable, CopyTable, ExpTable, KeccakTable, MptTable, RwTable, TxTable,
-        UXTable, WdTable,
+        Blake2fTable, BlockTable, BytecodeTable, CopyTable, EccTable, ExpTable, KeccakTable,
+        KzgTable, ModExpTable, MptTable, ReceiptTable, Ripemd160Table, RwTable, Sha256Table,
+        SigTable, TxTable, UXTable, WdTable,
     },
     tx_circuit::{TxCircuit, TxCircuitConfig, TxCircuitConfigArgs},
     util::{log2_ceil, Challenges, SubCircuit, SubCircuitConfig},
-    witness::{block_convert, Block, MptUpdates},
+    witness::{block_convert, Block, MptUpdates, RwMap},
 };
 use bus_mapping::{
     circuit_input_builder::{CircuitInputBuilder, FixedCParams},
     mock::BlockData,
 };
-use eth_types::{geth_types::GethData, Field};
+use eth_types::{
+    geth_types::{Account, BlockHeader, GethData},
+    Field, GethExecTrace, ToWord,
+};
 use halo2_proofs::{
     circuit::{Layouter, SimpleFloorPlanner, Value},
     plonk::{Circuit, ConstraintSystem, Error, Expression},
@@ -91,22 +139,34 @@ pub struct SuperCircuitConfig<F: Field> {
     u16_table: UXTable<16>,
     evm_circuit: EvmCircuitConfig<F>,
     state_circuit: StateCircuitConfig<F>,
+    mpt_circuit: MPTConfig<F>,
     tx_circuit: TxCircuitConfig<F>,
     bytecode_circuit: BytecodeCircuitConfig<F>,
     copy_circuit: CopyCircuitConfig<F>,
     keccak_circuit: KeccakCircuitConfig<F>,
+    sha256_circuit: Sha256CircuitConfig<F>,
+    modexp_circuit: ModExpCircuitConfig<F>,
+    ecc_circuit: EccCircuitConfig<F>,
+    ripemd160_circuit: Ripemd160CircuitConfig<F>,
+    blake2f_circuit: Blake2fCircuitConfig<F>,
+    kzg_circuit: KzgCircuitConfig<F>,
+    receipt_circuit: ReceiptCircuitConfig<F>,
     pi_circuit: PiCircuitConfig<F>,
     exp_circuit: ExpCircuitConfig<F>,
 }
 
 /// Circuit configuration arguments
 pub struct SuperCircuitConfigArgs<F: Field> {
+    /// Max blocks in a chunk
+    pub max_blocks: usize,
     /// Max txs
     pub max_txs: usize,
     /// Max withdrawals
     pub max_withdrawals: usize,
     /// Max calldata
     pub max_calldata: usize,
+    /// Max bytes of RLP encoded logs
+    pub max_log_bytes: usize,
     /// Mock randomness
     pub mock_randomness: F,
 }
@@ -118,9 +178,11 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
     fn new(
         meta: &mut ConstraintSystem<F>,
         Self::ConfigArgs {
+            max_blocks,
             max_txs,
             max_withdrawals,
             max_calldata,
+            max_log_bytes,
             mock_randomness,
         }: Self::ConfigArgs,
     ) -> Self {
@@ -134,6 +196,14 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
         let copy_table = CopyTable::construct(meta, q_copy_table);
         let exp_table = ExpTable::construct(meta);
         let keccak_table = KeccakTable::construct(meta);
+        let sig_table = SigTable::construct(meta);
+        let sha256_table = Sha256Table::construct(meta);
+        let modexp_table = ModExpTable::construct(meta);
+        let ecc_table = EccTable::construct(meta);
+        let ripemd160_table = Ripemd160Table::construct(meta);
+        let blake2f_table = Blake2fTable::construct(meta);
+        let kzg_table = KzgTable::construct(meta);
+        let receipt_table = ReceiptTable::construct(meta);
         let u8_table = UXTable::construct(meta);
         let u10_table = UXTable::construct(meta);
         let u16_table = UXTable::construct(meta);
@@ -156,16 +226,63 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
             },
         );
 
+        let sha256_circuit = Sha256CircuitConfig::new(
+            meta,
+            Sha256CircuitConfigArgs {
+                sha256_table: sha256_table.clone(),
+                challenges: challenges.clone(),
+            },
+        );
+
+        let ripemd160_circuit = Ripemd160CircuitConfig::new(
+            meta,
+            Ripemd160CircuitConfigArgs {
+                ripemd160_table: ripemd160_table.clone(),
+                challenges: challenges.clone(),
+            },
+        );
+
+        let blake2f_circuit = Blake2fCircuitConfig::new(
+            meta,
+            Blake2fCircuitConfigArgs {
+                blake2f_table: blake2f_table.clone(),
+                challenges: challenges.clone(),
+            },
+        );
+
+        let modexp_circuit = ModExpCircuitConfig::new(
+            meta,
+            ModExpCircuitConfigArgs {
+                modexp_table: modexp_table.clone(),
+                u16_table,
+            },
+        );
+
+        let receipt_circuit = ReceiptCircuitConfig::new(
+            meta,
+            ReceiptCircuitConfigArgs {
+                rw_table,
+                keccak_table: keccak_table.clone(),
+                receipt_table,
+                u8_table,
+                u16_table,
+                challenges: challenges.clone(),
+            },
+        );
+
         let pi_circuit = PiCircuitConfig::new(
             meta,
             PiCircuitConfigArgs {
+                max_blocks,
                 max_txs,
                 max_withdrawals,
                 max_calldata,
+                max_log_bytes,
                 block_table: block_table.clone(),
                 tx_table: tx_table.clone(),
-                wd_table,
+                wd_table: wd_table.clone(),
                 keccak_table: keccak_table.clone(),
+                receipt_table,
                 challenges: challenges.clone(),
             },
         );
@@ -173,7 +290,10 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
             meta,
             TxCircuitConfigArgs {
                 tx_table: tx_table.clone(),
+                block_table: block_table.clone(),
                 keccak_table: keccak_table.clone(),
+                sig_table: sig_table.clone(),
+                u16_table,
                 challenges: challenges.clone(),
             },
         );
@@ -207,11 +327,20 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
                 challenges: challenges.clone(),
             },
         );
+        let mpt_circuit = MPTConfig::new(
+            meta,
+            MPTCircuitConfigArgs {
+                mpt_table,
+                keccak_table: keccak_table.clone(),
+                challenges: challenges.clone(),
+                params: MPTCircuitParams::default(),
+            },
+        );
         let exp_circuit = ExpCircuitConfig::new(meta, exp_table);
         let evm_circuit = EvmCircuitConfig::new(
             meta,
             EvmCircuitConfigArgs {
-                challenges,
+                challenges: challenges.clone(),
                 tx_table,
                 rw_table,
                 bytecode_table,
@@ -219,10 +348,34 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
                 copy_table,
                 keccak_table,
                 exp_table,
+                sig_table,
+                sha256_table,
+                modexp_table,
+                ecc_table: ecc_table.clone(),
+                ripemd160_table,
+                blake2f_table,
+                kzg_table: kzg_table.clone(),
+                wd_table,
                 u8_table,
                 u16_table,
             },
         );
+        // The ECC and KZG circuits are configured last, so that their instance
+        // columns come after the ones of the other circuits.
+        let ecc_circuit = EccCircuitConfig::new(
+            meta,
+            EccCircuitConfigArgs {
+                ecc_table,
+                challenges: challenges.clone(),
+            },
+        );
+        let kzg_circuit = KzgCircuitConfig::new(
+            meta,
+            KzgCircuitConfigArgs {
+                kzg_table,
+                challenges,
+            },
+        );
 
         Self {
             block_table,
@@ -232,10 +385,18 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
             u16_table,
             evm_circuit,
             state_circuit,
+            mpt_circuit,
             copy_circuit,
             tx_circuit,
             bytecode_circuit,
             keccak_circuit,
+            sha256_circuit,
+            modexp_circuit,
+            ecc_circuit,
+            ripemd160_circuit,
+            blake2f_circuit,
+            kzg_circuit,
+            receipt_circuit,
             pi_circuit,
             exp_circuit,
         }
@@ -249,6 +410,8 @@ pub struct SuperCircuit<F: Field> {
     pub evm_circuit: EvmCircuit<F>,
     /// State Circuit
     pub state_circuit: StateCircuit<F>,
+    /// MPT Circuit
+    pub mpt_circuit: MPTCircuit<F>,
     /// The transaction circuit that will be used in the `synthesize` step.
     pub tx_circuit: TxCircuit<F>,
     /// Public Input Circuit
@@ -261,6 +424,20 @@ pub struct SuperCircuit<F: Field> {
     pub exp_circuit: ExpCircuit<F>,
     /// Keccak Circuit
     pub keccak_circuit: KeccakCircuit<F>,
+    /// Sha256 Circuit
+    pub sha256_circuit: Sha256Circuit<F>,
+    /// ModExp Circuit
+    pub modexp_circuit: ModExpCircuit<F>,
+    /// Ecc Circuit
+    pub ecc_circuit: EccCircuit<F>,
+    /// Ripemd160 Circuit
+    pub ripemd160_circuit: Ripemd160Circuit<F>,
+    /// Blake2f Circuit
+    pub blake2f_circuit: Blake2fCircuit<F>,
+    /// Kzg Circuit
+    pub kzg_circuit: KzgCircuit<F>,
+    /// Receipt Circuit
+    pub receipt_circuit: ReceiptCircuit<F>,
     /// Circuits Parameters
     pub circuits_params: FixedCParams,
     /// Mock randomness
@@ -287,12 +464,20 @@ impl<F: Field> SubCircuit<F> for SuperCircuit<F> {
         itertools::max([
             EvmCircuit::<F>::unusable_rows(),
             StateCircuit::<F>::unusable_rows(),
+            MPTCircuit::<F>::unusable_rows(),
             TxCircuit::<F>::unusable_rows(),
             PiCircuit::<F>::unusable_rows(),
             BytecodeCircuit::<F>::unusable_rows(),
             CopyCircuit::<F>::unusable_rows(),
             ExpCircuit::<F>::unusable_rows(),
             KeccakCircuit::<F>::unusable_rows(),
+            Sha256Circuit::<F>::unusable_rows(),
+            ModExpCircuit::<F>::unusable_rows(),
+            EccCircuit::<F>::unusable_rows(),
+            Ripemd160Circuit::<F>::unusable_rows(),
+            Blake2fCircuit::<F>::unusable_rows(),
+            KzgCircuit::<F>::unusable_rows(),
+            ReceiptCircuit::<F>::unusable_rows(),
         ])
         .unwrap()
     }
@@ -300,22 +485,38 @@ impl<F: Field> SubCircuit<F> for SuperCircuit<F> {
     fn new_from_block(block: &Block<F>) -> Self {
         let evm_circuit = EvmCircuit::new_from_block(block);
         let state_circuit = StateCircuit::new_from_block(block);
+        let mpt_circuit = MPTCircuit::new_from_block(block);
         let tx_circuit = TxCircuit::new_from_block(block);
         let pi_circuit = PiCircuit::new_from_block(block);
         let bytecode_circuit = BytecodeCircuit::new_from_block(block);
         let copy_circuit = CopyCircuit::new_from_block_no_external(block);
         let exp_circuit = ExpCircuit::new_from_block(block);
         let keccak_circuit = KeccakCircuit::new_from_block(block);
+        let sha256_circuit = Sha256Circuit::new_from_block(block);
+        let modexp_circuit = ModExpCircuit::new_from_block(block);
+        let ecc_circuit = EccCircuit::new_from_block(block);
+        let ripemd160_circuit = Ripemd160Circuit::new_from_block(block);
+        let blake2f_circuit = Blake2fCircuit::new_from_block(block);
+        let kzg_circuit = KzgCircuit::new_from_block(block);
+        let receipt_circuit = ReceiptCircuit::new_from_block(block);
 
         SuperCircuit::<_> {
             evm_circuit,
             state_circuit,
+            mpt_circuit,
             tx_circuit,
             pi_circuit,
             bytecode_circuit,
             copy_circuit,
             exp_circuit,
             keccak_circuit,
+            sha256_circuit,
+            modexp_circuit,
+            ecc_circuit,
+            ripemd160_circuit,
+            blake2f_circuit,
+            kzg_circuit,
+            receipt_circuit,
             circuits_params: block.circuits_params,
             mock_randomness: block.randomness,
         }
@@ -332,6 +533,8 @@ impl<F: Field> SubCircuit<F> for SuperCircuit<F> {
         instance.extend_from_slice(&self.state_circuit.instance());
         instance.extend_from_slice(&self.exp_circuit.instance());
         instance.extend_from_slice(&self.evm_circuit.instance());
+        instance.extend_from_slice(&self.ecc_circuit.instance());
+        instance.extend_from_slice(&self.kzg_circuit.instance());
 
         instance
     }
@@ -340,14 +543,25 @@ impl<F: Field> SubCircuit<F> for SuperCircuit<F> {
     fn min_num_rows_block(block: &Block<F>) -> (usize, usize) {
         let evm = EvmCircuit::min_num_rows_block(block);
         let state = StateCircuit::min_num_rows_block(block);
+        let mpt = MPTCircuit::min_num_rows_block(block);
         let bytecode = BytecodeCircuit::min_num_rows_block(block);
         let copy = CopyCircuit::min_num_rows_block(block);
         let keccak = KeccakCircuit::min_num_rows_block(block);
+        let sha256 = Sha256Circuit::min_num_rows_block(block);
+        let modexp = ModExpCircuit::min_num_rows_block(block);
+        let ecc = EccCircuit::min_num_rows_block(block);
+        let ripemd160 = Ripemd160Circuit::min_num_rows_block(block);
+        let blake2f = Blake2fCircuit::min_num_rows_block(block);
+        let kzg = KzgCircuit::min_num_rows_block(block);
+        let receipt = ReceiptCircuit::min_num_rows_block(block);
         let tx = TxCircuit::min_num_rows_block(block);
         let exp = ExpCircuit::min_num_rows_block(block);
         let pi = PiCircuit::min_num_rows_block(block);
 
-        let rows: Vec<(usize, usize)> = vec![evm, state, bytecode, copy, keccak, tx, exp, pi];
+        let rows: Vec<(usize, usize)> = vec![
+            evm, state, mpt, bytecode, copy, keccak, sha256, modexp, ecc, ripemd160, blake2f, kzg,
+            tx, exp, receipt, pi,
+        ];
         let (rows_without_padding, rows_with_padding): (Vec<usize>, Vec<usize>) =
             rows.into_iter().unzip();
         (
@@ -365,18 +579,34 @@ impl<F: Field> SubCircuit<F> for SuperCircuit<F> {
     ) -> Result<(), Error> {
         self.keccak_circuit
             .synthesize_sub(&config.keccak_circuit, challenges, layouter)?;
+        self.sha256_circuit
+            .synthesize_sub(&config.sha256_circuit, challenges, layouter)?;
+        self.modexp_circuit
+            .synthesize_sub(&config.modexp_circuit, challenges, layouter)?;
+        self.ecc_circuit
+            .synthesize_sub(&config.ecc_circuit, challenges, layouter)?;
+        self.ripemd160_circuit
+            .synthesize_sub(&config.ripemd160_circuit, challenges, layouter)?;
+        self.blake2f_circuit
+            .synthesize_sub(&config.blake2f_circuit, challenges, layouter)?;
+        self.kzg_circuit
+            .synthesize_sub(&config.kzg_circuit, challenges, layouter)?;
         self.bytecode_circuit
             .synthesize_sub(&config.bytecode_circuit, challenges, layouter)?;
         self.tx_circuit
             .synthesize_sub(&config.tx_circuit, challenges, layouter)?;
         self.state_circuit
             .synthesize_sub(&config.state_circuit, challenges, layouter)?;
+        self.mpt_circuit
+            .synthesize_sub(&config.mpt_circuit, challenges, layouter)?;
         self.copy_circuit
             .synthesize_sub(&config.copy_circuit, challenges, layouter)?;
         self.exp_circuit
             .synthesize_sub(&config.exp_circuit, challenges, layouter)?;
         self.evm_circuit
             .synthesize_sub(&config.evm_circuit, challenges, layouter)?;
+        self.receipt_circuit
+            .synthesize_sub(&config.receipt_circuit, challenges, layouter)?;
         self.pi_circuit
             .synthesize_sub(&config.pi_circuit, challenges, layouter)?;
         Ok(())
@@ -386,9 +616,11 @@ impl<F: Field> SubCircuit<F> for SuperCircuit<F> {
 /// Super Circuit configuration parameters
 #[derive(Default)]
 pub struct SuperCircuitParams<F: Field> {
+    max_blocks: usize,
     max_txs: usize,
     max_withdrawals: usize,
     max_calldata: usize,
+    max_log_bytes: usize,
     mock_randomness: F,
 }
 
@@ -403,9 +635,11 @@ impl<F: Field> Circuit<F> for SuperCircuit<F> {
 
     fn params(&self) -> Self::Params {
         SuperCircuitParams {
+            max_blocks: self.circuits_params.max_blocks,
             max_txs: self.circuits_params.max_txs,
             max_withdrawals: self.circuits_params.max_withdrawals,
             max_calldata: self.circuits_params.max_calldata,
+            max_log_bytes: self.circuits_params.max_log_bytes,
             mock_randomness: self.mock_randomness,
         }
     }
@@ -414,9 +648,11 @@ impl<F: Field> Circuit<F> for SuperCircuit<F> {
         Self::Config::new(
             meta,
             SuperCircuitConfigArgs {
+                max_blocks: params.max_blocks,
                 max_txs: params.max_txs,
                 max_withdrawals: params.max_withdrawals,
                 max_calldata: params.max_calldata,
+                max_log_bytes: params.max_log_bytes,
                 mock_randomness: params.mock_randomness,
             },
         )
@@ -436,13 +672,7 @@ impl<F: Field> Circuit<F> for SuperCircuit<F> {
             Value::known(block.randomness),
             Value::known(block.randomness),
         );
-        let rws = &self.state_circuit.rows;
-
-        config.block_table.load(&mut layouter, &block.context)?;
-
-        config
-            .mpt_table
-            .load(&mut layouter, &MptUpdates::mock_from(rws))?;
+        config.block_table.load(&mut layouter, &block.contexts)?;
 
         config.u8_table.load(&mut layouter)?;
         config.u10_table.load(&mut layouter)?;
@@ -471,22 +701,85 @@ impl<F: Field> SuperCircuit<F> {
         builder
             .handle_block(&geth_data.eth_block, &geth_data.geth_traces)
             .expect("could not handle block tx");
+        let mpt_witness = Self::generate_mpt_witness(&mut builder, &geth_data.accounts);
+
+        let ret = Self::build_from_circuit_input_builder(&builder, mpt_witness, mock_randomness)?;
+        Ok((ret.0, ret.1, ret.2, builder))
+    }
+
+    /// From the witness data of a chunk of consecutive blocks, the first of
+    /// which is the block of `geth_data`, generate a SuperCircuit instance
+    /// with all of the sub-circuits filled with their corresponding witnesses.
+    ///
+    /// Also, return with it the minimum required SRS degree for the
+    /// circuit and the Public Inputs needed.
+    #[allow(clippy::type_complexity)]
+    pub fn build_chunk(
+        geth_data: GethData,
+        blocks: &[(&eth_types::Block<eth_types::Transaction>, &[GethExecTrace])],
+        circuits_params: FixedCParams,
+        mock_randomness: F,
+    ) -> Result<(u32, Self, Vec<Vec<F>>, CircuitInputBuilder<FixedCParams>), bus_mapping::Error>
+    {
+        let accounts = geth_data.accounts.clone();
+        let block_data = BlockData::new_from_geth_data_with_params(geth_data, circuits_params);
+        let mut builder = block_data.new_circuit_input_builder();
+        builder
+            .handle_blocks(blocks)
+            .expect("could not handle chunk txs");
+        let mpt_witness = Self::generate_mpt_witness(&mut builder, &accounts);
 
-        let ret = Self::build_from_circuit_input_builder(&builder, mock_randomness)?;
+        let ret = Self::build_from_circuit_input_builder(&builder, mpt_witness, mock_randomness)?;
         Ok((ret.0, ret.1, ret.2, builder))
     }
 
-    /// From CircuitInputBuilder, generate a SuperCircuit instance with all of
-    /// the sub-circuits filled with their corresponding witnesses.
+    /// Generate the MPT witness of the updates of the blocks of `builder` from
+    /// the `accounts` of their pre-state.  The state root before the blocks
+    /// and the state root of the last block are set to the roots of the state
+    /// trie, and the hash of the last block to the hash of its new header.
+    ///
+    /// Panics if the blocks update the storage or create an account, which the
+    /// [`StateTrie`] doesn't support.
+    fn generate_mpt_witness(
+        builder: &mut CircuitInputBuilder<FixedCParams>,
+        accounts: &[Account],
+    ) -> Vec<Node> {
+        let mut state_trie = StateTrie::new(accounts);
+        let block = &mut builder.block;
+        block.headers[0].prev_state_root = state_trie.root().to_word();
+        let rws = RwMap::from(&block.container);
+        let mpt_witness =
+            state_trie.prove_updates(&MptUpdates::mock_from(&rws.table_assignments()));
+
+        let head = block
+            .headers
+            .last_mut()
+            .expect("a chunk has at least one block");
+        let eth_block = &mut head.eth_block;
+        eth_block.state_root = state_trie.root();
+        eth_block.hash = Some(BlockHeader::from(&*eth_block).hash(head.hardfork));
+        for tx in eth_block.transactions.iter_mut() {
+            tx.block_hash = eth_block.hash;
+        }
+        block.prev_state_root = head.prev_state_root;
+        block.eth_block = head.eth_block.clone();
+        mpt_witness
+    }
+
+    /// From CircuitInputBuilder and the MPT witness of its updates, generate a
+    /// SuperCircuit instance with all of the sub-circuits filled with their
+    /// corresponding witnesses.
     ///
     /// Also, return with it the minimum required SRS degree for the circuit and
     /// the Public Inputs needed.
     pub fn build_from_circuit_input_builder(
         builder: &CircuitInputBuilder<FixedCParams>,
+        mpt_witness: Vec<Node>,
         mock_randomness: F,
     ) -> Result<(u32, Self, Vec<Vec<F>>), bus_mapping::Error> {
         let mut block = block_convert(builder).unwrap();
         block.randomness = mock_randomness;
+        block.set_mpt_witness(mpt_witness);
 
         let (_, rows_needed) = Self::min_num_rows_block(&block);
         let k = log2_ceil(Self::unusable_rows() + rows_needed);
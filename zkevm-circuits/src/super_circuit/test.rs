@@ -7,15 +7,22 @@ use rand::SeedableRng;
 use rand_chacha::ChaCha20Rng;
 use std::collections::HashMap;
 
-use eth_types::{address, bytecode, geth_types::GethData, Word};
+use bus_mapping::precompile::PrecompileEcParams;
+use eth_types::{
+    address, bytecode,
+    geth_types::{transactions_root, BlockHeader, GethData},
+    Block, Transaction, Word, U64,
+};
 
 #[test]
 fn super_circuit_degree() {
     let mut cs = ConstraintSystem::<Fr>::default();
     let params = SuperCircuitParams {
+        max_blocks: 1,
         max_txs: 1,
         max_withdrawals: 5,
         max_calldata: 32,
+        max_log_bytes: 64,
         mock_randomness: Fr::from(0x100),
     };
     SuperCircuit::configure_with_params(&mut cs, params);
@@ -68,7 +75,7 @@ pub(crate) fn block_1tx() -> GethData {
                 .to(accs[0].address)
                 .gas(Word::from(1_000_000u64));
         },
-        |block, _tx| block.number(0xcafeu64),
+        |block, _tx| block.number(0xcafeu64).author(addr_b),
     )
     .unwrap()
     .into();
@@ -113,7 +120,7 @@ fn block_2tx() -> GethData {
                 .to(accs[0].address)
                 .gas(Word::from(1_000_000u64));
         },
-        |block, _tx| block.number(0xcafeu64),
+        |block, _tx| block.number(0xcafeu64).author(addr_b),
     )
     .unwrap()
     .into();
@@ -123,6 +130,53 @@ fn block_2tx() -> GethData {
 
 const TEST_MOCK_RANDOMNESS: u64 = 0x100;
 
+#[test]
+fn super_circuit_mpt_witness() {
+    let block = block_2tx();
+    let prev_state_root = StateTrie::new(&block.accounts).root();
+    let circuits_params = FixedCParams {
+        max_blocks: 1,
+        max_txs: 2,
+        max_withdrawals: 5,
+        max_calldata: 32,
+        max_rws: 256,
+        max_copy_rows: 256,
+        max_exp_steps: 256,
+        max_bytecode: 512,
+        max_evm_rows: 0,
+        max_keccak_rows: 0,
+        max_ecrecover: 0,
+        max_sha256_rows: 0,
+        max_ripemd160_rows: 0,
+        max_modexp: 0,
+        max_ec_ops: PrecompileEcParams::default(),
+        max_blake2f_rows: 0,
+        max_point_evaluations: 0,
+        max_log_bytes: 512,
+        max_mpt_rows: 0,
+    };
+    let (_, circuit, _, builder) =
+        SuperCircuit::<Fr>::build(block, circuits_params, Fr::from(TEST_MOCK_RANDOMNESS)).unwrap();
+
+    // The roots of the state trie are the state roots before and after the
+    // block, and the block hash is the one of the header with the new root.
+    let eth_block = &builder.block.eth_block;
+    assert_eq!(
+        builder.block.headers[0].prev_state_root,
+        prev_state_root.to_word()
+    );
+    assert_ne!(eth_block.state_root, prev_state_root);
+    assert_eq!(
+        eth_block.hash,
+        Some(BlockHeader::from(eth_block).hash(builder.block.hardfork))
+    );
+
+    let mpt_circuit = circuit.mpt_circuit;
+    assert!(!mpt_circuit.nodes.is_empty());
+    let prover = MockProver::<Fr>::run(15, &mpt_circuit, vec![]).unwrap();
+    prover.assert_satisfied_par();
+}
+
 // High memory usage test.  Run in serial with:
 // `cargo test [...] serial_ -- --ignored --test-threads 1`
 #[ignore]
@@ -130,6 +184,7 @@ const TEST_MOCK_RANDOMNESS: u64 = 0x100;
 fn serial_test_super_circuit_1tx_1max_tx() {
     let block = block_1tx();
     let circuits_params = FixedCParams {
+        max_blocks: 1,
         max_txs: 1,
         max_withdrawals: 5,
         max_calldata: 32,
@@ -139,6 +194,15 @@ fn serial_test_super_circuit_1tx_1max_tx() {
         max_bytecode: 512,
         max_evm_rows: 0,
         max_keccak_rows: 0,
+        max_ecrecover: 0,
+        max_sha256_rows: 0,
+        max_ripemd160_rows: 0,
+        max_modexp: 0,
+        max_ec_ops: PrecompileEcParams::default(),
+        max_blake2f_rows: 0,
+        max_point_evaluations: 0,
+        max_log_bytes: 512,
+        max_mpt_rows: 0,
     };
     test_super_circuit(block, circuits_params, Fr::from(TEST_MOCK_RANDOMNESS));
 }
@@ -147,6 +211,7 @@ fn serial_test_super_circuit_1tx_1max_tx() {
 fn serial_test_super_circuit_1tx_2max_tx() {
     let block = block_1tx();
     let circuits_params = FixedCParams {
+        max_blocks: 1,
         max_txs: 2,
         max_withdrawals: 5,
         max_calldata: 32,
@@ -156,6 +221,15 @@ fn serial_test_super_circuit_1tx_2max_tx() {
         max_bytecode: 512,
         max_evm_rows: 0,
         max_keccak_rows: 0,
+        max_ecrecover: 0,
+        max_sha256_rows: 0,
+        max_ripemd160_rows: 0,
+        max_modexp: 0,
+        max_ec_ops: PrecompileEcParams::default(),
+        max_blake2f_rows: 0,
+        max_point_evaluations: 0,
+        max_log_bytes: 512,
+        max_mpt_rows: 0,
     };
     test_super_circuit(block, circuits_params, Fr::from(TEST_MOCK_RANDOMNESS));
 }
@@ -164,6 +238,7 @@ fn serial_test_super_circuit_1tx_2max_tx() {
 fn serial_test_super_circuit_2tx_2max_tx() {
     let block = block_2tx();
     let circuits_params = FixedCParams {
+        max_blocks: 1,
         max_txs: 2,
         max_withdrawals: 5,
         max_calldata: 32,
@@ -173,6 +248,88 @@ fn serial_test_super_circuit_2tx_2max_tx() {
         max_bytecode: 512,
         max_evm_rows: 0,
         max_keccak_rows: 0,
+        max_ecrecover: 0,
+        max_sha256_rows: 0,
+        max_ripemd160_rows: 0,
+        max_modexp: 0,
+        max_ec_ops: PrecompileEcParams::default(),
+        max_blake2f_rows: 0,
+        max_point_evaluations: 0,
+        max_log_bytes: 512,
+        max_mpt_rows: 0,
     };
     test_super_circuit(block, circuits_params, Fr::from(TEST_MOCK_RANDOMNESS));
 }
+
+/// Split the two txs of `block_2tx` into a chunk of two consecutive blocks,
+/// with one tx each.
+fn chunk_2blocks() -> (GethData, [Block<Transaction>; 2]) {
+    let block = block_2tx();
+    let chain_id = block.chain_id.as_u64();
+    let hardfork = block.hardfork;
+
+    let mut blocks = [block.eth_block.clone(), block.eth_block.clone()];
+    for (index, eth_block) in blocks.iter_mut().enumerate() {
+        let mut tx = block.eth_block.transactions[index].clone();
+        tx.transaction_index = Some(U64::zero());
+        eth_block.transactions = vec![tx];
+        eth_block.transactions_root = transactions_root(chain_id, &eth_block.transactions).unwrap();
+    }
+    blocks[1].number = Some(U64::from(0xcaffu64));
+    blocks[1].parent_hash = blocks[0].hash.unwrap();
+    for eth_block in blocks.iter_mut() {
+        eth_block.hash = Some(BlockHeader::from(&*eth_block).hash(hardfork));
+        for tx in eth_block.transactions.iter_mut() {
+            tx.block_number = eth_block.number;
+            tx.block_hash = eth_block.hash;
+        }
+    }
+
+    let mut geth_data = block;
+    geth_data.eth_block = blocks[0].clone();
+    (geth_data, blocks)
+}
+
+#[ignore]
+#[test]
+fn serial_test_super_circuit_chunk_2blocks() {
+    let (geth_data, blocks) = chunk_2blocks();
+    let traces = geth_data.geth_traces.clone();
+    let circuits_params = FixedCParams {
+        max_blocks: 2,
+        max_txs: 2,
+        max_withdrawals: 5,
+        max_calldata: 32,
+        max_rws: 256,
+        max_copy_rows: 256,
+        max_exp_steps: 256,
+        max_bytecode: 512,
+        max_evm_rows: 0,
+        max_keccak_rows: 0,
+        max_ecrecover: 0,
+        max_sha256_rows: 0,
+        max_ripemd160_rows: 0,
+        max_modexp: 0,
+        max_ec_ops: PrecompileEcParams::default(),
+        max_blake2f_rows: 0,
+        max_point_evaluations: 0,
+        max_log_bytes: 512,
+        max_mpt_rows: 0,
+    };
+    let (k, circuit, instance, builder) = SuperCircuit::<Fr>::build_chunk(
+        geth_data,
+        &[(&blocks[0], &traces[..1]), (&blocks[1], &traces[1..])],
+        circuits_params,
+        Fr::from(TEST_MOCK_RANDOMNESS),
+    )
+    .unwrap();
+    assert_eq!(builder.block.headers.len(), 2);
+    assert_eq!(builder.block.block_steps.end_blocks.len(), 1);
+
+    let prover = MockProver::run(k, &circuit, instance).unwrap();
+    let res = prover.verify_par();
+    if let Err(err) = res {
+        error!("Verification failures: {:#?}", err);
+        panic!("Failed verification");
+    }
+}
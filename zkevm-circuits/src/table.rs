@@ -27,20 +27,36 @@ use itertools::Itertools;
 use std::array;
 use strum_macros::{EnumCount, EnumIter};
 
+/// blake2f table
+pub(crate) mod blake2f_table;
 /// block table
 pub(crate) mod block_table;
 /// bytecode table
 pub(crate) mod bytecode_table;
 /// copy Table
 pub(crate) mod copy_table;
+/// ecc table
+pub(crate) mod ecc_table;
 /// exp(exponentiation) table
 pub(crate) mod exp_table;
 /// keccak table
 pub(crate) mod keccak_table;
+/// kzg table
+pub(crate) mod kzg_table;
+/// modexp table
+pub(crate) mod modexp_table;
 /// mpt table
 pub mod mpt_table;
+/// receipt table
+pub(crate) mod receipt_table;
+/// ripemd160 table
+pub(crate) mod ripemd160_table;
 /// rw table
 pub(crate) mod rw_table;
+/// sha256 table
+pub(crate) mod sha256_table;
+/// signature table
+pub(crate) mod sig_table;
 /// tx table
 pub(crate) mod tx_table;
 /// ux table
@@ -48,15 +64,23 @@ pub(crate) mod ux_table;
 /// withdrawal table
 pub(crate) mod wd_table;
 
+pub use blake2f_table::Blake2fTable;
 pub(crate) use block_table::{BlockContextFieldTag, BlockTable};
 pub(crate) use bytecode_table::{BytecodeFieldTag, BytecodeTable};
 pub(crate) use copy_table::CopyTable;
+pub use ecc_table::{EccOpType, EccTable};
 pub(crate) use exp_table::ExpTable;
 pub use keccak_table::KeccakTable;
+pub use kzg_table::KzgTable;
 pub(crate) use ux_table::UXTable;
 
+pub use modexp_table::ModExpTable;
 pub use mpt_table::{MPTProofType, MptTable};
+pub use receipt_table::{ReceiptFieldTag, ReceiptTable, N_BYTES_BLOOM_LIMB};
+pub use ripemd160_table::Ripemd160Table;
 pub(crate) use rw_table::RwTable;
+pub use sha256_table::Sha256Table;
+pub(crate) use sig_table::SigTable;
 pub(crate) use tx_table::{
     TxContextFieldTag, TxFieldTag, TxLogFieldTag, TxReceiptFieldTag, TxTable,
 };
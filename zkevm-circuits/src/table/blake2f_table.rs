@@ -0,0 +1,132 @@
+use super::*;
+use bus_mapping::circuit_input_builder::Blake2fEvent;
+
+/// Blake2f Table, used to verify the compression of the BLAKE2F precompile
+/// from RLC'ed input.
+#[derive(Clone, Debug)]
+pub struct Blake2fTable {
+    /// True when the row is enabled
+    pub is_enabled: Column<Advice>,
+    /// Byte array input as `RLC(reversed(input))`
+    pub input_rlc: Column<Advice>,
+    /// Number of rounds, the first 4 bytes of the input
+    pub rounds: Column<Advice>,
+    /// True when the final block flag, the last byte of the input, is 0 or 1
+    pub is_valid: Column<Advice>,
+    /// Compressed state as `RLC(reversed(output))`, or 0 if the input is not
+    /// valid
+    pub output_rlc: Column<Advice>,
+}
+
+impl<F: Field> LookupTable<F> for Blake2fTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.is_enabled.into(),
+            self.input_rlc.into(),
+            self.rounds.into(),
+            self.is_valid.into(),
+            self.output_rlc.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("is_enabled"),
+            String::from("input_rlc"),
+            String::from("rounds"),
+            String::from("is_valid"),
+            String::from("output_rlc"),
+        ]
+    }
+}
+
+impl Blake2fTable {
+    /// Construct a new Blake2fTable
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            is_enabled: meta.advice_column(),
+            input_rlc: meta.advice_column_in(SecondPhase),
+            rounds: meta.advice_column(),
+            is_valid: meta.advice_column(),
+            output_rlc: meta.advice_column_in(SecondPhase),
+        }
+    }
+
+    /// Generate the blake2f table assignments from a blake2f event.
+    pub fn assignments<F: Field>(
+        event: &Blake2fEvent,
+        challenges: &Challenges<Value<F>>,
+    ) -> Vec<[Value<F>; 5]> {
+        let input_rlc = challenges
+            .keccak_input()
+            .map(|challenge| rlc::value(event.input.iter().rev(), challenge));
+        let output_rlc = challenges
+            .keccak_input()
+            .map(|challenge| rlc::value(event.output.iter().rev(), challenge));
+
+        vec![[
+            Value::known(F::ONE),
+            input_rlc,
+            Value::known(F::from(u64::from(event.rounds()))),
+            Value::known(F::from(event.is_valid() as u64)),
+            output_rlc,
+        ]]
+    }
+
+    /// Assign a table row for blake2f table
+    pub fn assign_row<F: Field>(
+        &self,
+        region: &mut Region<F>,
+        offset: usize,
+        values: [Value<F>; 5],
+    ) -> Result<(), Error> {
+        for (&column, value) in <Blake2fTable as LookupTable<F>>::advice_columns(self)
+            .iter()
+            .zip(values.iter())
+        {
+            region.assign_advice(|| format!("assign {}", offset), column, offset, || *value)?;
+        }
+        Ok(())
+    }
+
+    /// Provide this function for the case that we want to consume a blake2f
+    /// table but without running the full blake2f circuit
+    pub fn dev_load<'a, F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        events: impl IntoIterator<Item = &'a Blake2fEvent> + Clone,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "blake2f table",
+            |mut region| {
+                let mut offset = 0;
+                for column in <Blake2fTable as LookupTable<F>>::advice_columns(self) {
+                    region.assign_advice(
+                        || "blake2f table all-zero row",
+                        column,
+                        offset,
+                        || Value::known(F::ZERO),
+                    )?;
+                }
+                offset += 1;
+
+                let blake2f_table_columns = <Blake2fTable as LookupTable<F>>::advice_columns(self);
+                for event in events.clone() {
+                    for row in Self::assignments(event, challenges) {
+                        for (&column, value) in blake2f_table_columns.iter().zip_eq(row) {
+                            region.assign_advice(
+                                || format!("blake2f table row {}", offset),
+                                column,
+                                offset,
+                                || value,
+                            )?;
+                        }
+                        offset += 1;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
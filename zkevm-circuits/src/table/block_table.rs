@@ -25,6 +25,14 @@ pub enum BlockContextFieldTag {
     ChainId,
     /// Withdrawal Root field
     WithdrawalRoot,
+    /// Blob Base Fee field (EIP-4844)
+    BlobBaseFee,
+    /// Hardfork field
+    Hardfork,
+    /// Number of withdrawals of the previous blocks of the chunk.  Although
+    /// this is not a field in the block header, we add it here to locate the
+    /// withdrawals of the block in the withdrawal table.
+    WithdrawalOffset,
 }
 impl_expr!(BlockContextFieldTag);
 
@@ -49,17 +57,22 @@ impl BlockTable {
         }
     }
 
-    /// Assign the `BlockTable` from a `BlockContext`.
+    /// Assign the `BlockTable` from the `BlockContext`s of a chunk of
+    /// consecutive blocks.
     pub fn load<F: Field>(
         &self,
         layouter: &mut impl Layouter<F>,
-        block: &BlockContext,
+        blocks: &[BlockContext],
     ) -> Result<(), Error> {
         layouter.assign_region(
             || "block table",
             |mut region| {
                 for (offset, row) in iter::once([Value::known(F::ZERO); 4])
-                    .chain(block.table_assignments::<F>())
+                    .chain(
+                        blocks
+                            .iter()
+                            .flat_map(|block| block.table_assignments::<F>()),
+                    )
                     .enumerate()
                 {
                     region.assign_fixed(
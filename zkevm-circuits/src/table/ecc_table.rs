@@ -0,0 +1,250 @@
+use super::*;
+use bus_mapping::circuit_input_builder::{EcAddOp, EcMulOp, EcPairingOp, PrecompileEvents};
+use std::iter;
+
+/// Tag of the operation of a row in the [`EccTable`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EccOpType {
+    /// Point addition, i.e. `P + Q = R`.
+    Add = 1,
+    /// Scalar multiplication, i.e. `s * P = R`.
+    Mul,
+    /// Pairing check of the pairs of G1 and G2 points.
+    Pairing,
+}
+impl_expr!(EccOpType);
+
+/// The ecc table is used to verify the operations of the ECADD, ECMUL and
+/// ECPAIRING precompiles on the BN254 curve, whose coordinates are lower than
+/// the modulus of the base field.  The rows are laid out as follows:
+///
+/// | op_type | arg1 | arg2 | arg3 | arg4 | input_rlc | input_len | output1 | output2 |
+/// | ------- | ---- | ---- | ---- | ---- | --------- | --------- | ------- | ------- |
+/// | Add     | p_x  | p_y  | q_x  | q_y  | 0         | 0         | r_x     | r_y     |
+/// | Mul     | p_x  | p_y  | s    | 0    | 0         | 0         | r_x     | r_y     |
+/// | Pairing | 0    | 0    | 0    | 0    | input_rlc | input_len | output  | 0       |
+///
+/// The result of the pairings is given by the public inputs of the ECC
+/// circuit, see [`crate::ecc_circuit`].
+#[derive(Clone, Debug)]
+pub struct EccTable {
+    /// Indicates whether or not the row holds an operation.
+    pub q_enable: Column<Fixed>,
+    /// Tag of the operation.
+    pub op_type: Column<Advice>,
+    /// First argument of the operation.
+    pub arg1: Word<Column<Advice>>,
+    /// Second argument of the operation.
+    pub arg2: Word<Column<Advice>>,
+    /// Third argument of the operation.
+    pub arg3: Word<Column<Advice>>,
+    /// Fourth argument of the operation.
+    pub arg4: Word<Column<Advice>>,
+    /// Input bytes of a pairing as `RLC(reversed(input))`.
+    pub input_rlc: Column<Advice>,
+    /// Length of the input bytes of a pairing.
+    pub input_len: Column<Advice>,
+    /// First output of the operation.
+    pub output1: Word<Column<Advice>>,
+    /// Second output of the operation.
+    pub output2: Word<Column<Advice>>,
+    /// Whether the points are valid, the outputs are zero otherwise.
+    pub is_valid: Column<Advice>,
+}
+
+impl EccTable {
+    /// Construct the EccTable.
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        let table = Self {
+            q_enable: meta.fixed_column(),
+            op_type: meta.advice_column(),
+            arg1: Word::new([meta.advice_column(), meta.advice_column()]),
+            arg2: Word::new([meta.advice_column(), meta.advice_column()]),
+            arg3: Word::new([meta.advice_column(), meta.advice_column()]),
+            arg4: Word::new([meta.advice_column(), meta.advice_column()]),
+            input_rlc: meta.advice_column_in(SecondPhase),
+            input_len: meta.advice_column(),
+            output1: Word::new([meta.advice_column(), meta.advice_column()]),
+            output2: Word::new([meta.advice_column(), meta.advice_column()]),
+            is_valid: meta.advice_column(),
+        };
+        // The ECC circuit copies its results into the table.
+        for column in <EccTable as LookupTable<F>>::advice_columns(&table) {
+            meta.enable_equality(column);
+        }
+        table
+    }
+
+    fn row<F: Field>(
+        op_type: EccOpType,
+        args: [U256; 4],
+        input_rlc: Value<F>,
+        input_len: usize,
+        outputs: [U256; 2],
+        is_valid: bool,
+    ) -> [Value<F>; 16] {
+        let [arg1, arg2, arg3, arg4] = args.map(Word::<F>::from);
+        let [output1, output2] = outputs.map(Word::<F>::from);
+        [
+            Value::known(F::from(op_type as u64)),
+            Value::known(arg1.lo()),
+            Value::known(arg1.hi()),
+            Value::known(arg2.lo()),
+            Value::known(arg2.hi()),
+            Value::known(arg3.lo()),
+            Value::known(arg3.hi()),
+            Value::known(arg4.lo()),
+            Value::known(arg4.hi()),
+            input_rlc,
+            Value::known(F::from(input_len as u64)),
+            Value::known(output1.lo()),
+            Value::known(output1.hi()),
+            Value::known(output2.lo()),
+            Value::known(output2.hi()),
+            Value::known(F::from(is_valid as u64)),
+        ]
+    }
+
+    /// Generate the table row for a point addition.
+    pub fn add_assignment<F: Field>(op: &EcAddOp) -> [Value<F>; 16] {
+        Self::row(
+            EccOpType::Add,
+            [op.p.0, op.p.1, op.q.0, op.q.1],
+            Value::known(F::ZERO),
+            0,
+            [op.r.0, op.r.1],
+            op.is_valid,
+        )
+    }
+
+    /// Generate the table row for a scalar multiplication.
+    pub fn mul_assignment<F: Field>(op: &EcMulOp) -> [Value<F>; 16] {
+        Self::row(
+            EccOpType::Mul,
+            [op.p.0, op.p.1, op.s, U256::zero()],
+            Value::known(F::ZERO),
+            0,
+            [op.r.0, op.r.1],
+            op.is_valid,
+        )
+    }
+
+    /// Generate the table row for a pairing check.
+    pub fn pairing_assignment<F: Field>(
+        op: &EcPairingOp,
+        challenges: &Challenges<Value<F>>,
+    ) -> [Value<F>; 16] {
+        let input_rlc = challenges
+            .keccak_input()
+            .map(|challenge| rlc::value(op.input.iter().rev(), challenge));
+        Self::row(
+            EccOpType::Pairing,
+            [U256::zero(); 4],
+            input_rlc,
+            op.input.len(),
+            [U256::from(op.output as u64), U256::zero()],
+            op.is_valid,
+        )
+    }
+
+    /// Generate the table rows for all the operations of the precompile
+    /// events, the additions first, then the multiplications and the pairings.
+    pub fn assignments<F: Field>(
+        events: &PrecompileEvents,
+        challenges: &Challenges<Value<F>>,
+    ) -> Vec<[Value<F>; 16]> {
+        iter::empty()
+            .chain(events.get_ec_add_events().iter().map(Self::add_assignment))
+            .chain(events.get_ec_mul_events().iter().map(Self::mul_assignment))
+            .chain(
+                events
+                    .get_ec_pairing_events()
+                    .iter()
+                    .map(|op| Self::pairing_assignment(op, challenges)),
+            )
+            .collect()
+    }
+
+    /// Assign witness data from a block to the ecc table in a dev environment,
+    /// without verifying the operations.
+    pub fn dev_load<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        block: &Block<F>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "ecc table (dev load)",
+            |mut region| {
+                let rows = Self::assignments(&block.precompile_events, challenges);
+                for (offset, row) in rows.into_iter().enumerate() {
+                    region.assign_fixed(
+                        || format!("ecc table q_enable {offset}"),
+                        self.q_enable,
+                        offset,
+                        || Value::known(F::ONE),
+                    )?;
+                    for (column, value) in <EccTable as LookupTable<F>>::advice_columns(self)
+                        .into_iter()
+                        .zip(row)
+                    {
+                        region.assign_advice(
+                            || format!("ecc table row {offset}"),
+                            column,
+                            offset,
+                            || value,
+                        )?;
+                    }
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
+
+impl<F: Field> LookupTable<F> for EccTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.q_enable.into(),
+            self.op_type.into(),
+            self.arg1.lo().into(),
+            self.arg1.hi().into(),
+            self.arg2.lo().into(),
+            self.arg2.hi().into(),
+            self.arg3.lo().into(),
+            self.arg3.hi().into(),
+            self.arg4.lo().into(),
+            self.arg4.hi().into(),
+            self.input_rlc.into(),
+            self.input_len.into(),
+            self.output1.lo().into(),
+            self.output1.hi().into(),
+            self.output2.lo().into(),
+            self.output2.hi().into(),
+            self.is_valid.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("q_enable"),
+            String::from("op_type"),
+            String::from("arg1_lo"),
+            String::from("arg1_hi"),
+            String::from("arg2_lo"),
+            String::from("arg2_hi"),
+            String::from("arg3_lo"),
+            String::from("arg3_hi"),
+            String::from("arg4_lo"),
+            String::from("arg4_hi"),
+            String::from("input_rlc"),
+            String::from("input_len"),
+            String::from("output1_lo"),
+            String::from("output1_hi"),
+            String::from("output2_lo"),
+            String::from("output2_hi"),
+            String::from("is_valid"),
+        ]
+    }
+}
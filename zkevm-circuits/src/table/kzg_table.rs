@@ -0,0 +1,93 @@
+use super::*;
+use bus_mapping::circuit_input_builder::PointEvaluationEvent;
+
+/// The kzg table is used to verify the KZG point evaluation precompile calls,
+/// whose input is 192 bytes long.  The result of the evaluation is given by
+/// the public inputs of the KZG circuit, see [`crate::kzg_circuit`].
+#[derive(Clone, Debug)]
+pub struct KzgTable {
+    /// Indicates whether or not the row holds a point evaluation.
+    pub q_enable: Column<Fixed>,
+    /// Input bytes as `RLC(reversed(input))`.
+    pub input_rlc: Column<Advice>,
+    /// Whether the versioned hash matches the commitment and the proof opens
+    /// the commitment at `z` to `y`.
+    pub is_valid: Column<Advice>,
+}
+
+impl KzgTable {
+    /// Construct the KzgTable.
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            q_enable: meta.fixed_column(),
+            input_rlc: meta.advice_column_in(SecondPhase),
+            is_valid: meta.advice_column(),
+        }
+    }
+
+    /// Generate the table row for a point evaluation.
+    pub fn assignment<F: Field>(
+        event: &PointEvaluationEvent,
+        challenges: &Challenges<Value<F>>,
+    ) -> [Value<F>; 2] {
+        let input_rlc = challenges
+            .keccak_input()
+            .map(|challenge| rlc::value(event.input.iter().rev(), challenge));
+        [input_rlc, Value::known(F::from(event.is_valid as u64))]
+    }
+
+    /// Assign witness data from a block to the kzg table in a dev environment,
+    /// without verifying the point evaluations.
+    pub fn dev_load<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        block: &Block<F>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "kzg table (dev load)",
+            |mut region| {
+                let events = block.precompile_events.get_point_evaluation_events();
+                for (offset, event) in events.iter().enumerate() {
+                    region.assign_fixed(
+                        || format!("kzg table q_enable {offset}"),
+                        self.q_enable,
+                        offset,
+                        || Value::known(F::ONE),
+                    )?;
+                    for (column, value) in <KzgTable as LookupTable<F>>::advice_columns(self)
+                        .into_iter()
+                        .zip(Self::assignment(event, challenges))
+                    {
+                        region.assign_advice(
+                            || format!("kzg table row {offset}"),
+                            column,
+                            offset,
+                            || value,
+                        )?;
+                    }
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
+
+impl<F: Field> LookupTable<F> for KzgTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.q_enable.into(),
+            self.input_rlc.into(),
+            self.is_valid.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("q_enable"),
+            String::from("input_rlc"),
+            String::from("is_valid"),
+        ]
+    }
+}
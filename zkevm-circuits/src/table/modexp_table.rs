@@ -0,0 +1,113 @@
+use super::*;
+use bus_mapping::circuit_input_builder::ModExpEvent;
+
+/// The modexp table is used to verify `base ^ exponent % modulus = result`
+/// for the MODEXP precompile, with operands of at most 32 bytes.
+#[derive(Clone, Debug)]
+pub struct ModExpTable {
+    /// Indicates whether or not the row holds a modular exponentiation.
+    pub q_enable: Column<Fixed>,
+    /// Base of the exponentiation.
+    pub base: Word<Column<Advice>>,
+    /// Exponent of the exponentiation.
+    pub exponent: Word<Column<Advice>>,
+    /// Modulus of the exponentiation, which is never zero.
+    pub modulus: Word<Column<Advice>>,
+    /// Result of the exponentiation.
+    pub result: Word<Column<Advice>>,
+}
+
+impl ModExpTable {
+    /// Construct the ModExpTable.
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            q_enable: meta.fixed_column(),
+            base: Word::new([meta.advice_column(), meta.advice_column()]),
+            exponent: Word::new([meta.advice_column(), meta.advice_column()]),
+            modulus: Word::new([meta.advice_column(), meta.advice_column()]),
+            result: Word::new([meta.advice_column(), meta.advice_column()]),
+        }
+    }
+
+    /// Generate the table row for a modular exponentiation.
+    pub fn assignment<F: Field>(event: &ModExpEvent) -> [F; 8] {
+        let [base, exponent, modulus, result] =
+            [event.base, event.exponent, event.modulus, event.result].map(Word::<F>::from);
+        [
+            base.lo(),
+            base.hi(),
+            exponent.lo(),
+            exponent.hi(),
+            modulus.lo(),
+            modulus.hi(),
+            result.lo(),
+            result.hi(),
+        ]
+    }
+
+    /// Assign witness data from a block to the modexp table in a dev
+    /// environment, without verifying the exponentiations.
+    pub fn dev_load<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        block: &Block<F>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "modexp table (dev load)",
+            |mut region| {
+                let events = block.precompile_events.get_modexp_events();
+                for (offset, event) in events.iter().enumerate() {
+                    region.assign_fixed(
+                        || format!("modexp table q_enable {offset}"),
+                        self.q_enable,
+                        offset,
+                        || Value::known(F::ONE),
+                    )?;
+                    for (column, value) in <ModExpTable as LookupTable<F>>::advice_columns(self)
+                        .into_iter()
+                        .zip(Self::assignment::<F>(event))
+                    {
+                        region.assign_advice(
+                            || format!("modexp table row {offset}"),
+                            column,
+                            offset,
+                            || Value::known(value),
+                        )?;
+                    }
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
+
+impl<F: Field> LookupTable<F> for ModExpTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.q_enable.into(),
+            self.base.lo().into(),
+            self.base.hi().into(),
+            self.exponent.lo().into(),
+            self.exponent.hi().into(),
+            self.modulus.lo().into(),
+            self.modulus.hi().into(),
+            self.result.lo().into(),
+            self.result.hi().into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("q_enable"),
+            String::from("base_lo"),
+            String::from("base_hi"),
+            String::from("exponent_lo"),
+            String::from("exponent_hi"),
+            String::from("modulus_lo"),
+            String::from("modulus_hi"),
+            String::from("result_lo"),
+            String::from("result_hi"),
+        ]
+    }
+}
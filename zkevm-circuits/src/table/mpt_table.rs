@@ -43,6 +43,8 @@ impl From<AccountFieldTag> for MPTProofType {
 /// The MptTable shared between MPT Circuit and State Circuit
 #[derive(Clone, Copy, Debug)]
 pub struct MptTable {
+    /// Whether the row is part of the table
+    pub q_enable: Column<Fixed>,
     /// Account address
     pub address: Column<Advice>,
     /// Storage address
@@ -62,26 +64,25 @@ pub struct MptTable {
 impl<F: Field> LookupTable<F> for MptTable {
     fn columns(&self) -> Vec<Column<Any>> {
         vec![
-            self.address,
-            self.storage_key.lo(),
-            self.storage_key.hi(),
-            self.proof_type,
-            self.new_root.lo(),
-            self.new_root.hi(),
-            self.old_root.lo(),
-            self.old_root.hi(),
-            self.new_value.lo(),
-            self.new_value.hi(),
-            self.old_value.lo(),
-            self.old_value.hi(),
+            self.q_enable.into(),
+            self.address.into(),
+            self.storage_key.lo().into(),
+            self.storage_key.hi().into(),
+            self.proof_type.into(),
+            self.new_root.lo().into(),
+            self.new_root.hi().into(),
+            self.old_root.lo().into(),
+            self.old_root.hi().into(),
+            self.new_value.lo().into(),
+            self.new_value.hi().into(),
+            self.old_value.lo().into(),
+            self.old_value.hi().into(),
         ]
-        .into_iter()
-        .map(|col| col.into())
-        .collect::<Vec<Column<Any>>>()
     }
 
     fn annotations(&self) -> Vec<String> {
         vec![
+            String::from("q_enable"),
             String::from("address"),
             String::from("storage_key_lo"),
             String::from("storage_key_hi"),
@@ -102,6 +103,7 @@ impl MptTable {
     /// Construct a new MptTable
     pub(crate) fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
         Self {
+            q_enable: meta.fixed_column(),
             address: meta.advice_column(),
             storage_key: word::Word::new([meta.advice_column(), meta.advice_column()]),
             proof_type: meta.advice_column(),
@@ -166,14 +168,34 @@ impl MptTable {
         Ok(())
     }
 
+    /// Enable the table rows in `0..n_rows` of a region
+    pub(crate) fn assign_q_enable<F: Field>(
+        &self,
+        region: &mut Region<'_, F>,
+        n_rows: usize,
+    ) -> Result<(), Error> {
+        for offset in 0..n_rows {
+            region.assign_fixed(
+                || "assign mpt table q_enable",
+                self.q_enable,
+                offset,
+                || Value::known(F::ONE),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Load the table from `updates`, enabling at least `n_rows` rows so that
+    /// the fixed column doesn't depend on the witness.
     pub(crate) fn load<F: Field>(
         &self,
         layouter: &mut impl Layouter<F>,
         updates: &MptUpdates,
+        n_rows: usize,
     ) -> Result<(), Error> {
         layouter.assign_region(
             || "mpt table",
-            |mut region| self.load_with_region(&mut region, updates),
+            |mut region| self.load_with_region(&mut region, updates, n_rows),
         )
     }
 
@@ -181,8 +203,11 @@ impl MptTable {
         &self,
         region: &mut Region<'_, F>,
         updates: &MptUpdates,
+        n_rows: usize,
     ) -> Result<(), Error> {
-        for (offset, row) in updates.table_assignments().iter().enumerate() {
+        let rows = updates.table_assignments();
+        self.assign_q_enable(region, n_rows.max(rows.len()))?;
+        for (offset, row) in rows.iter().enumerate() {
             self.assign(region, offset, row)?;
         }
         Ok(())
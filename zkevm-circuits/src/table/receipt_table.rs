@@ -0,0 +1,136 @@
+use super::*;
+use eth_types::geth_types::{logs_bloom, Receipt};
+
+/// Tag of a row of the receipt table
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+pub enum ReceiptFieldTag {
+    /// EIP-2718 envelope of the receipt of a transaction, indexed by its id
+    Envelope = 1,
+    /// 16 bytes of the logs bloom of the block, indexed by their limb
+    LogsBloom,
+}
+impl_expr!(ReceiptFieldTag);
+
+/// Number of bytes of a limb of the logs bloom in the receipt table
+pub const N_BYTES_BLOOM_LIMB: usize = 16;
+
+/// The receipt table exposes the receipts of the block transactions, as RLC
+/// of their envelope, and the logs bloom of the block, as verified by the
+/// Receipt circuit.
+#[derive(Clone, Copy, Debug)]
+pub struct ReceiptTable {
+    /// Tag of the row, zero on the rows which are not part of the table
+    pub tag: Column<Advice>,
+    /// Transaction id of the receipt, or index of the bloom limb
+    pub index: Column<Advice>,
+    /// RLC of the envelope by the keccak challenge, or the bloom limb
+    pub value: Column<Advice>,
+    /// Length of the envelope
+    pub len: Column<Advice>,
+}
+
+impl ReceiptTable {
+    /// Construct the ReceiptTable.
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            tag: meta.advice_column(),
+            index: meta.advice_column(),
+            value: meta.advice_column_in(SecondPhase),
+            len: meta.advice_column(),
+        }
+    }
+
+    /// Generate the table rows of the receipts and of the logs bloom of the
+    /// block.
+    pub fn assignments<F: Field>(
+        receipts: &[Receipt],
+        challenges: &Challenges<Value<F>>,
+    ) -> Vec<[Value<F>; 4]> {
+        let envelopes = receipts.iter().enumerate().map(|(i, receipt)| {
+            let envelope = receipt.rlp();
+            [
+                Value::known(F::from(ReceiptFieldTag::Envelope as u64)),
+                Value::known(F::from(i as u64 + 1)),
+                challenges
+                    .keccak_input()
+                    .map(|challenge| rlc::value(envelope.iter().rev(), challenge)),
+                Value::known(F::from(envelope.len() as u64)),
+            ]
+        });
+        let bloom = logs_bloom(receipts);
+        let limbs = bloom
+            .as_bytes()
+            .chunks(N_BYTES_BLOOM_LIMB)
+            .enumerate()
+            .map(|(i, limb)| {
+                [
+                    Value::known(F::from(ReceiptFieldTag::LogsBloom as u64)),
+                    Value::known(F::from(i as u64)),
+                    Value::known(F::from_u128(
+                        limb.iter().fold(0, |acc, byte| acc * 256 + *byte as u128),
+                    )),
+                    Value::known(F::ZERO),
+                ]
+            });
+        envelopes.chain(limbs).collect()
+    }
+
+    /// Assign the receipts and logs bloom of the block to the receipt table
+    /// in a dev environment, without verifying them.
+    pub fn dev_load<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        receipts: &[Receipt],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "receipt table (dev load)",
+            |mut region| {
+                let columns = <ReceiptTable as LookupTable<F>>::advice_columns(self);
+                for column in columns.iter() {
+                    region.assign_advice(
+                        || "receipt table all-zero row",
+                        *column,
+                        0,
+                        || Value::known(F::ZERO),
+                    )?;
+                }
+                for (offset, row) in Self::assignments(receipts, challenges)
+                    .into_iter()
+                    .enumerate()
+                {
+                    for (column, value) in columns.iter().zip_eq(row) {
+                        region.assign_advice(
+                            || format!("receipt table row {}", offset + 1),
+                            *column,
+                            offset + 1,
+                            || value,
+                        )?;
+                    }
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
+
+impl<F: Field> LookupTable<F> for ReceiptTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.tag.into(),
+            self.index.into(),
+            self.value.into(),
+            self.len.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("tag"),
+            String::from("index"),
+            String::from("value"),
+            String::from("len"),
+        ]
+    }
+}
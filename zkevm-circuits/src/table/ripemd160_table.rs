@@ -0,0 +1,126 @@
+use super::*;
+use bus_mapping::circuit_input_builder::Ripemd160Event;
+
+/// Ripemd160 Table, used to verify ripemd160 hashing from RLC'ed input.
+#[derive(Clone, Debug)]
+pub struct Ripemd160Table {
+    /// True when the row is enabled
+    pub is_enabled: Column<Advice>,
+    /// Byte array input as `RLC(reversed(input))`
+    pub input_rlc: Column<Advice>,
+    /// Byte array input length
+    pub input_len: Column<Advice>,
+    /// Output digest as `RLC(reversed(digest))`
+    pub output_rlc: Column<Advice>,
+}
+
+impl<F: Field> LookupTable<F> for Ripemd160Table {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.is_enabled.into(),
+            self.input_rlc.into(),
+            self.input_len.into(),
+            self.output_rlc.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("is_enabled"),
+            String::from("input_rlc"),
+            String::from("input_len"),
+            String::from("output_rlc"),
+        ]
+    }
+}
+
+impl Ripemd160Table {
+    /// Construct a new Ripemd160Table
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            is_enabled: meta.advice_column(),
+            input_rlc: meta.advice_column_in(SecondPhase),
+            input_len: meta.advice_column(),
+            output_rlc: meta.advice_column_in(SecondPhase),
+        }
+    }
+
+    /// Generate the ripemd160 table assignments from a ripemd160 event.
+    pub fn assignments<F: Field>(
+        event: &Ripemd160Event,
+        challenges: &Challenges<Value<F>>,
+    ) -> Vec<[Value<F>; 4]> {
+        let input_rlc = challenges
+            .keccak_input()
+            .map(|challenge| rlc::value(event.input.iter().rev(), challenge));
+        let input_len = F::from(event.input.len() as u64);
+        let output_rlc = challenges
+            .keccak_input()
+            .map(|challenge| rlc::value(event.digest.iter().rev(), challenge));
+
+        vec![[
+            Value::known(F::ONE),
+            input_rlc,
+            Value::known(input_len),
+            output_rlc,
+        ]]
+    }
+
+    /// Assign a table row for ripemd160 table
+    pub fn assign_row<F: Field>(
+        &self,
+        region: &mut Region<F>,
+        offset: usize,
+        values: [Value<F>; 4],
+    ) -> Result<(), Error> {
+        for (&column, value) in <Ripemd160Table as LookupTable<F>>::advice_columns(self)
+            .iter()
+            .zip(values.iter())
+        {
+            region.assign_advice(|| format!("assign {}", offset), column, offset, || *value)?;
+        }
+        Ok(())
+    }
+
+    /// Provide this function for the case that we want to consume a ripemd160
+    /// table but without running the full ripemd160 circuit
+    pub fn dev_load<'a, F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        events: impl IntoIterator<Item = &'a Ripemd160Event> + Clone,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "ripemd160 table",
+            |mut region| {
+                let mut offset = 0;
+                for column in <Ripemd160Table as LookupTable<F>>::advice_columns(self) {
+                    region.assign_advice(
+                        || "ripemd160 table all-zero row",
+                        column,
+                        offset,
+                        || Value::known(F::ZERO),
+                    )?;
+                }
+                offset += 1;
+
+                let ripemd160_table_columns =
+                    <Ripemd160Table as LookupTable<F>>::advice_columns(self);
+                for event in events.clone() {
+                    for row in Self::assignments(event, challenges) {
+                        for (&column, value) in ripemd160_table_columns.iter().zip_eq(row) {
+                            region.assign_advice(
+                                || format!("ripemd160 table row {}", offset),
+                                column,
+                                offset,
+                                || value,
+                            )?;
+                        }
+                        offset += 1;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
@@ -0,0 +1,140 @@
+use super::*;
+use eth_types::{sign_types::SignData, Address};
+
+/// The sig table is used to verify signatures, used in tx circuit and ecrecover
+/// precompile.
+#[derive(Clone, Debug)]
+pub struct SigTable {
+    /// Indicates whether or not the gates are enabled on the current row.
+    pub q_enable: Column<Fixed>,
+    /// Keccak256 hash of the message that's signed.
+    pub msg_hash: Word<Column<Advice>>,
+    /// Recovery id of the signature, which is 0 or 1.
+    pub sig_v: Column<Advice>,
+    /// The r-component of the signature.
+    pub sig_r: Word<Column<Advice>>,
+    /// The s-component of the signature.
+    pub sig_s: Word<Column<Advice>>,
+    /// The recovered address, i.e. the 20-bytes address that must have signed
+    /// the message.
+    pub recovered_addr: Word<Column<Advice>>,
+    /// Indicates whether or not the signature is valid, which is 0 only when
+    /// no public key is recovered from it.
+    pub is_valid: Column<Advice>,
+}
+
+impl SigTable {
+    /// Construct the SigTable.
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        let table = Self {
+            q_enable: meta.fixed_column(),
+            msg_hash: Word::new([meta.advice_column(), meta.advice_column()]),
+            sig_v: meta.advice_column(),
+            sig_r: Word::new([meta.advice_column(), meta.advice_column()]),
+            sig_s: Word::new([meta.advice_column(), meta.advice_column()]),
+            recovered_addr: Word::new([meta.advice_column(), meta.advice_column()]),
+            is_valid: meta.advice_column(),
+        };
+        // The SignVerify chip copies its results into the table.
+        for column in <SigTable as LookupTable<F>>::advice_columns(&table) {
+            meta.enable_equality(column);
+        }
+        table
+    }
+
+    /// Generate the table row for a signature, with a zero address for a
+    /// signature that recovers no public key.
+    pub fn assignment<F: Field>(sign_data: &SignData) -> [F; 10] {
+        let msg_hash = Word::from(U256::from_little_endian(&sign_data.msg_hash.to_bytes()));
+        let sig_r = Word::from(U256::from_little_endian(&sign_data.signature.0.to_bytes()));
+        let sig_s = Word::from(U256::from_little_endian(&sign_data.signature.1.to_bytes()));
+        let is_recovered = sign_data.is_recovered();
+        let recovered_addr = Word::from(if is_recovered {
+            sign_data.get_addr()
+        } else {
+            Address::zero()
+        });
+        [
+            msg_hash.lo(),
+            msg_hash.hi(),
+            F::from(sign_data.v as u64),
+            sig_r.lo(),
+            sig_r.hi(),
+            sig_s.lo(),
+            sig_s.hi(),
+            recovered_addr.lo(),
+            recovered_addr.hi(),
+            F::from(is_recovered as u64),
+        ]
+    }
+
+    /// Assign witness data from a block to the sig table in a dev environment,
+    /// without verifying the signatures.
+    pub fn dev_load<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        block: &Block<F>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "sig table (dev load)",
+            |mut region| {
+                let signatures = block.precompile_events.get_ecrecover_events();
+                for (offset, sign_data) in signatures.iter().enumerate() {
+                    region.assign_fixed(
+                        || format!("sig table q_enable {offset}"),
+                        self.q_enable,
+                        offset,
+                        || Value::known(F::ONE),
+                    )?;
+                    for (column, value) in <SigTable as LookupTable<F>>::advice_columns(self)
+                        .into_iter()
+                        .zip(Self::assignment::<F>(sign_data))
+                    {
+                        region.assign_advice(
+                            || format!("sig table row {offset}"),
+                            column,
+                            offset,
+                            || Value::known(value),
+                        )?;
+                    }
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
+
+impl<F: Field> LookupTable<F> for SigTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.q_enable.into(),
+            self.msg_hash.lo().into(),
+            self.msg_hash.hi().into(),
+            self.sig_v.into(),
+            self.sig_r.lo().into(),
+            self.sig_r.hi().into(),
+            self.sig_s.lo().into(),
+            self.sig_s.hi().into(),
+            self.recovered_addr.lo().into(),
+            self.recovered_addr.hi().into(),
+            self.is_valid.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("q_enable"),
+            String::from("msg_hash_lo"),
+            String::from("msg_hash_hi"),
+            String::from("sig_v"),
+            String::from("sig_r_lo"),
+            String::from("sig_r_hi"),
+            String::from("sig_s_lo"),
+            String::from("sig_s_hi"),
+            String::from("recovered_addr_lo"),
+            String::from("recovered_addr_hi"),
+            String::from("is_valid"),
+        ]
+    }
+}
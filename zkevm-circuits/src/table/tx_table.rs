@@ -1,3 +1,5 @@
+use eth_types::evm_types::MAX_BLOBS_PER_BLOCK;
+
 use super::*;
 
 /// Tag used to identify each field in the transaction in a row of the
@@ -29,6 +31,23 @@ pub enum TxFieldTag {
     TxSignHash,
     /// CallData
     CallData,
+    /// Number of blob versioned hashes carried by the transaction (EIP-4844)
+    BlobVersionedHashesLen,
+    /// BlobVersionedHash, indexed by the position of the blob in the transaction
+    BlobVersionedHash,
+    /// Transaction type (EIP-2718), 0 for legacy transactions
+    TxType,
+    /// Number of addresses in the access list (EIP-2930)
+    AccessListAddressesLen,
+    /// MaxFeePerGas (EIP-1559), equal to GasPrice for transactions without
+    /// dynamic fees
+    MaxFeePerGas,
+    /// Whether the signature commits to the chain id (EIP-155), 0 only for
+    /// legacy transactions signed before EIP-155
+    IsReplayProtected,
+    /// Number of the block of the transaction, in a chunk of consecutive
+    /// blocks
+    BlockNumber,
 }
 impl_expr!(TxFieldTag);
 
@@ -41,6 +60,10 @@ pub enum TxLogFieldTag {
     Topic,
     /// Data field
     Data,
+    /// Number of topics
+    TopicLength,
+    /// Number of data bytes
+    DataLength,
 }
 impl_expr!(TxLogFieldTag);
 
@@ -147,10 +170,12 @@ impl TxTable {
                 offset += 1;
 
                 // Tx Table contains an initial region that has a size parametrized by max_txs
-                // with all the tx data except for calldata, and then a second
+                // with all the tx data except for blob hashes and calldata, then a region of
+                // MAX_BLOBS_PER_BLOCK rows with the blob versioned hashes, and then a last
                 // region that has a size parametrized by max_calldata with all
                 // the tx calldata.  This is required to achieve a constant fixed column tag
                 // regardless of the number of input txs or the calldata size of each tx.
+                let mut blob_hash_assignments: Vec<[Value<F>; 5]> = Vec::new();
                 let mut calldata_assignments: Vec<[Value<F>; 5]> = Vec::new();
                 // Assign Tx data (all tx fields except for calldata)
                 let padding_txs: Vec<_> = (txs.len()..max_txs)
@@ -183,6 +208,30 @@ impl TxTable {
                             TxContextFieldTag::CallDataGasCost,
                             word::Word::from(tx.call_data_gas_cost()),
                         ),
+                        (
+                            TxContextFieldTag::BlobVersionedHashesLen,
+                            word::Word::from(tx.blob_versioned_hashes.len() as u64),
+                        ),
+                        (
+                            TxContextFieldTag::TxType,
+                            word::Word::from(tx.transaction_type.as_u64()),
+                        ),
+                        (
+                            TxContextFieldTag::AccessListAddressesLen,
+                            word::Word::from(tx.access_list_addresses_len()),
+                        ),
+                        (
+                            TxContextFieldTag::MaxFeePerGas,
+                            word::Word::from(tx.max_fee_per_gas()),
+                        ),
+                        (
+                            TxContextFieldTag::IsReplayProtected,
+                            word::Word::from(tx.is_replay_protected()),
+                        ),
+                        (
+                            TxContextFieldTag::BlockNumber,
+                            word::Word::from(tx.block_num),
+                        ),
                     ]
                     .iter()
                     .map(|&(tag, word)| {
@@ -195,6 +244,21 @@ impl TxTable {
                         ]
                     })
                     .collect_vec();
+                    let tx_blob_hashes = tx
+                        .blob_versioned_hashes
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, hash)| {
+                            let word = word::Word::<F>::from(*hash);
+                            [
+                                tx_id,
+                                Value::known(F::from(TxContextFieldTag::BlobVersionedHash as u64)),
+                                Value::known(F::from(idx as u64)),
+                                Value::known(word.lo()),
+                                Value::known(word.hi()),
+                            ]
+                        })
+                        .collect_vec();
                     let tx_calldata = tx
                         .call_data
                         .iter()
@@ -213,8 +277,29 @@ impl TxTable {
                         assign_row(&mut region, offset, &advice_columns, &self.tag, &row, "")?;
                         offset += 1;
                     }
+                    blob_hash_assignments.extend(tx_blob_hashes.iter());
                     calldata_assignments.extend(tx_calldata.iter());
                 }
+                // Assign Tx blob versioned hashes
+                assert!(
+                    blob_hash_assignments.len() <= MAX_BLOBS_PER_BLOCK,
+                    "blob_hashes.len() <= MAX_BLOBS_PER_BLOCK: blob_hashes.len()={}",
+                    blob_hash_assignments.len(),
+                );
+                let padding_blob_hashes =
+                    (blob_hash_assignments.len()..MAX_BLOBS_PER_BLOCK).map(|_| {
+                        [
+                            Value::known(F::ZERO),
+                            Value::known(F::from(TxContextFieldTag::BlobVersionedHash as u64)),
+                            Value::known(F::ZERO),
+                            Value::known(F::ZERO),
+                            Value::known(F::ZERO),
+                        ]
+                    });
+                for row in blob_hash_assignments.into_iter().chain(padding_blob_hashes) {
+                    assign_row(&mut region, offset, &advice_columns, &self.tag, &row, "")?;
+                    offset += 1;
+                }
                 // Assign Tx calldata
                 let padding_calldata = (sum_txs_calldata..max_calldata).map(|_| {
                     [
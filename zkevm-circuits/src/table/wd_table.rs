@@ -2,9 +2,13 @@ use bus_mapping::circuit_input_builder::Withdrawal;
 
 use super::*;
 
-/// Table that contains the fields of all Withdrawals in a block
+/// Table that contains the fields of all Withdrawals in a block.  The
+/// withdrawals are indexed from 1, in the order they are processed, and are
+/// followed by all-zero padding withdrawals.  The row at offset 0 is empty.
 #[derive(Clone, Debug)]
 pub struct WdTable {
+    /// withdrawal index in the block, starting from 1
+    pub index: Column<Fixed>,
     /// withdrawal id
     pub id: Column<Advice>,
     /// validator id
@@ -19,6 +23,7 @@ impl WdTable {
     /// Construct a new WdTable
     pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
         Self {
+            index: meta.fixed_column(),
             id: meta.advice_column(),
             validator_id: meta.advice_column(),
             address: Word::new([meta.advice_column(), meta.advice_column()]),
@@ -40,24 +45,6 @@ impl WdTable {
             max_withdrawals
         );
 
-        fn assign_row<F: Field>(
-            region: &mut Region<'_, F>,
-            offset: usize,
-            advice_columns: &[Column<Advice>],
-            row: &[Value<F>; 5],
-            msg: &str,
-        ) -> Result<(), Error> {
-            for (index, column) in advice_columns.iter().enumerate() {
-                region.assign_advice(
-                    || format!("wd table {} row {}", msg, offset),
-                    *column,
-                    offset,
-                    || row[if index > 0 { index + 1 } else { index }],
-                )?;
-            }
-            Ok(())
-        }
-
         layouter.assign_region(
             || "wd table",
             |mut region| {
@@ -69,15 +56,20 @@ impl WdTable {
                     self.amount,
                 ];
 
-                // Assign withdrawal data
-                let padding_withdrawals: Vec<_> = (withdrawals.len()..max_withdrawals)
-                    .map(|i| Withdrawal::padding_withdrawal(i + 1))
-                    .collect();
-                for (offset, wd) in withdrawals
-                    .iter()
-                    .chain(padding_withdrawals.iter())
+                // Assign the empty row followed by the withdrawal data and padding
+                let padding = Withdrawal::default();
+                for (offset, wd) in once(&padding)
+                    .chain(withdrawals.iter())
+                    .chain(std::iter::repeat(&padding))
+                    .take(max_withdrawals + 1)
                     .enumerate()
                 {
+                    region.assign_fixed(
+                        || format!("wd table index {}", offset),
+                        self.index,
+                        offset,
+                        || Value::known(F::from(offset as u64)),
+                    )?;
                     let address_word = Word::from(wd.address);
                     let row = [
                         Value::known(F::from(wd.id)),
@@ -86,13 +78,14 @@ impl WdTable {
                         Value::known(address_word.hi()),
                         Value::known(F::from(wd.amount)),
                     ];
-                    assign_row(
-                        &mut region,
-                        offset,
-                        &advice_columns,
-                        &row,
-                        "assign wd table",
-                    )?;
+                    for (column, value) in advice_columns.iter().zip_eq(row) {
+                        region.assign_advice(
+                            || format!("wd table row {}", offset),
+                            *column,
+                            offset,
+                            || value,
+                        )?;
+                    }
                 }
 
                 Ok(())
@@ -104,6 +97,7 @@ impl WdTable {
 impl<F: Field> LookupTable<F> for WdTable {
     fn columns(&self) -> Vec<Column<Any>> {
         vec![
+            self.index.into(),
             self.id.into(),
             self.validator_id.into(),
             self.address.lo().into(),
@@ -114,6 +108,7 @@ impl<F: Field> LookupTable<F> for WdTable {
 
     fn annotations(&self) -> Vec<String> {
         vec![
+            String::from("index"),
             String::from("id"),
             String::from("validator_id"),
             String::from("address_lo"),
@@ -124,6 +119,7 @@ impl<F: Field> LookupTable<F> for WdTable {
 
     fn table_exprs(&self, meta: &mut VirtualCells<F>) -> Vec<Expression<F>> {
         vec![
+            meta.query_fixed(self.index, Rotation::cur()),
             meta.query_advice(self.id, Rotation::cur()),
             meta.query_advice(self.validator_id, Rotation::cur()),
             meta.query_advice(self.address.lo(), Rotation::cur()),
@@ -4,6 +4,7 @@
 // - *_be: Big-Endian bytes
 // - *_le: Little-Endian bytes
 
+pub(crate) mod rlp;
 pub mod sign_verify;
 
 #[cfg(any(test, feature = "test-circuits"))]
@@ -14,26 +15,94 @@ mod test;
 pub use dev::TxCircuit as TestTxCircuit;
 
 use crate::{
-    table::{KeccakTable, TxFieldTag, TxTable},
-    util::{word::Word, Challenges, SubCircuit, SubCircuitConfig},
+    evm_circuit::util::{
+        constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+        not, rlc,
+    },
+    table::{
+        BlockContextFieldTag, BlockTable, KeccakTable, LookupTable, SigTable, TxFieldTag, TxTable,
+        UXTable,
+    },
+    util::{word::Word, Challenges, Expr, SubCircuit, SubCircuitConfig},
     witness,
 };
-use eth_types::{geth_types::Transaction, sign_types::SignData, Field};
+use eth_types::{
+    evm_types::MAX_BLOBS_PER_BLOCK,
+    geth_types::{
+        Transaction, ACCESS_LIST_TX_TYPE, BLOB_TX_TYPE, DYNAMIC_FEE_TX_TYPE, LEGACY_TX_TYPE,
+    },
+    sign_types::SignData,
+    Field,
+};
+use gadgets::is_zero::{IsZeroChip, IsZeroConfig, IsZeroInstruction};
 use halo2_proofs::{
     circuit::{AssignedCell, Layouter, Region, Value},
-    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, Selector,
+        VirtualCells,
+    },
+    poly::Rotation,
 };
 use itertools::Itertools;
 use log::error;
+use rlp::{tx_rlp_rows, TxRlpConfig, N_BYTES_TX_ENVELOPE};
 use sign_verify::{AssignedSignatureVerify, SignVerifyChip, SignVerifyConfig};
 use std::{marker::PhantomData, ops::Deref};
 
 /// Number of static fields per tx: [nonce, gas, gas_price,
 /// caller_address, callee_address, is_create, value, call_data_length,
-/// call_data_gas_cost, tx_sign_hash].
-/// Note that call data bytes are laid out in the TxTable after all the static
-/// fields arranged by txs.
-pub(crate) const TX_LEN: usize = 10;
+/// call_data_gas_cost, blob_versioned_hashes_len, tx_type,
+/// access_list_addresses_len, max_fee_per_gas, is_replay_protected,
+/// block_number, tx_sign_hash].
+/// Note that blob versioned hashes and call data bytes are laid out in the
+/// TxTable after all the static fields arranged by txs.
+pub(crate) const TX_LEN: usize = 16;
+
+/// Tags of the static fields of a tx, in the order they are laid out in the
+/// TxTable.
+const TX_FIELD_TAGS: [TxFieldTag; TX_LEN] = [
+    TxFieldTag::Nonce,
+    TxFieldTag::Gas,
+    TxFieldTag::GasPrice,
+    TxFieldTag::CallerAddress,
+    TxFieldTag::CalleeAddress,
+    TxFieldTag::IsCreate,
+    TxFieldTag::Value,
+    TxFieldTag::CallDataLength,
+    TxFieldTag::CallDataGasCost,
+    TxFieldTag::BlobVersionedHashesLen,
+    TxFieldTag::TxType,
+    TxFieldTag::AccessListAddressesLen,
+    TxFieldTag::MaxFeePerGas,
+    TxFieldTag::IsReplayProtected,
+    TxFieldTag::BlockNumber,
+    TxFieldTag::TxSignHash,
+];
+
+/// Supported transaction types (EIP-2718).
+const TX_TYPES: [u64; 4] = [
+    LEGACY_TX_TYPE,
+    ACCESS_LIST_TX_TYPE,
+    DYNAMIC_FEE_TX_TYPE,
+    BLOB_TX_TYPE,
+];
+
+/// Query the value of the static field with the given tag of a tx, from the
+/// TxSignHash row of the tx, which is the last of its static fields.
+fn query_tx_field<F: Field>(
+    meta: &mut VirtualCells<'_, F>,
+    value: Word<Column<Advice>>,
+    tag: TxFieldTag,
+) -> Word<Expression<F>> {
+    let position = |tag| {
+        TX_FIELD_TAGS
+            .iter()
+            .position(|field_tag| *field_tag == tag)
+            .expect("tag of a static tx field") as i32
+    };
+    let rotation = Rotation(position(tag) - position(TxFieldTag::TxSignHash));
+    value.map(|column| meta.query_advice(column, rotation))
+}
 
 /// Config for TxCircuit
 #[derive(Clone, Debug)]
@@ -42,6 +111,38 @@ pub struct TxCircuitConfig<F: Field> {
     tag: Column<Fixed>,
     index: Column<Advice>,
     value: Word<Column<Advice>>,
+    /// Enabled at the TxSignHash row of every tx
+    q_sign_hash: Selector,
+    /// RLC of the message signed by the sender of the tx, which is the
+    /// EIP-2718 envelope for typed txs
+    sign_rlp_rlc: Column<Advice>,
+    /// Length of the message signed by the sender of the tx
+    sign_rlp_len: Column<Advice>,
+    /// `v` of the signature of the tx, which encodes its recovery id following
+    /// the signing scheme of the tx
+    sig_v: Column<Advice>,
+    sig_recovery_id: Column<Advice>,
+    /// Chain id of the block, which EIP-155 txs encode in `v`
+    chain_id: Column<Advice>,
+    /// Enabled on the rows of the signed messages of the txs
+    q_payload: Column<Fixed>,
+    /// Enabled on the first row of the signed messages of the txs
+    q_payload_first: Column<Fixed>,
+    /// Byte of the message signed by the sender of a tx, one per row
+    payload_byte: Column<Advice>,
+    /// Id of the tx of the signed message
+    payload_tx_id: Column<Advice>,
+    /// First byte of a signed message
+    payload_is_first: Column<Advice>,
+    /// Rows after the last signed message
+    payload_is_padding: Column<Advice>,
+    /// Length and RLC of the signed message up to the row
+    payload_len: Column<Advice>,
+    payload_rlc: Column<Advice>,
+    /// Decoding of the signed messages, binding their fields to the tx table
+    payload_rlp: TxRlpConfig,
+    /// Padding txs have a zero caller address
+    is_caller_zero: IsZeroConfig<F>,
     sign_verify: SignVerifyConfig,
     _marker: PhantomData<F>,
 }
@@ -50,8 +151,14 @@ pub struct TxCircuitConfig<F: Field> {
 pub struct TxCircuitConfigArgs<F: Field> {
     /// TxTable
     pub tx_table: TxTable,
+    /// BlockTable
+    pub block_table: BlockTable,
     /// KeccakTable
     pub keccak_table: KeccakTable,
+    /// SigTable
+    pub sig_table: SigTable,
+    /// U16Table
+    pub u16_table: UXTable<16>,
     /// Challenges
     pub challenges: Challenges<Expression<F>>,
 }
@@ -64,7 +171,10 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
         meta: &mut ConstraintSystem<F>,
         Self::ConfigArgs {
             tx_table,
+            block_table,
             keccak_table,
+            sig_table,
+            u16_table,
             challenges,
         }: Self::ConfigArgs,
     ) -> Self {
@@ -75,13 +185,263 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
         meta.enable_equality(value.lo());
         meta.enable_equality(value.hi());
 
-        let sign_verify = SignVerifyConfig::new(meta, keccak_table, challenges);
+        let q_sign_hash = meta.complex_selector();
+        let sign_rlp_rlc = meta.advice_column_in(SecondPhase);
+        let sign_rlp_len = meta.advice_column();
+        let sig_v = meta.advice_column();
+        let sig_recovery_id = meta.advice_column();
+        let chain_id = meta.advice_column();
+        let caller_inv = meta.advice_column();
+
+        // The static fields of a tx are assigned in consecutive rows, so the constraints
+        // below are enabled at the TxSignHash row and query the other fields of the same
+        // tx at a fixed rotation.  Address limbs are smaller than 128 bits, so their sum
+        // is zero only when both of them are zero.
+        let is_caller_zero = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_selector(q_sign_hash),
+            |meta| {
+                let caller = query_tx_field(meta, value, TxFieldTag::CallerAddress);
+                caller.lo() + caller.hi()
+            },
+            caller_inv,
+        );
+
+        meta.create_gate("tx type", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let tx_type = query_tx_field(meta, value, TxFieldTag::TxType);
+            cb.require_zero("tx type fits in the low limb", tx_type.hi());
+            cb.require_in_set(
+                "tx type is supported",
+                tx_type.lo(),
+                TX_TYPES.iter().map(|tx_type| tx_type.expr()).collect(),
+            );
+            // Lagrange basis over the supported tx types: 1 for the given tx type and 0
+            // for the others.
+            let tx_type_lo = tx_type.lo();
+            let is_tx_type = |expected: u64| {
+                TX_TYPES
+                    .iter()
+                    .filter(|other| **other != expected)
+                    .fold(1.expr(), |acc, other| {
+                        let denominator = F::from(expected) - F::from(*other);
+                        acc * (tx_type_lo.clone() - other.expr())
+                            * Expression::Constant(denominator.invert().unwrap())
+                    })
+            };
+            let is_legacy = is_tx_type(LEGACY_TX_TYPE);
+            let is_access_list = is_tx_type(ACCESS_LIST_TX_TYPE);
+            let is_blob = is_tx_type(BLOB_TX_TYPE);
+
+            cb.condition(not::expr(is_blob), |cb| {
+                cb.require_zero_word(
+                    "only blob txs carry blob versioned hashes",
+                    query_tx_field(meta, value, TxFieldTag::BlobVersionedHashesLen),
+                );
+            });
+            cb.condition(is_legacy.clone(), |cb| {
+                cb.require_zero_word(
+                    "legacy txs have no access list",
+                    query_tx_field(meta, value, TxFieldTag::AccessListAddressesLen),
+                );
+            });
+
+            // Legacy txs are signed either with (EIP-155) or without the chain id, while
+            // typed txs always commit to it.
+            let is_replay_protected = query_tx_field(meta, value, TxFieldTag::IsReplayProtected);
+            cb.require_zero(
+                "is_replay_protected fits in the low limb",
+                is_replay_protected.hi(),
+            );
+            cb.require_boolean("is_replay_protected is boolean", is_replay_protected.lo());
+            cb.condition(not::expr(is_legacy.clone()), |cb| {
+                cb.require_true("typed txs are replay protected", is_replay_protected.lo());
+            });
+            // The scheme is decided by the signed message, in which the chain id is bound
+            // by the TxRlpConfig, and must agree with `v`, which is 27/28 for legacy txs
+            // signed before EIP-155, chain_id * 2 + 35/36 for the other legacy txs and the
+            // recovery id for typed txs.
+            let recovery_id = meta.query_advice(sig_recovery_id, Rotation::cur());
+            cb.require_boolean("recovery id is boolean", recovery_id.expr());
+            cb.condition(not::expr(is_caller_zero.expr()), |cb| {
+                cb.require_equal(
+                    "v encodes the recovery id following the signing scheme",
+                    meta.query_advice(sig_v, Rotation::cur()),
+                    recovery_id
+                        + is_legacy.clone()
+                            * (not::expr(is_replay_protected.lo()) * 27.expr()
+                                + is_replay_protected.lo()
+                                    * (meta.query_advice(chain_id, Rotation::cur()) * 2.expr()
+                                        + 35.expr())),
+                );
+            });
+            cb.condition(is_legacy + is_access_list, |cb| {
+                cb.require_equal_word(
+                    "max fee per gas is the gas price for txs without dynamic fees",
+                    query_tx_field(meta, value, TxFieldTag::MaxFeePerGas),
+                    query_tx_field(meta, value, TxFieldTag::GasPrice),
+                );
+            });
+
+            cb.gate(meta.query_selector(q_sign_hash))
+        });
+
+        // The messages signed by the senders of the txs are laid out one byte per row
+        // after each other, followed by padding, and decoded by the TxRlpConfig, which
+        // binds their fields to the tx table.
+        let q_payload = meta.fixed_column();
+        let q_payload_first = meta.fixed_column();
+        let payload_byte = meta.advice_column();
+        let payload_tx_id = meta.advice_column();
+        let payload_is_first = meta.advice_column();
+        let payload_is_padding = meta.advice_column();
+        let payload_len = meta.advice_column();
+        let payload_rlc = meta.advice_column_in(SecondPhase);
+        let payload_rlp = TxRlpConfig::configure(
+            meta,
+            |meta| {
+                meta.query_fixed(q_payload, Rotation::cur())
+                    * not::expr(meta.query_advice(payload_is_padding, Rotation::cur()))
+            },
+            |meta| meta.query_advice(payload_is_first, Rotation::cur()),
+            |meta| meta.query_advice(payload_tx_id, Rotation::cur()),
+            payload_byte,
+            false,
+            &tx_table,
+            &block_table,
+            &u16_table,
+        );
+
+        meta.create_gate("tx sign payload", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let q_first = meta.query_fixed(q_payload_first, Rotation::cur());
+            let padding = meta.query_advice(payload_is_padding, Rotation::cur());
+            let first = meta.query_advice(payload_is_first, Rotation::cur());
+            let end_prev = payload_rlp.is_end(meta, Rotation::prev());
+
+            cb.require_boolean("is_padding is boolean", padding.expr());
+            cb.require_zero(
+                "padding is followed by padding",
+                not::expr(q_first.expr())
+                    * meta.query_advice(payload_is_padding, Rotation::prev())
+                    * not::expr(padding.expr()),
+            );
+            cb.require_equal(
+                "a message starts on the first row or after the previous message",
+                first.expr(),
+                not::expr(padding.expr()) * (q_first.expr() + not::expr(q_first) * end_prev),
+            );
+            cb.require_zero(
+                "no message ends in the padding",
+                padding.expr() * payload_rlp.is_end(meta, Rotation::cur()),
+            );
+            cb.require_zero(
+                "tx_id is carried in the message",
+                not::expr(padding.expr())
+                    * not::expr(first.expr())
+                    * (meta.query_advice(payload_tx_id, Rotation::cur())
+                        - meta.query_advice(payload_tx_id, Rotation::prev())),
+            );
+            cb.require_equal(
+                "len = len_prev + 1 in the message",
+                meta.query_advice(payload_len, Rotation::cur()),
+                not::expr(padding.expr())
+                    * (not::expr(first.expr()) * meta.query_advice(payload_len, Rotation::prev())
+                        + 1.expr()),
+            );
+            cb.require_equal(
+                "rlc = rlc_prev * r + byte in the message",
+                meta.query_advice(payload_rlc, Rotation::cur()),
+                not::expr(padding)
+                    * (not::expr(first)
+                        * meta.query_advice(payload_rlc, Rotation::prev())
+                        * challenges.keccak_input()
+                        + meta.query_advice(payload_byte, Rotation::cur())),
+            );
+
+            cb.gate(meta.query_fixed(q_payload, Rotation::cur()))
+        });
+
+        meta.lookup_any("tx chain id", |meta| {
+            let is_enabled = meta.query_selector(q_sign_hash);
+            let input = [
+                is_enabled.clone() * BlockContextFieldTag::ChainId.expr(),
+                0.expr(),
+                is_enabled * meta.query_advice(chain_id, Rotation::cur()),
+                0.expr(),
+            ];
+            let table = block_table.table_exprs(meta);
+
+            input.into_iter().zip(table).collect()
+        });
+
+        // Verify that the signed message of every tx is decoded in the signed messages,
+        // which binds its fields to the tx.
+        meta.lookup_any("tx sign payload", |meta| {
+            let is_enabled = meta.query_selector(q_sign_hash) * not::expr(is_caller_zero.expr());
+            let input = [
+                is_enabled.clone() * meta.query_advice(tx_id, Rotation::cur()),
+                is_enabled.clone() * meta.query_advice(sign_rlp_rlc, Rotation::cur()),
+                is_enabled.clone() * meta.query_advice(sign_rlp_len, Rotation::cur()),
+                is_enabled,
+            ];
+            let is_end = meta.query_fixed(q_payload, Rotation::cur())
+                * payload_rlp.is_end(meta, Rotation::cur());
+            let table = [
+                is_end.clone() * meta.query_advice(payload_tx_id, Rotation::cur()),
+                is_end.clone() * meta.query_advice(payload_rlc, Rotation::cur()),
+                is_end.clone() * meta.query_advice(payload_len, Rotation::cur()),
+                is_end,
+            ];
+
+            input.into_iter().zip(table).collect()
+        });
+
+        // Verify that TxSignHash = keccak(sign_rlp), where sign_rlp is the RLP encoding of
+        // the unsigned tx prefixed by its type for typed txs, which includes the chain id
+        // unless the tx is a legacy tx signed before EIP-155.  TxSignHash is copy
+        // constrained to the message hash whose signature is verified by the
+        // SignVerifyChip, so the signature is verified over the typed envelope.
+        meta.lookup_any("tx sign hash keccak", |meta| {
+            let is_enabled = meta.query_selector(q_sign_hash) * not::expr(is_caller_zero.expr());
+            let sign_hash = query_tx_field(meta, value, TxFieldTag::TxSignHash);
+            let input = [
+                is_enabled.clone(),
+                is_enabled.clone() * meta.query_advice(sign_rlp_rlc, Rotation::cur()),
+                is_enabled.clone() * meta.query_advice(sign_rlp_len, Rotation::cur()),
+                is_enabled.clone() * sign_hash.lo(),
+                is_enabled * sign_hash.hi(),
+            ];
+            let table = keccak_table.table_exprs(meta);
+
+            input.into_iter().zip(table).collect()
+        });
+
+        let sign_verify = SignVerifyConfig::new(meta, keccak_table, sig_table, challenges);
 
         Self {
             tx_id,
             tag,
             index,
             value,
+            q_sign_hash,
+            sign_rlp_rlc,
+            sign_rlp_len,
+            sig_v,
+            sig_recovery_id,
+            chain_id,
+            q_payload,
+            q_payload_first,
+            payload_byte,
+            payload_tx_id,
+            payload_is_first,
+            payload_is_padding,
+            payload_len,
+            payload_rlc,
+            payload_rlp,
+            is_caller_zero,
             sign_verify,
             _marker: PhantomData,
         }
@@ -89,9 +449,10 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
 }
 
 impl<F: Field> TxCircuitConfig<F> {
-    /// Load ECDSA RangeChip table.
+    /// Load ECDSA RangeChip table and the fields of the signed messages.
     pub fn load_aux_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
-        self.sign_verify.load_range(layouter)
+        self.sign_verify.load_range(layouter)?;
+        self.payload_rlp.load(layouter)
     }
 
     /// Assigns a tx circuit row and returns the assigned cell of the value in `word` in
@@ -148,33 +509,190 @@ pub struct TxCircuit<F: Field> {
     pub txs: Vec<Transaction>,
     /// Chain ID
     pub chain_id: u64,
+    /// Numbers of the blocks of the transactions in a chunk of consecutive
+    /// blocks, zero when not given
+    pub block_numbers: Vec<u64>,
+    /// Max number of supported ECRECOVER precompile calls
+    pub max_ecrecover: usize,
+    /// Signatures of the ECRECOVER precompile calls, which are verified by the
+    /// SignVerify chip after the tx signatures
+    pub ecrecover_sigs: Vec<SignData>,
+    /// Txs whose messages are signed and decoded in place of the ones of the
+    /// first txs, to test the binding of the signed fields to the tx table
+    #[cfg(test)]
+    signed_txs: Vec<Transaction>,
+    /// Replay protection flags assigned in place of the ones of the first txs,
+    /// to test their binding to the signing scheme
+    #[cfg(test)]
+    is_replay_protected: Vec<bool>,
 }
 
 impl<F: Field> TxCircuit<F> {
     /// Return a new TxCircuit
     pub fn new(max_txs: usize, max_calldata: usize, chain_id: u64, txs: Vec<Transaction>) -> Self {
+        Self::new_with_ecrecover(max_txs, max_calldata, 0, chain_id, txs, vec![])
+    }
+
+    /// Return a new TxCircuit that also verifies the signatures of ECRECOVER
+    /// precompile calls
+    pub fn new_with_ecrecover(
+        max_txs: usize,
+        max_calldata: usize,
+        max_ecrecover: usize,
+        chain_id: u64,
+        txs: Vec<Transaction>,
+        ecrecover_sigs: Vec<SignData>,
+    ) -> Self {
         TxCircuit::<F> {
             max_txs,
             max_calldata,
-            sign_verify: SignVerifyChip::new(max_txs),
+            sign_verify: SignVerifyChip::new(max_txs + max_ecrecover),
             txs,
             chain_id,
+            block_numbers: Vec::new(),
+            max_ecrecover,
+            ecrecover_sigs,
+            #[cfg(test)]
+            signed_txs: Vec::new(),
+            #[cfg(test)]
+            is_replay_protected: Vec::new(),
         }
     }
 
+    /// Return the tx whose message is signed in place of the i-th tx.
+    fn signed_tx(&self, i: usize) -> &Transaction {
+        #[cfg(test)]
+        if let Some(tx) = self.signed_txs.get(i) {
+            return tx;
+        }
+        &self.txs[i]
+    }
+
+    /// Return whether the signature of the i-th tx commits to the chain id.
+    fn is_replay_protected(&self, i: usize, tx: &Transaction) -> bool {
+        #[cfg(test)]
+        if let Some(is_replay_protected) = self.is_replay_protected.get(i) {
+            return *is_replay_protected;
+        }
+        tx.is_replay_protected()
+    }
+
     /// Return the minimum number of rows required to prove an input of a
     /// particular size.
-    pub fn min_num_rows(txs_len: usize, call_data_len: usize) -> usize {
-        let tx_table_len = txs_len * TX_LEN + call_data_len;
-        std::cmp::max(tx_table_len, SignVerifyChip::<F>::min_num_rows(txs_len))
+    pub fn min_num_rows(txs_len: usize, ecrecover_len: usize, call_data_len: usize) -> usize {
+        let tx_table_len = txs_len * TX_LEN + MAX_BLOBS_PER_BLOCK + call_data_len;
+        std::cmp::max(
+            tx_table_len.max(Self::payload_capacity(txs_len, call_data_len)),
+            SignVerifyChip::<F>::min_num_rows(txs_len + ecrecover_len),
+        )
+    }
+
+    /// Return the number of rows of the signed messages of the txs.
+    fn payload_capacity(max_txs: usize, max_calldata: usize) -> usize {
+        max_txs * N_BYTES_TX_ENVELOPE + max_calldata
+    }
+
+    fn assign_sign_payloads(
+        &self,
+        config: &TxCircuitConfig<F>,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let mut rows = vec![];
+        for i in 0..self.txs.len() {
+            let sign_rlp = self.signed_tx(i).rlp_unsigned(self.chain_id).map_err(|e| {
+                error!("rlp_unsigned error for tx {:?}", e);
+                Error::Synthesis
+            })?;
+            rows.extend(
+                tx_rlp_rows(&sign_rlp, false)
+                    .into_iter()
+                    .map(|row| (i + 1, row)),
+            );
+        }
+        let capacity = Self::payload_capacity(self.max_txs, self.max_calldata);
+        if rows.len() > capacity {
+            error!(
+                "signed messages of {} bytes exceed the capacity of {} bytes",
+                rows.len(),
+                capacity
+            );
+            return Err(Error::Synthesis);
+        }
+
+        layouter.assign_region(
+            || "tx sign payloads",
+            |mut region| {
+                let mut rlc = Value::known(F::ZERO);
+                let mut len = 0;
+                let mut prev_end = true;
+                for offset in 0..capacity {
+                    for (name, column, value) in [
+                        ("q_payload", config.q_payload, true),
+                        ("q_payload_first", config.q_payload_first, offset == 0),
+                    ] {
+                        region.assign_fixed(
+                            || name,
+                            column,
+                            offset,
+                            || Value::known(F::from(value as u64)),
+                        )?;
+                    }
+
+                    let (tx_id, byte, is_first) = match rows.get(offset) {
+                        Some((tx_id, row)) => {
+                            config.payload_rlp.assign_row(&mut region, offset, row)?;
+                            let byte = F::from(row.byte as u64);
+                            (rlc, len) = if prev_end {
+                                (Value::known(byte), 1)
+                            } else {
+                                (
+                                    rlc.zip(challenges.keccak_input())
+                                        .map(|(rlc, r)| rlc * r + byte),
+                                    len + 1,
+                                )
+                            };
+                            let is_first = prev_end;
+                            prev_end = row.is_end;
+                            (*tx_id, byte, is_first)
+                        }
+                        None => {
+                            (rlc, len) = (Value::known(F::ZERO), 0);
+                            (0, F::ZERO, false)
+                        }
+                    };
+                    for (name, column, value) in [
+                        ("payload_byte", config.payload_byte, byte),
+                        ("payload_tx_id", config.payload_tx_id, F::from(tx_id as u64)),
+                        (
+                            "payload_is_first",
+                            config.payload_is_first,
+                            F::from(is_first as u64),
+                        ),
+                        (
+                            "payload_is_padding",
+                            config.payload_is_padding,
+                            F::from((offset >= rows.len()) as u64),
+                        ),
+                        ("payload_len", config.payload_len, F::from(len)),
+                    ] {
+                        region.assign_advice(|| name, column, offset, || Value::known(value))?;
+                    }
+                    region.assign_advice(|| "payload_rlc", config.payload_rlc, offset, || rlc)?;
+                }
+                Ok(())
+            },
+        )
     }
 
     fn assign_tx_table(
         &self,
         config: &TxCircuitConfig<F>,
+        challenges: &Challenges<Value<F>>,
         layouter: &mut impl Layouter<F>,
         assigned_sig_verifs: Vec<AssignedSignatureVerify<F>>,
     ) -> Result<(), Error> {
+        let is_caller_zero_chip = IsZeroChip::construct(config.is_caller_zero.clone());
         layouter.assign_region(
             || "tx table",
             |mut region| {
@@ -197,8 +715,21 @@ impl<F: Field> TxCircuit<F> {
                     } else {
                         &tx_default
                     };
+                    // Padding txs have no signed message, and the keccak lookup is disabled
+                    // for them.
+                    let sign_rlp = if i < self.txs.len() {
+                        self.signed_tx(i)
+                            .rlp_unsigned(self.chain_id)
+                            .map_err(|e| {
+                                error!("rlp_unsigned error for tx {:?}", e);
+                                Error::Synthesis
+                            })?
+                            .to_vec()
+                    } else {
+                        vec![]
+                    };
 
-                    for (tag, value) in [
+                    for (j, (tag, value)) in [
                         (
                             TxFieldTag::Nonce,
                             Word::from(tx.nonce.as_u64()).into_value(),
@@ -223,14 +754,42 @@ impl<F: Field> TxCircuit<F> {
                             TxFieldTag::CallDataGasCost,
                             Word::from(tx.call_data_gas_cost()).into_value(),
                         ),
+                        (
+                            TxFieldTag::BlobVersionedHashesLen,
+                            Word::from(tx.blob_versioned_hashes.len() as u64).into_value(),
+                        ),
+                        (
+                            TxFieldTag::TxType,
+                            Word::from(tx.transaction_type.as_u64()).into_value(),
+                        ),
+                        (
+                            TxFieldTag::AccessListAddressesLen,
+                            Word::from(tx.access_list_addresses_len()).into_value(),
+                        ),
+                        (
+                            TxFieldTag::MaxFeePerGas,
+                            Word::from(tx.max_fee_per_gas()).into_value(),
+                        ),
+                        (
+                            TxFieldTag::IsReplayProtected,
+                            Word::from(self.is_replay_protected(i, tx) as u64).into_value(),
+                        ),
+                        (
+                            TxFieldTag::BlockNumber,
+                            Word::from(self.block_numbers.get(i).copied().unwrap_or_default())
+                                .into_value(),
+                        ),
                         (
                             TxFieldTag::TxSignHash,
                             assigned_sig_verif.msg_hash.map(|x| x.value().copied()),
                         ),
-                    ] {
+                    ]
+                    .into_iter()
+                    .enumerate()
+                    {
+                        debug_assert_eq!(TX_FIELD_TAGS[j], tag);
                         let assigned_cell =
                             config.assign_row(&mut region, offset, i + 1, tag, 0, value)?;
-                        offset += 1;
 
                         // Ref. spec 0. Copy constraints using fixed offsets between the tx rows and
                         // the SignVerifyChip
@@ -253,13 +812,86 @@ impl<F: Field> TxCircuit<F> {
                                 region.constrain_equal(
                                     assigned_cell.hi().cell(),
                                     assigned_sig_verif.msg_hash.hi().cell(),
-                                )?
+                                )?;
+
+                                config.q_sign_hash.enable(&mut region, offset)?;
+                                region.assign_advice(
+                                    || "sign_rlp_rlc",
+                                    config.sign_rlp_rlc,
+                                    offset,
+                                    || {
+                                        challenges.keccak_input().map(|challenge| {
+                                            rlc::value(sign_rlp.iter().rev(), challenge)
+                                        })
+                                    },
+                                )?;
+                                region.assign_advice(
+                                    || "sign_rlp_len",
+                                    config.sign_rlp_len,
+                                    offset,
+                                    || Value::known(F::from(sign_rlp.len() as u64)),
+                                )?;
+                                // The recovery id is the parity of the signature point
+                                // computed by the SignVerifyChip.
+                                assigned_sig_verif.sig_v.copy_advice(
+                                    || "sig_recovery_id",
+                                    &mut region,
+                                    config.sig_recovery_id,
+                                    offset,
+                                )?;
+                                for (name, column, value) in [
+                                    ("sig_v", config.sig_v, tx.v),
+                                    ("chain_id", config.chain_id, self.chain_id),
+                                ] {
+                                    region.assign_advice(
+                                        || name,
+                                        column,
+                                        offset,
+                                        || Value::known(F::from(value)),
+                                    )?;
+                                }
+                                let caller = Word::<F>::from(tx.from);
+                                is_caller_zero_chip.assign(
+                                    &mut region,
+                                    offset,
+                                    Value::known(caller.lo() + caller.hi()),
+                                )?;
                             }
                             _ => (),
                         }
+                        offset += 1;
                     }
                 }
 
+                // Assign blob versioned hashes
+                let mut blob_hash_count = 0;
+                for (i, tx) in self.txs.iter().enumerate() {
+                    for (index, hash) in tx.blob_versioned_hashes.iter().enumerate() {
+                        assert!(blob_hash_count < MAX_BLOBS_PER_BLOCK);
+                        config.assign_row(
+                            &mut region,
+                            offset,
+                            i + 1, // tx_id
+                            TxFieldTag::BlobVersionedHash,
+                            index,
+                            Word::from(*hash).into_value(),
+                        )?;
+                        offset += 1;
+                        blob_hash_count += 1;
+                    }
+                }
+                for _ in blob_hash_count..MAX_BLOBS_PER_BLOCK {
+                    config.assign_row(
+                        &mut region,
+                        offset,
+                        0, // tx_id
+                        TxFieldTag::BlobVersionedHash,
+                        0,
+                        Word::default().into_value(),
+                    )?;
+                    offset += 1;
+                }
+
                 // Assign call data
                 let mut calldata_count = 0;
                 for (i, tx) in self.txs.iter().enumerate() {
@@ -298,18 +930,32 @@ impl<F: Field> SubCircuit<F> for TxCircuit<F> {
     type Config = TxCircuitConfig<F>;
 
     fn unusable_rows() -> usize {
-        // No column queried at more than 3 distinct rotations, so returns 6 as
-        // minimum unusable rows.
-        6
+        // Columns value.lo and value.hi of TxTable are queried at 8 distinct
+        // rotations from the TxSignHash row of a tx
+        // - Rotation(0)
+        // - Rotation(-2)
+        // - Rotation(-3)
+        // - Rotation(-4)
+        // - Rotation(-5)
+        // - Rotation(-6)
+        // - Rotation(-12)
+        // - Rotation(-13)
+        // so returns 8 + 3 unusable rows.
+        11
     }
 
     fn new_from_block(block: &witness::Block<F>) -> Self {
-        Self::new(
-            block.circuits_params.max_txs,
-            block.circuits_params.max_calldata,
-            block.context.chain_id.as_u64(),
-            block.txs.iter().map(|tx| tx.deref().clone()).collect_vec(),
-        )
+        Self {
+            block_numbers: block.txs.iter().map(|tx| tx.block_num).collect_vec(),
+            ..Self::new_with_ecrecover(
+                block.circuits_params.max_txs,
+                block.circuits_params.max_calldata,
+                block.circuits_params.max_ecrecover,
+                block.context.chain_id.as_u64(),
+                block.txs.iter().map(|tx| tx.deref().clone()).collect_vec(),
+                block.precompile_events.get_ecrecover_events(),
+            )
+        }
     }
 
     /// Return the minimum number of rows required to prove the block
@@ -317,10 +963,12 @@ impl<F: Field> SubCircuit<F> for TxCircuit<F> {
         (
             Self::min_num_rows(
                 block.txs.len(),
+                block.precompile_events.get_ecrecover_events().len(),
                 block.txs.iter().map(|tx| tx.call_data.len()).sum(),
             ),
             Self::min_num_rows(
                 block.circuits_params.max_txs,
+                block.circuits_params.max_ecrecover,
                 block.circuits_params.max_calldata,
             ),
         )
@@ -334,22 +982,39 @@ impl<F: Field> SubCircuit<F> for TxCircuit<F> {
         layouter: &mut impl Layouter<F>,
     ) -> Result<(), Error> {
         assert!(self.txs.len() <= self.max_txs);
-        let sign_datas: Vec<SignData> = self
-            .txs
-            .iter()
-            .map(|tx| {
-                tx.sign_data(self.chain_id).map_err(|e| {
+        assert!(self.ecrecover_sigs.len() <= self.max_ecrecover);
+        let sign_datas: Vec<SignData> = (0..self.txs.len())
+            .map(|i| {
+                self.signed_tx(i).sign_data(self.chain_id).map_err(|e| {
                     error!("tx_to_sign_data error for tx {:?}", e);
                     Error::Synthesis
                 })
             })
             .try_collect()?;
 
+        // The signatures of the txs are followed by the ones of the ecrecover calls, each
+        // of them padded to their maximum, so that the tx table rows are copy constrained
+        // at fixed offsets.
+        let pad = |sigs: Vec<SignData>, max: usize| {
+            let padding = max - sigs.len();
+            sigs.into_iter()
+                .map(Some)
+                .chain(std::iter::repeat(None).take(padding))
+        };
+        let signatures = pad(sign_datas, self.max_txs)
+            .chain(pad(self.ecrecover_sigs.clone(), self.max_ecrecover))
+            .collect_vec();
+
         config.load_aux_tables(layouter)?;
-        let assigned_sig_verifs =
-            self.sign_verify
-                .assign(&config.sign_verify, layouter, &sign_datas, challenges)?;
-        self.assign_tx_table(config, layouter, assigned_sig_verifs)?;
+        let mut assigned_sig_verifs = self.sign_verify.assign_with_padding(
+            &config.sign_verify,
+            layouter,
+            &signatures,
+            challenges,
+        )?;
+        assigned_sig_verifs.truncate(self.max_txs);
+        self.assign_tx_table(config, challenges, layouter, assigned_sig_verifs)?;
+        self.assign_sign_payloads(config, challenges, layouter)?;
         Ok(())
     }
 
@@ -1,11 +1,12 @@
 pub use super::TxCircuit;
 
 use crate::{
-    table::{KeccakTable, TxTable},
+    table::{BlockTable, KeccakTable, SigTable, TxTable, UXTable},
     tx_circuit::{TxCircuitConfig, TxCircuitConfigArgs},
     util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness::BlockContext,
 };
-use bus_mapping::circuit_input_builder::keccak_inputs_tx_circuit;
+use bus_mapping::circuit_input_builder::{keccak_inputs_sign_verify, keccak_inputs_tx_circuit};
 use eth_types::Field;
 use halo2_proofs::{
     circuit::{Layouter, SimpleFloorPlanner},
@@ -14,7 +15,13 @@ use halo2_proofs::{
 use log::error;
 
 impl<F: Field> Circuit<F> for TxCircuit<F> {
-    type Config = (TxCircuitConfig<F>, Challenges, KeccakTable);
+    type Config = (
+        TxCircuitConfig<F>,
+        Challenges,
+        KeccakTable,
+        BlockTable,
+        UXTable<16>,
+    );
     type FloorPlanner = SimpleFloorPlanner;
     type Params = ();
 
@@ -24,7 +31,10 @@ impl<F: Field> Circuit<F> for TxCircuit<F> {
 
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
         let tx_table = TxTable::construct(meta);
+        let block_table = BlockTable::construct(meta);
         let keccak_table = KeccakTable::construct(meta);
+        let sig_table = SigTable::construct(meta);
+        let u16_table = UXTable::construct(meta);
         let challenges = Challenges::construct(meta);
 
         let config = {
@@ -33,30 +43,48 @@ impl<F: Field> Circuit<F> for TxCircuit<F> {
                 meta,
                 TxCircuitConfigArgs {
                     tx_table,
+                    block_table: block_table.clone(),
                     keccak_table: keccak_table.clone(),
+                    sig_table,
+                    u16_table,
                     challenges,
                 },
             )
         };
 
-        (config, challenges, keccak_table)
+        (config, challenges, keccak_table, block_table, u16_table)
     }
 
     fn synthesize(
         &self,
-        (config, challenges, keccak_table): Self::Config,
+        (config, challenges, keccak_table, block_table, u16_table): Self::Config,
         mut layouter: impl Layouter<F>,
     ) -> Result<(), Error> {
         let challenges = challenges.values(&mut layouter);
 
-        keccak_table.dev_load(
-            &mut layouter,
-            &keccak_inputs_tx_circuit(&self.txs[..], self.chain_id).map_err(|e| {
+        let mut keccak_inputs =
+            keccak_inputs_tx_circuit(&self.txs[..], self.chain_id).map_err(|e| {
+                error!("keccak_inputs_tx_circuit error: {:?}", e);
+                Error::Synthesis
+            })?;
+        keccak_inputs.extend(keccak_inputs_sign_verify(&self.ecrecover_sigs));
+        #[cfg(test)]
+        keccak_inputs.extend(
+            keccak_inputs_tx_circuit(&self.signed_txs, self.chain_id).map_err(|e| {
                 error!("keccak_inputs_tx_circuit error: {:?}", e);
                 Error::Synthesis
             })?,
-            &challenges,
+        );
+        keccak_table.dev_load(&mut layouter, &keccak_inputs, &challenges)?;
+        // The signed messages bind their chain id to the block table
+        block_table.load(
+            &mut layouter,
+            &[BlockContext {
+                chain_id: self.chain_id.into(),
+                ..Default::default()
+            }],
         )?;
+        u16_table.load(&mut layouter)?;
         self.synthesize_sub(&config, &challenges, &mut layouter)
     }
 }
@@ -0,0 +1,1448 @@
+//! Decoding of the RLP encoding of a transaction.
+//!
+//! The gadget decodes the EIP-2718 type and the RLP list of a transaction laid
+//! out one byte per row, either the unsigned fields signed by the sender or
+//! the signed envelope, and binds the fields to the [`TxTable`]:
+//! - the nonce, the gas price, the max fee per gas, the gas, the callee address and the value to
+//!   their static rows, and whether the transaction is a creation to `IsCreate`,
+//! - the call data bytes and their number to the `CallData` and `CallDataLength` rows,
+//! - the number of addresses of the access list to `AccessListAddressesLen`,
+//! - the blob versioned hashes and their number to the `BlobVersionedHash` and
+//!   `BlobVersionedHashesLen` rows,
+//! - the type to `TxType`, and for unsigned fields whether they include the chain id to
+//!   `IsReplayProtected`.
+//!
+//! The chain id is bound to the [`BlockTable`]. The max priority fee per gas,
+//! the max fee per blob gas, the addresses and storage keys of the access list
+//! and the signature are decoded but not bound, as the tx table has no row
+//! for them. The field of every item of the list is given by a fixed table
+//! of the fields of every transaction type.
+//!
+//! The bytes are not range checked, as the host binds them to the keccak
+//! input of a hash, and strings are at most 2^16 bytes long. The host enables
+//! the gadget on the rows of the encodings, marks the first row of every
+//! encoding, and enables the previous row of every other enabled row.
+
+use crate::{
+    evm_circuit::util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+    table::{BlockContextFieldTag, BlockTable, LookupTable, TxFieldTag, TxTable},
+};
+use eth_types::{
+    geth_types::{ACCESS_LIST_TX_TYPE, BLOB_TX_TYPE, DYNAMIC_FEE_TX_TYPE, LEGACY_TX_TYPE},
+    Field,
+};
+use gadgets::util::{not, Expr};
+use halo2_proofs::{
+    circuit::{Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, VirtualCells},
+    poly::Rotation,
+};
+use itertools::Itertools;
+use strum::{EnumCount, IntoEnumIterator};
+use strum_macros::{EnumCount, EnumIter};
+
+use super::TX_TYPES;
+
+/// Maximum length of the RLP encoding of a transaction besides its call data.
+pub(crate) const N_BYTES_TX_ENVELOPE: usize = 600;
+
+/// Field of an item of the RLP list of a transaction
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumCount, EnumIter)]
+pub(crate) enum TxRlpField {
+    Nonce,
+    GasPrice,
+    MaxPriorityFeePerGas,
+    MaxFeePerGas,
+    Gas,
+    To,
+    Value,
+    Data,
+    ChainId,
+    /// Empty r and s of the message signed by EIP-155 legacy transactions
+    Zero,
+    AccessList,
+    MaxFeePerBlobGas,
+    BlobVersionedHashes,
+    SigV,
+    SigR,
+    SigS,
+}
+
+impl TxRlpField {
+    /// Tag of the static row of the tx table the value of the field is bound
+    /// to
+    fn tag(self) -> Option<TxFieldTag> {
+        match self {
+            Self::Nonce => Some(TxFieldTag::Nonce),
+            Self::GasPrice => Some(TxFieldTag::GasPrice),
+            Self::MaxFeePerGas => Some(TxFieldTag::MaxFeePerGas),
+            Self::Gas => Some(TxFieldTag::Gas),
+            Self::To => Some(TxFieldTag::CalleeAddress),
+            Self::Value => Some(TxFieldTag::Value),
+            _ => None,
+        }
+    }
+
+    /// Whether the field is an integer of at most 32 bytes
+    fn is_scalar(self) -> bool {
+        !matches!(
+            self,
+            Self::To | Self::Data | Self::AccessList | Self::BlobVersionedHashes
+        )
+    }
+
+    /// Value of the field in the fixed table, zero being no field
+    fn kind(self) -> u64 {
+        self as u64 + 1
+    }
+}
+
+/// Fields of the RLP list of a transaction of the given type. The message
+/// signed by legacy transactions before EIP-155 ends with the data.
+fn tx_fields(tx_type: u64, is_signed: bool) -> Vec<TxRlpField> {
+    use TxRlpField::*;
+
+    let mut fields = match tx_type {
+        LEGACY_TX_TYPE => vec![Nonce, GasPrice, Gas, To, Value, Data],
+        ACCESS_LIST_TX_TYPE => vec![ChainId, Nonce, GasPrice, Gas, To, Value, Data, AccessList],
+        DYNAMIC_FEE_TX_TYPE | BLOB_TX_TYPE => vec![
+            ChainId,
+            Nonce,
+            MaxPriorityFeePerGas,
+            MaxFeePerGas,
+            Gas,
+            To,
+            Value,
+            Data,
+            AccessList,
+        ],
+        _ => unreachable!("unsupported tx type {}", tx_type),
+    };
+    if tx_type == BLOB_TX_TYPE {
+        fields.extend([MaxFeePerBlobGas, BlobVersionedHashes]);
+    }
+    if is_signed {
+        fields.extend([SigV, SigR, SigS]);
+    } else if tx_type == LEGACY_TX_TYPE {
+        fields.extend([ChainId, Zero, Zero]);
+    }
+    fields
+}
+
+/// Rows of the fixed table of the fields: the type, the index of the item in
+/// the list, the field and whether the list can end with the item
+fn field_table(is_signed: bool) -> Vec<[u64; 4]> {
+    let mut rows = vec![[0; 4]];
+    for tx_type in TX_TYPES {
+        let fields = tx_fields(tx_type, is_signed);
+        for (index, field) in fields.iter().enumerate() {
+            let is_last = index + 1 == fields.len()
+                || (!is_signed && tx_type == LEGACY_TX_TYPE && *field == TxRlpField::Data);
+            rows.push([tx_type, index as u64, field.kind(), is_last as u64]);
+        }
+    }
+    rows
+}
+
+/// Tag of a row of the gadget
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, EnumCount, EnumIter)]
+enum RowTag {
+    /// EIP-2718 type of a typed transaction
+    #[default]
+    TxType,
+    /// Prefix and length bytes of the list of the transaction
+    ListPrefix,
+    ListLen,
+    /// Prefix, length bytes and content of a string item, or a single byte
+    /// encoded as itself
+    ItemPrefix,
+    ItemLen,
+    Item,
+    /// Prefix and length bytes of the access list
+    AccessListPrefix,
+    AccessListLen,
+    /// Prefix and length bytes of an entry of the access list
+    EntryPrefix,
+    EntryLen,
+    /// Prefix and content of the address of an entry
+    AddressPrefix,
+    Address,
+    /// Prefix and length bytes of the storage keys of an entry
+    KeysPrefix,
+    KeysLen,
+    /// Prefix and content of a storage key
+    KeyPrefix,
+    Key,
+    /// Prefix and length bytes of the blob versioned hashes
+    HashesPrefix,
+    HashesLen,
+    /// Prefix and content of a blob versioned hash
+    HashPrefix,
+    Hash,
+}
+
+impl RowTag {
+    const LIST_PREFIXES: [Self; 5] = [
+        Self::ListPrefix,
+        Self::AccessListPrefix,
+        Self::EntryPrefix,
+        Self::KeysPrefix,
+        Self::HashesPrefix,
+    ];
+    const STR_PREFIXES: [Self; 4] = [
+        Self::ItemPrefix,
+        Self::AddressPrefix,
+        Self::KeyPrefix,
+        Self::HashPrefix,
+    ];
+    /// Prefixes which can be long, with their length bytes
+    const HEADERS: [(Self, Self); 6] = [
+        (Self::ListPrefix, Self::ListLen),
+        (Self::ItemPrefix, Self::ItemLen),
+        (Self::AccessListPrefix, Self::AccessListLen),
+        (Self::EntryPrefix, Self::EntryLen),
+        (Self::KeysPrefix, Self::KeysLen),
+        (Self::HashesPrefix, Self::HashesLen),
+    ];
+    /// Prefix, length bytes if any and content of the strings
+    const STRINGS: [(Self, Option<Self>, Self); 4] = [
+        (Self::ItemPrefix, Some(Self::ItemLen), Self::Item),
+        (Self::AddressPrefix, None, Self::Address),
+        (Self::KeyPrefix, None, Self::Key),
+        (Self::HashPrefix, None, Self::Hash),
+    ];
+    const LENS: [Self; 6] = [
+        Self::ListLen,
+        Self::ItemLen,
+        Self::AccessListLen,
+        Self::EntryLen,
+        Self::KeysLen,
+        Self::HashesLen,
+    ];
+    const CONTENTS: [Self; 4] = [Self::Item, Self::Address, Self::Key, Self::Hash];
+    /// Rows of the payload of the list of the transaction
+    const PAYLOAD: [Self; 17] = [
+        Self::ItemPrefix,
+        Self::ItemLen,
+        Self::Item,
+        Self::AccessListPrefix,
+        Self::AccessListLen,
+        Self::EntryPrefix,
+        Self::EntryLen,
+        Self::AddressPrefix,
+        Self::Address,
+        Self::KeysPrefix,
+        Self::KeysLen,
+        Self::KeyPrefix,
+        Self::Key,
+        Self::HashesPrefix,
+        Self::HashesLen,
+        Self::HashPrefix,
+        Self::Hash,
+    ];
+    /// Headers of the access list and of the blob versioned hashes
+    const INNER_HEADERS: [Self; 4] = [
+        Self::AccessListPrefix,
+        Self::AccessListLen,
+        Self::HashesPrefix,
+        Self::HashesLen,
+    ];
+    /// Rows of the payload of the access list or of the blob versioned hashes
+    const INNER: [Self; 10] = [
+        Self::EntryPrefix,
+        Self::EntryLen,
+        Self::AddressPrefix,
+        Self::Address,
+        Self::KeysPrefix,
+        Self::KeysLen,
+        Self::KeyPrefix,
+        Self::Key,
+        Self::HashPrefix,
+        Self::Hash,
+    ];
+    /// Rows of the payload of an entry of the access list
+    const ENTRY: [Self; 6] = [
+        Self::AddressPrefix,
+        Self::Address,
+        Self::KeysPrefix,
+        Self::KeysLen,
+        Self::KeyPrefix,
+        Self::Key,
+    ];
+    /// Rows of the payload of the storage keys of an entry
+    const KEYS: [Self; 2] = [Self::KeyPrefix, Self::Key];
+    /// First rows of an item which is not a single byte
+    const ITEM_PREFIXES: [Self; 3] = [Self::ItemPrefix, Self::AccessListPrefix, Self::HashesPrefix];
+
+    fn is_in(self, tags: &[Self]) -> bool {
+        tags.contains(&self)
+    }
+}
+
+/// Witness of a row of the gadget
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TxRlpRow {
+    pub(crate) byte: u8,
+    tag: RowTag,
+    field: Option<TxRlpField>,
+    is_single: bool,
+    is_long: bool,
+    len_rem: u64,
+    len_acc: u64,
+    is_hdr_end: bool,
+    hdr_len: u64,
+    rem_list: u64,
+    pub(crate) is_end: bool,
+    rem_str: u64,
+    is_str_end: bool,
+    str_len: u64,
+    rem_inner: u64,
+    is_inner_end: bool,
+    rem_entry: u64,
+    rem_keys: u64,
+    is_keys_end: bool,
+    is_item_end: bool,
+    item_count: u64,
+    tx_type: u64,
+    inner_count: u64,
+    is_lo: bool,
+    acc_lo: u128,
+    acc_hi: u128,
+}
+
+impl TxRlpRow {
+    /// Whether the row is a byte of the call data or of a blob versioned
+    /// hash, which are not part of the [`N_BYTES_TX_ENVELOPE`] bytes
+    pub(crate) fn is_unbounded(&self) -> bool {
+        (self.tag == RowTag::Item && self.field == Some(TxRlpField::Data))
+            || self.tag == RowTag::Hash
+    }
+}
+
+/// Bytes of an encoding laid out with their tag and field
+struct TxRlpLayout<'a> {
+    rlp: &'a [u8],
+    offset: usize,
+    field: Option<TxRlpField>,
+    rows: Vec<TxRlpRow>,
+}
+
+impl TxRlpLayout<'_> {
+    /// Push the row of the next byte of the encoding
+    fn push(&mut self, tag: RowTag) -> u8 {
+        let byte = self.rlp[self.offset];
+        self.offset += 1;
+        self.rows.push(TxRlpRow {
+            byte,
+            tag,
+            field: self.field,
+            ..Default::default()
+        });
+        byte
+    }
+
+    /// Push the prefix and length bytes of a list or a string, and return the
+    /// offset of the end of its payload
+    fn header(&mut self, prefix: RowTag, len: Option<RowTag>) -> usize {
+        let byte = self.push(prefix);
+        let (base, long_base) = if byte >= 0xc0 {
+            (0xc0, 0xf7)
+        } else {
+            (0x80, 0xb7)
+        };
+        let payload_len = if byte > long_base {
+            let len = len.expect("only lists and strings with a length tag are long");
+            (0..byte - long_base).fold(0, |acc, _| acc * 256 + self.push(len) as usize)
+        } else {
+            (byte - base) as usize
+        };
+        self.offset + payload_len
+    }
+
+    /// Push the prefix, length bytes and content of a string
+    fn string(&mut self, prefix: RowTag, len: Option<RowTag>, content: RowTag) {
+        let end = self.header(prefix, len);
+        while self.offset < end {
+            self.push(content);
+        }
+    }
+}
+
+/// Lay out and derive the rows of the RLP encoding of a transaction, either
+/// the message signed by its sender or its signed envelope.
+pub(crate) fn tx_rlp_rows(rlp: &[u8], is_signed: bool) -> Vec<TxRlpRow> {
+    let mut layout = TxRlpLayout {
+        rlp,
+        offset: 0,
+        field: None,
+        rows: vec![],
+    };
+    let tx_type = if rlp[0] < 0xc0 {
+        layout.push(RowTag::TxType) as u64
+    } else {
+        LEGACY_TX_TYPE
+    };
+    let end = layout.header(RowTag::ListPrefix, Some(RowTag::ListLen));
+    for field in tx_fields(tx_type, is_signed) {
+        if layout.offset == end {
+            break;
+        }
+        layout.field = Some(field);
+        match field {
+            TxRlpField::AccessList => {
+                let end = layout.header(RowTag::AccessListPrefix, Some(RowTag::AccessListLen));
+                while layout.offset < end {
+                    layout.header(RowTag::EntryPrefix, Some(RowTag::EntryLen));
+                    layout.string(RowTag::AddressPrefix, None, RowTag::Address);
+                    let keys_end = layout.header(RowTag::KeysPrefix, Some(RowTag::KeysLen));
+                    while layout.offset < keys_end {
+                        layout.string(RowTag::KeyPrefix, None, RowTag::Key);
+                    }
+                }
+            }
+            TxRlpField::BlobVersionedHashes => {
+                let end = layout.header(RowTag::HashesPrefix, Some(RowTag::HashesLen));
+                while layout.offset < end {
+                    layout.string(RowTag::HashPrefix, None, RowTag::Hash);
+                }
+            }
+            _ if layout.rlp[layout.offset] < 0x80 => {
+                layout.push(RowTag::Item);
+                layout.rows.last_mut().expect("item is pushed").is_single = true;
+            }
+            _ => layout.string(RowTag::ItemPrefix, Some(RowTag::ItemLen), RowTag::Item),
+        }
+    }
+    assert_eq!(layout.offset, rlp.len(), "encoding is fully laid out");
+
+    let mut rows = layout.rows;
+    for i in 0..rows.len() {
+        let (done, rest) = rows.split_at_mut(i);
+        derive_row(done.last(), &mut rest[0]);
+    }
+    rows
+}
+
+/// Derive the witness of a row from its byte, its tag, its field and the
+/// previous row of the encoding, if any
+fn derive_row(prev: Option<&TxRlpRow>, row: &mut TxRlpRow) {
+    let first = TxRlpRow::default();
+    let is_first = prev.is_none();
+    let prev = prev.unwrap_or(&first);
+    let tag = row.tag;
+    let byte = row.byte as u64;
+    let is_list_prefix = tag.is_in(&RowTag::LIST_PREFIXES);
+    let is_str_prefix = tag.is_in(&RowTag::STR_PREFIXES);
+    let is_len = tag.is_in(&RowTag::LENS);
+    let is_content = tag.is_in(&RowTag::CONTENTS);
+
+    // RLP headers
+    row.is_long = (is_list_prefix && byte > 0xf7) || (is_str_prefix && byte > 0xb7);
+    row.len_rem = match (row.is_long, is_list_prefix, is_len) {
+        (true, true, _) => byte - 0xf7,
+        (true, false, _) => byte - 0xb7,
+        (false, _, true) => prev.len_rem - 1,
+        _ => 0,
+    };
+    row.len_acc = if is_len { prev.len_acc * 256 + byte } else { 0 };
+    row.is_hdr_end =
+        ((is_list_prefix || is_str_prefix) && !row.is_long) || (is_len && row.len_rem == 0);
+    row.hdr_len = match (row.is_hdr_end, is_len, is_list_prefix) {
+        (false, _, _) => 0,
+        (true, true, _) => row.len_acc,
+        (true, false, true) => byte - 0xc0,
+        (true, false, false) => byte - 0x80,
+    };
+
+    // Remaining bytes of the list, the string, the access list or blob
+    // versioned hashes, the entry and the storage keys
+    row.rem_list = if tag.is_in(&RowTag::PAYLOAD) {
+        prev.rem_list - 1
+    } else if tag.is_in(&[RowTag::ListPrefix, RowTag::ListLen]) && row.is_hdr_end {
+        row.hdr_len
+    } else {
+        0
+    };
+    row.is_end = tag.is_in(&RowTag::PAYLOAD) && row.rem_list == 0;
+    let str_hdr_end = row.is_hdr_end && (is_str_prefix || tag == RowTag::ItemLen);
+    row.rem_str = if str_hdr_end {
+        row.hdr_len
+    } else if is_content && !row.is_single {
+        prev.rem_str - 1
+    } else {
+        0
+    };
+    row.is_str_end = (str_hdr_end || is_content) && row.rem_str == 0;
+    row.str_len = if str_hdr_end {
+        row.hdr_len
+    } else if row.is_single {
+        1
+    } else if is_content {
+        prev.str_len
+    } else {
+        0
+    };
+    let inner_hdr_end = tag.is_in(&RowTag::INNER_HEADERS) && row.is_hdr_end;
+    row.rem_inner = if tag.is_in(&RowTag::INNER) {
+        prev.rem_inner - 1
+    } else if inner_hdr_end {
+        row.hdr_len
+    } else {
+        0
+    };
+    row.rem_entry = if tag.is_in(&RowTag::ENTRY) {
+        prev.rem_entry - 1
+    } else if tag.is_in(&[RowTag::EntryPrefix, RowTag::EntryLen]) && row.is_hdr_end {
+        row.hdr_len
+    } else {
+        0
+    };
+    let keys_hdr_end = tag.is_in(&[RowTag::KeysPrefix, RowTag::KeysLen]) && row.is_hdr_end;
+    row.rem_keys = if tag.is_in(&RowTag::KEYS) {
+        prev.rem_keys - 1
+    } else if keys_hdr_end {
+        row.hdr_len
+    } else {
+        0
+    };
+    row.is_keys_end = (keys_hdr_end || (tag == RowTag::Key && row.is_str_end)) && row.rem_keys == 0;
+    row.is_inner_end =
+        (inner_hdr_end || row.is_keys_end || (tag == RowTag::Hash && row.is_str_end))
+            && row.rem_inner == 0;
+    row.is_item_end = (tag.is_in(&[RowTag::ItemPrefix, RowTag::ItemLen, RowTag::Item])
+        && row.is_str_end)
+        || row.is_inner_end;
+
+    // Items, type and number of entries of the access list or blob versioned
+    // hashes
+    let is_item_start = tag.is_in(&RowTag::ITEM_PREFIXES) || row.is_single;
+    row.item_count = prev.item_count + is_item_start as u64;
+    row.tx_type = match (is_first, tag) {
+        (true, RowTag::TxType) => byte,
+        (true, _) => 0,
+        (false, _) => prev.tx_type,
+    };
+    row.inner_count = if tag.is_in(&[RowTag::AccessListPrefix, RowTag::HashesPrefix]) {
+        0
+    } else {
+        prev.inner_count
+    } + tag.is_in(&[RowTag::EntryPrefix, RowTag::HashPrefix]) as u64;
+
+    // Values of the fields besides the call data and of the blob versioned
+    // hashes, in 16-byte limbs
+    let is_value =
+        (tag == RowTag::Item && row.field != Some(TxRlpField::Data)) || tag == RowTag::Hash;
+    if is_value {
+        let cont = prev.tag.is_in(&[RowTag::Item, RowTag::Hash]) && !row.is_single;
+        row.is_lo = row.rem_str < 16;
+        let (acc_lo, acc_hi) = if cont {
+            (prev.acc_lo, prev.acc_hi)
+        } else {
+            (0, 0)
+        };
+        (row.acc_lo, row.acc_hi) = if row.is_lo {
+            (acc_lo * 256 + byte as u128, acc_hi)
+        } else {
+            (0, acc_hi * 256 + byte as u128)
+        };
+    }
+}
+
+/// Config of the gadget decoding the RLP encoding of a transaction
+#[derive(Clone, Debug)]
+pub(crate) struct TxRlpConfig {
+    // is_signed: whether the encodings are signed envelopes rather than
+    // signed messages
+    is_signed: bool,
+    // Fixed table of the fields: the type, the index of the item in the list,
+    // the field and whether the list can end with the item
+    field_tx_type: Column<Fixed>,
+    field_index: Column<Fixed>,
+    field_kind: Column<Fixed>,
+    field_is_last: Column<Fixed>,
+
+    // One-hot tag of the row
+    tags: [Column<Advice>; RowTag::COUNT],
+    // One-hot field of the item of the row, zero out of the payload
+    fields: [Column<Advice>; TxRlpField::COUNT],
+    // is_single: item which is a single byte encoded as itself
+    is_single: Column<Advice>,
+    // is_long: prefix followed by length bytes
+    is_long: Column<Advice>,
+    // len_rem: number of length bytes after the row
+    len_rem: Column<Advice>,
+    // len_acc: length bytes accumulated with base 256
+    len_acc: Column<Advice>,
+    // is_hdr_end: last byte of the prefix and length of a list or string
+    is_hdr_end: Column<Advice>,
+    // hdr_len: length of the payload of the list or string, on its header end
+    hdr_len: Column<Advice>,
+    // rem_list: number of bytes of the list of the transaction after the row
+    rem_list: Column<Advice>,
+    rem_list_inv: Column<Advice>,
+    // is_end: last byte of the encoding
+    is_end: Column<Advice>,
+    // rem_str: number of content bytes of the string after the row
+    rem_str: Column<Advice>,
+    rem_str_inv: Column<Advice>,
+    // is_str_end: last byte of a string
+    is_str_end: Column<Advice>,
+    // str_len: length of the string
+    str_len: Column<Advice>,
+    // rem_inner: number of bytes of the access list or blob versioned hashes
+    // after the row
+    rem_inner: Column<Advice>,
+    rem_inner_inv: Column<Advice>,
+    // is_inner_end: last byte of the access list or blob versioned hashes
+    is_inner_end: Column<Advice>,
+    // rem_entry: number of bytes of the entry of the access list after the row
+    rem_entry: Column<Advice>,
+    // rem_keys: number of bytes of the storage keys of the entry after the row
+    rem_keys: Column<Advice>,
+    rem_keys_inv: Column<Advice>,
+    // is_keys_end: last byte of the storage keys of an entry
+    is_keys_end: Column<Advice>,
+    // is_item_end: last byte of an item of the list
+    is_item_end: Column<Advice>,
+    // item_count: number of items of the list up to the row
+    item_count: Column<Advice>,
+    // tx_type: type of the transaction, zero for legacy transactions
+    tx_type: Column<Advice>,
+    // inner_count: number of entries of the access list or blob versioned
+    // hashes up to the row
+    inner_count: Column<Advice>,
+    // is_lo: last 16 bytes of a value
+    is_lo: Column<Advice>,
+    // acc_lo and acc_hi: lo and hi limbs of the value up to the row
+    acc_lo: Column<Advice>,
+    acc_hi: Column<Advice>,
+}
+
+impl TxRlpConfig {
+    /// Configure the gadget on the `byte` column, enabled by `q_enable`, with
+    /// the first row of every encoding given by `is_first` and the id of its
+    /// transaction by `tx_id`. `q_enable` and `is_first` are boolean and of
+    /// degree at most 2 and 1.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn configure<F: Field>(
+        meta: &mut ConstraintSystem<F>,
+        q_enable: impl Fn(&mut VirtualCells<'_, F>) -> Expression<F>,
+        is_first: impl Fn(&mut VirtualCells<'_, F>) -> Expression<F>,
+        tx_id: impl Fn(&mut VirtualCells<'_, F>) -> Expression<F>,
+        byte: Column<Advice>,
+        is_signed: bool,
+        tx_table: &TxTable,
+        block_table: &BlockTable,
+        u16_table: &dyn LookupTable<F>,
+    ) -> Self {
+        let field_tx_type = meta.fixed_column();
+        let field_index = meta.fixed_column();
+        let field_kind = meta.fixed_column();
+        let field_is_last = meta.fixed_column();
+
+        let tags = std::array::from_fn(|_| meta.advice_column());
+        let fields = std::array::from_fn(|_| meta.advice_column());
+        let is_single = meta.advice_column();
+        let is_long = meta.advice_column();
+        let len_rem = meta.advice_column();
+        let len_acc = meta.advice_column();
+        let is_hdr_end = meta.advice_column();
+        let hdr_len = meta.advice_column();
+        let rem_list = meta.advice_column();
+        let rem_list_inv = meta.advice_column();
+        let is_end = meta.advice_column();
+        let rem_str = meta.advice_column();
+        let rem_str_inv = meta.advice_column();
+        let is_str_end = meta.advice_column();
+        let str_len = meta.advice_column();
+        let rem_inner = meta.advice_column();
+        let rem_inner_inv = meta.advice_column();
+        let is_inner_end = meta.advice_column();
+        let rem_entry = meta.advice_column();
+        let rem_keys = meta.advice_column();
+        let rem_keys_inv = meta.advice_column();
+        let is_keys_end = meta.advice_column();
+        let is_item_end = meta.advice_column();
+        let item_count = meta.advice_column();
+        let tx_type = meta.advice_column();
+        let inner_count = meta.advice_column();
+        let is_lo = meta.advice_column();
+        let acc_lo = meta.advice_column();
+        let acc_hi = meta.advice_column();
+
+        let tag = |meta: &mut VirtualCells<'_, F>, tag: RowTag, rotation: Rotation| {
+            meta.query_advice(tags[tag as usize], rotation)
+        };
+        let tag_sum = |meta: &mut VirtualCells<'_, F>, list: &[RowTag], rotation: Rotation| {
+            list.iter()
+                .fold(0.expr(), |acc, t| acc + tag(meta, *t, rotation))
+        };
+        let field = |meta: &mut VirtualCells<'_, F>, field: TxRlpField| {
+            meta.query_advice(fields[field as usize], Rotation::cur())
+        };
+        // Sum of the given map of the fields, weighted by the one-hot field
+        let field_sum = |meta: &mut VirtualCells<'_, F>, map: &dyn Fn(TxRlpField) -> u64| {
+            TxRlpField::iter().fold(0.expr(), |acc, f| {
+                acc + field(meta, f) * Expression::Constant(F::from(map(f)))
+            })
+        };
+        let inv_2 = Expression::Constant(F::from(2).invert().unwrap());
+        // Whether length bytes follow the row, as len_rem is at most 2
+        let has_len = |len_rem: Expression<F>| len_rem.expr() * (3.expr() - len_rem) * inv_2.expr();
+        // First row of an item of the list
+        let item_start = |meta: &mut VirtualCells<'_, F>| {
+            tag_sum(meta, &RowTag::ITEM_PREFIXES, Rotation::cur())
+                + meta.query_advice(is_single, Rotation::cur())
+        };
+        // Content of a value, which is any item but the call data or a blob
+        // versioned hash
+        let is_value = |meta: &mut VirtualCells<'_, F>| {
+            tag(meta, RowTag::Item, Rotation::cur()) * not::expr(field(meta, TxRlpField::Data))
+                + tag(meta, RowTag::Hash, Rotation::cur())
+        };
+
+        meta.create_gate("tx rlp row", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let cur = Rotation::cur();
+            let prev = Rotation::prev();
+            let first = is_first(meta);
+            let byte = meta.query_advice(byte, cur);
+            let single = meta.query_advice(is_single, cur);
+            let long = meta.query_advice(is_long, cur);
+            let hdr_end = meta.query_advice(is_hdr_end, cur);
+            let hdr_len = meta.query_advice(hdr_len, cur);
+            let end = meta.query_advice(is_end, cur);
+            let str_end = meta.query_advice(is_str_end, cur);
+            let inner_end = meta.query_advice(is_inner_end, cur);
+            let keys_end = meta.query_advice(is_keys_end, cur);
+            let item_end = meta.query_advice(is_item_end, cur);
+            let lo = meta.query_advice(is_lo, cur);
+            let len_rem = meta.query_advice(len_rem, cur);
+            let rem_list = meta.query_advice(rem_list, cur);
+            let rem_str = meta.query_advice(rem_str, cur);
+            let rem_inner = meta.query_advice(rem_inner, cur);
+            let rem_entry = meta.query_advice(rem_entry, cur);
+            let rem_keys = meta.query_advice(rem_keys, cur);
+            let iz_list = 1.expr() - rem_list.expr() * meta.query_advice(rem_list_inv, cur);
+            let iz_str = 1.expr() - rem_str.expr() * meta.query_advice(rem_str_inv, cur);
+            let iz_inner = 1.expr() - rem_inner.expr() * meta.query_advice(rem_inner_inv, cur);
+            let iz_keys = 1.expr() - rem_keys.expr() * meta.query_advice(rem_keys_inv, cur);
+
+            let list_prefix = tag_sum(meta, &RowTag::LIST_PREFIXES, cur);
+            let str_prefix = tag_sum(meta, &RowTag::STR_PREFIXES, cur);
+            let is_len = tag_sum(meta, &RowTag::LENS, cur);
+            let content = tag_sum(meta, &RowTag::CONTENTS, cur);
+            let in_payload = tag_sum(meta, &RowTag::PAYLOAD, cur);
+
+            for column in tags.iter().chain(fields.iter()) {
+                cb.require_boolean(
+                    "tags and fields are boolean",
+                    meta.query_advice(*column, cur),
+                );
+            }
+            cb.require_equal(
+                "a row has a single tag",
+                tag_sum(meta, &RowTag::iter().collect_vec(), cur),
+                1.expr(),
+            );
+            cb.require_equal(
+                "a row of the payload has a single field",
+                field_sum(meta, &|_| 1),
+                in_payload.expr(),
+            );
+            for (name, flag) in [
+                ("is_single is boolean", single.expr()),
+                ("is_long is boolean", long.expr()),
+                ("is_hdr_end is boolean", hdr_end.expr()),
+                ("is_end is boolean", end.expr()),
+                ("is_str_end is boolean", str_end.expr()),
+                ("is_inner_end is boolean", inner_end.expr()),
+                ("is_keys_end is boolean", keys_end.expr()),
+                ("is_item_end is boolean", item_end.expr()),
+                ("is_lo is boolean", lo.expr()),
+            ] {
+                cb.require_boolean(name, flag);
+            }
+            cb.require_zero(
+                "single bytes are items",
+                single.expr() * not::expr(tag(meta, RowTag::Item, cur)),
+            );
+            cb.require_zero(
+                "only prefixes are long",
+                long.expr() * not::expr(list_prefix.expr() + str_prefix.expr()),
+            );
+            cb.require_zero(
+                "an encoding starts with the type or the list",
+                first.expr() * not::expr(tag_sum(meta, &[RowTag::TxType, RowTag::ListPrefix], cur)),
+            );
+
+            // RLP headers
+            cb.require_equal(
+                "len_rem = number of length bytes, decremented on every length byte",
+                len_rem.expr(),
+                long.expr()
+                    * (list_prefix.expr() * (byte.expr() - 0xf7.expr())
+                        + str_prefix.expr() * (byte.expr() - 0xb7.expr()))
+                    + is_len.expr() * (meta.query_advice(len_rem, prev) - 1.expr()),
+            );
+            let len_acc = meta.query_advice(len_acc, cur);
+            cb.require_equal(
+                "len_acc = len_acc_prev * 256 + byte on length bytes",
+                len_acc.expr(),
+                is_len.expr() * (meta.query_advice(len_acc, prev) * 256.expr() + byte.expr()),
+            );
+            cb.require_equal(
+                "is_hdr_end = short prefix or last length byte",
+                hdr_end.expr(),
+                (list_prefix.expr() + str_prefix.expr()) * not::expr(long.expr())
+                    + is_len.expr() * not::expr(has_len(len_rem)),
+            );
+            cb.require_zero(
+                "hdr_len = length of the payload at the header end",
+                hdr_end.expr()
+                    * (hdr_len.expr()
+                        - list_prefix.expr() * (byte.expr() - 0xc0.expr())
+                        - str_prefix.expr() * (byte.expr() - 0x80.expr())
+                        - is_len.expr() * len_acc),
+            );
+
+            // Remaining bytes of the list, the string, the access list or blob
+            // versioned hashes, the entry and the storage keys
+            let str_hdr_end =
+                (str_prefix.expr() + tag(meta, RowTag::ItemLen, cur)) * hdr_end.expr();
+            let inner_hdr_end = tag_sum(meta, &RowTag::INNER_HEADERS, cur) * hdr_end.expr();
+            let keys_hdr_end =
+                tag_sum(meta, &[RowTag::KeysPrefix, RowTag::KeysLen], cur) * hdr_end.expr();
+            cb.require_equal(
+                "rem_list = list length, decremented on every payload byte",
+                rem_list.expr(),
+                in_payload.expr() * (meta.query_advice(rem_list, prev) - 1.expr())
+                    + tag_sum(meta, &[RowTag::ListPrefix, RowTag::ListLen], cur)
+                        * hdr_end.expr()
+                        * hdr_len.expr(),
+            );
+            cb.require_zero("iz_list = rem_list == 0", rem_list * iz_list.expr());
+            cb.require_equal(
+                "is_end = last byte of the list payload",
+                end.expr(),
+                in_payload * iz_list,
+            );
+            cb.require_equal(
+                "rem_str = string length, decremented on every content byte",
+                rem_str.expr(),
+                str_hdr_end.expr() * hdr_len.expr()
+                    + (content.expr() - single.expr())
+                        * (meta.query_advice(rem_str, prev) - 1.expr()),
+            );
+            cb.require_zero("iz_str = rem_str == 0", rem_str.expr() * iz_str.expr());
+            cb.require_equal(
+                "is_str_end = last byte of the string",
+                str_end.expr(),
+                (str_hdr_end.expr() + content.expr()) * iz_str,
+            );
+            cb.require_equal(
+                "str_len = string length, carried in the string",
+                meta.query_advice(str_len, cur),
+                str_hdr_end * hdr_len.expr()
+                    + (content - single.expr()) * meta.query_advice(str_len, prev)
+                    + single.expr(),
+            );
+            cb.require_equal(
+                "rem_inner = inner list length, decremented on every inner byte",
+                rem_inner.expr(),
+                tag_sum(meta, &RowTag::INNER, cur)
+                    * (meta.query_advice(rem_inner, prev) - 1.expr())
+                    + inner_hdr_end.expr() * hdr_len.expr(),
+            );
+            cb.require_zero("iz_inner = rem_inner == 0", rem_inner * iz_inner.expr());
+            cb.require_equal(
+                "rem_entry = entry length, decremented on every entry byte",
+                rem_entry.expr(),
+                tag_sum(meta, &RowTag::ENTRY, cur)
+                    * (meta.query_advice(rem_entry, prev) - 1.expr())
+                    + tag_sum(meta, &[RowTag::EntryPrefix, RowTag::EntryLen], cur)
+                        * hdr_end.expr()
+                        * hdr_len.expr(),
+            );
+            cb.require_equal(
+                "rem_keys = storage keys length, decremented on every key byte",
+                rem_keys.expr(),
+                tag_sum(meta, &RowTag::KEYS, cur) * (meta.query_advice(rem_keys, prev) - 1.expr())
+                    + keys_hdr_end.expr() * hdr_len.expr(),
+            );
+            cb.require_zero("iz_keys = rem_keys == 0", rem_keys * iz_keys.expr());
+            cb.require_equal(
+                "is_keys_end = last byte of the storage keys",
+                keys_end.expr(),
+                (keys_hdr_end + tag(meta, RowTag::Key, cur) * str_end.expr()) * iz_keys,
+            );
+            cb.require_equal(
+                "is_inner_end = last byte of the access list or blob versioned hashes",
+                inner_end.expr(),
+                (inner_hdr_end + keys_end.expr() + tag(meta, RowTag::Hash, cur) * str_end.expr())
+                    * iz_inner,
+            );
+            cb.require_equal(
+                "is_item_end = last byte of a string item or of an inner list",
+                item_end.expr(),
+                tag_sum(
+                    meta,
+                    &[RowTag::ItemPrefix, RowTag::ItemLen, RowTag::Item],
+                    cur,
+                ) * str_end.expr()
+                    + inner_end,
+            );
+            cb.require_zero(
+                "the list ends with its last item",
+                end * not::expr(item_end.expr()),
+            );
+            cb.require_zero("an entry ends with its storage keys", keys_end * rem_entry);
+
+            // Fixed parts of the items
+            for (name, row_tag, value) in [
+                ("addresses are 20 bytes long", RowTag::AddressPrefix, 0x94),
+                ("storage keys are 32 bytes long", RowTag::KeyPrefix, 0xa0),
+                (
+                    "blob versioned hashes are 32 bytes long",
+                    RowTag::HashPrefix,
+                    0xa0,
+                ),
+            ] {
+                cb.require_zero(name, tag(meta, row_tag, cur) * (byte.expr() - value.expr()));
+            }
+            let to = field(meta, TxRlpField::To);
+            cb.require_zero(
+                "the callee address is empty or 20 bytes long",
+                tag(meta, RowTag::ItemPrefix, cur)
+                    * to.expr()
+                    * (byte.expr() - 0x80.expr())
+                    * (byte.expr() - 0x94.expr()),
+            );
+            cb.require_zero(
+                "the callee address is not a single byte",
+                single.expr() * to,
+            );
+            for acc in [acc_lo, acc_hi] {
+                cb.require_zero(
+                    "the empty r and s of EIP-155 messages are zero",
+                    item_end.expr() * field(meta, TxRlpField::Zero) * meta.query_advice(acc, cur),
+                );
+            }
+            let start = item_start(meta);
+            for (list_field, prefix) in [
+                (TxRlpField::AccessList, RowTag::AccessListPrefix),
+                (TxRlpField::BlobVersionedHashes, RowTag::HashesPrefix),
+            ] {
+                cb.require_zero(
+                    "the access list and blob versioned hashes are lists",
+                    start.expr() * (field(meta, list_field) - tag(meta, prefix, cur)),
+                );
+            }
+
+            // Items, type and number of entries of the access list or blob
+            // versioned hashes
+            cb.require_equal(
+                "item_count = number of items of the list",
+                meta.query_advice(item_count, cur),
+                not::expr(first.expr()) * meta.query_advice(item_count, prev) + start,
+            );
+            cb.require_equal(
+                "tx_type = type byte of the encoding, carried in the encoding",
+                meta.query_advice(tx_type, cur),
+                first.expr() * tag(meta, RowTag::TxType, cur) * byte.expr()
+                    + not::expr(first.expr()) * meta.query_advice(tx_type, prev),
+            );
+            cb.require_equal(
+                "inner_count = number of entries of the access list or blob versioned hashes",
+                meta.query_advice(inner_count, cur),
+                not::expr(first)
+                    * not::expr(tag_sum(
+                        meta,
+                        &[RowTag::AccessListPrefix, RowTag::HashesPrefix],
+                        cur,
+                    ))
+                    * meta.query_advice(inner_count, prev)
+                    + tag_sum(meta, &[RowTag::EntryPrefix, RowTag::HashPrefix], cur),
+            );
+
+            // Values in 16-byte limbs, restarted on the first content byte
+            let value = is_value(meta);
+            let cont = tag_sum(meta, &[RowTag::Item, RowTag::Hash], prev) * not::expr(single);
+            cb.require_zero(
+                "only values have a lo limb",
+                lo.expr() * not::expr(value.expr()),
+            );
+            cb.require_equal(
+                "acc_lo = acc_lo_prev * 256 + byte in the lo limb",
+                meta.query_advice(acc_lo, cur),
+                value.expr()
+                    * lo.expr()
+                    * (cont.expr() * meta.query_advice(acc_lo, prev) * 256.expr() + byte.expr()),
+            );
+            cb.require_equal(
+                "acc_hi = acc_hi_prev * 256 + byte in the hi limb, carried in the lo limb",
+                meta.query_advice(acc_hi, cur),
+                value
+                    * (cont
+                        * meta.query_advice(acc_hi, prev)
+                        * (lo.expr() + not::expr(lo.expr()) * 256.expr())
+                        + not::expr(lo) * byte),
+            );
+
+            cb.gate(q_enable(meta))
+        });
+
+        meta.create_gate("tx rlp transition", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let cur = Rotation::cur();
+            let prev = Rotation::prev();
+            let single = meta.query_advice(is_single, cur);
+            let hdr_end_prev = meta.query_advice(is_hdr_end, prev);
+            let str_end_prev = meta.query_advice(is_str_end, prev);
+            let inner_end_prev = meta.query_advice(is_inner_end, prev);
+            let keys_end_prev = meta.query_advice(is_keys_end, prev);
+            let has_len_prev = has_len(meta.query_advice(len_rem, prev));
+
+            cb.require_zero(
+                "the encoding ends with the list",
+                meta.query_advice(is_end, prev),
+            );
+            cb.require_zero(
+                "the type is followed by the list",
+                tag(meta, RowTag::TxType, prev) * not::expr(tag(meta, RowTag::ListPrefix, cur)),
+            );
+            for (prefix, len) in RowTag::HEADERS {
+                cb.require_zero(
+                    "length bytes follow the long prefixes",
+                    has_len_prev.expr()
+                        * (tag(meta, prefix, prev) + tag(meta, len, prev))
+                        * not::expr(tag(meta, len, cur)),
+                );
+            }
+            cb.require_zero(
+                "an item follows the list header or the previous item",
+                (tag_sum(meta, &[RowTag::ListPrefix, RowTag::ListLen], prev) * hdr_end_prev.expr()
+                    + meta.query_advice(is_item_end, prev))
+                    * not::expr(item_start(meta)),
+            );
+            for (prefix, len, content) in RowTag::STRINGS {
+                let in_string = ((tag(meta, prefix, prev)
+                    + len.map_or(0.expr(), |len| tag(meta, len, prev)))
+                    * hdr_end_prev.expr()
+                    + tag(meta, content, prev))
+                    * not::expr(str_end_prev.expr());
+                cb.require_zero(
+                    "content follows the header until the end of the string",
+                    in_string.expr() * not::expr(tag(meta, content, cur)),
+                );
+                cb.require_zero(
+                    "strings of more than a byte are not a single byte",
+                    in_string * single.expr(),
+                );
+            }
+            cb.require_zero(
+                "an entry follows the access list header or an entry until the access list ends",
+                (tag_sum(
+                    meta,
+                    &[RowTag::AccessListPrefix, RowTag::AccessListLen],
+                    prev,
+                ) * hdr_end_prev.expr()
+                    + keys_end_prev.expr())
+                    * not::expr(inner_end_prev.expr())
+                    * not::expr(tag(meta, RowTag::EntryPrefix, cur)),
+            );
+            cb.require_zero(
+                "the address follows the entry header",
+                tag_sum(meta, &[RowTag::EntryPrefix, RowTag::EntryLen], prev)
+                    * hdr_end_prev.expr()
+                    * not::expr(tag(meta, RowTag::AddressPrefix, cur)),
+            );
+            cb.require_zero(
+                "the storage keys follow the address",
+                tag(meta, RowTag::Address, prev)
+                    * str_end_prev.expr()
+                    * not::expr(tag(meta, RowTag::KeysPrefix, cur)),
+            );
+            cb.require_zero(
+                "a storage key follows the keys header or a key until the keys end",
+                (tag_sum(meta, &[RowTag::KeysPrefix, RowTag::KeysLen], prev) * hdr_end_prev.expr()
+                    + tag(meta, RowTag::Key, prev) * str_end_prev.expr())
+                    * not::expr(keys_end_prev)
+                    * not::expr(tag(meta, RowTag::KeyPrefix, cur)),
+            );
+            cb.require_zero(
+                "a hash follows the hashes header or a hash until the hashes end",
+                (tag_sum(meta, &[RowTag::HashesPrefix, RowTag::HashesLen], prev) * hdr_end_prev
+                    + tag(meta, RowTag::Hash, prev) * str_end_prev)
+                    * not::expr(inner_end_prev)
+                    * not::expr(tag(meta, RowTag::HashPrefix, cur)),
+            );
+            let in_item = tag_sum(meta, &RowTag::PAYLOAD, cur) * not::expr(item_start(meta));
+            for column in fields {
+                cb.require_equal(
+                    "the field is carried in the item",
+                    in_item.expr() * meta.query_advice(column, cur),
+                    in_item.expr() * meta.query_advice(column, prev),
+                );
+            }
+
+            cb.gate(q_enable(meta) * not::expr(is_first(meta)))
+        });
+
+        let kind = |meta: &mut VirtualCells<'_, F>| field_sum(meta, &|f| f.kind());
+        meta.lookup_any("tx rlp field of the item", |meta| {
+            let start = q_enable(meta) * item_start(meta);
+            let input = [
+                start.expr() * meta.query_advice(tx_type, Rotation::cur()),
+                start.expr() * (meta.query_advice(item_count, Rotation::cur()) - 1.expr()),
+                start * kind(meta),
+            ];
+            let table = [field_tx_type, field_index, field_kind]
+                .map(|column| meta.query_fixed(column, Rotation::cur()));
+
+            input.into_iter().zip(table).collect()
+        });
+        meta.lookup_any("tx rlp last item", |meta| {
+            let end = q_enable(meta) * meta.query_advice(is_end, Rotation::cur());
+            let input = [
+                end.expr() * meta.query_advice(tx_type, Rotation::cur()),
+                end.expr() * (meta.query_advice(item_count, Rotation::cur()) - 1.expr()),
+                end.expr() * kind(meta),
+                end,
+            ];
+            let table = [field_tx_type, field_index, field_kind, field_is_last]
+                .map(|column| meta.query_fixed(column, Rotation::cur()));
+
+            input.into_iter().zip(table).collect()
+        });
+
+        // Lookups of (tx_id, tag, index, value) in the tx table
+        let tx_lookup =
+            |meta: &mut ConstraintSystem<F>,
+             name: &'static str,
+             input: &dyn Fn(&mut VirtualCells<'_, F>) -> [Expression<F>; 5]| {
+                meta.lookup_any(name, |meta| {
+                    let input = input(meta);
+                    let table = tx_table.table_exprs(meta);
+                    input.into_iter().zip(table).collect()
+                });
+            };
+        tx_lookup(meta, "tx rlp tx type", &|meta| {
+            let first = q_enable(meta) * is_first(meta);
+            [
+                first.expr() * tx_id(meta),
+                first.expr() * TxFieldTag::TxType.expr(),
+                0.expr(),
+                first * meta.query_advice(tx_type, Rotation::cur()),
+                0.expr(),
+            ]
+        });
+        if !is_signed {
+            // Only the unsigned fields of legacy txs signed before EIP-155 end with the
+            // call data rather than the chain id and two zeros.
+            tx_lookup(meta, "tx rlp replay protection", &|meta| {
+                let end = q_enable(meta) * meta.query_advice(is_end, Rotation::cur());
+                [
+                    end.expr() * tx_id(meta),
+                    end.expr() * TxFieldTag::IsReplayProtected.expr(),
+                    0.expr(),
+                    end * not::expr(field(meta, TxRlpField::Data)),
+                    0.expr(),
+                ]
+            });
+        }
+        tx_lookup(meta, "tx rlp field value", &|meta| {
+            let item_end = q_enable(meta) * meta.query_advice(is_item_end, Rotation::cur());
+            let is_bound = field_sum(meta, &|f| f.tag().is_some() as u64);
+            let cond = item_end.expr() * is_bound;
+            [
+                cond.expr() * tx_id(meta),
+                item_end * field_sum(meta, &|f| f.tag().map_or(0, |tag| tag as u64)),
+                0.expr(),
+                cond.expr() * meta.query_advice(acc_lo, Rotation::cur()),
+                cond * meta.query_advice(acc_hi, Rotation::cur()),
+            ]
+        });
+        tx_lookup(meta, "tx rlp is create", &|meta| {
+            let cond = q_enable(meta)
+                * meta.query_advice(is_item_end, Rotation::cur())
+                * field(meta, TxRlpField::To);
+            let inv_20 = Expression::Constant(F::from(20).invert().unwrap());
+            [
+                cond.expr() * tx_id(meta),
+                cond.expr() * TxFieldTag::IsCreate.expr(),
+                0.expr(),
+                cond * (1.expr() - meta.query_advice(str_len, Rotation::cur()) * inv_20),
+                0.expr(),
+            ]
+        });
+        tx_lookup(meta, "tx rlp length", &|meta| {
+            let item_end = q_enable(meta) * meta.query_advice(is_item_end, Rotation::cur());
+            let data = field(meta, TxRlpField::Data);
+            let lists =
+                field(meta, TxRlpField::AccessList) + field(meta, TxRlpField::BlobVersionedHashes);
+            [
+                item_end.expr() * (data.expr() + lists.expr()) * tx_id(meta),
+                item_end.expr()
+                    * (data.expr() * TxFieldTag::CallDataLength.expr()
+                        + field(meta, TxRlpField::AccessList)
+                            * TxFieldTag::AccessListAddressesLen.expr()
+                        + field(meta, TxRlpField::BlobVersionedHashes)
+                            * TxFieldTag::BlobVersionedHashesLen.expr()),
+                0.expr(),
+                item_end
+                    * (data * meta.query_advice(str_len, Rotation::cur())
+                        + lists * meta.query_advice(inner_count, Rotation::cur())),
+                0.expr(),
+            ]
+        });
+        tx_lookup(meta, "tx rlp call data", &|meta| {
+            let cond = q_enable(meta)
+                * tag(meta, RowTag::Item, Rotation::cur())
+                * field(meta, TxRlpField::Data);
+            [
+                cond.expr() * tx_id(meta),
+                cond.expr() * TxFieldTag::CallData.expr(),
+                cond.expr()
+                    * (meta.query_advice(str_len, Rotation::cur())
+                        - 1.expr()
+                        - meta.query_advice(rem_str, Rotation::cur())),
+                cond * meta.query_advice(byte, Rotation::cur()),
+                0.expr(),
+            ]
+        });
+        tx_lookup(meta, "tx rlp blob versioned hash", &|meta| {
+            let cond = q_enable(meta)
+                * tag(meta, RowTag::Hash, Rotation::cur())
+                * meta.query_advice(is_str_end, Rotation::cur());
+            [
+                cond.expr() * tx_id(meta),
+                cond.expr() * TxFieldTag::BlobVersionedHash.expr(),
+                cond.expr() * (meta.query_advice(inner_count, Rotation::cur()) - 1.expr()),
+                cond.expr() * meta.query_advice(acc_lo, Rotation::cur()),
+                cond * meta.query_advice(acc_hi, Rotation::cur()),
+            ]
+        });
+        meta.lookup_any("tx rlp chain id", |meta| {
+            let cond = q_enable(meta)
+                * meta.query_advice(is_item_end, Rotation::cur())
+                * field(meta, TxRlpField::ChainId);
+            let input = [
+                cond.expr() * BlockContextFieldTag::ChainId.expr(),
+                0.expr(),
+                cond.expr() * meta.query_advice(acc_lo, Rotation::cur()),
+                cond * meta.query_advice(acc_hi, Rotation::cur()),
+            ];
+            let table = block_table.table_exprs(meta);
+
+            input.into_iter().zip(table).collect()
+        });
+
+        // Range checks of the prefixes against their short or long range, of the
+        // types and single bytes, and of the limbs of values, split into a lower
+        // and an upper bound.
+        meta.lookup_any("tx rlp lower bounds", |meta| {
+            let byte = meta.query_advice(byte, Rotation::cur());
+            let long = meta.query_advice(is_long, Rotation::cur());
+
+            let value = tag_sum(meta, &RowTag::LIST_PREFIXES, Rotation::cur())
+                * (byte.expr() - 0xc0.expr() - 0x38.expr() * long.expr())
+                + tag_sum(meta, &RowTag::STR_PREFIXES, Rotation::cur())
+                    * (byte.expr() - 0x80.expr() - 0x38.expr() * long)
+                + tag(meta, RowTag::TxType, Rotation::cur()) * (byte.expr() - 1.expr())
+                + meta.query_advice(is_single, Rotation::cur()) * (0x7f.expr() - byte);
+
+            vec![(
+                q_enable(meta) * value,
+                u16_table.table_exprs(meta)[0].expr(),
+            )]
+        });
+        meta.lookup_any("tx rlp upper bounds", |meta| {
+            let byte = meta.query_advice(byte, Rotation::cur());
+            let long = meta.query_advice(is_long, Rotation::cur());
+            let lo = meta.query_advice(is_lo, Rotation::cur());
+            let rem_str = meta.query_advice(rem_str, Rotation::cur());
+
+            let value = tag_sum(meta, &RowTag::LIST_PREFIXES, Rotation::cur())
+                * (0xf7.expr() + 2.expr() * long.expr() - byte.expr())
+                + tag_sum(meta, &RowTag::STR_PREFIXES, Rotation::cur())
+                    * (0xb7.expr() + 2.expr() * long - byte.expr())
+                + tag(meta, RowTag::TxType, Rotation::cur()) * (3.expr() - byte)
+                + is_value(meta)
+                    * (lo.expr() * (15.expr() - rem_str.expr())
+                        + not::expr(lo) * (rem_str - 16.expr()));
+
+            vec![(
+                q_enable(meta) * value,
+                u16_table.table_exprs(meta)[0].expr(),
+            )]
+        });
+        meta.lookup_any("tx rlp scalar length", |meta| {
+            // Integers take at most 32 bytes
+            let value = tag(meta, RowTag::ItemPrefix, Rotation::cur())
+                * field_sum(meta, &|f| f.is_scalar() as u64)
+                * (0xa0.expr() - meta.query_advice(byte, Rotation::cur()));
+
+            vec![(
+                q_enable(meta) * value,
+                u16_table.table_exprs(meta)[0].expr(),
+            )]
+        });
+
+        Self {
+            is_signed,
+            field_tx_type,
+            field_index,
+            field_kind,
+            field_is_last,
+            tags,
+            fields,
+            is_single,
+            is_long,
+            len_rem,
+            len_acc,
+            is_hdr_end,
+            hdr_len,
+            rem_list,
+            rem_list_inv,
+            is_end,
+            rem_str,
+            rem_str_inv,
+            is_str_end,
+            str_len,
+            rem_inner,
+            rem_inner_inv,
+            is_inner_end,
+            rem_entry,
+            rem_keys,
+            rem_keys_inv,
+            is_keys_end,
+            is_item_end,
+            item_count,
+            tx_type,
+            inner_count,
+            is_lo,
+            acc_lo,
+            acc_hi,
+        }
+    }
+
+    /// Whether the row is the last byte of an encoding
+    pub(crate) fn is_end<F: Field>(
+        &self,
+        meta: &mut VirtualCells<'_, F>,
+        rotation: Rotation,
+    ) -> Expression<F> {
+        meta.query_advice(self.is_end, rotation)
+    }
+
+    /// Assign the fixed table of the fields of every transaction type
+    pub(crate) fn load<F: Field>(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_region(
+            || "tx rlp fields",
+            |mut region| {
+                for (offset, row) in field_table(self.is_signed).into_iter().enumerate() {
+                    for (column, value) in [
+                        self.field_tx_type,
+                        self.field_index,
+                        self.field_kind,
+                        self.field_is_last,
+                    ]
+                    .into_iter()
+                    .zip(row)
+                    {
+                        region.assign_fixed(
+                            || "tx rlp field",
+                            column,
+                            offset,
+                            || Value::known(F::from(value)),
+                        )?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Assign the gadget columns of a row of an encoding, whose byte is
+    /// assigned by the host
+    pub(crate) fn assign_row<F: Field>(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        row: &TxRlpRow,
+    ) -> Result<(), Error> {
+        for (column, tag) in self.tags.iter().zip(RowTag::iter()) {
+            region.assign_advice(
+                || "tx rlp tag",
+                *column,
+                offset,
+                || Value::known(F::from((row.tag == tag) as u64)),
+            )?;
+        }
+        for (column, field) in self.fields.iter().zip(TxRlpField::iter()) {
+            region.assign_advice(
+                || "tx rlp field",
+                *column,
+                offset,
+                || Value::known(F::from((row.field == Some(field)) as u64)),
+            )?;
+        }
+
+        let inv = |value: u64| F::from(value).invert().unwrap_or(F::ZERO);
+        for (name, column, value) in [
+            ("is_single", self.is_single, F::from(row.is_single as u64)),
+            ("is_long", self.is_long, F::from(row.is_long as u64)),
+            ("len_rem", self.len_rem, F::from(row.len_rem)),
+            ("len_acc", self.len_acc, F::from(row.len_acc)),
+            (
+                "is_hdr_end",
+                self.is_hdr_end,
+                F::from(row.is_hdr_end as u64),
+            ),
+            ("hdr_len", self.hdr_len, F::from(row.hdr_len)),
+            ("rem_list", self.rem_list, F::from(row.rem_list)),
+            ("rem_list_inv", self.rem_list_inv, inv(row.rem_list)),
+            ("is_end", self.is_end, F::from(row.is_end as u64)),
+            ("rem_str", self.rem_str, F::from(row.rem_str)),
+            ("rem_str_inv", self.rem_str_inv, inv(row.rem_str)),
+            (
+                "is_str_end",
+                self.is_str_end,
+                F::from(row.is_str_end as u64),
+            ),
+            ("str_len", self.str_len, F::from(row.str_len)),
+            ("rem_inner", self.rem_inner, F::from(row.rem_inner)),
+            ("rem_inner_inv", self.rem_inner_inv, inv(row.rem_inner)),
+            (
+                "is_inner_end",
+                self.is_inner_end,
+                F::from(row.is_inner_end as u64),
+            ),
+            ("rem_entry", self.rem_entry, F::from(row.rem_entry)),
+            ("rem_keys", self.rem_keys, F::from(row.rem_keys)),
+            ("rem_keys_inv", self.rem_keys_inv, inv(row.rem_keys)),
+            (
+                "is_keys_end",
+                self.is_keys_end,
+                F::from(row.is_keys_end as u64),
+            ),
+            (
+                "is_item_end",
+                self.is_item_end,
+                F::from(row.is_item_end as u64),
+            ),
+            ("item_count", self.item_count, F::from(row.item_count)),
+            ("tx_type", self.tx_type, F::from(row.tx_type)),
+            ("inner_count", self.inner_count, F::from(row.inner_count)),
+            ("is_lo", self.is_lo, F::from(row.is_lo as u64)),
+            ("acc_lo", self.acc_lo, F::from_u128(row.acc_lo)),
+            ("acc_hi", self.acc_hi, F::from_u128(row.acc_hi)),
+        ] {
+            region.assign_advice(|| name, column, offset, || Value::known(value))?;
+        }
+        Ok(())
+    }
+}
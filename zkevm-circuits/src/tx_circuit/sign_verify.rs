@@ -9,21 +9,20 @@ use crate::{
         param::N_BYTES_ACCOUNT_ADDRESS,
         util::{from_bytes, not, rlc},
     },
-    table::KeccakTable,
+    table::{KeccakTable, LookupTable, SigTable},
     util::{word::Word, Challenges, Expr},
 };
-use ecc::{maingate, EccConfig, GeneralEccChip};
-use ecdsa::ecdsa::{AssignedEcdsaSig, AssignedPublicKey, EcdsaChip};
+use ecc::{maingate, EccConfig, EccInstructions, GeneralEccChip};
 use eth_types::{
     self, keccak256,
     sign_types::{pk_bytes_le, pk_bytes_swap_endianness, SignData},
-    Field,
+    Field, U256,
 };
 use halo2_proofs::{
     arithmetic::CurveAffine,
     circuit::{AssignedCell, Cell, Layouter, Value},
     halo2curves::{
-        ff::PrimeField,
+        ff::{Field as _, PrimeField},
         group::{Curve, Group},
         secp256k1,
         secp256k1::Secp256k1Affine,
@@ -89,8 +88,13 @@ impl<F: Field> SignVerifyChip<F> {
         // region. TODO: Figure out a way to get these numbers automatically.
         let rows_range_chip_table = 295188;
         let rows_ecc_chip_aux = 226;
-        let rows_ecdsa_chip_verification = 104471;
-        let rows_signature_address_verify = 76;
+        // The rows needed to decompose the signature (r, s) into bytes for the
+        // SigTable are included in rows_ecdsa_chip_verification and
+        // rows_signature_address_verify, as well as the decomposition of the y
+        // coordinate for its parity and the proof that a non recoverable
+        // signature recovers no public key.
+        let rows_ecdsa_chip_verification = 104800;
+        let rows_signature_address_verify = 400;
         std::cmp::max(
             rows_range_chip_table,
             (rows_ecc_chip_aux + rows_ecdsa_chip_verification + rows_signature_address_verify)
@@ -110,9 +114,12 @@ impl<F: Field> Default for SignVerifyChip<F> {
     }
 }
 
-const NUMBER_OF_LIMBS: usize = 4;
-const BIT_LEN_LIMB: usize = 72;
+pub(crate) const NUMBER_OF_LIMBS: usize = 4;
+pub(crate) const BIT_LEN_LIMB: usize = 72;
 const BIT_LEN_LAST_LIMB: usize = 256 - (NUMBER_OF_LIMBS - 1) * BIT_LEN_LIMB;
+/// `r` of the non recoverable signature in the rows of the SigTable of the
+/// valid and padding signatures, as 5 is not the x coordinate of a point.
+const NON_RECOVERABLE_SIG_R: u64 = 5;
 
 /// SignVerify Configuration
 #[derive(Debug, Clone)]
@@ -126,12 +133,15 @@ pub(crate) struct SignVerifyConfig {
     // Keccak
     q_keccak: Selector,
     _keccak_table: KeccakTable,
+    // Signatures
+    sig_table: SigTable,
 }
 
 impl SignVerifyConfig {
     pub(crate) fn new<F: Field>(
         meta: &mut ConstraintSystem<F>,
         keccak_table: KeccakTable,
+        sig_table: SigTable,
         challenges: Challenges<Expression<F>>,
     ) -> Self {
         // ECDSA config
@@ -201,6 +211,7 @@ impl SignVerifyConfig {
             rlc,
             q_keccak,
             _keccak_table: keccak_table.clone(),
+            sig_table,
         }
     }
 
@@ -288,46 +299,91 @@ pub(crate) struct AssignedECDSA<F: Field> {
     pk_x_le: [AssignedValue<F>; 32],
     pk_y_le: [AssignedValue<F>; 32],
     msg_hash_le: [AssignedValue<F>; 32],
+    sig_r_le: [AssignedValue<F>; 32],
+    sig_s_le: [AssignedValue<F>; 32],
+    /// Parity of the y coordinate of the signature point
+    sig_v: AssignedValue<F>,
+}
+
+/// The fields of a signature in the SigTable.
+#[derive(Debug)]
+pub(crate) struct AssignedSignature<F: Field> {
+    msg_hash: Word<AssignedValue<F>>,
+    sig_v: AssignedValue<F>,
+    sig_r: Word<AssignedValue<F>>,
+    sig_s: Word<AssignedValue<F>>,
 }
 
 #[derive(Debug)]
 pub(crate) struct AssignedSignatureVerify<F: Field> {
     pub(crate) address: Word<AssignedValue<F>>,
     pub(crate) msg_hash: Word<AssignedValue<F>>,
+    /// Recovery id of the signature, which is the parity of the y coordinate
+    /// of the signature point.
+    pub(crate) sig_v: AssignedValue<F>,
+    /// 1 when the signature is verified, 0 for the padding and non recoverable
+    /// signatures.
+    pub(crate) is_valid: AssignedValue<F>,
+    /// The signature in the SigTable, which is the verified one when it's
+    /// valid, and else a signature that recovers no public key.
+    signature: AssignedSignature<F>,
 }
 
 // Return an array of bytes that corresponds to the little endian representation
 // of the integer, adding the constraints to verify the correctness of the
 // conversion (byte range check included).
-fn integer_to_bytes_le<F: Field, FE: PrimeField>(
+pub(crate) fn integer_to_bytes_le<F: Field, FE: PrimeField>(
     ctx: &mut RegionCtx<'_, F>,
     range_chip: &RangeChip<F>,
     int: &AssignedInteger<FE, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
 ) -> Result<[AssignedValue<F>; 32], Error> {
-    let bytes = int
-        .limbs()
-        .iter()
-        .zip_eq([BIT_LEN_LIMB, BIT_LEN_LIMB, BIT_LEN_LIMB, BIT_LEN_LAST_LIMB])
-        .map(|(limb, bit_len)| {
-            range_chip
-                .decompose(ctx, limb.as_ref().value().copied(), 8, bit_len)
-                .map(|(_, byte)| byte)
-        })
-        .collect::<Result<Vec<_>, _>>()?
-        .into_iter()
-        .flatten()
-        .collect_vec();
+    let mut bytes = Vec::new();
+    for (limb, bit_len) in
+        int.limbs()
+            .iter()
+            .zip_eq([BIT_LEN_LIMB, BIT_LEN_LIMB, BIT_LEN_LIMB, BIT_LEN_LAST_LIMB])
+    {
+        let (composed, limb_bytes) =
+            range_chip.decompose(ctx, limb.as_ref().value().copied(), 8, bit_len)?;
+        ctx.constrain_equal(composed.cell(), limb.as_ref().cell())?;
+        bytes.extend(limb_bytes);
+    }
     Ok(bytes.try_into().unwrap())
 }
 
+// Return the word built from 32 little endian byte cells, adding the constraints
+// to verify the correctness of the composition.
+pub(crate) fn word_from_bytes_le<F: Field>(
+    ctx: &mut RegionCtx<'_, F>,
+    main_gate: &MainGate<F>,
+    bytes_le: &[AssignedValue<F>; 32],
+    powers_of_256: &[F],
+) -> Result<Word<AssignedValue<F>>, Error> {
+    let [lo, hi] = [&bytes_le[..16], &bytes_le[16..]].map(|bytes| {
+        main_gate
+            .decompose(
+                ctx,
+                &bytes
+                    .iter()
+                    .zip_eq(powers_of_256)
+                    .map(|(cell, coeff)| maingate::Term::Assigned(cell, *coeff))
+                    .collect_vec(),
+                F::ZERO,
+                |_, _| Ok(()),
+            )
+            .map(|(word_cell, _)| word_cell)
+    });
+    Ok(Word::new([lo?, hi?]))
+}
+
 /// Helper structure pass around references to all the chips required for an
 /// ECDSA verification.
 struct ChipsRef<'a, F: Field, const NUMBER_OF_LIMBS: usize, const BIT_LEN_LIMB: usize> {
     main_gate: &'a MainGate<F>,
     range_chip: &'a RangeChip<F>,
     ecc_chip: &'a GeneralEccChip<Secp256k1Affine, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+    base_chip: &'a IntegerChip<secp256k1::Fp, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
     scalar_chip: &'a IntegerChip<secp256k1::Fq, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
-    ecdsa_chip: &'a EcdsaChip<Secp256k1Affine, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
 }
 
 impl<F: Field> SignVerifyChip<F> {
@@ -351,43 +407,61 @@ impl<F: Field> SignVerifyChip<F> {
             signature,
             pk,
             msg_hash,
+            ..
         } = sign_data;
         let (sig_r, sig_s) = signature;
 
         let ChipsRef {
-            main_gate: _,
+            main_gate,
             range_chip,
             ecc_chip,
+            base_chip,
             scalar_chip,
-            ecdsa_chip,
         } = chips;
 
         let integer_r = ecc_chip.new_unassigned_scalar(Value::known(*sig_r));
         let integer_s = ecc_chip.new_unassigned_scalar(Value::known(*sig_s));
         let msg_hash = ecc_chip.new_unassigned_scalar(Value::known(*msg_hash));
 
-        let r_assigned = scalar_chip.assign_integer(ctx, integer_r, Range::Remainder)?;
-        let s_assigned = scalar_chip.assign_integer(ctx, integer_s, Range::Remainder)?;
-        let sig = AssignedEcdsaSig {
-            r: r_assigned,
-            s: s_assigned,
-        };
+        let sig_r = scalar_chip.assign_integer(ctx, integer_r, Range::Remainder)?;
+        let sig_s = scalar_chip.assign_integer(ctx, integer_s, Range::Remainder)?;
 
-        let pk_in_circuit = ecc_chip.assign_point(ctx, Value::known(*pk))?;
-        let pk_assigned = AssignedPublicKey {
-            point: pk_in_circuit,
-        };
+        let pk = ecc_chip.assign_point(ctx, Value::known(*pk))?;
         let msg_hash = scalar_chip.assign_integer(ctx, msg_hash, Range::Remainder)?;
 
-        // Convert (msg_hash, pk_x, pk_y) integers to little endian bytes
+        // Convert (msg_hash, pk_x, pk_y, r, s) integers to little endian bytes
         let msg_hash_le = integer_to_bytes_le(ctx, range_chip, &msg_hash)?;
-        let pk_x = pk_assigned.point.x();
-        let pk_x_le = integer_to_bytes_le(ctx, range_chip, pk_x)?;
-        let pk_y = pk_assigned.point.y();
-        let pk_y_le = integer_to_bytes_le(ctx, range_chip, pk_y)?;
-
-        // Ref. spec SignVerifyChip 4. Verify the ECDSA signature
-        ecdsa_chip.verify(ctx, &sig, &pk_assigned, &msg_hash)?;
+        let pk_x_le = integer_to_bytes_le(ctx, range_chip, pk.x())?;
+        let pk_y_le = integer_to_bytes_le(ctx, range_chip, pk.y())?;
+        let sig_r_le = integer_to_bytes_le(ctx, range_chip, &sig_r)?;
+        let sig_s_le = integer_to_bytes_le(ctx, range_chip, &sig_s)?;
+
+        // Ref. spec SignVerifyChip 4. Verify the ECDSA signature as the EcdsaChip does,
+        // computing the signature point R = u1 * G + u2 * pk with u1 = msg_hash / s and
+        // u2 = r / s, but require R.x == r rather than R.x == r (mod n), so that the
+        // parity of R.y is the recovery id of the signature.
+        scalar_chip.assert_not_zero(ctx, &sig_r)?;
+        scalar_chip.assert_not_zero(ctx, &sig_s)?;
+        let (s_inv, _) = scalar_chip.invert(ctx, &sig_s)?;
+        let u1 = scalar_chip.mul(ctx, &msg_hash, &s_inv)?;
+        let u2 = scalar_chip.mul(ctx, &sig_r, &s_inv)?;
+        let generator = ecc_chip.assign_constant(ctx, Secp256k1Affine::generator())?;
+        let sig_point = ecc_chip.mul_batch_1d_horizontal(
+            ctx,
+            vec![(generator, u1), (pk, u2)],
+            self.window_size,
+        )?;
+        let [sig_point_x_le, sig_point_y_le] = [sig_point.x(), sig_point.y()].map(|coordinate| {
+            let coordinate = base_chip.reduce(ctx, coordinate)?;
+            base_chip.assert_in_field(ctx, &coordinate)?;
+            integer_to_bytes_le(ctx, range_chip, &coordinate)
+        });
+        for (x_byte, r_byte) in sig_point_x_le?.iter().zip(&sig_r_le) {
+            ctx.constrain_equal(x_byte.cell(), r_byte.cell())?;
+        }
+        let sig_v = main_gate
+            .to_bits(ctx, &sig_point_y_le?[0], 8)?
+            .swap_remove(0);
 
         // TODO: Update once halo2wrong suports the following methods:
         // - `IntegerChip::assign_integer_from_bytes_le`
@@ -397,6 +471,92 @@ impl<F: Field> SignVerifyChip<F> {
             pk_x_le,
             pk_y_le,
             msg_hash_le,
+            sig_r_le,
+            sig_s_le,
+            sig_v,
+        })
+    }
+
+    /// Assign a signature whose `r` is proven not to be the x coordinate of a
+    /// curve point, as r^3 + 7 is not a square, so that no public key is recovered
+    /// from it whatever its other fields.  This is the given non recoverable
+    /// signature, or else a fixed one.
+    fn assign_non_recoverable(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        chips: &ChipsRef<F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+        sign_data: Option<&SignData>,
+        powers_of_256: &[F],
+    ) -> Result<AssignedSignature<F>, Error> {
+        let ChipsRef {
+            main_gate,
+            range_chip,
+            ecc_chip,
+            base_chip,
+            ..
+        } = chips;
+
+        let (msg_hash, sig_v, sig_r, sig_s) = match sign_data {
+            Some(sign_data) => {
+                let to_word = |scalar: &secp256k1::Fq| {
+                    Word::<F>::from(U256::from_little_endian(&scalar.to_bytes()))
+                };
+                let sig_r = secp256k1::Fp::from_repr(sign_data.signature.0.to_repr()).unwrap();
+                (
+                    to_word(&sign_data.msg_hash),
+                    sign_data.v,
+                    sig_r,
+                    to_word(&sign_data.signature.1),
+                )
+            }
+            None => (
+                Word::default(),
+                0,
+                secp256k1::Fp::from(NON_RECOVERABLE_SIG_R),
+                Word::default(),
+            ),
+        };
+        let y_square = sig_r.square() * sig_r + secp256k1::Fp::from(7);
+        let Some(root) = Option::<secp256k1::Fp>::from((-y_square).sqrt()) else {
+            // r is the x coordinate of a point, and the recovered public key is the
+            // point at infinity.
+            error!("the public key recovered from a signature is the point at infinity");
+            return Err(Error::Synthesis);
+        };
+
+        let sig_r = base_chip.assign_integer(
+            ctx,
+            ecc_chip.new_unassigned_base(Value::known(sig_r)),
+            Range::Remainder,
+        )?;
+        base_chip.assert_in_field(ctx, &sig_r)?;
+        let root = base_chip.assign_integer(
+            ctx,
+            ecc_chip.new_unassigned_base(Value::known(root)),
+            Range::Remainder,
+        )?;
+        let sig_r_cube = {
+            let sig_r_square = base_chip.square(ctx, &sig_r)?;
+            base_chip.mul(ctx, &sig_r_square, &sig_r)?
+        };
+        let root_square = base_chip.square(ctx, &root)?;
+        let seven = base_chip.assign_constant(ctx, secp256k1::Fp::from(7))?;
+        let sum = base_chip.add(ctx, &sig_r_cube, &root_square)?;
+        let sum = base_chip.add(ctx, &sum, &seven)?;
+        base_chip.assert_zero(ctx, &sum)?;
+
+        let sig_r_le = integer_to_bytes_le(ctx, range_chip, &sig_r)?;
+        let assign_word = |ctx: &mut RegionCtx<F>, word: Word<F>| {
+            Ok::<_, Error>(Word::new([
+                main_gate.assign_value(ctx, Value::known(word.lo()))?,
+                main_gate.assign_value(ctx, Value::known(word.hi()))?,
+            ]))
+        };
+        Ok(AssignedSignature {
+            msg_hash: assign_word(ctx, msg_hash)?,
+            sig_v: main_gate.assign_value(ctx, Value::known(F::from(sig_v as u64)))?,
+            sig_r: word_from_bytes_le(ctx, main_gate, &sig_r_le, powers_of_256)?,
+            sig_s: assign_word(ctx, sig_s)?,
         })
     }
 
@@ -497,9 +657,12 @@ impl<F: Field> SignVerifyChip<F> {
         let main_gate = chips.main_gate;
         let range_chip = chips.range_chip;
 
+        // Non recoverable signatures are verified as padding signatures, and exposed in
+        // the SigTable by a proof that they recover no public key.
+        let non_recoverable = sign_data.filter(|sign_data| !sign_data.is_recovered());
         let (padding, sign_data) = match sign_data {
-            Some(sign_data) => (false, sign_data.clone()),
-            None => (true, SignData::default()),
+            Some(sign_data) if sign_data.is_recovered() => (false, sign_data.clone()),
+            _ => (true, SignData::default()),
         };
 
         let pk_le = pk_bytes_le(&sign_data.pk);
@@ -568,33 +731,41 @@ impl<F: Field> SignVerifyChip<F> {
         let iz_zero_lo = main_gate.is_zero(ctx, &address_cells.lo())?;
         let is_address_zero = main_gate.and(ctx, &iz_zero_lo, &iz_zero_hi)?;
 
+        let is_valid = main_gate.not(ctx, &is_address_zero)?;
+
         // Ref. spec SignVerifyChip 3. Verify that the signed message in the ecdsa_chip
         // corresponds to msg_hash
-        let msg_hash_cells = {
-            let msg_hash_lo_cell_bytes = &assigned_ecdsa.msg_hash_le[..16];
-            let msg_hash_hi_cell_bytes = &assigned_ecdsa.msg_hash_le[16..];
-            let (msg_hash_cell_lo, _) = main_gate.decompose(
-                ctx,
-                &msg_hash_lo_cell_bytes
-                    .iter()
-                    .zip_eq(&powers_of_256)
-                    .map(|(cell, coeff)| maingate::Term::Assigned(cell, *coeff))
-                    .collect_vec(),
-                F::ZERO,
-                |_, _| Ok(()),
-            )?;
-            let (msg_hash_cell_hi, _) = main_gate.decompose(
+        let msg_hash_cells =
+            word_from_bytes_le(ctx, main_gate, &assigned_ecdsa.msg_hash_le, &powers_of_256)?;
+        // The signature (r, s) verified in the ecdsa_chip, exposed in the SigTable
+        let sig_r_cells =
+            word_from_bytes_le(ctx, main_gate, &assigned_ecdsa.sig_r_le, &powers_of_256)?;
+        let sig_s_cells =
+            word_from_bytes_le(ctx, main_gate, &assigned_ecdsa.sig_s_le, &powers_of_256)?;
+
+        // The SigTable holds the verified signature when it's valid, and otherwise a
+        // non recoverable signature, so that is_valid is 0 only for the signatures
+        // that recover no public key.
+        let non_recoverable =
+            self.assign_non_recoverable(ctx, chips, non_recoverable, &powers_of_256)?;
+        let select_word = |ctx: &mut RegionCtx<F>,
+                           valid: &Word<AssignedValue<F>>,
+                           non_recoverable: &Word<AssignedValue<F>>| {
+            Ok::<_, Error>(Word::new([
+                main_gate.select(ctx, &valid.lo(), &non_recoverable.lo(), &is_valid)?,
+                main_gate.select(ctx, &valid.hi(), &non_recoverable.hi(), &is_valid)?,
+            ]))
+        };
+        let signature = AssignedSignature {
+            msg_hash: select_word(ctx, &msg_hash_cells, &non_recoverable.msg_hash)?,
+            sig_v: main_gate.select(
                 ctx,
-                &msg_hash_hi_cell_bytes
-                    .iter()
-                    .zip_eq(&powers_of_256)
-                    .map(|(cell, coeff)| maingate::Term::Assigned(cell, *coeff))
-                    .collect_vec(),
-                F::ZERO,
-                |_, _| Ok(()),
-            )?;
-
-            Word::new([msg_hash_cell_lo, msg_hash_cell_hi])
+                &assigned_ecdsa.sig_v,
+                &non_recoverable.sig_v,
+                &is_valid,
+            )?,
+            sig_r: select_word(ctx, &sig_r_cells, &non_recoverable.sig_r)?,
+            sig_s: select_word(ctx, &sig_s_cells, &non_recoverable.sig_s)?,
         };
 
         let pk_rlc = {
@@ -622,6 +793,9 @@ impl<F: Field> SignVerifyChip<F> {
         Ok(AssignedSignatureVerify {
             address: address_cells,
             msg_hash: msg_hash_cells,
+            sig_v: assigned_ecdsa.sig_v.clone(),
+            is_valid,
+            signature,
         })
     }
 
@@ -631,6 +805,20 @@ impl<F: Field> SignVerifyChip<F> {
         layouter: &mut impl Layouter<F>,
         signatures: &[SignData],
         challenges: &Challenges<Value<F>>,
+    ) -> Result<Vec<AssignedSignatureVerify<F>>, Error> {
+        let signatures = signatures.iter().cloned().map(Some).collect_vec();
+        self.assign_with_padding(config, layouter, &signatures, challenges)
+    }
+
+    /// Assign the signatures, where `None` is a padding signature (enabled when
+    /// address == 0).  This allows the callers to keep the signatures of
+    /// different sources at fixed offsets.
+    pub(crate) fn assign_with_padding(
+        &self,
+        config: &SignVerifyConfig,
+        layouter: &mut impl Layouter<F>,
+        signatures: &[Option<SignData>],
+        challenges: &Challenges<Value<F>>,
     ) -> Result<Vec<AssignedSignatureVerify<F>>, Error> {
         if signatures.len() > self.max_verif {
             error!(
@@ -646,6 +834,7 @@ impl<F: Field> SignVerifyChip<F> {
             config.ecc_chip_config(),
         );
         let cloned_ecc_chip = ecc_chip.clone();
+        let base_chip = cloned_ecc_chip.base_field_chip();
         let scalar_chip = cloned_ecc_chip.scalar_field_chip();
 
         layouter.assign_region(
@@ -658,14 +847,12 @@ impl<F: Field> SignVerifyChip<F> {
             },
         )?;
 
-        let ecdsa_chip = EcdsaChip::new(ecc_chip.clone());
-
         let chips = ChipsRef {
             main_gate: &main_gate,
             range_chip: &range_chip,
             ecc_chip: &ecc_chip,
+            base_chip,
             scalar_chip,
-            ecdsa_chip: &ecdsa_chip,
         };
 
         let assigned_ecdsas = layouter.assign_region(
@@ -674,11 +861,10 @@ impl<F: Field> SignVerifyChip<F> {
                 let mut assigned_ecdsas = Vec::new();
                 let mut ctx = RegionCtx::new(region, 0);
                 for i in 0..self.max_verif {
-                    let signature = if i < signatures.len() {
-                        signatures[i].clone()
-                    } else {
-                        // padding (enabled when address == 0)
-                        SignData::default()
+                    let signature = match signatures.get(i) {
+                        Some(Some(signature)) if signature.is_recovered() => signature.clone(),
+                        // padding and non recoverable signatures (enabled when address == 0)
+                        _ => SignData::default(),
                     };
                     let assigned_ecdsa = self.assign_ecdsa(&mut ctx, &chips, &signature)?;
                     assigned_ecdsas.push(assigned_ecdsa);
@@ -688,13 +874,13 @@ impl<F: Field> SignVerifyChip<F> {
             },
         )?;
 
-        layouter.assign_region(
+        let assigned_sig_verifs = layouter.assign_region(
             || "signature address verify",
             |region| {
                 let mut assigned_sig_verifs = Vec::new();
                 let mut ctx = RegionCtx::new(region, 0);
                 for (i, assigned_ecdsa) in assigned_ecdsas.iter().enumerate() {
-                    let sign_data = signatures.get(i); // None when padding (enabled when address == 0)
+                    let sign_data = signatures.get(i).and_then(Option::as_ref); // None when padding (enabled when address == 0)
                     let assigned_sig_verif = self.assign_signature_verify(
                         config,
                         &mut ctx,
@@ -708,6 +894,62 @@ impl<F: Field> SignVerifyChip<F> {
                 log::debug!("signature address verify: {} rows", ctx.offset());
                 Ok(assigned_sig_verifs)
             },
+        )?;
+
+        self.assign_sig_table(config, layouter, &assigned_sig_verifs)?;
+
+        Ok(assigned_sig_verifs)
+    }
+
+    /// Copy the verified and the non recoverable signatures into the SigTable,
+    /// so that they can be looked up by other circuits.
+    fn assign_sig_table(
+        &self,
+        config: &SignVerifyConfig,
+        layouter: &mut impl Layouter<F>,
+        assigned_sig_verifs: &[AssignedSignatureVerify<F>],
+    ) -> Result<(), Error> {
+        let sig_table = &config.sig_table;
+        layouter.assign_region(
+            || "sig table",
+            |mut region| {
+                for (offset, assigned_sig_verif) in assigned_sig_verifs.iter().enumerate() {
+                    region.assign_fixed(
+                        || format!("sig table q_enable {offset}"),
+                        sig_table.q_enable,
+                        offset,
+                        || Value::known(F::ONE),
+                    )?;
+                    let signature = &assigned_sig_verif.signature;
+                    for (column, assigned) in [
+                        (sig_table.msg_hash.lo(), signature.msg_hash.lo()),
+                        (sig_table.msg_hash.hi(), signature.msg_hash.hi()),
+                        (sig_table.sig_v, signature.sig_v.clone()),
+                        (sig_table.sig_r.lo(), signature.sig_r.lo()),
+                        (sig_table.sig_r.hi(), signature.sig_r.hi()),
+                        (sig_table.sig_s.lo(), signature.sig_s.lo()),
+                        (sig_table.sig_s.hi(), signature.sig_s.hi()),
+                        (
+                            sig_table.recovered_addr.lo(),
+                            assigned_sig_verif.address.lo(),
+                        ),
+                        (
+                            sig_table.recovered_addr.hi(),
+                            assigned_sig_verif.address.hi(),
+                        ),
+                        (sig_table.is_valid, assigned_sig_verif.is_valid.clone()),
+                    ] {
+                        assigned.copy_advice(
+                            || format!("sig table row {offset}"),
+                            &mut region,
+                            column,
+                            offset,
+                        )?;
+                    }
+                }
+                sig_table.annotate_columns_in_region(&mut region);
+                Ok(())
+            },
         )
     }
 }
@@ -724,7 +966,7 @@ mod sign_verify_tests {
         dev::MockProver,
         halo2curves::{
             bn256::Fr,
-            group::{Curve, Group},
+            group::{prime::PrimeCurveAffine, Curve, Group},
             CurveAffine,
         },
         plonk::Circuit,
@@ -741,11 +983,12 @@ mod sign_verify_tests {
     impl TestCircuitSignVerifyConfig {
         pub(crate) fn new<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
             let keccak_table = KeccakTable::construct(meta);
+            let sig_table = SigTable::construct(meta);
             let challenges = Challenges::construct(meta);
 
             let sign_verify = {
                 let challenges = challenges.exprs(meta);
-                SignVerifyConfig::new(meta, keccak_table, challenges)
+                SignVerifyConfig::new(meta, keccak_table, sig_table, challenges)
             };
 
             TestCircuitSignVerifyConfig {
@@ -836,12 +1079,12 @@ mod sign_verify_tests {
         secp256k1::Fq::random(rng)
     }
 
-    // Returns (r, s)
+    // Returns (r, s, v)
     fn sign_with_rng(
         rng: impl RngCore,
         sk: secp256k1::Fq,
         msg_hash: secp256k1::Fq,
-    ) -> (secp256k1::Fq, secp256k1::Fq) {
+    ) -> (secp256k1::Fq, secp256k1::Fq, u8) {
         let randomness = secp256k1::Fq::random(rng);
         sign(randomness, sk, msg_hash)
     }
@@ -863,15 +1106,44 @@ mod sign_verify_tests {
         for _ in 0..NUM_SIGS {
             let (sk, pk) = gen_key_pair(&mut rng);
             let msg_hash = gen_msg_hash(&mut rng);
-            let sig = sign_with_rng(&mut rng, sk, msg_hash);
+            let (sig_r, sig_s, v) = sign_with_rng(&mut rng, sk, msg_hash);
             signatures.push(SignData {
-                signature: sig,
+                signature: (sig_r, sig_s),
                 pk,
                 msg_hash,
+                v,
             });
         }
 
         let k = 19;
         run::<Fr>(k, MAX_VERIF, signatures);
     }
+
+    #[test]
+    fn sign_verify_non_recoverable() {
+        let mut rng = XorShiftRng::seed_from_u64(1);
+        const MAX_VERIF: usize = 3;
+        let (sk, pk) = gen_key_pair(&mut rng);
+        let msg_hash = gen_msg_hash(&mut rng);
+        let (sig_r, sig_s, v) = sign_with_rng(&mut rng, sk, msg_hash);
+        let signatures = vec![
+            SignData {
+                signature: (sig_r, sig_s),
+                pk,
+                msg_hash,
+                v,
+            },
+            // 5 is not the x coordinate of a point of the curve, so no public key
+            // is recovered from the signature.
+            SignData {
+                signature: (secp256k1::Fq::from(NON_RECOVERABLE_SIG_R), sig_s),
+                pk: Secp256k1Affine::identity(),
+                msg_hash,
+                v: 1,
+            },
+        ];
+
+        let k = 19;
+        run::<Fr>(k, MAX_VERIF, signatures);
+    }
 }
@@ -1,11 +1,15 @@
 use super::*;
 use crate::util::{log2_ceil, unusable_rows};
-use eth_types::address;
+use eth_types::{address, keccak256, word, AccessList, Address, Bytes, H256};
+use ethers_core::types::transaction::eip2930::AccessListItem;
+use ethers_signers::{LocalWallet, Signer};
 use halo2_proofs::{
     dev::{MockProver, VerifyFailure},
     halo2curves::bn256::Fr,
 };
-use mock::AddrOrWallet;
+use mock::{AddrOrWallet, MockTransaction};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
 
 #[test]
 fn tx_circuit_unusable_rows() {
@@ -22,7 +26,7 @@ fn run<F: Field>(
     max_calldata: usize,
 ) -> Result<(), Vec<VerifyFailure>> {
     let k = log2_ceil(
-        TxCircuit::<Fr>::unusable_rows() + TxCircuit::<Fr>::min_num_rows(max_txs, max_calldata),
+        TxCircuit::<Fr>::unusable_rows() + TxCircuit::<Fr>::min_num_rows(max_txs, 0, max_calldata),
     );
     // SignVerifyChip -> ECDSAChip -> MainGate instance column
     let circuit = TxCircuit::<F>::new(max_txs, max_calldata, chain_id, txs);
@@ -96,6 +100,198 @@ fn tx_circuit_bad_address() {
     .is_err(),);
 }
 
+fn typed_tx(rng: &mut ChaCha20Rng, transaction_type: u64, access_list: AccessList) -> Transaction {
+    MockTransaction::default()
+        .from(AddrOrWallet::random(rng))
+        .to(mock::MOCK_ACCOUNTS[0])
+        .value(word!("0x3e8"))
+        .gas_price(word!("0x4d2"))
+        .max_priority_fee_per_gas(word!("0x2"))
+        .max_fee_per_gas(word!("0x4d2"))
+        .transaction_type(transaction_type)
+        .access_list(access_list)
+        .input(Bytes::from(b"hello"))
+        .build()
+        .into()
+}
+
+fn access_list() -> AccessList {
+    AccessList(vec![AccessListItem {
+        address: Address::repeat_byte(0x11),
+        storage_keys: vec![H256::repeat_byte(0x22)],
+    }])
+}
+
+#[test]
+fn tx_circuit_typed_txs() {
+    const MAX_TXS: usize = 3;
+    const MAX_CALLDATA: usize = 32;
+
+    let mut rng = ChaCha20Rng::seed_from_u64(2u64);
+    let txs = vec![
+        typed_tx(&mut rng, LEGACY_TX_TYPE, AccessList::default()),
+        typed_tx(&mut rng, ACCESS_LIST_TX_TYPE, access_list()),
+        typed_tx(&mut rng, DYNAMIC_FEE_TX_TYPE, access_list()),
+    ];
+
+    assert_eq!(
+        run::<Fr>(txs, mock::MOCK_CHAIN_ID.as_u64(), MAX_TXS, MAX_CALLDATA),
+        Ok(())
+    );
+}
+
+#[test]
+fn tx_circuit_bad_tx_type() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    let mut rng = ChaCha20Rng::seed_from_u64(2u64);
+    // The tx is signed over a dynamic fee tx envelope, but claims to be an access list tx.
+    let mut tx = typed_tx(&mut rng, DYNAMIC_FEE_TX_TYPE, AccessList::default());
+    tx.transaction_type = ACCESS_LIST_TX_TYPE.into();
+
+    assert!(run::<Fr>(
+        vec![tx],
+        mock::MOCK_CHAIN_ID.as_u64(),
+        MAX_TXS,
+        MAX_CALLDATA
+    )
+    .is_err());
+}
+
+#[test]
+fn tx_circuit_legacy_tx_with_access_list() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    let mut rng = ChaCha20Rng::seed_from_u64(2u64);
+    // Legacy txs don't sign over the access list, so it can't be carried by them.
+    let tx = typed_tx(&mut rng, LEGACY_TX_TYPE, access_list());
+
+    assert!(run::<Fr>(
+        vec![tx],
+        mock::MOCK_CHAIN_ID.as_u64(),
+        MAX_TXS,
+        MAX_CALLDATA
+    )
+    .is_err());
+}
+
+#[test]
+fn tx_circuit_bad_signed_field() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    let mut rng = ChaCha20Rng::seed_from_u64(2u64);
+    let signed_tx = typed_tx(&mut rng, DYNAMIC_FEE_TX_TYPE, access_list());
+    // The signature and the signed message are valid, but the tx table claims
+    // another value than the signed one.
+    let mut tx = signed_tx.clone();
+    tx.value = word!("0x3e9");
+
+    let k = log2_ceil(
+        TxCircuit::<Fr>::unusable_rows() + TxCircuit::<Fr>::min_num_rows(MAX_TXS, 0, MAX_CALLDATA),
+    );
+    let mut circuit = TxCircuit::<Fr>::new(
+        MAX_TXS,
+        MAX_CALLDATA,
+        mock::MOCK_CHAIN_ID.as_u64(),
+        vec![tx],
+    );
+    circuit.signed_txs = vec![signed_tx];
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+
+    let errors = prover.verify().expect_err("the signed value is bound");
+    assert!(errors.iter().any(|error| matches!(
+        error,
+        VerifyFailure::Lookup { name, .. } if *name == "tx rlp field value"
+    )));
+}
+
+fn pre_eip155_tx(rng: &mut ChaCha20Rng) -> Transaction {
+    let wallet = LocalWallet::new(rng);
+    let mut tx: Transaction = MockTransaction::default()
+        .from(wallet.address())
+        .to(mock::MOCK_ACCOUNTS[0])
+        .value(word!("0x3e8"))
+        .gas_price(word!("0x4d2"))
+        .input(Bytes::from(b"hello"))
+        .build()
+        .into();
+    // A v of 27 or 28 marks the tx as signed without the chain id.
+    tx.v = 27;
+    let msg = tx.rlp_unsigned(mock::MOCK_CHAIN_ID.as_u64()).unwrap();
+    let sig = wallet.sign_hash(H256(keccak256(&msg))).unwrap();
+    tx.v = sig.v;
+    tx.r = sig.r;
+    tx.s = sig.s;
+    tx
+}
+
+#[test]
+fn tx_circuit_pre_eip155_tx() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 32;
+
+    let mut rng = ChaCha20Rng::seed_from_u64(2u64);
+    let txs = vec![
+        pre_eip155_tx(&mut rng),
+        mock::CORRECT_MOCK_TXS[0].clone().into(),
+    ];
+
+    assert_eq!(
+        run::<Fr>(txs, mock::MOCK_CHAIN_ID.as_u64(), MAX_TXS, MAX_CALLDATA),
+        Ok(())
+    );
+}
+
+#[test]
+fn tx_circuit_pre_eip155_tx_bad_scheme() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    let mut rng = ChaCha20Rng::seed_from_u64(2u64);
+    let mut tx = pre_eip155_tx(&mut rng);
+    // Keep the recovery id, but claim the signature follows EIP-155.
+    tx.v = tx.v - 27 + 35 + mock::MOCK_CHAIN_ID.as_u64() * 2;
+
+    assert!(run::<Fr>(
+        vec![tx],
+        mock::MOCK_CHAIN_ID.as_u64(),
+        MAX_TXS,
+        MAX_CALLDATA
+    )
+    .is_err());
+}
+
+#[test]
+fn tx_circuit_bad_replay_protection() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    let k = log2_ceil(
+        TxCircuit::<Fr>::unusable_rows() + TxCircuit::<Fr>::min_num_rows(MAX_TXS, 0, MAX_CALLDATA),
+    );
+    let mut circuit = TxCircuit::<Fr>::new(
+        MAX_TXS,
+        MAX_CALLDATA,
+        mock::MOCK_CHAIN_ID.as_u64(),
+        vec![mock::CORRECT_MOCK_TXS[0].clone().into()],
+    );
+    // The tx is signed following EIP-155, but the tx table claims otherwise.
+    circuit.is_replay_protected = vec![false];
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+
+    let errors = prover.verify().expect_err("the signing scheme is bound");
+    assert!(errors.iter().any(|error| matches!(
+        error,
+        VerifyFailure::Lookup { name, .. } if *name == "tx rlp replay protection"
+    )));
+    assert!(errors
+        .iter()
+        .any(|error| matches!(error, VerifyFailure::ConstraintNotSatisfied { .. })));
+}
+
 #[test]
 fn variadic_size_check() {
     const MAX_TXS: usize = 2;
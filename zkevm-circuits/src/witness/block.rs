@@ -1,19 +1,23 @@
-use super::{ExecStep, Rw, RwMap, Transaction};
+use super::{ExecStep, MptUpdates, Rw, RwMap, Transaction};
 use crate::{
     evm_circuit::{detect_fixed_table_tags, EvmCircuit},
     exp_circuit::param::OFFSET_INCREMENT,
     instance::public_data_convert,
+    mpt_circuit::witness_row::Node,
     table::BlockContextFieldTag,
     util::{log2_ceil, word, SubCircuit},
 };
 use bus_mapping::{
-    circuit_input_builder::{self, CopyEvent, ExpEvent, FixedCParams, Withdrawal},
+    circuit_input_builder::{
+        self, CopyEvent, ExpEvent, FixedCParams, PrecompileEvents, Withdrawal,
+    },
     state_db::CodeDB,
     Error,
 };
-use eth_types::{Address, Field, ToScalar, Word, H256};
+use eth_types::{evm_types::Hardfork, geth_types::Receipt, Address, Field, ToScalar, ToWord, Word};
 use halo2_proofs::circuit::Value;
 use itertools::Itertools;
+use std::iter;
 
 // TODO: Remove fields that are duplicated in`eth_block`
 /// Block is the struct used by all circuits, which contains all the needed
@@ -24,33 +28,54 @@ pub struct Block<F> {
     pub randomness: F,
     /// Transactions in the block
     pub txs: Vec<Transaction>,
+    /// Withdrawal steps that credit the withdrawals after the last transaction
+    /// of their block
+    pub withdrawal_steps: Vec<ExecStep>,
+    /// EndBlock steps between the blocks of a chunk, one after each block but
+    /// the last one
+    pub end_blocks: Vec<ExecStep>,
     /// EndBlock step that is repeated after the last transaction and before
     /// reaching the last EVM row.
     pub end_block_not_last: ExecStep,
     /// Last EndBlock step that appears in the last EVM row.
     pub end_block_last: ExecStep,
+    /// Receipts of the transactions, as written in the RwTable
+    pub receipts: Vec<Receipt>,
     /// Read write events in the RwTable
     pub rws: RwMap,
+    /// Updates of the state trie from the account and storage read write
+    /// events.  Their roots are mocked until the MPT witness is set.
+    pub mpt_updates: MptUpdates,
+    /// MPT circuit witness proving the `mpt_updates`, empty when the roots
+    /// are mocked
+    pub mpt_witness: Vec<Node>,
     /// Bytecode used in the block
     pub bytecodes: CodeDB,
-    /// The block context
+    /// The block context of the last block of the chunk
     pub context: BlockContext,
+    /// The block contexts of the blocks of the chunk, in order
+    pub contexts: Vec<BlockContext>,
     /// Copy events for the copy circuit's table.
     pub copy_events: Vec<CopyEvent>,
     /// Exponentiation traces for the exponentiation circuit's table.
     pub exp_events: Vec<ExpEvent>,
+    /// Precompile calls verified outside of the EVM circuit, e.g. the ecrecover
+    /// signatures verified by the SignVerify chip.
+    pub precompile_events: PrecompileEvents,
     /// Pad exponentiation circuit to make selectors fixed.
     pub exp_circuit_pad_to: usize,
     /// Circuit Setup Parameters
     pub circuits_params: FixedCParams,
     /// Inputs to the SHA3 opcode
     pub sha3_inputs: Vec<Vec<u8>>,
-    /// State root of the previous block
+    /// State root of the block before the chunk
     pub prev_state_root: Word, // TODO: Make this H256
     /// Keccak inputs
     pub keccak_inputs: Vec<Vec<u8>>,
-    /// Original Block from geth
+    /// Original Block from geth of the last block of the chunk
     pub eth_block: eth_types::Block<eth_types::Transaction>,
+    /// Original Blocks from geth of the blocks of the chunk, in order
+    pub eth_blocks: Vec<eth_types::Block<eth_types::Transaction>>,
 }
 
 impl<F: Field> Block<F> {
@@ -76,28 +101,64 @@ impl<F: Field> Block<F> {
         self.rws[step.rw_index(index)]
     }
 
-    /// Return the list of withdrawals of this block.
-    pub fn withdrawals(&self) -> Vec<Withdrawal> {
-        let eth_withdrawals = self.eth_block.withdrawals.clone().unwrap_or_default();
-        eth_withdrawals
+    /// Return the block context of the block of a transaction
+    pub(crate) fn tx_context(&self, tx: &Transaction) -> &BlockContext {
+        self.contexts
             .iter()
-            .map({
-                |w| {
-                    Withdrawal::new(
-                        w.index.as_u64(),
-                        w.validator_index.as_u64(),
-                        w.address,
-                        w.amount.as_u64(),
-                    )
-                    .unwrap()
-                }
-            })
-            .collect_vec()
+            .find(|context| context.number.as_u64() == tx.block_num)
+            .expect("the block of a tx is in the chunk")
+    }
+
+    /// Return whether an EndBlock step is at the end of the chunk, rather
+    /// than between two blocks of the chunk
+    pub(crate) fn is_chunk_end(&self, step: &ExecStep) -> bool {
+        !self
+            .end_blocks
+            .iter()
+            .any(|end_block| std::ptr::eq(end_block, step))
     }
 
-    /// Return the root of withdrawals in this block
-    pub fn withdrawals_root(&self) -> H256 {
-        self.eth_block.withdrawals_root.unwrap_or_default()
+    /// Return the number of transactions before an EndBlock step, which is
+    /// the number of transactions of the chunk for the EndBlock steps at its
+    /// end
+    pub(crate) fn txs_before_end_block(&self, step: &ExecStep) -> usize {
+        match self
+            .end_blocks
+            .iter()
+            .position(|end_block| std::ptr::eq(end_block, step))
+        {
+            Some(i) => {
+                let number = self.contexts[i].number.as_u64();
+                self.txs.iter().filter(|tx| tx.block_num <= number).count()
+            }
+            None => self.txs.len(),
+        }
+    }
+
+    /// Set the MPT circuit witness, generated from the trie modifications of
+    /// `mpt_updates`, which proves the state root transition of the chunk.
+    pub fn set_mpt_witness(&mut self, nodes: Vec<Node>) {
+        self.mpt_updates =
+            MptUpdates::from_witness(&self.rws.table_assignments(), self.prev_state_root, &nodes);
+        assert_eq!(
+            self.mpt_updates.new_root(),
+            self.eth_block.state_root.to_word(),
+            "the MPT witness doesn't end in the state root of the last block"
+        );
+        self.keccak_inputs.extend(
+            nodes
+                .iter()
+                .flat_map(|node| node.keccak_data.iter().map(|data| data.to_vec())),
+        );
+        self.mpt_witness = nodes;
+    }
+
+    /// Return the list of withdrawals of the blocks of the chunk, in order.
+    pub fn withdrawals(&self) -> Vec<Withdrawal> {
+        self.contexts
+            .iter()
+            .flat_map(|context| context.withdrawals.iter().copied())
+            .collect_vec()
     }
 
     /// Obtains the expected Circuit degree needed in order to be able to test
@@ -175,46 +236,80 @@ pub struct BlockContext {
     pub chain_id: Word,
     /// The withdrawal root
     pub withdrawals_root: Word,
+    /// The excess blob gas (EIP-4844)
+    pub excess_blob_gas: u64,
+    /// The blob base fee, the price per unit of blob gas (EIP-4844)
+    pub blob_base_fee: Word,
+    /// The hardfork the block is executed with
+    pub hardfork: Hardfork,
+    /// The number of withdrawals of the previous blocks of the chunk
+    pub withdrawal_offset: u64,
+    /// The withdrawals of the block
+    pub withdrawals: Vec<Withdrawal>,
 }
 
 impl BlockContext {
-    /// Assignments for block table
+    /// Create the block context of a block of a chunk, whose previous blocks
+    /// have `withdrawal_offset` withdrawals
+    pub fn new(head: &circuit_input_builder::BlockHead, withdrawal_offset: u64) -> Self {
+        Self {
+            coinbase: head.coinbase,
+            gas_limit: head.gas_limit,
+            number: head.number,
+            timestamp: head.timestamp,
+            difficulty: head.difficulty,
+            base_fee: head.base_fee,
+            history_hashes: head.history_hashes.clone(),
+            chain_id: head.chain_id,
+            withdrawals_root: head.withdrawals_root().as_fixed_bytes().into(),
+            excess_blob_gas: head.excess_blob_gas,
+            blob_base_fee: head.blob_base_fee,
+            hardfork: head.hardfork,
+            withdrawal_offset,
+            withdrawals: head.withdrawals(),
+        }
+    }
+
+    /// Assignments for block table.  The fields of the block are indexed by
+    /// its number, so that the blocks of a chunk share the table, except for
+    /// the chain id and the hardfork which are the same for all of them.
     pub fn table_assignments<F: Field>(&self) -> Vec<[Value<F>; 4]> {
+        let number = Value::known(self.number.to_scalar().unwrap());
         [
             vec![
                 [
                     Value::known(F::from(BlockContextFieldTag::Coinbase as u64)),
-                    Value::known(F::ZERO),
+                    number,
                     Value::known(word::Word::from(self.coinbase).lo()),
                     Value::known(word::Word::from(self.coinbase).hi()),
                 ],
                 [
                     Value::known(F::from(BlockContextFieldTag::Timestamp as u64)),
-                    Value::known(F::ZERO),
+                    number,
                     Value::known(self.timestamp.to_scalar().unwrap()),
                     Value::known(F::ZERO),
                 ],
                 [
                     Value::known(F::from(BlockContextFieldTag::Number as u64)),
-                    Value::known(F::ZERO),
+                    number,
                     Value::known(self.number.to_scalar().unwrap()),
                     Value::known(F::ZERO),
                 ],
                 [
                     Value::known(F::from(BlockContextFieldTag::Difficulty as u64)),
-                    Value::known(F::ZERO),
+                    number,
                     Value::known(word::Word::from(self.difficulty).lo()),
                     Value::known(word::Word::from(self.difficulty).hi()),
                 ],
                 [
                     Value::known(F::from(BlockContextFieldTag::GasLimit as u64)),
-                    Value::known(F::ZERO),
+                    number,
                     Value::known(F::from(self.gas_limit)),
                     Value::known(F::ZERO),
                 ],
                 [
                     Value::known(F::from(BlockContextFieldTag::BaseFee as u64)),
-                    Value::known(F::ZERO),
+                    number,
                     Value::known(word::Word::from(self.base_fee).lo()),
                     Value::known(word::Word::from(self.base_fee).hi()),
                 ],
@@ -226,10 +321,28 @@ impl BlockContext {
                 ],
                 [
                     Value::known(F::from(BlockContextFieldTag::WithdrawalRoot as u64)),
-                    Value::known(F::ZERO),
+                    number,
                     Value::known(word::Word::from(self.withdrawals_root).lo()),
                     Value::known(word::Word::from(self.withdrawals_root).hi()),
                 ],
+                [
+                    Value::known(F::from(BlockContextFieldTag::BlobBaseFee as u64)),
+                    number,
+                    Value::known(word::Word::from(self.blob_base_fee).lo()),
+                    Value::known(word::Word::from(self.blob_base_fee).hi()),
+                ],
+                [
+                    Value::known(F::from(BlockContextFieldTag::Hardfork as u64)),
+                    Value::known(F::ZERO),
+                    Value::known(F::from(self.hardfork.as_u64())),
+                    Value::known(F::ZERO),
+                ],
+                [
+                    Value::known(F::from(BlockContextFieldTag::WithdrawalOffset as u64)),
+                    number,
+                    Value::known(F::from(self.withdrawal_offset)),
+                    Value::known(F::ZERO),
+                ],
             ],
             {
                 let len_history = self.history_hashes.len();
@@ -251,22 +364,6 @@ impl BlockContext {
     }
 }
 
-impl From<&circuit_input_builder::Block> for BlockContext {
-    fn from(block: &circuit_input_builder::Block) -> Self {
-        Self {
-            coinbase: block.coinbase,
-            gas_limit: block.gas_limit,
-            number: block.number,
-            timestamp: block.timestamp,
-            difficulty: block.difficulty,
-            base_fee: block.base_fee,
-            history_hashes: block.history_hashes.clone(),
-            chain_id: block.chain_id,
-            withdrawals_root: block.withdrawals_root().as_fixed_bytes().into(),
-        }
-    }
-}
-
 /// Convert a block struct in bus-mapping to a witness block used in circuits
 pub fn block_convert<F: Field>(
     builder: &circuit_input_builder::CircuitInputBuilder<FixedCParams>,
@@ -275,31 +372,81 @@ pub fn block_convert<F: Field>(
     let code_db = &builder.code_db;
     let rws = RwMap::from(&block.container);
     rws.check_value();
+    let mpt_updates = MptUpdates::mock_from(&rws.table_assignments());
+    let contexts = block
+        .headers
+        .iter()
+        .scan(0, |withdrawal_offset, head| {
+            let context = BlockContext::new(head, *withdrawal_offset);
+            *withdrawal_offset += context.withdrawals.len() as u64;
+            Some(context)
+        })
+        .collect_vec();
     let mut block = Block {
         // randomness: F::from(0x100), // Special value to reveal elements after RLC
         randomness: F::from(0xcafeu64),
-        context: block.into(),
+        context: contexts
+            .last()
+            .expect("a chunk has at least one block")
+            .clone(),
+        contexts,
         rws,
+        mpt_updates,
+        mpt_witness: Vec::new(),
         txs: block.txs().to_vec(),
+        receipts: block.receipts(),
+        withdrawal_steps: block.block_steps.withdrawals.clone(),
+        end_blocks: block.block_steps.end_blocks.clone(),
         end_block_not_last: block.block_steps.end_block_not_last.clone(),
         end_block_last: block.block_steps.end_block_last.clone(),
         bytecodes: code_db.clone(),
         copy_events: block.copy_events.clone(),
         exp_events: block.exp_events.clone(),
+        precompile_events: block.precompile_events.clone(),
         sha3_inputs: block.sha3_inputs.clone(),
         circuits_params: builder.circuits_params,
         exp_circuit_pad_to: <usize>::default(),
-        prev_state_root: block.prev_state_root,
+        prev_state_root: block.headers[0].prev_state_root,
         keccak_inputs: circuit_input_builder::keccak_inputs(block, code_db)?,
         eth_block: block.eth_block.clone(),
+        eth_blocks: block
+            .headers
+            .iter()
+            .map(|head| head.eth_block.clone())
+            .collect(),
     };
     let public_data = public_data_convert(&block);
     let rpi_bytes = public_data.get_pi_bytes(
+        block.circuits_params.max_blocks,
         block.circuits_params.max_txs,
         block.circuits_params.max_withdrawals,
         block.circuits_params.max_calldata,
     );
     // PI Circuit
-    block.keccak_inputs.extend_from_slice(&[rpi_bytes]);
+    block.keccak_inputs.push(rpi_bytes);
+    for public_data in &public_data.blocks {
+        block
+            .keccak_inputs
+            .push(public_data.get_block_header_rlp().to_vec());
+        block.keccak_inputs.extend(
+            public_data
+                .get_tx_trie_nodes()
+                .into_iter()
+                .chain(public_data.get_receipt_trie_nodes())
+                .chain(public_data.get_withdrawal_trie_nodes())
+                .map(|node| node.rlp),
+        );
+    }
+    // Receipt Circuit: the addresses and topics accrued to the logs bloom
+    let bloom_inputs = block
+        .receipts
+        .iter()
+        .flat_map(|receipt| receipt.logs.iter())
+        .flat_map(|log| {
+            iter::once(log.address.as_bytes().to_vec())
+                .chain(log.topics.iter().map(|topic| topic.as_bytes().to_vec()))
+        })
+        .collect_vec();
+    block.keccak_inputs.extend(bloom_inputs);
     Ok(block)
 }
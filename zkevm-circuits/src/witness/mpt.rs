@@ -1,5 +1,6 @@
 use crate::{
-    evm_circuit::witness::Rw,
+    evm_circuit::{param::N_BYTES_WORD, witness::Rw},
+    mpt_circuit::witness_row::{Node, StartRowType},
     table::{AccountFieldTag, MPTProofType},
     util::word,
 };
@@ -19,8 +20,19 @@ pub struct MptUpdate {
 }
 
 impl MptUpdate {
-    fn proof_type<F: Field>(&self) -> F {
-        let proof_type = match self.key {
+    /// The account address of the update
+    pub fn address(&self) -> Address {
+        self.key.address()
+    }
+
+    /// The storage key of the update, 0 for account updates
+    pub fn storage_key(&self) -> Word {
+        self.key.storage_key()
+    }
+
+    /// The type of MPT proof that proves the update
+    pub fn mpt_proof_type(&self) -> MPTProofType {
+        match self.key {
             Key::AccountStorage { .. } => {
                 if self.old_value.is_zero() && self.new_value.is_zero() {
                     MPTProofType::StorageDoesNotExist
@@ -29,8 +41,21 @@ impl MptUpdate {
                 }
             }
             Key::Account { field_tag, .. } => field_tag.into(),
-        };
-        F::from(proof_type as u64)
+        }
+    }
+
+    /// The value before the update
+    pub fn old_value(&self) -> Word {
+        self.old_value
+    }
+
+    /// The value after the update
+    pub fn new_value(&self) -> Word {
+        self.new_value
+    }
+
+    fn proof_type<F: Field>(&self) -> F {
+        F::from(self.mpt_proof_type() as u64)
     }
 }
 
@@ -58,6 +83,20 @@ impl MptUpdates {
         self.old_root
     }
 
+    /// The root after all the updates
+    pub(crate) fn new_root(&self) -> Word {
+        self.updates
+            .values()
+            .last()
+            .map(|update| update.new_root)
+            .unwrap_or(self.old_root)
+    }
+
+    /// The updates in the order they are applied to the state trie
+    pub fn iter(&self) -> impl Iterator<Item = &MptUpdate> {
+        self.updates.values()
+    }
+
     pub(crate) fn get(&self, row: &Rw) -> Option<MptUpdate> {
         key(row).map(|key| *self.updates.get(&key).expect("missing key in mpt updates"))
     }
@@ -93,6 +132,51 @@ impl MptUpdates {
         }
     }
 
+    /// The updates of `rows` with the roots of the MPT circuit witness `nodes`,
+    /// which proves the updates in order starting from `old_root`.
+    pub(crate) fn from_witness(rows: &[Rw], old_root: Word, nodes: &[Node]) -> Self {
+        let mut updates = Self::mock_from(rows);
+        let proofs = nodes
+            .iter()
+            .filter_map(|node| {
+                let root = |row: StartRowType| {
+                    Word::from_big_endian(&node.values[row as usize][1..N_BYTES_WORD + 1])
+                };
+                node.start
+                    .as_ref()
+                    .filter(|start| start.proof_type != MPTProofType::Disabled)
+                    .map(|start| {
+                        (
+                            start.proof_type,
+                            root(StartRowType::RootS),
+                            root(StartRowType::RootC),
+                        )
+                    })
+            })
+            .collect_vec();
+        assert_eq!(
+            updates.updates.len(),
+            proofs.len(),
+            "the MPT witness doesn't prove all the updates"
+        );
+
+        let mut root = old_root;
+        for (update, (proof_type, proof_old_root, proof_new_root)) in
+            updates.updates.values_mut().zip(proofs)
+        {
+            assert_eq!(update.mpt_proof_type(), proof_type, "{:?}", update);
+            assert_eq!(
+                root, proof_old_root,
+                "the MPT witness roots are not chained"
+            );
+            update.old_root = proof_old_root;
+            update.new_root = proof_new_root;
+            root = proof_new_root;
+        }
+        updates.old_root = old_root;
+        updates
+    }
+
     pub(crate) fn table_assignments<F: Field>(&self) -> Vec<MptUpdateRow<Value<F>>> {
         self.updates
             .values()
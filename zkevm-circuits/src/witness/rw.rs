@@ -256,6 +256,25 @@ pub enum Rw {
         field_tag: TxReceiptFieldTag,
         value: u64,
     },
+    /// TxCreatedAccount
+    TxCreatedAccount {
+        rw_counter: usize,
+        is_write: bool,
+        tx_id: usize,
+        account_address: Address,
+        is_created: bool,
+        is_created_prev: bool,
+    },
+    /// TransientStorage
+    TransientStorage {
+        rw_counter: usize,
+        is_write: bool,
+        tx_id: usize,
+        account_address: Address,
+        storage_key: Word,
+        value: Word,
+        value_prev: Word,
+    },
 }
 
 /// Rw table row assignment
@@ -348,6 +367,26 @@ impl Rw {
         }
     }
 
+    pub(crate) fn tx_created_account_value_pair(&self) -> (bool, bool) {
+        match self {
+            Self::TxCreatedAccount {
+                is_created,
+                is_created_prev,
+                ..
+            } => (*is_created, *is_created_prev),
+            _ => unreachable!(),
+        }
+    }
+
+    pub(crate) fn transient_storage_value_pair(&self) -> (Word, Word) {
+        match self {
+            Self::TransientStorage {
+                value, value_prev, ..
+            } => (*value, *value_prev),
+            _ => unreachable!(),
+        }
+    }
+
     pub(crate) fn tx_refund_value_pair(&self) -> (u64, u64) {
         match self {
             Self::TxRefund {
@@ -483,7 +522,9 @@ impl Rw {
             | Self::Account { rw_counter, .. }
             | Self::CallContext { rw_counter, .. }
             | Self::TxLog { rw_counter, .. }
-            | Self::TxReceipt { rw_counter, .. } => *rw_counter,
+            | Self::TxReceipt { rw_counter, .. }
+            | Self::TxCreatedAccount { rw_counter, .. }
+            | Self::TransientStorage { rw_counter, .. } => *rw_counter,
         }
     }
 
@@ -499,7 +540,9 @@ impl Rw {
             | Self::Account { is_write, .. }
             | Self::CallContext { is_write, .. }
             | Self::TxLog { is_write, .. }
-            | Self::TxReceipt { is_write, .. } => *is_write,
+            | Self::TxReceipt { is_write, .. }
+            | Self::TxCreatedAccount { is_write, .. }
+            | Self::TransientStorage { is_write, .. } => *is_write,
         }
     }
 
@@ -516,6 +559,8 @@ impl Rw {
             Self::CallContext { .. } => Target::CallContext,
             Self::TxLog { .. } => Target::TxLog,
             Self::TxReceipt { .. } => Target::TxReceipt,
+            Self::TxCreatedAccount { .. } => Target::TxCreatedAccount,
+            Self::TransientStorage { .. } => Target::TransientStorage,
         }
     }
 
@@ -526,7 +571,9 @@ impl Rw {
             | Self::TxAccessListAccountStorage { tx_id, .. }
             | Self::TxRefund { tx_id, .. }
             | Self::TxLog { tx_id, .. }
-            | Self::TxReceipt { tx_id, .. } => Some(*tx_id),
+            | Self::TxReceipt { tx_id, .. }
+            | Self::TxCreatedAccount { tx_id, .. }
+            | Self::TransientStorage { tx_id, .. } => Some(*tx_id),
             Self::CallContext { call_id, .. }
             | Self::Stack { call_id, .. }
             | Self::Memory { call_id, .. } => Some(*call_id),
@@ -547,6 +594,12 @@ impl Rw {
             }
             | Self::AccountStorage {
                 account_address, ..
+            }
+            | Self::TxCreatedAccount {
+                account_address, ..
+            }
+            | Self::TransientStorage {
+                account_address, ..
             } => Some(*account_address),
             Self::Memory { memory_address, .. } => Some(U256::from(*memory_address).to_address()),
             Self::Stack { stack_pointer, .. } => {
@@ -580,14 +633,17 @@ impl Rw {
             | Self::TxAccessListAccount { .. }
             | Self::TxAccessListAccountStorage { .. }
             | Self::TxRefund { .. }
-            | Self::TxLog { .. } => None,
+            | Self::TxLog { .. }
+            | Self::TxCreatedAccount { .. }
+            | Self::TransientStorage { .. } => None,
         }
     }
 
     pub(crate) fn storage_key(&self) -> Option<Word> {
         match self {
             Self::AccountStorage { storage_key, .. }
-            | Self::TxAccessListAccountStorage { storage_key, .. } => Some(*storage_key),
+            | Self::TxAccessListAccountStorage { storage_key, .. }
+            | Self::TransientStorage { storage_key, .. } => Some(*storage_key),
             Self::Start { .. }
             | Self::CallContext { .. }
             | Self::Stack { .. }
@@ -596,7 +652,8 @@ impl Rw {
             | Self::Account { .. }
             | Self::TxAccessListAccount { .. }
             | Self::TxLog { .. }
-            | Self::TxReceipt { .. } => None,
+            | Self::TxReceipt { .. }
+            | Self::TxCreatedAccount { .. } => None,
         }
     }
 
@@ -607,9 +664,11 @@ impl Rw {
             | Self::Account { value, .. }
             | Self::AccountStorage { value, .. }
             | Self::Stack { value, .. }
-            | Self::TxLog { value, .. } => *value,
+            | Self::TxLog { value, .. }
+            | Self::TransientStorage { value, .. } => *value,
             Self::TxAccessListAccount { is_warm, .. }
             | Self::TxAccessListAccountStorage { is_warm, .. } => U256::from(*is_warm as u64),
+            Self::TxCreatedAccount { is_created, .. } => U256::from(*is_created as u64),
             Self::Memory { byte, .. } => U256::from(u64::from(*byte)),
             Self::TxRefund { value, .. } | Self::TxReceipt { value, .. } => U256::from(*value),
         }
@@ -617,14 +676,17 @@ impl Rw {
 
     pub(crate) fn value_prev_assignment(&self) -> Option<Word> {
         match self {
-            Self::Account { value_prev, .. } | Self::AccountStorage { value_prev, .. } => {
-                Some(*value_prev)
-            }
+            Self::Account { value_prev, .. }
+            | Self::AccountStorage { value_prev, .. }
+            | Self::TransientStorage { value_prev, .. } => Some(*value_prev),
             Self::TxAccessListAccount { is_warm_prev, .. }
             | Self::TxAccessListAccountStorage { is_warm_prev, .. } => {
                 Some(U256::from(*is_warm_prev as u64))
             }
             Self::TxRefund { value_prev, .. } => Some(U256::from(*value_prev)),
+            Self::TxCreatedAccount {
+                is_created_prev, ..
+            } => Some(U256::from(*is_created_prev as u64)),
             Self::Start { .. }
             | Self::Stack { .. }
             | Self::Memory { .. }
@@ -831,6 +893,8 @@ impl From<&operation::OperationContainer> for RwMap {
                         TxLogField::Address => TxLogFieldTag::Address,
                         TxLogField::Topic => TxLogFieldTag::Topic,
                         TxLogField::Data => TxLogFieldTag::Data,
+                        TxLogField::TopicLength => TxLogFieldTag::TopicLength,
+                        TxLogField::DataLength => TxLogFieldTag::DataLength,
                     },
                     index: op.op().index,
                     value: op.op().value,
@@ -855,6 +919,37 @@ impl From<&operation::OperationContainer> for RwMap {
                 })
                 .collect(),
         );
+        rws.insert(
+            Target::TxCreatedAccount,
+            container
+                .tx_created_account
+                .iter()
+                .map(|op| Rw::TxCreatedAccount {
+                    rw_counter: op.rwc().into(),
+                    is_write: op.rw().is_write(),
+                    tx_id: op.op().tx_id,
+                    account_address: op.op().address,
+                    is_created: op.op().is_created,
+                    is_created_prev: op.op().is_created_prev,
+                })
+                .collect(),
+        );
+        rws.insert(
+            Target::TransientStorage,
+            container
+                .transient_storage
+                .iter()
+                .map(|op| Rw::TransientStorage {
+                    rw_counter: op.rwc().into(),
+                    is_write: op.rw().is_write(),
+                    tx_id: op.op().tx_id,
+                    account_address: op.op().address,
+                    storage_key: op.op().key,
+                    value: op.op().value,
+                    value_prev: op.op().value_prev,
+                })
+                .collect(),
+        );
 
         Self(rws)
     }
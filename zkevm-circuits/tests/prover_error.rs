@@ -6,7 +6,7 @@
 use bus_mapping::mock::BlockData;
 use env_logger::Env;
 use eth_types::{
-    geth_types::{Account, GethData},
+    geth_types::{block_hardfork, Account, GethData},
     Block, Bytes, Error, Transaction, Word, H160, U256,
 };
 use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
@@ -71,8 +71,10 @@ fn prover_error() {
             accounts.push(account);
         }
     }
+    let hardfork = block_hardfork(&eth_block);
     let geth_traces = gen_geth_traces(
         chain_id,
+        hardfork,
         eth_block.clone(),
         accounts.clone(),
         vec![],
@@ -82,6 +84,7 @@ fn prover_error() {
     .expect("gen_geth_traces");
     let geth_data = GethData {
         chain_id,
+        hardfork,
         history_hashes,
         eth_block,
         geth_traces,